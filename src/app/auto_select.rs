@@ -0,0 +1,105 @@
+use futures_util::stream::{self, StreamExt};
+use std::collections::HashMap;
+
+use crate::app::delay_test::DelayTest;
+use crate::clash::{ClashClient, Proxy, ProxyType};
+
+/// Delay tests run at once by `test_nodes`
+const CONCURRENT_TESTS: usize = 8;
+
+/// A selector group waiting on delay tests of its nodes before switching to the fastest
+#[derive(Debug, Clone)]
+pub struct AutoSelect {
+    pub group: String,
+    /// Nodes slower than this (ms) are never picked
+    pub max_delay: Option<u32>,
+    /// Nodes whose result hasn't arrived yet
+    pub waiting: Vec<String>,
+    /// (node, delay) in group order; None when the test failed
+    pub results: Vec<(String, Option<u32>)>,
+}
+
+impl AutoSelect {
+    pub fn new(group: String, nodes: Vec<String>, max_delay: Option<u32>) -> Self {
+        Self {
+            group,
+            max_delay,
+            results: nodes.iter().map(|node| (node.clone(), None)).collect(),
+            waiting: nodes,
+        }
+    }
+
+    /// Record a result; true once every node has one
+    pub fn record(&mut self, node: &str, delay: Option<u32>) -> bool {
+        if let Some(pos) = self.waiting.iter().position(|n| n == node) {
+            self.waiting.remove(pos);
+            if let Some(result) = self.results.iter_mut().find(|(n, _)| n == node) {
+                result.1 = delay;
+            }
+        }
+        self.waiting.is_empty()
+    }
+}
+
+/// The reachable node with the lowest delay, ignoring nodes over `max_delay` ms
+/// Ties go to the node listed first in the group
+pub fn fastest(results: &[(String, Option<u32>)], max_delay: Option<u32>) -> Option<(&str, u32)> {
+    results
+        .iter()
+        .filter_map(|(node, delay)| Some((node.as_str(), (*delay)?)))
+        .filter(|(_, delay)| max_delay.is_none_or(|max| *delay <= max))
+        .min_by_key(|(_, delay)| *delay)
+}
+
+/// Whether delay tests make sense for `node` (not DIRECT, REJECT and the like)
+/// Names missing from `proxies` are assumed testable
+pub fn is_testable(node: &str, proxies: &HashMap<String, Proxy>) -> bool {
+    if node.eq_ignore_ascii_case("DIRECT") {
+        return false;
+    }
+    proxies.get(node).is_none_or(|proxy| {
+        !matches!(
+            proxy.proxy_type,
+            ProxyType::Direct
+                | ProxyType::Reject
+                | ProxyType::RejectDrop
+                | ProxyType::Compatible
+                | ProxyType::Pass
+        )
+    })
+}
+
+/// Test every node, a few at a time, returning (node, delay) in the given order
+pub async fn test_nodes(
+    client: &ClashClient,
+    test: &DelayTest,
+    nodes: &[String],
+) -> Vec<(String, Option<u32>)> {
+    stream::iter(nodes)
+        .map(|node| async move { (node.clone(), test.run(client, node).await) })
+        .buffered(CONCURRENT_TESTS)
+        .collect()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fastest_respects_threshold() {
+        let mut pending = AutoSelect::new(
+            "Proxy".to_string(),
+            vec!["HK".to_string(), "JP".to_string(), "US".to_string()],
+            Some(300),
+        );
+        assert!(!pending.record("US", Some(120)));
+        assert!(!pending.record("HK", None));
+        assert!(pending.record("JP", Some(120)));
+
+        assert_eq!(fastest(&pending.results, None), Some(("JP", 120)));
+        let slow = vec![("HK".to_string(), Some(450)), ("JP".to_string(), None)];
+        assert_eq!(fastest(&slow, Some(300)), None);
+        assert_eq!(fastest(&slow, None), Some(("HK", 450)));
+    }
+}
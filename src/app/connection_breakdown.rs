@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+
+use crate::clash::Connection;
+
+/// How the open connections are handled, for the Connections page's statistics header
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConnectionBreakdown {
+    pub tcp: usize,
+    pub udp: usize,
+    /// Exit DIRECT, or have no chain
+    pub direct: usize,
+    /// Exit through a proxy node
+    pub proxied: usize,
+    /// Exit REJECT or REJECT-DROP
+    pub rejected: usize,
+    /// Destinations resolved through the core's fake-ip pool
+    pub fake_ip: usize,
+    /// Destinations resolved any other way (normal, redir-host, hosts, mapping)
+    pub real_ip: usize,
+    /// The chain most connections take, as shown in the list, with its count
+    pub top_chain: Option<(String, usize)>,
+}
+
+impl ConnectionBreakdown {
+    pub fn of<'a>(connections: impl IntoIterator<Item = &'a Connection>) -> Self {
+        let mut breakdown = Self::default();
+        let mut chains: HashMap<String, usize> = HashMap::new();
+        for conn in connections {
+            if conn.metadata.is_udp() {
+                breakdown.udp += 1;
+            } else {
+                breakdown.tcp += 1;
+            }
+
+            match conn.chains.first().map(|exit| exit.to_ascii_uppercase()) {
+                None => breakdown.direct += 1,
+                Some(exit) if exit == "DIRECT" => breakdown.direct += 1,
+                Some(exit) if exit.starts_with("REJECT") => breakdown.rejected += 1,
+                Some(_) => breakdown.proxied += 1,
+            }
+
+            match conn.metadata.dns_mode.as_deref() {
+                Some(mode) if mode.eq_ignore_ascii_case("fake-ip") => breakdown.fake_ip += 1,
+                Some(mode) if !mode.is_empty() => breakdown.real_ip += 1,
+                _ => {}
+            }
+
+            if !conn.chains.is_empty() {
+                *chains.entry(conn.chains.join(" → ")).or_default() += 1;
+            }
+        }
+        // Ties go to the alphabetically first chain so the header doesn't flicker
+        breakdown.top_chain = chains
+            .into_iter()
+            .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)));
+        breakdown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breakdown_counts() {
+        let conn = |network: &str, dns_mode: Option<&str>, chains: &[&str]| -> Connection {
+            serde_json::from_value(serde_json::json!({
+                "id": "c",
+                "metadata": {
+                    "network": network, "type": "Tun",
+                    "sourceIP": "198.18.0.1", "sourcePort": "5000",
+                    "destinationIP": "1.2.3.4", "destinationPort": "443",
+                    "dnsMode": dns_mode
+                },
+                "upload": 0, "download": 0,
+                "start": "2026-01-01T00:00:00Z",
+                "chains": chains,
+                "rule": "MATCH"
+            }))
+            .unwrap()
+        };
+        let connections = [
+            conn("tcp", Some("fake-ip"), &["HK 01", "Proxy"]),
+            conn("udp", Some("fake-ip"), &["HK 01", "Proxy"]),
+            conn("tcp", Some("normal"), &["DIRECT"]),
+            conn("tcp", None, &["REJECT"]),
+            conn("udp", Some("redir-host"), &["JP 02", "Proxy"]),
+            conn("tcp", Some("fake-ip"), &[]),
+        ];
+
+        let breakdown = ConnectionBreakdown::of(&connections);
+        assert_eq!((breakdown.tcp, breakdown.udp), (4, 2));
+        assert_eq!(
+            (breakdown.direct, breakdown.proxied, breakdown.rejected),
+            (2, 3, 1)
+        );
+        assert_eq!((breakdown.fake_ip, breakdown.real_ip), (3, 2));
+        assert_eq!(breakdown.top_chain, Some(("HK 01 → Proxy".to_string(), 2)));
+        assert_eq!(ConnectionBreakdown::of(&[]), ConnectionBreakdown::default());
+    }
+}
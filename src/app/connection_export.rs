@@ -0,0 +1,115 @@
+use anyhow::Result;
+use std::path::PathBuf;
+
+use crate::app::delay_log::{csv_field, write_export, ExportFormat};
+use crate::clash::Connection;
+
+const CSV_HEADER: &str = "id,start,network,type,source,destination,host,process,chains,rule,rule_payload,upload_bytes,download_bytes";
+
+/// `ip:port` of either end of a connection
+fn endpoint(ip: &str, port: &str) -> String {
+    if ip.contains(':') {
+        format!("[{}]:{}", ip, port)
+    } else {
+        format!("{}:{}", ip, port)
+    }
+}
+
+/// One row per connection, with the chain joined by ` → ` as the Connections page shows it
+pub fn to_csv(connections: &[&Connection]) -> String {
+    let mut csv = format!("{}\n", CSV_HEADER);
+    for conn in connections {
+        let meta = &conn.metadata;
+        let fields = [
+            conn.id.clone(),
+            conn.start.clone(),
+            meta.network.clone(),
+            meta.conn_type.clone(),
+            endpoint(&meta.source_ip, &meta.source_port),
+            endpoint(&meta.destination_ip, &meta.destination_port),
+            meta.host.clone().unwrap_or_default(),
+            meta.process_name().unwrap_or_default().to_string(),
+            conn.chains.join(" → "),
+            conn.rule.clone(),
+            conn.rule_payload.clone().unwrap_or_default(),
+            conn.upload.to_string(),
+            conn.download.to_string(),
+        ];
+        let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Array of connections with their metadata, chain and byte counts
+pub fn to_json(connections: &[&Connection]) -> Result<String> {
+    let rows: Vec<serde_json::Value> = connections
+        .iter()
+        .map(|conn| {
+            let meta = &conn.metadata;
+            serde_json::json!({
+                "id": conn.id,
+                "start": conn.start,
+                "network": meta.network,
+                "type": meta.conn_type,
+                "source": endpoint(&meta.source_ip, &meta.source_port),
+                "destination": endpoint(&meta.destination_ip, &meta.destination_port),
+                "host": meta.host,
+                "process": meta.process_name(),
+                "process_path": meta.process_path,
+                "chains": conn.chains,
+                "rule": conn.rule,
+                "rule_payload": conn.rule_payload,
+                "upload_bytes": conn.upload,
+                "download_bytes": conn.download,
+            })
+        })
+        .collect();
+    Ok(serde_json::to_string_pretty(&rows)?)
+}
+
+/// Write `connections` to `exports/connections-<time>.<ext>` in the config dir
+pub fn export(connections: &[&Connection], format: ExportFormat) -> Result<PathBuf> {
+    let contents = match format {
+        ExportFormat::Csv => to_csv(connections),
+        ExportFormat::Json => to_json(connections)?,
+    };
+    write_export("connections", format, &contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connection_rows() {
+        let conn: Connection = serde_json::from_value(serde_json::json!({
+            "id": "c1",
+            "metadata": {
+                "network": "tcp", "type": "HTTPS",
+                "sourceIP": "192.168.1.2", "sourcePort": "5000",
+                "destinationIP": "2001:db8::1", "destinationPort": "443",
+                "host": "example.com", "process": "curl"
+            },
+            "upload": 10, "download": 2048,
+            "start": "2026-01-01T00:00:00Z",
+            "chains": ["HK 01", "Proxy"],
+            "rule": "DOMAIN-SUFFIX", "rulePayload": "example.com"
+        }))
+        .unwrap();
+
+        let csv = to_csv(&[&conn]);
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows[0], CSV_HEADER);
+        assert_eq!(
+            rows[1],
+            "c1,2026-01-01T00:00:00Z,tcp,HTTPS,192.168.1.2:5000,[2001:db8::1]:443,example.com,curl,HK 01 → Proxy,DOMAIN-SUFFIX,example.com,10,2048"
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&to_json(&[&conn]).unwrap()).unwrap();
+        assert_eq!(json[0]["chains"][1], "Proxy");
+        assert_eq!(json[0]["download_bytes"], 2048);
+        assert!(json[0]["process_path"].is_null());
+    }
+}
@@ -0,0 +1,146 @@
+use anyhow::Result;
+use chrono::{DateTime, Local, SecondsFormat};
+use std::path::PathBuf;
+
+/// One delay test run during the session
+#[derive(Debug, Clone)]
+pub struct DelayTest {
+    pub at: DateTime<Local>,
+    pub node: String,
+    /// Proxy type, e.g. "Shadowsocks"
+    pub node_type: String,
+    /// Milliseconds, None when the test failed
+    pub delay: Option<u32>,
+}
+
+/// File format of an exported delay log
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+/// Every delay test of the session, oldest first
+#[derive(Debug, Default)]
+pub struct DelayLog {
+    tests: Vec<DelayTest>,
+}
+
+impl DelayLog {
+    pub fn record(&mut self, node: &str, node_type: &str, delay: Option<u32>) {
+        self.tests.push(DelayTest {
+            at: Local::now(),
+            node: node.to_string(),
+            node_type: node_type.to_string(),
+            delay,
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.tests.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tests.is_empty()
+    }
+
+    /// `time,node,type,delay_ms` rows; failed tests leave `delay_ms` empty
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("time,node,type,delay_ms\n");
+        for test in &self.tests {
+            csv.push_str(&format!(
+                "{},{},{},{}\n",
+                test.at.to_rfc3339_opts(SecondsFormat::Secs, false),
+                csv_field(&test.node),
+                csv_field(&test.node_type),
+                test.delay
+                    .map(|delay| delay.to_string())
+                    .unwrap_or_default()
+            ));
+        }
+        csv
+    }
+
+    /// Array of `{time, node, type, delay_ms}`; failed tests have a null `delay_ms`
+    pub fn to_json(&self) -> Result<String> {
+        let tests: Vec<serde_json::Value> = self
+            .tests
+            .iter()
+            .map(|test| {
+                serde_json::json!({
+                    "time": test.at.to_rfc3339_opts(SecondsFormat::Secs, false),
+                    "node": test.node,
+                    "type": test.node_type,
+                    "delay_ms": test.delay,
+                })
+            })
+            .collect();
+        Ok(serde_json::to_string_pretty(&tests)?)
+    }
+
+    /// Write the log to `exports/delay-tests-<time>.<ext>` in the config dir
+    pub fn export(&self, format: ExportFormat) -> Result<PathBuf> {
+        let contents = match format {
+            ExportFormat::Csv => self.to_csv(),
+            ExportFormat::Json => self.to_json()?,
+        };
+        write_export("delay-tests", format, &contents)
+    }
+}
+
+/// Write `contents` to `exports/<name>-<time>.<ext>` in the config dir
+pub fn write_export(name: &str, format: ExportFormat, contents: &str) -> Result<PathBuf> {
+    let path = crate::config::paths::config_dir()?
+        .join("exports")
+        .join(format!(
+            "{}-{}.{}",
+            name,
+            Local::now().format("%Y%m%d-%H%M%S"),
+            format.extension()
+        ));
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, contents)?;
+    Ok(path)
+}
+
+/// Quote a CSV field when it holds a comma, quote or line break
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_quotes_and_failures() {
+        let mut log = DelayLog::default();
+        log.record("HK, \"Premium\"", "Shadowsocks", Some(120));
+        log.record("JP-1", "Vmess", None);
+
+        let csv = log.to_csv();
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows[0], "time,node,type,delay_ms");
+        assert!(rows[1].ends_with(",\"HK, \"\"Premium\"\"\",Shadowsocks,120"));
+        assert!(rows[2].ends_with(",JP-1,Vmess,"));
+
+        let json: serde_json::Value = serde_json::from_str(&log.to_json().unwrap()).unwrap();
+        assert_eq!(json[0]["delay_ms"], 120);
+        assert!(json[1]["delay_ms"].is_null());
+    }
+}
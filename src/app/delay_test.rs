@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::app::region;
+use crate::clash::ClashClient;
+use crate::config::DelayTestConfig;
+
+/// Endpoints answering with an empty 204, so a test times the round trip and not a page load
+pub const DEFAULT_URLS: [&str; 2] = [
+    "https://www.gstatic.com/generate_204",
+    "https://cp.cloudflare.com/generate_204",
+];
+
+/// Timeout (ms) of each delay test
+pub const TEST_TIMEOUT_MS: u32 = 5000;
+
+/// Where delay tests go: a list of URLs tried in turn, optionally rotated,
+/// with a URL per region tried first
+#[derive(Debug, Clone)]
+pub struct DelayTest {
+    urls: Vec<String>,
+    rotate: bool,
+    /// Upper-case region code → URL
+    regions: HashMap<String, String>,
+    /// URL the next rotated test starts at
+    next: Arc<AtomicUsize>,
+}
+
+impl Default for DelayTest {
+    fn default() -> Self {
+        Self::from_config(&DelayTestConfig::default())
+    }
+}
+
+impl DelayTest {
+    pub fn from_config(config: &DelayTestConfig) -> Self {
+        let urls = if config.urls.is_empty() {
+            DEFAULT_URLS.iter().map(|url| url.to_string()).collect()
+        } else {
+            config.urls.clone()
+        };
+        Self {
+            urls,
+            rotate: config.rotate,
+            regions: config
+                .regions
+                .iter()
+                .map(|(code, url)| (code.to_uppercase(), url.clone()))
+                .collect(),
+            next: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// URLs to try for `node`, in order: its region's, then the list
+    pub fn urls_for(&self, node: &str) -> Vec<&str> {
+        let start = if self.rotate {
+            self.next.fetch_add(1, Ordering::Relaxed) % self.urls.len()
+        } else {
+            0
+        };
+        let regional = region::region_of(node).and_then(|code| self.regions.get(code));
+        let mut urls: Vec<&str> = regional.map(String::as_str).into_iter().collect();
+        for url in self.urls[start..].iter().chain(&self.urls[..start]) {
+            if !urls.contains(&url.as_str()) {
+                urls.push(url);
+            }
+        }
+        urls
+    }
+
+    /// Delay of `node` in ms from the first URL it reaches, None when none answers
+    pub async fn run(&self, client: &ClashClient, node: &str) -> Option<u32> {
+        for url in self.urls_for(node) {
+            if let Ok(response) = client
+                .test_delay(node, Some(url), Some(TEST_TIMEOUT_MS))
+                .await
+            {
+                return Some(response.delay);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_urls_for_rotates_and_prefers_region() {
+        let config = DelayTestConfig {
+            urls: vec!["https://a/204".to_string(), "https://b/204".to_string()],
+            rotate: true,
+            regions: HashMap::from([("hk".to_string(), "https://hk/204".to_string())]),
+        };
+        let test = DelayTest::from_config(&config);
+        assert_eq!(test.urls_for("US 01"), ["https://a/204", "https://b/204"]);
+        assert_eq!(test.urls_for("US 02"), ["https://b/204", "https://a/204"]);
+        assert_eq!(
+            test.urls_for("🇭🇰 香港 01"),
+            ["https://hk/204", "https://a/204", "https://b/204"]
+        );
+
+        let fixed = DelayTest::default();
+        assert_eq!(fixed.urls_for("JP"), DEFAULT_URLS);
+        assert_eq!(fixed.urls_for("JP"), DEFAULT_URLS);
+    }
+}
@@ -0,0 +1,79 @@
+use chrono::{DateTime, Local};
+use std::collections::VecDeque;
+
+/// Errors kept for the error console; older ones are dropped
+const MAX_ERRORS: usize = 200;
+
+/// An error reported during the session
+#[derive(Debug, Clone)]
+pub struct ErrorEntry {
+    pub at: DateTime<Local>,
+    /// What was being done, e.g. "Failed to fetch rules"
+    pub context: String,
+    /// The error and each of its causes, outermost first
+    pub chain: Vec<String>,
+}
+
+/// Session-wide log of reported errors, newest last
+#[derive(Debug, Default)]
+pub struct ErrorLog {
+    entries: VecDeque<ErrorEntry>,
+}
+
+impl ErrorLog {
+    pub fn record(&mut self, context: &str, error: &anyhow::Error) {
+        let entry = ErrorEntry {
+            at: Local::now(),
+            context: context.to_string(),
+            chain: error.chain().map(|cause| cause.to_string()).collect(),
+        };
+        crate::ui::debug_log(&format!("error: {}: {:#}", entry.context, error));
+        if self.entries.len() == MAX_ERRORS {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    /// Newest first
+    pub fn entries(&self) -> impl Iterator<Item = &ErrorEntry> {
+        self.entries.iter().rev()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn keeps_the_full_chain_newest_first() {
+        let mut log = ErrorLog::default();
+        log.record("first", &anyhow::anyhow!("boom"));
+        let error = Err::<(), _>(anyhow::anyhow!("connection refused"))
+            .context("GET /rules")
+            .unwrap_err();
+        log.record("Failed to fetch rules", &error);
+
+        let newest = log.entries().next().unwrap();
+        assert_eq!(newest.context, "Failed to fetch rules");
+        assert_eq!(newest.chain, vec!["GET /rules", "connection refused"]);
+    }
+
+    #[test]
+    fn drops_the_oldest_when_full() {
+        let mut log = ErrorLog::default();
+        for i in 0..MAX_ERRORS + 5 {
+            log.record(&i.to_string(), &anyhow::anyhow!("error"));
+        }
+        assert_eq!(log.len(), MAX_ERRORS);
+        assert_eq!(log.entries().last().unwrap().context, "5");
+    }
+}
@@ -0,0 +1,210 @@
+use std::fmt;
+
+/// Characters of an HTML page's title kept in the diagnosis
+const MAX_TITLE_CHARS: usize = 60;
+
+/// Why a subscription download failed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FailureKind {
+    Dns,
+    Tls,
+    /// 401/403: the token was refused
+    Forbidden(u16),
+    /// 429
+    RateLimited,
+    HttpStatus(u16),
+    Timeout,
+    /// Refused, reset or unreachable
+    Connect,
+    /// An HTML page came back instead of a subscription
+    NotSubscription,
+    Other,
+}
+
+impl FailureKind {
+    pub fn label(self) -> String {
+        match self {
+            FailureKind::Dns => "DNS lookup failed".to_string(),
+            FailureKind::Tls => "TLS certificate rejected".to_string(),
+            FailureKind::Forbidden(status) => format!("HTTP {}: access denied", status),
+            FailureKind::RateLimited => "HTTP 429: rate limited".to_string(),
+            FailureKind::HttpStatus(status) => format!("HTTP {}", status),
+            FailureKind::Timeout => "Timed out".to_string(),
+            FailureKind::Connect => "Connection failed".to_string(),
+            FailureKind::NotSubscription => "Not a subscription".to_string(),
+            FailureKind::Other => "Download failed".to_string(),
+        }
+    }
+
+    /// What to try next
+    pub fn hint(self) -> &'static str {
+        match self {
+            FailureKind::Dns => {
+                "The host doesn't resolve: check the URL for typos, and the system DNS if the core isn't running"
+            }
+            FailureKind::Tls => {
+                "The certificate is expired, self-signed or for another host: check the system clock and the URL's domain"
+            }
+            FailureKind::Forbidden(_) => {
+                "The provider refused the token: the subscription may have expired or been reset, copy a fresh URL"
+            }
+            FailureKind::RateLimited => "Too many requests: wait a few minutes before updating again",
+            FailureKind::HttpStatus(status) if status >= 500 => {
+                "The provider's server failed: try again later"
+            }
+            FailureKind::HttpStatus(_) => "Check that the URL is still the one your provider gives",
+            FailureKind::Timeout | FailureKind::Connect => {
+                "The host may be blocked on this network: retry once a proxy works, or from another network"
+            }
+            FailureKind::NotSubscription => {
+                "The URL returned a web page (login, captcha or landing page): copy the subscription link itself"
+            }
+            FailureKind::Other => "See the error below",
+        }
+    }
+}
+
+/// A failed subscription download, classified so the Update page can say what to do
+#[derive(Debug, Clone, PartialEq)]
+pub struct FetchFailure {
+    pub kind: FailureKind,
+    /// The error as reported
+    pub detail: String,
+}
+
+impl FetchFailure {
+    pub fn classify(error: &anyhow::Error) -> Self {
+        let detail = format!("{:#}", error);
+        Self {
+            kind: classify_kind(error, &detail),
+            detail,
+        }
+    }
+
+    /// One line for the status bar
+    pub fn summary(&self) -> String {
+        match self.kind {
+            FailureKind::Other => self.detail.clone(),
+            kind => format!("{} ({})", kind.label(), self.detail),
+        }
+    }
+}
+
+fn classify_kind(error: &anyhow::Error, detail: &str) -> FailureKind {
+    if error.chain().any(|cause| cause.is::<HtmlBody>()) {
+        return FailureKind::NotSubscription;
+    }
+    let http = error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<reqwest::Error>());
+    if let Some(status) = http.and_then(reqwest::Error::status) {
+        return status_kind(status.as_u16());
+    }
+
+    // The core reports provider failures as text, so match that too
+    let text = detail.to_lowercase();
+    let mentions = |words: &[&str]| words.iter().any(|word| text.contains(word));
+    if mentions(&[
+        "dns error",
+        "failed to lookup address",
+        "no such host",
+        "name or service not known",
+        "nodename nor servname",
+    ]) {
+        FailureKind::Dns
+    } else if mentions(&["certificate", "unknownissuer", "x509", "handshake"]) {
+        FailureKind::Tls
+    } else if http.is_some_and(reqwest::Error::is_timeout) || mentions(&["timed out", "timeout"]) {
+        FailureKind::Timeout
+    } else if mentions(&["403 forbidden"]) {
+        FailureKind::Forbidden(403)
+    } else if mentions(&["429 too many"]) {
+        FailureKind::RateLimited
+    } else if http.is_some_and(reqwest::Error::is_connect)
+        || mentions(&["connection refused", "connection reset", "unreachable"])
+    {
+        FailureKind::Connect
+    } else {
+        FailureKind::Other
+    }
+}
+
+fn status_kind(status: u16) -> FailureKind {
+    match status {
+        401 | 403 => FailureKind::Forbidden(status),
+        429 => FailureKind::RateLimited,
+        _ => FailureKind::HttpStatus(status),
+    }
+}
+
+/// A subscription URL answered with an HTML page
+#[derive(Debug, Clone, PartialEq)]
+pub struct HtmlBody {
+    pub title: Option<String>,
+}
+
+impl HtmlBody {
+    /// The page `bytes` is, when it's HTML rather than a config or share links
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        let head = String::from_utf8_lossy(&bytes[..bytes.len().min(4096)]).into_owned();
+        // ASCII lowercasing keeps byte offsets, so they apply to `head` too
+        let lower = head.to_ascii_lowercase();
+        let start = lower.trim_start();
+        if !(start.starts_with("<!doctype html") || start.starts_with("<html")) {
+            return None;
+        }
+        let title = lower
+            .find("<title>")
+            .map(|at| &head[at + "<title>".len()..])
+            .map(|rest| rest[..rest.find('<').unwrap_or(rest.len())].trim())
+            .filter(|title| !title.is_empty())
+            .map(|title| title.chars().take(MAX_TITLE_CHARS).collect());
+        Some(Self { title })
+    }
+}
+
+impl fmt::Display for HtmlBody {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.title {
+            Some(title) => write!(f, "got an HTML page \"{}\"", title),
+            None => write!(f, "got an HTML page"),
+        }
+    }
+}
+
+impl std::error::Error for HtmlBody {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_fetch_failures() {
+        let kind =
+            |message: &str| FetchFailure::classify(&anyhow::anyhow!(message.to_string())).kind;
+        assert_eq!(
+            kind("error sending request: dns error: failed to lookup address information"),
+            FailureKind::Dns
+        );
+        assert_eq!(
+            kind("invalid peer certificate: UnknownIssuer"),
+            FailureKind::Tls
+        );
+        assert_eq!(
+            kind("Clash API returned error: 503 - {\"message\":\"429 Too Many Requests\"}"),
+            FailureKind::RateLimited
+        );
+        assert_eq!(kind("operation timed out"), FailureKind::Timeout);
+        assert_eq!(kind("something else"), FailureKind::Other);
+
+        let page = HtmlBody::detect(
+            b"\n<!DOCTYPE html><html><head><title> Just a moment... </title></head></html>",
+        )
+        .unwrap();
+        assert_eq!(page.title.as_deref(), Some("Just a moment..."));
+        let failure = FetchFailure::classify(&anyhow::Error::new(page));
+        assert_eq!(failure.kind, FailureKind::NotSubscription);
+        assert!(HtmlBody::detect(b"proxies:\n  - name: a\n").is_none());
+        assert!(HtmlBody::detect(b"dm1lc3M6Ly9leGFtcGxl").is_none());
+    }
+}
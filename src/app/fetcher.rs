@@ -0,0 +1,319 @@
+use anyhow::Result;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use crate::app::probe::{self, ProbeStatus};
+use crate::app::state::ClashSnapshot;
+use crate::clash::reconnect::{backoff_delay, jitter};
+use crate::clash::{
+    is_transient, ClashClient, ClashMode, ConfigResponse, ConnectionsResponse, Provider,
+    ProvidersResponse, Proxy, Rule, RuleProvider,
+};
+use crate::core::service;
+
+/// Tries at a node switch before it's given up and rolled back
+const SELECT_ATTEMPTS: u32 = 3;
+
+/// How long a connection test waits for the core
+const CONNECTION_TEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Clash API request handled by the background fetcher
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApiRequest {
+    Refresh,
+    SwitchMode(ClashMode),
+    SetLogLevel(String),
+    SelectProxy {
+        selector: String,
+        proxy: String,
+    },
+    /// Let a url-test group pick its node again
+    UnpinProxy(String),
+    Connections,
+    CloseConnection(String),
+    CloseAllConnections,
+    Rules,
+    RuleProvider(String),
+    Providers,
+    Provider(String),
+    HealthCheckProvider(String),
+    ToggleTun,
+    /// Fetch this URL through the core's proxy port
+    ProbeProxy(String),
+    /// Check the API answers at all
+    TestConnection,
+    /// The core's running configuration
+    CoreConfig,
+}
+
+impl ApiRequest {
+    /// Short description shown next to the busy spinner
+    pub fn label(&self) -> &'static str {
+        match self {
+            ApiRequest::Refresh => "Refreshing",
+            ApiRequest::SwitchMode(_) => "Switching mode",
+            ApiRequest::SetLogLevel(_) => "Setting log level",
+            ApiRequest::SelectProxy { .. } => "Switching node",
+            ApiRequest::UnpinProxy(_) => "Unpinning node",
+            ApiRequest::Connections => "Loading connections",
+            ApiRequest::CloseConnection(_) => "Closing connection",
+            ApiRequest::CloseAllConnections => "Closing connections",
+            ApiRequest::Rules => "Loading rules",
+            ApiRequest::RuleProvider(_) => "Loading rule-set",
+            ApiRequest::Providers => "Checking subscriptions",
+            ApiRequest::Provider(_) => "Loading provider",
+            ApiRequest::HealthCheckProvider(_) => "Checking provider",
+            ApiRequest::ToggleTun => "Toggling TUN",
+            ApiRequest::ProbeProxy(_) => "Checking proxy",
+            ApiRequest::TestConnection => "Testing connection",
+            ApiRequest::CoreConfig => "Reading core config",
+        }
+    }
+}
+
+/// Result of a Clash API request
+#[derive(Debug)]
+pub enum ApiResponse {
+    Refreshed {
+        snapshot: Result<ClashSnapshot>,
+        /// Whether the core process is running, checked when the API is unreachable
+        core_running: Option<bool>,
+    },
+    ModeSwitched {
+        mode: ClashMode,
+        result: Result<()>,
+    },
+    LogLevelSet {
+        level: String,
+        result: Result<()>,
+    },
+    ProxySelected {
+        selector: String,
+        proxy: String,
+        result: Result<()>,
+        /// The group as the core reports it after the switch, None if fetching it failed
+        group: Option<Proxy>,
+    },
+    ProxyUnpinned {
+        selector: String,
+        result: Result<()>,
+        /// The group as the core reports it afterwards, None if fetching it failed
+        group: Option<Proxy>,
+    },
+    Connections(Result<ConnectionsResponse>),
+    ConnectionClosed {
+        id: String,
+        result: Result<()>,
+    },
+    AllConnectionsClosed(Result<()>),
+    Rules(Result<Vec<Rule>>),
+    RuleProvider {
+        name: String,
+        result: Result<RuleProvider>,
+    },
+    Providers(Result<ProvidersResponse>),
+    Provider {
+        name: String,
+        result: Result<Provider>,
+    },
+    ProviderHealthChecked {
+        name: String,
+        result: Result<()>,
+    },
+    /// Whether TUN is on after the toggle
+    TunToggled(Result<bool>),
+    ProxyProbed {
+        url: String,
+        status: ProbeStatus,
+    },
+    ConnectionTested(Result<()>),
+    CoreConfig(Result<ConfigResponse>),
+}
+
+impl ApiResponse {
+    /// The request this response answers
+    pub fn request(&self) -> ApiRequest {
+        match self {
+            ApiResponse::Refreshed { .. } => ApiRequest::Refresh,
+            ApiResponse::ModeSwitched { mode, .. } => ApiRequest::SwitchMode(mode.clone()),
+            ApiResponse::LogLevelSet { level, .. } => ApiRequest::SetLogLevel(level.clone()),
+            ApiResponse::ProxySelected {
+                selector, proxy, ..
+            } => ApiRequest::SelectProxy {
+                selector: selector.clone(),
+                proxy: proxy.clone(),
+            },
+            ApiResponse::ProxyUnpinned { selector, .. } => ApiRequest::UnpinProxy(selector.clone()),
+            ApiResponse::Connections(_) => ApiRequest::Connections,
+            ApiResponse::ConnectionClosed { id, .. } => ApiRequest::CloseConnection(id.clone()),
+            ApiResponse::AllConnectionsClosed(_) => ApiRequest::CloseAllConnections,
+            ApiResponse::Rules(_) => ApiRequest::Rules,
+            ApiResponse::RuleProvider { name, .. } => ApiRequest::RuleProvider(name.clone()),
+            ApiResponse::Providers(_) => ApiRequest::Providers,
+            ApiResponse::Provider { name, .. } => ApiRequest::Provider(name.clone()),
+            ApiResponse::ProviderHealthChecked { name, .. } => {
+                ApiRequest::HealthCheckProvider(name.clone())
+            }
+            ApiResponse::TunToggled(_) => ApiRequest::ToggleTun,
+            ApiResponse::ProxyProbed { url, .. } => ApiRequest::ProbeProxy(url.clone()),
+            ApiResponse::ConnectionTested(_) => ApiRequest::TestConnection,
+            ApiResponse::CoreConfig(_) => ApiRequest::CoreConfig,
+        }
+    }
+}
+
+/// Spawn the background fetcher task
+/// Each request runs in its own task so a slow call never holds up the others,
+/// except node switches and unpins: they're queued and run in order so the last one pressed wins
+pub fn spawn_fetcher(
+    client: ClashClient,
+) -> (
+    mpsc::UnboundedSender<ApiRequest>,
+    mpsc::UnboundedReceiver<ApiResponse>,
+) {
+    let (request_tx, mut request_rx) = mpsc::unbounded_channel::<ApiRequest>();
+    let (response_tx, response_rx) = mpsc::unbounded_channel();
+    let (select_tx, mut select_rx) = mpsc::unbounded_channel::<ApiRequest>();
+
+    let select_client = client.clone();
+    let select_response_tx = response_tx.clone();
+    tokio::spawn(async move {
+        while let Some(request) = select_rx.recv().await {
+            let _ = select_response_tx.send(execute(&select_client, request).await);
+        }
+    });
+
+    tokio::spawn(async move {
+        while let Some(request) = request_rx.recv().await {
+            if matches!(
+                request,
+                ApiRequest::SelectProxy { .. } | ApiRequest::UnpinProxy(_)
+            ) {
+                let _ = select_tx.send(request);
+                continue;
+            }
+            let client = client.clone();
+            let tx = response_tx.clone();
+            tokio::spawn(async move {
+                let _ = tx.send(execute(&client, request).await);
+            });
+        }
+    });
+
+    (request_tx, response_rx)
+}
+
+async fn execute(client: &ClashClient, request: ApiRequest) -> ApiResponse {
+    match request {
+        ApiRequest::Refresh => {
+            let snapshot = ClashSnapshot::fetch(client).await;
+            let core_running = match &snapshot {
+                Ok(_) => Some(true),
+                Err(_) => service::is_core_running().await,
+            };
+            ApiResponse::Refreshed {
+                snapshot,
+                core_running,
+            }
+        }
+        ApiRequest::SwitchMode(mode) => {
+            let config = serde_json::json!({
+                "mode": mode.as_str()
+            });
+            let result = client.update_config(config).await;
+            ApiResponse::ModeSwitched { mode, result }
+        }
+        ApiRequest::SetLogLevel(level) => {
+            let config = serde_json::json!({
+                "log-level": level
+            });
+            let result = client.update_config(config).await;
+            ApiResponse::LogLevelSet { level, result }
+        }
+        ApiRequest::SelectProxy { selector, proxy } => {
+            let result = select_with_retry(client, &selector, &proxy).await;
+            // Only this group changed, so fetch it instead of every proxy
+            let group = match result {
+                Ok(()) => client.get_proxy(&selector).await.ok(),
+                Err(_) => None,
+            };
+            ApiResponse::ProxySelected {
+                selector,
+                proxy,
+                result,
+                group,
+            }
+        }
+        ApiRequest::UnpinProxy(selector) => {
+            let result = client.unpin_proxy(&selector).await;
+            let group = match result {
+                Ok(()) => client.get_proxy(&selector).await.ok(),
+                Err(_) => None,
+            };
+            ApiResponse::ProxyUnpinned {
+                selector,
+                result,
+                group,
+            }
+        }
+        ApiRequest::Connections => ApiResponse::Connections(client.get_connections().await),
+        ApiRequest::CloseConnection(id) => {
+            let result = client.close_connection(&id).await;
+            ApiResponse::ConnectionClosed { id, result }
+        }
+        ApiRequest::CloseAllConnections => {
+            ApiResponse::AllConnectionsClosed(client.close_all_connections().await)
+        }
+        ApiRequest::Rules => {
+            ApiResponse::Rules(client.get_rules().await.map(|response| response.rules))
+        }
+        ApiRequest::RuleProvider(name) => {
+            let result = client.get_rule_provider(&name).await;
+            ApiResponse::RuleProvider { name, result }
+        }
+        ApiRequest::Providers => ApiResponse::Providers(client.get_providers().await),
+        ApiRequest::Provider(name) => {
+            let result = client.get_provider(&name).await;
+            ApiResponse::Provider { name, result }
+        }
+        ApiRequest::HealthCheckProvider(name) => {
+            let result = client.healthcheck_provider(&name).await;
+            ApiResponse::ProviderHealthChecked { name, result }
+        }
+        ApiRequest::ToggleTun => ApiResponse::TunToggled(toggle_tun(client).await),
+        ApiRequest::ProbeProxy(url) => {
+            let status = probe::probe(client, &url).await;
+            ApiResponse::ProxyProbed { url, status }
+        }
+        ApiRequest::TestConnection => ApiResponse::ConnectionTested(
+            client.test_connection_within(CONNECTION_TEST_TIMEOUT).await,
+        ),
+        ApiRequest::CoreConfig => ApiResponse::CoreConfig(client.get_config().await),
+    }
+}
+
+/// Flip the core's TUN setting, returning the new one
+async fn toggle_tun(client: &ClashClient) -> Result<bool> {
+    let config = client.get_config().await?;
+    let enable = !config.tun.is_some_and(|tun| tun.enable);
+    client
+        .update_config(serde_json::json!({ "tun": { "enable": enable } }))
+        .await?;
+    Ok(enable)
+}
+
+/// Switch a group's node, retrying with backoff up to `SELECT_ATTEMPTS` times
+/// Only transport errors and server errors are retried; an unknown node or group fails at once
+async fn select_with_retry(client: &ClashClient, selector: &str, proxy: &str) -> Result<()> {
+    let mut attempt = 1;
+    loop {
+        match client.select_proxy(selector, proxy).await {
+            Ok(()) => return Ok(()),
+            Err(e) if attempt >= SELECT_ATTEMPTS || !is_transient(&e) => return Err(e),
+            Err(_) => {
+                tokio::time::sleep(backoff_delay(attempt, jitter())).await;
+                attempt += 1;
+            }
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+/// How often the TUI looks for a running GUI in the background
+pub const GUI_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Warns before clashctl writes files a running GUI (Mihomo Party, Clash Verge) may overwrite:
+/// its profiles, and the work config it generates
+#[derive(Debug, Default)]
+pub struct GuiGuard {
+    /// The GUI owning the profiles, while it's running
+    running: Option<&'static str>,
+    /// Action warned about last; repeating it goes ahead
+    warned: Option<String>,
+}
+
+impl GuiGuard {
+    /// Record what the latest check found running
+    pub fn set_running(&mut self, gui: Option<&'static str>) {
+        if gui.is_none() {
+            self.warned = None;
+        }
+        self.running = gui;
+    }
+
+    pub fn running(&self) -> Option<&'static str> {
+        self.running
+    }
+
+    /// Whether `action` may write now; while the GUI runs the first attempt is refused with
+    /// the warning to show, and repeating the same action right after goes ahead
+    pub fn check(&mut self, action: &str) -> Result<(), String> {
+        let Some(gui) = self.running else {
+            self.warned = None;
+            return Ok(());
+        };
+        if self.warned.as_deref() == Some(action) {
+            self.warned = None;
+            return Ok(());
+        }
+        self.warned = Some(action.to_string());
+        Err(format!(
+            "{} is running and may overwrite \"{}\": quit it first, or repeat it to go ahead",
+            gui, action
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confirmation_is_per_action() {
+        let mut guard = GuiGuard::default();
+        assert!(guard.check("Reorder rules").is_ok());
+
+        guard.set_running(Some("Mihomo Party"));
+        assert!(guard.check("Reorder rules").is_err());
+        // Another action doesn't ride on the first one's warning
+        assert!(guard.check("Bypass example.com").is_err());
+        assert!(guard.check("Bypass example.com").is_ok());
+        // Each write that went ahead is warned about again next time
+        assert!(guard.check("Bypass example.com").is_err());
+
+        guard.set_running(None);
+        assert!(guard.check("Reorder rules").is_ok());
+    }
+}
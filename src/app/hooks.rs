@@ -0,0 +1,170 @@
+use std::process::Stdio;
+
+use anyhow::{bail, Context, Result};
+use tokio::process::Command;
+use tokio::sync::mpsc;
+
+use crate::config::HooksConfig;
+
+/// Events a hook command can be attached to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HookEvent {
+    NodeSwitched,
+    ModeChanged,
+    SubscriptionUpdated,
+    CoreUnreachable,
+}
+
+impl HookEvent {
+    /// Config key, also passed to the command as `CLASHCTL_EVENT`
+    pub fn key(&self) -> &'static str {
+        match self {
+            HookEvent::NodeSwitched => "node_switched",
+            HookEvent::ModeChanged => "mode_changed",
+            HookEvent::SubscriptionUpdated => "subscription_updated",
+            HookEvent::CoreUnreachable => "core_unreachable",
+        }
+    }
+}
+
+/// A hook command that could not be started or exited with an error
+#[derive(Debug)]
+pub struct HookFailure {
+    pub event: HookEvent,
+    pub error: anyhow::Error,
+}
+
+/// Runs the configured hook commands in the background
+/// Core unreachable fires once and again only after the core has answered
+#[derive(Debug)]
+pub struct Hooks {
+    config: HooksConfig,
+    api_url: String,
+    core_down: bool,
+    failure_tx: mpsc::UnboundedSender<HookFailure>,
+    failure_rx: mpsc::UnboundedReceiver<HookFailure>,
+}
+
+impl Default for Hooks {
+    fn default() -> Self {
+        let (failure_tx, failure_rx) = mpsc::unbounded_channel();
+        Self {
+            config: HooksConfig::default(),
+            api_url: String::new(),
+            core_down: false,
+            failure_tx,
+            failure_rx,
+        }
+    }
+}
+
+impl Hooks {
+    pub fn set_config(&mut self, config: HooksConfig, api_url: &str) {
+        self.config = config;
+        self.api_url = api_url.to_string();
+    }
+
+    /// Run the command for `event` if one is configured, with `vars` in its environment
+    pub fn fire(&self, event: HookEvent, vars: &[(&str, &str)]) {
+        let Some(command) = self.config.command(event) else {
+            return;
+        };
+        let command = command.to_string();
+        let env = hook_env(event, &self.api_url, vars);
+        let failure_tx = self.failure_tx.clone();
+        tokio::spawn(async move {
+            if let Err(error) = run(&command, env).await {
+                let _ = failure_tx.send(HookFailure { event, error });
+            }
+        });
+    }
+
+    /// Record whether the core answered the last refresh
+    pub fn core_status(&mut self, error: Option<&str>) {
+        match error {
+            Some(error) if !self.core_down => {
+                self.core_down = true;
+                self.fire(HookEvent::CoreUnreachable, &[("CLASHCTL_ERROR", error)]);
+            }
+            Some(_) => {}
+            None => self.core_down = false,
+        }
+    }
+
+    /// Take the next failed hook, if any
+    pub fn next_failure(&mut self) -> Option<HookFailure> {
+        self.failure_rx.try_recv().ok()
+    }
+}
+
+/// Environment passed to a hook: the event, the API URL and the event's details
+fn hook_env(event: HookEvent, api_url: &str, vars: &[(&str, &str)]) -> Vec<(String, String)> {
+    let mut env = vec![
+        ("CLASHCTL_EVENT".to_string(), event.key().to_string()),
+        ("CLASHCTL_API_URL".to_string(), api_url.to_string()),
+    ];
+    env.extend(
+        vars.iter()
+            .map(|(name, value)| (name.to_string(), value.to_string())),
+    );
+    env
+}
+
+async fn run(command: &str, env: Vec<(String, String)>) -> Result<()> {
+    let mut shell = if cfg!(target_os = "windows") {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    let output = shell
+        .arg(command)
+        .envs(env)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .await
+        .with_context(|| format!("Failed to run {}", command))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let detail = stderr.trim();
+        if detail.is_empty() {
+            bail!("{}", output.status);
+        }
+        bail!("{}", detail);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hook_env_and_core_transitions() {
+        let env = hook_env(
+            HookEvent::NodeSwitched,
+            "http://127.0.0.1:9090",
+            &[("CLASHCTL_GROUP", "Proxy"), ("CLASHCTL_NODE", "HK-1")],
+        );
+        assert_eq!(env[0], ("CLASHCTL_EVENT".into(), "node_switched".into()));
+        assert_eq!(env[1].1, "http://127.0.0.1:9090");
+        assert_eq!(env[3], ("CLASHCTL_NODE".into(), "HK-1".into()));
+
+        // No command configured, so nothing is spawned
+        let mut hooks = Hooks::default();
+        hooks.core_status(Some("connection refused"));
+        assert!(hooks.core_down);
+        hooks.core_status(None);
+        assert!(!hooks.core_down);
+
+        let config: HooksConfig = serde_yaml::from_str("mode_changed: ' '").unwrap();
+        assert_eq!(config.command(HookEvent::ModeChanged), None);
+    }
+}
@@ -0,0 +1,237 @@
+use serde_json::{Map, Value};
+use std::fmt;
+use std::time::Duration;
+
+use crate::clash::ConfigResponse;
+
+/// How long a re-test waits for each port to accept a connection
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Ports below this need root (or CAP_NET_BIND_SERVICE) to bind
+const FIRST_UNPRIVILEGED_PORT: u16 = 1024;
+
+/// Inbound listener settings patched through /configs, in display order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InboundField {
+    MixedPort,
+    Port,
+    SocksPort,
+    RedirPort,
+    TproxyPort,
+    AllowLan,
+}
+
+impl InboundField {
+    pub const ALL: [InboundField; 6] = [
+        InboundField::MixedPort,
+        InboundField::Port,
+        InboundField::SocksPort,
+        InboundField::RedirPort,
+        InboundField::TproxyPort,
+        InboundField::AllowLan,
+    ];
+
+    /// Key in the /configs payload
+    pub fn key(self) -> &'static str {
+        match self {
+            InboundField::MixedPort => "mixed-port",
+            InboundField::Port => "port",
+            InboundField::SocksPort => "socks-port",
+            InboundField::RedirPort => "redir-port",
+            InboundField::TproxyPort => "tproxy-port",
+            InboundField::AllowLan => "allow-lan",
+        }
+    }
+}
+
+/// Inbound ports (0 = disabled) and allow-lan of the running core
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct InboundPorts {
+    pub mixed_port: u16,
+    pub port: u16,
+    pub socks_port: u16,
+    pub redir_port: u16,
+    pub tproxy_port: u16,
+    pub allow_lan: bool,
+}
+
+/// Something wrong with edited ports
+#[derive(Debug, Clone, PartialEq)]
+pub enum PortProblem {
+    /// Two listeners on the same port; the core would fail to bind the second
+    Conflict {
+        port: u16,
+        first: InboundField,
+        second: InboundField,
+    },
+    /// A changed port below 1024, which only a privileged core can bind
+    Privileged { field: InboundField, port: u16 },
+}
+
+impl PortProblem {
+    /// Conflicts can't be applied; privileged ports only need confirming
+    pub fn is_blocking(&self) -> bool {
+        matches!(self, PortProblem::Conflict { .. })
+    }
+}
+
+impl fmt::Display for PortProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PortProblem::Conflict {
+                port,
+                first,
+                second,
+            } => write!(
+                f,
+                "{} and {} both use port {}",
+                first.key(),
+                second.key(),
+                port
+            ),
+            PortProblem::Privileged { field, port } => write!(
+                f,
+                "{} {} is privileged; the core must run as root to bind it",
+                field.key(),
+                port
+            ),
+        }
+    }
+}
+
+impl InboundPorts {
+    pub fn from_config(config: &ConfigResponse) -> Self {
+        Self {
+            mixed_port: config.mixed_port,
+            port: config.port,
+            socks_port: config.socks_port,
+            redir_port: config.redir_port,
+            tproxy_port: config.tproxy_port,
+            allow_lan: config.allow_lan,
+        }
+    }
+
+    /// Port of `field`; None for allow-lan
+    pub fn port(&self, field: InboundField) -> Option<u16> {
+        match field {
+            InboundField::MixedPort => Some(self.mixed_port),
+            InboundField::Port => Some(self.port),
+            InboundField::SocksPort => Some(self.socks_port),
+            InboundField::RedirPort => Some(self.redir_port),
+            InboundField::TproxyPort => Some(self.tproxy_port),
+            InboundField::AllowLan => None,
+        }
+    }
+
+    pub fn set_port(&mut self, field: InboundField, port: u16) {
+        match field {
+            InboundField::MixedPort => self.mixed_port = port,
+            InboundField::Port => self.port = port,
+            InboundField::SocksPort => self.socks_port = port,
+            InboundField::RedirPort => self.redir_port = port,
+            InboundField::TproxyPort => self.tproxy_port = port,
+            InboundField::AllowLan => {}
+        }
+    }
+
+    /// Enabled listeners as (field, port)
+    pub fn listeners(&self) -> Vec<(InboundField, u16)> {
+        InboundField::ALL
+            .iter()
+            .filter_map(|field| Some((*field, self.port(*field)?)))
+            .filter(|(_, port)| *port != 0)
+            .collect()
+    }
+
+    /// Conflicting listeners, and ports changed from `original` to a privileged one
+    pub fn problems(&self, original: &InboundPorts) -> Vec<PortProblem> {
+        let listeners = self.listeners();
+        let mut problems = Vec::new();
+        for (i, (first, port)) in listeners.iter().enumerate() {
+            if let Some((second, _)) = listeners[i + 1..].iter().find(|(_, p)| p == port) {
+                problems.push(PortProblem::Conflict {
+                    port: *port,
+                    first: *first,
+                    second: *second,
+                });
+            }
+        }
+        for (field, port) in listeners {
+            if port < FIRST_UNPRIVILEGED_PORT && original.port(field) != Some(port) {
+                problems.push(PortProblem::Privileged { field, port });
+            }
+        }
+        problems
+    }
+
+    /// PATCH /configs body with only the fields changed from `original`
+    pub fn patch(&self, original: &InboundPorts) -> Option<Value> {
+        let mut body = Map::new();
+        for field in InboundField::ALL {
+            match field {
+                InboundField::AllowLan if self.allow_lan != original.allow_lan => {
+                    body.insert(field.key().to_string(), Value::Bool(self.allow_lan));
+                }
+                _ => {
+                    if let Some(port) = self
+                        .port(field)
+                        .filter(|p| Some(*p) != original.port(field))
+                    {
+                        body.insert(field.key().to_string(), Value::from(port));
+                    }
+                }
+            }
+        }
+        (!body.is_empty()).then_some(Value::Object(body))
+    }
+}
+
+/// Whether something accepts TCP connections on `host:port`
+pub async fn probe(host: &str, port: u16) -> bool {
+    matches!(
+        tokio::time::timeout(PROBE_TIMEOUT, tokio::net::TcpStream::connect((host, port))).await,
+        Ok(Ok(_))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_problems_and_patch() {
+        let original = InboundPorts {
+            mixed_port: 7890,
+            socks_port: 7891,
+            ..Default::default()
+        };
+        assert!(original.problems(&original).is_empty());
+        assert_eq!(original.patch(&original), None);
+
+        let mut edited = original;
+        edited.set_port(InboundField::Port, 7891);
+        edited.set_port(InboundField::RedirPort, 80);
+        edited.allow_lan = true;
+        let problems = edited.problems(&original);
+        assert_eq!(
+            problems,
+            vec![
+                PortProblem::Conflict {
+                    port: 7891,
+                    first: InboundField::Port,
+                    second: InboundField::SocksPort,
+                },
+                PortProblem::Privileged {
+                    field: InboundField::RedirPort,
+                    port: 80,
+                },
+            ]
+        );
+        assert!(problems[0].is_blocking() && !problems[1].is_blocking());
+
+        assert_eq!(
+            edited.patch(&original),
+            Some(serde_json::json!({"port": 7891, "redir-port": 80, "allow-lan": true}))
+        );
+    }
+}
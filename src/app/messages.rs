@@ -0,0 +1,124 @@
+use chrono::{DateTime, Local};
+use std::collections::VecDeque;
+
+/// Status messages kept for review; older ones are dropped
+const MAX_MESSAGES: usize = 200;
+
+/// Words that make a status message a warning rather than plain information
+const WARNING_WORDS: &[&str] = &["fail", "error", "unreachable", "invalid", "cannot", "not "];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    /// Guess the severity of a status message set without one
+    pub fn infer(text: &str) -> Self {
+        let lower = text.to_lowercase();
+        if WARNING_WORDS.iter().any(|word| lower.contains(word)) {
+            Severity::Warning
+        } else {
+            Severity::Info
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warning => "warn",
+            Severity::Error => "error",
+        }
+    }
+}
+
+/// A status message shown during the session
+#[derive(Debug, Clone)]
+pub struct MessageEntry {
+    pub at: DateTime<Local>,
+    pub severity: Severity,
+    pub text: String,
+}
+
+/// Session-wide history of status messages, newest last
+#[derive(Debug, Default)]
+pub struct MessageLog {
+    entries: VecDeque<MessageEntry>,
+    /// Warnings and errors recorded since the history was last opened
+    unread: usize,
+}
+
+impl MessageLog {
+    pub fn record(&mut self, severity: Severity, text: &str) {
+        if self.entries.len() == MAX_MESSAGES {
+            self.entries.pop_front();
+        }
+        if severity != Severity::Info {
+            self.unread += 1;
+        }
+        self.entries.push_back(MessageEntry {
+            at: Local::now(),
+            severity,
+            text: text.to_string(),
+        });
+    }
+
+    /// Record the status bar's message if it isn't the latest one already
+    pub fn observe(&mut self, status: Option<&str>) {
+        let Some(text) = status.filter(|text| !text.is_empty()) else {
+            return;
+        };
+        if self.entries.back().is_some_and(|last| last.text == text) {
+            return;
+        }
+        self.record(Severity::infer(text), text);
+    }
+
+    /// Newest first
+    pub fn entries(&self) -> impl Iterator<Item = &MessageEntry> {
+        self.entries.iter().rev()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn unread(&self) -> usize {
+        self.unread
+    }
+
+    pub fn mark_read(&mut self) {
+        self.unread = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn observes_each_new_status_once() {
+        let mut log = MessageLog::default();
+        log.observe(Some("Loaded 12 rules"));
+        log.observe(Some("Loaded 12 rules"));
+        log.observe(None);
+        log.observe(Some("3 providers failed"));
+        log.record(Severity::Error, "Failed to fetch rules: refused");
+        log.observe(Some("Failed to fetch rules: refused"));
+
+        let severities: Vec<Severity> = log.entries().map(|entry| entry.severity).collect();
+        assert_eq!(
+            severities,
+            vec![Severity::Error, Severity::Warning, Severity::Info]
+        );
+        assert_eq!(log.unread(), 2);
+        log.mark_read();
+        assert_eq!(log.unread(), 0);
+    }
+}
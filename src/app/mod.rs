@@ -1,5 +1,24 @@
+pub mod auto_select;
+pub mod connection_breakdown;
+pub mod connection_export;
+pub mod delay_log;
+pub mod delay_test;
+pub mod errors;
+pub mod fetch_failure;
+pub mod fetcher;
+pub mod gui_guard;
+pub mod hooks;
+pub mod inbound;
+pub mod messages;
 pub mod mode;
+pub mod node_info;
+pub mod notify;
+pub mod preflight;
+pub mod probe;
+pub mod region;
 pub mod state;
+pub mod tasks;
 
+pub use fetcher::{ApiRequest, ApiResponse};
 pub use mode::Mode;
 pub use state::{AppState, Page};
@@ -1,6 +1,10 @@
+use serde::{Deserialize, Serialize};
+
 /// App mode
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Mode {
+    #[default]
     Simple,
     Expert,
 }
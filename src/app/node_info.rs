@@ -0,0 +1,313 @@
+use chrono::DateTime;
+use serde_yaml::Value;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::clash::Proxy;
+use crate::config::dedup::ProxySource;
+use crate::config::delays::{CachedDelay, DelaySample};
+
+/// A subscription defining a node, and what its entry says about it
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeOrigin {
+    pub source: String,
+    pub server: Option<String>,
+    pub port: Option<u16>,
+    pub udp: Option<bool>,
+}
+
+impl NodeOrigin {
+    /// `server:port`, when the entry names a server
+    pub fn address(&self) -> Option<String> {
+        let server = self.server.as_ref()?;
+        Some(match self.port {
+            Some(port) => format!("{}:{}", server, port),
+            None => server.clone(),
+        })
+    }
+}
+
+/// Where each node name is defined, across every subscription
+#[derive(Debug, Clone, Default)]
+pub struct NodeOrigins(HashMap<String, Vec<NodeOrigin>>);
+
+impl NodeOrigins {
+    pub fn index(sources: &[ProxySource]) -> Self {
+        let mut origins: HashMap<String, Vec<NodeOrigin>> = HashMap::new();
+        for source in sources {
+            for proxy in &source.proxies {
+                let Some(name) = proxy.get("name").and_then(Value::as_str) else {
+                    continue;
+                };
+                origins
+                    .entry(name.to_string())
+                    .or_default()
+                    .push(NodeOrigin {
+                        source: source.name.clone(),
+                        server: proxy
+                            .get("server")
+                            .and_then(Value::as_str)
+                            .map(str::to_string),
+                        port: proxy
+                            .get("port")
+                            .and_then(Value::as_u64)
+                            .and_then(|port| u16::try_from(port).ok()),
+                        udp: proxy.get("udp").and_then(Value::as_bool),
+                    });
+            }
+        }
+        Self(origins)
+    }
+
+    /// Subscriptions defining `node`, in the order they were read
+    pub fn of(&self, node: &str) -> &[NodeOrigin] {
+        self.0.get(node).map(Vec::as_slice).unwrap_or_default()
+    }
+}
+
+/// Whether a node relays UDP: as the core reports it, else as its subscription entry says
+pub fn udp_support(proxy: Option<&Proxy>, origins: &[NodeOrigin]) -> Option<bool> {
+    proxy
+        .and_then(|proxy| proxy.udp)
+        .or_else(|| origins.iter().find_map(|origin| origin.udp))
+}
+
+/// Latest delay test of a node as (unix time, delay or None for a timeout),
+/// from the core's history or the cache kept across restarts, whichever is newer
+pub fn last_tested(
+    proxy: Option<&Proxy>,
+    cached: Option<&CachedDelay>,
+) -> Option<(i64, Option<u32>)> {
+    let from_core = proxy
+        .and_then(|proxy| proxy.history.as_ref()?.last())
+        .and_then(|entry| {
+            let time = DateTime::parse_from_rfc3339(&entry.time).ok()?;
+            Some((time.timestamp(), (entry.delay > 0).then_some(entry.delay)))
+        });
+    let from_cache = cached.map(|cached| (cached.tested_at, Some(cached.delay)));
+    match (from_core, from_cache) {
+        (Some(core), Some(cache)) if cache.0 > core.0 => Some(cache),
+        (core, cache) => core.or(cache),
+    }
+}
+
+/// Nested groups followed when resolving the node a group has selected
+const MAX_GROUP_DEPTH: usize = 8;
+
+/// Latest delays behind a group, for the Routes list
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct GroupDelays {
+    /// Test of the node its selection ends at as (unix time, delay or None for a timeout)
+    pub current: Option<(i64, Option<u32>)>,
+    /// Fastest successful test among its nodes as (unix time, delay)
+    pub best: Option<(i64, u32)>,
+}
+
+/// The node `name` ends at when it's a group, following the selection of nested groups
+pub fn resolve_selected<'a>(proxies: &'a HashMap<String, Proxy>, name: &'a str) -> &'a str {
+    let mut name = name;
+    for _ in 0..MAX_GROUP_DEPTH {
+        match proxies
+            .get(name)
+            .filter(|proxy| proxy.all.is_some())
+            .and_then(|proxy| proxy.now.as_deref())
+        {
+            Some(now) => name = now,
+            None => break,
+        }
+    }
+    name
+}
+
+/// Delays of the group selecting `current` out of `nodes`, from the core's history
+/// or the delay cache; DIRECT, REJECT and the like are left out
+pub fn group_delays(
+    proxies: &HashMap<String, Proxy>,
+    cache: &HashMap<String, CachedDelay>,
+    current: Option<&str>,
+    nodes: &[String],
+) -> GroupDelays {
+    let tested = |name: &str| {
+        let node = resolve_selected(proxies, name);
+        if !super::auto_select::is_testable(node, proxies) {
+            return None;
+        }
+        last_tested(proxies.get(node), cache.get(node))
+    };
+    GroupDelays {
+        current: current.and_then(&tested),
+        best: nodes
+            .iter()
+            .filter_map(|node| {
+                let (time, delay) = tested(node)?;
+                Some((time, delay?))
+            })
+            .min_by_key(|(_, delay)| *delay),
+    }
+}
+
+/// Delay tests of `node` since `since` as (unix time, delay or None for a timeout),
+/// oldest first: the delay history file plus the core's entries not saved to it yet
+pub fn delay_series(
+    recorded: &[DelaySample],
+    proxy: Option<&Proxy>,
+    node: &str,
+    since: i64,
+) -> Vec<(i64, Option<u32>)> {
+    let mut tests: BTreeMap<i64, u32> = recorded
+        .iter()
+        .filter(|sample| sample.node == node)
+        .map(|sample| (sample.time, sample.delay))
+        .collect();
+    for entry in proxy
+        .and_then(|proxy| proxy.history.as_ref())
+        .into_iter()
+        .flatten()
+    {
+        if let Ok(time) = DateTime::parse_from_rfc3339(&entry.time) {
+            tests.insert(time.timestamp(), entry.delay);
+        }
+    }
+    tests
+        .range(since..)
+        .map(|(time, delay)| (*time, (*delay > 0).then_some(*delay)))
+        .collect()
+}
+
+/// `seconds` ago as "just now", "5m ago", "3h ago" or "2d ago"
+pub fn ago(seconds: i64) -> String {
+    match seconds.max(0) {
+        0..=59 => "just now".to_string(),
+        s @ 60..=3_599 => format!("{}m ago", s / 60),
+        s @ 3_600..=86_399 => format!("{}h ago", s / 3_600),
+        s => format!("{}d ago", s / 86_400),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clash::{DelayHistory, ProxyType};
+
+    #[test]
+    fn test_node_origins_and_last_test() {
+        let proxies = |yaml: &str| serde_yaml::from_str(yaml).unwrap();
+        let sources = [
+            ProxySource {
+                name: "Airport".to_string(),
+                provider: None,
+                proxies: vec![proxies(
+                    "{name: HK, type: ss, server: hk.example.com, port: 443, udp: true}",
+                )],
+            },
+            ProxySource {
+                name: "Backup".to_string(),
+                provider: Some("backup".to_string()),
+                proxies: vec![proxies("{name: HK, type: trojan, server: 1.2.3.4}")],
+            },
+        ];
+        let origins = NodeOrigins::index(&sources);
+        let hk = origins.of("HK");
+        assert_eq!(hk.len(), 2);
+        assert_eq!(hk[0].address().as_deref(), Some("hk.example.com:443"));
+        assert_eq!(hk[0].udp, Some(true));
+        assert_eq!(udp_support(None, hk), Some(true));
+        assert_eq!(
+            udp_support(
+                Some(&Proxy {
+                    udp: Some(false),
+                    ..Proxy::default()
+                }),
+                hk
+            ),
+            Some(false)
+        );
+        assert_eq!(hk[1].address().as_deref(), Some("1.2.3.4"));
+        assert!(origins.of("JP").is_empty());
+
+        let proxy = Proxy {
+            history: Some(vec![DelayHistory {
+                time: "2024-05-01T10:00:00Z".to_string(),
+                delay: 0,
+                mean_delay: None,
+            }]),
+            ..Proxy::default()
+        };
+        let core_time = 1_714_557_600;
+        let older = CachedDelay {
+            delay: 90,
+            tested_at: core_time - 60,
+        };
+        assert_eq!(
+            last_tested(Some(&proxy), Some(&older)),
+            Some((core_time, None))
+        );
+        let newer = CachedDelay {
+            tested_at: core_time + 60,
+            ..older
+        };
+        assert_eq!(
+            last_tested(Some(&proxy), Some(&newer)),
+            Some((core_time + 60, Some(90)))
+        );
+        assert_eq!(ago(7_200), "2h ago");
+
+        let sample = |time: i64, node: &str, delay: u32| DelaySample {
+            time,
+            node: node.to_string(),
+            delay,
+        };
+        let recorded = [
+            sample(core_time - 7_200, "HK", 80),
+            sample(core_time - 60, "HK", 120),
+            sample(core_time - 60, "JP", 50),
+            sample(core_time, "HK", 0),
+        ];
+        assert_eq!(
+            delay_series(&recorded, Some(&proxy), "HK", core_time - 3_600),
+            vec![(core_time - 60, Some(120)), (core_time, None)]
+        );
+        assert_eq!(
+            delay_series(&recorded[..1], Some(&proxy), "HK", 0),
+            vec![(core_time - 7_200, Some(80)), (core_time, None)]
+        );
+    }
+
+    #[test]
+    fn test_group_delays_follow_nested_groups() {
+        let node = |name: &str, proxy_type: ProxyType| Proxy {
+            name: name.to_string(),
+            proxy_type,
+            ..Proxy::default()
+        };
+        let group = |name: &str, now: &str, all: &[&str]| Proxy {
+            now: Some(now.to_string()),
+            all: Some(all.iter().map(|name| name.to_string()).collect()),
+            ..node(name, ProxyType::Selector)
+        };
+        let proxies: HashMap<String, Proxy> = [
+            group("Proxy", "Auto", &["Auto", "JP", "DIRECT"]),
+            group("Auto", "HK", &["HK", "JP"]),
+            node("HK", ProxyType::Trojan),
+            node("JP", ProxyType::Vmess),
+            node("DIRECT", ProxyType::Direct),
+        ]
+        .into_iter()
+        .map(|proxy| (proxy.name.clone(), proxy))
+        .collect();
+        let cache: HashMap<String, CachedDelay> = [("HK", 300), ("JP", 80), ("DIRECT", 1)]
+            .into_iter()
+            .map(|(name, delay)| {
+                let tested_at = 100;
+                (name.to_string(), CachedDelay { delay, tested_at })
+            })
+            .collect();
+        let nodes = ["Auto", "JP", "DIRECT"].map(String::from);
+
+        assert_eq!(resolve_selected(&proxies, "Proxy"), "HK");
+        let delays = group_delays(&proxies, &cache, Some("Auto"), &nodes);
+        assert_eq!(delays.current, Some((100, Some(300))));
+        assert_eq!(delays.best, Some((100, 80)));
+        let direct = group_delays(&proxies, &cache, Some("DIRECT"), &nodes[2..]);
+        assert_eq!(direct, GroupDelays::default());
+    }
+}
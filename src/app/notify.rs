@@ -0,0 +1,184 @@
+use std::collections::{HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+use crate::clash::ProvidersResponse;
+use crate::config::{AlertConfig, NotificationConfig};
+
+/// How long a toast stays on screen once shown
+const TOAST_DURATION: Duration = Duration::from_secs(6);
+
+/// Critical events that raise a notification
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NotifyEvent {
+    NodeDown,
+    SubscriptionExpiring,
+    CoreUnreachable,
+    /// A download rate or connection count alert threshold was crossed
+    ThresholdExceeded,
+}
+
+impl NotifyEvent {
+    pub fn label(&self) -> &'static str {
+        match self {
+            NotifyEvent::NodeDown => "Node Down",
+            NotifyEvent::SubscriptionExpiring => "Subscription Expiring",
+            NotifyEvent::CoreUnreachable => "Core Unreachable",
+            NotifyEvent::ThresholdExceeded => "Threshold Exceeded",
+        }
+    }
+}
+
+/// A notification waiting in, or at the front of, the toast queue
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub event: NotifyEvent,
+    pub message: String,
+    shown_at: Option<Instant>,
+}
+
+/// Toast queue for critical events
+/// Each condition is raised once and again only after it has cleared
+#[derive(Debug, Default)]
+pub struct Notifier {
+    settings: NotificationConfig,
+    queue: VecDeque<Toast>,
+    active: HashSet<String>,
+}
+
+impl Notifier {
+    /// Apply the per-event toggles, dropping queued toasts of disabled events
+    pub fn set_settings(&mut self, settings: NotificationConfig) {
+        self.queue.retain(|toast| settings.is_enabled(toast.event));
+        self.settings = settings;
+    }
+
+    fn raise(&mut self, event: NotifyEvent, key: String, message: String) {
+        if self.settings.is_enabled(event) && self.active.insert(key) {
+            self.queue.push_back(Toast {
+                event,
+                message,
+                shown_at: None,
+            });
+        }
+    }
+
+    /// Record whether the core answered the last refresh
+    pub fn core_status(&mut self, error: Option<&str>) {
+        let key = "core".to_string();
+        match error {
+            Some(e) => self.raise(
+                NotifyEvent::CoreUnreachable,
+                key,
+                format!("Clash core is unreachable: {}", e),
+            ),
+            None => {
+                self.active.remove(&key);
+            }
+        }
+    }
+
+    /// Record a health check of the current node (None or 0 means it failed)
+    pub fn node_health(&mut self, node: &str, delay: Option<u32>) {
+        let key = format!("node:{}", node);
+        match delay {
+            Some(delay) if delay > 0 => {
+                self.active.remove(&key);
+            }
+            _ => self.raise(
+                NotifyEvent::NodeDown,
+                key,
+                format!("Current node {} failed its health check", node),
+            ),
+        }
+    }
+
+    /// Check provider subscriptions for an expiry within the configured window
+    pub fn subscriptions(&mut self, providers: &ProvidersResponse, now: i64) {
+        let window = self.settings.expiry_days as i64 * 86_400;
+        let mut names: Vec<&String> = providers.providers.keys().collect();
+        names.sort();
+        for name in names {
+            let Some(info) = &providers.providers[name].subscription_info else {
+                continue;
+            };
+            if info.expire == 0 {
+                continue;
+            }
+            let left = info.expire as i64 - now;
+            let key = format!("subscription:{}", name);
+            if left > window {
+                self.active.remove(&key);
+                continue;
+            }
+            let message = if left <= 0 {
+                format!("Subscription {} has expired", name)
+            } else {
+                format!(
+                    "Subscription {} expires in {} day(s)",
+                    name,
+                    (left + 86_399) / 86_400
+                )
+            };
+            self.raise(NotifyEvent::SubscriptionExpiring, key, message);
+        }
+    }
+
+    /// Check the latest traffic sample against the alert thresholds
+    pub fn thresholds(&mut self, alerts: &AlertConfig, download_rate: u64, connections: usize) {
+        let key = "alert:download".to_string();
+        match alerts.download_rate_mb {
+            Some(limit) if alerts.download_exceeded(download_rate) => self.raise(
+                NotifyEvent::ThresholdExceeded,
+                key,
+                format!(
+                    "Download rate {:.1} MB/s is over {} MB/s",
+                    download_rate as f64 / (1024.0 * 1024.0),
+                    limit
+                ),
+            ),
+            _ => {
+                self.active.remove(&key);
+            }
+        }
+
+        let key = "alert:connections".to_string();
+        match alerts.connections {
+            Some(limit) if alerts.connections_exceeded(connections) => self.raise(
+                NotifyEvent::ThresholdExceeded,
+                key,
+                format!("{} active connections, over {}", connections, limit),
+            ),
+            _ => {
+                self.active.remove(&key);
+            }
+        }
+    }
+
+    /// Drop the front toast once its time is up and start the next one's clock
+    pub fn tick(&mut self) {
+        while let Some(toast) = self.queue.front_mut() {
+            match toast.shown_at {
+                None => {
+                    toast.shown_at = Some(Instant::now());
+                    return;
+                }
+                Some(at) if at.elapsed() >= TOAST_DURATION => {
+                    self.queue.pop_front();
+                }
+                Some(_) => return,
+            }
+        }
+    }
+
+    /// Toast to show now, with how many more are waiting behind it
+    pub fn current(&self) -> Option<(&Toast, usize)> {
+        self.queue
+            .front()
+            .map(|toast| (toast, self.queue.len() - 1))
+    }
+
+    /// Whether subscription expiry should be polled at all
+    pub fn wants_subscriptions(&self) -> bool {
+        self.settings.subscription_expiring
+    }
+}
@@ -0,0 +1,126 @@
+use futures_util::future::join_all;
+use serde_yaml::Value;
+use std::time::{Duration, Instant};
+
+/// Nodes of a candidate profile tested before switching to it
+pub const PREFLIGHT_SAMPLE: usize = 8;
+
+/// Time a node's server gets to accept the connection
+const PREFLIGHT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// A proxy's name and the server it connects to
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeServer {
+    pub name: String,
+    pub server: String,
+    pub port: u16,
+}
+
+/// How many of the sampled nodes' servers accepted a TCP connection
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Preflight {
+    pub tested: usize,
+    pub reachable: usize,
+    /// Nodes in the profile
+    pub total: usize,
+    /// Median connect time of the reachable ones, in ms
+    pub median_ms: Option<u64>,
+}
+
+impl Preflight {
+    /// Most sampled nodes are unreachable
+    pub fn is_dead(&self) -> bool {
+        self.tested > 0 && self.reachable * 2 < self.tested
+    }
+
+    pub fn describe(&self) -> String {
+        let mut text = format!(
+            "{} of {} sampled nodes reachable ({} in profile)",
+            self.reachable, self.tested, self.total
+        );
+        if let Some(ms) = self.median_ms {
+            text.push_str(&format!(", median {}ms", ms));
+        }
+        text
+    }
+}
+
+/// Up to `count` nodes of a config, spread evenly over its `proxies`
+pub fn sample_nodes(bytes: &[u8], count: usize) -> (Vec<NodeServer>, usize) {
+    let value: Value = serde_yaml::from_slice(bytes).unwrap_or(Value::Null);
+    let nodes: Vec<NodeServer> = value
+        .get("proxies")
+        .and_then(Value::as_sequence)
+        .into_iter()
+        .flatten()
+        .filter_map(|proxy| {
+            Some(NodeServer {
+                name: proxy.get("name")?.as_str()?.to_string(),
+                server: proxy.get("server")?.as_str()?.to_string(),
+                port: u16::try_from(proxy.get("port")?.as_u64()?).ok()?,
+            })
+        })
+        .collect();
+    let total = nodes.len();
+    if total <= count {
+        return (nodes, total);
+    }
+    let sample = (0..count)
+        .map(|i| nodes[i * total / count].clone())
+        .collect();
+    (sample, total)
+}
+
+/// TCP-connect to every node's server at once; this shows the server is up,
+/// not that the proxy protocol or credentials work
+pub async fn run(nodes: Vec<NodeServer>, total: usize) -> Preflight {
+    let results = join_all(nodes.iter().map(|node| async move {
+        let started = Instant::now();
+        let connect = tokio::net::TcpStream::connect((node.server.as_str(), node.port));
+        match tokio::time::timeout(PREFLIGHT_TIMEOUT, connect).await {
+            Ok(Ok(_)) => Some(started.elapsed().as_millis() as u64),
+            _ => None,
+        }
+    }))
+    .await;
+    let mut times: Vec<u64> = results.into_iter().flatten().collect();
+    times.sort_unstable();
+    Preflight {
+        tested: nodes.len(),
+        reachable: times.len(),
+        total,
+        median_ms: times.get(times.len() / 2).copied(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_nodes_spreads_over_proxies() {
+        let config = "proxies:\n\
+            - {name: a, type: ss, server: 1.1.1.1, port: 1}\n\
+            - {name: b, type: ss, server: 1.1.1.2, port: 2}\n\
+            - {name: c, type: ss, server: 1.1.1.3, port: 3}\n\
+            - {name: d, type: ss, server: 1.1.1.4, port: 4}\n\
+            - {name: broken, type: ss}\n";
+        let (sample, total) = sample_nodes(config.as_bytes(), 2);
+        assert_eq!(total, 4);
+        let names: Vec<&str> = sample.iter().map(|node| node.name.as_str()).collect();
+        assert_eq!(names, ["a", "c"]);
+        assert_eq!(sample_nodes(b"mode: rule", 2), (Vec::new(), 0));
+
+        let preflight = Preflight {
+            tested: 8,
+            reachable: 3,
+            total: 40,
+            median_ms: Some(120),
+        };
+        assert!(preflight.is_dead());
+        assert_eq!(
+            preflight.describe(),
+            "3 of 8 sampled nodes reachable (40 in profile), median 120ms"
+        );
+    }
+}
@@ -0,0 +1,84 @@
+use std::time::{Duration, Instant};
+
+use crate::clash::{ClashClient, ConfigResponse};
+use crate::core::doctor::api_host;
+
+/// How often the proxy port is probed in the background
+pub const PROBE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Time allowed for the canary request
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Whether a request through the core's proxy port reached the canary URL
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProbeStatus {
+    /// Answered in this many milliseconds
+    Reachable(u64),
+    /// Why it didn't: no proxy port, the port refused, or the request failed
+    Unreachable(String),
+}
+
+/// The core's mixed port (else its HTTP port) as a proxy URL on the API's host
+pub fn proxy_url(api_url: &str, config: &ConfigResponse) -> Option<String> {
+    let port = match (config.mixed_port, config.port) {
+        (0, 0) => return None,
+        (0, port) => port,
+        (port, _) => port,
+    };
+    Some(format!("http://{}:{}", api_host(api_url), port))
+}
+
+/// Fetch `url` through the proxy port the core reports
+pub async fn probe(client: &ClashClient, url: &str) -> ProbeStatus {
+    let config = match client.get_config().await {
+        Ok(config) => config,
+        Err(e) => return ProbeStatus::Unreachable(format!("no core config: {}", e)),
+    };
+    let Some(proxy) = proxy_url(client.base_url(), &config) else {
+        return ProbeStatus::Unreachable("no mixed-port or port configured".to_string());
+    };
+
+    let started = Instant::now();
+    let http = match reqwest::Proxy::all(&proxy).and_then(|proxy| {
+        reqwest::Client::builder()
+            .proxy(proxy)
+            .timeout(PROBE_TIMEOUT)
+            .build()
+    }) {
+        Ok(http) => http,
+        Err(e) => return ProbeStatus::Unreachable(e.to_string()),
+    };
+    match http.get(url).send().await {
+        Ok(response) if response.status().is_success() => {
+            ProbeStatus::Reachable(started.elapsed().as_millis() as u64)
+        }
+        Ok(response) => ProbeStatus::Unreachable(format!("HTTP {}", response.status())),
+        Err(e) if e.is_timeout() => ProbeStatus::Unreachable("timed out".to_string()),
+        Err(e) if e.is_connect() => {
+            ProbeStatus::Unreachable(format!("{} refused the connection", proxy))
+        }
+        Err(e) => ProbeStatus::Unreachable(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proxy_url_prefers_mixed_port() {
+        let mut config: ConfigResponse = serde_json::from_str(r#"{"port": 7891}"#).unwrap();
+        assert_eq!(
+            proxy_url("http://10.0.0.2:9090", &config).as_deref(),
+            Some("http://10.0.0.2:7891")
+        );
+        config.mixed_port = 7890;
+        assert_eq!(
+            proxy_url("http://127.0.0.1:9090", &config).as_deref(),
+            Some("http://127.0.0.1:7890")
+        );
+        config.mixed_port = 0;
+        config.port = 0;
+        assert_eq!(proxy_url("http://127.0.0.1:9090", &config), None);
+    }
+}
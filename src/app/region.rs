@@ -0,0 +1,200 @@
+use std::collections::HashSet;
+
+/// Section for nodes whose name names no known region
+pub const OTHER_REGION: &str = "Other";
+
+/// Country/region codes and the names airports write them as
+const REGIONS: &[(&str, &[&str])] = &[
+    ("HK", &["hong kong", "hongkong", "香港"]),
+    ("TW", &["taiwan", "台湾", "台灣"]),
+    ("JP", &["japan", "tokyo", "osaka", "日本", "东京", "大阪"]),
+    ("SG", &["singapore", "新加坡", "狮城"]),
+    ("KR", &["korea", "seoul", "韩国", "首尔"]),
+    (
+        "US",
+        &[
+            "united states",
+            "america",
+            "los angeles",
+            "san jose",
+            "seattle",
+            "美国",
+            "洛杉矶",
+        ],
+    ),
+    ("CA", &["canada", "加拿大"]),
+    ("GB", &["united kingdom", "london", "英国", "伦敦"]),
+    ("DE", &["germany", "frankfurt", "德国"]),
+    ("FR", &["france", "paris", "法国"]),
+    ("NL", &["netherlands", "amsterdam", "荷兰"]),
+    ("RU", &["russia", "moscow", "俄罗斯"]),
+    ("TR", &["turkey", "türkiye", "土耳其"]),
+    ("IN", &["india", "mumbai", "印度"]),
+    ("AU", &["australia", "sydney", "澳大利亚", "澳洲"]),
+    ("MY", &["malaysia", "马来西亚"]),
+    ("TH", &["thailand", "泰国"]),
+    ("VN", &["vietnam", "越南"]),
+    ("PH", &["philippines", "菲律宾"]),
+    ("AR", &["argentina", "阿根廷"]),
+    ("BR", &["brazil", "巴西"]),
+    ("MO", &["macau", "macao", "澳门"]),
+];
+
+/// Codes written differently in node names
+const CODE_ALIASES: &[(&str, &str)] = &[("UK", "GB"), ("USA", "US")];
+
+/// Region code of a node, from a flag emoji, a code like `JP`/`HK01` or a place name
+pub fn region_of(node: &str) -> Option<&'static str> {
+    flag_region(node)
+        .or_else(|| code_region(node))
+        .or_else(|| name_region(node))
+}
+
+/// The country of the first flag emoji (a pair of regional indicator symbols)
+fn flag_region(node: &str) -> Option<&'static str> {
+    let letters: Vec<char> = node
+        .chars()
+        .skip_while(|c| !is_regional_indicator(*c))
+        .take(2)
+        .filter(|c| is_regional_indicator(*c))
+        .map(|c| (b'A' + (c as u32 - 0x1F1E6) as u8) as char)
+        .collect();
+    if letters.len() != 2 {
+        return None;
+    }
+    let code: String = letters.into_iter().collect();
+    // Flags of regions not listed are left to the name
+    known_code(&code)
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&c)
+}
+
+/// An upper-case code standing alone or before digits: `JP`, `HK01`, `US-2`
+fn code_region(node: &str) -> Option<&'static str> {
+    node.split(|c: char| !c.is_ascii_alphabetic())
+        .filter(|word| word.chars().all(|c| c.is_ascii_uppercase()))
+        .find_map(known_code)
+}
+
+fn name_region(node: &str) -> Option<&'static str> {
+    let lower = node.to_lowercase();
+    REGIONS
+        .iter()
+        .find(|(_, names)| names.iter().any(|name| lower.contains(name)))
+        .map(|(code, _)| *code)
+}
+
+fn known_code(code: &str) -> Option<&'static str> {
+    let code = CODE_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == code)
+        .map_or(code, |(_, code)| code);
+    REGIONS
+        .iter()
+        .map(|(known, _)| *known)
+        .find(|known| *known == code)
+}
+
+/// Flag emoji for a region code
+pub fn flag(code: &str) -> String {
+    if code.len() != 2 || !code.chars().all(|c| c.is_ascii_uppercase()) {
+        return String::new();
+    }
+    code.chars()
+        .filter_map(|c| char::from_u32(0x1F1E6 + (c as u32 - 'A' as u32)))
+        .collect()
+}
+
+/// Nodes sharing a region, in the order the region first appears (`Other` last)
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegionGroup {
+    pub region: &'static str,
+    /// Indices into the node list
+    pub nodes: Vec<usize>,
+}
+
+pub fn group_by_region(nodes: &[String]) -> Vec<RegionGroup> {
+    let mut groups: Vec<RegionGroup> = Vec::new();
+    for (index, node) in nodes.iter().enumerate() {
+        let region = region_of(node).unwrap_or(OTHER_REGION);
+        match groups.iter_mut().find(|group| group.region == region) {
+            Some(group) => group.nodes.push(index),
+            None => groups.push(RegionGroup {
+                region,
+                nodes: vec![index],
+            }),
+        }
+    }
+    groups.sort_by_key(|group| group.region == OTHER_REGION);
+    groups
+}
+
+/// One line of the grouped node list
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RegionRow {
+    /// Section header of the group at this index
+    Header(usize),
+    /// Node at this index of the node list
+    Node(usize),
+}
+
+/// Headers, each followed by its nodes unless the region is collapsed
+pub fn region_rows(groups: &[RegionGroup], collapsed: &HashSet<String>) -> Vec<RegionRow> {
+    let mut rows = Vec::new();
+    for (index, group) in groups.iter().enumerate() {
+        rows.push(RegionRow::Header(index));
+        if !collapsed.contains(group.region) {
+            rows.extend(group.nodes.iter().map(|node| RegionRow::Node(*node)));
+        }
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_region_detection_and_grouping() {
+        assert_eq!(region_of("🇯🇵 Tokyo 01"), Some("JP"));
+        assert_eq!(region_of("HK01 | IPLC"), Some("HK"));
+        assert_eq!(region_of("UK-London"), Some("GB"));
+        assert_eq!(region_of("香港 02"), Some("HK"));
+        assert_eq!(region_of("Singapore Premium"), Some("SG"));
+        // Lower-case words are not codes
+        assert_eq!(region_of("plus relay"), None);
+        assert_eq!(flag("JP"), "🇯🇵");
+
+        let nodes: Vec<String> = ["JP-1", "Relay", "HK-1", "🇯🇵 JP-2"]
+            .iter()
+            .map(|n| n.to_string())
+            .collect();
+        let groups = group_by_region(&nodes);
+        let summary: Vec<(&str, &[usize])> = groups
+            .iter()
+            .map(|g| (g.region, g.nodes.as_slice()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("JP", &[0, 3][..]),
+                ("HK", &[2][..]),
+                (OTHER_REGION, &[1][..])
+            ]
+        );
+
+        let collapsed = HashSet::from(["JP".to_string()]);
+        assert_eq!(
+            region_rows(&groups, &collapsed),
+            [
+                RegionRow::Header(0),
+                RegionRow::Header(1),
+                RegionRow::Node(2),
+                RegionRow::Header(2),
+                RegionRow::Node(1),
+            ]
+        );
+    }
+}
@@ -1,9 +1,25 @@
 use std::time::Instant;
 
+use crate::app::auto_select::{self, AutoSelect};
+use crate::app::delay_log::DelayLog;
+use crate::app::delay_test::DelayTest;
+use crate::app::errors::ErrorLog;
+use crate::app::fetcher::{self, ApiRequest, ApiResponse};
+use crate::app::gui_guard::GuiGuard;
+use crate::app::hooks::{HookEvent, Hooks};
+use crate::app::messages::{MessageLog, Severity};
+use crate::app::node_info;
+use crate::app::notify::Notifier;
+use crate::app::probe::ProbeStatus;
+use crate::app::tasks::TaskRegistry;
 use crate::app::Mode;
-use crate::clash::{ClashClient, ClashMode, Proxy, ProxyType};
-use crate::config::Preset;
+use crate::clash::{ClashClient, ClashMode, HumanRoute, Proxy, ProxyType};
+use crate::config::bypass::{self, Bypass};
+use crate::config::delays::CachedDelay;
+use crate::config::preset::PresetRestore;
+use crate::config::{AppConfig, Preset, DEFAULT_DELAY_TTL_MINUTES};
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use tokio::sync::mpsc;
 
@@ -14,14 +30,6 @@ pub struct DelayTestResult {
     pub delay: Option<u32>, // None if test failed
 }
 
-/// Delay test result
-#[derive(Debug, Clone)]
-pub struct DelayResult {
-    pub delay: u32,
-    #[allow(dead_code)]
-    pub tested_at: Instant,
-}
-
 /// Global application state
 #[derive(Debug)]
 pub struct AppState {
@@ -29,16 +37,53 @@ pub struct AppState {
     pub current_page: Page,
     pub mode: Mode,
     pub preset: Preset,
+    /// Core state the current preset overrode, restored when switching away
+    preset_restore: PresetRestore,
     pub status_message: Option<String>,
-    pub delay_cache: HashMap<String, DelayResult>,
+    /// Errors reported this session, for the error console
+    pub errors: ErrorLog,
+    /// Every status message of the session, with its time and severity
+    pub messages: MessageLog,
+    /// Toasts for critical events (node down, expiring subscription, core unreachable)
+    pub notifier: Notifier,
+    /// User commands run on events
+    pub hooks: Hooks,
+    /// Latest successful delay per node, saved on exit and reloaded at startup
+    pub delay_cache: HashMap<String, CachedDelay>,
+    /// Minutes after which a cached delay is shown as stale
+    pub delay_ttl_minutes: u64,
+    /// URLs delay tests go to
+    pub delay_test: DelayTest,
+    /// Last request through the proxy port, None until the first probe finishes
+    pub proxy_probe: Option<ProbeStatus>,
+    /// Every delay test of the session, for export
+    pub delay_log: DelayLog,
+    /// Background work (streams, delay tests, updates), drained on quit
+    pub tasks: TaskRegistry,
+    /// Temporary DIRECT rules, removed from the Clash config when they expire
+    pub bypasses: Vec<Bypass>,
+    /// Whether a GUI owning the profiles runs, and the write it last warned about
+    pub gui: GuiGuard,
     pub testing_nodes: Vec<String>,
+    /// Switches waiting on a delay test, as (selector, node)
+    pending_switches: Vec<(String, String)>,
+    /// Groups switching to their fastest node once every node is tested
+    pending_auto_selects: Vec<AutoSelect>,
+    /// Groups switched ahead of the core, with the node to roll back to if the switch fails
+    pending_selections: HashMap<String, Option<String>>,
     pub delay_rx: mpsc::UnboundedReceiver<DelayTestResult>,
     delay_tx: mpsc::UnboundedSender<DelayTestResult>,
+    pending_requests: Vec<ApiRequest>,
+    api_tx: mpsc::UnboundedSender<ApiRequest>,
+    api_rx: mpsc::UnboundedReceiver<ApiResponse>,
+    /// Bumped whenever something shown may have changed, so unchanged frames are skipped
+    version: u64,
 }
 
 impl AppState {
     pub fn new(client: ClashClient, preset: Preset) -> Self {
         let (delay_tx, delay_rx) = mpsc::unbounded_channel();
+        let (api_tx, api_rx) = fetcher::spawn_fetcher(client.clone());
         let mode = preset.default_mode();
 
         Self {
@@ -46,29 +91,354 @@ impl AppState {
             current_page: Page::Home,
             mode,
             preset,
+            preset_restore: PresetRestore::default(),
             status_message: None,
+            errors: ErrorLog::default(),
+            messages: MessageLog::default(),
+            notifier: Notifier::default(),
+            hooks: Hooks::default(),
             delay_cache: HashMap::new(),
+            delay_ttl_minutes: DEFAULT_DELAY_TTL_MINUTES,
+            delay_test: DelayTest::default(),
+            proxy_probe: None,
+            delay_log: DelayLog::default(),
+            tasks: TaskRegistry::default(),
+            bypasses: Vec::new(),
+            gui: GuiGuard::default(),
             testing_nodes: Vec::new(),
+            pending_switches: Vec::new(),
+            pending_auto_selects: Vec::new(),
+            pending_selections: HashMap::new(),
             delay_rx,
             delay_tx,
+            pending_requests: Vec::new(),
+            api_tx,
+            api_rx,
+            version: 0,
         }
     }
 
+    /// Mark the screen as out of date
+    pub fn touch(&mut self) {
+        self.version = self.version.wrapping_add(1);
+    }
+
+    /// Changes so far; a frame drawn at the same version can be reused
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
     /// Refresh Clash state from API
     pub async fn refresh(&mut self) -> Result<()> {
         self.clash_state.refresh().await
     }
 
-    /// Select a proxy for a selector group
-    pub async fn select_proxy(&mut self, selector: &str, proxy: &str) -> Result<()> {
-        self.clash_state
-            .client
-            .select_proxy(selector, proxy)
-            .await?;
-        self.status_message = Some(format!("Switched {} to {}", selector, proxy));
-        // Refresh to get updated state
-        let _ = self.refresh().await;
-        Ok(())
+    /// Point the app at a different Clash API
+    /// Restarts the background fetcher; responses still in flight for the old client are dropped
+    pub fn set_client(&mut self, client: ClashClient) {
+        let (api_tx, api_rx) = fetcher::spawn_fetcher(client.clone());
+        self.api_tx = api_tx;
+        self.api_rx = api_rx;
+        self.pending_requests.clear();
+        self.pending_selections.clear();
+        self.clash_state.client = client;
+        self.dispatch(ApiRequest::Refresh);
+    }
+
+    /// Show `error` in the status bar and keep it for the error console
+    pub fn report_error(&mut self, context: &str, error: &anyhow::Error) {
+        let message = format!("{}: {}", context, error);
+        self.messages.record(Severity::Error, &message);
+        self.status_message = Some(message);
+        self.errors.record(context, error);
+        self.touch();
+    }
+
+    /// Keep the status message in the history before it's replaced
+    pub fn log_status(&mut self) {
+        self.messages.observe(self.status_message.as_deref());
+    }
+
+    /// Whether `action` may write GUI profiles or the Clash config; while the GUI runs the
+    /// first attempt only shows a warning, see `GuiGuard::check`
+    pub fn allow_gui_write(&mut self, action: &str) -> bool {
+        match self.gui.check(action) {
+            Ok(()) => true,
+            Err(warning) => {
+                self.status_message = Some(warning);
+                false
+            }
+        }
+    }
+
+    /// Keep the bypasses for the next session, so they still expire after a restart
+    pub fn save_bypasses(&mut self) {
+        if let Err(e) = bypass::default_path().and_then(|path| bypass::save(&path, &self.bypasses))
+        {
+            self.report_error("Failed to save DIRECT bypasses", &e);
+        }
+    }
+
+    /// Move failed hook commands into the error console
+    pub fn process_hook_failures(&mut self) {
+        while let Some(failure) = self.hooks.next_failure() {
+            let context = format!("Hook {} failed", failure.event.key());
+            self.report_error(&context, &failure.error);
+        }
+    }
+
+    /// Queue a Clash API request (non-blocking)
+    /// Ignored if an identical request is still in flight
+    pub fn request(&mut self, request: ApiRequest) {
+        if !self.pending_requests.contains(&request) {
+            self.dispatch(request);
+        }
+    }
+
+    fn dispatch(&mut self, request: ApiRequest) {
+        if self.api_tx.send(request.clone()).is_ok() {
+            self.pending_requests.push(request);
+        }
+    }
+
+    /// Label of the most recent in-flight request
+    pub fn busy_label(&self) -> Option<&'static str> {
+        self.pending_requests.last().map(|request| request.label())
+    }
+
+    /// Take the next finished API response, if any
+    /// Shared state (proxies, mode, status) is applied here; page data is left to the caller
+    pub fn next_api_response(&mut self) -> Option<ApiResponse> {
+        let response = self.api_rx.try_recv().ok()?;
+
+        let request = response.request();
+        if let Some(pos) = self.pending_requests.iter().position(|r| r == &request) {
+            self.pending_requests.remove(pos);
+        }
+
+        match &response {
+            ApiResponse::Refreshed {
+                snapshot,
+                core_running,
+            } => {
+                self.clash_state.apply_snapshot(snapshot);
+                self.reapply_pending_selections();
+                self.clash_state.core_running = *core_running;
+                let error = snapshot.as_ref().err().map(|e| e.to_string());
+                self.notifier.core_status(error.as_deref());
+                self.hooks.core_status(error.as_deref());
+                if snapshot.is_ok() {
+                    self.check_current_node_history();
+                }
+            }
+            ApiResponse::Providers(Ok(providers)) => {
+                self.notifier
+                    .subscriptions(providers, chrono::Utc::now().timestamp());
+            }
+            ApiResponse::ModeSwitched { mode, result } => match result {
+                Ok(()) => {
+                    self.status_message = Some(format!("Switched to {} mode", mode.as_str()));
+                    self.hooks
+                        .fire(HookEvent::ModeChanged, &[("CLASHCTL_MODE", mode.as_str())]);
+                    // Refresh to get updated state
+                    self.dispatch(ApiRequest::Refresh);
+                }
+                Err(e) => self.report_error("Failed to switch mode", e),
+            },
+            ApiResponse::ProxyProbed { status, .. } => self.proxy_probe = Some(status.clone()),
+            ApiResponse::TunToggled(result) => match result {
+                Ok(enabled) => {
+                    self.status_message = Some(format!(
+                        "TUN mode {}",
+                        if *enabled { "enabled" } else { "disabled" }
+                    ));
+                    self.dispatch(ApiRequest::Refresh);
+                }
+                Err(e) => self.report_error("Failed to toggle TUN mode", e),
+            },
+            ApiResponse::LogLevelSet { level, result } => match result {
+                Ok(()) => {
+                    self.status_message = Some(format!("Core log level set to {}", level));
+                    self.clash_state.log_level = Some(level.clone());
+                }
+                Err(e) => self.report_error("Failed to set log level", e),
+            },
+            ApiResponse::ProxySelected {
+                selector,
+                proxy,
+                result,
+                group,
+            } => {
+                // A later switch of the same group, still queued, decides what it shows
+                let superseded = self.selecting(selector).is_some();
+                match result {
+                    Ok(()) => {
+                        self.status_message = Some(format!("Switched {} to {}", selector, proxy));
+                        self.hooks.fire(
+                            HookEvent::NodeSwitched,
+                            &[("CLASHCTL_GROUP", selector), ("CLASHCTL_NODE", proxy)],
+                        );
+                        if superseded {
+                            self.pending_selections
+                                .insert(selector.clone(), Some(proxy.clone()));
+                        } else {
+                            self.pending_selections.remove(selector);
+                            match group {
+                                Some(group) => self.clash_state.update_group(group.clone()),
+                                None => self.dispatch(ApiRequest::Refresh),
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        self.report_error("Failed to switch", e);
+                        if !superseded {
+                            if let Some(previous) = self.pending_selections.remove(selector) {
+                                self.clash_state.set_group_now(selector, previous);
+                            }
+                        }
+                    }
+                }
+            }
+            ApiResponse::ProxyUnpinned {
+                selector,
+                result,
+                group,
+            } => match result {
+                Ok(()) => {
+                    self.status_message = Some(format!("{} picks its own node again", selector));
+                    match group {
+                        Some(group) => self.clash_state.update_group(group.clone()),
+                        None => self.dispatch(ApiRequest::Refresh),
+                    }
+                }
+                Err(e) => self.report_error("Failed to unpin", e),
+            },
+            _ => {}
+        }
+
+        Some(response)
+    }
+
+    /// Select a proxy for a selector group (non-blocking)
+    /// The list shows the new node right away; it's rolled back if the core rejects the switch
+    pub fn select_proxy(&mut self, selector: &str, proxy: &str) {
+        let request = ApiRequest::SelectProxy {
+            selector: selector.to_string(),
+            proxy: proxy.to_string(),
+        };
+        if self.pending_requests.contains(&request) {
+            return;
+        }
+        if self.clash_state.proxies.contains_key(selector) {
+            let previous = self
+                .clash_state
+                .set_group_now(selector, Some(proxy.to_string()));
+            self.pending_selections
+                .entry(selector.to_string())
+                .or_insert(previous);
+        }
+        self.dispatch(request);
+    }
+
+    /// Clear the node pinned on a url-test group (non-blocking)
+    pub fn unpin_proxy(&mut self, selector: &str) {
+        let request = ApiRequest::UnpinProxy(selector.to_string());
+        if !self.pending_requests.contains(&request) {
+            self.dispatch(request);
+        }
+    }
+
+    /// Node of the latest switch of `selector` still in flight
+    fn selecting(&self, selector: &str) -> Option<&str> {
+        self.pending_requests
+            .iter()
+            .rev()
+            .find_map(|request| match request {
+                ApiRequest::SelectProxy {
+                    selector: group,
+                    proxy,
+                } if group == selector => Some(proxy.as_str()),
+                _ => None,
+            })
+    }
+
+    /// A refresh fetched while switches were in flight shows the old nodes; put the new ones back
+    fn reapply_pending_selections(&mut self) {
+        let selectors: Vec<String> = self.pending_selections.keys().cloned().collect();
+        for selector in selectors {
+            if let Some(proxy) = self.selecting(&selector).map(str::to_string) {
+                self.clash_state.set_group_now(&selector, Some(proxy));
+            }
+        }
+    }
+
+    /// Select a proxy, optionally testing it first (non-blocking)
+    /// With `test_first` the switch only happens once the delay test succeeds
+    pub fn select_proxy_checked(&mut self, selector: &str, proxy: &str, test_first: bool) {
+        if !test_first || !self.is_node_testable(proxy) {
+            self.select_proxy(selector, proxy);
+            return;
+        }
+
+        let pending = (selector.to_string(), proxy.to_string());
+        if !self.pending_switches.contains(&pending) {
+            self.pending_switches.push(pending);
+        }
+        self.status_message = Some(format!("Testing {} before switching...", proxy));
+        self.start_test_delay(proxy.to_string());
+    }
+
+    /// Test every node of `group` and switch to the fastest one within `max_delay` ms (non-blocking)
+    pub fn auto_select(&mut self, group: &str, nodes: &[String], max_delay: Option<u32>) {
+        let nodes: Vec<String> = nodes
+            .iter()
+            .filter(|node| self.is_node_testable(node))
+            .cloned()
+            .collect();
+        if nodes.is_empty() {
+            self.status_message = Some(format!("{} has no nodes to test", group));
+            return;
+        }
+        self.pending_auto_selects
+            .retain(|pending| pending.group != group);
+        self.pending_auto_selects.push(AutoSelect::new(
+            group.to_string(),
+            nodes.clone(),
+            max_delay,
+        ));
+        self.status_message = Some(format!(
+            "Testing {} nodes to pick the fastest in {}...",
+            nodes.len(),
+            group
+        ));
+        for node in nodes {
+            self.start_test_delay(node);
+        }
+    }
+
+    /// Switch a group whose auto-select tests are all done
+    fn finish_auto_select(&mut self, pending: AutoSelect) {
+        let picked = auto_select::fastest(&pending.results, pending.max_delay)
+            .map(|(node, delay)| (node.to_string(), delay));
+        match picked {
+            Some((node, delay)) => {
+                self.select_proxy(&pending.group, &node);
+                self.status_message = Some(format!(
+                    "Auto-selected {} ({}ms) in {}",
+                    node, delay, pending.group
+                ));
+            }
+            None => {
+                let limit = pending
+                    .max_delay
+                    .map(|max| format!(" under {}ms", max))
+                    .unwrap_or_default();
+                self.status_message = Some(format!(
+                    "No node in {} answered{}, kept the current one",
+                    pending.group, limit
+                ));
+            }
+        }
     }
 
     /// Test delay for a proxy (non-blocking)
@@ -84,16 +454,13 @@ impl AppState {
 
         // Clone what we need for the async task
         let client = self.clash_state.client.clone();
+        let test = self.delay_test.clone();
         let proxy_name = proxy.clone();
         let tx = self.delay_tx.clone();
 
         // Spawn background task
-        tokio::spawn(async move {
-            let result = client
-                .test_delay(&proxy_name, Some("https://www.google.com"), Some(5000))
-                .await;
-
-            let delay = result.ok().map(|r| r.delay);
+        self.tasks.spawn(format!("delay:{}", proxy), async move {
+            let delay = test.run(&client, &proxy_name).await;
 
             // Send result back
             let _ = tx.send(DelayTestResult {
@@ -106,6 +473,7 @@ impl AppState {
     /// Process any pending delay test results
     pub fn process_delay_results(&mut self) {
         while let Ok(result) = self.delay_rx.try_recv() {
+            self.touch();
             // Remove from testing list
             self.testing_nodes.retain(|n| n != &result.node);
 
@@ -114,13 +482,26 @@ impl AppState {
                 continue;
             }
 
+            if self.get_current_node().as_deref() == Some(result.node.as_str()) {
+                self.notifier.node_health(&result.node, result.delay);
+            }
+
+            let node_type = self
+                .clash_state
+                .proxies
+                .get(&result.node)
+                .map(|proxy| format!("{:?}", proxy.proxy_type))
+                .unwrap_or_default();
+            self.delay_log
+                .record(&result.node, &node_type, result.delay);
+
             // Update cache if test succeeded
             if let Some(delay) = result.delay {
                 self.delay_cache.insert(
                     result.node.clone(),
-                    DelayResult {
+                    CachedDelay {
                         delay,
-                        tested_at: Instant::now(),
+                        tested_at: chrono::Utc::now().timestamp(),
                     },
                 );
 
@@ -136,6 +517,54 @@ impl AppState {
             } else {
                 self.status_message = Some(format!("{}: Test failed", result.node));
             }
+
+            // Finish switches that were waiting on this test
+            let (ready, waiting): (Vec<_>, Vec<_>) = std::mem::take(&mut self.pending_switches)
+                .into_iter()
+                .partition(|(_, node)| node == &result.node);
+            self.pending_switches = waiting;
+            for (selector, node) in ready {
+                if result.delay.is_some() {
+                    self.select_proxy(&selector, &node);
+                } else {
+                    self.status_message = Some(format!(
+                        "{} is unreachable, kept the current node in {}",
+                        node, selector
+                    ));
+                }
+            }
+
+            // Groups picking their fastest node switch once their last node is tested
+            let (done, waiting): (Vec<_>, Vec<_>) = std::mem::take(&mut self.pending_auto_selects)
+                .into_iter()
+                .map(|mut pending| {
+                    let done = pending.record(&result.node, result.delay);
+                    (done, pending)
+                })
+                .partition(|(done, _)| *done);
+            self.pending_auto_selects = waiting.into_iter().map(|(_, pending)| pending).collect();
+            for (_, pending) in done {
+                self.finish_auto_select(pending);
+            }
+        }
+    }
+
+    /// Report the current node as down when its latest recorded health check failed
+    fn check_current_node_history(&mut self) {
+        let Some(node) = self.get_current_node() else {
+            return;
+        };
+        let latest = self
+            .clash_state
+            .proxies
+            .get(&node)
+            .and_then(|proxy| proxy.history.as_ref())
+            .and_then(|history| history.last())
+            .map(|entry| entry.delay);
+        if let Some(delay) = latest {
+            if self.is_node_testable(&node) {
+                self.notifier.node_health(&node, Some(delay));
+            }
         }
     }
 
@@ -148,57 +577,91 @@ impl AppState {
     }
 
     /// Get cached delay result for a node
-    pub fn get_delay(&self, node: &str) -> Option<&DelayResult> {
+    pub fn get_delay(&self, node: &str) -> Option<&CachedDelay> {
         if !self.is_node_testable(node) {
             return None;
         }
         self.delay_cache.get(node)
     }
 
-    /// Get current active node (from first available route)
+    /// Whether a cached delay is older than the TTL
+    pub fn is_delay_stale(&self, cached: &CachedDelay) -> bool {
+        self.is_tested_stale(cached.tested_at)
+    }
+
+    /// Whether a test run at unix time `tested_at` is older than the TTL
+    pub fn is_tested_stale(&self, tested_at: i64) -> bool {
+        let ttl = self.delay_ttl_minutes;
+        ttl > 0 && chrono::Utc::now().timestamp() - tested_at > ttl as i64 * 60
+    }
+
+    /// Delays of a route's selected node and of its fastest node
+    pub fn group_delays(&self, route: &HumanRoute) -> node_info::GroupDelays {
+        node_info::group_delays(
+            &self.clash_state.proxies,
+            &self.delay_cache,
+            route.current_node.as_deref(),
+            &route.all_nodes,
+        )
+    }
+
+    /// Get current active node (from the primary group, else the first available route)
     pub fn get_current_node(&self) -> Option<String> {
-        // Try to find the first route with a current node
         let routes = crate::clash::HumanRoute::from_proxies(&self.clash_state.proxies, self.mode);
-        for route in routes {
-            if let Some(node) = route.current_node {
-                return Some(node);
-            }
+        let primary = self.clash_state.current_selector.as_deref();
+        if let Some(node) = routes
+            .iter()
+            .find(|route| Some(route.name.as_str()) == primary)
+            .and_then(|route| route.current_node.clone())
+        {
+            return Some(node);
         }
-        None
+        routes.into_iter().find_map(|route| route.current_node)
     }
 
     /// Check if a node is testable (not Direct/Reject type)
     pub fn is_node_testable(&self, node_name: &str) -> bool {
-        if node_name.eq_ignore_ascii_case("DIRECT") {
-            return false;
+        auto_select::is_testable(node_name, &self.clash_state.proxies)
+    }
+
+    /// Switch Clash mode (Rule/Global/Direct) (non-blocking)
+    /// Switch to the next preset, applying its Clash mode and node or restoring what the previous one changed
+    pub fn cycle_preset(&mut self, config: &mut AppConfig) {
+        self.preset = self.preset.cycle(&config.custom_presets);
+        self.mode = self.preset.default_mode();
+        if let Err(e) = config.set_preset(&self.preset) {
+            self.errors.record("Failed to save preset", &e);
         }
-        if let Some(proxy) = self.clash_state.proxies.get(node_name) {
-            !matches!(
-                proxy.proxy_type,
-                ProxyType::Direct
-                    | ProxyType::Reject
-                    | ProxyType::RejectDrop
-                    | ProxyType::Compatible
-                    | ProxyType::Pass
-            )
-        } else {
-            // If we can't find the proxy, assume it's testable
-            // This handles cases where the node name might be in a nested structure
-            true
-        }
-    }
-
-    /// Switch Clash mode (Rule/Global/Direct)
-    pub async fn switch_mode(&mut self, mode: ClashMode) -> Result<()> {
-        let config = serde_json::json!({
-            "mode": mode.as_str()
-        });
+        let proxies = &self.clash_state.proxies;
+        let change = self.preset_restore.switch(
+            config.preset_core(&self.preset),
+            &self.clash_state.mode,
+            |group| proxies.get(group).and_then(|group| group.now.clone()),
+        );
 
-        self.clash_state.client.update_config(config).await?;
-        self.status_message = Some(format!("Switched to {} mode", mode.as_str()));
-        // Refresh to get updated state
-        let _ = self.refresh().await;
-        Ok(())
+        let mut message = format!(
+            "Switched to {} preset: {}",
+            self.preset.name(),
+            self.preset.description()
+        );
+        if let Some(mode) = change.mode {
+            message.push_str(&format!(" (mode: {})", mode.as_str()));
+            self.switch_mode(mode);
+        }
+        for (group, node) in change.selections {
+            message.push_str(&format!(" ({} → {})", group, node));
+            self.select_proxy(&group, &node);
+        }
+        self.status_message = Some(message);
+    }
+
+    pub fn switch_mode(&mut self, mode: ClashMode) {
+        self.request(ApiRequest::SwitchMode(mode));
+    }
+
+    /// Change the core's own log level (non-blocking)
+    pub fn set_core_log_level(&mut self, level: &str) {
+        self.request(ApiRequest::SetLogLevel(level.to_string()));
     }
 
     /// Update all providers
@@ -219,7 +682,8 @@ impl AppState {
 }
 
 /// Current page
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Page {
     Home,
     Routes,
@@ -229,6 +693,46 @@ pub enum Page {
     Settings,
     Logs,
     Performance,
+    Statistics,
+    Favorites,
+    Groups,
+    Heatmap,
+}
+
+impl Page {
+    /// Every page, Home first
+    pub const ALL: [Page; 12] = [
+        Page::Home,
+        Page::Routes,
+        Page::Rules,
+        Page::Update,
+        Page::Connections,
+        Page::Settings,
+        Page::Logs,
+        Page::Performance,
+        Page::Statistics,
+        Page::Favorites,
+        Page::Groups,
+        Page::Heatmap,
+    ];
+
+    /// Name used in the config
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Page::Home => "home",
+            Page::Routes => "routes",
+            Page::Rules => "rules",
+            Page::Update => "update",
+            Page::Connections => "connections",
+            Page::Settings => "settings",
+            Page::Logs => "logs",
+            Page::Performance => "performance",
+            Page::Statistics => "statistics",
+            Page::Favorites => "favorites",
+            Page::Groups => "groups",
+            Page::Heatmap => "heatmap",
+        }
+    }
 }
 
 /// Clash state from API
@@ -237,10 +741,16 @@ pub struct ClashState {
     pub client: ClashClient,
     pub mode: ClashMode,
     pub proxies: HashMap<String, Proxy>,
+    /// The core's own log level, e.g. "info"
+    pub log_level: Option<String>,
     pub current_selector: Option<String>,
     pub current_proxy: Option<String>,
+    /// Configured primary group, preferred over auto-detection while it exists
+    pub primary_group: Option<String>,
     pub last_update: Instant,
     pub error: Option<String>,
+    /// Whether the core process is running (None if unknown)
+    pub core_running: Option<bool>,
 }
 
 impl ClashState {
@@ -249,69 +759,86 @@ impl ClashState {
             client,
             mode: ClashMode::Rule,
             proxies: HashMap::new(),
+            log_level: None,
             current_selector: None,
             current_proxy: None,
+            primary_group: None,
             last_update: Instant::now(),
             error: None,
+            core_running: None,
         }
     }
 
     /// Refresh state from Clash API
     pub async fn refresh(&mut self) -> Result<()> {
-        self.error = None;
-
-        // Get config
-        match self.client.get_config().await {
-            Ok(config) => {
-                if let Some(raw_mode) = config.mode.as_deref() {
-                    if let Some(mode) = ClashMode::from_str(raw_mode) {
-                        self.mode = mode;
-                    }
-                }
-            }
-            Err(e) => {
-                self.error = Some(format!("Failed to get config: {}", e));
-                return Err(e);
-            }
-        }
+        let snapshot = ClashSnapshot::fetch(&self.client).await;
+        self.apply_snapshot(&snapshot);
+        self.core_running = match &snapshot {
+            Ok(_) => Some(true),
+            Err(_) => crate::core::service::is_core_running().await,
+        };
+        snapshot.map(|_| ())
+    }
 
-        // Get proxies
-        match self.client.get_proxies().await {
-            Ok(proxies_response) => {
-                self.proxies = proxies_response.proxies;
+    /// Apply a fetched snapshot (or record its error)
+    pub fn apply_snapshot(&mut self, snapshot: &Result<ClashSnapshot>) {
+        match snapshot {
+            Ok(snapshot) => {
+                self.error = None;
+                if let Some(mode) = &snapshot.mode {
+                    self.mode = mode.clone();
+                }
+                if let Some(proxies) = &snapshot.proxies {
+                    merge_proxies(&mut self.proxies, proxies);
+                }
+                if snapshot.log_level.is_some() {
+                    self.log_level = snapshot.log_level.clone();
+                }
 
-                // Find the main selector (usually "GLOBAL" or first selector)
                 self.find_main_selector();
+                self.last_update = Instant::now();
             }
             Err(e) => {
-                self.error = Some(format!("Failed to get proxies: {}", e));
-                return Err(e);
+                self.error = Some(e.to_string());
             }
         }
-
-        self.last_update = Instant::now();
-        Ok(())
     }
 
-    /// Find the main proxy selector
+    /// Find the main proxy selector: the configured primary group, else a detected one
     fn find_main_selector(&mut self) {
-        // Try to find "GLOBAL" first
-        if self.proxies.contains_key("GLOBAL") {
-            self.current_selector = Some("GLOBAL".to_string());
-            if let Some(proxy) = self.proxies.get("GLOBAL") {
-                self.current_proxy = proxy.now.clone();
-            }
-            return;
-        }
+        let selector = self
+            .primary_group
+            .clone()
+            .filter(|name| self.proxies.contains_key(name))
+            .or_else(|| detect_primary_group(&self.proxies, &self.mode));
+        self.current_proxy = selector
+            .as_ref()
+            .and_then(|name| self.proxies.get(name))
+            .and_then(|proxy| proxy.now.clone());
+        self.current_selector = selector;
+    }
 
-        // Otherwise find the first selector
-        for (name, proxy) in &self.proxies {
-            if proxy.proxy_type == ProxyType::Selector {
-                self.current_selector = Some(name.clone());
-                self.current_proxy = proxy.now.clone();
-                return;
-            }
-        }
+    /// Set a group's selected node locally, returning the one it replaced
+    pub fn set_group_now(&mut self, selector: &str, now: Option<String>) -> Option<String> {
+        let previous = match self.proxies.get_mut(selector) {
+            Some(group) => std::mem::replace(&mut group.now, now),
+            None => return None,
+        };
+        self.find_main_selector();
+        previous
+    }
+
+    /// Replace one group with its latest state from the core
+    pub fn update_group(&mut self, group: Proxy) {
+        self.proxies.insert(group.name.clone(), group);
+        self.find_main_selector();
+        self.last_update = Instant::now();
+    }
+
+    /// Use `group` as the primary group (None to auto-detect)
+    pub fn set_primary_group(&mut self, group: Option<String>) {
+        self.primary_group = group;
+        self.find_main_selector();
     }
 
     /// Get health status based on proxy state
@@ -330,6 +857,68 @@ impl ClashState {
     }
 }
 
+/// Guess the main selector group
+/// GLOBAL in global mode, otherwise the selector offering the most proxies
+/// (ties go to the first name), falling back to GLOBAL
+pub fn detect_primary_group(proxies: &HashMap<String, Proxy>, mode: &ClashMode) -> Option<String> {
+    if *mode == ClashMode::Global && proxies.contains_key("GLOBAL") {
+        return Some("GLOBAL".to_string());
+    }
+    proxies
+        .iter()
+        .filter(|(name, proxy)| proxy.proxy_type == ProxyType::Selector && *name != "GLOBAL")
+        .max_by(|(a_name, a), (b_name, b)| {
+            let count = |proxy: &Proxy| proxy.all.as_ref().map_or(0, Vec::len);
+            count(a).cmp(&count(b)).then_with(|| b_name.cmp(a_name))
+        })
+        .map(|(name, _)| name.clone())
+        .or_else(|| proxies.contains_key("GLOBAL").then(|| "GLOBAL".to_string()))
+}
+
+/// Mode and proxies fetched from the Clash API in one refresh
+#[derive(Debug, Clone)]
+pub struct ClashSnapshot {
+    pub mode: Option<ClashMode>,
+    pub log_level: Option<String>,
+    /// None when the proxies haven't changed since the client's previous fetch
+    pub proxies: Option<HashMap<String, Proxy>>,
+}
+
+impl ClashSnapshot {
+    /// Fetch config and proxies from Clash API, skipping proxies that haven't changed
+    pub async fn fetch(client: &ClashClient) -> Result<Self> {
+        let config = client
+            .get_config()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to get config: {}", e))?;
+        let proxies = client
+            .get_proxies_if_changed()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to get proxies: {}", e))?;
+
+        Ok(Self {
+            mode: config.mode.as_deref().and_then(ClashMode::from_str),
+            log_level: Some(config.log_level).filter(|level| !level.is_empty()),
+            proxies: proxies.map(|response| response.proxies),
+        })
+    }
+}
+
+/// Bring `current` up to date with `fetched`, touching only the proxies that changed
+/// Returns how many were added, replaced or removed
+fn merge_proxies(current: &mut HashMap<String, Proxy>, fetched: &HashMap<String, Proxy>) -> usize {
+    let before = current.len();
+    current.retain(|name, _| fetched.contains_key(name));
+    let mut changed = before - current.len();
+    for (name, proxy) in fetched {
+        if current.get(name) != Some(proxy) {
+            current.insert(name.clone(), proxy.clone());
+            changed += 1;
+        }
+    }
+    changed
+}
+
 /// Health status indicator
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum HealthStatus {
@@ -364,3 +953,199 @@ impl HealthStatus {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_proxies_touches_only_changes() {
+        let proxy = |name: &str, now: Option<&str>| Proxy {
+            proxy_type: ProxyType::Selector,
+            name: name.to_string(),
+            now: now.map(str::to_string),
+            ..Default::default()
+        };
+        let mut current: HashMap<String, Proxy> = [
+            ("Proxy".to_string(), proxy("Proxy", Some("HK-1"))),
+            ("Old".to_string(), proxy("Old", None)),
+        ]
+        .into();
+        let fetched: HashMap<String, Proxy> = [
+            ("Proxy".to_string(), proxy("Proxy", Some("JP-1"))),
+            ("New".to_string(), proxy("New", None)),
+        ]
+        .into();
+
+        assert_eq!(merge_proxies(&mut current, &fetched), 3);
+        assert_eq!(current, fetched);
+        assert_eq!(merge_proxies(&mut current, &fetched), 0);
+    }
+
+    fn group(proxy_type: ProxyType, name: &str, nodes: usize) -> (String, Proxy) {
+        let proxy = Proxy {
+            proxy_type,
+            name: name.to_string(),
+            all: Some((0..nodes).map(|i| format!("node-{}", i)).collect()),
+            ..Default::default()
+        };
+        (name.to_string(), proxy)
+    }
+
+    #[test]
+    fn test_detect_primary_group_in_global_mode() {
+        let proxies: HashMap<String, Proxy> = [
+            group(ProxyType::Selector, "GLOBAL", 3),
+            group(ProxyType::Selector, "Proxy", 10),
+        ]
+        .into();
+        assert_eq!(
+            detect_primary_group(&proxies, &ClashMode::Global).as_deref(),
+            Some("GLOBAL")
+        );
+    }
+
+    #[test]
+    fn test_detect_primary_group_picks_largest_selector_in_rule_mode() {
+        let proxies: HashMap<String, Proxy> = [
+            group(ProxyType::Selector, "GLOBAL", 20),
+            group(ProxyType::Selector, "Streaming", 4),
+            group(ProxyType::Selector, "Proxy", 8),
+            group(ProxyType::Selector, "Backup", 8),
+            group(ProxyType::URLTest, "Auto", 30),
+        ]
+        .into();
+        // GLOBAL and non-selectors don't count; ties go to the first name
+        assert_eq!(
+            detect_primary_group(&proxies, &ClashMode::Rule).as_deref(),
+            Some("Backup")
+        );
+    }
+
+    #[test]
+    fn test_detect_primary_group_without_selectors() {
+        let mut proxies: HashMap<String, Proxy> = [
+            group(ProxyType::URLTest, "Auto", 5),
+            group(ProxyType::Selector, "GLOBAL", 5),
+        ]
+        .into();
+        assert_eq!(
+            detect_primary_group(&proxies, &ClashMode::Rule).as_deref(),
+            Some("GLOBAL")
+        );
+        proxies.remove("GLOBAL");
+        assert_eq!(detect_primary_group(&proxies, &ClashMode::Rule), None);
+    }
+
+    /// State showing group `Proxy` on HK-1, with API requests going nowhere
+    /// and responses fed through the returned sender
+    fn offline_state() -> (
+        AppState,
+        mpsc::UnboundedSender<ApiResponse>,
+        mpsc::UnboundedReceiver<ApiRequest>,
+    ) {
+        let client = ClashClient::new("http://127.0.0.1:9".to_string(), None);
+        let mut state = AppState::new(client, Preset::Default);
+        let (api_tx, requests) = mpsc::unbounded_channel();
+        let (responses, api_rx) = mpsc::unbounded_channel();
+        state.api_tx = api_tx;
+        state.api_rx = api_rx;
+        state
+            .clash_state
+            .proxies
+            .insert("Proxy".to_string(), group_on("HK-1"));
+        (state, responses, requests)
+    }
+
+    fn group_on(node: &str) -> Proxy {
+        Proxy {
+            proxy_type: ProxyType::Selector,
+            name: "Proxy".to_string(),
+            now: Some(node.to_string()),
+            all: Some(vec!["HK-1".into(), "JP-1".into(), "SG-1".into()]),
+            ..Default::default()
+        }
+    }
+
+    fn selected(proxy: &str, result: Result<()>) -> ApiResponse {
+        let group = result.is_ok().then(|| group_on(proxy));
+        ApiResponse::ProxySelected {
+            selector: "Proxy".to_string(),
+            proxy: proxy.to_string(),
+            result,
+            group,
+        }
+    }
+
+    fn shown(state: &AppState) -> Option<&str> {
+        state.clash_state.proxies["Proxy"].now.as_deref()
+    }
+
+    #[tokio::test]
+    async fn test_failed_switch_rolls_back_to_node_before_first_switch() {
+        let (mut state, responses, _requests) = offline_state();
+
+        state.select_proxy("Proxy", "JP-1");
+        state.select_proxy("Proxy", "SG-1");
+        assert_eq!(shown(&state), Some("SG-1"));
+
+        responses
+            .send(selected("JP-1", Err(anyhow::anyhow!("down"))))
+            .unwrap();
+        state.next_api_response();
+        assert_eq!(shown(&state), Some("SG-1"));
+
+        responses
+            .send(selected("SG-1", Err(anyhow::anyhow!("down"))))
+            .unwrap();
+        state.next_api_response();
+        assert_eq!(shown(&state), Some("HK-1"));
+        assert!(state.pending_selections.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_superseded_success_becomes_the_rollback_target() {
+        let (mut state, responses, _requests) = offline_state();
+
+        state.select_proxy("Proxy", "JP-1");
+        state.select_proxy("Proxy", "SG-1");
+
+        // The core is on JP-1 now, but SG-1 is still on its way
+        responses.send(selected("JP-1", Ok(()))).unwrap();
+        state.next_api_response();
+        assert_eq!(shown(&state), Some("SG-1"));
+
+        responses
+            .send(selected("SG-1", Err(anyhow::anyhow!("down"))))
+            .unwrap();
+        state.next_api_response();
+        assert_eq!(shown(&state), Some("JP-1"));
+        assert!(state.pending_selections.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_superseded_failure_keeps_the_latest_switch() {
+        let (mut state, responses, _requests) = offline_state();
+
+        state.select_proxy("Proxy", "JP-1");
+        state.select_proxy("Proxy", "SG-1");
+
+        responses
+            .send(selected("JP-1", Err(anyhow::anyhow!("down"))))
+            .unwrap();
+        state.next_api_response();
+        assert_eq!(shown(&state), Some("SG-1"));
+
+        // A refresh fetched meanwhile still shows the old node
+        state
+            .clash_state
+            .set_group_now("Proxy", Some("HK-1".to_string()));
+        state.reapply_pending_selections();
+        assert_eq!(shown(&state), Some("SG-1"));
+
+        responses.send(selected("SG-1", Ok(()))).unwrap();
+        state.next_api_response();
+        assert_eq!(shown(&state), Some("SG-1"));
+        assert!(state.pending_selections.is_empty());
+    }
+}
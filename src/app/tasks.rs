@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::Duration;
+
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+/// A task in the registry
+#[derive(Debug)]
+struct Task {
+    handle: JoinHandle<()>,
+    /// Waits for the shutdown signal to finish its work, instead of being aborted on quit
+    graceful: bool,
+}
+
+/// Background tasks of the TUI by name, so they can be cancelled and drained on quit
+#[derive(Debug)]
+pub struct TaskRegistry {
+    tasks: HashMap<String, Task>,
+    shutdown: watch::Sender<bool>,
+}
+
+impl Default for TaskRegistry {
+    fn default() -> Self {
+        Self {
+            tasks: HashMap::new(),
+            shutdown: watch::channel(false).0,
+        }
+    }
+}
+
+impl TaskRegistry {
+    /// Run `task` under `name`, aborting the task already running under it
+    /// It's aborted on quit; use `spawn_graceful` for work that must be finished
+    pub fn spawn<F>(&mut self, name: impl Into<String>, task: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.insert(name.into(), tokio::spawn(task), false);
+    }
+
+    /// Run the task `start` builds under `name`; it's handed a receiver that turns
+    /// true on quit and given the shutdown grace period to wrap up
+    pub fn spawn_graceful<F>(
+        &mut self,
+        name: impl Into<String>,
+        start: impl FnOnce(watch::Receiver<bool>) -> F,
+    ) where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let task = start(self.shutdown.subscribe());
+        self.insert(name.into(), tokio::spawn(task), true);
+    }
+
+    fn insert(&mut self, name: String, handle: JoinHandle<()>, graceful: bool) {
+        self.tasks.retain(|_, task| !task.handle.is_finished());
+        if let Some(previous) = self.tasks.insert(name, Task { handle, graceful }) {
+            previous.handle.abort();
+        }
+    }
+
+    /// Abort the task running under `name`, returning whether it was still running
+    pub fn cancel(&mut self, name: &str) -> bool {
+        match self.tasks.remove(name) {
+            Some(task) => {
+                let running = !task.handle.is_finished();
+                task.handle.abort();
+                running
+            }
+            None => false,
+        }
+    }
+
+    /// Number of tasks still running
+    pub fn running(&self) -> usize {
+        self.tasks
+            .values()
+            .filter(|task| !task.handle.is_finished())
+            .count()
+    }
+
+    /// Signal quit, abort the plain tasks and give graceful ones up to `grace` before aborting them
+    pub async fn shutdown(&mut self, grace: Duration) {
+        let _ = self.shutdown.send(true);
+        let deadline = tokio::time::Instant::now() + grace;
+        for (_, mut task) in self.tasks.drain() {
+            if !task.graceful {
+                task.handle.abort();
+                continue;
+            }
+            if tokio::time::timeout_at(deadline, &mut task.handle)
+                .await
+                .is_err()
+            {
+                task.handle.abort();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_named_tasks_replace_cancel_and_drain() {
+        let mut tasks = TaskRegistry::default();
+        tasks.spawn("stream", std::future::pending());
+        tasks.spawn("stream", std::future::pending());
+        assert_eq!(tasks.running(), 1);
+        assert!(tasks.cancel("stream"));
+        assert!(!tasks.cancel("stream"));
+
+        let (done_tx, mut done_rx) = tokio::sync::mpsc::unbounded_channel();
+        tasks.spawn("slow", std::future::pending());
+        tasks.spawn_graceful("flush", move |mut shutdown| async move {
+            let _ = shutdown.changed().await;
+            let _ = done_tx.send(());
+        });
+        assert_eq!(tasks.running(), 2);
+
+        tasks.shutdown(Duration::from_secs(1)).await;
+        assert_eq!(tasks.running(), 0);
+        assert!(done_rx.try_recv().is_ok());
+    }
+}
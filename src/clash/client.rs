@@ -1,7 +1,11 @@
 use anyhow::{Context, Result};
 use futures_util::{SinkExt, StreamExt};
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
 use reqwest::Client as HttpClient;
+use reqwest::StatusCode;
 use serde::de::DeserializeOwned;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
 use tokio::sync::{mpsc, watch};
 use tokio_tungstenite::connect_async;
 use tokio_tungstenite::tungstenite::http::Request;
@@ -10,12 +14,51 @@ use url::Url;
 
 use super::types::*;
 
+/// Whether an API error is the core rejecting the secret (HTTP 401)
+pub fn is_unauthorized(error: &str) -> bool {
+    error.contains("401 Unauthorized")
+}
+
+/// The core answered a request with an error status
+#[derive(Debug)]
+pub struct StatusError {
+    action: &'static str,
+    pub status: StatusCode,
+}
+
+impl std::fmt::Display for StatusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.action, self.status)
+    }
+}
+
+impl std::error::Error for StatusError {}
+
+/// Whether a failed request may succeed if sent again: the core was unreachable or
+/// answered with a server error, rather than rejecting the request itself
+pub fn is_transient(error: &anyhow::Error) -> bool {
+    match error.downcast_ref::<StatusError>() {
+        Some(e) => e.status.is_server_error(),
+        None => error.downcast_ref::<reqwest::Error>().is_some(),
+    }
+}
+
 /// Clash External Controller API client
 #[derive(Debug, Clone)]
 pub struct ClashClient {
     base_url: String,
     secret: Option<String>,
     client: HttpClient,
+    /// Last `/proxies` response seen by this client or its clones
+    proxies_cache: Arc<Mutex<ProxiesCache>>,
+}
+
+/// Validators and body hash of the last `/proxies` response
+#[derive(Debug, Default)]
+struct ProxiesCache {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    body_hash: Option<u64>,
 }
 
 impl ClashClient {
@@ -25,9 +68,15 @@ impl ClashClient {
             base_url,
             secret,
             client: HttpClient::new(),
+            proxies_cache: Arc::default(),
         }
     }
 
+    /// The controller URL this client talks to
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
     /// Build authorization header
     fn auth_header(&self) -> Option<String> {
         self.secret.as_ref().map(|s| format!("Bearer {}", s))
@@ -35,6 +84,19 @@ impl ClashClient {
 
     /// Make a GET request
     async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        self.send_get(path)
+            .await?
+            .json()
+            .await
+            .context("Failed to parse Clash API response")
+    }
+
+    /// Make a GET request without parsing the response, returning the body size in bytes
+    pub async fn get_body_len(&self, path: &str) -> Result<usize> {
+        Ok(self.send_get(path).await?.bytes().await?.len())
+    }
+
+    async fn send_get(&self, path: &str) -> Result<reqwest::Response> {
         let url = format!("{}{}", self.base_url, path);
         let mut request = self.client.get(&url);
 
@@ -57,10 +119,7 @@ impl ClashClient {
             );
         }
 
-        response
-            .json()
-            .await
-            .context("Failed to parse Clash API response")
+        Ok(response)
     }
 
     /// Make a PUT request
@@ -95,6 +154,18 @@ impl ClashClient {
         Ok(())
     }
 
+    /// Test connection, giving up after `timeout`
+    pub async fn test_connection_within(&self, timeout: std::time::Duration) -> Result<()> {
+        tokio::time::timeout(timeout, self.test_connection())
+            .await
+            .map_err(|_| anyhow::anyhow!("Timed out after {}s", timeout.as_secs()))?
+    }
+
+    /// Get the core version
+    pub async fn get_version(&self) -> Result<VersionResponse> {
+        self.get("/version").await
+    }
+
     /// Get Clash configuration
     pub async fn get_config(&self) -> Result<ConfigResponse> {
         self.get("/configs").await
@@ -143,13 +214,100 @@ impl ClashClient {
         Ok(())
     }
 
+    /// Resolve a domain with the core's DNS
+    pub async fn dns_query(&self, name: &str) -> Result<DnsQueryResponse> {
+        self.get(&format!("/dns/query?name={}&type=A", name)).await
+    }
+
+    /// Open and close a WebSocket endpoint (e.g. /traffic) to check it accepts upgrades
+    pub async fn check_websocket(&self, path: &str) -> Result<()> {
+        let url = self.ws_url(path)?;
+        let mut request = Request::builder().uri(url.as_str()).body(())?;
+        if let Some(auth) = self.auth_header() {
+            request.headers_mut().insert("Authorization", auth.parse()?);
+        }
+
+        let (mut ws_stream, _) = connect_async(request)
+            .await
+            .with_context(|| format!("Failed to connect to {}", url))?;
+        let _ = ws_stream.close(None).await;
+        Ok(())
+    }
+
     /// Get all proxies
     pub async fn get_proxies(&self) -> Result<ProxiesResponse> {
         self.get("/proxies").await
     }
 
+    /// Get all proxies, or None when nothing changed since the last call on this client
+    /// Sends `If-None-Match`/`If-Modified-Since` when the core gave validators,
+    /// and otherwise compares the body with the previous one before parsing it
+    pub async fn get_proxies_if_changed(&self) -> Result<Option<ProxiesResponse>> {
+        let url = format!("{}/proxies", self.base_url);
+        let mut request = self.client.get(&url);
+        if let Some(auth) = self.auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        {
+            let cache = self.proxies_cache();
+            if let Some(etag) = &cache.etag {
+                request = request.header(IF_NONE_MATCH, etag.as_str());
+            }
+            if let Some(modified) = &cache.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, modified.as_str());
+            }
+        }
+
+        let response = request
+            .send()
+            .await
+            .context(format!("Failed to connect to Clash API at {}", url))?;
+        if response.status() == StatusCode::NOT_MODIFIED {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!(
+                "Clash API returned error: {} - {}",
+                status,
+                if body.is_empty() { "No details" } else { &body }
+            );
+        }
+
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let (etag, last_modified) = (header(ETAG), header(LAST_MODIFIED));
+        let body = response.bytes().await?;
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        body.hash(&mut hasher);
+        let body_hash = hasher.finish();
+        if self.proxies_cache().body_hash == Some(body_hash) {
+            return Ok(None);
+        }
+
+        let proxies: ProxiesResponse =
+            serde_json::from_slice(&body).context("Failed to parse Clash API response")?;
+        *self.proxies_cache() = ProxiesCache {
+            etag,
+            last_modified,
+            body_hash: Some(body_hash),
+        };
+        Ok(Some(proxies))
+    }
+
+    fn proxies_cache(&self) -> std::sync::MutexGuard<'_, ProxiesCache> {
+        self.proxies_cache
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
     /// Get specific proxy
-    #[allow(dead_code)]
     pub async fn get_proxy(&self, name: &str) -> Result<Proxy> {
         self.get(&format!("/proxies/{}", name)).await
     }
@@ -159,7 +317,7 @@ impl ClashClient {
         let url = format!("/proxies/{}", selector);
         let response = self
             .client
-            .put(&format!("{}{}", self.base_url, url))
+            .put(format!("{}{}", self.base_url, url))
             .header("Authorization", self.auth_header().unwrap_or_default())
             .json(&serde_json::json!({"name": proxy}))
             .send()
@@ -167,7 +325,30 @@ impl ClashClient {
             .context("Failed to select proxy")?;
 
         if !response.status().is_success() {
-            anyhow::bail!("Failed to select proxy: {}", response.status());
+            return Err(StatusError {
+                action: "Failed to select proxy",
+                status: response.status(),
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+
+    /// Clear the node pinned on a url-test group so it picks by delay again (mihomo)
+    /// Pinning is a plain `select_proxy` on the group
+    pub async fn unpin_proxy(&self, group: &str) -> Result<()> {
+        let url = format!("/proxies/{}", group);
+        let response = self
+            .client
+            .delete(format!("{}{}", self.base_url, url))
+            .header("Authorization", self.auth_header().unwrap_or_default())
+            .send()
+            .await
+            .context("Failed to unpin node")?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("Failed to unpin node: {}", response.status());
         }
 
         Ok(())
@@ -184,6 +365,7 @@ impl ClashClient {
         let mut params = vec![];
 
         if let Some(url) = test_url {
+            let url: String = url::form_urlencoded::byte_serialize(url.as_bytes()).collect();
             params.push(format!("url={}", url));
         }
         if let Some(t) = timeout {
@@ -203,11 +385,58 @@ impl ClashClient {
         self.get("/rules").await
     }
 
+    /// Get a rule provider; the core reports its size but not its entries
+    pub async fn get_rule_provider(&self, name: &str) -> Result<RuleProvider> {
+        self.get(&format!("/providers/rules/{}", name)).await
+    }
+
     /// Get providers
     pub async fn get_providers(&self) -> Result<ProvidersResponse> {
         self.get("/providers/proxies").await
     }
 
+    /// Get a single provider with its proxies
+    pub async fn get_provider(&self, name: &str) -> Result<Provider> {
+        self.get(&format!("/providers/proxies/{}", name)).await
+    }
+
+    /// Run the health check of a provider, refreshing the delay history of its proxies
+    pub async fn healthcheck_provider(&self, name: &str) -> Result<()> {
+        let url = format!("/providers/proxies/{}/healthcheck", name);
+        let response = self
+            .client
+            .get(format!("{}{}", self.base_url, url))
+            .header("Authorization", self.auth_header().unwrap_or_default())
+            .send()
+            .await
+            .context("Failed to run health check")?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("Failed to run health check: {}", response.status());
+        }
+
+        Ok(())
+    }
+
+    /// Have the core download `external-ui-url` into its `external-ui` directory
+    pub async fn upgrade_ui(&self) -> Result<()> {
+        let response = self
+            .client
+            .post(format!("{}/upgrade/ui", self.base_url))
+            .header("Authorization", self.auth_header().unwrap_or_default())
+            .send()
+            .await
+            .context("Failed to connect to Clash API")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to update the dashboard: {} - {}", status, body);
+        }
+
+        Ok(())
+    }
+
     /// Update provider
     pub async fn update_provider(&self, name: &str) -> Result<()> {
         let _: serde_json::Value = self.put(&format!("/providers/proxies/{}", name)).await?;
@@ -224,7 +453,7 @@ impl ClashClient {
         let url = format!("/connections/{}", id);
         let response = self
             .client
-            .delete(&format!("{}{}", self.base_url, url))
+            .delete(format!("{}{}", self.base_url, url))
             .header("Authorization", self.auth_header().unwrap_or_default())
             .send()
             .await
@@ -241,7 +470,7 @@ impl ClashClient {
     pub async fn close_all_connections(&self) -> Result<()> {
         let response = self
             .client
-            .delete(&format!("{}/connections", self.base_url))
+            .delete(format!("{}/connections", self.base_url))
             .header("Authorization", self.auth_header().unwrap_or_default())
             .send()
             .await
@@ -336,7 +565,15 @@ impl ClashClient {
     }
 
     fn logs_ws_url(&self, level: Option<&str>) -> Result<Url> {
-        let mut url = Url::parse(&self.base_url).context("Invalid base URL for logs WebSocket")?;
+        let mut url = self.ws_url("/logs")?;
+        if let Some(level) = level {
+            url.set_query(Some(&format!("level={}", level)));
+        }
+        Ok(url)
+    }
+
+    fn ws_url(&self, path: &str) -> Result<Url> {
+        let mut url = Url::parse(&self.base_url).context("Invalid base URL for WebSocket")?;
 
         match url.scheme() {
             "https" => url
@@ -349,10 +586,7 @@ impl ClashClient {
             _ => anyhow::bail!("Unsupported URL scheme: {}", url.scheme()),
         }
 
-        url.set_path("/logs");
-        if let Some(level) = level {
-            url.set_query(Some(&format!("level={}", level)));
-        }
+        url.set_path(path);
         Ok(url)
     }
 }
@@ -382,17 +616,17 @@ fn parse_ws_log(text: &str) -> Option<super::types::LogEntry> {
         (simple.level, simple.payload)
     } else {
         let timestamp = chrono::Local::now().format("%H:%M:%S").to_string();
-        return Some(super::types::LogEntry {
+        return Some(super::types::LogEntry::new(
             timestamp,
-            level: "INFO".to_string(),
-            message: text.to_string(),
-        });
+            "INFO".to_string(),
+            text.to_string(),
+        ));
     };
 
     let timestamp = chrono::Local::now().format("%H:%M:%S").to_string();
-    Some(super::types::LogEntry {
+    Some(super::types::LogEntry::new(
         timestamp,
-        level: level.to_uppercase(),
+        level.to_uppercase(),
         message,
-    })
+    ))
 }
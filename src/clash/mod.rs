@@ -1,7 +1,8 @@
 pub mod client;
 pub mod models;
+pub mod reconnect;
 pub mod types;
 
-pub use client::ClashClient;
+pub use client::{is_transient, is_unauthorized, ClashClient};
 pub use models::*;
 pub use types::*;
@@ -6,9 +6,10 @@ use std::collections::HashMap;
 #[derive(Debug, Clone)]
 pub struct HumanRoute {
     pub name: String,
-    #[allow(dead_code)]
     pub proxy_type: ProxyType,
     pub current_node: Option<String>,
+    /// Node pinned on a url-test group, overriding its automatic pick
+    pub fixed_node: Option<String>,
     pub all_nodes: Vec<String>,
     pub node_count: usize,
 }
@@ -24,6 +25,7 @@ impl HumanRoute {
                 ProxyType::Selector
                 | ProxyType::Smart
                 | ProxyType::URLTest
+                | ProxyType::Fallback
                 | ProxyType::LoadBalance => {
                     let all_nodes = proxy.all.clone().unwrap_or_default();
 
@@ -37,6 +39,7 @@ impl HumanRoute {
                         name: name.clone(),
                         proxy_type: proxy.proxy_type.clone(),
                         current_node: proxy.now.clone(),
+                        fixed_node: proxy.fixed.clone().filter(|node| !node.is_empty()),
                         node_count: all_nodes.len(),
                         all_nodes,
                     });
@@ -61,6 +64,16 @@ impl HumanRoute {
         routes
     }
 
+    /// Whether the group picks nodes by health check (url-test/fallback)
+    pub fn has_health_check(&self) -> bool {
+        matches!(self.proxy_type, ProxyType::URLTest | ProxyType::Fallback)
+    }
+
+    /// Whether a node can be pinned on the group, overriding its automatic pick
+    pub fn can_pin(&self) -> bool {
+        self.proxy_type == ProxyType::URLTest
+    }
+
     /// Get display name (truncate if too long)
     pub fn display_name(&self) -> String {
         if self.name.len() > 40 {
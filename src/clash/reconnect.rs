@@ -0,0 +1,79 @@
+use anyhow::Result;
+use std::future::Future;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::watch;
+
+/// Wait before the first retry
+const BASE_DELAY: Duration = Duration::from_secs(1);
+/// Longest wait between retries
+const MAX_DELAY: Duration = Duration::from_secs(60);
+/// A connection that stayed up this long starts the backoff over
+const STABLE_AFTER: Duration = Duration::from_secs(30);
+
+/// Wait before retry `attempt` (starting at 1): doubles up to MAX_DELAY
+/// The upper half is scaled by `jitter` (0.0 to 1.0) so clients don't retry in lockstep
+pub fn backoff_delay(attempt: u32, jitter: f64) -> Duration {
+    let doublings = attempt.saturating_sub(1).min(16);
+    let delay = BASE_DELAY.saturating_mul(1 << doublings).min(MAX_DELAY);
+    delay / 2 + delay.mul_f64(jitter.clamp(0.0, 1.0) / 2.0)
+}
+
+/// Jitter from the clock's sub-second part
+pub fn jitter() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.subsec_nanos() as f64 / 1e9)
+        .unwrap_or(0.5)
+}
+
+/// Keep a streaming connection up until `shutdown` fires
+/// `connect` runs one connection to completion; whenever it ends, `on_retry(attempt, retry_at, reason)`
+/// is called and the next attempt waits with exponential backoff and jitter
+pub async fn keep_connected<F, Fut>(
+    mut shutdown: watch::Receiver<bool>,
+    mut connect: F,
+    mut on_retry: impl FnMut(u32, Instant, String),
+) where
+    F: FnMut(watch::Receiver<bool>) -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    let mut attempt = 0;
+    loop {
+        let started = Instant::now();
+        let result = connect(shutdown.clone()).await;
+        if *shutdown.borrow() {
+            return;
+        }
+
+        if started.elapsed() >= STABLE_AFTER {
+            attempt = 0;
+        }
+        attempt += 1;
+        let reason = match result {
+            Ok(()) => "connection ended".to_string(),
+            Err(e) => format!("error: {}", e),
+        };
+        let delay = backoff_delay(attempt, jitter());
+        on_retry(attempt, Instant::now() + delay, reason);
+
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = shutdown.changed() => return,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_delay() {
+        assert_eq!(backoff_delay(1, 0.0), Duration::from_millis(500));
+        assert_eq!(backoff_delay(1, 1.0), Duration::from_secs(1));
+        assert_eq!(backoff_delay(4, 1.0), Duration::from_secs(8));
+        assert_eq!(backoff_delay(4, 0.5), Duration::from_secs(6));
+        // Capped, even after many failures
+        assert_eq!(backoff_delay(40, 1.0), MAX_DELAY);
+    }
+}
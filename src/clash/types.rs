@@ -21,6 +21,7 @@ impl ClashMode {
         }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(raw: &str) -> Option<Self> {
         match raw.to_lowercase().as_str() {
             "rule" => Some(ClashMode::Rule),
@@ -48,6 +49,10 @@ pub struct ConfigResponse {
     pub socks_port: u16,
     #[serde(rename = "redir-port", default)]
     pub redir_port: u16,
+    #[serde(rename = "mixed-port", default)]
+    pub mixed_port: u16,
+    #[serde(rename = "tproxy-port", default)]
+    pub tproxy_port: u16,
     #[serde(default)]
     pub path: Option<String>,
     #[serde(rename = "allow-lan", default)]
@@ -56,6 +61,38 @@ pub struct ConfigResponse {
     pub mode: Option<String>,
     #[serde(rename = "log-level", default)]
     pub log_level: String,
+    #[serde(default)]
+    pub tun: Option<TunConfig>,
+}
+
+/// TUN section of the core's running config
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TunConfig {
+    #[serde(default)]
+    pub enable: bool,
+}
+
+/// DNS lookup through the core from GET /dns/query
+#[derive(Debug, Clone, Deserialize)]
+pub struct DnsQueryResponse {
+    #[serde(rename = "Status", default)]
+    pub status: i32,
+    #[serde(rename = "Answer", default)]
+    pub answer: Vec<DnsAnswer>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DnsAnswer {
+    #[serde(default)]
+    pub data: String,
+}
+
+/// Version response from GET /version
+#[derive(Debug, Clone, Deserialize)]
+pub struct VersionResponse {
+    pub version: String,
+    #[serde(default)]
+    pub meta: bool,
 }
 
 /// Proxy type
@@ -93,7 +130,7 @@ pub enum ProxyType {
 }
 
 /// Proxy node or group
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(default)]
 pub struct Proxy {
     #[serde(rename = "type")]
@@ -103,6 +140,8 @@ pub struct Proxy {
     pub all: Option<Vec<String>>,
     pub history: Option<Vec<DelayHistory>>,
     pub udp: Option<bool>,
+    /// Node pinned on a url-test group, overriding its own pick (mihomo)
+    pub fixed: Option<String>,
 }
 
 impl Default for Proxy {
@@ -114,12 +153,13 @@ impl Default for Proxy {
             all: None,
             history: None,
             udp: None,
+            fixed: None,
         }
     }
 }
 
 /// Delay history
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct DelayHistory {
     pub time: String,
     pub delay: u32,
@@ -147,6 +187,20 @@ pub struct RulesResponse {
     pub rules: Vec<Rule>,
 }
 
+/// Rule provider from GET /providers/rules/:name
+#[derive(Debug, Clone, Deserialize)]
+pub struct RuleProvider {
+    pub name: String,
+    #[serde(default)]
+    pub behavior: String,
+    #[serde(rename = "ruleCount", default)]
+    pub rule_count: usize,
+    #[serde(rename = "vehicleType", default)]
+    pub vehicle_type: String,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<String>,
+}
+
 /// Provider info
 #[derive(Debug, Clone, Deserialize)]
 pub struct Provider {
@@ -205,10 +259,58 @@ pub struct ConnectionMetadata {
     pub host: Option<String>,
     #[serde(rename = "dnsMode")]
     pub dns_mode: Option<String>,
+    /// Process name, reported when the core's `find-process-mode` is on
+    pub process: Option<String>,
     #[serde(rename = "processPath")]
     pub process_path: Option<String>,
 }
 
+/// Address family of a connection
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IpFamily {
+    V4,
+    V6,
+}
+
+impl IpFamily {
+    pub fn label(self) -> &'static str {
+        match self {
+            IpFamily::V4 => "v4",
+            IpFamily::V6 => "v6",
+        }
+    }
+}
+
+impl ConnectionMetadata {
+    /// Family of the destination address, or of the source while the destination is unresolved
+    pub fn ip_family(&self) -> Option<IpFamily> {
+        [&self.destination_ip, &self.source_ip]
+            .into_iter()
+            .find_map(|ip| ip.parse::<std::net::IpAddr>().ok())
+            .map(|ip| match ip {
+                std::net::IpAddr::V4(_) => IpFamily::V4,
+                std::net::IpAddr::V6(_) => IpFamily::V6,
+            })
+    }
+
+    pub fn is_udp(&self) -> bool {
+        self.network.eq_ignore_ascii_case("udp")
+    }
+
+    /// Name of the process that opened the connection, falling back to the file name of its path
+    pub fn process_name(&self) -> Option<&str> {
+        self.process
+            .as_deref()
+            .filter(|name| !name.is_empty())
+            .or_else(|| {
+                self.process_path
+                    .as_deref()
+                    .and_then(|path| path.rsplit(['/', '\\']).next())
+                    .filter(|name| !name.is_empty())
+            })
+    }
+}
+
 /// Connection info
 #[derive(Debug, Clone, Deserialize)]
 pub struct Connection {
@@ -239,12 +341,105 @@ pub struct LogEntry {
     pub timestamp: String,
     pub level: String,
     pub message: String,
+    /// Connection details parsed from the message, if it describes one
+    #[serde(default)]
+    pub connection: Option<LogConnection>,
+}
+
+impl LogEntry {
+    pub fn new(timestamp: String, level: String, message: String) -> Self {
+        let connection = LogConnection::parse(&message);
+        Self {
+            timestamp,
+            level,
+            message,
+            connection,
+        }
+    }
+}
+
+/// Connection described by a mihomo log line, e.g.
+/// `[TCP] 192.168.1.2:50000(curl) --> example.com:443 match DomainSuffix(example.com) using Proxy`
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct LogConnection {
+    pub network: String,
+    /// Source address as "ip:port"
+    pub source: String,
+    /// Destination as "host:port" or "ip:port"
+    pub destination: String,
+    pub rule: Option<String>,
+    pub proxy: Option<String>,
+}
+
+impl LogConnection {
+    /// Parse a mihomo connection log line (None for other messages)
+    pub fn parse(message: &str) -> Option<Self> {
+        let (network, rest) = message.strip_prefix('[')?.split_once("] ")?;
+        if !network.eq_ignore_ascii_case("tcp") && !network.eq_ignore_ascii_case("udp") {
+            return None;
+        }
+        let (left, right) = rest.split_once(" --> ")?;
+
+        // Dial failures: "dial PROXY (match RULE) SRC --> DST error: ..."
+        let (mut rule, mut proxy, source) = match left.strip_prefix("dial ") {
+            Some(dial) => {
+                let (proxy, tail) = dial.split_once(" (match ")?;
+                let (rule, source) = tail.split_once(") ")?;
+                (Some(rule), Some(proxy), source)
+            }
+            None => (None, None, left),
+        };
+        // Drop the process name that follows the source address
+        let source = source.split('(').next()?.trim();
+
+        let (destination, tail) = right.split_once(' ').unwrap_or((right, ""));
+        if let Some(matched) = tail.strip_prefix("match ") {
+            if let Some((matched_rule, using)) = matched.split_once(" using ") {
+                rule = Some(matched_rule);
+                proxy = Some(using);
+            }
+        } else if let Some(using) = tail.strip_prefix("doesn't match any rule using ") {
+            rule = Some("Match");
+            proxy = Some(using);
+        }
+
+        if source.is_empty() || destination.is_empty() {
+            return None;
+        }
+        Some(Self {
+            network: network.to_uppercase(),
+            source: source.to_string(),
+            destination: destination.to_string(),
+            rule: rule.map(|r| r.trim().to_string()),
+            proxy: proxy.map(|p| p.trim().to_string()),
+        })
+    }
+
+    /// Whether a live connection is the one this log line describes
+    pub fn matches(&self, connection: &Connection) -> bool {
+        let meta = &connection.metadata;
+        if format!("{}:{}", meta.source_ip, meta.source_port) != self.source {
+            return false;
+        }
+        let by_ip = format!("{}:{}", meta.destination_ip, meta.destination_port);
+        let by_host = meta
+            .host
+            .as_deref()
+            .filter(|host| !host.is_empty())
+            .map(|host| format!("{}:{}", host, meta.destination_port));
+        self.destination == by_ip || by_host.as_deref() == Some(self.destination.as_str())
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum LogStreamStatus {
     Connected,
     Disconnected(String),
+    /// Waiting to reconnect after the stream dropped
+    Retrying {
+        attempt: u32,
+        retry_at: std::time::Instant,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -252,3 +447,88 @@ pub enum LogStreamEvent {
     Entry(LogEntry),
     Status(LogStreamStatus),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_connection_parse() {
+        let matched = LogConnection::parse(
+            "[TCP] 192.168.1.2:50000(curl) --> example.com:443 match DomainSuffix(example.com) using Proxy[HK-1]",
+        )
+        .unwrap();
+        assert_eq!(
+            matched,
+            LogConnection {
+                network: "TCP".to_string(),
+                source: "192.168.1.2:50000".to_string(),
+                destination: "example.com:443".to_string(),
+                rule: Some("DomainSuffix(example.com)".to_string()),
+                proxy: Some("Proxy[HK-1]".to_string()),
+            }
+        );
+
+        let dial = LogConnection::parse(
+            "[UDP] dial Proxy (match GeoIP(US)) 10.0.0.5:5353 --> 8.8.8.8:53 error: i/o timeout",
+        )
+        .unwrap();
+        assert_eq!(dial.network, "UDP");
+        assert_eq!(dial.source, "10.0.0.5:5353");
+        assert_eq!(dial.destination, "8.8.8.8:53");
+        assert_eq!(dial.rule.as_deref(), Some("GeoIP(US)"));
+        assert_eq!(dial.proxy.as_deref(), Some("Proxy"));
+
+        let unmatched = LogConnection::parse(
+            "[TCP] 127.0.0.1:61000 --> 10.1.2.3:22 doesn't match any rule using DIRECT",
+        )
+        .unwrap();
+        assert_eq!(unmatched.rule.as_deref(), Some("Match"));
+        assert_eq!(unmatched.proxy.as_deref(), Some("DIRECT"));
+
+        assert_eq!(
+            LogConnection::parse("Start initial configuration in progress"),
+            None
+        );
+        assert_eq!(
+            LogConnection::parse("[DNS] example.com --> 93.184.216.34"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_log_connection_matches_host_or_ip() {
+        let connection: Connection = serde_json::from_value(serde_json::json!({
+            "id": "1",
+            "metadata": {
+                "network": "tcp",
+                "type": "HTTP",
+                "sourceIP": "192.168.1.2",
+                "destinationIP": "93.184.216.34",
+                "sourcePort": "50000",
+                "destinationPort": "443",
+                "host": "example.com",
+            },
+            "upload": 0,
+            "download": 0,
+            "start": "2026-10-16T00:00:00Z",
+            "chains": ["DIRECT"],
+            "rule": "Match",
+        }))
+        .unwrap();
+        let log = |line: &str| LogConnection::parse(line).unwrap();
+
+        assert!(
+            log("[TCP] 192.168.1.2:50000 --> example.com:443 match Match using DIRECT")
+                .matches(&connection)
+        );
+        assert!(
+            log("[TCP] 192.168.1.2:50000 --> 93.184.216.34:443 match Match using DIRECT")
+                .matches(&connection)
+        );
+        assert!(
+            !log("[TCP] 192.168.1.2:50001 --> example.com:443 match Match using DIRECT")
+                .matches(&connection)
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use anyhow::{Context, Result};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Makes temporary names unique between writers in this process
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Replace `path` with `contents` through a temporary file and a rename,
+/// so readers (and a crash) see the old file or the new one, never half of it
+/// An existing file's permissions are kept
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    replace(path, contents.as_ref(), false)
+}
+
+/// `write_atomic` for files holding secrets: the new file is only readable by its owner
+/// (mode 600 on unix) from the moment it's created, whatever the old one allowed
+pub fn write_private(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    replace(path, contents.as_ref(), true)
+}
+
+fn replace(path: &Path, contents: &[u8], private: bool) -> Result<()> {
+    let temp = temp_path(path);
+    let result = (|| -> Result<()> {
+        let mut file = if private {
+            create_private(&temp)?
+        } else {
+            File::create(&temp)?
+        };
+        file.write_all(contents)?;
+        if !private {
+            if let Ok(metadata) = fs::metadata(path) {
+                file.set_permissions(metadata.permissions())?;
+            }
+        }
+        file.sync_all()?;
+        fs::rename(&temp, path)?;
+        Ok(())
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result.with_context(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(unix)]
+fn create_private(path: &Path) -> std::io::Result<File> {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)
+}
+
+#[cfg(not(unix))]
+fn create_private(path: &Path) -> std::io::Result<File> {
+    File::create(path)
+}
+
+/// Hidden file beside `path`, so the rename stays on one filesystem
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    path.with_file_name(format!(
+        ".{}.{}-{}.tmp",
+        name,
+        std::process::id(),
+        TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+/// Exclusive advisory lock on `<path>.lock`, released when dropped
+/// Taken around read-modify-write of files several updates change at once
+#[derive(Debug)]
+pub struct FileLock {
+    _file: File,
+}
+
+/// Wait for the lock on `path`
+pub fn lock(path: &Path) -> Result<FileLock> {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".lock");
+    let lock_path = PathBuf::from(name);
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .with_context(|| format!("Failed to open {}", lock_path.display()))?;
+    file.lock()
+        .with_context(|| format!("Failed to lock {}", lock_path.display()))?;
+    Ok(FileLock { _file: file })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_atomic_replaces_and_lock_excludes() {
+        let root =
+            std::env::temp_dir().join(format!("clashctl-atomic-test-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let path = root.join("profile.yaml");
+
+        write_atomic(&path, "v1").unwrap();
+        write_atomic(&path, "v2").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "v2");
+        let names: Vec<_> = fs::read_dir(&root)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(names, ["profile.yaml"]);
+
+        let held = lock(&path).unwrap();
+        let other = File::options()
+            .write(true)
+            .open(root.join("profile.yaml.lock"))
+            .unwrap();
+        assert!(other.try_lock().is_err());
+        drop(held);
+        assert!(other.try_lock().is_ok());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_private_tightens_existing_file() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root =
+            std::env::temp_dir().join(format!("clashctl-private-test-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let path = root.join("config.yaml");
+        fs::write(&path, "old").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        write_private(&path, "secret: s3cr3t").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "secret: s3cr3t");
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
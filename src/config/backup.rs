@@ -0,0 +1,215 @@
+use anyhow::{Context, Result};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::atomic::write_atomic;
+
+/// Number of operations kept before the oldest backups are deleted
+const MAX_OPERATIONS: usize = 20;
+
+/// A file copied aside before clashctl overwrote it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackedUpFile {
+    pub original: PathBuf,
+    pub backup: PathBuf,
+}
+
+/// Files backed up before one config-mutating operation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupOperation {
+    /// Creation time in unix milliseconds
+    pub created: i64,
+    /// What was about to change, e.g. "Switch to Work"
+    pub label: String,
+    pub files: Vec<BackedUpFile>,
+    /// Config the core should reload after a rollback
+    #[serde(default)]
+    pub reload: Option<PathBuf>,
+}
+
+impl BackupOperation {
+    /// Backup copy of `original`, if it was part of this operation
+    pub fn backup_of(&self, original: &Path) -> Option<&Path> {
+        self.files
+            .iter()
+            .find(|file| file.original == original)
+            .map(|file| file.backup.as_path())
+    }
+}
+
+/// Timestamped backups in `backups` under the state dir
+pub struct BackupStore {
+    dir: PathBuf,
+}
+
+impl BackupStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Open the store in the default location
+    pub fn open_default() -> Result<Self> {
+        Ok(Self::new(super::paths::state_dir()?.join("backups")))
+    }
+
+    /// Point copies recorded while the store lived in `old_dir` at this one
+    pub fn rebase(&self, old_dir: &Path) -> Result<()> {
+        let mut operations = self.operations()?;
+        for file in operations
+            .iter_mut()
+            .flat_map(|operation| &mut operation.files)
+        {
+            match file.backup.file_name() {
+                Some(name) if file.backup.starts_with(old_dir) => file.backup = self.dir.join(name),
+                _ => {}
+            }
+        }
+        if operations.is_empty() {
+            return Ok(());
+        }
+        self.save_operations(&operations)
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.dir.join("index.json")
+    }
+
+    /// Recorded operations, oldest first
+    pub fn operations(&self) -> Result<Vec<BackupOperation>> {
+        let path = self.index_path();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&path)?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    fn save_operations(&self, operations: &[BackupOperation]) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        write_atomic(
+            &self.index_path(),
+            serde_json::to_string_pretty(operations)?,
+        )
+    }
+
+    /// Copy the existing files aside before they are overwritten
+    /// Files that don't exist yet are skipped
+    pub fn record(&self, label: &str, paths: &[&Path], reload: Option<&Path>) -> Result<()> {
+        let now = Local::now();
+        let stamp = now.format("%Y%m%d-%H%M%S%.3f").to_string();
+        fs::create_dir_all(&self.dir)?;
+
+        let mut files = Vec::new();
+        for (index, path) in paths.iter().enumerate() {
+            if !path.is_file() {
+                continue;
+            }
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| "config".to_string());
+            // Operations recorded within the same millisecond must not share copies
+            let mut backup = self.dir.join(format!("{}-{}-{}", stamp, index, name));
+            let mut attempt = 1;
+            while backup.exists() {
+                backup = self
+                    .dir
+                    .join(format!("{}-{}-{}-{}", stamp, index, attempt, name));
+                attempt += 1;
+            }
+            fs::copy(path, &backup)
+                .with_context(|| format!("Failed to back up {}", path.display()))?;
+            files.push(BackedUpFile {
+                original: path.to_path_buf(),
+                backup,
+            });
+        }
+        if files.is_empty() {
+            return Ok(());
+        }
+
+        let mut operations = self.operations()?;
+        operations.push(BackupOperation {
+            created: now.timestamp_millis(),
+            label: label.to_string(),
+            files,
+            reload: reload.map(Path::to_path_buf),
+        });
+        if operations.len() > MAX_OPERATIONS {
+            let excess = operations.len() - MAX_OPERATIONS;
+            for operation in operations.drain(..excess) {
+                for file in operation.files {
+                    let _ = fs::remove_file(file.backup);
+                }
+            }
+        }
+        self.save_operations(&operations)
+    }
+
+    /// Most recent operation that can be rolled back
+    pub fn latest(&self) -> Result<Option<BackupOperation>> {
+        Ok(self.operations()?.pop())
+    }
+
+    /// Restore the files of the most recent operation and drop it from the store
+    pub fn restore_latest(&self) -> Result<Option<BackupOperation>> {
+        let mut operations = self.operations()?;
+        let Some(operation) = operations.pop() else {
+            return Ok(None);
+        };
+
+        for file in &operation.files {
+            fs::copy(&file.backup, &file.original)
+                .with_context(|| format!("Failed to restore {}", file.original.display()))?;
+        }
+        for file in &operation.files {
+            let _ = fs::remove_file(&file.backup);
+        }
+        self.save_operations(&operations)?;
+
+        Ok(Some(operation))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_restore() {
+        let dir = std::env::temp_dir().join(format!("clashctl-backup-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let config = dir.join("config.yaml");
+        let store = BackupStore::new(dir.join("backups"));
+
+        fs::write(&config, "v1").unwrap();
+        store.record("first", &[&config], Some(&config)).unwrap();
+        fs::write(&config, "v2").unwrap();
+        store
+            .record("second", &[&config, &dir.join("missing.yaml")], None)
+            .unwrap();
+        fs::write(&config, "v3").unwrap();
+
+        let latest = store.latest().unwrap().unwrap();
+        assert_eq!(latest.label, "second");
+        assert_eq!(latest.files.len(), 1);
+
+        store.restore_latest().unwrap();
+        assert_eq!(fs::read_to_string(&config).unwrap(), "v2");
+        let restored = store.restore_latest().unwrap().unwrap();
+        assert_eq!(restored.reload.as_deref(), Some(config.as_path()));
+        assert_eq!(fs::read_to_string(&config).unwrap(), "v1");
+        assert!(store.restore_latest().unwrap().is_none());
+
+        // A corrupt index is an error, not an empty history to write over
+        fs::write(dir.join("backups").join("index.json"), "[{").unwrap();
+        assert!(store.operations().is_err());
+        assert!(store.record("third", &[&config], None).is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
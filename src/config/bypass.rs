@@ -0,0 +1,141 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::atomic::write_atomic;
+
+/// Minutes a bypass lasts unless another duration is typed
+pub const DEFAULT_MINUTES: u64 = 30;
+
+/// Second-level labels under which names are registered, as in `example.co.uk`
+const SECOND_LEVEL: &[&str] = &["ac", "co", "com", "edu", "gov", "net", "org"];
+
+/// A DOMAIN-SUFFIX → DIRECT rule clashctl added and takes back out when it expires
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bypass {
+    pub suffix: String,
+    /// Unix time the rule is removed at
+    pub expires_at: i64,
+}
+
+impl Bypass {
+    /// The rule written to the Clash config
+    pub fn rule(&self) -> String {
+        rule_for(&self.suffix)
+    }
+}
+
+fn rule_for(suffix: &str) -> String {
+    format!("DOMAIN-SUFFIX,{},DIRECT", suffix)
+}
+
+/// Get the bypass list file path
+pub fn default_path() -> Result<PathBuf> {
+    Ok(super::paths::state_dir()?.join("bypasses.json"))
+}
+
+/// Load the bypasses still active or waiting to be removed
+pub fn load(path: &Path) -> Result<Vec<Bypass>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+pub fn save(path: &Path, bypasses: &[Bypass]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    write_atomic(path, serde_json::to_string_pretty(bypasses)?)?;
+    Ok(())
+}
+
+/// The registered domain of `host` (`www.example.co.uk` → `example.co.uk`), None for IPs
+pub fn suffix_for_host(host: &str) -> Option<String> {
+    let host = host.trim().trim_end_matches('.').to_lowercase();
+    if host.is_empty() || host.parse::<std::net::IpAddr>().is_ok() {
+        return None;
+    }
+    let labels: Vec<&str> = host.split('.').collect();
+    let keep = match labels.as_slice() {
+        [.., second, tld] if tld.len() == 2 && SECOND_LEVEL.contains(second) => 3,
+        _ => 2,
+    };
+    Some(labels[labels.len().saturating_sub(keep)..].join("."))
+}
+
+/// Whether `rules` already send `suffix` DIRECT with the rule a bypass would add
+pub fn has_bypass_rule(rules: &[String], suffix: &str) -> bool {
+    let rule = rule_for(suffix);
+    rules
+        .iter()
+        .any(|existing| existing.replace(' ', "") == rule)
+}
+
+/// `rules` with the bypass of `suffix` first
+pub fn with_bypass_rule(rules: &[String], suffix: &str) -> Vec<String> {
+    let mut updated = vec![rule_for(suffix)];
+    updated.extend_from_slice(rules);
+    updated
+}
+
+/// `rules` without the copy of the bypass rule clashctl inserted, the topmost one;
+/// an identical rule of the user's further down stays
+pub fn without_bypass_rule(rules: &[String], suffix: &str) -> Vec<String> {
+    let rule = rule_for(suffix);
+    let mut updated = rules.to_vec();
+    if let Some(index) = updated
+        .iter()
+        .position(|existing| existing.replace(' ', "") == rule)
+    {
+        updated.remove(index);
+    }
+    updated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bypass_suffix_and_rules() {
+        assert_eq!(
+            suffix_for_host("cdn.static.example.com").as_deref(),
+            Some("example.com")
+        );
+        assert_eq!(
+            suffix_for_host("www.bbc.co.uk.").as_deref(),
+            Some("bbc.co.uk")
+        );
+        assert_eq!(suffix_for_host("localhost").as_deref(), Some("localhost"));
+        assert_eq!(suffix_for_host("10.0.0.1"), None);
+
+        let rules = vec!["MATCH,Proxy".to_string()];
+        assert!(!has_bypass_rule(&rules, "example.com"));
+        let added = with_bypass_rule(&rules, "example.com");
+        assert_eq!(
+            added,
+            vec!["DOMAIN-SUFFIX,example.com,DIRECT", "MATCH,Proxy"]
+        );
+        assert!(has_bypass_rule(&added, "example.com"));
+        assert_eq!(without_bypass_rule(&added, "example.com"), rules);
+    }
+
+    #[test]
+    fn test_user_rule_survives_bypass() {
+        let rules = vec![
+            "DOMAIN-SUFFIX,other.com,Proxy".to_string(),
+            "DOMAIN-SUFFIX, example.com, DIRECT".to_string(),
+            "MATCH,Proxy".to_string(),
+        ];
+        // The user's rule already does it, so no bypass is added or later removed
+        assert!(has_bypass_rule(&rules, "example.com"));
+
+        // A rule the user adds while a bypass is active outlives the bypass
+        let added = with_bypass_rule(&rules[..1], "example.com");
+        let mut edited = added.clone();
+        edited.extend_from_slice(&rules[1..]);
+        assert_eq!(without_bypass_rule(&edited, "example.com"), rules);
+    }
+}
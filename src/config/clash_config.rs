@@ -5,6 +5,8 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
+use super::clash_verge;
+
 /// Clash proxy provider configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClashProxyProvider {
@@ -29,6 +31,14 @@ pub struct HealthCheck {
 pub struct ClashConfig {
     #[serde(rename = "proxy-providers", default)]
     pub proxy_providers: HashMap<String, ClashProxyProvider>,
+    /// `host:port` the core serves its API on
+    #[serde(rename = "external-controller", default)]
+    pub external_controller: Option<String>,
+    #[serde(default)]
+    pub secret: Option<String>,
+    /// Unix socket the core serves its API on, which clashctl can't use
+    #[serde(rename = "external-controller-unix", default)]
+    pub external_controller_unix: Option<String>,
 }
 
 impl ClashConfig {
@@ -39,6 +49,22 @@ impl ClashConfig {
         Ok(config)
     }
 
+    /// API URL for `external-controller`; a wildcard or missing host means this machine
+    pub fn controller_url(&self) -> Option<String> {
+        let address = self.external_controller.as_deref()?.trim();
+        let address = address
+            .strip_prefix("http://")
+            .unwrap_or(address)
+            .trim_end_matches('/');
+        let (host, port) = address.rsplit_once(':')?;
+        let port: u16 = port.parse().ok()?;
+        let host = match host {
+            "" | "0.0.0.0" | "[::]" | "::" => "127.0.0.1",
+            host => host,
+        };
+        Some(format!("http://{}:{}", host, port))
+    }
+
     /// Try to find Clash config in common locations
     #[allow(dead_code)]
     pub fn find_config() -> Option<PathBuf> {
@@ -55,6 +81,10 @@ impl ClashConfig {
             return Some(path);
         }
 
+        if let Some(path) = clash_verge::list_from_env().and_then(clash_verge::runtime_config) {
+            return Some(path);
+        }
+
         if let Some(hint) = hint {
             if hint.is_file() {
                 return Some(hint.to_path_buf());
@@ -104,6 +134,12 @@ impl ClashConfig {
             }
         }
 
+        if let Some(path) =
+            clash_verge::list_in_default_dirs().and_then(clash_verge::runtime_config)
+        {
+            return Some(path);
+        }
+
         let mut scan_roots = Vec::new();
         if let Some(home) = dirs::home_dir() {
             scan_roots.push(home.join("Library/Application Support"));
@@ -134,6 +170,289 @@ impl ClashConfig {
     }
 }
 
+/// Health check of a url-test/fallback group in the Clash config
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupHealthCheck {
+    pub url: String,
+    /// Seconds between checks
+    pub interval: u32,
+}
+
+impl GroupHealthCheck {
+    /// Used for fields the group doesn't set
+    pub const DEFAULT_URL: &'static str = "https://www.gstatic.com/generate_204";
+    pub const DEFAULT_INTERVAL: u32 = 300;
+}
+
+/// Read the `url`/`interval` of a proxy group from config YAML
+pub fn read_group_health_check(bytes: &[u8], group: &str) -> Result<GroupHealthCheck> {
+    let mut value: serde_yaml::Value = serde_yaml::from_slice(bytes)?;
+    let entry = find_group(&mut value, group)?;
+    Ok(GroupHealthCheck {
+        url: entry
+            .get("url")
+            .and_then(|v| v.as_str())
+            .unwrap_or(GroupHealthCheck::DEFAULT_URL)
+            .to_string(),
+        interval: entry
+            .get("interval")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as u32)
+            .unwrap_or(GroupHealthCheck::DEFAULT_INTERVAL),
+    })
+}
+
+/// Set the `url`/`interval` of a proxy group, returning the new config YAML
+pub fn write_group_health_check(
+    bytes: &[u8],
+    group: &str,
+    check: &GroupHealthCheck,
+) -> Result<Vec<u8>> {
+    let mut value: serde_yaml::Value = serde_yaml::from_slice(bytes)?;
+    let entry = find_group(&mut value, group)?;
+    entry.insert("url".into(), check.url.clone().into());
+    entry.insert("interval".into(), check.interval.into());
+    Ok(serde_yaml::to_string(&value)?.into_bytes())
+}
+
+/// Write a node group as a `select` proxy group, replacing its members if it exists
+/// The group is also listed in `parent` (e.g. the primary selector) so it can be picked there
+pub fn write_select_group(
+    bytes: &[u8],
+    name: &str,
+    nodes: &[String],
+    parent: Option<&str>,
+) -> Result<Vec<u8>> {
+    let mut value: serde_yaml::Value = serde_yaml::from_slice(bytes)?;
+    let members: serde_yaml::Sequence = nodes.iter().map(|node| node.as_str().into()).collect();
+    match find_group(&mut value, name) {
+        Ok(entry) => {
+            entry.insert("type".into(), "select".into());
+            entry.insert("proxies".into(), members.into());
+        }
+        Err(_) => {
+            let mut entry = serde_yaml::Mapping::new();
+            entry.insert("name".into(), name.into());
+            entry.insert("type".into(), "select".into());
+            entry.insert("proxies".into(), members.into());
+            let root = value
+                .as_mapping_mut()
+                .ok_or_else(|| anyhow::anyhow!("Clash config is not a YAML mapping"))?;
+            let groups = root
+                .entry("proxy-groups".into())
+                .or_insert_with(|| serde_yaml::Sequence::new().into());
+            groups
+                .as_sequence_mut()
+                .ok_or_else(|| anyhow::anyhow!("proxy-groups is not a list"))?
+                .push(entry.into());
+        }
+    }
+
+    if let Some(parent) = parent.filter(|parent| *parent != name) {
+        if let Ok(entry) = find_group(&mut value, parent) {
+            let proxies = entry
+                .entry("proxies".into())
+                .or_insert_with(|| serde_yaml::Sequence::new().into());
+            if let Some(proxies) = proxies.as_sequence_mut() {
+                if !proxies.iter().any(|proxy| proxy.as_str() == Some(name)) {
+                    proxies.push(name.into());
+                }
+            }
+        }
+    }
+    Ok(serde_yaml::to_string(&value)?.into_bytes())
+}
+
+/// Remove a proxy group and drop it from the members of every other group
+pub fn remove_proxy_group(bytes: &[u8], name: &str) -> Result<Vec<u8>> {
+    let mut value: serde_yaml::Value = serde_yaml::from_slice(bytes)?;
+    find_group(&mut value, name)?;
+    if let Some(groups) = value
+        .get_mut("proxy-groups")
+        .and_then(|groups| groups.as_sequence_mut())
+    {
+        groups.retain(|entry| entry.get("name").and_then(|v| v.as_str()) != Some(name));
+        for entry in groups.iter_mut() {
+            if let Some(proxies) = entry
+                .get_mut("proxies")
+                .and_then(|proxies| proxies.as_sequence_mut())
+            {
+                proxies.retain(|proxy| proxy.as_str() != Some(name));
+            }
+        }
+    }
+    Ok(serde_yaml::to_string(&value)?.into_bytes())
+}
+
+/// Add `http` proxy providers as (name, url, path), refreshed every `interval` seconds
+/// They are appended to the `use` list of every group that already uses providers,
+/// or of the first `select` group when none does, so their nodes can be picked
+pub fn add_http_providers(
+    bytes: &[u8],
+    providers: &[(String, String, String)],
+    interval: u32,
+) -> Result<Vec<u8>> {
+    let mut value: serde_yaml::Value = serde_yaml::from_slice(bytes)?;
+    let root = value
+        .as_mapping_mut()
+        .ok_or_else(|| anyhow::anyhow!("Clash config is not a YAML mapping"))?;
+    let entries = root
+        .entry("proxy-providers".into())
+        .or_insert_with(|| serde_yaml::Mapping::new().into())
+        .as_mapping_mut()
+        .ok_or_else(|| anyhow::anyhow!("proxy-providers is not a mapping"))?;
+    for (name, url, path) in providers {
+        let mut health_check = serde_yaml::Mapping::new();
+        health_check.insert("enable".into(), true.into());
+        health_check.insert("url".into(), GroupHealthCheck::DEFAULT_URL.into());
+        health_check.insert("interval".into(), GroupHealthCheck::DEFAULT_INTERVAL.into());
+
+        let mut entry = serde_yaml::Mapping::new();
+        entry.insert("type".into(), "http".into());
+        entry.insert("url".into(), url.as_str().into());
+        entry.insert("path".into(), path.as_str().into());
+        entry.insert("interval".into(), interval.into());
+        entry.insert("health-check".into(), health_check.into());
+        entries.insert(name.as_str().into(), entry.into());
+    }
+
+    let Some(groups) = value
+        .get_mut("proxy-groups")
+        .and_then(|groups| groups.as_sequence_mut())
+    else {
+        return Ok(serde_yaml::to_string(&value)?.into_bytes());
+    };
+    let mut users: Vec<usize> = groups
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| entry.get("use").is_some())
+        .map(|(index, _)| index)
+        .collect();
+    if users.is_empty() {
+        users.extend(
+            groups
+                .iter()
+                .position(|entry| entry.get("type").and_then(|v| v.as_str()) == Some("select")),
+        );
+    }
+    for index in users {
+        let Some(entry) = groups[index].as_mapping_mut() else {
+            continue;
+        };
+        let used = entry
+            .entry("use".into())
+            .or_insert_with(|| serde_yaml::Sequence::new().into());
+        if let Some(used) = used.as_sequence_mut() {
+            for (name, _, _) in providers {
+                if !used.iter().any(|provider| provider.as_str() == Some(name)) {
+                    used.push(name.as_str().into());
+                }
+            }
+        }
+    }
+    Ok(serde_yaml::to_string(&value)?.into_bytes())
+}
+
+/// Add an `exclude-filter` regex to proxy providers as (name, filter)
+/// A provider that already has a filter keeps it, with the new one as an alternative
+pub fn add_provider_exclude_filters(bytes: &[u8], filters: &[(String, String)]) -> Result<Vec<u8>> {
+    let mut value: serde_yaml::Value = serde_yaml::from_slice(bytes)?;
+    for (name, filter) in filters {
+        let entry = value
+            .get_mut("proxy-providers")
+            .and_then(|providers| providers.get_mut(name.as_str()))
+            .and_then(|entry| entry.as_mapping_mut())
+            .ok_or_else(|| {
+                anyhow::anyhow!("Provider {} is not defined in proxy-providers", name)
+            })?;
+        let merged = match entry.get("exclude-filter").and_then(|v| v.as_str()) {
+            Some(existing) if existing.contains(filter.as_str()) => continue,
+            Some(existing) if !existing.is_empty() => format!("{}|{}", existing, filter),
+            _ => filter.clone(),
+        };
+        entry.insert("exclude-filter".into(), merged.into());
+    }
+    Ok(serde_yaml::to_string(&value)?.into_bytes())
+}
+
+/// The top-level `rules` of config YAML, in match order
+pub fn read_rules(bytes: &[u8]) -> Result<Vec<String>> {
+    let value: serde_yaml::Value = serde_yaml::from_slice(bytes)?;
+    let rules = value
+        .get("rules")
+        .and_then(|rules| rules.as_sequence())
+        .ok_or_else(|| anyhow::anyhow!("Clash config has no rules list"))?;
+    Ok(rules
+        .iter()
+        .filter_map(|rule| rule.as_str().map(str::to_string))
+        .collect())
+}
+
+/// Replace the top-level `rules`, returning the new config YAML
+pub fn write_rules(bytes: &[u8], rules: &[String]) -> Result<Vec<u8>> {
+    let mut value: serde_yaml::Value = serde_yaml::from_slice(bytes)?;
+    let root = value
+        .as_mapping_mut()
+        .ok_or_else(|| anyhow::anyhow!("Clash config is not a YAML mapping"))?;
+    let rules: serde_yaml::Sequence = rules.iter().map(|rule| rule.as_str().into()).collect();
+    root.insert("rules".into(), rules.into());
+    Ok(serde_yaml::to_string(&value)?.into_bytes())
+}
+
+/// `external-ui` directory and `external-ui-url` of config YAML
+pub fn read_external_ui(bytes: &[u8]) -> Result<(Option<String>, Option<String>)> {
+    let value: serde_yaml::Value = serde_yaml::from_slice(bytes)?;
+    let field = |key: &str| {
+        value
+            .get(key)
+            .and_then(|v| v.as_str())
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+    Ok((field("external-ui"), field("external-ui-url")))
+}
+
+/// Set `external-ui` and `external-ui-url`, returning the new config YAML
+pub fn write_external_ui(bytes: &[u8], dir: &str, url: &str) -> Result<Vec<u8>> {
+    let mut value: serde_yaml::Value = serde_yaml::from_slice(bytes)?;
+    let root = value
+        .as_mapping_mut()
+        .ok_or_else(|| anyhow::anyhow!("Clash config is not a YAML mapping"))?;
+    root.insert("external-ui".into(), dir.into());
+    root.insert("external-ui-url".into(), url.into());
+    Ok(serde_yaml::to_string(&value)?.into_bytes())
+}
+
+/// `rules` with a PROCESS-NAME rule sending `process` to `target` first,
+/// replacing any PROCESS-NAME rule already there for the same process
+pub fn with_process_rule(rules: &[String], process: &str, target: &str) -> Vec<String> {
+    let mut updated = vec![format!("PROCESS-NAME,{},{}", process, target)];
+    updated.extend(
+        rules
+            .iter()
+            .filter(|rule| {
+                let mut parts = rule.split(',').map(str::trim);
+                !(parts.next() == Some("PROCESS-NAME") && parts.next() == Some(process))
+            })
+            .cloned(),
+    );
+    updated
+}
+
+fn find_group<'a>(
+    value: &'a mut serde_yaml::Value,
+    group: &str,
+) -> Result<&'a mut serde_yaml::Mapping> {
+    value
+        .get_mut("proxy-groups")
+        .and_then(|groups| groups.as_sequence_mut())
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.as_mapping_mut())
+        .find(|entry| entry.get("name").and_then(|v| v.as_str()) == Some(group))
+        .ok_or_else(|| anyhow::anyhow!("Group {} is not defined in proxy-groups", group))
+}
+
 fn config_path_from_env(var: &str) -> Option<PathBuf> {
     let raw = std::env::var_os(var)?;
     let path = PathBuf::from(raw);
@@ -266,3 +585,167 @@ fn select_most_recent(candidates: Vec<PathBuf>) -> Option<PathBuf> {
 
     best.map(|(_, path)| path)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_health_check_round_trip() {
+        let yaml = b"proxy-groups:\n  - name: Auto\n    type: url-test\n    proxies: [a, b]\n  - name: Backup\n    type: fallback\n    url: http://cp.cloudflare.com\n    interval: 60\n";
+        let auto = read_group_health_check(yaml, "Auto").unwrap();
+        assert_eq!(auto.interval, GroupHealthCheck::DEFAULT_INTERVAL);
+        assert_eq!(
+            read_group_health_check(yaml, "Backup").unwrap().url,
+            "http://cp.cloudflare.com"
+        );
+
+        let check = GroupHealthCheck {
+            url: "https://example.com/204".to_string(),
+            interval: 120,
+        };
+        let written = write_group_health_check(yaml, "Auto", &check).unwrap();
+        assert_eq!(read_group_health_check(&written, "Auto").unwrap(), check);
+        // Other groups are left alone
+        assert_eq!(
+            read_group_health_check(&written, "Backup")
+                .unwrap()
+                .interval,
+            60
+        );
+        assert!(read_group_health_check(yaml, "Missing").is_err());
+    }
+
+    #[test]
+    fn test_write_and_remove_select_group() {
+        let yaml = b"proxy-groups:\n  - name: Proxy\n    type: select\n    proxies: [a, b]\n";
+        let nodes = vec!["a".to_string()];
+        let written = write_select_group(yaml, "Streaming", &nodes, Some("Proxy")).unwrap();
+        let value: serde_yaml::Value = serde_yaml::from_slice(&written).unwrap();
+        let groups = value["proxy-groups"].as_sequence().unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[1]["type"].as_str(), Some("select"));
+        assert_eq!(groups[0]["proxies"][2].as_str(), Some("Streaming"));
+
+        // Writing again replaces the members without listing the group twice
+        let nodes = vec!["a".to_string(), "b".to_string()];
+        let rewritten = write_select_group(&written, "Streaming", &nodes, Some("Proxy")).unwrap();
+        let value: serde_yaml::Value = serde_yaml::from_slice(&rewritten).unwrap();
+        assert_eq!(
+            value["proxy-groups"][1]["proxies"]
+                .as_sequence()
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(
+            value["proxy-groups"][0]["proxies"]
+                .as_sequence()
+                .unwrap()
+                .len(),
+            3
+        );
+
+        let providers = vec![(
+            "Imported".to_string(),
+            "https://example.com/sub".to_string(),
+            "./proxy_providers/Imported.yaml".to_string(),
+        )];
+        let with_providers = add_http_providers(&rewritten, &providers, 86400).unwrap();
+        let value: serde_yaml::Value = serde_yaml::from_slice(&with_providers).unwrap();
+        assert_eq!(
+            value["proxy-providers"]["Imported"]["type"].as_str(),
+            Some("http")
+        );
+        // No group used providers yet, so the first select group picks it up
+        assert_eq!(
+            value["proxy-groups"][0]["use"][0].as_str(),
+            Some("Imported")
+        );
+        let filters = vec![("Imported".to_string(), "^(?:HK 02)$".to_string())];
+        let filtered = add_provider_exclude_filters(&with_providers, &filters).unwrap();
+        let filtered = add_provider_exclude_filters(&filtered, &filters).unwrap();
+        let value: serde_yaml::Value = serde_yaml::from_slice(&filtered).unwrap();
+        assert_eq!(
+            value["proxy-providers"]["Imported"]["exclude-filter"].as_str(),
+            Some("^(?:HK 02)$")
+        );
+
+        let removed = remove_proxy_group(&rewritten, "Streaming").unwrap();
+        let value: serde_yaml::Value = serde_yaml::from_slice(&removed).unwrap();
+        assert_eq!(value["proxy-groups"].as_sequence().unwrap().len(), 1);
+        assert_eq!(
+            value["proxy-groups"][0]["proxies"]
+                .as_sequence()
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_external_ui_round_trip() {
+        let yaml = b"mixed-port: 7890\nexternal-ui: ./dashboard\n";
+        assert_eq!(
+            read_external_ui(yaml).unwrap(),
+            (Some("./dashboard".to_string()), None)
+        );
+        let written = write_external_ui(yaml, "./dashboard", "https://example.com/ui.zip").unwrap();
+        assert_eq!(
+            read_external_ui(&written).unwrap().1.as_deref(),
+            Some("https://example.com/ui.zip")
+        );
+    }
+
+    #[test]
+    fn test_controller_url() {
+        let config: ClashConfig =
+            serde_yaml::from_str("external-controller: 0.0.0.0:9097\nsecret: s3cret\n").unwrap();
+        assert_eq!(
+            config.controller_url().as_deref(),
+            Some("http://127.0.0.1:9097")
+        );
+        assert_eq!(config.secret.as_deref(), Some("s3cret"));
+        let url = |address: &str| {
+            ClashConfig {
+                external_controller: Some(address.to_string()),
+                ..config.clone()
+            }
+            .controller_url()
+        };
+        assert_eq!(url(":9090").as_deref(), Some("http://127.0.0.1:9090"));
+        assert_eq!(url("[::1]:9090").as_deref(), Some("http://[::1]:9090"));
+        assert_eq!(
+            url("192.168.1.2:9090/").as_deref(),
+            Some("http://192.168.1.2:9090")
+        );
+        assert_eq!(url("9090"), None);
+
+        let unix: ClashConfig =
+            serde_yaml::from_str("external-controller-unix: mihomo.sock\n").unwrap();
+        assert_eq!(unix.controller_url(), None);
+        assert_eq!(
+            unix.external_controller_unix.as_deref(),
+            Some("mihomo.sock")
+        );
+    }
+
+    #[test]
+    fn test_with_process_rule_replaces_previous() {
+        let rules: Vec<String> = [
+            "DOMAIN-SUFFIX,google.com,Proxy",
+            "PROCESS-NAME,Dropbox,Proxy",
+            "MATCH,Proxy",
+        ]
+        .map(String::from)
+        .to_vec();
+        assert_eq!(
+            with_process_rule(&rules, "Dropbox", "DIRECT"),
+            vec![
+                "PROCESS-NAME,Dropbox,DIRECT",
+                "DOMAIN-SUFFIX,google.com,Proxy",
+                "MATCH,Proxy"
+            ]
+        );
+    }
+}
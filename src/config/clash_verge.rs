@@ -0,0 +1,244 @@
+use anyhow::Result;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::atomic::{self, write_atomic};
+use super::profile_store::{NewProfile, ProfileList, ProfileStore, StoredProfile};
+
+/// Clash Verge Rev's data directory name, under the platform data dir
+const APP_DIR: &str = "io.github.clash-verge-rev.clash-verge-rev";
+
+/// Config Clash Verge generates from the current profile and runs the core with
+const RUNTIME_CONFIG: &str = "clash-verge.yaml";
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ClashVergeProfileList {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current: Option<String>,
+    #[serde(default)]
+    pub items: Vec<ClashVergeProfileItem>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_yaml::Value>,
+}
+
+/// Entries also include enhancement scripts and merge files, not only subscriptions
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ClashVergeProfileItem {
+    pub uid: String,
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub profile_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// File name under `profiles/`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Unix seconds, unlike Mihomo Party's milliseconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated: Option<i64>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_yaml::Value>,
+}
+
+impl ClashVergeProfileList {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+        Ok(serde_yaml::from_str(&content)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        write_atomic(path, serde_yaml::to_string(self)?)
+    }
+}
+
+/// Clash Verge Rev: `profiles.yaml` listing files in `profiles/`, run from `clash-verge.yaml`
+#[derive(Debug, Clone)]
+pub struct ClashVergeStore {
+    list_path: PathBuf,
+}
+
+impl ClashVergeStore {
+    pub fn new(list_path: PathBuf) -> Self {
+        Self { list_path }
+    }
+
+    fn root(&self) -> &Path {
+        self.list_path.parent().unwrap_or(Path::new("."))
+    }
+
+    /// Load, change and save the list while holding its lock
+    fn modify<T>(&self, change: impl FnOnce(&mut ClashVergeProfileList) -> T) -> Result<T> {
+        let _lock = atomic::lock(&self.list_path)?;
+        let mut list = ClashVergeProfileList::load(&self.list_path)?;
+        let result = change(&mut list);
+        list.save(&self.list_path)?;
+        Ok(result)
+    }
+
+    fn stored(&self, item: ClashVergeProfileItem) -> StoredProfile {
+        let file = item.file.unwrap_or_else(|| format!("{}.yaml", item.uid));
+        StoredProfile {
+            path: self.root().join("profiles").join(file),
+            name: item.name.unwrap_or_else(|| item.uid.clone()),
+            id: item.uid,
+            profile_type: item.profile_type.unwrap_or_default(),
+            url: item.url,
+            updated_ms: item.updated.map(|secs| secs * 1000),
+        }
+    }
+}
+
+impl ProfileStore for ClashVergeStore {
+    fn name(&self) -> &'static str {
+        "Clash Verge"
+    }
+
+    fn process_names(&self) -> &'static [&'static str] {
+        &["clash-verge", "Clash Verge"]
+    }
+
+    fn list_path(&self) -> &Path {
+        &self.list_path
+    }
+
+    fn load(&self) -> Result<ProfileList> {
+        let list = ClashVergeProfileList::load(&self.list_path)?;
+        Ok(ProfileList {
+            items: list
+                .items
+                .into_iter()
+                .map(|item| self.stored(item))
+                .collect(),
+            current: list.current,
+        })
+    }
+
+    fn work_config_path(&self) -> PathBuf {
+        self.root().join(RUNTIME_CONFIG)
+    }
+
+    fn set_updated(&self, id: &str, updated_ms: i64) -> Result<()> {
+        self.modify(|list| {
+            if let Some(item) = list.items.iter_mut().find(|item| item.uid == id) {
+                item.updated = Some(updated_ms / 1000);
+            }
+        })
+    }
+
+    fn set_current(&self, id: &str) -> Result<()> {
+        self.modify(|list| list.current = Some(id.to_string()))
+    }
+
+    fn add(&self, profiles: &[NewProfile]) -> Result<Vec<StoredProfile>> {
+        self.modify(|list| {
+            let base_id = Utc::now().timestamp_millis();
+            let mut added = Vec::new();
+            for (offset, profile) in profiles.iter().enumerate() {
+                // Clash Verge prefixes uids with R for remote and L for local profiles
+                let (prefix, profile_type) = if profile.url.is_some() {
+                    ("R", "remote")
+                } else {
+                    ("L", "local")
+                };
+                let uid = format!("{}{:x}", prefix, base_id + offset as i64);
+                let item = ClashVergeProfileItem {
+                    file: Some(format!("{}.yaml", uid)),
+                    uid,
+                    profile_type: Some(profile_type.to_string()),
+                    name: Some(profile.name.clone()),
+                    url: profile.url.clone(),
+                    updated: None,
+                    extra: HashMap::new(),
+                };
+                added.push(self.stored(item.clone()));
+                list.items.push(item);
+            }
+            added
+        })
+    }
+}
+
+/// `profiles.yaml` in the directory named by `CLASH_VERGE_DIR`
+pub fn list_from_env() -> Option<PathBuf> {
+    let dir = PathBuf::from(std::env::var_os("CLASH_VERGE_DIR")?);
+    list_in(&dir)
+}
+
+/// The profile list of the data directory `hint` is in: its runtime config, a profile or the directory
+pub fn list_from_hint(hint: &Path) -> Option<PathBuf> {
+    hint.ancestors().take(3).find_map(list_in)
+}
+
+/// Clash Verge Rev's data directory, then the older Clash Verge one on Linux
+pub fn list_in_default_dirs() -> Option<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(data) = dirs::data_dir() {
+        dirs.push(data.join(APP_DIR));
+    }
+    if let Some(home) = dirs::home_dir() {
+        dirs.push(home.join(".config/clash-verge"));
+    }
+    dirs.iter().find_map(|dir| list_in(dir))
+}
+
+/// The runtime config next to a profile list, once Clash Verge has generated it
+pub fn runtime_config(list_path: PathBuf) -> Option<PathBuf> {
+    Some(list_path.with_file_name(RUNTIME_CONFIG)).filter(|path| path.is_file())
+}
+
+/// `dir/profiles.yaml`, if `dir` looks like a Clash Verge data directory
+fn list_in(dir: &Path) -> Option<PathBuf> {
+    let list = dir.join("profiles.yaml");
+    (list.is_file() && dir.join("profiles").is_dir()).then_some(list)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verge_store_reads_and_writes_its_schema() {
+        let root = std::env::temp_dir().join(format!("clashctl-verge-test-{}", std::process::id()));
+        fs::create_dir_all(root.join("profiles")).unwrap();
+        fs::write(root.join(RUNTIME_CONFIG), "mixed-port: 7897").unwrap();
+        fs::write(
+            root.join("profiles.yaml"),
+            "current: RabC\nchain: []\nitems:\n\
+             - uid: Merge\n  type: merge\n  file: Merge.yaml\n\
+             - uid: RabC\n  type: remote\n  name: Airport\n  file: RabC.yaml\n  \
+             url: https://example.com/sub\n  updated: 1700000000\n  option:\n    with_proxy: false\n",
+        )
+        .unwrap();
+
+        let list_path = list_from_hint(&root.join(RUNTIME_CONFIG)).unwrap();
+        let store = ClashVergeStore::new(list_path.clone());
+        let list = store.load().unwrap();
+        assert_eq!(list.current.as_deref(), Some("RabC"));
+        assert_eq!(list.items[0].name, "Merge");
+        let airport = &list.items[1];
+        assert_eq!(airport.path, root.join("profiles/RabC.yaml"));
+        assert_eq!(airport.updated_ms, Some(1_700_000_000_000));
+
+        store.set_updated("RabC", 1_800_000_000_123).unwrap();
+        let added = store
+            .add(&[NewProfile {
+                name: "Backup".to_string(),
+                url: Some("https://example.com/b".to_string()),
+            }])
+            .unwrap();
+        assert!(added[0].id.starts_with('R'));
+
+        // Seconds on disk, and fields clashctl doesn't know are kept
+        let saved = fs::read_to_string(&list_path).unwrap();
+        assert!(saved.contains("updated: 1800000000\n"));
+        assert!(saved.contains("with_proxy: false"));
+        assert!(saved.contains("chain: []"));
+        assert_eq!(store.load().unwrap().items[2].path, added[0].path);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,250 @@
+use serde_yaml::{Mapping, Value};
+use std::collections::HashMap;
+
+/// Proxies of one subscription: a GUI profile, a proxy provider or the config itself
+#[derive(Debug, Clone)]
+pub struct ProxySource {
+    pub name: String,
+    /// Proxy provider the proxies come from, which can take an `exclude-filter`
+    pub provider: Option<String>,
+    pub proxies: Vec<Mapping>,
+}
+
+/// A proxy and the source it was found in
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProxyRef {
+    pub source: String,
+    pub name: String,
+}
+
+/// Proxies sharing one endpoint: the first one found is kept, the others are duplicates
+#[derive(Debug, Clone)]
+pub struct DuplicateSet {
+    /// `server:port`
+    pub endpoint: String,
+    pub kept: ProxyRef,
+    pub dropped: Vec<ProxyRef>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DedupReport {
+    pub sources: usize,
+    pub total: usize,
+    pub sets: Vec<DuplicateSet>,
+    /// Every proxy once, in the order found
+    unique: Vec<Mapping>,
+    /// Provider of each source that has one
+    providers: HashMap<String, String>,
+}
+
+/// Find proxies with the same server, port and credential (uuid or password) across sources
+/// Names are ignored: airports rename the same node per subscription
+pub fn analyze(sources: &[ProxySource]) -> DedupReport {
+    let mut report = DedupReport {
+        sources: sources.len(),
+        ..Default::default()
+    };
+    // Endpoint key → index into `sets`, or None while only one proxy has it
+    let mut seen: HashMap<(String, String, String), (ProxyRef, Option<usize>)> = HashMap::new();
+
+    for source in sources {
+        if let Some(provider) = &source.provider {
+            report
+                .providers
+                .insert(source.name.clone(), provider.clone());
+        }
+        for proxy in &source.proxies {
+            let Some(name) = scalar(proxy, "name") else {
+                continue;
+            };
+            report.total += 1;
+            let current = ProxyRef {
+                source: source.name.clone(),
+                name,
+            };
+            let Some(key) = endpoint_key(proxy) else {
+                report.unique.push(proxy.clone());
+                continue;
+            };
+            match seen.get_mut(&key) {
+                None => {
+                    seen.insert(key, (current, None));
+                    report.unique.push(proxy.clone());
+                }
+                Some((kept, set)) => {
+                    let index = *set.get_or_insert_with(|| {
+                        report.sets.push(DuplicateSet {
+                            endpoint: format!("{}:{}", key.0, key.1),
+                            kept: kept.clone(),
+                            dropped: Vec::new(),
+                        });
+                        report.sets.len() - 1
+                    });
+                    report.sets[index].dropped.push(current);
+                }
+            }
+        }
+    }
+    report
+}
+
+impl DedupReport {
+    /// Proxies that would be removed
+    pub fn duplicate_count(&self) -> usize {
+        self.sets.iter().map(|set| set.dropped.len()).sum()
+    }
+
+    /// For each proxy provider holding duplicates, an `exclude-filter` regex matching their names
+    /// A duplicate named like the proxy kept from the same provider can't be told apart and is left in
+    pub fn exclusion_filters(&self) -> Vec<(String, String)> {
+        let mut names: Vec<(String, Vec<String>)> = Vec::new();
+        for set in &self.sets {
+            for dropped in &set.dropped {
+                let Some(provider) = self.providers.get(&dropped.source) else {
+                    continue;
+                };
+                if dropped.source == set.kept.source && dropped.name == set.kept.name {
+                    continue;
+                }
+                match names.iter_mut().find(|(name, _)| name == provider) {
+                    Some((_, list)) => list.push(dropped.name.clone()),
+                    None => names.push((provider.clone(), vec![dropped.name.clone()])),
+                }
+            }
+        }
+        names
+            .into_iter()
+            .map(|(provider, list)| (provider, exclusion_filter(&list)))
+            .collect()
+    }
+
+    /// Duplicates only removable by merging, because their source isn't a proxy provider
+    pub fn unfilterable_count(&self) -> usize {
+        self.sets
+            .iter()
+            .flat_map(|set| &set.dropped)
+            .filter(|dropped| !self.providers.contains_key(&dropped.source))
+            .count()
+    }
+
+    /// Every proxy once; names taken by an earlier proxy get a ` (2)`, ` (3)`... suffix
+    pub fn merged_proxies(&self) -> Vec<(String, Mapping)> {
+        let mut taken: HashMap<String, usize> = HashMap::new();
+        self.unique
+            .iter()
+            .map(|proxy| {
+                let base = scalar(proxy, "name").unwrap_or_default();
+                let count = taken.entry(base.clone()).or_insert(0);
+                *count += 1;
+                let name = if *count == 1 {
+                    base
+                } else {
+                    format!("{} ({})", base, count)
+                };
+                let mut proxy = proxy.clone();
+                proxy.insert("name".into(), Value::String(name.clone()));
+                (name, proxy)
+            })
+            .collect()
+    }
+}
+
+/// Proxies listed under `proxies:` in a config or provider file
+pub fn proxies_in(bytes: &[u8]) -> Option<Vec<Mapping>> {
+    let value: Value = serde_yaml::from_slice(bytes).ok()?;
+    let proxies = value.get("proxies")?.as_sequence()?;
+    Some(
+        proxies
+            .iter()
+            .filter_map(Value::as_mapping)
+            .cloned()
+            .collect(),
+    )
+}
+
+/// A regex (as used by `exclude-filter`) matching exactly these names
+pub fn exclusion_filter(names: &[String]) -> String {
+    let escaped: Vec<String> = names.iter().map(|name| escape_regex(name)).collect();
+    format!("^(?:{})$", escaped.join("|"))
+}
+
+fn escape_regex(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "\\.+*?()|[]{}^$".contains(c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// (server, port, credential); the credential tells apart users sharing one server
+fn endpoint_key(proxy: &Mapping) -> Option<(String, String, String)> {
+    let server = scalar(proxy, "server")?.to_lowercase();
+    let port = scalar(proxy, "port")?;
+    let credential = ["uuid", "password", "auth-str", "psk"]
+        .iter()
+        .find_map(|key| scalar(proxy, key))
+        .unwrap_or_default();
+    Some((server, port, credential))
+}
+
+fn scalar(map: &Mapping, key: &str) -> Option<String> {
+    match map.get(Value::String(key.to_string()))? {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(name: &str, provider: bool, yaml: &str) -> ProxySource {
+        ProxySource {
+            name: name.to_string(),
+            provider: provider.then(|| name.to_string()),
+            proxies: proxies_in(yaml.as_bytes()).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_analyze_duplicates() {
+        let sources = [
+            source(
+                "A",
+                true,
+                "proxies:\n\
+                 - {name: HK 01, type: vmess, server: hk.example.com, port: 443, uuid: u1}\n\
+                 - {name: JP 01, type: ss, server: jp.example.com, port: 8388, password: p}\n",
+            ),
+            source(
+                "B",
+                true,
+                "proxies:\n\
+                 - {name: 香港 01 (x2), type: vmess, server: HK.example.com, port: 443, uuid: u1}\n\
+                 - {name: HK 01, type: vmess, server: hk.example.com, port: 443, uuid: u2}\n",
+            ),
+        ];
+        let report = analyze(&sources);
+        assert_eq!(report.total, 4);
+        assert_eq!(report.duplicate_count(), 1);
+        assert_eq!(report.sets[0].endpoint, "hk.example.com:443");
+        assert_eq!(report.sets[0].kept.name, "HK 01");
+        assert_eq!(report.sets[0].dropped[0].name, "香港 01 (x2)");
+
+        assert_eq!(
+            report.exclusion_filters(),
+            [("B".to_string(), "^(?:香港 01 \\(x2\\))$".to_string())]
+        );
+        // A different uuid is another user, kept but renamed
+        let names: Vec<String> = report
+            .merged_proxies()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(names, ["HK 01", "JP 01", "HK 01 (2)"]);
+    }
+}
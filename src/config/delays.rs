@@ -0,0 +1,353 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Local, TimeZone, Timelike};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use super::atomic::write_atomic;
+use crate::clash::Proxy;
+
+/// Samples older than this are dropped when the store is opened
+pub const RETENTION_DAYS: i64 = 7;
+
+/// Columns of the heatmap: hours, either the last 24 or the 24 hours of the day
+pub const HEATMAP_COLUMNS: usize = 24;
+
+/// One delay test of a node, taken from the core's delay history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DelaySample {
+    /// Unix timestamp of the test
+    pub time: i64,
+    pub node: String,
+    /// Milliseconds, 0 when the test timed out
+    pub delay: u32,
+}
+
+/// Latest successful delay test of a node, kept across restarts
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CachedDelay {
+    pub delay: u32,
+    /// Unix timestamp of the test
+    pub tested_at: i64,
+}
+
+impl CachedDelay {
+    /// Whether the test is older than `ttl_minutes` (0 keeps results fresh forever)
+    pub fn is_stale(&self, now: i64, ttl_minutes: u64) -> bool {
+        ttl_minutes > 0 && now - self.tested_at > ttl_minutes as i64 * 60
+    }
+}
+
+/// Get the delay history file path
+pub fn default_path() -> Result<PathBuf> {
+    Ok(super::paths::cache_dir()?.join("delays.jsonl"))
+}
+
+/// Append samples to the delay history file
+pub fn append(path: &Path, samples: &[DelaySample]) -> Result<()> {
+    if samples.is_empty() {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    for sample in samples {
+        writeln!(file, "{}", serde_json::to_string(sample)?)?;
+    }
+    Ok(())
+}
+
+/// Get the delay cache file path
+pub fn cache_path() -> Result<PathBuf> {
+    Ok(super::paths::cache_dir()?.join("delay_cache.json"))
+}
+
+/// Load the delay cache saved on exit, dropping results older than `RETENTION_DAYS`
+pub fn load_cache(path: &Path, now: i64) -> Result<HashMap<String, CachedDelay>> {
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+    let mut cache: HashMap<String, CachedDelay> = serde_json::from_str(&fs::read_to_string(path)?)?;
+    let cutoff = now - RETENTION_DAYS * 86400;
+    cache.retain(|_, cached| cached.tested_at >= cutoff);
+    Ok(cache)
+}
+
+/// Save the delay cache for the next session
+pub fn save_cache(path: &Path, cache: &HashMap<String, CachedDelay>) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    write_atomic(path, serde_json::to_string(cache)?)?;
+    Ok(())
+}
+
+/// Load all samples from the delay history file, skipping malformed lines
+pub fn load(path: &Path) -> Result<Vec<DelaySample>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(path)?;
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Drop samples older than `RETENTION_DAYS`, rewriting the file only when some were dropped
+pub fn prune(path: &Path, now: i64) -> Result<Vec<DelaySample>> {
+    let samples = load(path)?;
+    let cutoff = now - RETENTION_DAYS * 86400;
+    let kept: Vec<DelaySample> = samples
+        .iter()
+        .filter(|sample| sample.time >= cutoff)
+        .cloned()
+        .collect();
+    if kept.len() < samples.len() {
+        let mut content = String::new();
+        for sample in &kept {
+            content.push_str(&serde_json::to_string(sample)?);
+            content.push('\n');
+        }
+        write_atomic(path, content)?;
+    }
+    Ok(kept)
+}
+
+/// Picks the entries of the core's delay history that weren't recorded yet
+#[derive(Debug, Default)]
+pub struct DelayRecorder {
+    /// Time of the latest recorded sample per node
+    last_seen: HashMap<String, i64>,
+}
+
+impl DelayRecorder {
+    /// Continue after the samples already in the store
+    pub fn from_samples(samples: &[DelaySample]) -> Self {
+        let mut last_seen: HashMap<String, i64> = HashMap::new();
+        for sample in samples {
+            let seen = last_seen.entry(sample.node.clone()).or_insert(sample.time);
+            *seen = (*seen).max(sample.time);
+        }
+        Self { last_seen }
+    }
+
+    /// New history entries of every node; groups are skipped, their delay is their node's
+    pub fn sample(&mut self, proxies: &HashMap<String, Proxy>) -> Vec<DelaySample> {
+        let mut samples = Vec::new();
+        for proxy in proxies.values().filter(|proxy| proxy.all.is_none()) {
+            let Some(history) = &proxy.history else {
+                continue;
+            };
+            let last = self.last_seen.get(&proxy.name).copied().unwrap_or(i64::MIN);
+            let mut newest = last;
+            for entry in history {
+                let Ok(time) = DateTime::parse_from_rfc3339(&entry.time) else {
+                    continue;
+                };
+                let time = time.timestamp();
+                if time > last {
+                    samples.push(DelaySample {
+                        time,
+                        node: proxy.name.clone(),
+                        delay: entry.delay,
+                    });
+                    newest = newest.max(time);
+                }
+            }
+            if newest > last {
+                self.last_seen.insert(proxy.name.clone(), newest);
+            }
+        }
+        samples.sort_by(|a, b| a.time.cmp(&b.time).then_with(|| a.node.cmp(&b.node)));
+        samples
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum HeatmapView {
+    /// One column per hour of the last 24 hours
+    #[default]
+    Recent,
+    /// One column per hour of the day, over the whole retention window
+    HourOfDay,
+}
+
+impl HeatmapView {
+    pub fn next(self) -> Self {
+        match self {
+            HeatmapView::Recent => HeatmapView::HourOfDay,
+            HeatmapView::HourOfDay => HeatmapView::Recent,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            HeatmapView::Recent => "last 24 hours",
+            HeatmapView::HourOfDay => "hour of day, last 7 days",
+        }
+    }
+}
+
+/// Tests of one node falling in one column
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct HeatmapCell {
+    pub tests: u32,
+    pub timeouts: u32,
+    /// Sum of the successful delays
+    total_delay: u64,
+}
+
+impl HeatmapCell {
+    /// Mean of the successful tests
+    pub fn mean_delay(&self) -> Option<u32> {
+        let ok = self.tests - self.timeouts;
+        (ok > 0).then(|| (self.total_delay / ok as u64) as u32)
+    }
+
+    fn add(&mut self, delay: u32) {
+        self.tests += 1;
+        if delay == 0 {
+            self.timeouts += 1;
+        } else {
+            self.total_delay += delay as u64;
+        }
+    }
+}
+
+/// Delay tests per node (rows, sorted by name) and hour (columns)
+#[derive(Debug, Clone, Default)]
+pub struct Heatmap {
+    /// Local hour each column starts at
+    pub hours: Vec<u32>,
+    pub rows: Vec<(String, Vec<HeatmapCell>)>,
+}
+
+impl Heatmap {
+    pub fn build(samples: &[DelaySample], view: HeatmapView, now: DateTime<Local>) -> Self {
+        let current_hour = now
+            .with_minute(0)
+            .and_then(|time| time.with_second(0))
+            .and_then(|time| time.with_nanosecond(0))
+            .unwrap_or(now);
+        let first_hour = current_hour - Duration::hours(HEATMAP_COLUMNS as i64 - 1);
+        let hours = match view {
+            HeatmapView::Recent => (0..HEATMAP_COLUMNS as i64)
+                .map(|offset| (first_hour + Duration::hours(offset)).hour())
+                .collect(),
+            HeatmapView::HourOfDay => (0..HEATMAP_COLUMNS as u32).collect(),
+        };
+
+        let mut rows: BTreeMap<&str, Vec<HeatmapCell>> = BTreeMap::new();
+        for sample in samples {
+            let Some(time) = Local.timestamp_opt(sample.time, 0).single() else {
+                continue;
+            };
+            let column = match view {
+                HeatmapView::Recent => {
+                    let offset = (time - first_hour).num_hours();
+                    if time < first_hour || offset >= HEATMAP_COLUMNS as i64 {
+                        continue;
+                    }
+                    offset as usize
+                }
+                HeatmapView::HourOfDay => time.hour() as usize,
+            };
+            rows.entry(sample.node.as_str())
+                .or_insert_with(|| vec![HeatmapCell::default(); HEATMAP_COLUMNS])[column]
+                .add(sample.delay);
+        }
+
+        Self {
+            hours,
+            rows: rows
+                .into_iter()
+                .map(|(node, cells)| (node.to_string(), cells))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heatmap_buckets() {
+        let now = Local.with_ymd_and_hms(2024, 5, 1, 12, 30, 0).unwrap();
+        let at = |hours_ago: i64| (now - Duration::hours(hours_ago)).timestamp();
+        let samples = vec![
+            DelaySample {
+                time: at(0),
+                node: "JP 01".to_string(),
+                delay: 100,
+            },
+            DelaySample {
+                time: at(0),
+                node: "JP 01".to_string(),
+                delay: 300,
+            },
+            DelaySample {
+                time: at(1),
+                node: "HK 01".to_string(),
+                delay: 0,
+            },
+            // Older than the recent view, still in its hour of day
+            DelaySample {
+                time: at(48),
+                node: "HK 01".to_string(),
+                delay: 80,
+            },
+        ];
+
+        let recent = Heatmap::build(&samples, HeatmapView::Recent, now);
+        assert_eq!(recent.hours[HEATMAP_COLUMNS - 1], 12);
+        assert_eq!(recent.rows[0].0, "HK 01");
+        let hk = &recent.rows[0].1[HEATMAP_COLUMNS - 2];
+        assert_eq!((hk.tests, hk.timeouts, hk.mean_delay()), (1, 1, None));
+        assert_eq!(
+            recent.rows[1].1[HEATMAP_COLUMNS - 1].mean_delay(),
+            Some(200)
+        );
+
+        let by_hour = Heatmap::build(&samples, HeatmapView::HourOfDay, now);
+        assert_eq!(by_hour.rows[0].1[12].mean_delay(), Some(80));
+    }
+
+    #[test]
+    fn test_delay_cache_round_trip_and_staleness() {
+        let path = std::env::temp_dir().join(format!(
+            "clashctl-delay-cache-test-{}.json",
+            std::process::id()
+        ));
+        let now = 1_700_000_000;
+        let cache = HashMap::from([
+            (
+                "JP 01".to_string(),
+                CachedDelay {
+                    delay: 120,
+                    tested_at: now - 15 * 60,
+                },
+            ),
+            (
+                "US 01".to_string(),
+                CachedDelay {
+                    delay: 300,
+                    tested_at: now - (RETENTION_DAYS + 1) * 86400,
+                },
+            ),
+        ]);
+        save_cache(&path, &cache).unwrap();
+        let loaded = load_cache(&path, now).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.len(), 1);
+        let jp = loaded["JP 01"];
+        assert!(jp.is_stale(now, 10));
+        assert!(!jp.is_stale(now, 30));
+        assert!(!jp.is_stale(now, 0));
+    }
+}
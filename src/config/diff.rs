@@ -0,0 +1,233 @@
+use serde_yaml::{Mapping, Value};
+use std::collections::{HashMap, HashSet};
+
+/// Summary of what changes between two Clash configs
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigDiff {
+    pub added_nodes: Vec<String>,
+    pub removed_nodes: Vec<String>,
+    /// Nodes with the same type/server/port under a new name, as (old, new)
+    pub renamed_nodes: Vec<(String, String)>,
+    pub added_groups: Vec<String>,
+    pub removed_groups: Vec<String>,
+    /// Groups whose type or members changed
+    pub changed_groups: Vec<String>,
+    pub rules_before: usize,
+    pub rules_after: usize,
+}
+
+impl ConfigDiff {
+    /// Compare two configs; unparsable input counts as an empty config
+    pub fn between(old: &[u8], new: &[u8]) -> Self {
+        let old = Summary::parse(old);
+        let new = Summary::parse(new);
+
+        let mut added: Vec<&Node> = new
+            .nodes
+            .iter()
+            .filter(|node| !old.has_node(&node.name))
+            .collect();
+        let mut removed: Vec<&Node> = old
+            .nodes
+            .iter()
+            .filter(|node| !new.has_node(&node.name))
+            .collect();
+
+        // A removed node whose endpoint reappears under another name was renamed
+        let mut renamed_nodes = Vec::new();
+        removed.retain(|old_node| {
+            match added
+                .iter()
+                .position(|new_node| new_node.endpoint == old_node.endpoint)
+            {
+                Some(index) => {
+                    let new_node = added.remove(index);
+                    renamed_nodes.push((old_node.name.clone(), new_node.name.clone()));
+                    false
+                }
+                None => true,
+            }
+        });
+
+        let old_groups: HashMap<&str, &Group> =
+            old.groups.iter().map(|g| (g.name.as_str(), g)).collect();
+        let new_groups: HashMap<&str, &Group> =
+            new.groups.iter().map(|g| (g.name.as_str(), g)).collect();
+
+        Self {
+            added_nodes: added.into_iter().map(|node| node.name.clone()).collect(),
+            removed_nodes: removed.into_iter().map(|node| node.name.clone()).collect(),
+            renamed_nodes,
+            added_groups: new
+                .groups
+                .iter()
+                .filter(|g| !old_groups.contains_key(g.name.as_str()))
+                .map(|g| g.name.clone())
+                .collect(),
+            removed_groups: old
+                .groups
+                .iter()
+                .filter(|g| !new_groups.contains_key(g.name.as_str()))
+                .map(|g| g.name.clone())
+                .collect(),
+            changed_groups: new
+                .groups
+                .iter()
+                .filter(|g| {
+                    old_groups
+                        .get(g.name.as_str())
+                        .is_some_and(|old_group| *old_group != *g)
+                })
+                .map(|g| g.name.clone())
+                .collect(),
+            rules_before: old.rules,
+            rules_after: new.rules,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.renamed_nodes.is_empty()
+            && self.added_groups.is_empty()
+            && self.removed_groups.is_empty()
+            && self.changed_groups.is_empty()
+            && self.rules_before == self.rules_after
+    }
+
+    /// Change in rule count (positive when rules were added)
+    pub fn rule_delta(&self) -> i64 {
+        self.rules_after as i64 - self.rules_before as i64
+    }
+}
+
+struct Node {
+    name: String,
+    /// (type, server, port), used to detect renames
+    endpoint: (String, String, String),
+}
+
+#[derive(PartialEq)]
+struct Group {
+    name: String,
+    group_type: String,
+    members: Vec<String>,
+}
+
+#[derive(Default)]
+struct Summary {
+    nodes: Vec<Node>,
+    node_names: HashSet<String>,
+    groups: Vec<Group>,
+    rules: usize,
+}
+
+impl Summary {
+    fn parse(bytes: &[u8]) -> Self {
+        let value: Value = serde_yaml::from_slice(bytes).unwrap_or(Value::Null);
+        let Some(root) = value.as_mapping() else {
+            return Self::default();
+        };
+
+        let nodes: Vec<Node> = sequence(root, "proxies")
+            .filter_map(Value::as_mapping)
+            .filter_map(|proxy| {
+                Some(Node {
+                    name: scalar(proxy, "name")?,
+                    endpoint: (
+                        scalar(proxy, "type").unwrap_or_default(),
+                        scalar(proxy, "server").unwrap_or_default(),
+                        scalar(proxy, "port").unwrap_or_default(),
+                    ),
+                })
+            })
+            .collect();
+
+        let groups = sequence(root, "proxy-groups")
+            .filter_map(Value::as_mapping)
+            .filter_map(|group| {
+                let members = sequence(group, "proxies")
+                    .chain(sequence(group, "use"))
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect();
+                Some(Group {
+                    name: scalar(group, "name")?,
+                    group_type: scalar(group, "type").unwrap_or_default(),
+                    members,
+                })
+            })
+            .collect();
+
+        Self {
+            node_names: nodes.iter().map(|node| node.name.clone()).collect(),
+            nodes,
+            groups,
+            rules: sequence(root, "rules").count(),
+        }
+    }
+
+    fn has_node(&self, name: &str) -> bool {
+        self.node_names.contains(name)
+    }
+}
+
+fn sequence<'a>(map: &'a Mapping, key: &str) -> impl Iterator<Item = &'a Value> {
+    map.get(Value::String(key.to_string()))
+        .and_then(Value::as_sequence)
+        .into_iter()
+        .flatten()
+}
+
+fn scalar(map: &Mapping, key: &str) -> Option<String> {
+    match map.get(Value::String(key.to_string()))? {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_diff() {
+        let old = r#"
+proxies:
+  - {name: HK 01, type: ss, server: hk.example.com, port: 443}
+  - {name: JP 01, type: ss, server: jp.example.com, port: 443}
+  - {name: US 01, type: ss, server: us.example.com, port: 443}
+proxy-groups:
+  - {name: Proxy, type: select, proxies: [HK 01, JP 01, US 01]}
+  - {name: Old, type: select, proxies: [HK 01]}
+rules:
+  - MATCH,Proxy
+"#;
+        let new = r#"
+proxies:
+  - {name: 香港 01, type: ss, server: hk.example.com, port: 443}
+  - {name: JP 01, type: ss, server: jp.example.com, port: 443}
+  - {name: SG 01, type: ss, server: sg.example.com, port: 443}
+proxy-groups:
+  - {name: Proxy, type: select, proxies: [香港 01, JP 01, SG 01]}
+  - {name: Auto, type: url-test, proxies: [JP 01]}
+rules:
+  - DOMAIN,a.com,DIRECT
+  - MATCH,Proxy
+"#;
+        let diff = ConfigDiff::between(old.as_bytes(), new.as_bytes());
+        assert_eq!(diff.added_nodes, vec!["SG 01"]);
+        assert_eq!(diff.removed_nodes, vec!["US 01"]);
+        assert_eq!(
+            diff.renamed_nodes,
+            vec![("HK 01".to_string(), "香港 01".to_string())]
+        );
+        assert_eq!(diff.added_groups, vec!["Auto"]);
+        assert_eq!(diff.removed_groups, vec!["Old"]);
+        assert_eq!(diff.changed_groups, vec!["Proxy"]);
+        assert_eq!(diff.rule_delta(), 1);
+        assert!(ConfigDiff::between(old.as_bytes(), old.as_bytes()).is_empty());
+    }
+}
@@ -0,0 +1,183 @@
+use std::collections::HashSet;
+
+/// File format of an imported or exported whitelist/blacklist
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DomainListFormat {
+    /// One domain per line
+    Plain,
+    /// `0.0.0.0 example.com`
+    Hosts,
+    /// `||example.com^`
+    Adblock,
+}
+
+impl DomainListFormat {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Plain => Self::Hosts,
+            Self::Hosts => Self::Adblock,
+            Self::Adblock => Self::Plain,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Plain => "plain",
+            Self::Hosts => "hosts",
+            Self::Adblock => "adblock",
+        }
+    }
+}
+
+/// Domains read from a list file
+#[derive(Debug, Default, PartialEq)]
+pub struct ParsedDomains {
+    /// Lowercased, without duplicates, in file order
+    pub domains: Vec<String>,
+    /// Entries that are not a valid domain name
+    pub invalid: Vec<String>,
+}
+
+/// Read a domain list in any of the supported formats, which may be mixed
+/// Comments (`#`, `!`), adblock headers, exception rules and localhost entries are skipped
+pub fn parse(text: &str) -> ParsedDomains {
+    let mut parsed = ParsedDomains::default();
+    let mut seen = HashSet::new();
+    for line in text.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() || line.starts_with('!') || line.starts_with('[') {
+            continue;
+        }
+        for entry in line_entries(line) {
+            let domain = entry
+                .trim_start_matches("*.")
+                .trim_start_matches('.')
+                .trim_end_matches('.')
+                .to_lowercase();
+            if is_local(&domain) {
+                continue;
+            }
+            if !is_valid_domain(&domain) {
+                parsed.invalid.push(entry.to_string());
+            } else if seen.insert(domain.clone()) {
+                parsed.domains.push(domain);
+            }
+        }
+    }
+    parsed
+}
+
+/// Domains named on one non-comment line
+fn line_entries(line: &str) -> Vec<&str> {
+    // Exception rules allow a domain rather than listing it
+    if line.starts_with("@@") {
+        return Vec::new();
+    }
+    if let Some(rule) = line.strip_prefix("||") {
+        let end = rule.find(['^', '$', '/']).unwrap_or(rule.len());
+        return vec![&rule[..end]];
+    }
+    let mut fields = line.split_whitespace();
+    match fields.next() {
+        // Hosts line: an address followed by one or more names
+        Some(first) if first.parse::<std::net::IpAddr>().is_ok() => fields.collect(),
+        Some(first) => vec![first],
+        None => Vec::new(),
+    }
+}
+
+fn is_local(domain: &str) -> bool {
+    matches!(
+        domain,
+        "localhost" | "localhost.localdomain" | "local" | "broadcasthost" | "ip6-localhost"
+    )
+}
+
+/// Letters, digits and inner hyphens in dot-separated labels, with at least two labels
+pub fn is_valid_domain(domain: &str) -> bool {
+    if domain.len() > 253 || !domain.contains('.') {
+        return false;
+    }
+    domain.split('.').all(|label| {
+        !label.is_empty()
+            && label.len() <= 63
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    })
+}
+
+/// Add the domains `list` doesn't have yet, returning how many were added
+pub fn merge(list: &mut Vec<String>, domains: &[String]) -> usize {
+    let before = list.len();
+    for domain in domains {
+        if !list.contains(domain) {
+            list.push(domain.clone());
+        }
+    }
+    list.len() - before
+}
+
+/// Write `domains` in `format`, one per line
+pub fn render(domains: &[String], format: DomainListFormat) -> String {
+    let mut text = match format {
+        DomainListFormat::Adblock => String::from("[Adblock Plus 2.0]\n"),
+        DomainListFormat::Plain | DomainListFormat::Hosts => String::new(),
+    };
+    for domain in domains {
+        let line = match format {
+            DomainListFormat::Plain => domain.clone(),
+            DomainListFormat::Hosts => format!("0.0.0.0 {}", domain),
+            DomainListFormat::Adblock => format!("||{}^", domain),
+        };
+        text.push_str(&line);
+        text.push('\n');
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mixed_formats() {
+        let text = "\
+[Adblock Plus 2.0]
+! Title: test
+127.0.0.1 localhost
+0.0.0.0 ads.example.com tracker.example.com # trackers
+||Ads.Example.com^$third-party
+@@||allowed.example.com^
+*.cdn.example.org
+example.net
+not_a domain..com
+-bad-.com
+";
+        let parsed = parse(text);
+        assert_eq!(
+            parsed.domains,
+            vec![
+                "ads.example.com",
+                "tracker.example.com",
+                "cdn.example.org",
+                "example.net"
+            ]
+        );
+        assert_eq!(parsed.invalid, vec!["not_a", "-bad-.com"]);
+    }
+
+    #[test]
+    fn test_render_round_trips() {
+        let domains = vec!["a.example.com".to_string(), "b.example.org".to_string()];
+        for format in [
+            DomainListFormat::Plain,
+            DomainListFormat::Hosts,
+            DomainListFormat::Adblock,
+        ] {
+            assert_eq!(parse(&render(&domains, format)).domains, domains);
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+/// How subscriptions are downloaded: some hosts are blocked without the proxy,
+/// others through it
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DownloadPolicy {
+    /// The system proxy settings (`HTTPS_PROXY` and the like), else direct
+    #[default]
+    System,
+    /// Straight to the host, ignoring any proxy settings
+    Direct,
+    /// Through the core's mixed (or HTTP) port
+    Proxy,
+}
+
+impl DownloadPolicy {
+    pub const ALL: [DownloadPolicy; 3] = [
+        DownloadPolicy::System,
+        DownloadPolicy::Direct,
+        DownloadPolicy::Proxy,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DownloadPolicy::System => "system",
+            DownloadPolicy::Direct => "direct",
+            DownloadPolicy::Proxy => "via proxy",
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use anyhow::Result;
+use chrono::{Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use crate::clash::LogEntry;
+
+/// Most matches a history search returns, newest first
+pub const SEARCH_LIMIT: usize = 5000;
+
+/// A log line as stored, with the date its stream timestamp lacks
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredLine {
+    /// Unix timestamp the line was received
+    at: i64,
+    #[serde(flatten)]
+    entry: LogEntry,
+}
+
+/// Get the log history file path
+pub fn default_path() -> Result<PathBuf> {
+    Ok(super::paths::cache_dir()?.join("logs.jsonl"))
+}
+
+/// Log lines kept on disk, bounded in size: lines go to the current file until it
+/// holds half the budget, then it replaces the previous one and a new file starts
+#[derive(Debug)]
+pub struct LogHistory {
+    path: PathBuf,
+    max_bytes: u64,
+    file: Option<File>,
+    /// Size of the current file
+    written: u64,
+}
+
+impl LogHistory {
+    pub fn new(path: PathBuf, max_bytes: u64) -> Self {
+        let written = fs::metadata(&path).map_or(0, |meta| meta.len());
+        Self {
+            path,
+            max_bytes,
+            file: None,
+            written,
+        }
+    }
+
+    /// The file holding the lines before the current one
+    fn previous_path(&self) -> PathBuf {
+        self.path.with_extension("1.jsonl")
+    }
+
+    /// Store lines received at `now`, rotating the file once it holds half the budget
+    pub fn append(&mut self, entries: &[LogEntry], now: i64) -> Result<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        if self.written >= self.max_bytes / 2 {
+            self.file = None;
+            if self.path.exists() {
+                fs::rename(&self.path, self.previous_path())?;
+            }
+            self.written = 0;
+        }
+        let file = match self.file.as_mut() {
+            Some(file) => file,
+            None => {
+                if let Some(parent) = self.path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&self.path)?;
+                self.file.insert(file)
+            }
+        };
+        let mut content = String::new();
+        for entry in entries {
+            let line = StoredLine {
+                at: now,
+                entry: entry.clone(),
+            };
+            content.push_str(&serde_json::to_string(&line)?);
+            content.push('\n');
+        }
+        file.write_all(content.as_bytes())?;
+        self.written += content.len() as u64;
+        Ok(())
+    }
+
+    /// Stored lines passing `matches`, newest first and at most `SEARCH_LIMIT`,
+    /// with the date added to their timestamp
+    pub fn search(&self, matches: impl Fn(&LogEntry) -> bool) -> Result<Vec<LogEntry>> {
+        let mut found = Vec::new();
+        for path in [self.previous_path(), self.path.clone()] {
+            read_matching(&path, &matches, &mut found)?;
+        }
+        found.reverse();
+        found.truncate(SEARCH_LIMIT);
+        Ok(found)
+    }
+}
+
+/// Append the lines of `path` passing `matches` to `found`, skipping malformed ones
+fn read_matching(
+    path: &Path,
+    matches: &impl Fn(&LogEntry) -> bool,
+    found: &mut Vec<LogEntry>,
+) -> Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+    for line in BufReader::new(File::open(path)?).lines() {
+        let Ok(stored) = serde_json::from_str::<StoredLine>(&line?) else {
+            continue;
+        };
+        if !matches(&stored.entry) {
+            continue;
+        }
+        let mut entry = stored.entry;
+        if let Some(at) = Local.timestamp_opt(stored.at, 0).single() {
+            entry.timestamp = at.format("%m-%d %H:%M:%S").to_string();
+        }
+        found.push(entry);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_history_rotates_and_searches() {
+        let dir = std::env::temp_dir().join(format!("clashctl-logs-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let line = |message: &str| {
+            LogEntry::new(
+                "10:00:00".to_string(),
+                "INFO".to_string(),
+                message.to_string(),
+            )
+        };
+
+        // Each line is about 100 bytes, so the current file rotates after two
+        let mut history = LogHistory::new(dir.join("logs.jsonl"), 400);
+        for i in 0..7 {
+            history
+                .append(&[line(&format!("dial example-{}.com", i))], 1_700_000_000)
+                .unwrap();
+        }
+        let found = history
+            .search(|entry| entry.message.contains("example"))
+            .unwrap();
+        let messages: Vec<&str> = found.iter().map(|entry| entry.message.as_str()).collect();
+        // The oldest lines were dropped with the file they were in
+        assert_eq!(messages.first(), Some(&"dial example-6.com"));
+        assert!(!messages.contains(&"dial example-0.com"));
+        assert!(found[0].timestamp.contains(' '));
+
+        let found = history
+            .search(|entry| entry.message.ends_with("5.com"))
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,9 +1,13 @@
 use anyhow::Result;
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use super::atomic::{self, write_atomic};
+use super::profile_store::{NewProfile, ProfileList, ProfileStore, StoredProfile};
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct MihomoPartyProfileList {
     #[serde(default)]
@@ -37,88 +41,150 @@ impl MihomoPartyProfileList {
 
     pub fn save(&self, path: &Path) -> Result<()> {
         let content = serde_yaml::to_string(self)?;
-        fs::write(path, content)?;
-        Ok(())
+        write_atomic(path, content)
     }
 }
 
-pub fn find_profile_list_with_hint(hint: Option<&Path>) -> Option<PathBuf> {
-    if let Some(path) = profile_path_from_env("CLASH_PARTY_DIR") {
-        return Some(path);
+/// Mihomo Party: `profile.yaml` listing `profiles/<id>.yaml`, run from `work/config.yaml`
+#[derive(Debug, Clone)]
+pub struct MihomoPartyStore {
+    list_path: PathBuf,
+}
+
+impl MihomoPartyStore {
+    pub fn new(list_path: PathBuf) -> Self {
+        Self { list_path }
     }
 
-    if let Some(hint) = hint {
-        if let Some(found) = resolve_profile_list_from_hint(hint) {
-            return Some(found);
-        }
+    fn root(&self) -> &Path {
+        self.list_path.parent().unwrap_or(Path::new("."))
     }
 
-    let mut possible_paths = Vec::new();
-    if let Some(home) = dirs::home_dir() {
-        possible_paths.push(home.join("Library/Application Support/mihomo-party/profile.yaml"));
-        possible_paths
-            .push(home.join("Library/Application Support/Clash Verge/mihomo-party/profile.yaml"));
-        possible_paths.push(home.join(".config/mihomo-party/profile.yaml"));
-        possible_paths.push(home.join(".config/clash-verge/mihomo-party/profile.yaml"));
-        possible_paths.push(home.join("AppData/Roaming/mihomo-party/profile.yaml"));
-        possible_paths.push(home.join("AppData/Roaming/Clash Verge/mihomo-party/profile.yaml"));
+    fn profile_path(&self, id: &str) -> PathBuf {
+        self.root().join("profiles").join(format!("{id}.yaml"))
     }
 
-    for path in possible_paths {
-        if path.is_file() {
-            return Some(path);
-        }
+    /// Load, change and save the list while holding its lock
+    fn modify<T>(&self, change: impl FnOnce(&mut MihomoPartyProfileList) -> T) -> Result<T> {
+        let _lock = atomic::lock(&self.list_path)?;
+        let mut list = MihomoPartyProfileList::load(&self.list_path)?;
+        let result = change(&mut list);
+        list.save(&self.list_path)?;
+        Ok(result)
     }
+}
 
-    let mut scan_roots = Vec::new();
-    if let Some(home) = dirs::home_dir() {
-        scan_roots.push(home.join("Library/Application Support"));
-        scan_roots.push(home.join(".config"));
-        scan_roots.push(home.join("AppData/Roaming"));
+impl ProfileStore for MihomoPartyStore {
+    fn name(&self) -> &'static str {
+        "Mihomo Party"
     }
 
-    for root in scan_roots {
-        if root.is_dir() {
-            if let Some(found) = scan_for_profile_list(&root, 3) {
-                return Some(found);
-            }
-        }
+    fn process_names(&self) -> &'static [&'static str] {
+        &["mihomo-party", "Mihomo Party", "clash-party", "Clash Party"]
     }
 
-    None
-}
+    fn list_path(&self) -> &Path {
+        &self.list_path
+    }
 
-pub fn profile_path_from_list(list_path: &Path, id: &str) -> Option<PathBuf> {
-    let root = list_path.parent()?;
-    Some(root.join("profiles").join(format!("{id}.yaml")))
-}
+    fn load(&self) -> Result<ProfileList> {
+        let list = MihomoPartyProfileList::load(&self.list_path)?;
+        let items = list
+            .items
+            .into_iter()
+            .map(|item| StoredProfile {
+                path: self.profile_path(&item.id),
+                id: item.id,
+                name: item.name,
+                profile_type: item.profile_type,
+                url: item.url,
+                updated_ms: item.updated,
+            })
+            .collect();
+        Ok(ProfileList {
+            items,
+            current: list.current,
+        })
+    }
 
-pub fn update_profile_updated_at(list_path: &Path, id: &str, updated_at_ms: i64) -> Result<()> {
-    let mut list = MihomoPartyProfileList::load(list_path)?;
-    if let Some(item) = list.items.iter_mut().find(|item| item.id == id) {
-        item.updated = Some(updated_at_ms);
+    fn work_config_path(&self) -> PathBuf {
+        self.root().join("work").join("config.yaml")
+    }
+
+    fn set_updated(&self, id: &str, updated_ms: i64) -> Result<()> {
+        self.modify(|list| {
+            if let Some(item) = list.items.iter_mut().find(|item| item.id == id) {
+                item.updated = Some(updated_ms);
+            }
+        })
+    }
+
+    fn set_current(&self, id: &str) -> Result<()> {
+        self.modify(|list| list.current = Some(id.to_string()))
+    }
+
+    fn add(&self, profiles: &[NewProfile]) -> Result<Vec<StoredProfile>> {
+        self.modify(|list| {
+            let base_id = Utc::now().timestamp_millis();
+            let mut added = Vec::new();
+            for (offset, profile) in profiles.iter().enumerate() {
+                let id = format!("{:x}", base_id + offset as i64);
+                let profile_type = if profile.url.is_some() {
+                    "remote"
+                } else {
+                    "local"
+                };
+                list.items.push(MihomoPartyProfileItem {
+                    id: id.clone(),
+                    name: profile.name.clone(),
+                    profile_type: profile_type.to_string(),
+                    url: profile.url.clone(),
+                    updated: None,
+                    extra: HashMap::new(),
+                });
+                added.push(StoredProfile {
+                    path: self.profile_path(&id),
+                    id,
+                    name: profile.name.clone(),
+                    profile_type: profile_type.to_string(),
+                    url: profile.url.clone(),
+                    updated_ms: None,
+                });
+            }
+            added
+        })
     }
-    list.save(list_path)
 }
 
-pub fn set_current_profile(list_path: &Path, id: &str) -> Result<()> {
-    let mut list = MihomoPartyProfileList::load(list_path)?;
-    list.current = Some(id.to_string());
-    list.save(list_path)
+/// `profile.yaml` in, or under, the directory named by `CLASH_PARTY_DIR`
+pub fn list_from_env() -> Option<PathBuf> {
+    profile_path_from_env("CLASH_PARTY_DIR")
 }
 
-pub fn work_config_path_from_list(list_path: &Path) -> Option<PathBuf> {
-    let root = list_path.parent()?;
-    Some(root.join("work").join("config.yaml"))
+/// Mihomo Party's default data directories
+pub fn list_in_default_dirs() -> Option<PathBuf> {
+    let home = dirs::home_dir()?;
+    [
+        "Library/Application Support/mihomo-party/profile.yaml",
+        "Library/Application Support/Clash Verge/mihomo-party/profile.yaml",
+        ".config/mihomo-party/profile.yaml",
+        ".config/clash-verge/mihomo-party/profile.yaml",
+        "AppData/Roaming/mihomo-party/profile.yaml",
+        "AppData/Roaming/Clash Verge/mihomo-party/profile.yaml",
+    ]
+    .into_iter()
+    .map(|path| home.join(path))
+    .find(|path| path.is_file())
 }
 
-pub fn count_proxies_in_profile(path: &Path) -> Option<usize> {
-    let content = fs::read_to_string(path).ok()?;
-    let value: serde_yaml::Value = serde_yaml::from_str(&content).ok()?;
-    value
-        .get("proxies")
-        .and_then(|v| v.as_sequence())
-        .map(|seq| seq.len())
+/// Any `profile.yaml` a few levels under the usual app data roots
+pub fn scan_for_list() -> Option<PathBuf> {
+    let home = dirs::home_dir()?;
+    ["Library/Application Support", ".config", "AppData/Roaming"]
+        .into_iter()
+        .map(|root| home.join(root))
+        .filter(|root| root.is_dir())
+        .find_map(|root| scan_for_profile_list(&root, 3))
 }
 
 fn profile_path_from_env(var: &str) -> Option<PathBuf> {
@@ -139,7 +205,8 @@ fn profile_path_from_env(var: &str) -> Option<PathBuf> {
     None
 }
 
-fn resolve_profile_list_from_hint(hint: &Path) -> Option<PathBuf> {
+/// `profile.yaml` next to `hint` or in its `mihomo-party` ancestor
+pub fn list_from_hint(hint: &Path) -> Option<PathBuf> {
     if hint.is_file() {
         if let Some(parent) = hint.parent() {
             let candidate = parent.join("profile.yaml");
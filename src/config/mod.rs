@@ -1,8 +1,30 @@
 #![allow(dead_code)]
 
+pub mod atomic;
+pub mod backup;
+pub mod bypass;
 pub mod clash_config;
+pub mod clash_verge;
+pub mod dedup;
+pub mod delays;
+pub mod diff;
+pub mod domains;
+pub mod download;
+pub mod log_history;
 pub mod mihomo_party;
+pub mod node_filter;
+pub mod node_rename;
+pub mod paths;
 pub mod preset;
+pub mod profile_store;
+pub mod rule_order;
+pub mod rule_set;
+pub mod secret;
+pub mod share;
+pub mod snapshot;
+pub mod stats;
+pub mod template;
+pub mod validate;
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
@@ -10,9 +32,17 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
+use crate::app::hooks::HookEvent;
+use crate::app::notify::NotifyEvent;
+use crate::clash::HumanRoute;
+use crate::ui::i18n::Language;
 use crate::ui::theme::Theme;
+use atomic::{write_atomic, write_private};
 pub use clash_config::ClashConfig;
-pub use preset::Preset;
+use download::DownloadPolicy;
+use node_filter::NodeFilter;
+use node_rename::RenameRules;
+pub use preset::{CustomPreset, Preset, PresetCore};
 
 /// Node group definition
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +51,194 @@ pub struct NodeGroup {
     pub nodes: Vec<String>,
 }
 
+/// Local Clash core service settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ServiceConfig {
+    /// "systemd", "launchd" or "command" (defaults to the platform's init system)
+    #[serde(default)]
+    pub manager: Option<String>,
+
+    /// systemd unit or launchd label (defaults to "mihomo")
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// Shell commands used by the "command" manager
+    #[serde(default)]
+    pub start_command: Option<String>,
+    #[serde(default)]
+    pub stop_command: Option<String>,
+    #[serde(default)]
+    pub restart_command: Option<String>,
+
+    /// Path of the core binary replaced by `clashctl core upgrade`
+    #[serde(default)]
+    pub binary_path: Option<String>,
+}
+
+/// Which critical events raise a notification
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationConfig {
+    /// The current node fails a health check
+    pub node_down: bool,
+
+    /// A subscription expires within `expiry_days`
+    pub subscription_expiring: bool,
+
+    /// The Clash API stops answering
+    pub core_unreachable: bool,
+
+    /// Days before expiry a subscription is reported
+    pub expiry_days: u64,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            node_down: true,
+            subscription_expiring: true,
+            core_unreachable: true,
+            expiry_days: 7,
+        }
+    }
+}
+
+impl NotificationConfig {
+    pub fn is_enabled(&self, event: NotifyEvent) -> bool {
+        match event {
+            NotifyEvent::NodeDown => self.node_down,
+            NotifyEvent::SubscriptionExpiring => self.subscription_expiring,
+            NotifyEvent::CoreUnreachable => self.core_unreachable,
+            // Setting a threshold is the opt-in
+            NotifyEvent::ThresholdExceeded => true,
+        }
+    }
+
+    fn toggle(&mut self, event: NotifyEvent) -> bool {
+        let flag = match event {
+            NotifyEvent::NodeDown => &mut self.node_down,
+            NotifyEvent::SubscriptionExpiring => &mut self.subscription_expiring,
+            NotifyEvent::CoreUnreachable => &mut self.core_unreachable,
+            NotifyEvent::ThresholdExceeded => return true,
+        };
+        *flag = !*flag;
+        *flag
+    }
+}
+
+/// Regex rules applied to the Logs page
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogRulesConfig {
+    /// Lines matching a pattern are colored and/or shown at another level (first match wins)
+    pub highlight: Vec<LogHighlight>,
+
+    /// Lines matching any of these patterns are dropped
+    pub mute: Vec<String>,
+}
+
+/// One highlight rule: `pattern` → `color` and/or `level`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogHighlight {
+    pub pattern: String,
+
+    /// Color name (`magenta`, `light-blue`) or `#rrggbb`
+    pub color: Option<String>,
+
+    /// Level the line is shown and filtered at: `debug`, `info`, `warning` or `error`
+    pub level: Option<String>,
+}
+
+/// URLs nodes are delay-tested against
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DelayTestConfig {
+    /// Tried in order until one answers, so a URL blocked in one region doesn't fail every node
+    /// (gstatic and Cloudflare `generate_204` when empty)
+    pub urls: Vec<String>,
+
+    /// Start each test at the next URL instead of the first, spreading tests across them
+    pub rotate: bool,
+
+    /// URL tried first for nodes of a region (`HK`, `JP`, ...), as read from their names
+    pub regions: HashMap<String, String>,
+}
+
+/// Traffic thresholds that raise an alert, off when unset
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlertConfig {
+    /// Total download rate in MB/s
+    pub download_rate_mb: Option<u64>,
+
+    /// Number of active connections
+    pub connections: Option<usize>,
+}
+
+impl AlertConfig {
+    pub fn is_active(&self) -> bool {
+        self.download_rate_mb.is_some() || self.connections.is_some()
+    }
+
+    /// Whether a download rate (bytes/s) is over the threshold
+    pub fn download_exceeded(&self, rate: u64) -> bool {
+        self.download_rate_mb
+            .is_some_and(|limit| rate > limit * 1024 * 1024)
+    }
+
+    /// Whether a connection count is over the threshold
+    pub fn connections_exceeded(&self, count: usize) -> bool {
+        self.connections.is_some_and(|limit| count > limit)
+    }
+}
+
+/// Shell commands run when an event happens, with its details in `CLASHCTL_*` env vars
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HooksConfig {
+    /// A selector group switched node
+    pub node_switched: Option<String>,
+
+    /// The proxy mode changed
+    pub mode_changed: Option<String>,
+
+    /// A subscription was updated
+    pub subscription_updated: Option<String>,
+
+    /// The core stopped answering
+    pub core_unreachable: Option<String>,
+}
+
+impl HooksConfig {
+    /// Command configured for an event, if any
+    pub fn command(&self, event: HookEvent) -> Option<&str> {
+        let command = match event {
+            HookEvent::NodeSwitched => &self.node_switched,
+            HookEvent::ModeChanged => &self.mode_changed,
+            HookEvent::SubscriptionUpdated => &self.subscription_updated,
+            HookEvent::CoreUnreachable => &self.core_unreachable,
+        };
+        command
+            .as_deref()
+            .filter(|command| !command.trim().is_empty())
+    }
+}
+
+/// Download rate alert thresholds (MB/s) offered in Settings
+pub const DOWNLOAD_ALERT_CHOICES: [u64; 4] = [10, 50, 100, 200];
+
+/// Connection count alert thresholds offered in Settings
+pub const CONNECTION_ALERT_CHOICES: [usize; 4] = [200, 500, 1000, 2000];
+
+/// Next value in a cycle of `choices` starting from off (None) and wrapping back to it
+fn next_choice<T: Copy + PartialOrd>(current: Option<T>, choices: &[T]) -> Option<T> {
+    match current {
+        None => choices.first().copied(),
+        Some(current) => choices.iter().copied().find(|choice| *choice > current),
+    }
+}
+
 /// clashctl application configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
@@ -30,6 +248,23 @@ pub struct AppConfig {
     /// Optional secret for authentication
     pub secret: Option<String>,
 
+    /// Keep the secret in the system keychain; config.yaml only records a reference
+    #[serde(default)]
+    pub keychain: bool,
+
+    /// Why a keychain reference could not be resolved on load
+    #[serde(skip)]
+    pub secret_error: Option<String>,
+
+    /// Unresolved keychain reference, written back as-is on save
+    #[serde(skip)]
+    secret_reference: Option<String>,
+
+    /// The saved secret while one from `--secret` or the environment is in use,
+    /// written back in its place so that one isn't persisted
+    #[serde(skip)]
+    saved_secret: Option<Option<String>>,
+
     /// Default mode (Simple or Expert)
     pub default_mode: String,
 
@@ -48,44 +283,212 @@ pub struct AppConfig {
     #[serde(default)]
     pub favorite_nodes: Vec<String>,
 
+    /// Selector group favorites are applied to by default
+    #[serde(default)]
+    pub favorite_selector: Option<String>,
+
+    /// Main selector group shown on Home (auto-detected when unset)
+    #[serde(default)]
+    pub primary_group: Option<String>,
+
     /// Custom node groups
     #[serde(default)]
     pub node_groups: HashMap<String, Vec<String>>,
 
+    /// Node groups written to the Clash config as `select` proxy groups
+    #[serde(default)]
+    pub materialized_groups: Vec<String>,
+
     /// UI theme
     #[serde(default)]
     pub theme: String,
 
+    /// Accessibility mode: no colors, ASCII borders and text markers
+    #[serde(default)]
+    pub no_color: bool,
+
+    /// Low-bandwidth mode for slow remote terminals: slower refreshes, fewer redraws, no emoji
+    #[serde(default)]
+    pub low_bandwidth: bool,
+
+    /// UI language ("en" or "zh-CN")
+    #[serde(default)]
+    pub language: String,
+
     /// Cached Clash config path (for subscriptions)
     #[serde(default)]
     pub clash_config_path: Option<String>,
+
+    /// Custom display order of route groups
+    #[serde(default)]
+    pub route_order: Vec<String>,
+
+    /// Route groups hidden from the Routes page
+    #[serde(default)]
+    pub hidden_groups: Vec<String>,
+
+    /// User-defined presets, cycled through after the built-in ones
+    #[serde(default)]
+    pub custom_presets: Vec<CustomPreset>,
+
+    /// Clash mode and node applied when switching to a preset, per preset name
+    #[serde(default)]
+    pub presets: HashMap<String, PresetCore>,
+
+    /// Auto-update interval in minutes, per subscription name
+    #[serde(default)]
+    pub update_intervals: HashMap<String, u64>,
+
+    /// Include/exclude regexes on proxy names, per subscription name
+    #[serde(default)]
+    pub node_filters: HashMap<String, NodeFilter>,
+
+    /// How node names are rewritten when subscriptions are converted
+    #[serde(default)]
+    pub node_rename: RenameRules,
+
+    /// Subscriptions downloaded at once by a bulk update (3 when unset)
+    #[serde(default)]
+    pub update_concurrency: Option<usize>,
+
+    /// How subscriptions are downloaded unless `download_policies` says otherwise (system when unset)
+    #[serde(default)]
+    pub download_policy: Option<DownloadPolicy>,
+
+    /// Download policy per subscription name
+    #[serde(default)]
+    pub download_policies: HashMap<String, DownloadPolicy>,
+
+    /// Local Clash core service management
+    #[serde(default)]
+    pub service: ServiceConfig,
+
+    /// Run a delay test before switching nodes and keep the current one if it fails
+    #[serde(default)]
+    pub test_before_switch: bool,
+
+    /// Check a sample of a profile's nodes are reachable before switching to it (`S` does it once)
+    #[serde(default)]
+    pub preflight_before_switch: bool,
+
+    /// Slowest delay (ms) a node may have to be auto-selected (any reachable node when unset)
+    #[serde(default)]
+    pub auto_select_max_delay: Option<u32>,
+
+    /// Minutes after which a delay result is shown as stale (10 when unset, 0 never)
+    #[serde(default)]
+    pub delay_ttl_minutes: Option<u64>,
+
+    /// Seconds between refreshes of the Connections page (2 when unset)
+    #[serde(default)]
+    pub connections_refresh_secs: Option<u64>,
+
+    /// URLs delay tests use
+    #[serde(default)]
+    pub delay_test: DelayTestConfig,
+
+    /// Canary fetched through the proxy port to check proxying end to end (empty disables)
+    #[serde(default)]
+    pub probe_url: Option<String>,
+
+    /// Per-event notification toggles
+    #[serde(default)]
+    pub notifications: NotificationConfig,
+
+    /// Template raw subscriptions are converted onto (the work config when unset)
+    #[serde(default)]
+    pub profile_template: Option<String>,
+
+    /// Download rate / connection count alert thresholds
+    #[serde(default)]
+    pub alerts: AlertConfig,
+
+    /// Highlight, re-classify or mute log lines
+    #[serde(default)]
+    pub log_rules: LogRulesConfig,
+
+    /// Megabytes of log lines kept on disk so the Logs page can search past its buffer (0 disables)
+    #[serde(default)]
+    pub log_history_mb: u64,
+
+    /// Shell commands run on events (node switched, mode changed, ...)
+    #[serde(default)]
+    pub hooks: HooksConfig,
 }
 
+/// Minutes a delay result stays fresh when `delay_ttl_minutes` is unset
+pub const DEFAULT_DELAY_TTL_MINUTES: u64 = 10;
+
+/// Seconds between Connections page refreshes when `connections_refresh_secs` is unset
+pub const DEFAULT_CONNECTIONS_REFRESH_SECS: u64 = 2;
+
+/// Connections page refresh intervals (seconds) cycled on the page
+pub const CONNECTIONS_REFRESH_CHOICES: [u64; 4] = [1, 2, 5, 10];
+
+/// Canary for the proxy probe when `probe_url` is unset
+pub const DEFAULT_PROBE_URL: &str = "https://www.gstatic.com/generate_204";
+
+/// Subscriptions a bulk update downloads at once when `update_concurrency` is unset
+pub const DEFAULT_UPDATE_CONCURRENCY: usize = 3;
+
+/// Auto-update intervals (minutes) offered on the Update page
+pub const UPDATE_INTERVAL_CHOICES: [u64; 4] = [60, 360, 720, 1440];
+
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
             api_url: "http://127.0.0.1:9090".to_string(),
             secret: None,
+            keychain: false,
+            secret_error: None,
+            secret_reference: None,
+            saved_secret: None,
             default_mode: "simple".to_string(),
             current_preset: "default".to_string(),
             whitelist: Vec::new(),
             blacklist: Vec::new(),
             favorite_nodes: Vec::new(),
+            favorite_selector: None,
+            primary_group: None,
             node_groups: HashMap::new(),
+            materialized_groups: Vec::new(),
             theme: "dark".to_string(),
+            no_color: false,
+            low_bandwidth: false,
+            language: "en".to_string(),
             clash_config_path: None,
+            route_order: Vec::new(),
+            hidden_groups: Vec::new(),
+            custom_presets: Vec::new(),
+            presets: HashMap::new(),
+            update_intervals: HashMap::new(),
+            node_filters: HashMap::new(),
+            node_rename: RenameRules::default(),
+            update_concurrency: None,
+            download_policy: None,
+            download_policies: HashMap::new(),
+            service: ServiceConfig::default(),
+            test_before_switch: false,
+            preflight_before_switch: false,
+            auto_select_max_delay: None,
+            delay_ttl_minutes: None,
+            connections_refresh_secs: None,
+            delay_test: DelayTestConfig::default(),
+            probe_url: None,
+            notifications: NotificationConfig::default(),
+            profile_template: None,
+            alerts: AlertConfig::default(),
+            log_rules: LogRulesConfig::default(),
+            log_history_mb: 0,
+            hooks: HooksConfig::default(),
         }
     }
 }
 
 impl AppConfig {
-    /// Get the default config file path
+    /// Get the config file path, honouring `--config` and `CLASHCTL_CONFIG`
     pub fn default_path() -> Result<PathBuf> {
-        let config_dir =
-            dirs::config_dir().ok_or_else(|| anyhow::anyhow!("Could not find config directory"))?;
-
-        let clashctl_dir = config_dir.join("clashctl");
-        Ok(clashctl_dir.join("config.yaml"))
+        paths::config_file()
     }
 
     /// Load configuration from file
@@ -98,11 +501,60 @@ impl AppConfig {
         }
 
         let contents = fs::read_to_string(&path)?;
-        let config: AppConfig = serde_yaml::from_str(&contents)?;
+        let mut config: AppConfig = serde_yaml::from_str(&contents)?;
+        config.resolve_secret();
         Ok(config)
     }
 
+    /// Replace a keychain reference in `secret` with the stored secret
+    /// On failure the secret is unset and the reference kept for the next save
+    fn resolve_secret(&mut self) {
+        let Some(account) = self.secret.as_deref().and_then(secret::parse_reference) else {
+            return;
+        };
+        let account = account.to_string();
+        match secret::load(&account) {
+            Ok(value) => self.secret = Some(value),
+            Err(e) => {
+                self.secret_error = Some(format!("{:#}", e));
+                self.secret_reference = self.secret.take();
+            }
+        }
+    }
+
+    /// The config as written to disk: with the keychain on, the secret is moved
+    /// there and replaced by a reference, falling back to plaintext if that fails
+    fn stored(&self) -> AppConfig {
+        let mut stored = self.clone();
+        stored.secret = self.persisted_secret().cloned();
+        match &stored.secret {
+            None => stored.secret = self.secret_reference.clone(),
+            Some(value)
+                if self.keychain
+                    && secret::parse_reference(value).is_none()
+                    && secret::store(&self.api_url, value).is_ok() =>
+            {
+                stored.secret = Some(secret::reference(&self.api_url));
+            }
+            _ => {}
+        }
+        stored
+    }
+
+    fn to_yaml(&self) -> Result<String> {
+        Ok(serde_yaml::to_string(&self.stored())?)
+    }
+
+    /// The secret config.yaml keeps, which isn't the one in use when that came from the command line
+    fn persisted_secret(&self) -> Option<&String> {
+        match &self.saved_secret {
+            Some(saved) => saved.as_ref(),
+            None => self.secret.as_ref(),
+        }
+    }
+
     /// Save configuration to file
+    /// A file holding the secret in plaintext is only readable by its owner
     pub fn save(&self) -> Result<()> {
         let path = Self::default_path()?;
 
@@ -111,21 +563,52 @@ impl AppConfig {
             fs::create_dir_all(parent)?;
         }
 
-        let contents = serde_yaml::to_string(self)?;
-        fs::write(&path, contents)?;
+        let stored = self.stored();
+        let yaml = serde_yaml::to_string(&stored)?;
+        let plaintext_secret = stored
+            .secret
+            .as_deref()
+            .is_some_and(|value| secret::parse_reference(value).is_none());
+        if plaintext_secret {
+            write_private(&path, &yaml)?;
+        } else {
+            write_atomic(&path, yaml)?;
+        }
 
         Ok(())
     }
 
     /// Merge command line arguments into config
-    pub fn merge_cli(&mut self, api_url: Option<String>, secret: Option<String>) {
+    /// A secret given there (or by `CLASHCTL_SECRET`/`CLASH_SECRET`) is only saved with `persist_secret`
+    pub fn merge_cli(
+        &mut self,
+        api_url: Option<String>,
+        secret: Option<String>,
+        persist_secret: bool,
+    ) {
         if let Some(url) = api_url {
             self.api_url = url;
         }
 
-        if let Some(s) = secret {
-            self.secret = Some(s);
+        match secret {
+            Some(s) if persist_secret => self.set_secret(Some(s)),
+            Some(s) => self.use_secret_for_session(s),
+            None => {}
+        }
+    }
+
+    /// Use `secret` and save it with the config
+    pub fn set_secret(&mut self, secret: Option<String>) {
+        self.saved_secret = None;
+        self.secret = secret;
+    }
+
+    /// Use `secret` until clashctl exits, keeping the saved one in config.yaml
+    pub fn use_secret_for_session(&mut self, secret: String) {
+        if self.saved_secret.is_none() {
+            self.saved_secret = Some(self.secret.take());
         }
+        self.secret = Some(secret);
     }
 
     /// Update preset and save
@@ -134,6 +617,16 @@ impl AppConfig {
         self.save()
     }
 
+    /// The saved preset, Default if it no longer exists
+    pub fn get_preset(&self) -> Preset {
+        Preset::find(&self.current_preset, &self.custom_presets).unwrap_or_default()
+    }
+
+    /// Core settings of `preset`, if the config gives it any
+    pub fn preset_core(&self, preset: &Preset) -> Option<&PresetCore> {
+        self.presets.get(preset.as_str())
+    }
+
     /// Add domain to whitelist (always proxy)
     pub fn add_to_whitelist(&mut self, domain: String) -> Result<()> {
         if !self.whitelist.contains(&domain) {
@@ -173,6 +666,21 @@ impl AppConfig {
         Ok(())
     }
 
+    /// Add several nodes to favorites, returns how many were new
+    pub fn add_favorites(&mut self, nodes: &[String]) -> Result<usize> {
+        let before = self.favorite_nodes.len();
+        for node in nodes {
+            if !self.favorite_nodes.contains(node) {
+                self.favorite_nodes.push(node.clone());
+            }
+        }
+        let added = self.favorite_nodes.len() - before;
+        if added > 0 {
+            self.save()?;
+        }
+        Ok(added)
+    }
+
     /// Remove node from favorites
     pub fn remove_favorite(&mut self, node: &str) -> Result<()> {
         self.favorite_nodes.retain(|n| n != node);
@@ -184,6 +692,194 @@ impl AppConfig {
         self.favorite_nodes.contains(&node.to_string())
     }
 
+    /// Set (or clear) the default group for applying favorites
+    pub fn set_favorite_selector(&mut self, group: Option<String>) -> Result<()> {
+        self.favorite_selector = group;
+        self.save()
+    }
+
+    /// Set or clear the primary selector group
+    pub fn set_primary_group(&mut self, group: Option<String>) -> Result<()> {
+        self.primary_group = group;
+        self.save()
+    }
+
+    /// Auto-update interval (minutes) for a subscription
+    pub fn update_interval(&self, name: &str) -> Option<u64> {
+        self.update_intervals.get(name).copied()
+    }
+
+    /// Node filter of a subscription, empty when none is configured
+    pub fn node_filter(&self, name: &str) -> NodeFilter {
+        self.node_filters.get(name).cloned().unwrap_or_default()
+    }
+
+    /// Subscriptions a bulk update downloads at once, at least one
+    pub fn update_concurrency(&self) -> usize {
+        self.update_concurrency
+            .unwrap_or(DEFAULT_UPDATE_CONCURRENCY)
+            .max(1)
+    }
+
+    /// Cycle a subscription's auto-update interval: off → 1h → 6h → 12h → 24h → off
+    pub fn cycle_update_interval(&mut self, name: &str) -> Result<Option<u64>> {
+        let next = next_choice(self.update_interval(name), &UPDATE_INTERVAL_CHOICES);
+
+        match next {
+            Some(minutes) => self.update_intervals.insert(name.to_string(), minutes),
+            None => self.update_intervals.remove(name),
+        };
+        self.save()?;
+        Ok(next)
+    }
+
+    /// How a subscription is downloaded: its own policy, else the default
+    pub fn download_policy(&self, name: &str) -> DownloadPolicy {
+        self.download_policies
+            .get(name)
+            .copied()
+            .unwrap_or_else(|| self.default_download_policy())
+    }
+
+    pub fn default_download_policy(&self) -> DownloadPolicy {
+        self.download_policy.unwrap_or_default()
+    }
+
+    /// Cycle a subscription's download policy: default → system → direct → via proxy → default
+    pub fn cycle_download_policy(&mut self, name: &str) -> Result<Option<DownloadPolicy>> {
+        let next = next_choice(
+            self.download_policies.get(name).copied(),
+            &DownloadPolicy::ALL,
+        );
+        match next {
+            Some(policy) => self.download_policies.insert(name.to_string(), policy),
+            None => self.download_policies.remove(name),
+        };
+        self.save()?;
+        Ok(next)
+    }
+
+    /// Cycle the default download policy: system → direct → via proxy → system
+    pub fn cycle_default_download_policy(&mut self) -> Result<DownloadPolicy> {
+        let next = next_choice(Some(self.default_download_policy()), &DownloadPolicy::ALL)
+            .unwrap_or_default();
+        self.download_policy = (next != DownloadPolicy::System).then_some(next);
+        self.save()?;
+        Ok(next)
+    }
+
+    /// Toggle testing nodes before switching, returns the new value
+    pub fn toggle_test_before_switch(&mut self) -> Result<bool> {
+        self.test_before_switch = !self.test_before_switch;
+        self.save()?;
+        Ok(self.test_before_switch)
+    }
+
+    /// Toggle keeping the secret in the system keychain
+    /// Turning it on fails, leaving it off, when the keychain can't store the secret
+    pub fn toggle_keychain(&mut self) -> Result<bool> {
+        if self.keychain {
+            self.keychain = false;
+            // An unreadable entry has nothing left to keep
+            self.secret_reference = None;
+            self.save()?;
+            secret::delete(&self.api_url)?;
+        } else {
+            if let Some(value) = self.persisted_secret() {
+                secret::store(&self.api_url, value)?;
+            }
+            self.keychain = true;
+            self.save()?;
+        }
+        Ok(self.keychain)
+    }
+
+    /// Cycle the base for raw subscriptions: work config → each template → work config
+    pub fn cycle_profile_template(&mut self, templates: &[String]) -> Result<Option<String>> {
+        let next = match &self.profile_template {
+            None => templates.first().cloned(),
+            Some(current) => templates
+                .iter()
+                .position(|name| name == current)
+                .and_then(|pos| templates.get(pos + 1))
+                .cloned(),
+        };
+        self.profile_template = next.clone();
+        self.save()?;
+        Ok(next)
+    }
+
+    /// Toggle notifications for one event, returns the new value
+    pub fn toggle_notification(&mut self, event: NotifyEvent) -> Result<bool> {
+        let enabled = self.notifications.toggle(event);
+        self.save()?;
+        Ok(enabled)
+    }
+
+    /// Cycle the download rate alert: off → 10 → 50 → 100 → 200 MB/s → off
+    pub fn cycle_download_alert(&mut self) -> Result<Option<u64>> {
+        self.alerts.download_rate_mb =
+            next_choice(self.alerts.download_rate_mb, &DOWNLOAD_ALERT_CHOICES);
+        self.save()?;
+        Ok(self.alerts.download_rate_mb)
+    }
+
+    /// Cycle the connection count alert: off → 200 → 500 → 1000 → 2000 → off
+    pub fn cycle_connection_alert(&mut self) -> Result<Option<usize>> {
+        self.alerts.connections = next_choice(self.alerts.connections, &CONNECTION_ALERT_CHOICES);
+        self.save()?;
+        Ok(self.alerts.connections)
+    }
+
+    /// Apply the custom route order and hidden groups to a route list
+    pub fn arrange_routes(&self, routes: Vec<HumanRoute>, include_hidden: bool) -> Vec<HumanRoute> {
+        let mut remaining: Vec<HumanRoute> = routes
+            .into_iter()
+            .filter(|route| include_hidden || !self.is_group_hidden(&route.name))
+            .collect();
+
+        let mut arranged = Vec::with_capacity(remaining.len());
+        for name in &self.route_order {
+            if let Some(pos) = remaining.iter().position(|route| &route.name == name) {
+                arranged.push(remaining.remove(pos));
+            }
+        }
+        // Groups without a custom position keep their default order
+        arranged.append(&mut remaining);
+        arranged
+    }
+
+    /// Check if a route group is hidden
+    pub fn is_group_hidden(&self, name: &str) -> bool {
+        self.hidden_groups.iter().any(|g| g == name)
+    }
+
+    /// Toggle hidden state of a route group, returns true if now hidden
+    pub fn toggle_hidden_group(&mut self, name: &str) -> Result<bool> {
+        let hidden = if self.is_group_hidden(name) {
+            self.hidden_groups.retain(|g| g != name);
+            false
+        } else {
+            self.hidden_groups.push(name.to_string());
+            true
+        };
+        self.save()?;
+        Ok(hidden)
+    }
+
+    /// Save a new route display order
+    pub fn set_route_order(&mut self, order: Vec<String>) -> Result<()> {
+        self.route_order = order;
+        self.save()
+    }
+
+    /// Clear custom route order and hidden groups
+    pub fn reset_route_layout(&mut self) -> Result<()> {
+        self.route_order.clear();
+        self.hidden_groups.clear();
+        self.save()
+    }
+
     /// Export configuration to a specific path
     pub fn export_to(&self, path: &std::path::Path) -> Result<()> {
         // Ensure directory exists
@@ -191,8 +887,8 @@ impl AppConfig {
             fs::create_dir_all(parent)?;
         }
 
-        let contents = serde_yaml::to_string(self)?;
-        fs::write(path, contents)?;
+        // Holds the secret, like the config itself
+        write_private(path, self.to_yaml()?)?;
 
         Ok(())
     }
@@ -204,7 +900,8 @@ impl AppConfig {
         }
 
         let contents = fs::read_to_string(path)?;
-        let config: AppConfig = serde_yaml::from_str(&contents)?;
+        let mut config: AppConfig = serde_yaml::from_str(&contents)?;
+        config.resolve_secret();
         Ok(config)
     }
 
@@ -236,6 +933,21 @@ impl AppConfig {
         Ok(())
     }
 
+    /// Add several nodes to a group, creating it if needed
+    /// Returns how many nodes were new to the group
+    pub fn add_nodes_to_group(&mut self, group_name: &str, nodes: &[String]) -> Result<usize> {
+        let group = self.node_groups.entry(group_name.to_string()).or_default();
+        let before = group.len();
+        for node in nodes {
+            if !group.contains(node) {
+                group.push(node.clone());
+            }
+        }
+        let added = group.len() - before;
+        self.save()?;
+        Ok(added)
+    }
+
     /// Remove node from a group
     pub fn remove_node_from_group(&mut self, group_name: &str, node: &str) -> Result<()> {
         let group = self
@@ -250,6 +962,21 @@ impl AppConfig {
     /// Delete a group
     pub fn delete_group(&mut self, name: &str) -> Result<()> {
         self.node_groups.remove(name);
+        self.materialized_groups.retain(|group| group != name);
+        self.save()
+    }
+
+    /// Whether a node group is kept as a proxy group in the Clash config
+    pub fn is_group_materialized(&self, name: &str) -> bool {
+        self.materialized_groups.iter().any(|group| group == name)
+    }
+
+    /// Record whether a node group is kept as a proxy group in the Clash config
+    pub fn set_group_materialized(&mut self, name: &str, materialized: bool) -> Result<()> {
+        self.materialized_groups.retain(|group| group != name);
+        if materialized {
+            self.materialized_groups.push(name.to_string());
+        }
         self.save()
     }
 
@@ -275,6 +1002,50 @@ impl AppConfig {
         self.theme = theme.as_str().to_string();
         self.save()
     }
+
+    /// Minutes a delay result stays fresh
+    pub fn delay_ttl(&self) -> u64 {
+        self.delay_ttl_minutes.unwrap_or(DEFAULT_DELAY_TTL_MINUTES)
+    }
+
+    /// Seconds between Connections page refreshes, at least 1
+    pub fn connections_refresh(&self) -> u64 {
+        self.connections_refresh_secs
+            .unwrap_or(DEFAULT_CONNECTIONS_REFRESH_SECS)
+            .max(1)
+    }
+
+    /// Cycle the Connections page refresh: 1 → 2 → 5 → 10 → 1 seconds
+    pub fn cycle_connections_refresh(&mut self) -> Result<u64> {
+        let next = next_choice(
+            Some(self.connections_refresh()),
+            &CONNECTIONS_REFRESH_CHOICES,
+        )
+        .unwrap_or(CONNECTIONS_REFRESH_CHOICES[0]);
+        self.connections_refresh_secs = Some(next);
+        self.save()?;
+        Ok(next)
+    }
+
+    /// URL the proxy probe fetches, None when probing is off
+    pub fn probe_url(&self) -> Option<&str> {
+        match self.probe_url.as_deref().map(str::trim) {
+            None => Some(DEFAULT_PROBE_URL),
+            Some("") => None,
+            Some(url) => Some(url),
+        }
+    }
+
+    /// Get UI language
+    pub fn get_language(&self) -> Language {
+        Language::from_str(&self.language)
+    }
+
+    /// Set UI language
+    pub fn set_language(&mut self, language: Language) -> Result<()> {
+        self.language = language.as_str().to_string();
+        self.save()
+    }
 }
 
 #[cfg(test)]
@@ -288,4 +1059,83 @@ mod tests {
         assert_eq!(config.default_mode, "simple");
         assert_eq!(config.current_preset, "default");
     }
+
+    #[test]
+    fn test_session_secret_is_not_persisted() {
+        let mut config = AppConfig {
+            secret: Some("saved".to_string()),
+            ..AppConfig::default()
+        };
+        config.merge_cli(None, Some("from-env".to_string()), false);
+        assert_eq!(config.secret.as_deref(), Some("from-env"));
+        assert!(config.to_yaml().unwrap().contains("secret: saved"));
+
+        config.set_secret(Some("typed".to_string()));
+        assert!(config.to_yaml().unwrap().contains("secret: typed"));
+
+        config.merge_cli(None, Some("opted-in".to_string()), true);
+        assert!(config.to_yaml().unwrap().contains("secret: opted-in"));
+    }
+
+    #[test]
+    fn test_notification_defaults() {
+        let notifications: NotificationConfig = serde_yaml::from_str("node_down: false").unwrap();
+        assert!(!notifications.is_enabled(NotifyEvent::NodeDown));
+        assert!(notifications.is_enabled(NotifyEvent::SubscriptionExpiring));
+        assert!(notifications.is_enabled(NotifyEvent::CoreUnreachable));
+        assert_eq!(notifications.expiry_days, 7);
+    }
+
+    #[test]
+    fn test_alert_thresholds() {
+        let alerts = AlertConfig {
+            download_rate_mb: Some(50),
+            connections: None,
+        };
+        assert!(alerts.download_exceeded(51 * 1024 * 1024));
+        assert!(!alerts.download_exceeded(50 * 1024 * 1024));
+        assert!(!alerts.connections_exceeded(10_000));
+
+        assert_eq!(next_choice(None, &CONNECTION_ALERT_CHOICES), Some(200));
+        assert_eq!(
+            next_choice(Some(500), &CONNECTION_ALERT_CHOICES),
+            Some(1000)
+        );
+        assert_eq!(next_choice(Some(2000), &CONNECTION_ALERT_CHOICES), None);
+    }
+
+    #[test]
+    fn test_arrange_routes() {
+        let route = |name: &str| HumanRoute {
+            name: name.to_string(),
+            proxy_type: crate::clash::ProxyType::Selector,
+            current_node: None,
+            fixed_node: None,
+            all_nodes: Vec::new(),
+            node_count: 0,
+        };
+        let routes = vec![route("GLOBAL"), route("Auto"), route("HK"), route("JP")];
+
+        let config = AppConfig {
+            route_order: vec![
+                "JP".to_string(),
+                "Missing".to_string(),
+                "GLOBAL".to_string(),
+            ],
+            hidden_groups: vec!["HK".to_string()],
+            ..AppConfig::default()
+        };
+
+        let names = |routes: Vec<HumanRoute>| -> Vec<String> {
+            routes.into_iter().map(|r| r.name).collect()
+        };
+        assert_eq!(
+            names(config.arrange_routes(routes.clone(), false)),
+            vec!["JP", "GLOBAL", "Auto"]
+        );
+        assert_eq!(
+            names(config.arrange_routes(routes, true)),
+            vec!["JP", "GLOBAL", "Auto", "HK"]
+        );
+    }
 }
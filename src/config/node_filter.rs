@@ -0,0 +1,143 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+
+/// Regexes on proxy names deciding which nodes of a subscription are kept
+/// Informational entries like "剩余流量" or "过期时间" are the usual target of `exclude`
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct NodeFilter {
+    /// Keep only nodes matching this
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include: Option<String>,
+    /// Drop nodes matching this
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclude: Option<String>,
+}
+
+impl NodeFilter {
+    pub fn is_empty(&self) -> bool {
+        self.include.is_none() && self.exclude.is_none()
+    }
+
+    pub fn compile(&self) -> Result<NodeMatcher> {
+        let compile = |pattern: &Option<String>, field: &str| {
+            pattern
+                .as_deref()
+                .map(|pattern| {
+                    Regex::new(pattern)
+                        .with_context(|| format!("Invalid node filter {}: {}", field, pattern))
+                })
+                .transpose()
+        };
+        Ok(NodeMatcher {
+            include: compile(&self.include, "include")?,
+            exclude: compile(&self.exclude, "exclude")?,
+        })
+    }
+
+    /// Short form for the Update list, e.g. `+HK|JP -流量`
+    pub fn describe(&self) -> String {
+        let parts: Vec<String> = [("+", &self.include), ("-", &self.exclude)]
+            .into_iter()
+            .filter_map(|(sign, pattern)| Some(format!("{}{}", sign, pattern.as_deref()?)))
+            .collect();
+        parts.join(" ")
+    }
+}
+
+/// A compiled `NodeFilter`
+#[derive(Debug, Clone, Default)]
+pub struct NodeMatcher {
+    include: Option<Regex>,
+    exclude: Option<Regex>,
+}
+
+impl NodeMatcher {
+    pub fn keeps(&self, name: &str) -> bool {
+        self.include.as_ref().is_none_or(|re| re.is_match(name))
+            && !self.exclude.as_ref().is_some_and(|re| re.is_match(name))
+    }
+}
+
+/// Drop filtered proxies from a full config and from the groups listing them
+/// The bytes are returned unchanged when nothing is filtered out
+pub fn filter_config(bytes: &[u8], matcher: &NodeMatcher) -> Result<Vec<u8>> {
+    let mut value: Value = serde_yaml::from_slice(bytes)?;
+    let Some(proxies) = value.get_mut("proxies").and_then(Value::as_sequence_mut) else {
+        return Ok(bytes.to_vec());
+    };
+    let mut dropped = Vec::new();
+    proxies.retain(|proxy| {
+        let name = proxy
+            .get("name")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let keep = matcher.keeps(name);
+        if !keep {
+            dropped.push(name.to_string());
+        }
+        keep
+    });
+    if dropped.is_empty() {
+        return Ok(bytes.to_vec());
+    }
+
+    if let Some(groups) = value
+        .get_mut("proxy-groups")
+        .and_then(Value::as_sequence_mut)
+    {
+        for group in groups {
+            if let Some(members) = group.get_mut("proxies").and_then(Value::as_sequence_mut) {
+                members.retain(|member| {
+                    !member
+                        .as_str()
+                        .is_some_and(|name| dropped.iter().any(|dropped| dropped == name))
+                });
+            }
+        }
+    }
+    Ok(serde_yaml::to_string(&value)?.into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_config_drops_nodes_and_group_members() {
+        let filter = NodeFilter {
+            include: None,
+            exclude: Some("剩余流量|过期时间|官网".to_string()),
+        };
+        let matcher = filter.compile().unwrap();
+        assert!(matcher.keeps("HK 01"));
+        assert!(!matcher.keeps("剩余流量：100 GB"));
+
+        let config = "proxies:\n\
+            - {name: '剩余流量：100 GB', type: ss, server: a, port: 1}\n\
+            - {name: HK 01, type: ss, server: b, port: 2}\n\
+            proxy-groups:\n\
+            - {name: Proxy, type: select, proxies: ['剩余流量：100 GB', HK 01, DIRECT]}\n";
+        let output = filter_config(config.as_bytes(), &matcher).unwrap();
+        let value: Value = serde_yaml::from_slice(&output).unwrap();
+        assert_eq!(value["proxies"].as_sequence().unwrap().len(), 1);
+        assert_eq!(
+            value["proxy-groups"][0]["proxies"],
+            serde_yaml::from_str::<Value>("[HK 01, DIRECT]").unwrap()
+        );
+
+        let include_only = NodeFilter {
+            include: Some("^JP".to_string()),
+            exclude: None,
+        };
+        assert!(!include_only.compile().unwrap().keeps("HK 01"));
+        assert_eq!(include_only.describe(), "+^JP");
+        assert!(NodeFilter {
+            include: Some("(".to_string()),
+            exclude: None
+        }
+        .compile()
+        .is_err());
+    }
+}
@@ -0,0 +1,188 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+use std::collections::{HashMap, HashSet};
+
+/// How node names are rewritten when a subscription is converted, in this order:
+/// regex replacements, emoji stripping, then the subscription name as a prefix
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct RenameRules {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub replace: Vec<RenameReplace>,
+    #[serde(default)]
+    pub strip_emoji: bool,
+    /// Prefix names with `<subscription> | `, telling merged subscriptions apart
+    #[serde(default)]
+    pub prefix_subscription: bool,
+}
+
+/// Replace matches of `pattern` with `with` (`$1` refers to capture groups)
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct RenameReplace {
+    pub pattern: String,
+    #[serde(default)]
+    pub with: String,
+}
+
+impl RenameRules {
+    pub fn is_empty(&self) -> bool {
+        self.replace.is_empty() && !self.strip_emoji && !self.prefix_subscription
+    }
+
+    /// The rules for the nodes of `subscription`
+    pub fn compile(&self, subscription: &str) -> Result<Renamer> {
+        let replace = self
+            .replace
+            .iter()
+            .map(|rule| {
+                Regex::new(&rule.pattern)
+                    .map(|re| (re, rule.with.clone()))
+                    .with_context(|| format!("Invalid node rename pattern: {}", rule.pattern))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Renamer {
+            replace,
+            strip_emoji: self.strip_emoji,
+            prefix: self
+                .prefix_subscription
+                .then(|| format!("{} | ", subscription)),
+        })
+    }
+}
+
+/// Compiled `RenameRules` for one subscription
+#[derive(Debug, Clone, Default)]
+pub struct Renamer {
+    replace: Vec<(Regex, String)>,
+    strip_emoji: bool,
+    prefix: Option<String>,
+}
+
+impl Renamer {
+    pub fn rename(&self, name: &str) -> String {
+        let mut name = name.to_string();
+        for (re, with) in &self.replace {
+            name = re.replace_all(&name, with.as_str()).into_owned();
+        }
+        if self.strip_emoji {
+            name = name.chars().filter(|c| !is_emoji(*c)).collect();
+            name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+        }
+        if let Some(prefix) = &self.prefix {
+            name.insert_str(0, prefix);
+        }
+        name
+    }
+
+    /// New names for `names`, kept unique with a ` 2`, ` 3`... suffix
+    pub fn rename_all<'a>(&self, names: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+        let mut taken = HashSet::new();
+        names
+            .into_iter()
+            .map(|name| {
+                let base = self.rename(name);
+                let mut candidate = base.clone();
+                let mut n = 2;
+                while !taken.insert(candidate.clone()) {
+                    candidate = format!("{} {}", base, n);
+                    n += 1;
+                }
+                candidate
+            })
+            .collect()
+    }
+}
+
+/// Flags, pictographs, dingbats and the joiners/selectors that combine them
+fn is_emoji(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x1F000..=0x1FAFF | 0x2600..=0x27BF | 0x2B00..=0x2BFF | 0xFE0F | 0x200D | 0xE0020..=0xE007F
+    )
+}
+
+/// Rename the proxies of a full config and their entries in proxy groups
+/// The bytes are returned unchanged when no name changes
+pub fn rename_config(bytes: &[u8], renamer: &Renamer) -> Result<Vec<u8>> {
+    let mut value: Value = serde_yaml::from_slice(bytes)?;
+    let Some(proxies) = value.get_mut("proxies").and_then(Value::as_sequence_mut) else {
+        return Ok(bytes.to_vec());
+    };
+    let old: Vec<String> = proxies
+        .iter()
+        .map(|proxy| {
+            proxy
+                .get("name")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string()
+        })
+        .collect();
+    let new = renamer.rename_all(old.iter().map(String::as_str));
+    if old == new {
+        return Ok(bytes.to_vec());
+    }
+    for (proxy, name) in proxies.iter_mut().zip(&new) {
+        if let Some(map) = proxy.as_mapping_mut() {
+            map.insert(Value::from("name"), Value::from(name.as_str()));
+        }
+    }
+
+    let renamed: HashMap<&str, &str> = old
+        .iter()
+        .map(String::as_str)
+        .zip(new.iter().map(String::as_str))
+        .collect();
+    if let Some(groups) = value
+        .get_mut("proxy-groups")
+        .and_then(Value::as_sequence_mut)
+    {
+        for group in groups {
+            if let Some(members) = group.get_mut("proxies").and_then(Value::as_sequence_mut) {
+                for member in members {
+                    if let Some(name) = member.as_str().and_then(|name| renamed.get(name)) {
+                        *member = Value::from(*name);
+                    }
+                }
+            }
+        }
+    }
+    Ok(serde_yaml::to_string(&value)?.into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rename_config_rewrites_proxies_and_groups() {
+        let rules = RenameRules {
+            replace: vec![RenameReplace {
+                pattern: "香港".to_string(),
+                with: "HK".to_string(),
+            }],
+            strip_emoji: true,
+            prefix_subscription: true,
+        };
+        let renamer = rules.compile("Airport").unwrap();
+        assert_eq!(renamer.rename("🇭🇰 香港 01"), "Airport | HK 01");
+        assert_eq!(
+            renamer.rename_all(["🇭🇰 香港", "香港"]),
+            ["Airport | HK", "Airport | HK 2"]
+        );
+
+        let config = "proxies:\n\
+            - {name: '🇭🇰 香港 01', type: ss, server: a, port: 1}\n\
+            proxy-groups:\n\
+            - {name: Proxy, type: select, proxies: ['🇭🇰 香港 01', DIRECT]}\n";
+        let output = rename_config(config.as_bytes(), &renamer).unwrap();
+        let value: Value = serde_yaml::from_slice(&output).unwrap();
+        assert_eq!(value["proxies"][0]["name"], "Airport | HK 01");
+        assert_eq!(
+            value["proxy-groups"][0]["proxies"],
+            serde_yaml::from_str::<Value>("['Airport | HK 01', DIRECT]").unwrap()
+        );
+        assert!(RenameRules::default().is_empty());
+    }
+}
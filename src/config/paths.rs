@@ -0,0 +1,148 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use super::backup::BackupStore;
+
+/// Environment variable overriding the config file path
+pub const CONFIG_ENV: &str = "CLASHCTL_CONFIG";
+
+/// Config file given with `--config`
+static CONFIG_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Use `path` as the config file for the rest of the run
+pub fn set_config_file(path: PathBuf) {
+    let _ = CONFIG_OVERRIDE.set(path);
+}
+
+/// The config file: `--config`, then `CLASHCTL_CONFIG`, then `config.yaml` in the config dir
+pub fn config_file() -> Result<PathBuf> {
+    if let Some(path) = CONFIG_OVERRIDE.get() {
+        return Ok(path.clone());
+    }
+    if let Some(path) = std::env::var_os(CONFIG_ENV).filter(|value| !value.is_empty()) {
+        return Ok(PathBuf::from(path));
+    }
+    Ok(config_dir()?.join("config.yaml"))
+}
+
+/// Settings and user templates (`~/.config/clashctl` on Linux)
+pub fn config_dir() -> Result<PathBuf> {
+    let dir =
+        dirs::config_dir().ok_or_else(|| anyhow::anyhow!("Could not find config directory"))?;
+    Ok(dir.join("clashctl"))
+}
+
+/// Traffic stats and backups (`~/.local/state/clashctl` on Linux)
+/// Platforms without a state dir use the local data dir
+pub fn state_dir() -> Result<PathBuf> {
+    let dir = dirs::state_dir()
+        .or_else(dirs::data_local_dir)
+        .ok_or_else(|| anyhow::anyhow!("Could not find state directory"))?;
+    Ok(dir.join("clashctl"))
+}
+
+/// Data that can be rebuilt, like the delay history (`~/.cache/clashctl` on Linux)
+pub fn cache_dir() -> Result<PathBuf> {
+    let dir = dirs::cache_dir().ok_or_else(|| anyhow::anyhow!("Could not find cache directory"))?;
+    Ok(dir.join("clashctl"))
+}
+
+/// Move history and backups written next to the config by older versions
+/// Returns the paths moved
+pub fn migrate() -> Result<Vec<PathBuf>> {
+    migrate_from(&config_dir()?, &state_dir()?, &cache_dir()?)
+}
+
+fn migrate_from(config: &Path, state: &Path, cache: &Path) -> Result<Vec<PathBuf>> {
+    let moves = [
+        (config.join("stats.jsonl"), state.join("stats.jsonl")),
+        (config.join("backups"), state.join("backups")),
+        (config.join("delays.jsonl"), cache.join("delays.jsonl")),
+    ];
+    let mut moved = Vec::new();
+    for (from, to) in moves {
+        // Anything already at the new location wins
+        if from == to || !from.exists() || to.exists() {
+            continue;
+        }
+        move_path(&from, &to).with_context(|| format!("Failed to move {}", from.display()))?;
+        if to.is_dir() {
+            BackupStore::new(to.clone()).rebase(&from)?;
+        }
+        moved.push(to);
+    }
+    Ok(moved)
+}
+
+/// Rename `from` to `to`, copying when they are on different filesystems
+/// Directories are expected to be flat, like the backups dir
+fn move_path(from: &Path, to: &Path) -> Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    if from.is_dir() {
+        fs::create_dir_all(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            fs::copy(entry.path(), to.join(entry.file_name()))?;
+        }
+        fs::remove_dir_all(from)?;
+    } else {
+        fs::copy(from, to)?;
+        fs::remove_file(from)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrate_moves_history_and_backups() {
+        let root = std::env::temp_dir().join(format!("clashctl-paths-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let (config, state, cache) = (root.join("config"), root.join("state"), root.join("cache"));
+        fs::create_dir_all(&config).unwrap();
+        fs::write(config.join("stats.jsonl"), "old stats").unwrap();
+        fs::write(config.join("delays.jsonl"), "old delays").unwrap();
+        fs::create_dir_all(&cache).unwrap();
+        fs::write(cache.join("delays.jsonl"), "new delays").unwrap();
+
+        let profile = root.join("profile.yaml");
+        fs::write(&profile, "v1").unwrap();
+        BackupStore::new(config.join("backups"))
+            .record("switch", &[&profile], None)
+            .unwrap();
+        fs::write(&profile, "v2").unwrap();
+
+        let moved = migrate_from(&config, &state, &cache).unwrap();
+        assert_eq!(
+            moved,
+            vec![state.join("stats.jsonl"), state.join("backups")]
+        );
+        assert_eq!(
+            fs::read_to_string(state.join("stats.jsonl")).unwrap(),
+            "old stats"
+        );
+        // Existing files at the new location are kept
+        assert_eq!(
+            fs::read_to_string(cache.join("delays.jsonl")).unwrap(),
+            "new delays"
+        );
+        assert!(!config.join("backups").exists());
+
+        // Backups still restore from their new location
+        BackupStore::new(state.join("backups"))
+            .restore_latest()
+            .unwrap();
+        assert_eq!(fs::read_to_string(&profile).unwrap(), "v1");
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -1,9 +1,13 @@
 use serde::{Deserialize, Serialize};
 
+use crate::app::{Mode, Page};
+use crate::clash::ClashMode;
+
 /// Preset defines a complexity template
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum Preset {
     /// Default: Simple mode by default, full features available
+    #[default]
     Default,
     /// Work: Hide speed test, minimal UI
     Work,
@@ -11,9 +15,34 @@ pub enum Preset {
     Strict,
     /// Expert: Default to Expert mode, show all details
     Expert,
+    /// Defined in the config's `custom_presets`
+    Custom(CustomPreset),
+}
+
+/// A user-defined preset, cycled through after the built-in ones
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomPreset {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub default_mode: Mode,
+    #[serde(default = "default_true")]
+    pub show_speed_test: bool,
+    /// Pages that can be opened; all when empty (Home always can)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pages: Vec<Page>,
+    /// Seconds between Clash state refreshes (5 when unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_secs: Option<u64>,
+}
+
+fn default_true() -> bool {
+    true
 }
 
 impl Preset {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "default" => Some(Preset::Default),
@@ -24,12 +53,23 @@ impl Preset {
         }
     }
 
+    /// Built-in preset, else the custom one of this name
+    pub fn find(s: &str, custom: &[CustomPreset]) -> Option<Self> {
+        Self::from_str(s).or_else(|| {
+            custom
+                .iter()
+                .find(|preset| preset.name.eq_ignore_ascii_case(s))
+                .map(|preset| Preset::Custom(preset.clone()))
+        })
+    }
+
     pub fn as_str(&self) -> &str {
         match self {
             Preset::Default => "default",
             Preset::Work => "work",
             Preset::Strict => "strict",
             Preset::Expert => "expert",
+            Preset::Custom(preset) => &preset.name,
         }
     }
 
@@ -39,6 +79,7 @@ impl Preset {
             Preset::Work => "Work",
             Preset::Strict => "Strict",
             Preset::Expert => "Expert",
+            Preset::Custom(preset) => &preset.name,
         }
     }
 
@@ -48,6 +89,7 @@ impl Preset {
             Preset::Work => "Minimal UI, hide speed test, focus on switching",
             Preset::Strict => "Disable quick operations, require Expert mode",
             Preset::Expert => "Expert mode by default, show all details",
+            Preset::Custom(preset) => &preset.description,
         }
     }
 
@@ -58,6 +100,25 @@ impl Preset {
             Preset::Work => false,
             Preset::Strict => true,
             Preset::Expert => true,
+            Preset::Custom(preset) => preset.show_speed_test,
+        }
+    }
+
+    /// Whether `page` can be opened in this preset
+    pub fn shows_page(&self, page: Page) -> bool {
+        match self {
+            Preset::Custom(preset) => {
+                page == Page::Home || preset.pages.is_empty() || preset.pages.contains(&page)
+            }
+            _ => true,
+        }
+    }
+
+    /// Seconds between Clash state refreshes, if the preset sets it
+    pub fn refresh_secs(&self) -> Option<u64> {
+        match self {
+            Preset::Custom(preset) => preset.refresh_secs.filter(|secs| *secs > 0),
+            _ => None,
         }
     }
 
@@ -69,6 +130,7 @@ impl Preset {
             Preset::Work => true,
             Preset::Strict => false,
             Preset::Expert => true,
+            Preset::Custom(_) => true,
         }
     }
 
@@ -79,6 +141,7 @@ impl Preset {
             Preset::Work => crate::app::Mode::Simple,
             Preset::Strict => crate::app::Mode::Simple,
             Preset::Expert => crate::app::Mode::Expert,
+            Preset::Custom(preset) => preset.default_mode,
         }
     }
 
@@ -90,6 +153,7 @@ impl Preset {
             Preset::Work => true,
             Preset::Strict => true,
             Preset::Expert => true,
+            Preset::Custom(_) => true,
         }
     }
 
@@ -104,20 +168,101 @@ impl Preset {
         ]
     }
 
-    /// Next preset in cycle
+    /// Next built-in preset in cycle
     pub fn next(&self) -> Self {
         match self {
             Preset::Default => Preset::Work,
             Preset::Work => Preset::Strict,
             Preset::Strict => Preset::Expert,
-            Preset::Expert => Preset::Default,
+            Preset::Expert | Preset::Custom(_) => Preset::Default,
         }
     }
+
+    /// Next preset in cycle, going through `custom` after Expert;
+    /// custom presets named like a built-in one are skipped
+    pub fn cycle(&self, custom: &[CustomPreset]) -> Self {
+        let mut custom = custom
+            .iter()
+            .filter(|preset| Self::from_str(&preset.name).is_none());
+        let next = match self {
+            Preset::Expert => custom.next(),
+            Preset::Custom(current) => custom
+                .skip_while(|preset| preset.name != current.name)
+                .nth(1),
+            _ => return self.next(),
+        };
+        next.map_or(Preset::Default, |preset| Preset::Custom(preset.clone()))
+    }
 }
 
-impl Default for Preset {
-    fn default() -> Self {
-        Preset::Default
+/// Core settings a preset applies when switched to, from `presets.<name>` in the config
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PresetCore {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<ClashMode>,
+    /// Group to select `node` in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node: Option<String>,
+}
+
+impl PresetCore {
+    pub fn selection(&self) -> Option<(&str, &str)> {
+        Some((self.group.as_deref()?, self.node.as_deref()?))
+    }
+}
+
+/// Core changes to make when switching presets
+#[derive(Debug, Default, PartialEq)]
+pub struct PresetChange {
+    pub mode: Option<ClashMode>,
+    /// Groups and the node to select in each
+    pub selections: Vec<(String, String)>,
+}
+
+/// Core state presets have overridden, put back once a preset no longer sets it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PresetRestore {
+    mode: Option<ClashMode>,
+    selection: Option<(String, String)>,
+}
+
+impl PresetRestore {
+    /// Changes for switching to a preset with `core` settings (none when unset),
+    /// remembering what they replace; `now` gives a group's selected node
+    pub fn switch(
+        &mut self,
+        core: Option<&PresetCore>,
+        mode: &ClashMode,
+        now: impl Fn(&str) -> Option<String>,
+    ) -> PresetChange {
+        let mut change = PresetChange::default();
+        match core.and_then(|core| core.mode.clone()) {
+            Some(wanted) => {
+                self.mode.get_or_insert_with(|| mode.clone());
+                change.mode = (wanted != *mode).then_some(wanted);
+            }
+            None => change.mode = self.mode.take().filter(|previous| previous != mode),
+        }
+        match core.and_then(PresetCore::selection) {
+            Some((group, node)) => {
+                if self.selection.as_ref().is_some_and(|(g, _)| g != group) {
+                    // Another group is targeted now; put the old one back
+                    change.selections.extend(self.selection.take());
+                }
+                if self.selection.is_none() {
+                    self.selection = now(group).map(|previous| (group.to_string(), previous));
+                }
+                if now(group).as_deref() != Some(node) {
+                    change
+                        .selections
+                        .push((group.to_string(), node.to_string()));
+                }
+            }
+            None => change.selections.extend(self.selection.take()),
+        }
+        change
     }
 }
 
@@ -152,4 +297,46 @@ mod tests {
         assert_eq!(preset.next().next().next(), Preset::Expert);
         assert_eq!(preset.next().next().next().next(), Preset::Default);
     }
+
+    #[test]
+    fn test_preset_restore_puts_core_back() {
+        let work = PresetCore {
+            mode: Some(ClashMode::Global),
+            group: Some("Proxy".to_string()),
+            node: Some("JP".to_string()),
+        };
+        let now = |_: &str| Some("HK".to_string());
+        let mut restore = PresetRestore::default();
+
+        let change = restore.switch(Some(&work), &ClashMode::Rule, now);
+        assert_eq!(change.mode, Some(ClashMode::Global));
+        assert_eq!(change.selections, [("Proxy".to_string(), "JP".to_string())]);
+
+        let change = restore.switch(None, &ClashMode::Global, |_| Some("JP".to_string()));
+        assert_eq!(change.mode, Some(ClashMode::Rule));
+        assert_eq!(change.selections, [("Proxy".to_string(), "HK".to_string())]);
+        assert_eq!(restore, PresetRestore::default());
+        assert_eq!(
+            restore.switch(None, &ClashMode::Rule, now),
+            PresetChange::default()
+        );
+    }
+
+    #[test]
+    fn test_custom_presets_join_the_cycle() {
+        let custom: Vec<CustomPreset> =
+            serde_yaml::from_str("[{name: Kiosk, pages: [routes]}, {name: work}, {name: Ops}]")
+                .unwrap();
+        let kiosk = Preset::find("kiosk", &custom).unwrap();
+        assert_eq!(kiosk.name(), "Kiosk");
+        assert!(kiosk.show_speed_test());
+        assert!(kiosk.shows_page(Page::Home));
+        assert!(!kiosk.shows_page(Page::Logs));
+
+        assert_eq!(Preset::Expert.cycle(&custom), kiosk);
+        assert_eq!(kiosk.cycle(&custom).name(), "Ops");
+        assert_eq!(kiosk.cycle(&custom).cycle(&custom), Preset::Default);
+        assert_eq!(Preset::Expert.cycle(&[]), Preset::Default);
+        assert_eq!(Preset::find("work", &custom), Some(Preset::Work));
+    }
 }
@@ -0,0 +1,93 @@
+use anyhow::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use super::clash_verge::{self, ClashVergeStore};
+use super::mihomo_party::{self, MihomoPartyStore};
+
+/// A profile as listed by a GUI
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredProfile {
+    pub id: String,
+    pub name: String,
+    /// `remote`, `local`, or a GUI-specific kind like Clash Verge's `merge`
+    pub profile_type: String,
+    pub url: Option<String>,
+    /// Last download, in unix milliseconds
+    pub updated_ms: Option<i64>,
+    /// The profile's config file
+    pub path: PathBuf,
+}
+
+/// The profile list and which profile is in use
+#[derive(Debug, Clone, Default)]
+pub struct ProfileList {
+    pub items: Vec<StoredProfile>,
+    pub current: Option<String>,
+}
+
+/// A profile to add: remote when it has a URL, local otherwise
+#[derive(Debug, Clone)]
+pub struct NewProfile {
+    pub name: String,
+    pub url: Option<String>,
+}
+
+/// Where a Clash GUI keeps its subscriptions and the config its core runs
+/// Plain Clash setups have none; their subscriptions are the config's proxy providers
+pub trait ProfileStore: std::fmt::Debug + Send + Sync {
+    /// The GUI, for messages
+    fn name(&self) -> &'static str;
+
+    /// Executable names of the GUI, to tell whether it's running and may overwrite
+    /// what clashctl writes
+    fn process_names(&self) -> &'static [&'static str];
+
+    /// The profile list file
+    fn list_path(&self) -> &Path;
+
+    fn load(&self) -> Result<ProfileList>;
+
+    /// Config the core runs; switching to a profile overwrites it
+    fn work_config_path(&self) -> PathBuf;
+
+    /// Record when a profile was downloaded, in unix milliseconds
+    fn set_updated(&self, id: &str, updated_ms: i64) -> Result<()>;
+
+    fn set_current(&self, id: &str) -> Result<()>;
+
+    /// Append profiles to the list, returning them as stored
+    fn add(&self, profiles: &[NewProfile]) -> Result<Vec<StoredProfile>>;
+}
+
+/// The profile store of an installed GUI: Mihomo Party or Clash Verge Rev
+/// Directories named by `CLASH_PARTY_DIR`/`CLASH_VERGE_DIR` come first, then the one
+/// holding `hint` (the Clash config), then the GUIs' default locations
+pub fn find_profile_store(hint: Option<&Path>) -> Option<Arc<dyn ProfileStore>> {
+    fn party(path: PathBuf) -> Arc<dyn ProfileStore> {
+        Arc::new(MihomoPartyStore::new(path))
+    }
+    fn verge(path: PathBuf) -> Arc<dyn ProfileStore> {
+        Arc::new(ClashVergeStore::new(path))
+    }
+
+    mihomo_party::list_from_env()
+        .map(party)
+        .or_else(|| clash_verge::list_from_env().map(verge))
+        .or_else(|| hint.and_then(clash_verge::list_from_hint).map(verge))
+        .or_else(|| hint.and_then(mihomo_party::list_from_hint).map(party))
+        .or_else(|| mihomo_party::list_in_default_dirs().map(party))
+        .or_else(|| clash_verge::list_in_default_dirs().map(verge))
+        .or_else(|| mihomo_party::scan_for_list().map(party))
+}
+
+/// Number of proxies in a profile that is a full Clash config
+pub fn count_proxies_in_profile(path: &Path) -> Option<usize> {
+    let content = fs::read_to_string(path).ok()?;
+    let value: serde_yaml::Value = serde_yaml::from_str(&content).ok()?;
+    value
+        .get("proxies")
+        .and_then(|v| v.as_sequence())
+        .map(|seq| seq.len())
+}
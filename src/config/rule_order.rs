@@ -0,0 +1,163 @@
+/// Rules of the Clash config being reordered, remembering where each started
+#[derive(Debug, Clone)]
+pub struct RuleOrder {
+    original: Vec<String>,
+    /// Original index of each rule, in the edited order
+    order: Vec<usize>,
+}
+
+/// One difference between the edited rules and the config
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuleChange {
+    /// Rule at `from` in the config now sits at `to` in the edited list (0-based)
+    Moved {
+        rule: String,
+        from: usize,
+        to: usize,
+    },
+    Deleted {
+        rule: String,
+        from: usize,
+    },
+}
+
+impl RuleOrder {
+    pub fn new(rules: Vec<String>) -> Self {
+        Self {
+            order: (0..rules.len()).collect(),
+            original: rules,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.order
+            .get(index)
+            .map(|from| self.original[*from].as_str())
+    }
+
+    /// Rules in the edited order
+    pub fn rules(&self) -> Vec<String> {
+        self.order
+            .iter()
+            .map(|from| self.original[*from].clone())
+            .collect()
+    }
+
+    /// Swap the rule at `index` with the one above; false at the top
+    pub fn move_up(&mut self, index: usize) -> bool {
+        if index == 0 || index >= self.order.len() {
+            return false;
+        }
+        self.order.swap(index - 1, index);
+        true
+    }
+
+    /// Swap the rule at `index` with the one below; false at the bottom
+    pub fn move_down(&mut self, index: usize) -> bool {
+        if index + 1 >= self.order.len() {
+            return false;
+        }
+        self.order.swap(index, index + 1);
+        true
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<String> {
+        (index < self.order.len()).then(|| self.original[self.order.remove(index)].clone())
+    }
+
+    /// Deleted rules, then the fewest moves that explain the new order
+    /// Rules that kept their order relative to each other are not reported as moved
+    pub fn changes(&self) -> Vec<RuleChange> {
+        let mut changes: Vec<RuleChange> = (0..self.original.len())
+            .filter(|from| !self.order.contains(from))
+            .map(|from| RuleChange::Deleted {
+                rule: self.original[from].clone(),
+                from,
+            })
+            .collect();
+        let stable = longest_increasing(&self.order);
+        for (to, from) in self.order.iter().enumerate() {
+            if !stable[to] {
+                changes.push(RuleChange::Moved {
+                    rule: self.original[*from].clone(),
+                    from: *from,
+                    to,
+                });
+            }
+        }
+        changes
+    }
+
+    /// Indexes of the rules `changes` reports as moved
+    pub fn moved(&self) -> Vec<usize> {
+        let stable = longest_increasing(&self.order);
+        (0..self.order.len()).filter(|to| !stable[*to]).collect()
+    }
+}
+
+/// Mark the entries of one longest strictly increasing subsequence
+fn longest_increasing(values: &[usize]) -> Vec<bool> {
+    // tails[k]: index of the smallest value ending an increasing run of length k + 1
+    let mut tails: Vec<usize> = Vec::new();
+    let mut previous: Vec<Option<usize>> = vec![None; values.len()];
+    for (i, value) in values.iter().enumerate() {
+        let k = tails.partition_point(|&tail| values[tail] < *value);
+        previous[i] = k.checked_sub(1).map(|k| tails[k]);
+        if k == tails.len() {
+            tails.push(i);
+        } else {
+            tails[k] = i;
+        }
+    }
+    let mut marked = vec![false; values.len()];
+    let mut next = tails.last().copied();
+    while let Some(i) = next {
+        marked[i] = true;
+        next = previous[i];
+    }
+    marked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changes_report_fewest_moves() {
+        let rules: Vec<String> = ["A", "B", "C", "D", "E"].map(String::from).to_vec();
+        let mut order = RuleOrder::new(rules);
+        assert!(order.changes().is_empty());
+
+        // E moved to the top, C deleted
+        for index in (1..=4).rev() {
+            assert!(order.move_up(index));
+        }
+        assert!(!order.move_up(0));
+        assert_eq!(order.remove(3), Some("C".to_string()));
+        assert_eq!(order.rules(), vec!["E", "A", "B", "D"]);
+
+        assert_eq!(
+            order.changes(),
+            vec![
+                RuleChange::Deleted {
+                    rule: "C".to_string(),
+                    from: 2
+                },
+                RuleChange::Moved {
+                    rule: "E".to_string(),
+                    from: 4,
+                    to: 0
+                },
+            ]
+        );
+        assert_eq!(order.moved(), vec![0]);
+    }
+}
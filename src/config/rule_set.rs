@@ -0,0 +1,248 @@
+use anyhow::{Context, Result};
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+
+/// Payload entries of a rule provider from `rule-providers` of the Clash config
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleSet {
+    pub name: String,
+    /// `domain`, `ipcidr` or `classical`
+    pub behavior: String,
+    /// File the entries were read from, or `inline`
+    pub source: String,
+    pub entries: Vec<String>,
+}
+
+impl RuleSet {
+    /// The first entry that would match `target`, a domain or IP address
+    pub fn find_match(&self, target: &str) -> Option<&str> {
+        let target = target.trim().trim_end_matches('.').to_lowercase();
+        if target.is_empty() {
+            return None;
+        }
+        self.entries
+            .iter()
+            .find(|entry| entry_matches(&self.behavior, entry, &target))
+            .map(String::as_str)
+    }
+}
+
+/// Read the rule provider `name` of config YAML; file paths are relative to `config_dir`
+pub fn read_rule_set(bytes: &[u8], config_dir: &Path, name: &str) -> Result<RuleSet> {
+    let value: serde_yaml::Value = serde_yaml::from_slice(bytes)?;
+    let provider = value
+        .get("rule-providers")
+        .and_then(|providers| providers.get(name))
+        .ok_or_else(|| anyhow::anyhow!("No rule provider named {}", name))?;
+    let field = |key: &str| provider.get(key).and_then(|v| v.as_str()).unwrap_or("");
+    let behavior = field("behavior").to_lowercase();
+    let format = field("format").to_lowercase();
+
+    if field("type") == "inline" {
+        let entries = provider
+            .get("payload")
+            .and_then(|payload| payload.as_sequence())
+            .map(|payload| {
+                payload
+                    .iter()
+                    .filter_map(|entry| entry.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+        return Ok(RuleSet {
+            name: name.to_string(),
+            behavior,
+            source: "inline".to_string(),
+            entries,
+        });
+    }
+
+    if format == "mrs" {
+        anyhow::bail!("{} is a binary mrs rule-set, which can't be listed", name);
+    }
+    let path = field("path");
+    if path.is_empty() {
+        anyhow::bail!("{} has no path; set one to inspect its file", name);
+    }
+    let path = resolve(config_dir, path);
+    let text = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let entries = if format == "text" || path.extension().is_some_and(|ext| ext == "list") {
+        parse_text(&text)
+    } else {
+        parse_yaml(&text)?
+    };
+    Ok(RuleSet {
+        name: name.to_string(),
+        behavior,
+        source: path.display().to_string(),
+        entries,
+    })
+}
+
+fn resolve(config_dir: &Path, path: &str) -> PathBuf {
+    let path = Path::new(path);
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        config_dir.join(path)
+    }
+}
+
+/// One entry per line, `#` starts a comment
+fn parse_text(text: &str) -> Vec<String> {
+    text.lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| line.trim_matches(|c| c == '\'' || c == '"').to_string())
+        .collect()
+}
+
+/// The `payload` list of a YAML rule-set
+fn parse_yaml(text: &str) -> Result<Vec<String>> {
+    let value: serde_yaml::Value = serde_yaml::from_str(text)?;
+    let payload = value
+        .get("payload")
+        .and_then(|payload| payload.as_sequence())
+        .ok_or_else(|| anyhow::anyhow!("Rule-set has no payload list"))?;
+    Ok(payload
+        .iter()
+        .filter_map(|entry| entry.as_str().map(str::to_string))
+        .collect())
+}
+
+fn entry_matches(behavior: &str, entry: &str, target: &str) -> bool {
+    let entry = entry.trim();
+    match behavior {
+        "domain" => domain_matches(entry, target),
+        "ipcidr" => cidr_contains(entry, target),
+        _ => {
+            let mut parts = entry.split(',').map(str::trim);
+            let (Some(kind), Some(value)) = (parts.next(), parts.next()) else {
+                return false;
+            };
+            let value = value.to_lowercase();
+            match kind.to_uppercase().as_str() {
+                "DOMAIN" => value == target,
+                "DOMAIN-SUFFIX" => target == value || target.ends_with(&format!(".{}", value)),
+                "DOMAIN-KEYWORD" => target.contains(&value),
+                "IP-CIDR" | "IP-CIDR6" => cidr_contains(&value, target),
+                _ => false,
+            }
+        }
+    }
+}
+
+/// Domain rule-set syntax: `+.` matches the domain and its subdomains,
+/// `.` only subdomains and `*.` a single level of subdomain
+fn domain_matches(entry: &str, target: &str) -> bool {
+    let entry = entry.to_lowercase();
+    if let Some(suffix) = entry.strip_prefix("+.") {
+        target == suffix || target.ends_with(&format!(".{}", suffix))
+    } else if let Some(suffix) = entry.strip_prefix("*.") {
+        target
+            .strip_suffix(suffix)
+            .and_then(|label| label.strip_suffix('.'))
+            .is_some_and(|label| !label.is_empty() && !label.contains('.'))
+    } else if entry.starts_with('.') {
+        target.ends_with(&entry)
+    } else {
+        entry == target
+    }
+}
+
+fn cidr_contains(cidr: &str, target: &str) -> bool {
+    let Ok(ip) = target.parse::<IpAddr>() else {
+        return false;
+    };
+    let (network, prefix) = cidr.split_once('/').unwrap_or((cidr, ""));
+    let Ok(network) = network.parse::<IpAddr>() else {
+        return false;
+    };
+    match (network, ip) {
+        (IpAddr::V4(network), IpAddr::V4(ip)) => {
+            let prefix = prefix.parse::<u32>().unwrap_or(32).min(32);
+            let mask = u32::MAX.checked_shl(32 - prefix).unwrap_or(0);
+            u32::from(network) & mask == u32::from(ip) & mask
+        }
+        (IpAddr::V6(network), IpAddr::V6(ip)) => {
+            let prefix = prefix.parse::<u32>().unwrap_or(128).min(128);
+            let mask = u128::MAX.checked_shl(128 - prefix).unwrap_or(0);
+            u128::from(network) & mask == u128::from(ip) & mask
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule_set(behavior: &str, entries: &[&str]) -> RuleSet {
+        RuleSet {
+            name: "test".to_string(),
+            behavior: behavior.to_string(),
+            source: "inline".to_string(),
+            entries: entries.iter().map(|entry| entry.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_rule_set_matches_by_behavior() {
+        let domains = rule_set(
+            "domain",
+            &["+.google.com", ".ads.net", "*.cdn.org", "exact.io"],
+        );
+        assert_eq!(domains.find_match("google.com"), Some("+.google.com"));
+        assert_eq!(domains.find_match("mail.Google.com."), Some("+.google.com"));
+        assert_eq!(domains.find_match("ads.net"), None);
+        assert_eq!(domains.find_match("x.ads.net"), Some(".ads.net"));
+        assert_eq!(domains.find_match("a.cdn.org"), Some("*.cdn.org"));
+        assert_eq!(domains.find_match("a.b.cdn.org"), None);
+        assert_eq!(domains.find_match("exact.io"), Some("exact.io"));
+
+        let cidrs = rule_set("ipcidr", &["10.0.0.0/8", "2001:db8::/32"]);
+        assert_eq!(cidrs.find_match("10.1.2.3"), Some("10.0.0.0/8"));
+        assert_eq!(cidrs.find_match("11.0.0.1"), None);
+        assert_eq!(cidrs.find_match("2001:db8::1"), Some("2001:db8::/32"));
+
+        let classical = rule_set(
+            "classical",
+            &["DOMAIN-KEYWORD,tracker", "DOMAIN-SUFFIX,apple.com"],
+        );
+        assert_eq!(
+            classical.find_match("icloud.apple.com"),
+            Some("DOMAIN-SUFFIX,apple.com")
+        );
+        assert_eq!(
+            classical.find_match("mytracker.io"),
+            Some("DOMAIN-KEYWORD,tracker")
+        );
+        assert_eq!(classical.find_match("example.com"), None);
+    }
+
+    #[test]
+    fn test_read_rule_set_inline_and_files() {
+        let dir = std::env::temp_dir().join(format!("clashctl-rule-set-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("ruleset")).unwrap();
+        std::fs::write(dir.join("ruleset/ads.yaml"), "payload:\n  - '+.ads.com'\n").unwrap();
+        std::fs::write(
+            dir.join("ruleset/cn.list"),
+            "# cn\n10.0.0.0/8\n\n1.2.3.0/24\n",
+        )
+        .unwrap();
+        let config = b"rule-providers:\n  ads:\n    type: http\n    behavior: domain\n    path: ./ruleset/ads.yaml\n  cn:\n    type: file\n    behavior: ipcidr\n    format: text\n    path: ruleset/cn.list\n  mine:\n    type: inline\n    behavior: domain\n    payload:\n      - example.com\n  bin:\n    type: http\n    behavior: domain\n    format: mrs\n    path: ./bin.mrs\n";
+
+        let ads = read_rule_set(config, &dir, "ads").unwrap();
+        assert_eq!(ads.entries, vec!["+.ads.com"]);
+        assert_eq!(ads.behavior, "domain");
+        let cn = read_rule_set(config, &dir, "cn").unwrap();
+        assert_eq!(cn.entries, vec!["10.0.0.0/8", "1.2.3.0/24"]);
+        let mine = read_rule_set(config, &dir, "mine").unwrap();
+        assert_eq!((mine.source.as_str(), mine.entries.len()), ("inline", 1));
+        assert!(read_rule_set(config, &dir, "bin").is_err());
+        assert!(read_rule_set(config, &dir, "missing").is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,105 @@
+use anyhow::{Context, Result};
+use std::sync::Mutex;
+
+/// Environment variables the secret is read from, first set wins
+pub const ENV_VARS: [&str; 2] = ["CLASHCTL_SECRET", "CLASH_SECRET"];
+
+/// Keychain service name entries are stored under
+const SERVICE: &str = "clashctl";
+
+/// Prefix of the `secret` value recorded in config.yaml for a keychain entry
+const REFERENCE_PREFIX: &str = "keychain:";
+
+/// Last (account, secret) pair read from or written to the keychain,
+/// so saving the config doesn't rewrite an unchanged entry
+static LAST_STORED: Mutex<Option<(String, String)>> = Mutex::new(None);
+
+/// Secret given by `CLASHCTL_SECRET` or `CLASH_SECRET`
+pub fn from_env() -> Option<String> {
+    ENV_VARS
+        .iter()
+        .filter_map(std::env::var_os)
+        .map(|value| value.to_string_lossy().trim().to_string())
+        .find(|value| !value.is_empty())
+}
+
+/// The config value standing in for the secret of `account`
+pub fn reference(account: &str) -> String {
+    format!("{}{}", REFERENCE_PREFIX, account)
+}
+
+/// The account a config value refers to, if it is a keychain reference
+pub fn parse_reference(value: &str) -> Option<&str> {
+    value
+        .strip_prefix(REFERENCE_PREFIX)
+        .filter(|account| !account.is_empty())
+}
+
+/// Store `secret` for `account` (the API URL) in the system keychain
+pub fn store(account: &str, secret: &str) -> Result<()> {
+    if is_cached(account, secret) {
+        return Ok(());
+    }
+    entry(account)?
+        .set_password(secret)
+        .context("Failed to store the secret in the keychain")?;
+    cache(account, secret);
+    Ok(())
+}
+
+/// Read the secret of `account` from the system keychain
+pub fn load(account: &str) -> Result<String> {
+    let secret = entry(account)?.get_password().with_context(|| {
+        format!(
+            "Failed to read the secret for {} from the keychain",
+            account
+        )
+    })?;
+    cache(account, &secret);
+    Ok(secret)
+}
+
+/// Remove the keychain entry of `account`; a missing entry is not an error
+pub fn delete(account: &str) -> Result<()> {
+    if let Ok(mut last) = LAST_STORED.lock() {
+        *last = None;
+    }
+    match entry(account)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e).context("Failed to remove the secret from the keychain"),
+    }
+}
+
+fn entry(account: &str) -> Result<keyring::Entry> {
+    keyring::Entry::new(SERVICE, account).context("Keychain is not available")
+}
+
+fn is_cached(account: &str, secret: &str) -> bool {
+    LAST_STORED
+        .lock()
+        .map(|last| {
+            last.as_ref()
+                .is_some_and(|(a, s)| a.as_str() == account && s.as_str() == secret)
+        })
+        .unwrap_or(false)
+}
+
+fn cache(account: &str, secret: &str) {
+    if let Ok(mut last) = LAST_STORED.lock() {
+        *last = Some((account.to_string(), secret.to_string()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reference_round_trips() {
+        let value = reference("http://127.0.0.1:9090");
+        assert_eq!(value, "keychain:http://127.0.0.1:9090");
+        assert_eq!(parse_reference(&value), Some("http://127.0.0.1:9090"));
+        assert_eq!(parse_reference("keychain:"), None);
+        assert_eq!(parse_reference("plain-secret"), None);
+    }
+}
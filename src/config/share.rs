@@ -0,0 +1,363 @@
+use anyhow::Result;
+use base64::Engine;
+use serde_yaml::{Mapping, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use url::Url;
+
+/// Proxy entries from a Clash config plus the YAML caches of its proxy providers
+pub fn load_proxies(config_path: &Path) -> Result<Vec<Mapping>> {
+    let root: Value = serde_yaml::from_slice(&fs::read(config_path)?)?;
+    let mut proxies = proxies_of(&root);
+
+    let base_dir = config_path.parent().unwrap_or(Path::new("."));
+    let providers = root
+        .get("proxy-providers")
+        .and_then(Value::as_mapping)
+        .into_iter()
+        .flat_map(|providers| providers.values());
+    for provider in providers {
+        let Some(path) = provider.get("path").and_then(Value::as_str) else {
+            continue;
+        };
+        // Raw (non-YAML) provider caches are skipped
+        let cached = fs::read(base_dir.join(path))
+            .ok()
+            .and_then(|bytes| serde_yaml::from_slice::<Value>(&bytes).ok());
+        if let Some(cached) = cached {
+            proxies.extend(proxies_of(&cached));
+        }
+    }
+
+    Ok(proxies)
+}
+
+fn proxies_of(root: &Value) -> Vec<Mapping> {
+    root.get("proxies")
+        .and_then(Value::as_sequence)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(Value::as_mapping)
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Share links for the named proxies (all proxies when `names` is None)
+/// Returns the links and how many proxies couldn't be converted
+pub fn export_links(proxies: &[Mapping], names: Option<&[String]>) -> (Vec<String>, usize) {
+    let mut links = Vec::new();
+    let mut skipped = 0;
+    for proxy in proxies {
+        let wanted = match (names, get_str(proxy, "name")) {
+            (None, _) => true,
+            (Some(names), Some(name)) => names.contains(&name),
+            (Some(_), None) => false,
+        };
+        if !wanted {
+            continue;
+        }
+        match proxy_to_link(proxy) {
+            Some(link) => links.push(link),
+            None => skipped += 1,
+        }
+    }
+    (links, skipped)
+}
+
+/// Base64 subscription blob, one link per line
+pub fn encode_subscription(links: &[String]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(links.join("\n"))
+}
+
+/// Where an export named `label` is written
+pub fn export_path(label: &str) -> Result<PathBuf> {
+    let file_name: String = label
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    Ok(super::paths::config_dir()?
+        .join("exports")
+        .join(format!("{}.txt", file_name)))
+}
+
+/// Share link (ss://, vmess://, vless://, trojan://) for a Clash proxy entry
+/// Returns None for other proxy types or entries missing required fields
+pub fn proxy_to_link(proxy: &Mapping) -> Option<String> {
+    match get_str(proxy, "type")?.to_lowercase().as_str() {
+        "ss" => ss_link(proxy),
+        "vmess" => vmess_link(proxy),
+        "vless" => vless_link(proxy),
+        "trojan" => trojan_link(proxy),
+        _ => None,
+    }
+}
+
+fn get<'a>(map: &'a Mapping, key: &str) -> Option<&'a Value> {
+    map.get(Value::String(key.to_string()))
+}
+
+fn get_str(map: &Mapping, key: &str) -> Option<String> {
+    match get(map, key)? {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+fn get_bool(map: &Mapping, key: &str) -> bool {
+    get(map, key).and_then(Value::as_bool).unwrap_or(false)
+}
+
+fn get_list(map: &Mapping, key: &str) -> Option<String> {
+    let items: Vec<String> = get(map, key)?
+        .as_sequence()?
+        .iter()
+        .filter_map(|item| item.as_str().map(str::to_string))
+        .collect();
+    (!items.is_empty()).then(|| items.join(","))
+}
+
+/// ws/grpc transport options as link parameters: path, host and serviceName
+fn transport_params(proxy: &Mapping) -> Vec<(&'static str, String)> {
+    let mut params = Vec::new();
+    if let Some(ws) = get(proxy, "ws-opts").and_then(Value::as_mapping) {
+        if let Some(path) = get_str(ws, "path") {
+            params.push(("path", path));
+        }
+        if let Some(host) = get(ws, "headers")
+            .and_then(Value::as_mapping)
+            .and_then(|headers| get_str(headers, "Host"))
+        {
+            params.push(("host", host));
+        }
+    }
+    if let Some(service) = get(proxy, "grpc-opts")
+        .and_then(Value::as_mapping)
+        .and_then(|grpc| get_str(grpc, "grpc-service-name"))
+    {
+        params.push(("serviceName", service));
+    }
+    params
+}
+
+/// `scheme://user@server:port?query#name`
+fn build_url(
+    scheme: &str,
+    user: &str,
+    proxy: &Mapping,
+    params: &[(&str, String)],
+) -> Option<String> {
+    let server = get_str(proxy, "server")?;
+    let port = get_str(proxy, "port")?;
+    let host = if server.contains(':') {
+        format!("[{}]", server)
+    } else {
+        server
+    };
+    let mut url = Url::parse(&format!("{}://{}:{}", scheme, host, port)).ok()?;
+    url.set_username(user).ok()?;
+    if !params.is_empty() {
+        url.query_pairs_mut().extend_pairs(params);
+    }
+    url.set_fragment(get_str(proxy, "name").as_deref());
+    Some(url.to_string())
+}
+
+fn ss_link(proxy: &Mapping) -> Option<String> {
+    let userinfo = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(format!(
+        "{}:{}",
+        get_str(proxy, "cipher")?,
+        get_str(proxy, "password")?
+    ));
+
+    let mut params = Vec::new();
+    if let Some(plugin) = get_str(proxy, "plugin") {
+        let opts = match get(proxy, "plugin-opts") {
+            Some(Value::String(opts)) => Some(opts.clone()),
+            Some(Value::Mapping(opts)) => Some(
+                opts.iter()
+                    .filter_map(|(key, value)| {
+                        let key = key.as_str()?;
+                        let value = match value {
+                            Value::String(s) => s.clone(),
+                            Value::Number(n) => n.to_string(),
+                            Value::Bool(b) => b.to_string(),
+                            _ => return None,
+                        };
+                        Some(format!("{}={}", key, value))
+                    })
+                    .collect::<Vec<_>>()
+                    .join(";"),
+            ),
+            _ => None,
+        };
+        let value = match opts.filter(|opts| !opts.is_empty()) {
+            Some(opts) => format!("{};{}", plugin, opts),
+            None => plugin,
+        };
+        params.push(("plugin", value));
+    }
+
+    build_url("ss", &userinfo, proxy, &params)
+}
+
+fn vmess_link(proxy: &Mapping) -> Option<String> {
+    let network = get_str(proxy, "network").unwrap_or_else(|| "tcp".to_string());
+    let transport: std::collections::HashMap<_, _> = transport_params(proxy).into_iter().collect();
+    let path = transport
+        .get("path")
+        .or_else(|| transport.get("serviceName"))
+        .cloned()
+        .unwrap_or_default();
+
+    let json = serde_json::json!({
+        "v": "2",
+        "ps": get_str(proxy, "name").unwrap_or_default(),
+        "add": get_str(proxy, "server")?,
+        "port": get_str(proxy, "port")?,
+        "id": get_str(proxy, "uuid")?,
+        "aid": get_str(proxy, "alterId").unwrap_or_else(|| "0".to_string()),
+        "scy": get_str(proxy, "cipher").unwrap_or_else(|| "auto".to_string()),
+        "net": network,
+        "type": "none",
+        "host": transport.get("host").cloned().unwrap_or_default(),
+        "path": path,
+        "tls": if get_bool(proxy, "tls") { "tls" } else { "" },
+        "sni": get_str(proxy, "servername").unwrap_or_default(),
+        "alpn": get_list(proxy, "alpn").unwrap_or_default(),
+    });
+    Some(format!(
+        "vmess://{}",
+        base64::engine::general_purpose::STANDARD.encode(json.to_string())
+    ))
+}
+
+fn vless_link(proxy: &Mapping) -> Option<String> {
+    let uuid = get_str(proxy, "uuid")?;
+    let reality = get(proxy, "reality-opts").and_then(Value::as_mapping);
+    let security = if reality.is_some() {
+        "reality"
+    } else if get_bool(proxy, "tls") {
+        "tls"
+    } else {
+        "none"
+    };
+
+    let mut params = vec![
+        (
+            "type",
+            get_str(proxy, "network").unwrap_or_else(|| "tcp".to_string()),
+        ),
+        ("security", security.to_string()),
+    ];
+    for (param, key) in [
+        ("sni", "servername"),
+        ("flow", "flow"),
+        ("encryption", "encryption"),
+    ] {
+        if let Some(value) = get_str(proxy, key) {
+            params.push((param, value));
+        }
+    }
+    if let Some(reality) = reality {
+        for (param, key) in [
+            ("pbk", "public-key"),
+            ("sid", "short-id"),
+            ("spx", "spider-x"),
+            ("fp", "fingerprint"),
+        ] {
+            if let Some(value) = get_str(reality, key) {
+                params.push((param, value));
+            }
+        }
+    }
+    if let Some(alpn) = get_list(proxy, "alpn") {
+        params.push(("alpn", alpn));
+    }
+    if get_bool(proxy, "udp") {
+        params.push(("udp", "1".to_string()));
+    }
+    params.extend(transport_params(proxy));
+
+    build_url("vless", &uuid, proxy, &params)
+}
+
+fn trojan_link(proxy: &Mapping) -> Option<String> {
+    let password = get_str(proxy, "password")?;
+
+    let mut params = Vec::new();
+    if let Some(network) = get_str(proxy, "network") {
+        params.push(("type", network));
+    }
+    if let Some(sni) = get_str(proxy, "sni") {
+        params.push(("sni", sni));
+    }
+    if let Some(alpn) = get_list(proxy, "alpn") {
+        params.push(("alpn", alpn));
+    }
+    if get_bool(proxy, "skip-cert-verify") {
+        params.push(("allowInsecure", "1".to_string()));
+    }
+    if get_bool(proxy, "udp") {
+        params.push(("udp", "1".to_string()));
+    }
+    params.extend(transport_params(proxy));
+
+    build_url("trojan", &password, proxy, &params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proxy(yaml: &str) -> Mapping {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_share_links() {
+        let ss = proxy("{name: HK 01, type: ss, server: hk.example.com, port: 8388, cipher: aes-128-gcm, password: pass}");
+        assert_eq!(
+            proxy_to_link(&ss).unwrap(),
+            "ss://YWVzLTEyOC1nY206cGFzcw@hk.example.com:8388#HK%2001"
+        );
+
+        let trojan = proxy(
+            "{name: JP, type: trojan, server: jp.example.com, port: 443, password: 'p@ss', sni: jp.example.com, udp: true}",
+        );
+        let link = Url::parse(&proxy_to_link(&trojan).unwrap()).unwrap();
+        assert_eq!(link.scheme(), "trojan");
+        assert_eq!(link.username(), "p%40ss");
+        assert_eq!(link.port(), Some(443));
+        assert_eq!(link.query(), Some("sni=jp.example.com&udp=1"));
+
+        let vmess = proxy(
+            "{name: US, type: vmess, server: us.example.com, port: 443, uuid: abc, tls: true, network: ws, ws-opts: {path: /ws}}",
+        );
+        let link = proxy_to_link(&vmess).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(
+            &base64::engine::general_purpose::STANDARD
+                .decode(link.strip_prefix("vmess://").unwrap())
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(json["add"], "us.example.com");
+        assert_eq!(json["net"], "ws");
+        assert_eq!(json["path"], "/ws");
+        assert_eq!(json["tls"], "tls");
+
+        let (links, skipped) = export_links(
+            &[
+                ss,
+                proxy("{name: W, type: wireguard, server: w.com, port: 1}"),
+            ],
+            None,
+        );
+        assert_eq!(links.len(), 1);
+        assert_eq!(skipped, 1);
+    }
+}
@@ -0,0 +1,143 @@
+use anyhow::{Context, Result};
+use chrono::{Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::atomic::write_atomic;
+
+/// A copy of the Clash config kept under a name until deleted, to return to a known good setup
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub name: String,
+    /// Creation time in unix milliseconds
+    pub created: i64,
+    /// Config the snapshot was taken of, and is restored to
+    pub source: PathBuf,
+    /// The saved copy
+    pub file: PathBuf,
+}
+
+impl Snapshot {
+    /// Creation time as local `YYYY-MM-DD HH:MM`
+    pub fn created_label(&self) -> String {
+        Local
+            .timestamp_millis_opt(self.created)
+            .single()
+            .map(|at| at.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default()
+    }
+}
+
+/// Named config snapshots in `snapshots` under the state dir
+/// Unlike backups they are never rotated out
+pub struct SnapshotStore {
+    dir: PathBuf,
+}
+
+impl SnapshotStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Open the store in the default location
+    pub fn open_default() -> Result<Self> {
+        Ok(Self::new(super::paths::state_dir()?.join("snapshots")))
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.dir.join("index.json")
+    }
+
+    /// Snapshots, newest first
+    pub fn list(&self) -> Result<Vec<Snapshot>> {
+        let path = self.index_path();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let mut snapshots: Vec<Snapshot> = serde_json::from_str(&fs::read_to_string(&path)?)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        snapshots.sort_by_key(|snapshot| std::cmp::Reverse(snapshot.created));
+        Ok(snapshots)
+    }
+
+    fn save(&self, snapshots: &[Snapshot]) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        write_atomic(&self.index_path(), serde_json::to_string_pretty(snapshots)?)
+    }
+
+    /// Copy `source` into the store as `name`; an empty name is replaced by the date
+    pub fn take(&self, name: &str, source: &Path) -> Result<Snapshot> {
+        let now = Local::now();
+        let name = match name.trim() {
+            "" => format!("Snapshot {}", now.format("%Y-%m-%d %H:%M")),
+            name => name.to_string(),
+        };
+        let mut snapshots = self.list()?;
+        fs::create_dir_all(&self.dir)?;
+        let stamp = now.format("%Y%m%d-%H%M%S%.3f").to_string();
+        let mut file = self.dir.join(format!("{}.yaml", stamp));
+        let mut attempt = 1;
+        while file.exists() {
+            file = self.dir.join(format!("{}-{}.yaml", stamp, attempt));
+            attempt += 1;
+        }
+        fs::copy(source, &file).with_context(|| format!("Failed to copy {}", source.display()))?;
+
+        let snapshot = Snapshot {
+            name,
+            created: now.timestamp_millis(),
+            source: source.to_path_buf(),
+            file,
+        };
+        snapshots.insert(0, snapshot.clone());
+        self.save(&snapshots)?;
+        Ok(snapshot)
+    }
+
+    /// Forget a snapshot and delete its copy
+    pub fn delete(&self, snapshot: &Snapshot) -> Result<()> {
+        let mut snapshots = self.list()?;
+        snapshots.retain(|kept| kept.file != snapshot.file);
+        self.save(&snapshots)?;
+        let _ = fs::remove_file(&snapshot.file);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_list_and_delete() {
+        let dir =
+            std::env::temp_dir().join(format!("clashctl-snapshot-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let config = dir.join("config.yaml");
+        let store = SnapshotStore::new(dir.join("snapshots"));
+
+        fs::write(&config, "v1").unwrap();
+        let first = store.take("Known good", &config).unwrap();
+        fs::write(&config, "v2").unwrap();
+        let second = store.take(" ", &config).unwrap();
+        assert!(second.name.starts_with("Snapshot "));
+
+        let snapshots = store.list().unwrap();
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!(snapshots[1], first);
+        assert_eq!(fs::read_to_string(&first.file).unwrap(), "v1");
+
+        store.delete(&first).unwrap();
+        assert_eq!(store.list().unwrap(), vec![second]);
+        assert!(!first.file.exists());
+
+        // A corrupt index is an error, not an empty store to write over
+        fs::write(dir.join("snapshots").join("index.json"), "[{").unwrap();
+        assert!(store.list().is_err());
+        assert!(store.take("Lost", &config).is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,458 @@
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use super::atomic::write_atomic;
+use crate::clash::ConnectionsResponse;
+
+/// Number of days shown in the daily usage chart
+pub const REPORT_DAYS: usize = 14;
+
+/// Days of records kept in the stats file
+/// The longest window a report looks at is the current month, so keep a little over a month
+pub const RETENTION_DAYS: i64 = 32;
+
+/// Maximum number of hosts kept per record
+const MAX_HOSTS_PER_RECORD: usize = 50;
+
+/// Traffic recorded during (part of) one hour
+/// The same hour may appear in several records; they are summed when loaded
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TrafficRecord {
+    /// Start of the hour as a unix timestamp
+    pub hour: i64,
+    pub upload: u64,
+    pub download: u64,
+    /// Bytes (up + down) per host
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub hosts: HashMap<String, u64>,
+}
+
+impl TrafficRecord {
+    fn is_empty(&self) -> bool {
+        self.upload == 0 && self.download == 0 && self.hosts.is_empty()
+    }
+}
+
+/// Truncate a unix timestamp to the start of its hour
+pub fn hour_of(timestamp: i64) -> i64 {
+    timestamp - timestamp.rem_euclid(3600)
+}
+
+/// Get the stats file path
+pub fn default_path() -> Result<PathBuf> {
+    Ok(super::paths::state_dir()?.join("stats.jsonl"))
+}
+
+/// Append a record to the stats file
+pub fn append(path: &Path, record: &TrafficRecord) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(record)?)?;
+    Ok(())
+}
+
+/// Load all records from the stats file, skipping malformed lines
+pub fn load(path: &Path) -> Result<Vec<TrafficRecord>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(path)?;
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Drop records older than `RETENTION_DAYS`, rewriting the file only when some were dropped
+pub fn prune(path: &Path, now: i64) -> Result<Vec<TrafficRecord>> {
+    let records = load(path)?;
+    let cutoff = now - RETENTION_DAYS * 86400;
+    let kept: Vec<TrafficRecord> = records
+        .iter()
+        .filter(|record| record.hour >= cutoff)
+        .cloned()
+        .collect();
+    if kept.len() < records.len() {
+        let mut content = String::new();
+        for record in &kept {
+            content.push_str(&serde_json::to_string(record)?);
+            content.push('\n');
+        }
+        write_atomic(path, content)?;
+    }
+    Ok(kept)
+}
+
+/// Accumulates traffic deltas between connection snapshots
+#[derive(Debug, Default)]
+pub struct TrafficRecorder {
+    last_upload: Option<u64>,
+    last_download: Option<u64>,
+    last_connections: HashMap<String, u64>,
+    current: Option<TrafficRecord>,
+}
+
+impl TrafficRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a snapshot taken at `timestamp`
+    /// Returns the previous hour's record once the hour rolls over
+    pub fn sample(&mut self, data: &ConnectionsResponse, timestamp: i64) -> Option<TrafficRecord> {
+        let hour = hour_of(timestamp);
+        let finished = match &self.current {
+            Some(current) if current.hour != hour => self.take(),
+            _ => None,
+        };
+        let current = self.current.get_or_insert_with(|| TrafficRecord {
+            hour,
+            ..Default::default()
+        });
+
+        // Counters reset when Clash restarts; count from zero in that case
+        let delta = |now: u64, last: Option<u64>| match last {
+            Some(last) if now >= last => now - last,
+            Some(_) => now,
+            None => 0,
+        };
+        current.upload += delta(data.upload_total, self.last_upload);
+        current.download += delta(data.download_total, self.last_download);
+
+        let first_sample = self.last_upload.is_none();
+        let mut connections = HashMap::with_capacity(data.connections.len());
+        for conn in &data.connections {
+            let bytes = conn.upload + conn.download;
+            let host = match conn.metadata.host.as_deref() {
+                Some(host) if !host.is_empty() => host,
+                _ => conn.metadata.destination_ip.as_str(),
+            };
+            let previous = match self.last_connections.get(&conn.id) {
+                Some(previous) => Some(*previous),
+                // Traffic of connections opened before the first sample is unknown
+                None if first_sample => Some(bytes),
+                None => None,
+            };
+            let delta = bytes.saturating_sub(previous.unwrap_or(0));
+            if delta > 0 && !host.is_empty() {
+                *current.hosts.entry(host.to_string()).or_insert(0) += delta;
+            }
+            connections.insert(conn.id.clone(), bytes);
+        }
+
+        self.last_upload = Some(data.upload_total);
+        self.last_download = Some(data.download_total);
+        self.last_connections = connections;
+
+        finished
+    }
+
+    /// Take the traffic accumulated so far, if any
+    pub fn take(&mut self) -> Option<TrafficRecord> {
+        let mut record = self.current.take().filter(|record| !record.is_empty())?;
+        if record.hosts.len() > MAX_HOSTS_PER_RECORD {
+            let mut hosts: Vec<(String, u64)> = record.hosts.drain().collect();
+            hosts.sort_by_key(|(_, bytes)| std::cmp::Reverse(*bytes));
+            hosts.truncate(MAX_HOSTS_PER_RECORD);
+            record.hosts = hosts.into_iter().collect();
+        }
+        Some(record)
+    }
+}
+
+/// Connections and traffic matched by one rule
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RuleHits {
+    /// Number of connections that matched the rule
+    pub hits: u64,
+    pub upload: u64,
+    pub download: u64,
+}
+
+impl RuleHits {
+    pub fn bytes(&self) -> u64 {
+        self.upload + self.download
+    }
+}
+
+/// Per-rule hit counters built from connection snapshots during this session
+#[derive(Debug, Clone, Default)]
+pub struct RuleStats {
+    /// Keyed by (lowercased rule type, payload)
+    rules: HashMap<(String, String), RuleHits>,
+    /// Bytes (up, down) per connection id at the previous sample
+    last_connections: HashMap<String, (u64, u64)>,
+    /// Unix timestamp of the first sample
+    pub since: Option<i64>,
+}
+
+impl RuleStats {
+    fn key(rule_type: &str, payload: &str) -> (String, String) {
+        (rule_type.to_ascii_lowercase(), payload.to_string())
+    }
+
+    /// Add a snapshot taken at `timestamp`
+    /// A connection counts as a hit the first time it is seen
+    pub fn sample(&mut self, data: &ConnectionsResponse, timestamp: i64) {
+        self.since.get_or_insert(timestamp);
+
+        let mut connections = HashMap::with_capacity(data.connections.len());
+        for conn in &data.connections {
+            let key = Self::key(&conn.rule, conn.rule_payload.as_deref().unwrap_or(""));
+            let entry = self.rules.entry(key).or_default();
+            let (last_upload, last_download) = match self.last_connections.get(&conn.id) {
+                Some(last) => *last,
+                None => {
+                    entry.hits += 1;
+                    (0, 0)
+                }
+            };
+            entry.upload += conn.upload.saturating_sub(last_upload);
+            entry.download += conn.download.saturating_sub(last_download);
+            connections.insert(conn.id.clone(), (conn.upload, conn.download));
+        }
+        self.last_connections = connections;
+    }
+
+    /// Hits recorded for a rule from `GET /rules`
+    pub fn get(&self, rule_type: &str, payload: &str) -> RuleHits {
+        self.rules
+            .get(&Self::key(rule_type, payload))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// Upload/download byte counts
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Usage {
+    pub upload: u64,
+    pub download: u64,
+}
+
+impl Usage {
+    pub fn total(&self) -> u64 {
+        self.upload + self.download
+    }
+
+    fn add(&mut self, record: &TrafficRecord) {
+        self.upload += record.upload;
+        self.download += record.download;
+    }
+}
+
+/// Usage summary built from the stats file
+#[derive(Debug, Clone, Default)]
+pub struct UsageReport {
+    /// Per-day usage for the last `REPORT_DAYS` days, oldest first
+    pub daily: Vec<(NaiveDate, Usage)>,
+    pub today: Usage,
+    /// Last 7 days including today
+    pub week: Usage,
+    /// Current calendar month
+    pub month: Usage,
+    /// Hosts with the most traffic this month, largest first
+    pub top_hosts: Vec<(String, u64)>,
+}
+
+impl UsageReport {
+    pub fn from_records(records: &[TrafficRecord], now: DateTime<Local>) -> Self {
+        let today = now.date_naive();
+        let first_day = today - Duration::days(REPORT_DAYS as i64 - 1);
+        let week_start = today - Duration::days(6);
+        let month_start = today.with_day(1).unwrap_or(today);
+
+        let mut report = UsageReport {
+            daily: (0..REPORT_DAYS as i64)
+                .map(|offset| (first_day + Duration::days(offset), Usage::default()))
+                .collect(),
+            ..Default::default()
+        };
+        let mut hosts: HashMap<&str, u64> = HashMap::new();
+
+        for record in records {
+            let date = match Local.timestamp_opt(record.hour, 0).single() {
+                Some(time) => time.date_naive(),
+                None => continue,
+            };
+            if date > today {
+                continue;
+            }
+            if date >= first_day {
+                let index = (date - first_day).num_days() as usize;
+                report.daily[index].1.add(record);
+            }
+            if date == today {
+                report.today.add(record);
+            }
+            if date >= week_start {
+                report.week.add(record);
+            }
+            if date >= month_start {
+                report.month.add(record);
+                for (host, bytes) in &record.hosts {
+                    *hosts.entry(host.as_str()).or_insert(0) += bytes;
+                }
+            }
+        }
+
+        let mut top_hosts: Vec<(String, u64)> = hosts
+            .into_iter()
+            .map(|(host, bytes)| (host.to_string(), bytes))
+            .collect();
+        top_hosts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        top_hosts.truncate(10);
+        report.top_hosts = top_hosts;
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clash::{Connection, ConnectionMetadata};
+
+    fn snapshot(upload: u64, download: u64, conns: &[(&str, &str, u64)]) -> ConnectionsResponse {
+        ConnectionsResponse {
+            upload_total: upload,
+            download_total: download,
+            connections: conns
+                .iter()
+                .map(|(id, host, bytes)| Connection {
+                    id: id.to_string(),
+                    metadata: ConnectionMetadata {
+                        network: "tcp".to_string(),
+                        conn_type: "HTTP".to_string(),
+                        source_ip: String::new(),
+                        destination_ip: "1.2.3.4".to_string(),
+                        source_port: String::new(),
+                        destination_port: "443".to_string(),
+                        host: Some(host.to_string()),
+                        dns_mode: None,
+                        process: None,
+                        process_path: None,
+                    },
+                    upload: 0,
+                    download: *bytes,
+                    start: String::new(),
+                    chains: Vec::new(),
+                    rule: String::new(),
+                    rule_payload: None,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_recorder_counts_deltas_per_hour() {
+        let mut recorder = TrafficRecorder::new();
+        assert!(recorder
+            .sample(&snapshot(100, 1000, &[("a", "x.com", 500)]), 7200)
+            .is_none());
+        assert!(recorder
+            .sample(
+                &snapshot(150, 1600, &[("a", "x.com", 800), ("b", "y.com", 200)]),
+                7210
+            )
+            .is_none());
+
+        let finished = recorder
+            .sample(&snapshot(160, 1700, &[("b", "y.com", 300)]), 10800)
+            .unwrap();
+        assert_eq!(finished.hour, 7200);
+        assert_eq!(finished.upload, 50);
+        assert_eq!(finished.download, 600);
+        assert_eq!(finished.hosts.get("x.com"), Some(&300));
+        assert_eq!(finished.hosts.get("y.com"), Some(&200));
+
+        let current = recorder.take().unwrap();
+        assert_eq!(current.hour, 10800);
+        assert_eq!(current.upload, 10);
+        assert_eq!(current.hosts.get("y.com"), Some(&100));
+    }
+
+    #[test]
+    fn test_rule_stats() {
+        let with_rule = |mut data: ConnectionsResponse| {
+            for conn in &mut data.connections {
+                conn.rule = "DomainSuffix".to_string();
+                conn.rule_payload = Some(conn.metadata.host.clone().unwrap_or_default());
+            }
+            data
+        };
+        let mut stats = RuleStats::default();
+        stats.sample(&with_rule(snapshot(0, 0, &[("a", "x.com", 500)])), 100);
+        stats.sample(
+            &with_rule(snapshot(0, 0, &[("a", "x.com", 800), ("b", "x.com", 100)])),
+            110,
+        );
+        stats.sample(&with_rule(snapshot(0, 0, &[("c", "y.com", 50)])), 120);
+
+        let x = stats.get("DOMAINSUFFIX", "x.com");
+        assert_eq!(x.hits, 2);
+        assert_eq!(x.download, 900);
+        assert_eq!(stats.get("DomainSuffix", "y.com").hits, 1);
+        assert_eq!(stats.get("Match", "").hits, 0);
+        assert_eq!(stats.since, Some(100));
+    }
+
+    #[test]
+    fn test_usage_report() {
+        let now = Local.with_ymd_and_hms(2024, 5, 20, 12, 0, 0).unwrap();
+        let at = |day: u32, bytes: u64, host: &str| TrafficRecord {
+            hour: Local
+                .with_ymd_and_hms(2024, 5, day, 10, 0, 0)
+                .unwrap()
+                .timestamp(),
+            upload: bytes,
+            download: bytes * 10,
+            hosts: HashMap::from([(host.to_string(), bytes * 11)]),
+        };
+        let records = vec![
+            at(20, 1, "a"),
+            at(20, 2, "b"),
+            at(15, 4, "b"),
+            at(1, 8, "c"),
+        ];
+
+        let report = UsageReport::from_records(&records, now);
+        assert_eq!(report.daily.len(), REPORT_DAYS);
+        assert_eq!(report.daily.last().unwrap().1.upload, 3);
+        assert_eq!(report.today.total(), 33);
+        assert_eq!(report.week.upload, 7);
+        assert_eq!(report.month.upload, 15);
+        assert_eq!(report.top_hosts[0], ("c".to_string(), 88));
+        assert_eq!(report.top_hosts[1], ("b".to_string(), 66));
+    }
+
+    #[test]
+    fn test_prune_drops_old_records() {
+        let path = std::env::temp_dir().join(format!(
+            "clashctl-stats-prune-test-{}.jsonl",
+            std::process::id()
+        ));
+        let now = 1_700_000_000;
+        let record = |hour: i64| TrafficRecord {
+            hour,
+            upload: 1,
+            ..Default::default()
+        };
+        append(&path, &record(now - (RETENTION_DAYS + 1) * 86400)).unwrap();
+        append(&path, &record(hour_of(now))).unwrap();
+
+        let kept = prune(&path, now).unwrap();
+        let reloaded = load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(kept, vec![record(hour_of(now))]);
+        assert_eq!(reloaded, kept);
+    }
+}
@@ -0,0 +1,99 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Template used when no base config is chosen and the work config is missing
+pub const DEFAULT_TEMPLATE: &str = "minimal";
+
+/// Base configs shipped with clashctl, as (name, YAML)
+const BUILTIN: [(&str, &str); 3] = [
+    ("minimal", include_str!("templates/minimal.yaml")),
+    ("global", include_str!("templates/global.yaml")),
+    ("whitelist-cn", include_str!("templates/whitelist-cn.yaml")),
+];
+
+/// User templates in `~/.config/clashctl/templates/<name>.yaml`
+pub fn templates_dir() -> Result<PathBuf> {
+    Ok(super::paths::config_dir()?.join("templates"))
+}
+
+/// Names of all templates: built-in ones first, then user templates sorted by name
+/// A user template with a built-in name replaces it instead of being listed twice
+pub fn names() -> Vec<String> {
+    let mut names: Vec<String> = BUILTIN.iter().map(|(name, _)| name.to_string()).collect();
+    if let Ok(dir) = templates_dir() {
+        names.extend(user_template_names(&dir));
+    }
+    names
+}
+
+fn user_template_names(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let is_yaml = matches!(
+                path.extension().and_then(|ext| ext.to_str()),
+                Some("yaml" | "yml")
+            );
+            if !is_yaml {
+                return None;
+            }
+            path.file_stem()?.to_str().map(str::to_string)
+        })
+        .filter(|name| !BUILTIN.iter().any(|(builtin, _)| builtin == name))
+        .collect();
+    names.sort();
+    names
+}
+
+/// YAML of a template, preferring the user's copy over the built-in one
+pub fn load(name: &str) -> Result<Vec<u8>> {
+    if let Ok(dir) = templates_dir() {
+        for ext in ["yaml", "yml"] {
+            let path = dir.join(format!("{}.{}", name, ext));
+            if path.is_file() {
+                return fs::read(&path)
+                    .with_context(|| format!("Failed to read template {}", path.display()));
+            }
+        }
+    }
+    BUILTIN
+        .iter()
+        .find(|(builtin, _)| *builtin == name)
+        .map(|(_, yaml)| yaml.as_bytes().to_vec())
+        .ok_or_else(|| anyhow::anyhow!("Unknown profile template '{}'", name))
+}
+
+/// Base config a raw subscription is converted onto
+/// The chosen template wins; otherwise the work config, or the default template when it is missing
+pub fn base_config(template: Option<&str>, work_config: Option<&Path>) -> Result<Vec<u8>> {
+    if let Some(name) = template {
+        return load(name);
+    }
+    match work_config.filter(|path| path.is_file()) {
+        Some(path) => {
+            fs::read(path).with_context(|| format!("Failed to read base config {}", path.display()))
+        }
+        None => load(DEFAULT_TEMPLATE),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::validate;
+
+    #[test]
+    fn test_builtin_templates_are_valid() {
+        for (name, yaml) in BUILTIN {
+            let problems = validate::validate_config(yaml.as_bytes());
+            assert!(!validate::has_errors(&problems), "{}: {:?}", name, problems);
+        }
+        // Without a work config the default template is used
+        let missing = Path::new("/nonexistent/work/config.yaml");
+        assert!(base_config(None, Some(missing)).is_ok());
+        assert!(load("no-such-template").is_err());
+    }
+}
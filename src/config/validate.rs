@@ -0,0 +1,319 @@
+use serde_yaml::{Mapping, Value};
+use std::collections::{HashMap, HashSet};
+
+/// Proxy names built into the core
+const BUILTIN_PROXIES: [&str; 6] = [
+    "DIRECT",
+    "REJECT",
+    "REJECT-DROP",
+    "PASS",
+    "COMPATIBLE",
+    "GLOBAL",
+];
+
+/// Rule types whose payload contains nested rules, skipped when checking targets
+const NESTED_RULE_TYPES: [&str; 4] = ["AND", "OR", "NOT", "SUB-RULE"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    /// The core would reject the config
+    Error,
+    /// Likely a mistake, but the config still loads
+    Warning,
+}
+
+/// A problem found while validating a Clash config
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigProblem {
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl ConfigProblem {
+    fn error(message: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            message,
+        }
+    }
+
+    fn warning(message: String) -> Self {
+        Self {
+            severity: Severity::Warning,
+            message,
+        }
+    }
+}
+
+/// Whether any of the problems would make the core reject the config
+pub fn has_errors(problems: &[ConfigProblem]) -> bool {
+    problems.iter().any(|p| p.severity == Severity::Error)
+}
+
+/// Fields each proxy type needs besides `name` and `type`
+fn required_fields(proxy_type: &str) -> Option<&'static [&'static str]> {
+    let fields: &'static [&'static str] = match proxy_type {
+        "ss" => &["server", "port", "cipher", "password"],
+        "ssr" => &["server", "port", "cipher", "password", "obfs", "protocol"],
+        "vmess" | "vless" => &["server", "port", "uuid"],
+        "trojan" => &["server", "port", "password"],
+        "snell" => &["server", "port", "psk"],
+        "ssh" => &["server", "port", "username"],
+        "wireguard" => &["private-key"],
+        "hysteria" | "hysteria2" | "tuic" | "socks5" | "http" | "mieru" | "anytls" => {
+            &["server", "port"]
+        }
+        "direct" | "dns" | "reject" => &[],
+        _ => return None,
+    };
+    Some(fields)
+}
+
+fn get<'a>(map: &'a Mapping, key: &str) -> Option<&'a Value> {
+    map.get(Value::String(key.to_string()))
+}
+
+fn get_str<'a>(map: &'a Mapping, key: &str) -> Option<&'a str> {
+    get(map, key).and_then(Value::as_str)
+}
+
+fn is_missing(value: Option<&Value>) -> bool {
+    match value {
+        None | Some(Value::Null) => true,
+        Some(Value::String(s)) => s.trim().is_empty(),
+        Some(_) => false,
+    }
+}
+
+/// Check a Clash config for problems the core would reject on reload:
+/// missing required proxy fields, duplicate names and references to
+/// proxies, groups or providers that don't exist
+pub fn validate_config(bytes: &[u8]) -> Vec<ConfigProblem> {
+    let value: Value = match serde_yaml::from_slice(bytes) {
+        Ok(value) => value,
+        Err(e) => return vec![ConfigProblem::error(format!("Invalid YAML: {}", e))],
+    };
+    let Some(root) = value.as_mapping() else {
+        return vec![ConfigProblem::error(
+            "Config is not a YAML mapping".to_string(),
+        )];
+    };
+
+    let mut problems = Vec::new();
+    // Name -> where it was first defined
+    let mut names: HashMap<String, &'static str> = HashMap::new();
+
+    let proxies = get(root, "proxies").and_then(Value::as_sequence);
+    for (index, proxy) in proxies.into_iter().flatten().enumerate() {
+        let Some(proxy) = proxy.as_mapping() else {
+            problems.push(ConfigProblem::error(format!(
+                "Proxy #{} is not a mapping",
+                index + 1
+            )));
+            continue;
+        };
+        let Some(name) = get_str(proxy, "name").filter(|name| !name.trim().is_empty()) else {
+            problems.push(ConfigProblem::error(format!(
+                "Proxy #{} has no name",
+                index + 1
+            )));
+            continue;
+        };
+        if names.insert(name.to_string(), "proxy").is_some() {
+            problems.push(ConfigProblem::error(format!(
+                "Duplicate proxy name '{}'",
+                name
+            )));
+        }
+
+        let Some(proxy_type) = get_str(proxy, "type") else {
+            problems.push(ConfigProblem::error(format!(
+                "Proxy '{}' has no type",
+                name
+            )));
+            continue;
+        };
+        match required_fields(&proxy_type.to_lowercase()) {
+            Some(fields) => {
+                let missing: Vec<&str> = fields
+                    .iter()
+                    .copied()
+                    .filter(|field| is_missing(get(proxy, field)))
+                    .collect();
+                if !missing.is_empty() {
+                    problems.push(ConfigProblem::error(format!(
+                        "Proxy '{}' ({}) is missing {}",
+                        name,
+                        proxy_type,
+                        missing.join(", ")
+                    )));
+                }
+            }
+            None => problems.push(ConfigProblem::warning(format!(
+                "Proxy '{}' has unknown type '{}'",
+                name, proxy_type
+            ))),
+        }
+    }
+
+    let providers: HashSet<&str> = get(root, "proxy-providers")
+        .and_then(Value::as_mapping)
+        .map(|map| map.keys().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    let groups: Vec<&Mapping> = get(root, "proxy-groups")
+        .and_then(Value::as_sequence)
+        .map(|groups| groups.iter().filter_map(Value::as_mapping).collect())
+        .unwrap_or_default();
+
+    // Collect group names first so groups may reference groups defined later
+    for (index, group) in groups.iter().enumerate() {
+        match get_str(group, "name").filter(|name| !name.trim().is_empty()) {
+            Some(name) => match names.insert(name.to_string(), "group") {
+                Some("proxy") => problems.push(ConfigProblem::error(format!(
+                    "Group '{}' has the same name as a proxy",
+                    name
+                ))),
+                Some(_) => problems.push(ConfigProblem::error(format!(
+                    "Duplicate group name '{}'",
+                    name
+                ))),
+                None => {}
+            },
+            None => problems.push(ConfigProblem::error(format!(
+                "Proxy group #{} has no name",
+                index + 1
+            ))),
+        }
+    }
+
+    let exists = |name: &str| names.contains_key(name) || BUILTIN_PROXIES.contains(&name);
+
+    for group in &groups {
+        let Some(name) = get_str(group, "name") else {
+            continue;
+        };
+        if is_missing(get(group, "type")) {
+            problems.push(ConfigProblem::error(format!(
+                "Group '{}' has no type",
+                name
+            )));
+        }
+
+        let members: Vec<&str> = get(group, "proxies")
+            .and_then(Value::as_sequence)
+            .map(|items| items.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        for member in &members {
+            if !exists(member) {
+                problems.push(ConfigProblem::error(format!(
+                    "Group '{}' references missing proxy or group '{}'",
+                    name, member
+                )));
+            }
+        }
+
+        let uses: Vec<&str> = get(group, "use")
+            .and_then(Value::as_sequence)
+            .map(|items| items.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        for provider in &uses {
+            if !providers.contains(provider) {
+                problems.push(ConfigProblem::error(format!(
+                    "Group '{}' uses missing provider '{}'",
+                    name, provider
+                )));
+            }
+        }
+
+        let includes_all = [
+            "include-all",
+            "include-all-proxies",
+            "include-all-providers",
+        ]
+        .iter()
+        .any(|key| get(group, key).and_then(Value::as_bool) == Some(true));
+        if members.is_empty() && uses.is_empty() && !includes_all {
+            problems.push(ConfigProblem::error(format!(
+                "Group '{}' has no proxies",
+                name
+            )));
+        }
+    }
+
+    let rules = get(root, "rules").and_then(Value::as_sequence);
+    for rule in rules.into_iter().flatten().filter_map(Value::as_str) {
+        let parts: Vec<&str> = rule.split(',').map(str::trim).collect();
+        let rule_type = parts[0].to_uppercase();
+        if NESTED_RULE_TYPES.contains(&rule_type.as_str()) {
+            continue;
+        }
+        let target = if rule_type == "MATCH" {
+            parts.get(1)
+        } else {
+            parts.get(2)
+        };
+        match target {
+            Some(target) if !exists(target) => problems.push(ConfigProblem::error(format!(
+                "Rule '{}' targets missing proxy or group '{}'",
+                rule, target
+            ))),
+            Some(_) => {}
+            None => problems.push(ConfigProblem::error(format!(
+                "Rule '{}' has no target",
+                rule
+            ))),
+        }
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_config_has_no_problems() {
+        let config = r#"
+proxies:
+  - {name: a, type: ss, server: 1.1.1.1, port: 443, cipher: aes-128-gcm, password: x}
+  - {name: b, type: trojan, server: b.com, port: 443, password: y}
+proxy-providers:
+  sub: {type: http, url: "http://example.com"}
+proxy-groups:
+  - {name: Auto, type: url-test, proxies: [a, b]}
+  - {name: Proxy, type: select, proxies: [Auto, DIRECT], use: [sub]}
+rules:
+  - DOMAIN-SUFFIX,google.com,Proxy
+  - AND,((DOMAIN,a.com),(NETWORK,UDP)),REJECT
+  - MATCH,DIRECT
+"#;
+        assert!(validate_config(config.as_bytes()).is_empty());
+    }
+
+    #[test]
+    fn test_reports_problems() {
+        let config = r#"
+proxies:
+  - {name: a, type: vmess, server: 1.1.1.1, port: 443}
+  - {name: a, type: trojan, server: b.com, port: 443, password: y}
+proxy-groups:
+  - {name: Proxy, type: select, proxies: [a, missing], use: [nope]}
+  - {name: Empty, type: select}
+rules:
+  - DOMAIN,x.com,Gone
+"#;
+        let problems = validate_config(config.as_bytes());
+        let messages: Vec<&str> = problems.iter().map(|p| p.message.as_str()).collect();
+        assert!(has_errors(&problems));
+        assert!(messages.contains(&"Proxy 'a' (vmess) is missing uuid"));
+        assert!(messages.contains(&"Duplicate proxy name 'a'"));
+        assert!(messages.contains(&"Group 'Proxy' references missing proxy or group 'missing'"));
+        assert!(messages.contains(&"Group 'Proxy' uses missing provider 'nope'"));
+        assert!(messages.contains(&"Group 'Empty' has no proxies"));
+        assert!(
+            messages.contains(&"Rule 'DOMAIN,x.com,Gone' targets missing proxy or group 'Gone'")
+        );
+    }
+}
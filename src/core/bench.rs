@@ -0,0 +1,155 @@
+use std::time::{Duration, Instant};
+
+use crate::clash::ClashClient;
+
+/// Controller endpoints the TUI polls while monitoring
+pub const ENDPOINTS: &[&str] = &["/configs", "/proxies", "/connections"];
+/// Requests per endpoint unless `-n` says otherwise
+pub const DEFAULT_ITERATIONS: usize = 20;
+/// Time allowed for each request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// p90 latency under which every endpoint keeps up with live monitoring
+const LIVE_P90: Duration = Duration::from_millis(250);
+/// p90 latency under which monitoring works with a slower refresh interval
+const USABLE_P90: Duration = Duration::from_secs(1);
+
+/// Timings of one endpoint over the benchmark
+#[derive(Debug, Clone, Default)]
+pub struct EndpointBench {
+    pub path: &'static str,
+    /// Round trips of the successful requests, in the order they ran
+    pub latencies: Vec<Duration>,
+    /// Body bytes of the successful requests
+    pub bytes: usize,
+    pub failures: usize,
+    pub last_error: Option<String>,
+}
+
+impl EndpointBench {
+    /// Nearest-rank percentile (`p` in 0–100) of the successful round trips
+    pub fn percentile(&self, p: f64) -> Option<Duration> {
+        let mut sorted = self.latencies.clone();
+        sorted.sort();
+        let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+        sorted.get(rank.clamp(1, sorted.len().max(1)) - 1).copied()
+    }
+
+    /// Average body size in bytes
+    pub fn average_bytes(&self) -> usize {
+        self.bytes / self.latencies.len().max(1)
+    }
+
+    /// Body bytes per second of the time spent waiting on successful requests
+    pub fn throughput(&self) -> Option<f64> {
+        let total: Duration = self.latencies.iter().sum();
+        (!total.is_zero()).then(|| self.bytes as f64 / total.as_secs_f64())
+    }
+
+    /// Successful requests per second, one at a time
+    pub fn requests_per_sec(&self) -> Option<f64> {
+        let total: Duration = self.latencies.iter().sum();
+        (!total.is_zero()).then(|| self.latencies.len() as f64 / total.as_secs_f64())
+    }
+}
+
+/// How responsive the controller is for the TUI, judged by the slowest endpoint's p90
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Verdict {
+    Live,
+    /// Usable with a refresh interval of a few seconds
+    Slow,
+    TooSlow,
+}
+
+impl Verdict {
+    pub fn of(results: &[EndpointBench]) -> Self {
+        let worst = results
+            .iter()
+            .map(|result| {
+                if result.failures > 0 {
+                    Duration::MAX
+                } else {
+                    result.percentile(90.0).unwrap_or(Duration::MAX)
+                }
+            })
+            .max()
+            .unwrap_or(Duration::MAX);
+        if worst <= LIVE_P90 {
+            Verdict::Live
+        } else if worst <= USABLE_P90 {
+            Verdict::Slow
+        } else {
+            Verdict::TooSlow
+        }
+    }
+
+    pub fn describe(self) -> &'static str {
+        match self {
+            Verdict::Live => "responsive enough for live monitoring",
+            Verdict::Slow => "usable; raise connections_refresh_secs to 5 or more",
+            Verdict::TooSlow => "too slow or unreliable for live monitoring",
+        }
+    }
+}
+
+/// Request each endpoint `iterations` times in turn, one request at a time
+pub async fn run(client: &ClashClient, iterations: usize) -> Vec<EndpointBench> {
+    let mut results: Vec<EndpointBench> = ENDPOINTS
+        .iter()
+        .map(|path| EndpointBench {
+            path,
+            ..EndpointBench::default()
+        })
+        .collect();
+    for _ in 0..iterations {
+        for result in &mut results {
+            let started = Instant::now();
+            match tokio::time::timeout(REQUEST_TIMEOUT, client.get_body_len(result.path)).await {
+                Ok(Ok(bytes)) => {
+                    result.latencies.push(started.elapsed());
+                    result.bytes += bytes;
+                }
+                Ok(Err(e)) => {
+                    result.failures += 1;
+                    result.last_error = Some(e.root_cause().to_string());
+                }
+                Err(_) => {
+                    result.failures += 1;
+                    result.last_error =
+                        Some(format!("Timed out after {}s", REQUEST_TIMEOUT.as_secs()));
+                }
+            }
+        }
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentiles_and_verdict() {
+        let bench = EndpointBench {
+            path: "/proxies",
+            latencies: [40, 10, 30, 20, 500]
+                .into_iter()
+                .map(Duration::from_millis)
+                .collect(),
+            bytes: 5_000,
+            ..EndpointBench::default()
+        };
+        assert_eq!(bench.percentile(50.0), Some(Duration::from_millis(30)));
+        assert_eq!(bench.percentile(90.0), Some(Duration::from_millis(500)));
+        assert_eq!(bench.percentile(0.0), Some(Duration::from_millis(10)));
+        assert_eq!(bench.average_bytes(), 1_000);
+        assert_eq!(EndpointBench::default().percentile(50.0), None);
+
+        assert_eq!(Verdict::of(std::slice::from_ref(&bench)), Verdict::Slow);
+        let failing = EndpointBench {
+            failures: 1,
+            ..bench.clone()
+        };
+        assert_eq!(Verdict::of(&[bench, failing]), Verdict::TooSlow);
+    }
+}
@@ -0,0 +1,59 @@
+use anyhow::{Context, Result};
+use std::process::{Command, Stdio};
+
+/// A web dashboard the core can download and serve under `/ui`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dashboard {
+    pub name: &'static str,
+    /// Archive set as `external-ui-url`
+    pub url: &'static str,
+}
+
+pub const DASHBOARDS: [Dashboard; 3] = [
+    Dashboard {
+        name: "metacubexd",
+        url: "https://github.com/MetaCubeX/metacubexd/archive/refs/heads/gh-pages.zip",
+    },
+    Dashboard {
+        name: "yacd",
+        url: "https://github.com/MetaCubeX/Yacd-meta/archive/refs/heads/gh-pages.zip",
+    },
+    Dashboard {
+        name: "zashboard",
+        url: "https://github.com/Zephyruso/zashboard/releases/latest/download/dist.zip",
+    },
+];
+
+/// `external-ui` used when the Clash config doesn't set one (relative to the core's home dir)
+pub const DEFAULT_DIR: &str = "ui";
+
+/// The known dashboard downloaded from `url`
+pub fn find(url: &str) -> Option<&'static Dashboard> {
+    DASHBOARDS.iter().find(|dashboard| dashboard.url == url)
+}
+
+/// Where the core serves the dashboard
+pub fn page_url(api_url: &str) -> String {
+    format!("{}/ui/", api_url.trim_end_matches('/'))
+}
+
+/// Open `url` in the default browser
+pub fn open_in_browser(url: &str) -> Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(target_os = "windows") {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    command
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .context("Failed to start the browser")?;
+    Ok(())
+}
@@ -0,0 +1,344 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use url::Url;
+
+use crate::clash::ClashClient;
+use crate::config::{AppConfig, ClashConfig};
+
+/// Time allowed for each API/network check
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+/// Domain resolved through the core's DNS
+const DNS_TEST_DOMAIN: &str = "www.gstatic.com";
+/// External URL fetched through the proxy port
+const FETCH_TEST_URL: &str = "https://www.gstatic.com/generate_204";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CheckStatus {
+    Pass,
+    Fail,
+    /// Not run because an earlier check it depends on failed
+    Skip,
+}
+
+/// Outcome of one diagnostic step
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// How to fix a failure
+    pub hint: Option<String>,
+}
+
+impl CheckResult {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Pass,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Fail,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn skip(name: &'static str, reason: &str) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Skip,
+            detail: format!("skipped ({})", reason),
+            hint: None,
+        }
+    }
+}
+
+/// Run every check in order, skipping the ones whose prerequisites failed
+pub async fn run(config: &AppConfig) -> Vec<CheckResult> {
+    let client = ClashClient::new(config.api_url.clone(), config.secret.clone());
+    let mut results = Vec::new();
+
+    let reachable = check_api_reachable(&config.api_url).await;
+    let api_up = reachable.status == CheckStatus::Pass;
+    results.push(reachable);
+
+    let authorized = if api_up {
+        let result = check_secret(&client, config.secret.is_some()).await;
+        let ok = result.status == CheckStatus::Pass;
+        results.push(result);
+        ok
+    } else {
+        results.push(CheckResult::skip("Secret", "API unreachable"));
+        false
+    };
+
+    if authorized {
+        for path in ["/logs", "/traffic"] {
+            results.push(check_websocket(&client, path).await);
+        }
+    } else {
+        results.push(CheckResult::skip("WebSocket", "API not usable"));
+    }
+
+    results.push(check_config_file(config.clash_config_path.as_deref()));
+
+    let proxy_port = if authorized {
+        let (result, port) = check_proxy_port(&client, &config.api_url).await;
+        results.push(result);
+        port
+    } else {
+        results.push(CheckResult::skip("Proxy port", "API not usable"));
+        None
+    };
+
+    results.push(if authorized {
+        check_dns(&client).await
+    } else {
+        CheckResult::skip("DNS", "API not usable")
+    });
+
+    results.push(match proxy_port {
+        Some(proxy) => check_external_fetch(&proxy).await,
+        None => CheckResult::skip("External fetch", "no reachable proxy port"),
+    });
+
+    results
+}
+
+async fn check_api_reachable(api_url: &str) -> CheckResult {
+    const NAME: &str = "API reachable";
+    // Any HTTP answer counts, even 401: the secret is checked separately
+    let response = reqwest::Client::new()
+        .get(format!("{}/version", api_url))
+        .timeout(CHECK_TIMEOUT)
+        .send()
+        .await;
+    match response {
+        Ok(response) => CheckResult::pass(
+            NAME,
+            format!("{} answered ({})", api_url, response.status()),
+        ),
+        Err(e) => CheckResult::fail(
+            NAME,
+            format!("{}: {}", api_url, e),
+            "Start the core (clashctl service start) and check that --api-url matches \
+             `external-controller` in the Clash config",
+        ),
+    }
+}
+
+async fn check_secret(client: &ClashClient, has_secret: bool) -> CheckResult {
+    const NAME: &str = "Secret";
+    match tokio::time::timeout(CHECK_TIMEOUT, client.get_version()).await {
+        Ok(Ok(version)) => CheckResult::pass(
+            NAME,
+            format!(
+                "{} (core {})",
+                if has_secret {
+                    "accepted"
+                } else {
+                    "not required"
+                },
+                version.version
+            ),
+        ),
+        Ok(Err(e)) if e.to_string().contains("401") => CheckResult::fail(
+            NAME,
+            if has_secret {
+                "rejected by the core"
+            } else {
+                "the core requires a secret"
+            },
+            "Pass --secret (or `a`/`k` in Settings) matching `secret:` in the Clash config",
+        ),
+        Ok(Err(e)) => CheckResult::fail(NAME, e.to_string(), "Check the core's log for errors"),
+        Err(_) => CheckResult::fail(
+            NAME,
+            "timed out",
+            "The core is slow to answer; check its CPU usage and log",
+        ),
+    }
+}
+
+async fn check_websocket(client: &ClashClient, path: &str) -> CheckResult {
+    const NAME: &str = "WebSocket";
+    match tokio::time::timeout(CHECK_TIMEOUT, client.check_websocket(path)).await {
+        Ok(Ok(())) => CheckResult::pass(NAME, format!("{} upgraded", path)),
+        Ok(Err(e)) => CheckResult::fail(
+            NAME,
+            format!("{}: {:#}", path, e),
+            "A reverse proxy in front of the API must forward WebSocket upgrades; \
+             logs and live traffic need them",
+        ),
+        Err(_) => CheckResult::fail(
+            NAME,
+            format!("{}: timed out", path),
+            "A reverse proxy in front of the API must forward WebSocket upgrades",
+        ),
+    }
+}
+
+fn check_config_file(hint: Option<&str>) -> CheckResult {
+    const NAME: &str = "Config file";
+    match ClashConfig::find_config_with_hint(hint.map(Path::new)) {
+        Some(path) => CheckResult::pass(NAME, path.display().to_string()),
+        None => CheckResult::fail(
+            NAME,
+            "no Clash config found",
+            "Set CLASH_CONFIG_PATH (or CLASH_PARTY_DIR/CLASH_VERGE_DIR for Mihomo Party/Clash Verge); \
+             subscriptions and config edits need it",
+        ),
+    }
+}
+
+/// Check the core's mixed (or HTTP) port accepts connections, returning it as a proxy URL
+async fn check_proxy_port(client: &ClashClient, api_url: &str) -> (CheckResult, Option<String>) {
+    const NAME: &str = "Proxy port";
+    let core_config = match tokio::time::timeout(CHECK_TIMEOUT, client.get_config()).await {
+        Ok(Ok(core_config)) => core_config,
+        Ok(Err(e)) => {
+            return (
+                CheckResult::fail(NAME, e.to_string(), "Check the core's log"),
+                None,
+            )
+        }
+        Err(_) => {
+            return (
+                CheckResult::fail(NAME, "timed out", "Check the core's log"),
+                None,
+            )
+        }
+    };
+    let (label, port) = match (core_config.mixed_port, core_config.port) {
+        (0, 0) => {
+            return (
+                CheckResult::fail(
+                    NAME,
+                    "no mixed-port or port configured",
+                    "Add `mixed-port: 7890` to the Clash config",
+                ),
+                None,
+            )
+        }
+        (0, port) => ("port", port),
+        (port, _) => ("mixed-port", port),
+    };
+
+    let host = api_host(api_url);
+    let address = format!("{}:{}", host, port);
+    match tokio::time::timeout(CHECK_TIMEOUT, tokio::net::TcpStream::connect(&address)).await {
+        Ok(Ok(_)) => (
+            CheckResult::pass(NAME, format!("{} {} accepts connections", label, address)),
+            Some(format!("http://{}", address)),
+        ),
+        Ok(Err(e)) => (
+            CheckResult::fail(
+                NAME,
+                format!("{} {}: {}", label, address, e),
+                "Check nothing else holds the port and, for a remote core, set `allow-lan: true`",
+            ),
+            None,
+        ),
+        Err(_) => (
+            CheckResult::fail(
+                NAME,
+                format!("{} {}: timed out", label, address),
+                "A firewall may be dropping connections to the port",
+            ),
+            None,
+        ),
+    }
+}
+
+async fn check_dns(client: &ClashClient) -> CheckResult {
+    const NAME: &str = "DNS";
+    match tokio::time::timeout(CHECK_TIMEOUT, client.dns_query(DNS_TEST_DOMAIN)).await {
+        Ok(Ok(response)) if response.status == 0 && !response.answer.is_empty() => {
+            CheckResult::pass(
+                NAME,
+                format!("{} → {}", DNS_TEST_DOMAIN, response.answer[0].data),
+            )
+        }
+        Ok(Ok(response)) => CheckResult::fail(
+            NAME,
+            format!("{}: no answer (rcode {})", DNS_TEST_DOMAIN, response.status),
+            "Check the `dns:` nameservers in the Clash config are reachable",
+        ),
+        Ok(Err(e)) => CheckResult::fail(
+            NAME,
+            e.to_string(),
+            "Enable `dns:` in the Clash config (the DNS API needs a mihomo core)",
+        ),
+        Err(_) => CheckResult::fail(
+            NAME,
+            "timed out",
+            "Check the `dns:` nameservers in the Clash config are reachable",
+        ),
+    }
+}
+
+async fn check_external_fetch(proxy: &str) -> CheckResult {
+    const NAME: &str = "External fetch";
+    let started = Instant::now();
+    let result: Result<reqwest::StatusCode> = async {
+        let http = reqwest::Client::builder()
+            .proxy(reqwest::Proxy::all(proxy)?)
+            .timeout(CHECK_TIMEOUT * 2)
+            .build()?;
+        let response = http
+            .get(FETCH_TEST_URL)
+            .send()
+            .await
+            .context("Request through the proxy failed")?;
+        Ok(response.status())
+    }
+    .await;
+    match result {
+        Ok(status) if status.is_success() => CheckResult::pass(
+            NAME,
+            format!(
+                "{} via {} in {}ms",
+                FETCH_TEST_URL,
+                proxy,
+                started.elapsed().as_millis()
+            ),
+        ),
+        Ok(status) => CheckResult::fail(
+            NAME,
+            format!("{} returned {}", FETCH_TEST_URL, status),
+            "The selected node may be blocked; pick another one on the Routes page",
+        ),
+        Err(e) => CheckResult::fail(
+            NAME,
+            format!("{:#}", e),
+            "The selected node may be down; test and switch nodes on the Routes page",
+        ),
+    }
+}
+
+/// Host of the API URL, where the core's proxy ports listen too
+pub(crate) fn api_host(api_url: &str) -> String {
+    Url::parse(api_url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| "127.0.0.1".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_host() {
+        assert_eq!(api_host("http://127.0.0.1:9090"), "127.0.0.1");
+        assert_eq!(api_host("https://router.lan:9090/"), "router.lan");
+        assert_eq!(api_host("not a url"), "127.0.0.1");
+    }
+}
@@ -0,0 +1,57 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::process::Command;
+
+/// Program and arguments of the user's editor: `$VISUAL`, then `$EDITOR`, else
+/// Notepad on Windows and vi elsewhere
+/// A value like `code --wait` is split on whitespace
+fn editor_command(visual: Option<&str>, editor: Option<&str>) -> (String, Vec<String>) {
+    let fallback = if cfg!(windows) { "notepad" } else { "vi" };
+    let mut words = [visual, editor]
+        .into_iter()
+        .flatten()
+        .find(|value| !value.trim().is_empty())
+        .unwrap_or(fallback)
+        .split_whitespace()
+        .map(str::to_string);
+    let program = words.next().unwrap_or_else(|| fallback.to_string());
+    (program, words.collect())
+}
+
+/// Open `path` in the user's editor and wait for it to exit
+/// Blocks, so the caller must have handed the terminal over first
+pub fn edit(path: &Path) -> Result<()> {
+    let (program, args) = editor_command(
+        std::env::var("VISUAL").ok().as_deref(),
+        std::env::var("EDITOR").ok().as_deref(),
+    );
+    let status = Command::new(&program)
+        .args(&args)
+        .arg(path)
+        .status()
+        .with_context(|| format!("Failed to start {} (set $EDITOR)", program))?;
+    if !status.success() {
+        anyhow::bail!("{} exited with {}", program, status);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_editor_command() {
+        assert_eq!(
+            editor_command(Some("code --wait"), Some("nano")),
+            ("code".to_string(), vec!["--wait".to_string()])
+        );
+        assert_eq!(
+            editor_command(Some(" "), Some("nano")),
+            ("nano".to_string(), Vec::new())
+        );
+        let (program, args) = editor_command(None, None);
+        assert!(program == "vi" || program == "notepad");
+        assert!(args.is_empty());
+    }
+}
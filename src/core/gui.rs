@@ -0,0 +1,57 @@
+use tokio::process::Command;
+
+/// Whether a process with one of `names` is running (None if it can't be told)
+/// Names are compared without a `.exe` suffix and, on Windows, ignoring case
+pub async fn is_running(names: &[&str]) -> Option<bool> {
+    if cfg!(windows) {
+        let output = Command::new("tasklist")
+            .args(["/FO", "CSV", "/NH"])
+            .output()
+            .await
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        return Some(tasklist_has(
+            &String::from_utf8_lossy(&output.stdout),
+            names,
+        ));
+    }
+    for name in names {
+        let output = Command::new("pgrep")
+            .args(["-x", name])
+            .output()
+            .await
+            .ok()?;
+        match output.status.code() {
+            Some(0) => return Some(true),
+            Some(1) => continue,
+            _ => return None,
+        }
+    }
+    Some(false)
+}
+
+/// Whether `tasklist /FO CSV /NH` output lists one of `names`
+fn tasklist_has(output: &str, names: &[&str]) -> bool {
+    output
+        .lines()
+        .filter_map(|line| line.split(',').next())
+        .map(|image| image.trim().trim_matches('"'))
+        .map(|image| image.strip_suffix(".exe").unwrap_or(image))
+        .any(|image| names.iter().any(|name| name.eq_ignore_ascii_case(image)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tasklist_has() {
+        let output = "\"System Idle Process\",\"0\",\"Services\",\"0\",\"8 K\"\r\n\
+                      \"Mihomo Party.exe\",\"4120\",\"Console\",\"1\",\"95,112 K\"\r\n";
+        assert!(tasklist_has(output, &["mihomo-party", "Mihomo Party"]));
+        assert!(!tasklist_has(output, &["clash-verge"]));
+        assert!(!tasklist_has("", &["mihomo-party"]));
+    }
+}
@@ -1 +1,7 @@
-
+pub mod bench;
+pub mod dashboard;
+pub mod doctor;
+pub mod editor;
+pub mod gui;
+pub mod service;
+pub mod upgrade;
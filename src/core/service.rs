@@ -0,0 +1,222 @@
+use anyhow::{bail, Context, Result};
+use std::time::Duration;
+use tokio::process::Command;
+
+use crate::config::ServiceConfig;
+
+/// Default systemd unit / launchd label of the core service
+const DEFAULT_SERVICE_NAME: &str = "mihomo";
+
+/// How long a service command may run before it's reported as stuck
+/// A custom command that keeps the core in the foreground never finishes on its own
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Process names of known Clash cores
+const CORE_PROCESS_NAMES: [&str; 5] = [
+    "mihomo",
+    "mihomo-alpha",
+    "verge-mihomo",
+    "clash-meta",
+    "clash",
+];
+
+/// Service control action
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ServiceAction {
+    Start,
+    Stop,
+    Restart,
+}
+
+impl ServiceAction {
+    pub fn as_str(&self) -> &str {
+        match self {
+            ServiceAction::Start => "start",
+            ServiceAction::Stop => "stop",
+            ServiceAction::Restart => "restart",
+        }
+    }
+
+    pub fn past_tense(&self) -> &str {
+        match self {
+            ServiceAction::Start => "started",
+            ServiceAction::Stop => "stopped",
+            ServiceAction::Restart => "restarted",
+        }
+    }
+}
+
+/// How the local Clash core is started and stopped
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServiceManager {
+    /// `systemctl --user`
+    Systemd { unit: String },
+    /// `launchctl` user agent
+    Launchd { label: String },
+    /// User-configured shell commands
+    Command {
+        start: Option<String>,
+        stop: Option<String>,
+        restart: Option<String>,
+    },
+}
+
+impl ServiceManager {
+    /// Build the service manager from config, defaulting to the platform's init system
+    pub fn from_config(config: &ServiceConfig) -> Result<Self> {
+        let name = config
+            .name
+            .clone()
+            .unwrap_or_else(|| DEFAULT_SERVICE_NAME.to_string());
+        let manager = config
+            .manager
+            .as_deref()
+            .map(str::to_lowercase)
+            .unwrap_or_else(|| default_manager().to_string());
+
+        match manager.as_str() {
+            "systemd" => Ok(ServiceManager::Systemd { unit: name }),
+            "launchd" => Ok(ServiceManager::Launchd { label: name }),
+            "command" => Ok(ServiceManager::Command {
+                start: config.start_command.clone(),
+                stop: config.stop_command.clone(),
+                restart: config.restart_command.clone(),
+            }),
+            other => bail!(
+                "Unknown service manager '{}' (expected systemd, launchd or command)",
+                other
+            ),
+        }
+    }
+
+    /// Short human-readable description
+    pub fn describe(&self) -> String {
+        match self {
+            ServiceManager::Systemd { unit } => format!("systemd --user ({})", unit),
+            ServiceManager::Launchd { label } => format!("launchd ({})", label),
+            ServiceManager::Command { .. } => "custom commands".to_string(),
+        }
+    }
+
+    /// Start, stop or restart the core service
+    pub async fn run(&self, action: ServiceAction) -> Result<()> {
+        match self {
+            ServiceManager::Systemd { unit } => {
+                run_command("systemctl", &["--user", action.as_str(), unit]).await
+            }
+            ServiceManager::Launchd { label } => match action {
+                ServiceAction::Start => run_command("launchctl", &["start", label]).await,
+                ServiceAction::Stop => run_command("launchctl", &["stop", label]).await,
+                ServiceAction::Restart => {
+                    run_command("launchctl", &["stop", label]).await?;
+                    run_command("launchctl", &["start", label]).await
+                }
+            },
+            ServiceManager::Command {
+                start,
+                stop,
+                restart,
+            } => {
+                let command = match action {
+                    ServiceAction::Start => start.as_deref(),
+                    ServiceAction::Stop => stop.as_deref(),
+                    ServiceAction::Restart => restart.as_deref(),
+                };
+                match (command, action) {
+                    (Some(command), _) => run_command("sh", &["-c", command]).await,
+                    // Fall back to stop + start when no restart command is configured
+                    (None, ServiceAction::Restart) if start.is_some() => {
+                        if let Some(stop) = stop.as_deref() {
+                            run_command("sh", &["-c", stop]).await?;
+                        }
+                        run_command("sh", &["-c", start.as_deref().unwrap_or_default()]).await
+                    }
+                    (None, _) => bail!("No {} command configured", action.as_str()),
+                }
+            }
+        }
+    }
+
+    /// Whether the service is running (None if it can't be determined)
+    pub async fn is_running(&self) -> Option<bool> {
+        match self {
+            ServiceManager::Systemd { unit } => {
+                let output = Command::new("systemctl")
+                    .args(["--user", "is-active", "--quiet", unit])
+                    .output()
+                    .await
+                    .ok()?;
+                Some(output.status.success())
+            }
+            ServiceManager::Launchd { label } => {
+                let output = Command::new("launchctl")
+                    .args(["list", label])
+                    .output()
+                    .await
+                    .ok()?;
+                // A running job reports its PID
+                Some(
+                    output.status.success()
+                        && String::from_utf8_lossy(&output.stdout).contains("\"PID\""),
+                )
+            }
+            ServiceManager::Command { .. } => is_core_running().await,
+        }
+    }
+}
+
+fn default_manager() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "launchd"
+    } else {
+        "systemd"
+    }
+}
+
+async fn run_command(program: &str, args: &[&str]) -> Result<()> {
+    let output = tokio::time::timeout(COMMAND_TIMEOUT, Command::new(program).args(args).output())
+        .await
+        .map_err(|_| {
+            anyhow::anyhow!(
+                "{} {} did not finish within {}s",
+                program,
+                args.join(" "),
+                COMMAND_TIMEOUT.as_secs()
+            )
+        })?
+        .with_context(|| format!("Failed to run {}", program))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let detail = stderr.trim();
+        bail!(
+            "{} {} failed: {}",
+            program,
+            args.join(" "),
+            if detail.is_empty() {
+                output.status.to_string()
+            } else {
+                detail.to_string()
+            }
+        );
+    }
+
+    Ok(())
+}
+
+/// Whether a known Clash core process is running (None if `pgrep` is unavailable)
+pub async fn is_core_running() -> Option<bool> {
+    for name in CORE_PROCESS_NAMES {
+        let output = Command::new("pgrep")
+            .args(["-x", name])
+            .output()
+            .await
+            .ok()?;
+        match output.status.code() {
+            Some(0) => return Some(true),
+            Some(1) => continue,
+            _ => return None,
+        }
+    }
+    Some(false)
+}
@@ -0,0 +1,221 @@
+use anyhow::{bail, Context, Result};
+use flate2::read::GzDecoder;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::clash::ClashClient;
+use crate::config::ServiceConfig;
+
+/// GitHub API endpoint of the latest mihomo release
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/MetaCubeX/mihomo/releases/latest";
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    #[serde(default)]
+    assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleaseAsset {
+    name: String,
+    browser_download_url: String,
+    /// "sha256:<hex>", provided by GitHub for newer uploads
+    #[serde(default)]
+    digest: Option<String>,
+}
+
+/// The release asset that would replace the current core binary
+#[derive(Debug, Clone)]
+pub struct UpgradePlan {
+    /// Version reported by `/version` (None if the core is unreachable)
+    pub current: Option<String>,
+    pub latest: String,
+    pub asset_name: String,
+    download_url: String,
+    sha256: String,
+}
+
+impl UpgradePlan {
+    /// Whether the latest release is newer than the running core
+    pub fn is_newer(&self) -> bool {
+        let Some(current) = &self.current else {
+            return true;
+        };
+        match (parse_version(current), parse_version(&self.latest)) {
+            (Some(current), Some(latest)) => latest > current,
+            // Alpha builds and other custom versions can't be compared
+            _ => current.trim_start_matches('v') != self.latest.trim_start_matches('v'),
+        }
+    }
+}
+
+/// Look up the latest release and the asset for this OS/arch
+pub async fn check(client: &ClashClient) -> Result<UpgradePlan> {
+    let current = client.get_version().await.ok().map(|v| v.version);
+    let http = http_client()?;
+
+    let release: Release = http
+        .get(LATEST_RELEASE_URL)
+        .send()
+        .await
+        .context("Failed to reach GitHub")?
+        .error_for_status()
+        .context("GitHub API returned an error")?
+        .json()
+        .await
+        .context("Failed to parse GitHub release")?;
+
+    let asset_name = asset_name(&release.tag_name)?;
+    let asset = release
+        .assets
+        .iter()
+        .find(|asset| asset.name == asset_name)
+        .with_context(|| format!("Release {} has no asset {}", release.tag_name, asset_name))?;
+
+    let sha256 = match asset
+        .digest
+        .as_deref()
+        .and_then(|digest| digest.strip_prefix("sha256:"))
+    {
+        Some(hex) => hex.to_lowercase(),
+        None => checksum_from_list(&http, &release, &asset_name).await?,
+    };
+
+    Ok(UpgradePlan {
+        current,
+        latest: release.tag_name.clone(),
+        asset_name,
+        download_url: asset.browser_download_url.clone(),
+        sha256,
+    })
+}
+
+/// Configured path of the core binary
+pub fn binary_path(config: &ServiceConfig) -> Result<PathBuf> {
+    match config.binary_path.as_deref() {
+        Some(path) if !path.is_empty() => Ok(PathBuf::from(path)),
+        _ => bail!("Set service.binary_path in the config to the core binary"),
+    }
+}
+
+/// Download the release asset, verify it and swap it in at `binary_path`
+/// The previous binary is kept next to it with a `.bak` suffix
+pub async fn install(plan: &UpgradePlan, binary_path: &Path) -> Result<()> {
+    let archive = http_client()?
+        .get(&plan.download_url)
+        .send()
+        .await
+        .context("Failed to download core")?
+        .error_for_status()
+        .context("Core download failed")?
+        .bytes()
+        .await
+        .context("Failed to download core")?;
+
+    let actual = format!("{:x}", Sha256::digest(&archive));
+    if actual != plan.sha256 {
+        bail!(
+            "Checksum mismatch for {}: expected {}, got {}",
+            plan.asset_name,
+            plan.sha256,
+            actual
+        );
+    }
+
+    let mut binary = Vec::new();
+    GzDecoder::new(archive.as_ref())
+        .read_to_end(&mut binary)
+        .context("Failed to decompress core")?;
+
+    let staged = with_suffix(binary_path, "new");
+    fs::write(&staged, &binary).with_context(|| format!("Failed to write {}", staged.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&staged, fs::Permissions::from_mode(0o755))?;
+    }
+
+    if binary_path.exists() {
+        fs::rename(binary_path, with_suffix(binary_path, "bak"))
+            .with_context(|| format!("Failed to back up {}", binary_path.display()))?;
+    }
+    fs::rename(&staged, binary_path)
+        .with_context(|| format!("Failed to replace {}", binary_path.display()))?;
+
+    Ok(())
+}
+
+/// Release asset name for the current platform, e.g. `mihomo-linux-amd64-v1.18.5.gz`
+fn asset_name(tag: &str) -> Result<String> {
+    let os = match std::env::consts::OS {
+        "linux" => "linux",
+        "macos" => "darwin",
+        other => bail!("Core upgrade is not supported on {}", other),
+    };
+    let arch = match std::env::consts::ARCH {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        "arm" => "armv7",
+        "x86" => "386",
+        other => bail!("Core upgrade is not supported on {}", other),
+    };
+    Ok(format!("mihomo-{}-{}-{}.gz", os, arch, tag))
+}
+
+/// Find the asset's checksum in a `checksums.txt`-style release asset
+async fn checksum_from_list(
+    http: &reqwest::Client,
+    release: &Release,
+    asset_name: &str,
+) -> Result<String> {
+    let list = release
+        .assets
+        .iter()
+        .find(|asset| asset.name.to_lowercase().contains("checksums"))
+        .with_context(|| format!("Release {} publishes no checksums", release.tag_name))?;
+
+    let content = http
+        .get(&list.browser_download_url)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+
+    content
+        .lines()
+        .find_map(|line| {
+            let mut parts = line.split_whitespace();
+            let hash = parts.next()?;
+            let name = parts.next()?.trim_start_matches('*');
+            (name == asset_name).then(|| hash.to_lowercase())
+        })
+        .with_context(|| format!("No checksum listed for {}", asset_name))
+}
+
+fn http_client() -> Result<reqwest::Client> {
+    // GitHub rejects API requests without a User-Agent
+    Ok(reqwest::Client::builder()
+        .user_agent(concat!("clashctl/", env!("CARGO_PKG_VERSION")))
+        .build()?)
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Parse "v1.18.5" (or "1.18.5") into comparable parts
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let mut parts = version.trim().trim_start_matches('v').split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    let patch = parts.next()?.parse().ok()?;
+    Some((major, minor, patch))
+}
@@ -1,9 +1,10 @@
 use anyhow::Result;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 
 mod app;
 mod clash;
 mod config;
+mod core;
 mod ui;
 
 #[derive(Parser)]
@@ -15,18 +16,153 @@ struct Cli {
     #[arg(long, default_value = "http://127.0.0.1:9090")]
     api_url: String,
 
-    /// Clash External Controller secret
+    /// Clash External Controller secret (also set by the CLASHCTL_SECRET or CLASH_SECRET environment variable)
     #[arg(long)]
     secret: Option<String>,
 
+    /// Save the secret given by --secret or the environment to the config file
+    #[arg(long)]
+    save_secret: bool,
+
     /// Test API connection and print status
     #[arg(long)]
     test: bool,
+
+    /// Config file to use instead of the default (also set by the CLASHCTL_CONFIG environment variable)
+    #[arg(long, value_name = "PATH")]
+    config: Option<std::path::PathBuf>,
+
+    /// Draw the TUI without colors (also enabled by the NO_COLOR environment variable)
+    #[arg(long)]
+    no_color: bool,
+
+    /// Refresh and redraw less, for running over a slow SSH link
+    #[arg(long)]
+    low_bandwidth: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Manage the local Clash core service
+    Service {
+        #[command(subcommand)]
+        action: ServiceCommand,
+    },
+    /// Manage the Clash core binary
+    Core {
+        #[command(subcommand)]
+        action: CoreCommand,
+    },
+    /// Check the API, config, proxy port, DNS and internet access end to end
+    Doctor,
+    /// Measure the latency of the controller endpoints the TUI polls
+    Bench {
+        /// Requests per endpoint
+        #[arg(short = 'n', long, default_value_t = core::bench::DEFAULT_ITERATIONS)]
+        iterations: usize,
+    },
+    /// Manage subscriptions
+    Subs {
+        #[command(subcommand)]
+        action: SubsCommand,
+    },
+    /// Manage selector groups
+    Group {
+        #[command(subcommand)]
+        action: GroupCommand,
+    },
+    /// List and switch Mihomo Party / Clash Verge Rev profiles
+    Profile {
+        #[command(subcommand)]
+        action: ProfileCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum ProfileCommand {
+    /// List the GUI profiles, marking the current one
+    List,
+    /// Download (if needed), convert and apply a profile, then reload the core
+    Switch {
+        /// Profile name, or a part of it matching a single profile
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum GroupCommand {
+    /// Test every node of a selector group and switch to the fastest one
+    Best {
+        /// Selector group to switch
+        group: String,
+
+        /// Skip nodes slower than this many ms (default: `auto_select_max_delay` in the config)
+        #[arg(long, value_name = "MS")]
+        max_delay: Option<u32>,
+    },
+}
+
+#[derive(Subcommand)]
+enum SubsCommand {
+    /// Import every subscription URL in a file, one per line (`[name] <url>`)
+    Import {
+        /// File listing the subscription URLs
+        file: std::path::PathBuf,
+    },
+    /// Add a subscription URL to the Clash config as an `http` proxy provider and reload
+    AddProvider {
+        /// Subscription URL
+        url: String,
+
+        /// Provider name (default: the URL's `#fragment` or host)
+        #[arg(long)]
+        name: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum CoreCommand {
+    /// Download the latest mihomo release and restart the core
+    Upgrade {
+        /// Only check for a newer release
+        #[arg(long)]
+        check: bool,
+
+        /// Reinstall even if the core is already up to date
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ServiceCommand {
+    /// Start the core service
+    Start,
+    /// Stop the core service
+    Stop,
+    /// Restart the core service
+    Restart,
+    /// Show whether the core service is running
+    Status,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
+    if let Some(path) = cli.config.clone() {
+        config::paths::set_config_file(path);
+    }
+
+    // History and backups used to live next to the config file
+    if let Err(e) = config::paths::migrate() {
+        eprintln!(
+            "Warning: failed to move clashctl data to its new location: {:#}",
+            e
+        );
+    }
 
     // Load or create config
     let mut config = config::AppConfig::load().unwrap_or_default();
@@ -37,13 +173,49 @@ async fn main() -> Result<()> {
     } else {
         None
     };
-    config.merge_cli(api_url, cli.secret.clone());
+    let secret = cli.secret.clone().or_else(config::secret::from_env);
+    config.merge_cli(api_url, secret, cli.save_secret);
 
     // Save config for next time
     let _ = config.save();
 
     // Get preset
-    let preset = config::Preset::from_str(&config.current_preset).unwrap_or_default();
+    let preset = config.get_preset();
+
+    match cli.command {
+        Some(Command::Service { action }) => return run_service_command(&config, action).await,
+        Some(Command::Core {
+            action: CoreCommand::Upgrade { check, force },
+        }) => return upgrade_core(&config, check, force).await,
+        Some(Command::Doctor) => return run_doctor(&config).await,
+        Some(Command::Bench { iterations }) => return run_bench(&config, iterations).await,
+        Some(Command::Subs {
+            action: SubsCommand::Import { file },
+        }) => {
+            let entries = ui::import::read_list(&file)?;
+            if entries.is_empty() {
+                eprintln!("✗ No subscription URLs in {}", file.display());
+                std::process::exit(1);
+            }
+            let target = ui::import::ImportTarget::resolve(&mut config)?;
+            return import_subscriptions(&config, target, entries).await;
+        }
+        Some(Command::Subs {
+            action: SubsCommand::AddProvider { url, name },
+        }) => {
+            let entry = ui::import::single_entry(&url, name.as_deref())?;
+            let target = ui::import::ImportTarget::providers(&mut config)?;
+            return import_subscriptions(&config, target, vec![entry]).await;
+        }
+        Some(Command::Group {
+            action: GroupCommand::Best { group, max_delay },
+        }) => {
+            let max_delay = max_delay.or(config.auto_select_max_delay);
+            return select_fastest_node(&config, &group, max_delay).await;
+        }
+        Some(Command::Profile { action }) => return run_profile_command(&config, action).await,
+        None => {}
+    }
 
     // Test mode - just test connection and print info
     if cli.test {
@@ -51,6 +223,12 @@ async fn main() -> Result<()> {
     }
 
     // Start TUI
+    ui::theme::set_no_color(
+        cli.no_color
+            || config.no_color
+            || std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()),
+    );
+    ui::theme::set_low_bandwidth(cli.low_bandwidth || config.low_bandwidth);
     ui::run(
         config.api_url.clone(),
         config.secret.clone(),
@@ -62,6 +240,381 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+async fn run_service_command(config: &config::AppConfig, command: ServiceCommand) -> Result<()> {
+    use core::service::{ServiceAction, ServiceManager};
+
+    let manager = ServiceManager::from_config(&config.service)?;
+    let action = match command {
+        ServiceCommand::Start => ServiceAction::Start,
+        ServiceCommand::Stop => ServiceAction::Stop,
+        ServiceCommand::Restart => ServiceAction::Restart,
+        ServiceCommand::Status => {
+            let status = match manager.is_running().await {
+                Some(true) => "running",
+                Some(false) => "stopped",
+                None => "unknown",
+            };
+            println!("Core service via {}: {}", manager.describe(), status);
+            return Ok(());
+        }
+    };
+
+    match manager.run(action).await {
+        Ok(()) => {
+            println!(
+                "✓ Core service {} via {}",
+                action.past_tense(),
+                manager.describe()
+            );
+            Ok(())
+        }
+        Err(e) => {
+            eprintln!("✗ Failed to {} core service: {}", action.as_str(), e);
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn upgrade_core(config: &config::AppConfig, check_only: bool, force: bool) -> Result<()> {
+    use core::service::{ServiceAction, ServiceManager};
+    use core::upgrade;
+
+    let client = clash::ClashClient::new(config.api_url.clone(), config.secret.clone());
+
+    println!("Checking latest mihomo release...");
+    let plan = upgrade::check(&client).await?;
+    println!(
+        "  Current: {}",
+        plan.current
+            .as_deref()
+            .unwrap_or("unknown (core unreachable)")
+    );
+    println!("  Latest:  {}", plan.latest);
+
+    if !plan.is_newer() && !force {
+        println!("✓ Core is up to date");
+        return Ok(());
+    }
+    if check_only {
+        println!("Upgrade available. Run: clashctl core upgrade");
+        return Ok(());
+    }
+
+    let binary_path = upgrade::binary_path(&config.service)?;
+    println!("\nDownloading {}...", plan.asset_name);
+    upgrade::install(&plan, &binary_path).await?;
+    println!("✓ Installed {} to {}", plan.latest, binary_path.display());
+
+    println!("\nRestarting core service...");
+    let manager = ServiceManager::from_config(&config.service)?;
+    match manager.run(ServiceAction::Restart).await {
+        Ok(()) => println!("✓ Core service restarted via {}", manager.describe()),
+        Err(e) => {
+            eprintln!("✗ Failed to restart core service: {}", e);
+            eprintln!("  Restart it manually to use the new binary");
+            std::process::exit(1);
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_doctor(config: &config::AppConfig) -> Result<()> {
+    use core::doctor::{self, CheckStatus};
+
+    println!("Checking clashctl setup against {}...\n", config.api_url);
+    let results = doctor::run(config).await;
+    for result in &results {
+        let mark = match result.status {
+            CheckStatus::Pass => "✓",
+            CheckStatus::Fail => "✗",
+            CheckStatus::Skip => "-",
+        };
+        println!("{} {:<15} {}", mark, result.name, result.detail);
+        if let Some(hint) = &result.hint {
+            println!("  → {}", hint);
+        }
+    }
+
+    let failed = results
+        .iter()
+        .filter(|result| result.status == CheckStatus::Fail)
+        .count();
+    if failed > 0 {
+        eprintln!("\n✗ {} check(s) failed", failed);
+        std::process::exit(1);
+    }
+    println!("\n✓ All checks passed");
+    Ok(())
+}
+
+async fn run_bench(config: &config::AppConfig, iterations: usize) -> Result<()> {
+    use core::bench::{self, Verdict};
+
+    let iterations = iterations.max(1);
+    let client = clash::ClashClient::new(config.api_url.clone(), config.secret.clone());
+    println!(
+        "Benchmarking {} with {} request(s) per endpoint...\n",
+        config.api_url, iterations
+    );
+    let results = bench::run(&client, iterations).await;
+
+    let ms = |latency: Option<std::time::Duration>| {
+        latency.map_or("-".to_string(), |latency| {
+            format!("{:.1}", latency.as_secs_f64() * 1000.0)
+        })
+    };
+    println!(
+        "{:<13} {:>7} {:>8} {:>8} {:>8} {:>8} {:>9} {:>10} {:>7}",
+        "endpoint", "ok", "p50 ms", "p90 ms", "p99 ms", "max ms", "size", "KB/s", "req/s"
+    );
+    for result in &results {
+        println!(
+            "{:<13} {:>7} {:>8} {:>8} {:>8} {:>8} {:>9} {:>10} {:>7}",
+            result.path,
+            format!("{}/{}", result.latencies.len(), iterations),
+            ms(result.percentile(50.0)),
+            ms(result.percentile(90.0)),
+            ms(result.percentile(99.0)),
+            ms(result.percentile(100.0)),
+            format!("{}B", result.average_bytes()),
+            result
+                .throughput()
+                .map_or("-".to_string(), |rate| format!("{:.1}", rate / 1024.0)),
+            result
+                .requests_per_sec()
+                .map_or("-".to_string(), |rate| format!("{:.1}", rate)),
+        );
+        if let Some(error) = &result.last_error {
+            println!("  → {} failed: {}", result.failures, error);
+        }
+    }
+
+    let verdict = Verdict::of(&results);
+    if verdict == Verdict::TooSlow {
+        eprintln!("\n✗ Controller is {}", verdict.describe());
+        std::process::exit(1);
+    }
+    println!("\n✓ Controller is {}", verdict.describe());
+    Ok(())
+}
+
+async fn import_subscriptions(
+    config: &config::AppConfig,
+    target: ui::import::ImportTarget,
+    entries: Vec<ui::import::ImportEntry>,
+) -> Result<()> {
+    use ui::import::{self, ImportEvent, ImportStatus};
+
+    println!(
+        "Importing {} subscription(s) into {}...\n",
+        entries.len(),
+        target.describe()
+    );
+
+    let client = clash::ClashClient::new(config.api_url.clone(), config.secret.clone());
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let task = tokio::spawn(import::run(
+        target,
+        entries.clone(),
+        config.profile_template.clone(),
+        config.node_rename.clone(),
+        config.default_download_policy(),
+        client,
+        tx,
+    ));
+    while let Some(event) = rx.recv().await {
+        if let ImportEvent::Status(index, status) = event {
+            let entry = &entries[index];
+            match status {
+                ImportStatus::Pending => {}
+                ImportStatus::Downloading => println!("  … {}", entry.url),
+                ImportStatus::Imported(summary) => println!("✓ {}", summary),
+                ImportStatus::Skipped(reason) => println!("- {}: {}", entry.name, reason),
+                ImportStatus::Failed(error) => println!("✗ {}: {}", entry.name, error),
+            }
+        }
+    }
+
+    let statuses = match task.await? {
+        Ok(statuses) => statuses,
+        Err(e) => {
+            eprintln!("\n✗ {}", e);
+            if e.chain().count() > 1 {
+                eprintln!("  {}", e.root_cause());
+            }
+            std::process::exit(1);
+        }
+    };
+    let failed = statuses
+        .iter()
+        .filter(|status| matches!(status, ImportStatus::Failed(_)))
+        .count();
+    let imported = statuses
+        .iter()
+        .filter(|status| matches!(status, ImportStatus::Imported(_)))
+        .count();
+    if failed > 0 {
+        eprintln!("\n✗ {} imported, {} failed", imported, failed);
+        std::process::exit(1);
+    }
+    println!("\n✓ {} imported", imported);
+    Ok(())
+}
+
+async fn run_profile_command(config: &config::AppConfig, command: ProfileCommand) -> Result<()> {
+    use ui::profile_switch;
+
+    let profiles = profile_switch::list(config)?;
+    if profiles.is_empty() {
+        eprintln!("✗ No Mihomo Party / Clash Verge Rev profiles found");
+        std::process::exit(1);
+    }
+    let name = match command {
+        ProfileCommand::List => {
+            for profile in &profiles {
+                println!(
+                    "{} {:<30} {:>5} proxies  updated {}",
+                    if profile.is_current { "*" } else { " " },
+                    profile.name,
+                    profile.proxy_count,
+                    profile.updated_at.as_deref().unwrap_or("never")
+                );
+            }
+            return Ok(());
+        }
+        ProfileCommand::Switch { name } => name,
+    };
+
+    let profile = match profile_switch::find(&profiles, &name) {
+        Ok(profile) => profile,
+        Err(e) => {
+            eprintln!("✗ {}", e);
+            std::process::exit(1);
+        }
+    };
+    if let ui::pages::update::SubscriptionSource::Profile { store, .. } = &profile.source {
+        if core::gui::is_running(store.process_names()).await == Some(true) {
+            eprintln!(
+                "Warning: {} is running and may overwrite the switch; quit it first if it doesn't stick",
+                store.name()
+            );
+        }
+    }
+    println!("Switching to {}...", profile.name);
+    let client = clash::ClashClient::new(config.api_url.clone(), config.secret.clone());
+    let prepared = match profile_switch::prepare(&profile, config, &client).await {
+        Ok(prepared) => prepared,
+        Err(e) => {
+            eprintln!("✗ {:#}", e);
+            std::process::exit(1);
+        }
+    };
+    if let Some(summary) = prepared.skipped_summary() {
+        println!("  Share links skipped: {}", summary);
+    }
+    for problem in &prepared.problems {
+        println!("  {:?}: {}", problem.severity, problem.message);
+    }
+    if prepared.has_errors() {
+        eprintln!("✗ Not applied: {} has config errors", profile.name);
+        std::process::exit(1);
+    }
+
+    if let Err(e) = profile_switch::apply(
+        &client,
+        &profile,
+        &prepared.work_config_path,
+        &prepared.output_bytes,
+        prepared.applied_proxy_count,
+    )
+    .await
+    {
+        eprintln!("✗ {:#}", e);
+        std::process::exit(1);
+    }
+
+    let proxies = client.get_proxies().await?.proxies;
+    let groups = proxies.values().filter(|proxy| proxy.all.is_some()).count();
+    let nodes = proxies
+        .values()
+        .filter(|proxy| proxy.all.is_none())
+        .filter(|proxy| app::auto_select::is_testable(&proxy.name, &proxies))
+        .count();
+    let rules = client.get_rules().await?.rules.len();
+    println!(
+        "✓ Switched to {} ({} proxies, {} groups, {} rules)",
+        profile.name, nodes, groups, rules
+    );
+    Ok(())
+}
+
+async fn select_fastest_node(
+    config: &config::AppConfig,
+    group: &str,
+    max_delay: Option<u32>,
+) -> Result<()> {
+    use app::auto_select;
+    use clash::ProxyType;
+
+    let client = clash::ClashClient::new(config.api_url.clone(), config.secret.clone());
+    let proxies = client.get_proxies().await?.proxies;
+    let Some(selector) = proxies.get(group) else {
+        eprintln!("✗ No group named {}", group);
+        std::process::exit(1);
+    };
+    if selector.proxy_type != ProxyType::Selector {
+        eprintln!(
+            "✗ {} is a {:?} group, which picks its own node",
+            group, selector.proxy_type
+        );
+        std::process::exit(1);
+    }
+    let nodes: Vec<String> = selector
+        .all
+        .iter()
+        .flatten()
+        .filter(|node| auto_select::is_testable(node, &proxies))
+        .cloned()
+        .collect();
+    if nodes.is_empty() {
+        eprintln!("✗ {} has no nodes to test", group);
+        std::process::exit(1);
+    }
+
+    println!("Testing {} node(s) in {}...\n", nodes.len(), group);
+    let test = app::delay_test::DelayTest::from_config(&config.delay_test);
+    let results = auto_select::test_nodes(&client, &test, &nodes).await;
+    for (node, delay) in &results {
+        match delay {
+            Some(delay) => println!("  {:>6}ms  {}", delay, node),
+            None => println!("  {:>8}  {}", "failed", node),
+        }
+    }
+
+    let Some((node, delay)) = auto_select::fastest(&results, max_delay) else {
+        let limit = max_delay
+            .map(|max| format!(" under {}ms", max))
+            .unwrap_or_default();
+        eprintln!(
+            "\n✗ No node answered{}, kept {}",
+            limit,
+            selector.now.as_deref().unwrap_or("the current node")
+        );
+        std::process::exit(1);
+    };
+    if selector.now.as_deref() == Some(node) {
+        println!(
+            "\n✓ {} already uses the fastest node {} ({}ms)",
+            group, node, delay
+        );
+        return Ok(());
+    }
+    client.select_proxy(group, node).await?;
+    println!("\n✓ Switched {} to {} ({}ms)", group, node, delay);
+    Ok(())
+}
+
 async fn test_api_connection(api_url: &str, secret: &Option<String>) -> Result<()> {
     use clash::ClashClient;
 
@@ -74,6 +627,9 @@ async fn test_api_connection(api_url: &str, secret: &Option<String>) -> Result<(
         Ok(_) => println!("✓ Connected successfully!"),
         Err(e) => {
             eprintln!("✗ Connection failed: {}", e);
+            if core::service::is_core_running().await == Some(false) {
+                eprintln!("  Clash core process is not running. Try: clashctl service start");
+            }
             std::process::exit(1);
         }
     }
@@ -0,0 +1,768 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{layout::Rect, Frame};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+use super::{AppEvent, Context, PageController};
+use crate::app::connection_export;
+use crate::app::delay_log::ExportFormat;
+use crate::app::{ApiRequest, ApiResponse, AppState, Page};
+use crate::clash::{ConnectionsResponse, LogConnection};
+use crate::config::bypass::{self, Bypass};
+use crate::config::AppConfig;
+use crate::ui::search::SearchFilter;
+use crate::ui::{
+    add_bypass_rule, add_process_rule, list, pages, rate_per_sec, resolve_clash_config_path, theme,
+    ConnectionRuleEvent,
+};
+
+const NO_PROCESS: &str =
+    "The core reported no process for this connection (is find-process-mode off?)";
+
+/// Status shown once `suffix` goes DIRECT until `expires_at`
+fn bypass_message(suffix: &str, expires_at: i64) -> String {
+    let until = chrono::DateTime::from_timestamp(expires_at, 0)
+        .map(|at| at.with_timezone(&chrono::Local).format("%H:%M").to_string())
+        .unwrap_or_default();
+    format!(
+        "{} goes DIRECT until {}; the rule is removed then",
+        suffix, until
+    )
+}
+
+/// Active connections, with per-connection rates and search
+pub struct ConnectionsController {
+    data: Option<ConnectionsResponse>,
+    /// id -> (up, down) B/s
+    rates: HashMap<String, (u64, u64)>,
+    download_rate: u64,
+    sampled_at: Option<Instant>,
+    selected_index: usize,
+    scroll_offset: usize,
+    last_refresh: Instant,
+    search_query: String,
+    search_mode: bool,
+    /// Only connections opened by this process are shown
+    process_filter: Option<String>,
+    /// TCP/UDP and IPv4/IPv6 narrowing
+    kind_filter: pages::KindFilter,
+    /// (process, selected target) while picking where a PROCESS-NAME rule sends it
+    rule_picker: Option<(String, usize)>,
+    /// Connection to select once loaded
+    jump: Option<LogConnection>,
+    /// Waiting for the export format key
+    export_prompt: bool,
+    /// (domain suffix, minutes typed) while asking how long it goes DIRECT
+    bypass_prompt: Option<(String, String)>,
+    /// Connections opened and closed in the latest refresh
+    churn: pages::ConnectionChurn,
+    /// Automatic refreshes are off; `r` still refreshes
+    paused: bool,
+    /// Connections grouped by the rule they matched
+    by_rule: Option<pages::RuleGrouping>,
+    /// Rules being added to the Clash config in the background report back here
+    rule_tx: mpsc::UnboundedSender<ConnectionRuleEvent>,
+    rule_rx: mpsc::UnboundedReceiver<ConnectionRuleEvent>,
+}
+
+impl Default for ConnectionsController {
+    fn default() -> Self {
+        let (rule_tx, rule_rx) = mpsc::unbounded_channel();
+        Self {
+            data: None,
+            rates: HashMap::new(),
+            download_rate: 0,
+            sampled_at: None,
+            selected_index: 0,
+            scroll_offset: 0,
+            last_refresh: Instant::now(),
+            search_query: String::new(),
+            search_mode: false,
+            process_filter: None,
+            kind_filter: pages::KindFilter::default(),
+            rule_picker: None,
+            jump: None,
+            export_prompt: false,
+            bypass_prompt: None,
+            churn: pages::ConnectionChurn::default(),
+            paused: false,
+            by_rule: None,
+            rule_tx,
+            rule_rx,
+        }
+    }
+}
+
+impl ConnectionsController {
+    /// Select `target` once the next connections arrive
+    pub fn jump_to(&mut self, target: LogConnection) {
+        self.jump = Some(target);
+    }
+
+    /// Indexes of the connections shown with the current search and process filter
+    fn visible(&self) -> Vec<usize> {
+        let Some(data) = &self.data else {
+            return Vec::new();
+        };
+        let filter = SearchFilter::new(&self.search_query);
+        data.connections
+            .iter()
+            .enumerate()
+            .filter(|(_, conn)| {
+                pages::connection_visible(
+                    conn,
+                    &filter,
+                    self.process_filter.as_deref(),
+                    self.kind_filter,
+                )
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Rule groups of the connections shown, and the rows of the grouped list
+    fn rule_rows(&self) -> (Vec<pages::connections::RuleGroup>, Vec<pages::RuleRow>) {
+        let (Some(data), Some(grouping)) = (&self.data, &self.by_rule) else {
+            return (Vec::new(), Vec::new());
+        };
+        let groups = pages::group_by_rule(&data.connections, &self.visible());
+        let rows = pages::rule_rows(&groups, &grouping.expanded);
+        (groups, rows)
+    }
+
+    /// Keys of the list grouped by rule; false for keys the flat list handles too
+    fn handle_grouped_key(&mut self, key: KeyEvent) -> bool {
+        let (groups, rows) = self.rule_rows();
+        let Some(grouping) = self.by_rule.as_mut() else {
+            return false;
+        };
+        match key.code {
+            code @ (KeyCode::Up
+            | KeyCode::Down
+            | KeyCode::PageUp
+            | KeyCode::PageDown
+            | KeyCode::Home
+            | KeyCode::End) => {
+                let next = match code {
+                    KeyCode::Up => Some(grouping.selected.saturating_sub(1)),
+                    KeyCode::Down => {
+                        Some((grouping.selected + 1).min(rows.len().saturating_sub(1)))
+                    }
+                    _ => list::jump(code, grouping.selected, rows.len()),
+                };
+                if let Some(next) = next.filter(|next| *next < rows.len()) {
+                    grouping.selected = next;
+                    grouping.scroll_offset =
+                        list::follow(next, grouping.scroll_offset, list::page_size());
+                    // Connection keys (close, bypass, app filter) act on the row's connection
+                    if let pages::RuleRow::Connection(index) = rows[next] {
+                        self.selected_index = index;
+                    }
+                }
+            }
+            KeyCode::Enter | KeyCode::Right | KeyCode::Left => {
+                let group = match rows.get(grouping.selected) {
+                    Some(pages::RuleRow::Group(index)) => *index,
+                    // On a connection, collapse the group it belongs to
+                    Some(pages::RuleRow::Connection(connection)) => {
+                        let Some(index) = groups
+                            .iter()
+                            .position(|group| group.connections.contains(connection))
+                        else {
+                            return true;
+                        };
+                        grouping.selected = rows
+                            .iter()
+                            .position(|row| *row == pages::RuleRow::Group(index))
+                            .unwrap_or(0);
+                        grouping.expanded.remove(&groups[index].label());
+                        return true;
+                    }
+                    None => return true,
+                };
+                let label = groups[group].label();
+                let expand = match key.code {
+                    KeyCode::Right => true,
+                    KeyCode::Left => false,
+                    _ => !grouping.expanded.contains(&label),
+                };
+                if expand {
+                    grouping.expanded.insert(label);
+                } else {
+                    grouping.expanded.remove(&label);
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Search for the rule of the selected group or connection
+    fn selected_rule_query(&self) -> Option<String> {
+        if self.by_rule.is_some() {
+            let (groups, rows) = self.rule_rows();
+            let selected = self.by_rule.as_ref()?.selected;
+            if let Some(pages::RuleRow::Group(index)) = rows.get(selected) {
+                return Some(groups[*index].rules_query());
+            }
+        }
+        let conn = self.data.as_ref()?.connections.get(self.selected_index)?;
+        Some(pages::rules_query(
+            &conn.rule,
+            conn.rule_payload.as_deref().unwrap_or(""),
+        ))
+    }
+
+    /// Process of the selected connection, when the core reported one
+    fn selected_process(&self) -> Option<String> {
+        self.data
+            .as_ref()?
+            .connections
+            .get(self.selected_index)?
+            .metadata
+            .process_name()
+            .map(str::to_string)
+    }
+
+    /// Domain suffix of the selected connection's host
+    fn selected_suffix(&self) -> Option<String> {
+        let conn = self.data.as_ref()?.connections.get(self.selected_index)?;
+        bypass::suffix_for_host(conn.metadata.host.as_deref()?)
+    }
+
+    fn show_bypass_prompt(&self, state: &mut AppState) {
+        if let Some((suffix, minutes)) = &self.bypass_prompt {
+            state.status_message = Some(format!(
+                "Send {} DIRECT for {}_ minutes (Enter adds the rule, Esc cancels)",
+                suffix, minutes
+            ));
+        }
+    }
+
+    async fn handle_bypass_key(&mut self, key: KeyEvent, ctx: &mut Context<'_>) {
+        let Some((suffix, minutes)) = self.bypass_prompt.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Char(c) if c.is_ascii_digit() && minutes.len() < 4 => minutes.push(c),
+            KeyCode::Backspace => {
+                minutes.pop();
+            }
+            KeyCode::Esc => {
+                self.bypass_prompt = None;
+                ctx.state.status_message = Some("Bypass cancelled".to_string());
+                return;
+            }
+            KeyCode::Enter => {
+                let minutes = minutes.parse::<u64>().unwrap_or(0);
+                let suffix = suffix.clone();
+                self.bypass_prompt = None;
+                if minutes == 0 {
+                    ctx.state.status_message = Some("Bypass needs at least 1 minute".to_string());
+                    return;
+                }
+                let expires_at = chrono::Utc::now().timestamp() + minutes as i64 * 60;
+                // A bypass already in place only has its expiry moved
+                if let Some(active) = ctx
+                    .state
+                    .bypasses
+                    .iter_mut()
+                    .find(|bypass| bypass.suffix == suffix)
+                {
+                    active.expires_at = expires_at;
+                    ctx.state.save_bypasses();
+                    ctx.state.status_message = Some(bypass_message(&suffix, expires_at));
+                    return;
+                }
+                let Some(config_path) = resolve_clash_config_path(ctx.config) else {
+                    ctx.state.status_message = Some("Clash config not found".to_string());
+                    return;
+                };
+                if !ctx.state.allow_gui_write(&format!("Bypass {}", suffix)) {
+                    return;
+                }
+                let client = ctx.state.clash_state.client.clone();
+                let tx = self.rule_tx.clone();
+                ctx.state.status_message = Some(format!("Sending {} DIRECT...", suffix));
+                ctx.state.tasks.spawn("bypass-rule", async move {
+                    let result = add_bypass_rule(&config_path, &client, &suffix).await;
+                    let _ = tx.send(ConnectionRuleEvent::Bypass {
+                        suffix,
+                        expires_at,
+                        result,
+                    });
+                });
+                return;
+            }
+            _ => {}
+        }
+        self.show_bypass_prompt(ctx.state);
+    }
+
+    /// Write the connections shown to a CSV or JSON file
+    fn export(&self, format: ExportFormat, state: &mut AppState) {
+        let Some(data) = &self.data else {
+            return;
+        };
+        let shown: Vec<_> = self
+            .visible()
+            .into_iter()
+            .map(|index| &data.connections[index])
+            .collect();
+        match connection_export::export(&shown, format) {
+            Ok(path) => {
+                state.status_message = Some(format!(
+                    "Exported {} connections to {}",
+                    shown.len(),
+                    path.display()
+                ));
+            }
+            Err(e) => state.report_error("Failed to export connections", &e),
+        }
+    }
+
+    async fn handle_rule_picker_key(&mut self, key: KeyEvent, ctx: &mut Context<'_>) {
+        let Some((process, target_index)) = self.rule_picker.clone() else {
+            return;
+        };
+        let targets = pages::connection_rule_targets(ctx.state, ctx.config);
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => self.rule_picker = None,
+            KeyCode::Up => {
+                self.rule_picker = Some((process, target_index.saturating_sub(1)));
+            }
+            KeyCode::Down if target_index + 1 < targets.len() => {
+                self.rule_picker = Some((process, target_index + 1));
+            }
+            KeyCode::Enter if target_index < targets.len() => {
+                let target = targets[target_index].clone();
+                // The picker stays open, so Enter again goes ahead
+                if !ctx
+                    .state
+                    .allow_gui_write(&format!("Route {} to {}", process, target))
+                {
+                    return;
+                }
+                self.rule_picker = None;
+                let Some(config_path) = resolve_clash_config_path(ctx.config) else {
+                    ctx.state.status_message = Some("Clash config not found".to_string());
+                    return;
+                };
+                let client = ctx.state.clash_state.client.clone();
+                let tx = self.rule_tx.clone();
+                ctx.state.status_message = Some(format!("Routing {} to {}...", process, target));
+                ctx.state.tasks.spawn("process-rule", async move {
+                    let result = add_process_rule(&config_path, &client, &process, &target).await;
+                    let _ = tx.send(ConnectionRuleEvent::Process {
+                        process,
+                        target,
+                        result,
+                    });
+                });
+            }
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                ctx.emit(AppEvent::ConfirmQuit);
+            }
+            _ => {}
+        }
+    }
+
+    fn apply(&mut self, data: &ConnectionsResponse, ctx: &mut Context<'_>) {
+        // Rates are averaged since the previous sample
+        if let Some(sampled_at) = self.sampled_at {
+            let elapsed = sampled_at.elapsed();
+            self.rates = self
+                .data
+                .as_ref()
+                .map(|previous| {
+                    pages::connection_rates(previous, data, |current, before| {
+                        rate_per_sec(current, before, elapsed)
+                    })
+                })
+                .unwrap_or_default();
+            if let Some(previous) = &self.data {
+                if let Some(rate) =
+                    rate_per_sec(data.download_total, previous.download_total, elapsed)
+                {
+                    self.download_rate = rate;
+                }
+            }
+        }
+        self.sampled_at = Some(Instant::now());
+        self.churn = match &self.data {
+            Some(previous) => pages::ConnectionChurn::between(previous, data),
+            None => pages::ConnectionChurn::default(),
+        };
+
+        if let Some(target) = self.jump.take() {
+            match data
+                .connections
+                .iter()
+                .position(|conn| target.matches(conn))
+            {
+                Some(index) => {
+                    self.search_query.clear();
+                    self.process_filter = None;
+                    self.kind_filter = pages::KindFilter::default();
+                    self.selected_index = index;
+                    self.scroll_offset = index;
+                }
+                None => {
+                    ctx.state.status_message = Some(format!(
+                        "Connection {} → {} is no longer active",
+                        target.source, target.destination
+                    ));
+                }
+            }
+        }
+
+        self.selected_index = self
+            .selected_index
+            .min(data.connections.len().saturating_sub(1));
+        self.data = Some(data.clone());
+
+        // Groups come and go with their connections
+        let rows = self.rule_rows().1.len();
+        if let Some(grouping) = self.by_rule.as_mut() {
+            grouping.selected = grouping.selected.min(rows.saturating_sub(1));
+        }
+    }
+}
+
+impl PageController for ConnectionsController {
+    async fn enter(&mut self, ctx: &mut Context<'_>) {
+        self.selected_index = 0;
+        self.scroll_offset = 0;
+        // Fetch connections immediately
+        ctx.state.request(ApiRequest::Connections);
+        self.last_refresh = Instant::now();
+    }
+
+    async fn handle_key(&mut self, key: KeyEvent, ctx: &mut Context<'_>) {
+        if self.search_mode {
+            // Handle search mode input
+            match key.code {
+                KeyCode::Char(c) => {
+                    self.search_query.push(c);
+                }
+                KeyCode::Backspace => {
+                    self.search_query.pop();
+                }
+                KeyCode::Esc => {
+                    self.search_mode = false;
+                    self.search_query.clear();
+                }
+                KeyCode::Enter => {
+                    self.search_mode = false;
+                }
+                _ => {}
+            }
+            return;
+        }
+        if self.rule_picker.is_some() {
+            self.handle_rule_picker_key(key, ctx).await;
+            return;
+        }
+        if self.bypass_prompt.is_some() {
+            self.handle_bypass_key(key, ctx).await;
+            return;
+        }
+        if self.export_prompt {
+            self.export_prompt = false;
+            match key.code {
+                KeyCode::Char('c') | KeyCode::Char('C') => {
+                    self.export(ExportFormat::Csv, ctx.state)
+                }
+                KeyCode::Char('j') | KeyCode::Char('J') => {
+                    self.export(ExportFormat::Json, ctx.state)
+                }
+                _ => ctx.state.status_message = Some("Export cancelled".to_string()),
+            }
+            return;
+        }
+
+        if self.handle_grouped_key(key) {
+            return;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc | KeyCode::Char('h') => {
+                // Return to Home instead of quitting
+                ctx.navigate(Page::Home);
+            }
+            KeyCode::Char('/') => {
+                // Enter search mode
+                self.search_mode = true;
+                self.search_query.clear();
+            }
+            KeyCode::Char('r') => {
+                // Refresh connections
+                ctx.state.request(ApiRequest::Connections);
+                self.last_refresh = Instant::now();
+            }
+            // Selection moves over the connections shown; the scroll offset counts shown rows
+            code @ (KeyCode::Up
+            | KeyCode::Down
+            | KeyCode::PageUp
+            | KeyCode::PageDown
+            | KeyCode::Home
+            | KeyCode::End) => {
+                let visible = self.visible();
+                let position = visible
+                    .iter()
+                    .position(|index| *index == self.selected_index)
+                    .unwrap_or(0);
+                let next = match code {
+                    KeyCode::Up => Some(position.saturating_sub(1)),
+                    KeyCode::Down => Some((position + 1).min(visible.len().saturating_sub(1))),
+                    _ => list::jump(code, position, visible.len()),
+                };
+                if let Some(next) = next.filter(|next| *next < visible.len()) {
+                    self.selected_index = visible[next];
+                    self.scroll_offset = if next < self.scroll_offset {
+                        next
+                    } else {
+                        list::follow(next, self.scroll_offset, list::page_size())
+                    };
+                }
+            }
+            KeyCode::Char('p') => {
+                if let Some(process) = self.process_filter.take() {
+                    ctx.state.status_message =
+                        Some(format!("Showing all apps, not just {}", process));
+                } else {
+                    match self.selected_process() {
+                        Some(process) => {
+                            self.scroll_offset = 0;
+                            ctx.state.status_message =
+                                Some(format!("Showing connections of {}", process));
+                            self.process_filter = Some(process);
+                        }
+                        None => ctx.state.status_message = Some(NO_PROCESS.to_string()),
+                    }
+                }
+            }
+            KeyCode::Char('n') | KeyCode::Char('v') => {
+                if key.code == KeyCode::Char('n') {
+                    self.kind_filter.next_network();
+                } else {
+                    self.kind_filter.next_family();
+                }
+                self.scroll_offset = 0;
+                ctx.state.status_message = Some(match self.kind_filter.describe() {
+                    Some(kind) => format!("Showing {} connections", kind),
+                    None => "Showing every kind of connection".to_string(),
+                });
+            }
+            KeyCode::Char('b') | KeyCode::Char('B') => match self.selected_suffix() {
+                Some(suffix) => {
+                    self.bypass_prompt = Some((suffix, bypass::DEFAULT_MINUTES.to_string()));
+                    self.show_bypass_prompt(ctx.state);
+                }
+                None => {
+                    ctx.state.status_message =
+                        Some("The selected connection has no domain to bypass".to_string());
+                }
+            },
+            KeyCode::Char(' ') => {
+                self.paused = !self.paused;
+                ctx.state.status_message = Some(if self.paused {
+                    "Auto-refresh paused; r refreshes, Space resumes".to_string()
+                } else {
+                    "Auto-refresh resumed".to_string()
+                });
+            }
+            KeyCode::Char('i') => match ctx.config.cycle_connections_refresh() {
+                Ok(secs) => {
+                    ctx.state.status_message = Some(format!("Connections refresh every {}s", secs));
+                }
+                Err(e) => ctx
+                    .state
+                    .report_error("Failed to save refresh interval", &e),
+            },
+            KeyCode::Char('g') => {
+                self.by_rule = match self.by_rule.take() {
+                    Some(_) => None,
+                    None => Some(pages::RuleGrouping::default()),
+                };
+                ctx.state.status_message = Some(if self.by_rule.is_some() {
+                    "Grouped by rule; Enter expands, R opens the rule".to_string()
+                } else {
+                    "Showing every connection".to_string()
+                });
+            }
+            KeyCode::Char('R') => match self.selected_rule_query() {
+                Some(query) => ctx.emit(AppEvent::ShowRule(query)),
+                None => ctx.state.status_message = Some("No connection selected".to_string()),
+            },
+            KeyCode::Char('P') => match self.selected_process() {
+                Some(process) => self.rule_picker = Some((process, 0)),
+                None => ctx.state.status_message = Some(NO_PROCESS.to_string()),
+            },
+            KeyCode::Char('d') | KeyCode::Char('D') => {
+                // Close selected connection
+                if let Some(conn) = &self.data {
+                    if self.selected_index < conn.connections.len() {
+                        let connection_id = conn.connections[self.selected_index].id.clone();
+                        ctx.state
+                            .request(ApiRequest::CloseConnection(connection_id));
+                    }
+                }
+            }
+            KeyCode::Char('E') => {
+                let shown = self.visible().len();
+                if shown == 0 {
+                    ctx.state.status_message = Some("No connections to export".to_string());
+                } else {
+                    self.export_prompt = true;
+                    let filtered = if self.search_query.is_empty()
+                        && self.process_filter.is_none()
+                        && self.kind_filter == pages::KindFilter::default()
+                    {
+                        ""
+                    } else {
+                        " shown"
+                    };
+                    ctx.state.status_message = Some(format!(
+                        "Export {}{} connections as: c CSV, j JSON (any other key cancels)",
+                        shown, filtered
+                    ));
+                }
+            }
+            KeyCode::Char('a') | KeyCode::Char('A') => {
+                // Close all connections
+                ctx.state.request(ApiRequest::CloseAllConnections);
+            }
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                ctx.emit(AppEvent::ConfirmQuit);
+            }
+            _ => {}
+        }
+    }
+
+    async fn tick(&mut self, ctx: &mut Context<'_>) -> bool {
+        let interval = theme::paced(Duration::from_secs(ctx.config.connections_refresh()));
+        if ctx.state.current_page == Page::Connections
+            && !self.paused
+            && self.last_refresh.elapsed() >= interval
+        {
+            ctx.state.request(ApiRequest::Connections);
+            self.last_refresh = Instant::now();
+        }
+        let mut changed = false;
+        while let Ok(event) = self.rule_rx.try_recv() {
+            changed = true;
+            let state = &mut *ctx.state;
+            match event {
+                ConnectionRuleEvent::Bypass {
+                    suffix,
+                    expires_at,
+                    result: Ok(true),
+                } => {
+                    state.status_message = Some(bypass_message(&suffix, expires_at));
+                    state.bypasses.push(Bypass { suffix, expires_at });
+                    state.save_bypasses();
+                }
+                ConnectionRuleEvent::Bypass {
+                    suffix,
+                    result: Ok(false),
+                    ..
+                } => {
+                    state.status_message = Some(format!(
+                        "{} already goes DIRECT by a rule in the Clash config",
+                        suffix
+                    ));
+                }
+                ConnectionRuleEvent::Bypass {
+                    suffix,
+                    result: Err(e),
+                    ..
+                } => state.report_error(&format!("Failed to bypass {}", suffix), &e),
+                ConnectionRuleEvent::Process {
+                    process,
+                    result: Ok(rule),
+                    ..
+                } => {
+                    state.status_message = Some(format!(
+                        "Added {} and reloaded the core; new connections of {} follow it",
+                        rule, process
+                    ));
+                    state.request(ApiRequest::Refresh);
+                }
+                ConnectionRuleEvent::Process {
+                    process,
+                    target,
+                    result: Err(e),
+                } => state.report_error(&format!("Failed to route {} to {}", process, target), &e),
+            }
+        }
+        changed
+    }
+
+    async fn on_event(&mut self, event: &AppEvent, ctx: &mut Context<'_>) {
+        let AppEvent::Api(response) = event else {
+            return;
+        };
+        let state = &mut *ctx.state;
+        match response {
+            ApiResponse::Connections(Ok(data)) => self.apply(data, ctx),
+            ApiResponse::Connections(Err(e)) => {
+                state.report_error("Failed to fetch connections", e);
+            }
+            ApiResponse::ConnectionClosed { result, .. } => match result {
+                Ok(()) => {
+                    state.status_message = Some("Connection closed!".to_string());
+                    state.request(ApiRequest::Connections);
+                    self.last_refresh = Instant::now();
+                }
+                Err(e) => {
+                    state.report_error("Failed to close connection", e);
+                }
+            },
+            ApiResponse::AllConnectionsClosed(result) => match result {
+                Ok(()) => {
+                    state.status_message = Some("All connections closed!".to_string());
+                    self.selected_index = 0;
+                    self.scroll_offset = 0;
+                    state.request(ApiRequest::Connections);
+                    self.last_refresh = Instant::now();
+                }
+                Err(e) => {
+                    state.report_error("Failed to close all connections", e);
+                }
+            },
+            _ => {}
+        }
+    }
+
+    fn captures_text(&self) -> bool {
+        self.search_mode || self.bypass_prompt.is_some()
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, state: &AppState, config: &AppConfig) {
+        pages::render_connections(
+            f,
+            area,
+            state,
+            config,
+            self.data.as_ref(),
+            &self.rates,
+            self.download_rate,
+            self.selected_index,
+            self.scroll_offset,
+            &self.search_query,
+            self.search_mode,
+            self.process_filter.as_deref(),
+            self.kind_filter,
+            self.rule_picker
+                .as_ref()
+                .map(|(process, target)| (process.as_str(), *target)),
+            &self.churn,
+            pages::ConnectionRefresh {
+                paused: self.paused,
+                interval_secs: config.connections_refresh(),
+            },
+            self.by_rule.as_ref(),
+        );
+    }
+}
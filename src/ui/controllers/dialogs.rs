@@ -0,0 +1,559 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::Frame;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use super::{AppEvent, Context};
+use crate::app::hooks::HookEvent;
+use crate::app::{ApiRequest, AppState};
+use crate::clash::{is_unauthorized, ClashClient};
+use crate::config::backup::BackupStore;
+use crate::config::validate::ConfigProblem;
+use crate::config::{AppConfig, ClashConfig};
+use crate::ui::file_picker::{render_file_picker, FilePicker, PickerOutcome};
+use crate::ui::keymap::{help_lines, render_key_help};
+use crate::ui::pages::update::SubscriptionSource;
+use crate::ui::palette::{render_palette, Palette, PaletteOutcome};
+use crate::ui::{
+    debug_log, profile_switch, render_controller_offer, render_conversion_report,
+    render_error_console, render_message_history, render_profile_preview, render_quit_confirmation,
+    render_secret_prompt, render_validation_dialog, save_profile, ControllerOffer,
+    ConversionReport, PreviewAction, ProfilePreview,
+};
+
+/// Secret typed in the 401 prompt, shown masked
+#[derive(Default)]
+struct SecretPrompt {
+    input: String,
+    /// Also write it to the clashctl config (or keychain)
+    save: bool,
+    /// Why the last attempt failed
+    error: Option<String>,
+}
+
+/// Modal dialogs drawn over every page; the open one takes all input
+#[derive(Default)]
+pub struct Dialogs {
+    quit: bool,
+    /// Config problems blocking a reload
+    validation: Option<(String, Vec<ConfigProblem>)>,
+    validation_scroll: usize,
+    /// Share links lost converting a subscription; shown before its diff
+    conversion: Option<(String, ConversionReport)>,
+    conversion_scroll: usize,
+    /// Profile change awaiting confirmation
+    preview: Option<ProfilePreview>,
+    preview_scroll: usize,
+    /// Scroll of the error console, while open
+    errors: Option<usize>,
+    /// The console shows the status message history instead of the errors
+    console_messages: bool,
+    /// Scroll of the key bindings overlay, while open
+    help: Option<usize>,
+    /// Controller found in the Clash config, awaiting confirmation
+    controller: Option<ControllerOffer>,
+    /// Secret being typed after the core answered 401
+    secret: Option<SecretPrompt>,
+    /// The secret prompt was closed; it isn't reopened until a secret is accepted
+    secret_dismissed: bool,
+    /// Choosing the Clash config by hand after auto-detection failed
+    config_picker: Option<FilePicker>,
+    /// Command palette, while open
+    palette: Option<Palette>,
+}
+
+impl Dialogs {
+    pub fn confirm_quit(&mut self) {
+        self.quit = true;
+    }
+
+    pub fn show_problems(&mut self, title: String, problems: Vec<ConfigProblem>) {
+        self.validation = Some((title, problems));
+        self.validation_scroll = 0;
+    }
+
+    pub fn show_conversion_report(&mut self, name: String, report: ConversionReport) {
+        self.conversion = Some((name, report));
+        self.conversion_scroll = 0;
+    }
+
+    pub fn show_errors(&mut self) {
+        self.errors = Some(0);
+        self.console_messages = false;
+    }
+
+    pub fn show_messages(&mut self) {
+        self.errors = Some(0);
+        self.console_messages = true;
+    }
+
+    pub fn show_help(&mut self) {
+        self.help = Some(0);
+    }
+
+    pub fn offer_controller(&mut self, offer: ControllerOffer) {
+        self.controller = Some(offer);
+    }
+
+    /// Ask for the secret, unless the prompt is open or was dismissed
+    pub fn prompt_secret(&mut self) {
+        if self.secret.is_none() && !self.secret_dismissed {
+            self.secret = Some(SecretPrompt::default());
+        }
+    }
+
+    /// Open the Clash config picker at the remembered path, or the home directory
+    pub fn pick_clash_config(&mut self, config: &AppConfig) {
+        if self.config_picker.is_some() {
+            return;
+        }
+        let start = config
+            .clash_config_path
+            .as_ref()
+            .map(PathBuf::from)
+            .or_else(dirs::home_dir)
+            .unwrap_or_else(|| PathBuf::from("."));
+        self.config_picker = Some(FilePicker::new(&start));
+    }
+
+    pub fn show_palette(&mut self) {
+        self.palette = Some(Palette::default());
+    }
+
+    pub fn show_preview(&mut self, preview: ProfilePreview) {
+        self.preview = Some(preview);
+        self.preview_scroll = 0;
+    }
+
+    /// Handle `key` if a dialog is open; false lets the current page have it
+    pub async fn handle_key(&mut self, key: KeyEvent, ctx: &mut Context<'_>) -> bool {
+        if self.quit {
+            match key.code {
+                KeyCode::Char('y') | KeyCode::Char('Y') => ctx.emit(AppEvent::Quit),
+                KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => self.quit = false,
+                _ => {}
+            }
+            return true;
+        }
+
+        // Config validation results block other input until dismissed
+        if let Some((_, problems)) = &self.validation {
+            match key.code {
+                KeyCode::Up => self.validation_scroll = self.validation_scroll.saturating_sub(1),
+                KeyCode::Down if self.validation_scroll + 1 < problems.len() => {
+                    self.validation_scroll += 1;
+                }
+                KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => self.validation = None,
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.validation = None;
+                    self.quit = true;
+                }
+                _ => {}
+            }
+            return true;
+        }
+
+        if let Some(scroll) = self.errors.as_mut() {
+            let len = if self.console_messages {
+                ctx.state.messages.len()
+            } else {
+                ctx.state.errors.len()
+            };
+            match key.code {
+                KeyCode::Up => *scroll = scroll.saturating_sub(1),
+                KeyCode::Down if *scroll + 1 < len => *scroll += 1,
+                KeyCode::Tab => {
+                    self.console_messages = !self.console_messages;
+                    *scroll = 0;
+                }
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('!') => self.errors = None,
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.errors = None;
+                    self.quit = true;
+                }
+                _ => {}
+            }
+            return true;
+        }
+
+        if let Some(offer) = self.controller.take() {
+            match key.code {
+                KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+                    use_controller(offer, ctx).await
+                }
+                KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                    ctx.state.status_message = Some(format!("Keeping {}", ctx.config.api_url));
+                }
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.quit = true;
+                }
+                _ => self.controller = Some(offer),
+            }
+            return true;
+        }
+
+        if let Some(prompt) = self.secret.as_mut() {
+            match key.code {
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.quit = true;
+                }
+                KeyCode::Char(c) => prompt.input.push(c),
+                KeyCode::Backspace => {
+                    prompt.input.pop();
+                }
+                KeyCode::Tab => prompt.save = !prompt.save,
+                KeyCode::Esc => {
+                    self.secret = None;
+                    self.secret_dismissed = true;
+                    ctx.state.status_message =
+                        Some("Secret not set; enter it in Settings with k".to_string());
+                }
+                KeyCode::Enter => {
+                    let secret = prompt.input.trim().to_string();
+                    match use_secret(secret, prompt.save, ctx).await {
+                        Ok(()) => {
+                            self.secret = None;
+                            self.secret_dismissed = false;
+                        }
+                        Err(e) => prompt.error = Some(e.to_string()),
+                    }
+                }
+                _ => {}
+            }
+            return true;
+        }
+
+        if let Some(scroll) = self.help.as_mut() {
+            let state = &*ctx.state;
+            match key.code {
+                KeyCode::Up => *scroll = scroll.saturating_sub(1),
+                KeyCode::Down
+                    if *scroll + 1 < help_lines(state.current_page, &state.preset).len() =>
+                {
+                    *scroll += 1
+                }
+                KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') | KeyCode::Char('?') => {
+                    self.help = None
+                }
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.help = None;
+                    self.quit = true;
+                }
+                _ => {}
+            }
+            return true;
+        }
+
+        if let Some(palette) = self.palette.as_mut() {
+            if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                self.palette = None;
+                self.quit = true;
+                return true;
+            }
+            match palette.handle_key(key.code) {
+                PaletteOutcome::Pending => {}
+                PaletteOutcome::Cancelled => self.palette = None,
+                PaletteOutcome::Run(action) => {
+                    self.palette = None;
+                    ctx.emit(AppEvent::RunAction(action));
+                }
+            }
+            return true;
+        }
+
+        if let Some(picker) = self.config_picker.as_mut() {
+            if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                self.config_picker = None;
+                self.quit = true;
+                return true;
+            }
+            match picker.handle_key(key.code) {
+                PickerOutcome::Pending => {}
+                PickerOutcome::Cancelled => {
+                    self.config_picker = None;
+                    ctx.state.status_message = Some("Clash config not found".to_string());
+                }
+                PickerOutcome::Picked(path) => match ClashConfig::load(&path) {
+                    Ok(_) => {
+                        self.config_picker = None;
+                        ctx.config.clash_config_path = Some(path.to_string_lossy().to_string());
+                        match ctx.config.save() {
+                            Ok(()) => {
+                                ctx.state.status_message =
+                                    Some(format!("Clash config set to {}", path.display()));
+                                ctx.emit(AppEvent::SubscriptionsChanged);
+                            }
+                            Err(e) => ctx.state.report_error("Failed to save config", &e),
+                        }
+                    }
+                    Err(e) => ctx
+                        .state
+                        .report_error(&format!("{} is not a Clash config", path.display()), &e),
+                },
+            }
+            return true;
+        }
+
+        if let Some((_, report)) = &self.conversion {
+            match key.code {
+                KeyCode::Up => self.conversion_scroll = self.conversion_scroll.saturating_sub(1),
+                KeyCode::Down if self.conversion_scroll + 1 < report.skipped().count() => {
+                    self.conversion_scroll += 1;
+                }
+                KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => self.conversion = None,
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.conversion = None;
+                    self.quit = true;
+                }
+                _ => {}
+            }
+            return true;
+        }
+
+        // Profile changes are applied only after confirming the diff
+        let Some(preview) = self.preview.take() else {
+            return false;
+        };
+        match key.code {
+            KeyCode::Up => {
+                self.preview_scroll = self.preview_scroll.saturating_sub(1);
+                self.preview = Some(preview);
+            }
+            KeyCode::Down => {
+                self.preview_scroll += 1;
+                self.preview = Some(preview);
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                ctx.state.status_message = Some(format!("Cancelled: {}", preview.name));
+            }
+            KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+                apply_preview(preview.action, ctx).await;
+            }
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.quit = true;
+            }
+            _ => self.preview = Some(preview),
+        }
+        true
+    }
+
+    pub fn render(&self, f: &mut Frame, state: &AppState) {
+        if self.quit {
+            render_quit_confirmation(f, f.size());
+        } else if let Some(scroll) = self.errors {
+            if self.console_messages {
+                render_message_history(f, f.size(), &state.messages, scroll);
+            } else {
+                render_error_console(f, f.size(), &state.errors, scroll);
+            }
+        } else if let Some(offer) = &self.controller {
+            render_controller_offer(f, f.size(), offer, state.clash_state.client.base_url());
+        } else if let Some(prompt) = &self.secret {
+            render_secret_prompt(
+                f,
+                f.size(),
+                state.clash_state.client.base_url(),
+                &prompt.input,
+                prompt.save,
+                prompt.error.as_deref(),
+            );
+        } else if let Some(scroll) = self.help {
+            render_key_help(f, f.size(), state.current_page, &state.preset, scroll);
+        } else if let Some((title, problems)) = &self.validation {
+            render_validation_dialog(f, f.size(), title, problems, self.validation_scroll);
+        } else if let Some(palette) = &self.palette {
+            render_palette(f, f.size(), palette);
+        } else if let Some(picker) = &self.config_picker {
+            render_file_picker(f, f.size(), "Pick the Clash config", picker);
+        } else if let Some((name, report)) = &self.conversion {
+            render_conversion_report(f, f.size(), name, report, self.conversion_scroll);
+        } else if let Some(preview) = &self.preview {
+            render_profile_preview(f, f.size(), preview, self.preview_scroll);
+        }
+    }
+}
+
+/// Switch to the offered controller if it answers, and remember it
+async fn use_controller(offer: ControllerOffer, ctx: &mut Context<'_>) {
+    let state = &mut *ctx.state;
+    let client = ClashClient::new(offer.api_url.clone(), offer.secret.clone());
+    if let Err(e) = client.test_connection_within(Duration::from_secs(5)).await {
+        state.report_error(&format!("{} is unreachable too", offer.api_url), &e);
+        return;
+    }
+    ctx.config.api_url = offer.api_url.clone();
+    ctx.config.set_secret(offer.secret);
+    if let Err(e) = ctx.config.save() {
+        state.report_error("Failed to save config", &e);
+    }
+    state.set_client(client);
+    *ctx.last_refresh = Instant::now();
+    state.status_message = Some(format!(
+        "Connected to {} (from {})",
+        offer.api_url,
+        offer.source.display()
+    ));
+}
+
+/// Connect with `secret` if the core accepts it, saving it to the config when `save` is set
+async fn use_secret(secret: String, save: bool, ctx: &mut Context<'_>) -> anyhow::Result<()> {
+    if secret.is_empty() {
+        anyhow::bail!("Enter the secret set in the Clash config");
+    }
+    let client = ClashClient::new(ctx.config.api_url.clone(), Some(secret.clone()));
+    if let Err(e) = client.test_connection_within(Duration::from_secs(5)).await {
+        if is_unauthorized(&e.to_string()) {
+            anyhow::bail!("The core rejected this secret");
+        }
+        return Err(e);
+    }
+    let state = &mut *ctx.state;
+    state.status_message = Some("Authenticated for this session".to_string());
+    if save {
+        ctx.config.set_secret(Some(secret));
+        match ctx.config.save() {
+            Ok(()) => state.status_message = Some("Authenticated, secret saved".to_string()),
+            Err(e) => state.report_error("Authenticated, but saving the secret failed", &e),
+        }
+    } else {
+        ctx.config.use_secret_for_session(secret);
+    }
+    state.set_client(client);
+    *ctx.last_refresh = Instant::now();
+    Ok(())
+}
+
+async fn apply_preview(action: PreviewAction, ctx: &mut Context<'_>) {
+    let state = &mut *ctx.state;
+    match action {
+        PreviewAction::Update { item, bytes } => {
+            if let SubscriptionSource::Profile {
+                id,
+                profile_path,
+                store,
+            } = &item.source
+            {
+                match save_profile(store.as_ref(), id, &bytes, profile_path) {
+                    Ok(_) => {
+                        state.status_message = Some(format!("Updated {} successfully!", item.name));
+                        state.hooks.fire(
+                            HookEvent::SubscriptionUpdated,
+                            &[("CLASHCTL_SUBSCRIPTION", &item.name)],
+                        );
+                        ctx.emit(AppEvent::SubscriptionsChanged);
+                    }
+                    Err(e) => {
+                        state.status_message =
+                            Some(format!("Failed to update {}: {}", item.name, e));
+                    }
+                }
+            }
+        }
+        PreviewAction::Rollback(operation) => {
+            // Only restore the operation the user confirmed, checking before any file changes
+            let restored = BackupStore::open_default().and_then(|store| match store.latest()? {
+                Some(latest) if latest.created != operation.created => Ok(Some(None)),
+                Some(_) => Ok(store.restore_latest()?.map(Some)),
+                None => Ok(None),
+            });
+            match restored {
+                Ok(Some(Some(restored))) => {
+                    let reload_result = match &restored.reload {
+                        Some(path) => {
+                            state
+                                .clash_state
+                                .client
+                                .reload_config_path(&path.to_string_lossy())
+                                .await
+                        }
+                        None => Ok(()),
+                    };
+                    state.status_message = Some(match reload_result {
+                        Ok(()) => format!("Rolled back: {}", restored.label),
+                        Err(e) => format!("Restored files but reload failed: {}", e),
+                    });
+                    state.request(ApiRequest::Refresh);
+                    state.request(ApiRequest::Rules);
+                    ctx.emit(AppEvent::SubscriptionsChanged);
+                }
+                Ok(Some(None)) => {
+                    state.status_message = Some(
+                        "Backups changed since the preview, nothing was rolled back".to_string(),
+                    );
+                }
+                Ok(None) => {
+                    state.status_message = Some("No backups to roll back".to_string());
+                }
+                Err(e) => {
+                    state.report_error("Rollback failed", &e);
+                }
+            }
+        }
+        PreviewAction::Switch {
+            item,
+            work_config_path,
+            output_bytes,
+            applied_proxy_count,
+        } => {
+            let applied = profile_switch::apply(
+                &state.clash_state.client,
+                &item,
+                &work_config_path,
+                &output_bytes,
+                applied_proxy_count,
+            )
+            .await;
+            match applied {
+                Ok(()) => {
+                    let _ = state.refresh().await;
+                    let rules = match state.clash_state.client.get_rules().await {
+                        Ok(rules_response) => {
+                            debug_log(&format!(
+                                "switch rules_count={}",
+                                rules_response.rules.len()
+                            ));
+                            rules_response.rules
+                        }
+                        Err(e) => {
+                            debug_log(&format!("switch rules fetch failed: {}", e));
+                            state.request(ApiRequest::Rules);
+                            Vec::new()
+                        }
+                    };
+                    if let Some(group) = state
+                        .clash_state
+                        .current_selector
+                        .as_ref()
+                        .and_then(|name| state.clash_state.proxies.get(name))
+                    {
+                        if let Some(all) = &group.all {
+                            debug_log(&format!("switch refresh group_nodes={}", all.len()));
+                            let sample: Vec<String> = all.iter().take(5).cloned().collect();
+                            debug_log(&format!("switch group_nodes_sample={:?}", sample));
+                        }
+                    }
+                    debug_log(&format!(
+                        "switch proxies_count={}",
+                        state.clash_state.proxies.len()
+                    ));
+                    *ctx.last_refresh = Instant::now();
+                    let status = if let Some(count) = applied_proxy_count {
+                        format!(
+                            "Switched to {} ({} proxies, {} rules)",
+                            item.name,
+                            count,
+                            rules.len()
+                        )
+                    } else {
+                        format!("Switched to {} ({} rules)", item.name, rules.len())
+                    };
+                    state.status_message = Some(status);
+                    ctx.emit(AppEvent::ProfileSwitched(rules));
+                }
+                Err(e) => {
+                    state.report_error(&format!("Failed to switch to {}", item.name), &e);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{layout::Rect, Frame};
+use std::time::Instant;
+
+use super::{AppEvent, Context, PageController};
+use crate::app::{AppState, Page};
+use crate::config::AppConfig;
+use crate::ui::pages;
+
+/// Favorite nodes, switched to in their default or a picked group
+#[derive(Default)]
+pub struct FavoritesController {
+    selected_index: usize,
+    /// Selected group when picking a group
+    group_picker: Option<usize>,
+}
+
+impl PageController for FavoritesController {
+    async fn enter(&mut self, _ctx: &mut Context<'_>) {
+        self.selected_index = 0;
+        self.group_picker = None;
+    }
+
+    async fn handle_key(&mut self, key: KeyEvent, ctx: &mut Context<'_>) {
+        let (state, config) = (&mut *ctx.state, &mut *ctx.config);
+        let favorite_count = config.favorite_nodes.len();
+
+        if let Some(group_index) = self.group_picker {
+            // Group picker for the selected favorite
+            let node = config
+                .favorite_nodes
+                .get(self.selected_index)
+                .cloned()
+                .unwrap_or_default();
+            let groups = pages::favorite_groups(state, config, &node);
+
+            match key.code {
+                KeyCode::Esc | KeyCode::Left | KeyCode::Char('q') => {
+                    self.group_picker = None;
+                }
+                KeyCode::Up => {
+                    self.group_picker = Some(group_index.saturating_sub(1));
+                }
+                KeyCode::Down if group_index + 1 < groups.len() => {
+                    self.group_picker = Some(group_index + 1);
+                }
+                KeyCode::Enter if group_index < groups.len() => {
+                    state.select_proxy_checked(
+                        &groups[group_index].name,
+                        &node,
+                        config.test_before_switch,
+                    );
+                    self.group_picker = None;
+                    *ctx.last_refresh = Instant::now();
+                }
+                KeyCode::Char('D') if group_index < groups.len() => {
+                    let group = groups[group_index].name.clone();
+                    let new_default = if config.favorite_selector.as_ref() == Some(&group) {
+                        None
+                    } else {
+                        Some(group)
+                    };
+                    let message = match &new_default {
+                        Some(group) => {
+                            format!("Favorites now apply to {} by default", group)
+                        }
+                        None => "Cleared default group for favorites".to_string(),
+                    };
+                    match config.set_favorite_selector(new_default) {
+                        Ok(()) => state.status_message = Some(message),
+                        Err(e) => state.report_error("Failed to save default group", &e),
+                    }
+                }
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    ctx.emit(AppEvent::ConfirmQuit);
+                }
+                _ => {}
+            }
+        } else {
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => {
+                    ctx.navigate(Page::Home);
+                }
+                KeyCode::Char('h') => ctx.navigate(Page::Home),
+                KeyCode::Up => {
+                    self.selected_index = self.selected_index.saturating_sub(1);
+                }
+                KeyCode::Down if self.selected_index + 1 < favorite_count => {
+                    self.selected_index += 1;
+                }
+                KeyCode::Enter if self.selected_index < favorite_count => {
+                    let node = config.favorite_nodes[self.selected_index].clone();
+                    let groups = pages::favorite_groups(state, config, &node);
+                    // The default group, else the primary group when it offers the node
+                    let default_group = config
+                        .favorite_selector
+                        .clone()
+                        .filter(|group| groups.iter().any(|r| &r.name == group))
+                        .or_else(|| {
+                            state
+                                .clash_state
+                                .current_selector
+                                .clone()
+                                .filter(|group| groups.iter().any(|r| &r.name == group))
+                        });
+
+                    if groups.is_empty() {
+                        state.status_message =
+                            Some(format!("{} is not in any selector group", node));
+                    } else if let Some(group) = default_group {
+                        state.select_proxy_checked(&group, &node, config.test_before_switch);
+                        *ctx.last_refresh = Instant::now();
+                    } else if groups.len() == 1 {
+                        state.select_proxy_checked(
+                            &groups[0].name,
+                            &node,
+                            config.test_before_switch,
+                        );
+                        *ctx.last_refresh = Instant::now();
+                    } else {
+                        self.group_picker = Some(0);
+                    }
+                }
+                KeyCode::Char('g') | KeyCode::Right if self.selected_index < favorite_count => {
+                    let node = &config.favorite_nodes[self.selected_index];
+                    if pages::favorite_groups(state, config, node).is_empty() {
+                        state.status_message =
+                            Some(format!("{} is not in any selector group", node));
+                    } else {
+                        self.group_picker = Some(0);
+                    }
+                }
+                KeyCode::Char('x') | KeyCode::Delete if self.selected_index < favorite_count => {
+                    let node = config.favorite_nodes[self.selected_index].clone();
+                    match config.remove_favorite(&node) {
+                        Ok(()) => {
+                            state.status_message = Some(format!("Removed {} from favorites", node));
+                            self.selected_index =
+                                self.selected_index.min(favorite_count.saturating_sub(2));
+                        }
+                        Err(e) => {
+                            state.report_error("Failed to remove favorite", &e);
+                        }
+                    }
+                }
+                KeyCode::Char('t') | KeyCode::Char('T') => {
+                    if state.preset.show_speed_test() {
+                        let testable_nodes: Vec<String> = config
+                            .favorite_nodes
+                            .iter()
+                            .filter(|node| state.is_node_testable(node))
+                            .cloned()
+                            .collect();
+
+                        if !testable_nodes.is_empty() {
+                            state.status_message =
+                                Some(format!("Testing {} nodes...", testable_nodes.len()));
+                            for node in testable_nodes {
+                                state.start_test_delay(node);
+                            }
+                        }
+                    } else {
+                        state.status_message =
+                            Some("Speed test disabled in current preset".to_string());
+                    }
+                }
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    ctx.emit(AppEvent::ConfirmQuit);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, state: &AppState, config: &AppConfig) {
+        pages::render_favorites(
+            f,
+            area,
+            state,
+            config,
+            self.selected_index,
+            self.group_picker,
+        );
+    }
+}
@@ -0,0 +1,236 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{layout::Rect, Frame};
+use tokio::sync::mpsc;
+
+use super::{AppEvent, Context, PageController};
+use crate::app::{AppState, Page};
+use crate::config::AppConfig;
+use crate::ui::{
+    list, pages, poll_node_group_syncs, resync_node_group, spawn_node_group_sync, NodeGroupSynced,
+};
+
+/// Custom node groups and their members
+pub struct GroupsController {
+    action: pages::GroupsAction,
+    /// Name typed for a new group
+    input: String,
+    selected_index: usize,
+    /// Node groups being written to the Clash config in the background
+    syncs: Vec<mpsc::UnboundedReceiver<NodeGroupSynced>>,
+}
+
+impl Default for GroupsController {
+    fn default() -> Self {
+        Self {
+            action: pages::GroupsAction::None,
+            input: String::new(),
+            selected_index: 0,
+            syncs: Vec::new(),
+        }
+    }
+}
+
+impl PageController for GroupsController {
+    async fn enter(&mut self, _ctx: &mut Context<'_>) {
+        self.action = pages::GroupsAction::None;
+        self.selected_index = 0;
+    }
+
+    async fn handle_key(&mut self, key: KeyEvent, ctx: &mut Context<'_>) {
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            ctx.emit(AppEvent::ConfirmQuit);
+            return;
+        }
+        let (state, config) = (&mut *ctx.state, &mut *ctx.config);
+        match self.action.clone() {
+            pages::GroupsAction::None => {
+                let names = config.get_group_names();
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => {
+                        ctx.navigate(Page::Home);
+                    }
+                    KeyCode::Char('h') => ctx.navigate(Page::Home),
+                    KeyCode::Up => {
+                        self.selected_index = self.selected_index.saturating_sub(1);
+                    }
+                    KeyCode::Down if self.selected_index + 1 < names.len() => {
+                        self.selected_index += 1;
+                    }
+                    code @ (KeyCode::PageUp | KeyCode::PageDown | KeyCode::Home | KeyCode::End) => {
+                        if let Some(index) = list::jump(code, self.selected_index, names.len()) {
+                            self.selected_index = index;
+                        }
+                    }
+                    KeyCode::Char('n') => {
+                        self.input.clear();
+                        self.action = pages::GroupsAction::CreateGroup;
+                    }
+                    KeyCode::Enter if self.selected_index < names.len() => {
+                        self.action =
+                            pages::GroupsAction::ViewingGroup(names[self.selected_index].clone());
+                        self.selected_index = 0;
+                    }
+                    KeyCode::Char('d') if self.selected_index < names.len() => {
+                        let group = names[self.selected_index].clone();
+                        if config.is_group_materialized(&group)
+                            && !state.allow_gui_write(&format!("Delete group {}", group))
+                        {
+                            return;
+                        }
+                        // Take it out of the Clash config too, so no dangling proxy group is left
+                        let mut message = format!("Deleted group {}", group);
+                        if config.is_group_materialized(&group) {
+                            self.syncs
+                                .push(spawn_node_group_sync(state, config, &group, None, false));
+                            message.push_str(", removing it from the Clash config...");
+                        }
+                        state.status_message = Some(match config.delete_group(&group) {
+                            Ok(()) => message,
+                            Err(e) => format!("Failed to delete group: {}", e),
+                        });
+                        self.selected_index =
+                            self.selected_index.min(names.len().saturating_sub(2));
+                    }
+                    _ => {}
+                }
+            }
+            pages::GroupsAction::CreateGroup => match key.code {
+                KeyCode::Esc => {
+                    self.input.clear();
+                    self.action = pages::GroupsAction::None;
+                }
+                KeyCode::Enter => {
+                    let name = self.input.trim().to_string();
+                    if name.is_empty() {
+                        state.status_message = Some("Group name cannot be empty".to_string());
+                    } else {
+                        match config.create_group(name.clone(), Vec::new()) {
+                            Ok(()) => {
+                                state.status_message = Some(format!("Created group {}", name));
+                                self.input.clear();
+                                self.action = pages::GroupsAction::SelectingNodes(name);
+                                self.selected_index = 0;
+                            }
+                            Err(e) => {
+                                state.report_error("Failed to create group", &e);
+                            }
+                        }
+                    }
+                }
+                KeyCode::Backspace => {
+                    self.input.pop();
+                }
+                KeyCode::Char(c) => self.input.push(c),
+                _ => {}
+            },
+            pages::GroupsAction::SelectingNodes(group) => {
+                let nodes = pages::group_available_nodes(state);
+                match key.code {
+                    KeyCode::Enter | KeyCode::Esc => {
+                        let count = config.get_group_nodes(&group).map_or(0, Vec::len);
+                        let mut message = format!("Group {} has {} node(s)", group, count);
+                        message.push_str(&resync_node_group(
+                            state,
+                            config,
+                            &group,
+                            &mut self.syncs,
+                        ));
+                        state.status_message = Some(message);
+                        self.action = pages::GroupsAction::ViewingGroup(group);
+                        self.selected_index = 0;
+                    }
+                    KeyCode::Up => {
+                        self.selected_index = self.selected_index.saturating_sub(1);
+                    }
+                    KeyCode::Down if self.selected_index + 1 < nodes.len() => {
+                        self.selected_index += 1;
+                    }
+                    code @ (KeyCode::PageUp | KeyCode::PageDown | KeyCode::Home | KeyCode::End) => {
+                        if let Some(index) = list::jump(code, self.selected_index, nodes.len()) {
+                            self.selected_index = index;
+                        }
+                    }
+                    KeyCode::Char(' ') if self.selected_index < nodes.len() => {
+                        let node = nodes[self.selected_index].clone();
+                        let in_group = config
+                            .get_group_nodes(&group)
+                            .is_some_and(|members| members.contains(&node));
+                        let result = if in_group {
+                            config.remove_node_from_group(&group, &node)
+                        } else {
+                            config.add_node_to_group(&group, node)
+                        };
+                        if let Err(e) = result {
+                            state.report_error("Failed to update group", &e);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            pages::GroupsAction::ViewingGroup(group) => {
+                let nodes = config.get_group_nodes(&group).cloned().unwrap_or_default();
+                match key.code {
+                    KeyCode::Esc | KeyCode::Char('q') => {
+                        let names = config.get_group_names();
+                        self.selected_index =
+                            names.iter().position(|name| name == &group).unwrap_or(0);
+                        self.action = pages::GroupsAction::None;
+                    }
+                    KeyCode::Up => {
+                        self.selected_index = self.selected_index.saturating_sub(1);
+                    }
+                    KeyCode::Down if self.selected_index + 1 < nodes.len() => {
+                        self.selected_index += 1;
+                    }
+                    code @ (KeyCode::PageUp | KeyCode::PageDown | KeyCode::Home | KeyCode::End) => {
+                        if let Some(index) = list::jump(code, self.selected_index, nodes.len()) {
+                            self.selected_index = index;
+                        }
+                    }
+                    KeyCode::Char('a') => {
+                        self.action = pages::GroupsAction::SelectingNodes(group);
+                        self.selected_index = 0;
+                    }
+                    KeyCode::Char('d') | KeyCode::Delete if self.selected_index < nodes.len() => {
+                        let node = &nodes[self.selected_index];
+                        state.status_message =
+                            Some(match config.remove_node_from_group(&group, node) {
+                                Ok(()) => format!(
+                                    "Removed {} from {}{}",
+                                    node,
+                                    group,
+                                    resync_node_group(state, config, &group, &mut self.syncs)
+                                ),
+                                Err(e) => {
+                                    format!("Failed to update group: {}", e)
+                                }
+                            });
+                        self.selected_index =
+                            self.selected_index.min(nodes.len().saturating_sub(2));
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    async fn tick(&mut self, ctx: &mut Context<'_>) -> bool {
+        poll_node_group_syncs(&mut self.syncs, ctx.state, ctx.config)
+    }
+
+    fn captures_text(&self) -> bool {
+        self.action == pages::GroupsAction::CreateGroup
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, state: &AppState, config: &AppConfig) {
+        pages::render_groups(
+            f,
+            area,
+            state,
+            config,
+            &self.action,
+            &self.input,
+            self.selected_index,
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{layout::Rect, Frame};
+
+use super::{AppEvent, Context, PageController};
+use crate::app::{AppState, Page};
+use crate::config::{delays, AppConfig};
+use crate::ui::{list, load_heatmap, pages};
+
+/// Delay history per node and hour
+#[derive(Default)]
+pub struct HeatmapController {
+    heatmap: delays::Heatmap,
+    view: delays::HeatmapView,
+    scroll: usize,
+}
+
+impl PageController for HeatmapController {
+    async fn enter(&mut self, ctx: &mut Context<'_>) {
+        self.scroll = 0;
+        match load_heatmap(self.view) {
+            Ok(loaded) => self.heatmap = loaded,
+            Err(e) => ctx.state.report_error("Failed to load delay history", &e),
+        }
+    }
+
+    async fn handle_key(&mut self, key: KeyEvent, ctx: &mut Context<'_>) {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc | KeyCode::Char('h') => ctx.navigate(Page::Home),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                ctx.emit(AppEvent::ConfirmQuit);
+            }
+            KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Down if self.scroll + 1 < self.heatmap.rows.len() => {
+                self.scroll += 1;
+            }
+            KeyCode::Char('v') | KeyCode::Char('r') => {
+                if key.code == KeyCode::Char('v') {
+                    self.view = self.view.next();
+                }
+                match load_heatmap(self.view) {
+                    Ok(loaded) => {
+                        self.heatmap = loaded;
+                        ctx.state.status_message = Some(format!("Showing {}", self.view.label()));
+                    }
+                    Err(e) => ctx.state.report_error("Failed to load delay history", &e),
+                }
+            }
+            code => {
+                if let Some(index) = list::jump(code, self.scroll, self.heatmap.rows.len()) {
+                    self.scroll = index;
+                }
+            }
+        }
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, state: &AppState, _config: &AppConfig) {
+        pages::render_heatmap(f, area, state, &self.heatmap, self.view, self.scroll);
+    }
+}
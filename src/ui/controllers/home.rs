@@ -0,0 +1,129 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{layout::Rect, Frame};
+use std::time::{Duration, Instant};
+
+use super::{AppEvent, Context, PageController};
+use crate::app::{ApiRequest, ApiResponse, AppState, Page};
+use crate::config::AppConfig;
+use crate::ui::{pages, theme};
+
+/// How often traffic is sampled while Home is open
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Status overview, live dashboard and the keys opening every other page
+pub struct HomeController {
+    dashboard: pages::home::Dashboard,
+    last_refresh: Instant,
+    sampled_at: Option<Instant>,
+}
+
+impl Default for HomeController {
+    fn default() -> Self {
+        Self {
+            dashboard: pages::home::Dashboard::default(),
+            last_refresh: Instant::now(),
+            sampled_at: None,
+        }
+    }
+}
+
+impl PageController for HomeController {
+    async fn enter(&mut self, ctx: &mut Context<'_>) {
+        ctx.state.request(ApiRequest::Connections);
+        ctx.state.request(ApiRequest::Providers);
+        self.last_refresh = Instant::now();
+    }
+
+    async fn handle_key(&mut self, key: KeyEvent, ctx: &mut Context<'_>) {
+        let (state, config) = (&mut *ctx.state, &mut *ctx.config);
+        let page = match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => {
+                ctx.emit(AppEvent::ConfirmQuit);
+                return;
+            }
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                ctx.emit(AppEvent::ConfirmQuit);
+                return;
+            }
+            KeyCode::Char('r') => {
+                state.request(ApiRequest::Refresh);
+                *ctx.last_refresh = Instant::now();
+                return;
+            }
+            KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                let current_theme = config.get_theme();
+                let next_theme = current_theme.next();
+                let _ = config.set_theme(next_theme);
+                state.status_message = Some(format!("Switched to {} theme", next_theme.name()));
+                return;
+            }
+            // Note: 't' key for speed test is removed from Home page
+            KeyCode::Char('m') => {
+                // Switch to next mode (Rule -> Global -> Direct -> Rule)
+                let next_mode = state.clash_state.mode.next();
+                state.switch_mode(next_mode);
+                *ctx.last_refresh = Instant::now();
+                return;
+            }
+            KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                state.cycle_preset(config);
+                return;
+            }
+            KeyCode::Char('c') => Page::Connections,
+            KeyCode::Char('g') => Page::Routes,
+            KeyCode::Char('l') => Page::Rules,
+            KeyCode::Char('u') => Page::Update,
+            KeyCode::Char('s') => Page::Settings,
+            KeyCode::Char('p') => Page::Performance,
+            KeyCode::Char('f') => Page::Favorites,
+            KeyCode::Char('G') => Page::Groups,
+            KeyCode::Char('H') => Page::Heatmap,
+            KeyCode::Char('d') => Page::Statistics,
+            KeyCode::Char('o') => Page::Logs,
+            _ => return,
+        };
+        ctx.navigate(page);
+    }
+
+    async fn tick(&mut self, ctx: &mut Context<'_>) -> bool {
+        if ctx.state.current_page == Page::Home
+            && self.last_refresh.elapsed() >= theme::paced(REFRESH_INTERVAL)
+        {
+            ctx.state.request(ApiRequest::Connections);
+            self.last_refresh = Instant::now();
+        }
+        false
+    }
+
+    async fn on_event(&mut self, event: &AppEvent, _ctx: &mut Context<'_>) {
+        // Samples requested by other pages count too
+        match event {
+            AppEvent::Api(ApiResponse::Connections(Ok(data))) => {
+                let elapsed = self.sampled_at.map(|at| at.elapsed());
+                self.dashboard.sample(data, elapsed);
+                self.sampled_at = Some(Instant::now());
+            }
+            AppEvent::Api(ApiResponse::Providers(Ok(providers))) => {
+                self.dashboard
+                    .set_subscriptions(providers, chrono::Utc::now().timestamp());
+            }
+            AppEvent::Api(ApiResponse::ProxySelected {
+                selector,
+                proxy,
+                result: Ok(()),
+                ..
+            }) => self.dashboard.record_switch(selector, proxy),
+            _ => {}
+        }
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, state: &AppState, config: &AppConfig) {
+        pages::render_home(
+            f,
+            area,
+            state,
+            &self.dashboard,
+            config.notifications.expiry_days,
+        );
+    }
+}
@@ -0,0 +1,385 @@
+use chrono::Local;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{layout::Rect, Frame};
+use std::time::Instant;
+use tokio::sync::{mpsc, watch};
+
+use super::{AppEvent, Context, PageController};
+use crate::app::{ApiResponse, AppState, Page};
+use crate::clash::{reconnect, ClashClient, LogEntry, LogStreamEvent, LogStreamStatus};
+use crate::config::log_history::{self, LogHistory};
+use crate::config::AppConfig;
+use crate::ui::log_rules::LogRules;
+use crate::ui::{list, pages};
+
+/// Lines kept, newest first
+const MAX_LINES: usize = 1000;
+/// Registry name of the log stream task
+const LOG_STREAM_TASK: &str = "logs";
+
+/// The core's log stream, filtered by level and search query
+pub struct LogsController {
+    data: Vec<LogEntry>,
+    level_filter: pages::LogLevel,
+    search_query: String,
+    search_mode: bool,
+    /// Selected log line (newest first)
+    selected_index: usize,
+    tx: mpsc::UnboundedSender<LogStreamEvent>,
+    rx: mpsc::UnboundedReceiver<LogStreamEvent>,
+    shutdown: Option<watch::Sender<bool>>,
+    connected: bool,
+    status_detail: Option<String>,
+    /// Next reconnect attempt
+    retry: Option<(u32, Instant)>,
+    /// Highlight, re-classify and mute rules from the config
+    rules: LogRules,
+    /// Lines kept on disk, when `log_history_mb` is set
+    history: Option<LogHistory>,
+    /// History search results, shown instead of the live lines
+    past: Option<Vec<LogEntry>>,
+}
+
+impl LogsController {
+    pub fn new() -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        Self {
+            data: Vec::new(),
+            level_filter: pages::LogLevel::All,
+            search_query: String::new(),
+            search_mode: false,
+            selected_index: 0,
+            tx,
+            rx,
+            shutdown: None,
+            connected: false,
+            status_detail: None,
+            retry: None,
+            rules: LogRules::default(),
+            history: None,
+            past: None,
+        }
+    }
+
+    /// (Re)subscribe to the log stream at the current filter level
+    fn start_stream(&mut self, state: &mut AppState, status: &str) {
+        self.connected = false;
+        self.status_detail = Some(status.to_string());
+        self.retry = None;
+        self.stop_stream(state);
+
+        let client = state.clash_state.client.clone();
+        let level = log_level_to_ws(self.level_filter, state.clash_state.log_level.as_deref())
+            .map(|value| value.to_string());
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        self.shutdown = Some(shutdown_tx);
+        let logs_tx = self.tx.clone();
+        let status_tx = self.tx.clone();
+        state.tasks.spawn(
+            LOG_STREAM_TASK,
+            reconnect::keep_connected(
+                shutdown_rx,
+                move |shutdown| {
+                    let client: ClashClient = client.clone();
+                    let level = level.clone();
+                    let logs_tx = logs_tx.clone();
+                    async move {
+                        client
+                            .stream_logs(level.as_deref(), shutdown, logs_tx)
+                            .await
+                    }
+                },
+                move |attempt, retry_at, reason| {
+                    // Only the first failure in a row is logged, retries show in the status bar
+                    if attempt == 1 {
+                        let _ = status_tx.send(LogStreamEvent::Entry(LogEntry::new(
+                            Local::now().format("%H:%M:%S").to_string(),
+                            "ERROR".to_string(),
+                            format!("Log stream lost ({}), reconnecting", reason),
+                        )));
+                    }
+                    let _ = status_tx.send(LogStreamEvent::Status(LogStreamStatus::Disconnected(
+                        reason,
+                    )));
+                    let _ = status_tx.send(LogStreamEvent::Status(LogStreamStatus::Retrying {
+                        attempt,
+                        retry_at,
+                    }));
+                },
+            ),
+        );
+    }
+
+    fn stop_stream(&mut self, state: &mut AppState) {
+        if let Some(tx) = self.shutdown.take() {
+            let _ = tx.send(true);
+        }
+        state.tasks.cancel(LOG_STREAM_TASK);
+    }
+
+    /// Lines on screen: history search results, or the live buffer
+    fn shown(&self) -> &[LogEntry] {
+        self.past.as_deref().unwrap_or(&self.data)
+    }
+
+    fn filtered_count(&self) -> usize {
+        pages::filter_logs(self.shown(), self.level_filter, &self.search_query).len()
+    }
+
+    /// Show the stored lines matching the level filter and search query
+    fn search_history(&mut self, state: &mut AppState) {
+        let Some(history) = &self.history else {
+            state.status_message =
+                Some("Log history is off; set log_history_mb in the config".to_string());
+            return;
+        };
+        let (level, query) = (self.level_filter, self.search_query.as_str());
+        match history.search(|entry| pages::log_matches(entry, level, query)) {
+            Ok(found) => {
+                state.status_message = Some(format!(
+                    "{} lines in history{}",
+                    found.len(),
+                    if query.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" matching \"{}\"", query)
+                    }
+                ));
+                self.past = Some(found);
+                self.selected_index = 0;
+            }
+            Err(e) => state.report_error("Failed to read the log history", &e),
+        }
+    }
+}
+
+/// Level requested from the logs WebSocket
+/// With no display filter, debug lines are streamed while the core itself logs at debug
+fn log_level_to_ws(level: pages::LogLevel, core_level: Option<&str>) -> Option<&'static str> {
+    match level {
+        pages::LogLevel::All if core_level == Some("debug") => Some("debug"),
+        pages::LogLevel::All => None,
+        pages::LogLevel::Info => Some("info"),
+        pages::LogLevel::Warning => Some("warning"),
+        pages::LogLevel::Error => Some("error"),
+    }
+}
+
+impl PageController for LogsController {
+    async fn enter(&mut self, ctx: &mut Context<'_>) {
+        self.selected_index = 0;
+        self.search_mode = false;
+        self.search_query.clear();
+        self.data.clear();
+        self.past = None;
+        self.history = None;
+        if ctx.config.log_history_mb > 0 {
+            match log_history::default_path() {
+                Ok(path) => {
+                    self.history = Some(LogHistory::new(
+                        path,
+                        ctx.config.log_history_mb * 1024 * 1024,
+                    ))
+                }
+                Err(e) => ctx.state.report_error("Log history is unavailable", &e),
+            }
+        }
+        let (rules, problems) = LogRules::compile(&ctx.config.log_rules);
+        self.rules = rules;
+        if !problems.is_empty() {
+            ctx.state.report_error(
+                "Skipped invalid log rules",
+                &anyhow::anyhow!(problems.join("; ")),
+            );
+        }
+        self.start_stream(ctx.state, "connecting");
+    }
+
+    fn leave(&mut self, state: &mut AppState) {
+        self.stop_stream(state);
+        self.connected = false;
+        self.status_detail = None;
+        self.retry = None;
+    }
+
+    async fn handle_key(&mut self, key: KeyEvent, ctx: &mut Context<'_>) {
+        if self.search_mode {
+            // Handle search mode input
+            match key.code {
+                KeyCode::Char(c) => {
+                    self.search_query.push(c);
+                    self.selected_index = 0;
+                }
+                KeyCode::Backspace => {
+                    self.search_query.pop();
+                    self.selected_index = 0;
+                }
+                KeyCode::Esc => {
+                    self.search_mode = false;
+                    self.search_query.clear();
+                }
+                KeyCode::Enter => {
+                    self.search_mode = false;
+                    if self.past.is_some() {
+                        self.search_history(ctx.state);
+                    }
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        let state = &mut *ctx.state;
+        match key.code {
+            KeyCode::Esc if self.past.is_some() => {
+                self.past = None;
+                self.selected_index = 0;
+            }
+            KeyCode::Char('q') | KeyCode::Esc | KeyCode::Char('h') => ctx.navigate(Page::Home),
+            KeyCode::Char('H') => {
+                if self.past.take().is_some() {
+                    self.selected_index = 0;
+                } else {
+                    self.search_history(state);
+                }
+            }
+            KeyCode::Char('r') => {
+                // Refresh logs
+                state.status_message = Some("Reconnecting logs...".to_string());
+                self.data.clear();
+                self.past = None;
+                self.selected_index = 0;
+                self.start_stream(state, "reconnecting");
+            }
+            KeyCode::Char('f') | KeyCode::Char('F') => {
+                // Change filter level
+                self.level_filter = self.level_filter.next();
+                self.selected_index = 0;
+                state.status_message = Some(format!("Filter: {}", self.level_filter.as_str()));
+                self.data.clear();
+                self.start_stream(state, "reconnecting");
+                if self.past.is_some() {
+                    self.search_history(state);
+                }
+            }
+            KeyCode::Char('L') => {
+                // Cycle the core's own log level, not the display filter
+                let level =
+                    pages::logs::next_core_log_level(state.clash_state.log_level.as_deref());
+                state.set_core_log_level(level);
+            }
+            KeyCode::Char('/') => {
+                // Enter search mode
+                self.search_mode = true;
+                self.search_query.clear();
+            }
+            KeyCode::Up => {
+                self.selected_index = self.selected_index.saturating_sub(1);
+            }
+            KeyCode::Down if self.selected_index + 1 < self.filtered_count() => {
+                self.selected_index += 1;
+            }
+            code @ (KeyCode::PageUp | KeyCode::PageDown | KeyCode::Home | KeyCode::End) => {
+                if let Some(index) = list::jump(code, self.selected_index, self.filtered_count()) {
+                    self.selected_index = index;
+                }
+            }
+            KeyCode::Enter => {
+                let target =
+                    pages::filter_logs(self.shown(), self.level_filter, &self.search_query)
+                        .get(self.selected_index)
+                        .and_then(|log| log.connection.clone());
+                match target {
+                    Some(target) => ctx.emit(AppEvent::ShowConnection(target)),
+                    None => {
+                        state.status_message =
+                            Some("This log line has no connection details".to_string());
+                    }
+                }
+            }
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                ctx.emit(AppEvent::ConfirmQuit);
+            }
+            _ => {}
+        }
+    }
+
+    async fn tick(&mut self, ctx: &mut Context<'_>) -> bool {
+        let mut changed = false;
+        let mut received = Vec::new();
+        while let Ok(event) = self.rx.try_recv() {
+            changed = true;
+            match event {
+                LogStreamEvent::Entry(mut entry) => {
+                    if !self.rules.apply(&mut entry) {
+                        continue;
+                    }
+                    // Keep the selected line in place as new lines arrive on top
+                    if self.selected_index > 0
+                        && self.past.is_none()
+                        && pages::log_matches(&entry, self.level_filter, &self.search_query)
+                    {
+                        self.selected_index += 1;
+                    }
+                    if self.history.is_some() {
+                        received.push(entry.clone());
+                    }
+                    self.data.insert(0, entry);
+                    self.data.truncate(MAX_LINES);
+                }
+                LogStreamEvent::Status(status) => match status {
+                    LogStreamStatus::Connected => {
+                        self.connected = true;
+                        self.status_detail = None;
+                        self.retry = None;
+                    }
+                    LogStreamStatus::Disconnected(reason) => {
+                        self.connected = false;
+                        self.status_detail = Some(reason);
+                    }
+                    LogStreamStatus::Retrying { attempt, retry_at } => {
+                        self.retry = Some((attempt, retry_at));
+                    }
+                },
+            }
+        }
+        if let Some(history) = self.history.as_mut() {
+            if let Err(e) = history.append(&received, chrono::Utc::now().timestamp()) {
+                ctx.state
+                    .report_error("Stopped writing the log history", &e);
+                self.history = None;
+            }
+        }
+        changed
+    }
+
+    async fn on_event(&mut self, event: &AppEvent, ctx: &mut Context<'_>) {
+        // Resubscribe so the stream matches the new core level
+        if let AppEvent::Api(ApiResponse::LogLevelSet { result: Ok(()), .. }) = event {
+            if ctx.state.current_page == Page::Logs {
+                self.start_stream(ctx.state, "reconnecting");
+            }
+        }
+    }
+
+    fn captures_text(&self) -> bool {
+        self.search_mode
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, state: &AppState, _config: &AppConfig) {
+        pages::render_logs(
+            f,
+            area,
+            state,
+            self.shown(),
+            self.past.is_some(),
+            self.level_filter,
+            &self.search_query,
+            self.selected_index,
+            self.connected,
+            self.status_detail.as_deref(),
+            self.retry,
+            &self.rules,
+        );
+    }
+}
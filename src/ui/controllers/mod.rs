@@ -0,0 +1,627 @@
+//! Pages as controllers: each page owns its state, handles its keys and renders itself
+//! `Controllers` routes input, API responses and cross-page events to them
+
+mod connections;
+mod dialogs;
+mod favorites;
+mod groups;
+mod heatmap;
+mod home;
+mod logs;
+mod performance;
+mod routes;
+mod rules;
+mod settings;
+mod statistics;
+mod update;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{layout::Rect, Frame};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, watch};
+
+use super::palette::PaletteAction;
+use super::{
+    spawn_bypass_removal, spawn_gui_check, take_clash_config_missing, theme, ControllerOffer,
+    ConversionReport, ProfilePreview,
+};
+use crate::app::gui_guard::GUI_CHECK_INTERVAL;
+use crate::app::probe::PROBE_INTERVAL;
+use crate::app::{ApiRequest, ApiResponse, AppState, Page};
+use crate::clash::{is_unauthorized, HumanRoute, LogConnection, ProxyType, Rule};
+use crate::config::bypass::Bypass;
+use crate::config::validate::ConfigProblem;
+use crate::config::{stats, AppConfig};
+
+/// How often the Clash state is refreshed
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How often provider subscriptions are checked for an upcoming expiry
+const SUBSCRIPTION_EXPIRY_CHECK_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// How often traffic is sampled for alert thresholds away from the Connections/Performance pages
+const ALERT_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How long expired bypasses wait after a failed removal before it's tried again
+const BYPASS_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Something the app reacts to
+pub enum AppEvent {
+    /// A key press, for the open dialog or else the current page
+    Key(KeyEvent),
+    /// A finished Clash API request, seen by every page
+    Api(ApiResponse),
+    /// Open a page
+    Navigate(Page),
+    /// Open the Connections page with this connection selected
+    ShowConnection(LogConnection),
+    /// Open the Rules page searching for this rule
+    ShowRule(String),
+    /// Ask before quitting
+    ConfirmQuit,
+    /// Open the error console
+    ShowErrors,
+    /// Open the command palette
+    ShowPalette,
+    /// Open the key bindings of the current page
+    ShowHelp,
+    /// Offer the controller found in the Clash config after the API was unreachable
+    OfferController(ControllerOffer),
+    /// Ask for the secret after the core rejected the configured one
+    PromptSecret,
+    /// Run an action picked in the command palette
+    RunAction(PaletteAction),
+    /// Quit right away
+    Quit,
+    /// Config problems blocking a reload, shown until dismissed
+    ShowProblems(String, Vec<ConfigProblem>),
+    /// Share links a subscription lost in conversion, shown until dismissed
+    ShowConversionReport(String, ConversionReport),
+    /// A profile change to apply once its diff is confirmed
+    PreviewProfile(Box<ProfilePreview>),
+    /// Subscription files changed on disk
+    SubscriptionsChanged,
+    /// Another profile was switched to, with the rules it loaded
+    ProfileSwitched(Vec<Rule>),
+    /// Hand the terminal to the user's editor for this file
+    EditFile(PathBuf),
+    /// The editor opened by `EditFile` exited, with the error if it couldn't run
+    FileEdited(PathBuf, Option<String>),
+}
+
+/// What a controller may touch while handling an event
+pub struct Context<'a> {
+    pub state: &'a mut AppState,
+    pub config: &'a mut AppConfig,
+    /// Start of the current auto-refresh interval; reset it to put the next refresh off
+    pub last_refresh: &'a mut Instant,
+    /// Events raised for the app or other pages, dispatched once the handler returns
+    pub events: Vec<AppEvent>,
+}
+
+impl Context<'_> {
+    pub fn emit(&mut self, event: AppEvent) {
+        self.events.push(event);
+    }
+
+    pub fn navigate(&mut self, page: Page) {
+        self.emit(AppEvent::Navigate(page));
+    }
+}
+
+/// One page: its state, input handling and rendering
+pub trait PageController {
+    /// The page was opened
+    async fn enter(&mut self, _ctx: &mut Context<'_>) {}
+
+    /// Another page was opened
+    fn leave(&mut self, _state: &mut AppState) {}
+
+    async fn handle_key(&mut self, key: KeyEvent, ctx: &mut Context<'_>);
+
+    /// Whether typed characters go to a text field, so app-wide keys are left alone
+    fn captures_text(&self) -> bool {
+        false
+    }
+
+    /// Background work, run every loop whichever page is open
+    /// Returns whether it changed what the page shows
+    async fn tick(&mut self, _ctx: &mut Context<'_>) -> bool {
+        false
+    }
+
+    /// API responses and changes made elsewhere, seen by every page
+    async fn on_event(&mut self, _event: &AppEvent, _ctx: &mut Context<'_>) {}
+
+    fn render(&self, f: &mut Frame, area: Rect, state: &AppState, config: &AppConfig);
+}
+
+/// Run `$body` with `$c` bound to the controller of `$page`
+macro_rules! with_controller {
+    ($self:ident, $page:expr, |$c:ident| $body:expr) => {
+        match $page {
+            Page::Home => {
+                let $c = &mut $self.home;
+                $body
+            }
+            Page::Routes => {
+                let $c = &mut $self.routes;
+                $body
+            }
+            Page::Rules => {
+                let $c = &mut $self.rules;
+                $body
+            }
+            Page::Update => {
+                let $c = &mut $self.update;
+                $body
+            }
+            Page::Connections => {
+                let $c = &mut $self.connections;
+                $body
+            }
+            Page::Settings => {
+                let $c = &mut $self.settings;
+                $body
+            }
+            Page::Logs => {
+                let $c = &mut $self.logs;
+                $body
+            }
+            Page::Performance => {
+                let $c = &mut $self.performance;
+                $body
+            }
+            Page::Statistics => {
+                let $c = &mut $self.statistics;
+                $body
+            }
+            Page::Favorites => {
+                let $c = &mut $self.favorites;
+                $body
+            }
+            Page::Groups => {
+                let $c = &mut $self.groups;
+                $body
+            }
+            Page::Heatmap => {
+                let $c = &mut $self.heatmap;
+                $body
+            }
+        }
+    };
+}
+
+/// Every page's controller, plus the dialogs drawn over them
+pub struct Controllers {
+    home: home::HomeController,
+    routes: routes::RoutesController,
+    rules: rules::RulesController,
+    update: update::UpdateController,
+    connections: connections::ConnectionsController,
+    settings: settings::SettingsController,
+    logs: logs::LogsController,
+    performance: performance::PerformanceController,
+    statistics: statistics::StatisticsController,
+    favorites: favorites::FavoritesController,
+    groups: groups::GroupsController,
+    heatmap: heatmap::HeatmapController,
+    dialogs: dialogs::Dialogs,
+    quit: bool,
+    /// File to open in the user's editor before the next frame
+    pending_edit: Option<PathBuf>,
+    expiry_last_check: Option<Instant>,
+    alert_last_check: Instant,
+    probe_last_check: Option<Instant>,
+    /// Expired bypasses being taken out of the Clash config in the background
+    bypass_removal: Option<mpsc::UnboundedReceiver<(Bypass, anyhow::Result<()>)>>,
+    bypass_last_failure: Option<Instant>,
+    /// Background look for a running GUI, see `AppState::gui`
+    gui_check: Option<mpsc::UnboundedReceiver<Option<&'static str>>>,
+    gui_last_check: Option<Instant>,
+}
+
+impl Controllers {
+    pub fn new(rule_stats: watch::Receiver<stats::RuleStats>) -> Self {
+        Self {
+            home: home::HomeController::default(),
+            routes: routes::RoutesController::default(),
+            rules: rules::RulesController::new(rule_stats),
+            update: update::UpdateController::new(),
+            connections: connections::ConnectionsController::default(),
+            settings: settings::SettingsController::new(),
+            logs: logs::LogsController::new(),
+            performance: performance::PerformanceController::default(),
+            statistics: statistics::StatisticsController::default(),
+            favorites: favorites::FavoritesController::default(),
+            groups: groups::GroupsController::default(),
+            heatmap: heatmap::HeatmapController::default(),
+            dialogs: dialogs::Dialogs::default(),
+            quit: false,
+            pending_edit: None,
+            expiry_last_check: None,
+            alert_last_check: Instant::now(),
+            probe_last_check: None,
+            bypass_removal: None,
+            bypass_last_failure: None,
+            gui_check: None,
+            gui_last_check: None,
+        }
+    }
+
+    /// Whether the user chose to quit
+    pub fn should_quit(&self) -> bool {
+        self.quit
+    }
+
+    /// File a page asked to edit, once
+    pub fn take_pending_edit(&mut self) -> Option<PathBuf> {
+        self.pending_edit.take()
+    }
+
+    /// Handle an event and every event raised while handling it, in order
+    pub async fn dispatch(
+        &mut self,
+        event: AppEvent,
+        state: &mut AppState,
+        config: &mut AppConfig,
+        last_refresh: &mut Instant,
+    ) {
+        let mut queue = VecDeque::from([event]);
+        while let Some(event) = queue.pop_front() {
+            state.touch();
+            let mut ctx = Context {
+                state: &mut *state,
+                config: &mut *config,
+                last_refresh: &mut *last_refresh,
+                events: Vec::new(),
+            };
+            match event {
+                AppEvent::Key(key) => {
+                    if !self.dialogs.handle_key(key, &mut ctx).await {
+                        let page = ctx.state.current_page;
+                        with_controller!(self, page, |c| {
+                            if key.code == KeyCode::Char('!') && !c.captures_text() {
+                                ctx.emit(AppEvent::ShowErrors);
+                            } else if key.code == KeyCode::Char(':') && !c.captures_text() {
+                                ctx.emit(AppEvent::ShowPalette);
+                            } else if key.code == KeyCode::Char('?') && !c.captures_text() {
+                                ctx.emit(AppEvent::ShowHelp);
+                            } else {
+                                c.handle_key(key, &mut ctx).await;
+                            }
+                        });
+                    }
+                }
+                AppEvent::Navigate(page) => self.navigate(page, &mut ctx).await,
+                AppEvent::ShowConnection(target) => {
+                    self.navigate(Page::Connections, &mut ctx).await;
+                    self.connections.jump_to(target);
+                }
+                AppEvent::ShowRule(query) => {
+                    self.navigate(Page::Rules, &mut ctx).await;
+                    if ctx.state.current_page == Page::Rules {
+                        self.rules.show_rule(query);
+                    }
+                }
+                AppEvent::ConfirmQuit => self.dialogs.confirm_quit(),
+                AppEvent::ShowErrors => {
+                    self.dialogs.show_errors();
+                    ctx.state.messages.mark_read();
+                }
+                AppEvent::ShowPalette => self.dialogs.show_palette(),
+                AppEvent::ShowHelp => self.dialogs.show_help(),
+                AppEvent::OfferController(offer) => self.dialogs.offer_controller(offer),
+                AppEvent::PromptSecret => self.dialogs.prompt_secret(),
+                AppEvent::RunAction(action) => self.run_action(action, &mut ctx).await,
+                AppEvent::Quit => self.quit = true,
+                AppEvent::EditFile(path) => self.pending_edit = Some(path),
+                AppEvent::ShowProblems(title, problems) => {
+                    self.dialogs.show_problems(title, problems)
+                }
+                AppEvent::ShowConversionReport(name, report) => {
+                    self.dialogs.show_conversion_report(name, report)
+                }
+                AppEvent::PreviewProfile(preview) => self.dialogs.show_preview(*preview),
+                event => {
+                    for page in Page::ALL {
+                        with_controller!(self, page, |c| c.on_event(&event, &mut ctx).await);
+                    }
+                    if matches!(event, AppEvent::Api(ApiResponse::Refreshed { .. }))
+                        && ctx
+                            .state
+                            .clash_state
+                            .error
+                            .as_deref()
+                            .is_some_and(is_unauthorized)
+                    {
+                        self.dialogs.prompt_secret();
+                    }
+                }
+            }
+            // A preset switch may hide the open page
+            if !ctx.state.preset.shows_page(ctx.state.current_page) {
+                self.navigate(Page::Home, &mut ctx).await;
+            }
+            if take_clash_config_missing() {
+                self.dialogs.pick_clash_config(ctx.config);
+            }
+            ctx.state.log_status();
+            queue.extend(ctx.events);
+        }
+    }
+
+    /// Background work of every page, then the app-wide refresh timers
+    pub async fn tick(
+        &mut self,
+        state: &mut AppState,
+        config: &mut AppConfig,
+        last_refresh: &mut Instant,
+    ) {
+        let mut ctx = Context {
+            state: &mut *state,
+            config: &mut *config,
+            last_refresh: &mut *last_refresh,
+            events: Vec::new(),
+        };
+        for page in Page::ALL {
+            if with_controller!(self, page, |c| c.tick(&mut ctx).await) {
+                ctx.state.touch();
+            }
+        }
+
+        let refresh_interval = ctx
+            .state
+            .preset
+            .refresh_secs()
+            .map_or(REFRESH_INTERVAL, Duration::from_secs);
+        if ctx.last_refresh.elapsed() >= theme::paced(refresh_interval) {
+            ctx.state.request(ApiRequest::Refresh);
+            *ctx.last_refresh = Instant::now();
+        }
+
+        // Look for subscriptions about to expire
+        if ctx.state.notifier.wants_subscriptions()
+            && self
+                .expiry_last_check
+                .is_none_or(|at| at.elapsed() >= SUBSCRIPTION_EXPIRY_CHECK_INTERVAL)
+        {
+            ctx.state.request(ApiRequest::Providers);
+            self.expiry_last_check = Some(Instant::now());
+        }
+
+        ctx.state.notifier.tick();
+
+        // Check proxying end to end, not just the controller
+        if let Some(url) = ctx.config.probe_url() {
+            if self
+                .probe_last_check
+                .is_none_or(|at| at.elapsed() >= PROBE_INTERVAL)
+            {
+                ctx.state.request(ApiRequest::ProbeProxy(url.to_string()));
+                self.probe_last_check = Some(Instant::now());
+            }
+        }
+
+        if take_clash_config_missing() {
+            self.dialogs.pick_clash_config(ctx.config);
+            ctx.state.touch();
+        }
+
+        // Take expired DIRECT bypasses back out of the Clash config
+        if let Some(rx) = self.bypass_removal.as_mut() {
+            loop {
+                match rx.try_recv() {
+                    Ok((bypass, Ok(()))) => {
+                        ctx.state.status_message =
+                            Some(format!("Bypass of {} expired, rule removed", bypass.suffix));
+                    }
+                    Ok((bypass, Err(e))) => {
+                        ctx.state.report_error(
+                            &format!("Failed to remove expired rule {}", bypass.rule()),
+                            &e,
+                        );
+                        // Kept, so the removal is tried again
+                        ctx.state.bypasses.push(bypass);
+                        self.bypass_last_failure = Some(Instant::now());
+                    }
+                    Err(mpsc::error::TryRecvError::Empty) => break,
+                    Err(mpsc::error::TryRecvError::Disconnected) => {
+                        self.bypass_removal = None;
+                        ctx.state.save_bypasses();
+                        break;
+                    }
+                }
+                ctx.state.touch();
+            }
+        }
+        let now = chrono::Utc::now().timestamp();
+        if self.bypass_removal.is_none()
+            && self
+                .bypass_last_failure
+                .is_none_or(|at| at.elapsed() >= BYPASS_RETRY_INTERVAL)
+            && ctx
+                .state
+                .bypasses
+                .iter()
+                .any(|bypass| bypass.expires_at <= now)
+        {
+            // Saved once every removal is done, so a quit midway leaves them to retry
+            let (expired, active) = std::mem::take(&mut ctx.state.bypasses)
+                .into_iter()
+                .partition::<Vec<_>, _>(|bypass| bypass.expires_at <= now);
+            ctx.state.bypasses = active;
+            let client = ctx.state.clash_state.client.clone();
+            self.bypass_removal = Some(spawn_bypass_removal(
+                &mut ctx.state.tasks,
+                ctx.config,
+                client,
+                expired,
+            ));
+        }
+
+        // Keep watching for a GUI that may overwrite the files clashctl writes
+        if let Some(rx) = self.gui_check.as_mut() {
+            match rx.try_recv() {
+                Ok(running) => {
+                    if running != ctx.state.gui.running() {
+                        ctx.state.gui.set_running(running);
+                        ctx.state.touch();
+                    }
+                    self.gui_check = None;
+                }
+                Err(mpsc::error::TryRecvError::Empty) => {}
+                Err(mpsc::error::TryRecvError::Disconnected) => self.gui_check = None,
+            }
+        }
+        if self.gui_check.is_none()
+            && self
+                .gui_last_check
+                .is_none_or(|at| at.elapsed() >= GUI_CHECK_INTERVAL)
+        {
+            self.gui_check = Some(spawn_gui_check(&mut ctx.state.tasks, ctx.config));
+            self.gui_last_check = Some(Instant::now());
+        }
+
+        // Keep sampling traffic in the background while an alert threshold is set
+        if ctx.config.alerts.is_active()
+            && !matches!(
+                ctx.state.current_page,
+                Page::Connections | Page::Performance
+            )
+            && self.alert_last_check.elapsed() >= theme::paced(ALERT_CHECK_INTERVAL)
+        {
+            ctx.state.request(ApiRequest::Connections);
+            self.alert_last_check = Instant::now();
+        }
+
+        ctx.state.log_status();
+        for event in ctx.events {
+            self.dispatch(event, state, config, last_refresh).await;
+        }
+    }
+
+    /// Render the current page
+    pub fn render(&self, f: &mut Frame, area: Rect, state: &AppState, config: &AppConfig) {
+        match state.current_page {
+            Page::Home => self.home.render(f, area, state, config),
+            Page::Routes => self.routes.render(f, area, state, config),
+            Page::Rules => self.rules.render(f, area, state, config),
+            Page::Update => self.update.render(f, area, state, config),
+            Page::Connections => self.connections.render(f, area, state, config),
+            Page::Settings => self.settings.render(f, area, state, config),
+            Page::Logs => self.logs.render(f, area, state, config),
+            Page::Performance => self.performance.render(f, area, state, config),
+            Page::Statistics => self.statistics.render(f, area, state, config),
+            Page::Favorites => self.favorites.render(f, area, state, config),
+            Page::Groups => self.groups.render(f, area, state, config),
+            Page::Heatmap => self.heatmap.render(f, area, state, config),
+        }
+    }
+
+    /// Render the open dialog, if any, over the whole screen
+    pub fn render_dialogs(&self, f: &mut Frame, state: &AppState) {
+        self.dialogs.render(f, state);
+    }
+
+    /// Do what a palette entry says, as its page key would
+    async fn run_action(&mut self, action: PaletteAction, ctx: &mut Context<'_>) {
+        let state = &mut *ctx.state;
+        match action {
+            PaletteAction::GoTo(page) => self.navigate(page, ctx).await,
+            PaletteAction::SwitchMode(mode) => {
+                state.switch_mode(mode);
+                *ctx.last_refresh = Instant::now();
+            }
+            PaletteAction::TestPrimaryGroup | PaletteAction::PickFastest => {
+                let primary = state.clash_state.current_selector.clone();
+                let Some(route) = HumanRoute::from_proxies(&state.clash_state.proxies, state.mode)
+                    .into_iter()
+                    .find(|route| Some(&route.name) == primary.as_ref())
+                else {
+                    state.status_message = Some("No primary group to act on".to_string());
+                    return;
+                };
+                if !state.preset.show_speed_test() {
+                    state.status_message =
+                        Some("Speed test disabled in current preset".to_string());
+                } else if action == PaletteAction::PickFastest {
+                    if route.proxy_type != ProxyType::Selector {
+                        state.status_message =
+                            Some(format!("{} picks its own node", route.display_name()));
+                        return;
+                    }
+                    state.auto_select(
+                        &route.name,
+                        &route.all_nodes,
+                        ctx.config.auto_select_max_delay,
+                    );
+                    *ctx.last_refresh = Instant::now();
+                } else {
+                    let testable: Vec<String> = route
+                        .all_nodes
+                        .iter()
+                        .filter(|node| state.is_node_testable(node))
+                        .cloned()
+                        .collect();
+                    state.status_message = Some(format!(
+                        "Testing {} nodes in {}...",
+                        testable.len(),
+                        route.display_name()
+                    ));
+                    for node in testable {
+                        state.start_test_delay(node);
+                    }
+                }
+            }
+            PaletteAction::UpdateAllSubscriptions => {
+                self.navigate(Page::Update, ctx).await;
+                if ctx.state.allow_gui_write("Update all subscriptions") {
+                    self.update.update_all(ctx.state, ctx.config);
+                }
+            }
+            PaletteAction::CloseAllConnections => state.request(ApiRequest::CloseAllConnections),
+            PaletteAction::ToggleTun => state.request(ApiRequest::ToggleTun),
+            PaletteAction::Refresh => {
+                state.request(ApiRequest::Refresh);
+                *ctx.last_refresh = Instant::now();
+            }
+            PaletteAction::CyclePreset => state.cycle_preset(ctx.config),
+            PaletteAction::CycleTheme => {
+                let theme = ctx.config.get_theme().next();
+                let _ = ctx.config.set_theme(theme);
+                state.status_message = Some(format!("Switched to {} theme", theme.name()));
+            }
+            PaletteAction::ShowErrors => {
+                self.dialogs.show_errors();
+                state.messages.mark_read();
+            }
+            PaletteAction::ShowMessages => {
+                self.dialogs.show_messages();
+                state.messages.mark_read();
+            }
+            PaletteAction::ShowHelp => self.dialogs.show_help(),
+            PaletteAction::Quit => self.dialogs.confirm_quit(),
+        }
+    }
+
+    async fn navigate(&mut self, page: Page, ctx: &mut Context<'_>) {
+        let current = ctx.state.current_page;
+        if page == current {
+            return;
+        }
+        if !ctx.state.preset.shows_page(page) {
+            ctx.state.status_message = Some(format!(
+                "The {} page is hidden in the {} preset",
+                page.as_str(),
+                ctx.state.preset.name()
+            ));
+            return;
+        }
+        with_controller!(self, current, |c| c.leave(ctx.state));
+        ctx.state.current_page = page;
+        with_controller!(self, page, |c| c.enter(ctx).await);
+    }
+}
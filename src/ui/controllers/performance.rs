@@ -0,0 +1,143 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{layout::Rect, Frame};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use super::{AppEvent, Context, PageController};
+use crate::app::{ApiRequest, ApiResponse, AppState, Page};
+use crate::clash::ConnectionsResponse;
+use crate::config::AppConfig;
+use crate::ui::{pages, rate_per_sec, theme};
+
+/// How often traffic is sampled while the page is open
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Traffic totals and rates, sampled from the connections endpoint
+pub struct PerformanceController {
+    last_refresh: Instant,
+    sampled_at: Option<Instant>,
+    upload_total: u64,
+    download_total: u64,
+    upload_rate: u64,
+    download_rate: u64,
+    connection_count: usize,
+    /// Latest connections, summed per exit when drawn
+    connections: Option<ConnectionsResponse>,
+    /// Connection id -> (up, down) B/s
+    rates: HashMap<String, (u64, u64)>,
+    /// Group traffic by the rule's group instead of the exit node
+    by_group: bool,
+}
+
+impl Default for PerformanceController {
+    fn default() -> Self {
+        Self {
+            last_refresh: Instant::now(),
+            sampled_at: None,
+            upload_total: 0,
+            download_total: 0,
+            upload_rate: 0,
+            download_rate: 0,
+            connection_count: 0,
+            connections: None,
+            rates: HashMap::new(),
+            by_group: false,
+        }
+    }
+}
+
+impl PageController for PerformanceController {
+    async fn enter(&mut self, ctx: &mut Context<'_>) {
+        // Fetch initial performance data
+        self.sampled_at = None;
+        self.upload_rate = 0;
+        self.download_rate = 0;
+        self.connections = None;
+        self.rates.clear();
+        ctx.state.request(ApiRequest::Connections);
+        self.last_refresh = Instant::now();
+    }
+
+    async fn handle_key(&mut self, key: KeyEvent, ctx: &mut Context<'_>) {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc | KeyCode::Char('h') => ctx.navigate(Page::Home),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                ctx.emit(AppEvent::ConfirmQuit);
+            }
+            KeyCode::Char('c') => ctx.navigate(Page::Connections),
+            KeyCode::Char('g') => self.by_group = !self.by_group,
+            KeyCode::Char('r') => {
+                // Manual refresh
+                ctx.state.request(ApiRequest::Connections);
+                self.last_refresh = Instant::now();
+            }
+            _ => {}
+        }
+    }
+
+    async fn tick(&mut self, ctx: &mut Context<'_>) -> bool {
+        if ctx.state.current_page == Page::Performance
+            && self.last_refresh.elapsed() >= theme::paced(REFRESH_INTERVAL)
+        {
+            ctx.state.request(ApiRequest::Connections);
+            self.last_refresh = Instant::now();
+        }
+        false
+    }
+
+    async fn on_event(&mut self, event: &AppEvent, ctx: &mut Context<'_>) {
+        let AppEvent::Api(ApiResponse::Connections(Ok(data))) = event else {
+            return;
+        };
+        // Rates are averaged since the previous sample
+        if let Some(sampled_at) = self.sampled_at {
+            let elapsed = sampled_at.elapsed();
+            if let Some(rate) = rate_per_sec(data.upload_total, self.upload_total, elapsed) {
+                self.upload_rate = rate;
+            }
+            if let Some(rate) = rate_per_sec(data.download_total, self.download_total, elapsed) {
+                self.download_rate = rate;
+            }
+            self.rates = self
+                .connections
+                .as_ref()
+                .map(|previous| {
+                    pages::connection_rates(previous, data, |current, before| {
+                        rate_per_sec(current, before, elapsed)
+                    })
+                })
+                .unwrap_or_default();
+        }
+        self.connections = Some(data.clone());
+        self.upload_total = data.upload_total;
+        self.download_total = data.download_total;
+        self.connection_count = data.connections.len();
+        self.sampled_at = Some(Instant::now());
+        ctx.state.notifier.thresholds(
+            &ctx.config.alerts,
+            self.download_rate,
+            self.connection_count,
+        );
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, state: &AppState, config: &AppConfig) {
+        let exits = self
+            .connections
+            .as_ref()
+            .map(|data| pages::exit_traffic(data, &self.rates, self.by_group))
+            .unwrap_or_default();
+        pages::render_performance(
+            f,
+            area,
+            state,
+            self.upload_total,
+            self.download_total,
+            self.upload_rate,
+            self.download_rate,
+            self.connection_count,
+            &config.alerts,
+            &exits,
+            self.by_group,
+        );
+    }
+}
@@ -0,0 +1,880 @@
+use chrono::{DateTime, Local};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{layout::Rect, Frame};
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+use tokio::sync::mpsc;
+
+use super::{AppEvent, Context, PageController};
+use crate::app::delay_log::ExportFormat;
+use crate::app::node_info::NodeOrigins;
+use crate::app::region::{self, RegionGroup, RegionRow};
+use crate::app::{ApiRequest, ApiResponse, AppState, Page};
+use crate::clash::ProxyType;
+use crate::config::clash_config::{read_group_health_check, GroupHealthCheck};
+use crate::config::delays::{self, DelaySample};
+use crate::config::AppConfig;
+use crate::ui::search::SearchFilter;
+use crate::ui::{
+    collect_proxy_sources, export_share_links, list, pages, poll_node_group_syncs,
+    resolve_clash_config_path, save_group_health_check, spawn_node_group_sync, NodeGroupSynced,
+};
+
+/// Route groups, and the nodes of the expanded one
+#[derive(Default)]
+pub struct RoutesController {
+    selected_route: usize,
+    /// Whether viewing node list
+    expanded: bool,
+    /// Whether reordering/hiding route groups
+    edit_mode: bool,
+    selected_node: usize,
+    /// Nodes marked for batch actions
+    marked_nodes: Vec<String>,
+    /// Group name prompt for marked nodes
+    group_input: Option<String>,
+    /// url-test/fallback settings editor
+    health_edit: Option<pages::HealthCheckEdit>,
+    /// Find-as-you-type query in the node list
+    node_search: Option<String>,
+    /// Waiting for the format of a delay test export
+    export_prompt: bool,
+    /// Whether the node list is grouped by region
+    by_region: bool,
+    /// Regions whose nodes are hidden in the grouped list
+    collapsed_regions: HashSet<String>,
+    /// Region header under the cursor; when None the cursor is on the selected node
+    region_header: Option<String>,
+    /// Subscriptions each node is defined in, read when a group is opened
+    origins: Option<NodeOrigins>,
+    /// When each node was switched to this session
+    selected_at: HashMap<String, DateTime<Local>>,
+    /// Delay history file, charted in the node info
+    delay_history: Vec<DelaySample>,
+    /// Node groups being written to the Clash config in the background
+    syncs: Vec<mpsc::UnboundedReceiver<NodeGroupSynced>>,
+}
+
+impl RoutesController {
+    /// Collapse every region but the selected node's
+    fn collapse_regions(&mut self, nodes: &[String]) {
+        let open = nodes
+            .get(self.selected_node)
+            .and_then(|n| region::region_of(n));
+        self.collapsed_regions = region::group_by_region(nodes)
+            .into_iter()
+            .filter(|group| Some(group.region) != open)
+            .map(|group| group.region.to_string())
+            .collect();
+        self.region_header = None;
+    }
+
+    /// Put the cursor back on the selected node, opening its region
+    fn reveal_selected_node(&mut self, nodes: &[String]) {
+        if let Some(node) = nodes.get(self.selected_node) {
+            let region = region::region_of(node).unwrap_or(region::OTHER_REGION);
+            self.collapsed_regions.remove(region);
+        }
+        self.region_header = None;
+    }
+
+    /// Open a collapsed region, or collapse an open one
+    fn toggle_region(&mut self, region: &str) {
+        if !self.collapsed_regions.remove(region) {
+            self.collapsed_regions.insert(region.to_string());
+        }
+    }
+
+    /// Row of the grouped list under the cursor
+    fn region_cursor(&self, groups: &[RegionGroup], rows: &[RegionRow]) -> usize {
+        rows.iter()
+            .position(|row| match row {
+                RegionRow::Header(group) => {
+                    self.region_header.as_deref() == Some(groups[*group].region)
+                }
+                RegionRow::Node(node) => {
+                    self.region_header.is_none() && *node == self.selected_node
+                }
+            })
+            .unwrap_or(0)
+    }
+
+    fn set_region_cursor(&mut self, groups: &[RegionGroup], row: RegionRow) {
+        match row {
+            RegionRow::Header(group) => {
+                self.region_header = Some(groups[group].region.to_string());
+            }
+            RegionRow::Node(node) => {
+                self.region_header = None;
+                self.selected_node = node;
+            }
+        }
+    }
+}
+
+impl PageController for RoutesController {
+    async fn enter(&mut self, ctx: &mut Context<'_>) {
+        self.selected_route = 0;
+        self.selected_node = 0;
+        self.expanded = false;
+        ctx.state.request(ApiRequest::Refresh);
+        *ctx.last_refresh = Instant::now();
+    }
+
+    async fn handle_key(&mut self, key: KeyEvent, ctx: &mut Context<'_>) {
+        let (state, config) = (&mut *ctx.state, &mut *ctx.config);
+        let routes = config.arrange_routes(
+            crate::clash::HumanRoute::from_proxies(&state.clash_state.proxies, state.mode),
+            self.edit_mode,
+        );
+
+        if self.export_prompt {
+            self.export_prompt = false;
+            let format = match key.code {
+                KeyCode::Char('c') | KeyCode::Char('C') => ExportFormat::Csv,
+                KeyCode::Char('j') | KeyCode::Char('J') => ExportFormat::Json,
+                _ => {
+                    state.status_message = Some("Export cancelled".to_string());
+                    return;
+                }
+            };
+            match state.delay_log.export(format) {
+                Ok(path) => {
+                    state.status_message = Some(format!(
+                        "Exported {} delay tests to {}",
+                        state.delay_log.len(),
+                        path.display()
+                    ));
+                }
+                Err(e) => state.report_error("Failed to export delay tests", &e),
+            }
+            return;
+        }
+
+        if self.edit_mode {
+            // Route layout edit mode
+            let max_index = routes.len().saturating_sub(1);
+            let move_up = matches!(key.code, KeyCode::Char('K'))
+                || (key.code == KeyCode::Up && key.modifiers.contains(KeyModifiers::SHIFT));
+            let move_down = matches!(key.code, KeyCode::Char('J'))
+                || (key.code == KeyCode::Down && key.modifiers.contains(KeyModifiers::SHIFT));
+
+            if move_up || move_down {
+                let target = if move_up {
+                    self.selected_route.checked_sub(1)
+                } else {
+                    Some(self.selected_route + 1).filter(|idx| *idx <= max_index)
+                };
+                if let Some(target) = target {
+                    let mut order: Vec<String> =
+                        routes.iter().map(|route| route.name.clone()).collect();
+                    order.swap(self.selected_route, target);
+                    match config.set_route_order(order) {
+                        Ok(()) => self.selected_route = target,
+                        Err(e) => {
+                            state.report_error("Failed to save order", &e);
+                        }
+                    }
+                }
+                return;
+            }
+
+            match key.code {
+                KeyCode::Char('e') | KeyCode::Esc | KeyCode::Char('q') => {
+                    self.edit_mode = false;
+                    let visible = config
+                        .arrange_routes(
+                            crate::clash::HumanRoute::from_proxies(
+                                &state.clash_state.proxies,
+                                state.mode,
+                            ),
+                            false,
+                        )
+                        .len();
+                    self.selected_route = self.selected_route.min(visible.saturating_sub(1));
+                    state.status_message = Some("Route layout saved".to_string());
+                }
+                KeyCode::Up => {
+                    self.selected_route = self.selected_route.saturating_sub(1);
+                }
+                KeyCode::Down if self.selected_route < max_index => {
+                    self.selected_route += 1;
+                }
+                KeyCode::Char(' ') | KeyCode::Char('x') if self.selected_route < routes.len() => {
+                    let name = routes[self.selected_route].name.clone();
+                    match config.toggle_hidden_group(&name) {
+                        Ok(true) => state.status_message = Some(format!("Hidden {}", name)),
+                        Ok(false) => state.status_message = Some(format!("Showing {}", name)),
+                        Err(e) => state.report_error("Failed to save layout", &e),
+                    }
+                }
+                KeyCode::Char('R') => match config.reset_route_layout() {
+                    Ok(()) => {
+                        self.selected_route = 0;
+                        state.status_message = Some("Route layout reset to default".to_string());
+                    }
+                    Err(e) => {
+                        state.report_error("Failed to reset layout", &e);
+                    }
+                },
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    ctx.emit(AppEvent::Quit)
+                }
+                _ => {}
+            }
+        } else if !self.expanded {
+            // Route list mode
+            let max_index = routes.len().saturating_sub(1);
+
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => {
+                    // Return to Home instead of quitting
+                    ctx.navigate(Page::Home);
+                }
+                KeyCode::Char('h') => ctx.navigate(Page::Home),
+                KeyCode::Char('r') => {
+                    state.request(ApiRequest::Refresh);
+                    *ctx.last_refresh = Instant::now();
+                    self.expanded = false;
+                    self.selected_route = 0;
+                    self.selected_node = 0;
+                }
+                KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    state.cycle_preset(config)
+                }
+                KeyCode::Up => {
+                    self.selected_route = self.selected_route.saturating_sub(1);
+                }
+                KeyCode::Down if self.selected_route < max_index => {
+                    self.selected_route += 1;
+                }
+                KeyCode::Char('E') => {
+                    if state.delay_log.is_empty() {
+                        state.status_message =
+                            Some("No delay tests to export yet, press t to test".to_string());
+                    } else {
+                        self.export_prompt = true;
+                        state.status_message = Some(format!(
+                            "Export {} delay tests as: c CSV, j JSON (any other key cancels)",
+                            state.delay_log.len()
+                        ));
+                    }
+                }
+                KeyCode::Char('x') if self.selected_route < routes.len() => {
+                    // Export the selected group's nodes as share links
+                    let route = &routes[self.selected_route];
+                    state.status_message = Some(
+                        match export_share_links(config, &route.name, Some(&route.all_nodes)) {
+                            Ok(message) => message,
+                            Err(e) => format!("Export failed: {}", e),
+                        },
+                    );
+                }
+                KeyCode::Char('M') if self.selected_route < routes.len() => {
+                    // Take a node group written by clashctl back out of the Clash config
+                    let group = routes[self.selected_route].name.clone();
+                    if !config.is_group_materialized(&group) {
+                        state.status_message =
+                            Some(format!("{} was not written by clashctl", group));
+                    } else if state
+                        .allow_gui_write(&format!("Remove {} from the Clash config", group))
+                    {
+                        self.syncs
+                            .push(spawn_node_group_sync(state, config, &group, None, false));
+                        state.status_message =
+                            Some(format!("Removing {} from the Clash config...", group));
+                    }
+                }
+                KeyCode::Char('P') if self.selected_route < routes.len() => {
+                    // Make the selected group the primary one, or go back to auto-detection
+                    let group = routes[self.selected_route].name.clone();
+                    let primary = if config.primary_group.as_ref() == Some(&group) {
+                        None
+                    } else {
+                        Some(group)
+                    };
+                    match config.set_primary_group(primary.clone()) {
+                        Ok(()) => {
+                            state.clash_state.set_primary_group(primary);
+                            state.status_message = Some(match &config.primary_group {
+                                Some(group) => format!("{} is now the primary group", group),
+                                None => format!(
+                                    "Primary group auto-detected: {}",
+                                    state
+                                        .clash_state
+                                        .current_selector
+                                        .as_deref()
+                                        .unwrap_or("none")
+                                ),
+                            });
+                        }
+                        Err(e) => {
+                            state.report_error("Failed to save primary group", &e);
+                        }
+                    }
+                }
+                KeyCode::Char('X') => {
+                    // Export every node in the config and its providers
+                    state.status_message = Some(match export_share_links(config, "all", None) {
+                        Ok(message) => message,
+                        Err(e) => format!("Export failed: {}", e),
+                    });
+                }
+                KeyCode::Enter | KeyCode::Right if self.selected_route < routes.len() => {
+                    // Enter node selection mode
+                    self.expanded = true;
+                    self.selected_node = 0;
+                    self.marked_nodes.clear();
+                    self.group_input = None;
+                    self.health_edit = None;
+                    self.node_search = None;
+
+                    // Find current node index
+                    let route = &routes[self.selected_route];
+                    if let Some(current) = &route.current_node {
+                        if let Some(idx) = route.all_nodes.iter().position(|n| n == current) {
+                            self.selected_node = idx;
+                        }
+                    }
+                    if self.by_region {
+                        self.collapse_regions(&route.all_nodes);
+                    }
+                    self.delay_history = match delays::default_path().and_then(|p| delays::load(&p))
+                    {
+                        Ok(samples) => samples,
+                        Err(e) => {
+                            state.errors.record("Failed to load delay history", &e);
+                            Vec::new()
+                        }
+                    };
+                    if self.origins.is_none() {
+                        self.origins = Some(match collect_proxy_sources(config) {
+                            Ok(sources) => NodeOrigins::index(&sources),
+                            Err(e) => {
+                                state.errors.record("Failed to read node sources", &e);
+                                NodeOrigins::default()
+                            }
+                        });
+                    }
+                }
+                KeyCode::Char('e') => {
+                    self.edit_mode = true;
+                    state.status_message =
+                        Some("Editing route layout: K/J move, Space hide/show".to_string());
+                }
+                KeyCode::Char('t') | KeyCode::Char('T') => {
+                    // Batch test all nodes in selected route (only if preset allows)
+                    if state.preset.show_speed_test() && self.selected_route < routes.len() {
+                        let route = &routes[self.selected_route];
+                        // Filter out non-testable nodes (Direct, Reject, etc.) silently
+                        let testable_nodes: Vec<String> = route
+                            .all_nodes
+                            .iter()
+                            .filter(|node| state.is_node_testable(node))
+                            .cloned()
+                            .collect();
+
+                        if !testable_nodes.is_empty() {
+                            state.status_message = Some(format!(
+                                "Testing {} nodes in {}...",
+                                testable_nodes.len(),
+                                route.display_name()
+                            ));
+                            for node in testable_nodes {
+                                state.start_test_delay(node);
+                            }
+                        }
+                        // Silently skip if no testable nodes
+                    } else if !state.preset.show_speed_test() {
+                        state.status_message =
+                            Some("Speed test disabled in current preset".to_string());
+                    }
+                }
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    ctx.emit(AppEvent::Quit)
+                }
+                _ => {}
+            }
+        } else {
+            // Node selection mode
+            if self.selected_route >= routes.len() {
+                self.expanded = false;
+                return;
+            }
+
+            let route = &routes[self.selected_route];
+            let max_node_index = route.all_nodes.len().saturating_sub(1);
+            if self.selected_node > max_node_index {
+                self.selected_node = max_node_index;
+            }
+
+            // Find-as-you-type: the selection follows the best fuzzy match
+            if let Some(query) = self.node_search.as_mut() {
+                match key.code {
+                    KeyCode::Char(c) => query.push(c),
+                    KeyCode::Backspace => {
+                        query.pop();
+                    }
+                    KeyCode::Enter | KeyCode::Esc => {
+                        self.node_search = None;
+                        return;
+                    }
+                    _ => {}
+                }
+                let filter = SearchFilter::new(query);
+                let nodes = route.all_nodes.iter().map(String::as_str);
+                let target = match key.code {
+                    KeyCode::Up => nodes
+                        .enumerate()
+                        .take(self.selected_node)
+                        .rev()
+                        .find(|(_, node)| filter.matches([*node]))
+                        .map(|(index, _)| index),
+                    KeyCode::Down => nodes
+                        .enumerate()
+                        .skip(self.selected_node + 1)
+                        .find(|(_, node)| filter.matches([*node]))
+                        .map(|(index, _)| index),
+                    _ if filter.is_empty() => None,
+                    _ => filter.best_match(nodes),
+                };
+                if let Some(index) = target {
+                    self.selected_node = index;
+                    if self.by_region {
+                        self.reveal_selected_node(&route.all_nodes);
+                    }
+                }
+                return;
+            }
+
+            // Health check editor for url-test/fallback groups
+            if let Some(edit) = self.health_edit.as_mut() {
+                let field = if edit.on_interval {
+                    &mut edit.interval
+                } else {
+                    &mut edit.url
+                };
+                match key.code {
+                    KeyCode::Char(c) if !edit.on_interval || c.is_ascii_digit() => field.push(c),
+                    KeyCode::Backspace => {
+                        field.pop();
+                    }
+                    KeyCode::Tab | KeyCode::BackTab | KeyCode::Up | KeyCode::Down => {
+                        edit.on_interval = !edit.on_interval;
+                    }
+                    KeyCode::Esc => self.health_edit = None,
+                    KeyCode::Enter => {
+                        let url = edit.url.trim().to_string();
+                        match edit.interval.parse::<u32>() {
+                            _ if !url.starts_with("http://") && !url.starts_with("https://") => {
+                                state.status_message = Some(
+                                    "Test URL must start with http:// or https://".to_string(),
+                                );
+                            }
+                            Ok(interval) if interval > 0 => {
+                                if !state.allow_gui_write(&format!(
+                                    "Save health check of {}",
+                                    edit.group
+                                )) {
+                                    return;
+                                }
+                                let check = GroupHealthCheck { url, interval };
+                                let message =
+                                    match save_group_health_check(config, &edit.group, &check) {
+                                        Ok(path) => match state
+                                            .clash_state
+                                            .client
+                                            .reload_config_path(&path.to_string_lossy())
+                                            .await
+                                        {
+                                            Ok(()) => format!(
+                                                "{}: testing {} every {}s",
+                                                edit.group, check.url, check.interval
+                                            ),
+                                            Err(e) => format!(
+                                                "Saved {} but reload failed: {}",
+                                                edit.group, e
+                                            ),
+                                        },
+                                        Err(e) => format!("Failed to update {}: {}", edit.group, e),
+                                    };
+                                state.status_message = Some(message);
+                                state.request(ApiRequest::Refresh);
+                                self.health_edit = None;
+                            }
+                            _ => {
+                                state.status_message = Some(
+                                    "Interval must be a positive number of seconds".to_string(),
+                                );
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+                return;
+            }
+
+            // Group name prompt for a batch add
+            if let Some(input) = self.group_input.as_mut() {
+                match key.code {
+                    KeyCode::Char(c) => input.push(c),
+                    KeyCode::Backspace => {
+                        input.pop();
+                    }
+                    KeyCode::Esc => self.group_input = None,
+                    KeyCode::Enter | KeyCode::Tab => {
+                        let group = input.trim().to_string();
+                        // Tab writes the group to the Clash config; groups already there stay in sync
+                        let sync = key.code == KeyCode::Tab || config.is_group_materialized(&group);
+                        if group.is_empty() {
+                            state.status_message = Some("Group name cannot be empty".to_string());
+                        } else if sync
+                            && !state
+                                .allow_gui_write(&format!("Sync {} to the Clash config", group))
+                        {
+                            // The prompt stays open, so the same key again goes ahead
+                        } else {
+                            let mut message =
+                                match config.add_nodes_to_group(&group, &self.marked_nodes) {
+                                    Ok(added) => {
+                                        self.marked_nodes.clear();
+                                        format!("Added {} node(s) to group {}", added, group)
+                                    }
+                                    Err(e) => format!("Failed to update group: {}", e),
+                                };
+                            if sync {
+                                let parent = state.clash_state.current_selector.clone();
+                                self.syncs.push(spawn_node_group_sync(
+                                    state, config, &group, parent, true,
+                                ));
+                                message.push_str(", syncing to the Clash config...");
+                            }
+                            state.status_message = Some(message);
+                            self.group_input = None;
+                        }
+                    }
+                    _ => {}
+                }
+                return;
+            }
+
+            if key.code == KeyCode::Char('R') {
+                self.by_region = !self.by_region;
+                if self.by_region {
+                    self.collapse_regions(&route.all_nodes);
+                } else {
+                    self.region_header = None;
+                }
+                return;
+            }
+
+            // Grouped by region: the cursor moves over headers and the nodes of open regions
+            if self.by_region {
+                let groups = region::group_by_region(&route.all_nodes);
+                let rows = region::region_rows(&groups, &self.collapsed_regions);
+                let cursor = self.region_cursor(&groups, &rows);
+                let target = match key.code {
+                    KeyCode::Up => Some(cursor.saturating_sub(1)),
+                    KeyCode::Down => Some((cursor + 1).min(rows.len().saturating_sub(1))),
+                    code @ (KeyCode::PageUp | KeyCode::PageDown | KeyCode::Home | KeyCode::End) => {
+                        list::jump(code, cursor, rows.len())
+                    }
+                    _ => None,
+                };
+                if let Some(row) = target.and_then(|target| rows.get(target)) {
+                    self.set_region_cursor(&groups, *row);
+                    return;
+                }
+
+                if let Some(RegionRow::Header(group)) = rows.get(cursor) {
+                    let group = &groups[*group];
+                    let nodes: Vec<String> = group
+                        .nodes
+                        .iter()
+                        .map(|node| route.all_nodes[*node].clone())
+                        .collect();
+                    match key.code {
+                        KeyCode::Enter | KeyCode::Right | KeyCode::Char(' ') => {
+                            self.toggle_region(group.region);
+                        }
+                        KeyCode::Char('t') | KeyCode::Char('T')
+                            if state.preset.show_speed_test() =>
+                        {
+                            let testable: Vec<String> = nodes
+                                .into_iter()
+                                .filter(|node| state.is_node_testable(node))
+                                .collect();
+                            state.status_message = Some(format!(
+                                "Testing {} nodes in {}...",
+                                testable.len(),
+                                group.region
+                            ));
+                            for node in testable {
+                                state.start_test_delay(node);
+                            }
+                        }
+                        KeyCode::Char('a') | KeyCode::Char('A')
+                            if route.proxy_type == ProxyType::Selector
+                                && state.preset.show_speed_test() =>
+                        {
+                            state.auto_select(&route.name, &nodes, config.auto_select_max_delay);
+                            *ctx.last_refresh = Instant::now();
+                        }
+                        _ => {}
+                    }
+                    // Back, search and quit go on; node actions don't apply to a header
+                    let passes = matches!(
+                        key.code,
+                        KeyCode::Char('q')
+                            | KeyCode::Esc
+                            | KeyCode::Left
+                            | KeyCode::Char('h')
+                            | KeyCode::Char('/')
+                    ) || (key.code == KeyCode::Char('c')
+                        && key.modifiers.contains(KeyModifiers::CONTROL));
+                    if !passes {
+                        return;
+                    }
+                    self.region_header = None;
+                } else if key.code == KeyCode::Char(' ') {
+                    // Mark/unmark and move on to the next row
+                    let node = &route.all_nodes[self.selected_node];
+                    if let Some(pos) = self.marked_nodes.iter().position(|n| n == node) {
+                        self.marked_nodes.remove(pos);
+                    } else {
+                        self.marked_nodes.push(node.clone());
+                    }
+                    if let Some(row) = rows.get(cursor + 1) {
+                        self.set_region_cursor(&groups, *row);
+                    }
+                    return;
+                }
+            }
+
+            match key.code {
+                KeyCode::Char('q') => {
+                    // Back to route list (same as Esc)
+                    self.expanded = false;
+                }
+                KeyCode::Esc if !self.marked_nodes.is_empty() => {
+                    self.marked_nodes.clear();
+                }
+                KeyCode::Char(' ') if self.selected_node < route.all_nodes.len() => {
+                    // Mark/unmark for batch actions and move on
+                    let node = &route.all_nodes[self.selected_node];
+                    if let Some(pos) = self.marked_nodes.iter().position(|n| n == node) {
+                        self.marked_nodes.remove(pos);
+                    } else {
+                        self.marked_nodes.push(node.clone());
+                    }
+                    if self.selected_node < max_node_index {
+                        self.selected_node += 1;
+                    }
+                }
+                KeyCode::Char('/') => {
+                    self.node_search = Some(String::new());
+                }
+                KeyCode::Char('G') => {
+                    if self.marked_nodes.is_empty() {
+                        if let Some(node) = route.all_nodes.get(self.selected_node) {
+                            self.marked_nodes.push(node.clone());
+                        }
+                    }
+                    if !self.marked_nodes.is_empty() {
+                        self.group_input = Some(String::new());
+                    }
+                }
+                KeyCode::Esc | KeyCode::Left => {
+                    // Back to route list
+                    self.expanded = false;
+                }
+                KeyCode::Char('h') => {
+                    self.expanded = false;
+                    ctx.navigate(Page::Home);
+                }
+                KeyCode::Char('e') if route.has_health_check() => {
+                    // Edit the group's test URL and interval in the Clash config
+                    let check = resolve_clash_config_path(config)
+                        .ok_or_else(|| anyhow::anyhow!("Clash config not found"))
+                        .and_then(|path| Ok(std::fs::read(path)?))
+                        .and_then(|bytes| read_group_health_check(&bytes, &route.name));
+                    match check {
+                        Ok(check) => {
+                            self.health_edit = Some(pages::HealthCheckEdit {
+                                group: route.name.clone(),
+                                url: check.url,
+                                interval: check.interval.to_string(),
+                                on_interval: false,
+                            });
+                        }
+                        Err(e) => {
+                            state.status_message =
+                                Some(format!("Cannot edit {}: {}", route.name, e));
+                        }
+                    }
+                }
+                KeyCode::Up => {
+                    self.selected_node = self.selected_node.saturating_sub(1);
+                }
+                KeyCode::Down if self.selected_node < max_node_index => {
+                    self.selected_node += 1;
+                }
+                code @ (KeyCode::PageUp | KeyCode::PageDown | KeyCode::Home | KeyCode::End) => {
+                    if let Some(index) = list::jump(code, self.selected_node, route.all_nodes.len())
+                    {
+                        self.selected_node = index;
+                    }
+                }
+                KeyCode::Enter if self.selected_node < route.all_nodes.len() => {
+                    // Switch to selected node
+                    let node = &route.all_nodes[self.selected_node];
+                    let selector = route.name.clone();
+
+                    state.select_proxy_checked(&selector, node, config.test_before_switch);
+
+                    *ctx.last_refresh = Instant::now();
+                    // Stay in node selection mode to see the change
+                }
+                KeyCode::Char('u') if route.fixed_node.is_some() => {
+                    // Hand the url-test group its automatic pick back
+                    state.unpin_proxy(&route.name);
+                    *ctx.last_refresh = Instant::now();
+                }
+                KeyCode::Char('t') | KeyCode::Char('T') => {
+                    // Batch test marked nodes, or all nodes in this route (only if preset allows)
+                    if state.preset.show_speed_test() {
+                        let candidates = if self.marked_nodes.is_empty() {
+                            &route.all_nodes
+                        } else {
+                            &self.marked_nodes
+                        };
+                        // Filter out non-testable nodes (Direct, Reject, etc.) silently
+                        let testable_nodes: Vec<String> = candidates
+                            .iter()
+                            .filter(|node| state.is_node_testable(node))
+                            .cloned()
+                            .collect();
+
+                        if !testable_nodes.is_empty() {
+                            state.status_message =
+                                Some(format!("Testing {} nodes...", testable_nodes.len()));
+                            for node in testable_nodes {
+                                state.start_test_delay(node);
+                            }
+                        }
+                        // Silently skip if no testable nodes
+                    } else {
+                        state.status_message =
+                            Some("Speed test disabled in current preset".to_string());
+                    }
+                }
+                KeyCode::Char('a') | KeyCode::Char('A') => {
+                    if route.proxy_type != ProxyType::Selector {
+                        state.status_message = Some(format!("{} picks its own node", route.name));
+                    } else if state.preset.show_speed_test() {
+                        state.auto_select(
+                            &route.name,
+                            &route.all_nodes,
+                            config.auto_select_max_delay,
+                        );
+                        *ctx.last_refresh = Instant::now();
+                    } else {
+                        state.status_message =
+                            Some("Speed test disabled in current preset".to_string());
+                    }
+                }
+                KeyCode::Char('*') if !self.marked_nodes.is_empty() => {
+                    // Add all marked nodes to favorites
+                    match config.add_favorites(&self.marked_nodes) {
+                        Ok(added) => {
+                            state.status_message =
+                                Some(format!("Added {} node(s) to favorites", added));
+                            self.marked_nodes.clear();
+                        }
+                        Err(e) => {
+                            state.report_error("Failed to add favorites", &e);
+                        }
+                    }
+                }
+                KeyCode::Char('*') if self.selected_node < route.all_nodes.len() => {
+                    // Toggle favorite for selected node
+                    let node = &route.all_nodes[self.selected_node];
+                    if config.is_favorite(node) {
+                        if let Err(e) = config.remove_favorite(node) {
+                            state.report_error("Failed to remove favorite", &e);
+                        } else {
+                            state.status_message = Some(format!("Removed {} from favorites", node));
+                        }
+                    } else if let Err(e) = config.add_favorite(node.clone()) {
+                        state.report_error("Failed to add favorite", &e);
+                    } else {
+                        state.status_message = Some(format!("Added {} to favorites", node));
+                    }
+                }
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    ctx.emit(AppEvent::Quit)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    async fn tick(&mut self, ctx: &mut Context<'_>) -> bool {
+        poll_node_group_syncs(&mut self.syncs, ctx.state, ctx.config)
+    }
+
+    async fn on_event(&mut self, event: &AppEvent, _ctx: &mut Context<'_>) {
+        match event {
+            AppEvent::ProfileSwitched(_) => {
+                self.expanded = false;
+                self.selected_route = 0;
+                self.selected_node = 0;
+                self.origins = None;
+            }
+            AppEvent::SubscriptionsChanged => self.origins = None,
+            AppEvent::Api(ApiResponse::ProxySelected {
+                proxy,
+                result: Ok(()),
+                ..
+            }) => {
+                self.selected_at.insert(proxy.clone(), Local::now());
+            }
+            _ => {}
+        }
+    }
+
+    fn captures_text(&self) -> bool {
+        self.expanded
+            && (self.node_search.is_some()
+                || self.group_input.is_some()
+                || self.health_edit.is_some())
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, state: &AppState, config: &AppConfig) {
+        if self.expanded {
+            pages::render_routes_with_nodes(
+                f,
+                area,
+                state,
+                config,
+                self.selected_route,
+                &pages::NodeSelection {
+                    index: self.selected_node,
+                    marked: &self.marked_nodes,
+                    group_input: self.group_input.as_deref(),
+                    health_edit: self.health_edit.as_ref(),
+                    search: self.node_search.as_deref(),
+                    regions: self.by_region.then_some(pages::RegionSelection {
+                        collapsed: &self.collapsed_regions,
+                        header: self.region_header.as_deref(),
+                    }),
+                    origins: self.origins.as_ref(),
+                    selected_at: &self.selected_at,
+                    delay_history: &self.delay_history,
+                },
+            )
+        } else {
+            pages::render_routes(f, area, state, config, self.selected_route, self.edit_mode)
+        }
+    }
+}
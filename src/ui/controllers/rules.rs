@@ -0,0 +1,608 @@
+use anyhow::{Context as _, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{layout::Rect, Frame};
+use std::path::Path;
+use tokio::sync::watch;
+
+use super::{AppEvent, Context, PageController};
+use crate::app::{ApiRequest, ApiResponse, AppState, Mode, Page};
+use crate::clash::Rule;
+use crate::config::clash_config::read_rules;
+use crate::config::domains::{self, DomainListFormat};
+use crate::config::rule_order::RuleOrder;
+use crate::config::rule_set::read_rule_set;
+use crate::config::{stats, AppConfig};
+use crate::ui::{import, list, pages, resolve_clash_config_path, save_rule_order};
+
+/// The core's rules, the whitelist/blacklist and per-rule hit statistics
+pub struct RulesController {
+    /// Rules data from API
+    data: Vec<Rule>,
+    scroll_offset: usize,
+    search_query: String,
+    search_mode: bool,
+    edit_mode: pages::RuleEditMode,
+    edit_input: String,
+    /// Selected rule index in Simple mode
+    selected_index: usize,
+    /// Which list is focused in Simple mode
+    list_focus: pages::RuleListFocus,
+    /// Show per-rule hit statistics instead of the rule list
+    show_stats: bool,
+    rule_stats: watch::Receiver<stats::RuleStats>,
+    /// Reordering the rules of the Clash config
+    reorder: Option<pages::RuleReorder>,
+    /// Inspecting the entries of a RULE-SET rule's provider
+    rule_set: Option<pages::RuleSetView>,
+    /// Simple shows the whitelist/blacklist, Expert all rules; None follows the app mode
+    view: Option<Mode>,
+}
+
+impl RulesController {
+    pub fn new(rule_stats: watch::Receiver<stats::RuleStats>) -> Self {
+        Self {
+            data: Vec::new(),
+            scroll_offset: 0,
+            search_query: String::new(),
+            search_mode: false,
+            edit_mode: pages::RuleEditMode::None,
+            edit_input: String::new(),
+            selected_index: 0,
+            list_focus: pages::RuleListFocus::Whitelist,
+            show_stats: false,
+            rule_stats,
+            reorder: None,
+            rule_set: None,
+            view: None,
+        }
+    }
+
+    /// Whether the whitelist/blacklist are shown instead of all rules
+    fn domain_lists(&self, state: &AppState) -> bool {
+        self.view.unwrap_or(state.mode) == Mode::Simple
+    }
+
+    /// Length of the list with focus
+    fn focused_len(&self, config: &AppConfig) -> usize {
+        match self.list_focus {
+            pages::RuleListFocus::Whitelist => config.whitelist.len(),
+            pages::RuleListFocus::Blacklist => config.blacklist.len(),
+        }
+    }
+
+    /// Show the rule list searched for `query`, e.g. a rule a connection matched
+    pub fn show_rule(&mut self, query: String) {
+        self.search_query = query;
+        self.search_mode = false;
+        self.show_stats = false;
+        self.reorder = None;
+        self.rule_set = None;
+        self.scroll_offset = 0;
+        self.view = Some(Mode::Expert);
+    }
+
+    /// Read the entries of the provider behind the RULE-SET rule Enter points at
+    fn open_rule_set(&mut self, config: &mut AppConfig) -> Result<String> {
+        let name = pages::rule_set_target(&self.data, &self.search_query, self.scroll_offset)
+            .map(|rule| rule.payload.clone())
+            .ok_or_else(|| anyhow::anyhow!("No RULE-SET rule from here down"))?;
+        let path = resolve_clash_config_path(config)
+            .ok_or_else(|| anyhow::anyhow!("Clash config not found"))?;
+        let bytes =
+            std::fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let dir = path.parent().unwrap_or(Path::new("."));
+        let set = read_rule_set(&bytes, dir, &name)?;
+        self.rule_set = Some(pages::RuleSetView {
+            set,
+            provider: None,
+            query: String::new(),
+            search_mode: false,
+            scroll_offset: 0,
+        });
+        Ok(name)
+    }
+
+    fn handle_rule_set_key(&mut self, key: KeyEvent, ctx: &mut Context<'_>) {
+        let Some(view) = self.rule_set.as_mut() else {
+            return;
+        };
+        if view.search_mode {
+            match key.code {
+                KeyCode::Char(c) => {
+                    view.query.push(c);
+                    view.scroll_offset = 0;
+                }
+                KeyCode::Backspace => {
+                    view.query.pop();
+                    view.scroll_offset = 0;
+                }
+                KeyCode::Esc => {
+                    view.search_mode = false;
+                    view.query.clear();
+                }
+                KeyCode::Enter => view.search_mode = false,
+                _ => {}
+            }
+            return;
+        }
+        let len = view.filtered().len();
+        match key.code {
+            KeyCode::Char('/') => view.search_mode = true,
+            KeyCode::Up => view.scroll_offset = view.scroll_offset.saturating_sub(1),
+            KeyCode::Down => {
+                view.scroll_offset =
+                    (view.scroll_offset + 1).min(len.saturating_sub(list::page_size()));
+            }
+            code @ (KeyCode::PageUp | KeyCode::PageDown | KeyCode::Home | KeyCode::End) => {
+                if let Some(offset) = list::jump(code, view.scroll_offset, len) {
+                    view.scroll_offset = offset.min(len.saturating_sub(list::page_size()));
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') => self.rule_set = None,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                ctx.emit(AppEvent::ConfirmQuit);
+            }
+            _ => {}
+        }
+    }
+
+    /// Start reordering the rules of the resolved Clash config
+    fn open_reorder(&mut self, config: &mut AppConfig) -> Result<()> {
+        let path = resolve_clash_config_path(config)
+            .ok_or_else(|| anyhow::anyhow!("Clash config not found"))?;
+        let original =
+            std::fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let rules = read_rules(&original)?;
+        if rules.is_empty() {
+            anyhow::bail!("{} has no rules", path.display());
+        }
+        self.reorder = Some(pages::RuleReorder {
+            path,
+            original,
+            order: RuleOrder::new(rules),
+            selected: 0,
+            preview: false,
+        });
+        Ok(())
+    }
+
+    /// Keys while reordering; `y` in the dry-run preview writes the rules and reloads
+    async fn handle_reorder_key(&mut self, key: KeyEvent, ctx: &mut Context<'_>) {
+        let Some(reorder) = self.reorder.as_mut() else {
+            return;
+        };
+        let state = &mut *ctx.state;
+        if reorder.preview {
+            match key.code {
+                KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    if !state.allow_gui_write("Reorder rules") {
+                        return;
+                    }
+                    let rules = reorder.order.rules();
+                    let changes = reorder.order.changes().len();
+                    let result = save_rule_order(&reorder.path, &reorder.original, &rules);
+                    match result {
+                        Ok(()) => {
+                            let reloaded = state
+                                .clash_state
+                                .client
+                                .reload_config_path(&reorder.path.to_string_lossy())
+                                .await;
+                            match reloaded {
+                                Ok(()) => {
+                                    state.status_message =
+                                        Some(format!("Rules saved ({} change(s))", changes));
+                                }
+                                Err(e) => state.report_error("Rules saved but reload failed", &e),
+                            }
+                            state.request(ApiRequest::Rules);
+                            self.reorder = None;
+                        }
+                        Err(e) => {
+                            state.report_error("Rules not saved", &e);
+                            reorder.preview = false;
+                        }
+                    }
+                }
+                KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => reorder.preview = false,
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    ctx.emit(AppEvent::ConfirmQuit);
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        let len = reorder.order.len();
+        match key.code {
+            KeyCode::Up => reorder.selected = reorder.selected.saturating_sub(1),
+            KeyCode::Down if reorder.selected + 1 < len => reorder.selected += 1,
+            code @ (KeyCode::PageUp | KeyCode::PageDown | KeyCode::Home | KeyCode::End) => {
+                if let Some(index) = list::jump(code, reorder.selected, len) {
+                    reorder.selected = index;
+                }
+            }
+            KeyCode::Char('K') if reorder.order.move_up(reorder.selected) => {
+                reorder.selected -= 1;
+            }
+            KeyCode::Char('J') if reorder.order.move_down(reorder.selected) => {
+                reorder.selected += 1;
+            }
+            KeyCode::Char('d') | KeyCode::Char('D') => {
+                if let Some(rule) = reorder.order.remove(reorder.selected) {
+                    state.status_message = Some(format!("Removed {}", rule));
+                    reorder.selected = reorder.selected.min(reorder.order.len().saturating_sub(1));
+                }
+            }
+            KeyCode::Enter => reorder.preview = true,
+            KeyCode::Esc | KeyCode::Char('q') => {
+                state.status_message = Some("Rule edits discarded".to_string());
+                self.reorder = None;
+            }
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                ctx.emit(AppEvent::ConfirmQuit);
+            }
+            _ => {}
+        }
+    }
+
+    fn focused_list<'a>(&self, config: &'a mut AppConfig) -> (&'static str, &'a mut Vec<String>) {
+        match self.list_focus {
+            pages::RuleListFocus::Whitelist => ("whitelist", &mut config.whitelist),
+            pages::RuleListFocus::Blacklist => ("blacklist", &mut config.blacklist),
+        }
+    }
+
+    /// Add the domains of a plain, hosts or adblock file to the focused list
+    fn import_list(&self, config: &mut AppConfig, path: &Path) -> Result<String> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let parsed = domains::parse(&text);
+        if parsed.domains.is_empty() {
+            anyhow::bail!("No domains in {}", path.display());
+        }
+        let (name, list) = self.focused_list(config);
+        let added = domains::merge(list, &parsed.domains);
+        config.save()?;
+
+        let mut message = format!("Imported {} domain(s) into the {}", added, name);
+        let known = parsed.domains.len() - added;
+        if known > 0 {
+            message.push_str(&format!(", {} already listed", known));
+        }
+        if let Some(first) = parsed.invalid.first() {
+            message.push_str(&format!(
+                ", {} invalid skipped (e.g. {})",
+                parsed.invalid.len(),
+                first
+            ));
+        }
+        Ok(message)
+    }
+
+    fn export_list(
+        &self,
+        config: &mut AppConfig,
+        path: &Path,
+        format: DomainListFormat,
+    ) -> Result<String> {
+        let (name, list) = self.focused_list(config);
+        let count = list.len();
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, domains::render(list, format))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(format!(
+            "Exported {} {} domain(s) to {}",
+            count,
+            name,
+            path.display()
+        ))
+    }
+}
+
+impl PageController for RulesController {
+    async fn enter(&mut self, ctx: &mut Context<'_>) {
+        self.scroll_offset = 0;
+        // Fetch rules immediately
+        ctx.state.request(ApiRequest::Rules);
+    }
+
+    async fn handle_key(&mut self, key: KeyEvent, ctx: &mut Context<'_>) {
+        if self.reorder.is_some() {
+            self.handle_reorder_key(key, ctx).await;
+            return;
+        }
+        if self.rule_set.is_some() {
+            self.handle_rule_set_key(key, ctx);
+            return;
+        }
+        let (state, config) = (&mut *ctx.state, &mut *ctx.config);
+        // Handle edit mode input
+        if self.edit_mode != pages::RuleEditMode::None {
+            match key.code {
+                KeyCode::Char(c) => {
+                    self.edit_input.push(c);
+                }
+                KeyCode::Backspace => {
+                    self.edit_input.pop();
+                }
+                KeyCode::Esc => {
+                    self.edit_mode = pages::RuleEditMode::None;
+                    self.edit_input.clear();
+                }
+                KeyCode::Tab => {
+                    if let pages::RuleEditMode::Export(format) = self.edit_mode {
+                        self.edit_mode = pages::RuleEditMode::Export(format.next());
+                    }
+                }
+                KeyCode::Enter
+                    if matches!(
+                        self.edit_mode,
+                        pages::RuleEditMode::Import | pages::RuleEditMode::Export(_)
+                    ) =>
+                {
+                    let path = import::expand_path(self.edit_input.trim());
+                    let result = match self.edit_mode {
+                        pages::RuleEditMode::Export(format) => {
+                            self.export_list(config, &path, format)
+                        }
+                        _ => self.import_list(config, &path),
+                    };
+                    match result {
+                        Ok(message) => state.status_message = Some(message),
+                        Err(e) => state.report_error("Domain list not saved", &e),
+                    }
+                    self.edit_mode = pages::RuleEditMode::None;
+                    self.edit_input.clear();
+                }
+                KeyCode::Enter => {
+                    if !self.edit_input.is_empty() {
+                        let result = match self.edit_mode {
+                            pages::RuleEditMode::AddWhitelist => {
+                                config.add_to_whitelist(self.edit_input.clone())
+                            }
+                            pages::RuleEditMode::AddBlacklist => {
+                                config.add_to_blacklist(self.edit_input.clone())
+                            }
+                            _ => Ok(()),
+                        };
+
+                        if let Err(e) = result {
+                            state.report_error("Failed to save rule", &e);
+                        } else {
+                            state.status_message = Some(format!("Rule added: {}", self.edit_input));
+                            // Show the domain where it landed
+                            self.list_focus = match self.edit_mode {
+                                pages::RuleEditMode::AddBlacklist => {
+                                    pages::RuleListFocus::Blacklist
+                                }
+                                _ => pages::RuleListFocus::Whitelist,
+                            };
+                            let (_, list) = self.focused_list(config);
+                            self.selected_index = list
+                                .iter()
+                                .position(|domain| *domain == self.edit_input)
+                                .unwrap_or(0);
+                        }
+                    }
+                    self.edit_mode = pages::RuleEditMode::None;
+                    self.edit_input.clear();
+                }
+                _ => {}
+            }
+        } else if self.search_mode {
+            // Handle search mode input
+            match key.code {
+                KeyCode::Char(c) => {
+                    self.search_query.push(c);
+                }
+                KeyCode::Backspace => {
+                    self.search_query.pop();
+                }
+                KeyCode::Esc => {
+                    self.search_mode = false;
+                    self.search_query.clear();
+                }
+                KeyCode::Enter => {
+                    self.search_mode = false;
+                }
+                _ => {}
+            }
+        } else {
+            // Normal mode key handling
+            let domain_lists = self.domain_lists(state);
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => {
+                    // Return to Home instead of quitting
+                    ctx.navigate(Page::Home);
+                }
+                KeyCode::Char('h') => ctx.navigate(Page::Home),
+                KeyCode::Char('r') => {
+                    // Refresh rules
+                    state.request(ApiRequest::Rules);
+                }
+                KeyCode::Char('s') | KeyCode::Char('S') => {
+                    self.show_stats = !self.show_stats;
+                    self.scroll_offset = 0;
+                }
+                KeyCode::Char('/') => {
+                    // Enter search mode, over all rules
+                    self.search_mode = true;
+                    self.search_query.clear();
+                    self.view = Some(Mode::Expert);
+                }
+                KeyCode::Char('v') | KeyCode::Char('V') => {
+                    self.view = Some(if domain_lists {
+                        Mode::Expert
+                    } else {
+                        Mode::Simple
+                    });
+                    self.selected_index = 0;
+                }
+                KeyCode::Char('w') | KeyCode::Char('W') => {
+                    // Add to whitelist
+                    self.edit_mode = pages::RuleEditMode::AddWhitelist;
+                    self.edit_input.clear();
+                }
+                KeyCode::Char('b') | KeyCode::Char('B') => {
+                    // Add to blacklist
+                    self.edit_mode = pages::RuleEditMode::AddBlacklist;
+                    self.edit_input.clear();
+                }
+                KeyCode::Enter if !domain_lists => match self.open_rule_set(config) {
+                    Ok(name) => state.request(ApiRequest::RuleProvider(name)),
+                    Err(e) => state.report_error("Cannot inspect rule-set", &e),
+                },
+                KeyCode::Char('e') | KeyCode::Char('E') => {
+                    if let Err(e) = self.open_reorder(config) {
+                        state.report_error("Cannot edit rules", &e);
+                    }
+                }
+                KeyCode::Char('i') | KeyCode::Char('I') => {
+                    self.edit_mode = pages::RuleEditMode::Import;
+                    self.edit_input.clear();
+                }
+                KeyCode::Char('x') | KeyCode::Char('X') => {
+                    self.edit_mode = pages::RuleEditMode::Export(DomainListFormat::Plain);
+                    self.edit_input.clear();
+                }
+                KeyCode::Char('d') | KeyCode::Char('D') if domain_lists => {
+                    // Delete selected rule
+                    let result = match self.list_focus {
+                        pages::RuleListFocus::Whitelist => {
+                            if self.selected_index < config.whitelist.len() {
+                                let domain = config.whitelist[self.selected_index].clone();
+                                config.remove_from_whitelist(&domain)
+                            } else {
+                                Ok(())
+                            }
+                        }
+                        pages::RuleListFocus::Blacklist => {
+                            if self.selected_index < config.blacklist.len() {
+                                let domain = config.blacklist[self.selected_index].clone();
+                                config.remove_from_blacklist(&domain)
+                            } else {
+                                Ok(())
+                            }
+                        }
+                    };
+
+                    if let Err(e) = result {
+                        state.report_error("Failed to delete rule", &e);
+                    } else {
+                        state.status_message = Some("Rule deleted".to_string());
+                        // Adjust selected index if needed
+                        let list_len = match self.list_focus {
+                            pages::RuleListFocus::Whitelist => config.whitelist.len(),
+                            pages::RuleListFocus::Blacklist => config.blacklist.len(),
+                        };
+                        if self.selected_index >= list_len && list_len > 0 {
+                            self.selected_index = list_len - 1;
+                        }
+                    }
+                }
+                KeyCode::Up if domain_lists => {
+                    self.selected_index = self.selected_index.saturating_sub(1);
+                }
+                KeyCode::Down if domain_lists => {
+                    self.selected_index =
+                        (self.selected_index + 1).min(self.focused_len(config).saturating_sub(1));
+                }
+                code @ (KeyCode::PageUp | KeyCode::PageDown | KeyCode::Home | KeyCode::End)
+                    if domain_lists =>
+                {
+                    let len = self.focused_len(config);
+                    if let Some(index) = list::jump(code, self.selected_index, len) {
+                        self.selected_index = index;
+                    }
+                }
+                KeyCode::Up => {
+                    self.scroll_offset = self.scroll_offset.saturating_sub(1);
+                }
+                KeyCode::Down => {
+                    self.scroll_offset = (self.scroll_offset + 1)
+                        .min(self.data.len().saturating_sub(list::page_size()));
+                }
+                code @ (KeyCode::PageUp | KeyCode::PageDown | KeyCode::Home | KeyCode::End) => {
+                    // The offset stops where the last page is full
+                    let last_page = self.data.len().saturating_sub(list::page_size());
+                    if let Some(offset) = list::jump(code, self.scroll_offset, self.data.len()) {
+                        self.scroll_offset = offset.min(last_page);
+                    }
+                }
+                KeyCode::Left => {
+                    self.list_focus = pages::RuleListFocus::Whitelist;
+                    self.selected_index = 0;
+                }
+                KeyCode::Right => {
+                    self.list_focus = pages::RuleListFocus::Blacklist;
+                    self.selected_index = 0;
+                }
+                KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    state.cycle_preset(config)
+                }
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    ctx.emit(AppEvent::ConfirmQuit);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    async fn on_event(&mut self, event: &AppEvent, ctx: &mut Context<'_>) {
+        match event {
+            AppEvent::Api(ApiResponse::Rules(Ok(rules))) => {
+                self.data = rules.clone();
+                if ctx.state.current_page == Page::Rules {
+                    ctx.state.status_message = Some(format!("Loaded {} rules", self.data.len()));
+                }
+            }
+            AppEvent::Api(ApiResponse::Rules(Err(e))) => {
+                ctx.state.report_error("Failed to fetch rules", e);
+            }
+            AppEvent::Api(ApiResponse::RuleProvider {
+                name,
+                result: Ok(provider),
+            }) => {
+                // Without the core's view the entries are still shown, so a failure is ignored
+                if let Some(view) = self.rule_set.as_mut().filter(|view| &view.set.name == name) {
+                    view.provider = Some(provider.clone());
+                }
+            }
+            AppEvent::ProfileSwitched(rules) if !rules.is_empty() => self.data = rules.clone(),
+            _ => {}
+        }
+    }
+
+    fn captures_text(&self) -> bool {
+        self.search_mode
+            || self.edit_mode != pages::RuleEditMode::None
+            || self.rule_set.as_ref().is_some_and(|view| view.search_mode)
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, state: &AppState, config: &AppConfig) {
+        pages::render_rules(
+            f,
+            area,
+            state,
+            self.scroll_offset,
+            &self.search_query,
+            self.search_mode,
+            self.edit_mode,
+            &self.edit_input,
+            config,
+            self.selected_index,
+            &self.data,
+            self.list_focus,
+            self.domain_lists(state),
+            self.show_stats.then(|| self.rule_stats.borrow()).as_deref(),
+            self.reorder.as_ref(),
+            self.rule_set.as_ref(),
+        );
+    }
+}
@@ -0,0 +1,911 @@
+use anyhow::Context as _;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{layout::Rect, Frame};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use url::Url;
+
+use super::{AppEvent, Context, PageController};
+use crate::app::inbound::{InboundField, InboundPorts};
+use crate::app::notify::NotifyEvent;
+use crate::app::{ApiRequest, ApiResponse, AppState, Page};
+use crate::clash::ClashClient;
+use crate::config::clash_config::read_external_ui;
+use crate::config::snapshot::SnapshotStore;
+use crate::config::validate;
+use crate::config::{paths, AppConfig};
+use crate::core::dashboard::{self, DASHBOARDS};
+use crate::core::doctor::api_host;
+use crate::core::service::{ServiceAction, ServiceManager};
+use crate::core::upgrade;
+use crate::ui::{
+    apply_inbound_ports, detect_controller, i18n, install_core_upgrade, list, pages,
+    resolve_clash_config_path, restore_snapshot, save_config_edit, service_status, set_external_ui,
+    SettingsEvent,
+};
+
+/// App settings, connection details and the core service
+pub struct SettingsController {
+    action: pages::SettingsAction,
+    /// Core service status
+    service_running: Option<bool>,
+    task_tx: mpsc::UnboundedSender<SettingsEvent>,
+    task_rx: mpsc::UnboundedReceiver<SettingsEvent>,
+    /// Whether `c` asked for a connection test whose answer is still to come
+    testing_connection: bool,
+    /// Whether `o` asked for the core config to edit its inbound ports
+    reading_ports: bool,
+}
+
+impl SettingsController {
+    pub fn new() -> Self {
+        let (task_tx, task_rx) = mpsc::unbounded_channel();
+        Self {
+            action: pages::SettingsAction::None,
+            service_running: None,
+            task_tx,
+            task_rx,
+            testing_connection: false,
+            reading_ports: false,
+        }
+    }
+
+    fn select_dashboard(&mut self, index: usize) {
+        if let pages::SettingsAction::Dashboard { selected, .. } = &mut self.action {
+            *selected = index;
+        }
+    }
+
+    async fn handle_ports_key(&mut self, key: KeyEvent, ctx: &mut Context<'_>) {
+        let pages::SettingsAction::EditPorts(editor) = &mut self.action else {
+            return;
+        };
+        let state = &mut *ctx.state;
+
+        if let Some(input) = editor.input.as_mut() {
+            match key.code {
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    ctx.emit(AppEvent::ConfirmQuit);
+                }
+                KeyCode::Char(c) if c.is_ascii_digit() && input.len() < 5 => input.push(c),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Esc => editor.input = None,
+                KeyCode::Enter => match input.parse::<u16>() {
+                    Ok(port) => {
+                        let field = editor.field();
+                        editor.ports.set_port(field, port);
+                        editor.input = None;
+                        editor.confirming = false;
+                    }
+                    Err(_) => {
+                        state.status_message =
+                            Some("Enter a port between 0 (off) and 65535".to_string());
+                    }
+                },
+                _ => {}
+            }
+            return;
+        }
+
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                ctx.emit(AppEvent::ConfirmQuit);
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                editor.selected = editor.selected.saturating_sub(1);
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                editor.selected = (editor.selected + 1).min(InboundField::ALL.len() - 1);
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => match editor.ports.port(editor.field()) {
+                Some(port) => editor.input = Some(port.to_string()),
+                None => {
+                    editor.ports.allow_lan = !editor.ports.allow_lan;
+                    editor.confirming = false;
+                }
+            },
+            KeyCode::Char('r') => {
+                editor.ports = editor.original;
+                editor.confirming = false;
+            }
+            KeyCode::Char('s') => {
+                let problems = editor.ports.problems(&editor.original);
+                if let Some(conflict) = problems.iter().find(|p| p.is_blocking()) {
+                    state.status_message = Some(format!("Cannot apply: {}", conflict));
+                    return;
+                }
+                if !problems.is_empty() && !editor.confirming {
+                    editor.confirming = true;
+                    return;
+                }
+                editor.confirming = false;
+                let Some(patch) = editor.ports.patch(&editor.original) else {
+                    state.status_message = Some("Inbound ports unchanged".to_string());
+                    return;
+                };
+
+                let client = state.clash_state.client.clone();
+                let host = api_host(&ctx.config.api_url);
+                let tx = self.task_tx.clone();
+                state.status_message = Some("Applying inbound ports...".to_string());
+                state.tasks.spawn("inbound-ports", async move {
+                    let result = apply_inbound_ports(&client, &host, patch).await;
+                    let _ = tx.send(SettingsEvent::PortsApplied(result));
+                });
+            }
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.action = pages::SettingsAction::None;
+            }
+            _ => {}
+        }
+    }
+
+    async fn handle_snapshots_key(&mut self, key: KeyEvent, ctx: &mut Context<'_>) {
+        let pages::SettingsAction::Snapshots(view) = &mut self.action else {
+            return;
+        };
+        let state = &mut *ctx.state;
+
+        if let Some(input) = view.name_input.as_mut() {
+            match key.code {
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    ctx.emit(AppEvent::ConfirmQuit);
+                }
+                KeyCode::Char(c) => input.push(c),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Esc => view.name_input = None,
+                KeyCode::Enter => {
+                    let Some(source) = view.config_path.clone() else {
+                        state.status_message = Some("No Clash config found".to_string());
+                        return;
+                    };
+                    let name = input.clone();
+                    view.name_input = None;
+                    let taken = SnapshotStore::open_default().and_then(|store| {
+                        let snapshot = store.take(&name, &source)?;
+                        Ok((snapshot, store.list()?))
+                    });
+                    match taken {
+                        Ok((snapshot, snapshots)) => {
+                            state.status_message =
+                                Some(format!("Saved snapshot {}", snapshot.name));
+                            view.snapshots = snapshots;
+                            view.selected = 0;
+                        }
+                        Err(e) => state.report_error("Failed to take snapshot", &e),
+                    }
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        if view.confirm_restore {
+            view.confirm_restore = false;
+            if !matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                return;
+            }
+            let Some(snapshot) = view.selected().cloned() else {
+                return;
+            };
+            if !state.allow_gui_write(&format!("Restore {}", snapshot.name)) {
+                return;
+            }
+            let client = state.clash_state.client.clone();
+            let tx = self.task_tx.clone();
+            state.status_message = Some(format!("Restoring {}...", snapshot.name));
+            state.tasks.spawn("snapshot-restore", async move {
+                let result = restore_snapshot(&client, &snapshot).await;
+                let _ = tx.send(SettingsEvent::SnapshotRestored {
+                    name: snapshot.name,
+                    result,
+                });
+            });
+            return;
+        }
+
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                ctx.emit(AppEvent::ConfirmQuit);
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                view.selected = view.selected.saturating_sub(1);
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                view.selected = (view.selected + 1).min(view.snapshots.len().saturating_sub(1));
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') => view.name_input = Some(String::new()),
+            KeyCode::Enter if view.selected().is_some() => view.confirm_restore = true,
+            KeyCode::Char('x') | KeyCode::Delete => {
+                let Some(snapshot) = view.selected().cloned() else {
+                    return;
+                };
+                let deleted = SnapshotStore::open_default().and_then(|store| {
+                    store.delete(&snapshot)?;
+                    store.list()
+                });
+                match deleted {
+                    Ok(snapshots) => {
+                        state.status_message = Some(format!("Deleted snapshot {}", snapshot.name));
+                        view.snapshots = snapshots;
+                        view.selected = view.selected.min(view.snapshots.len().saturating_sub(1));
+                    }
+                    Err(e) => state.report_error("Failed to delete snapshot", &e),
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.action = pages::SettingsAction::None;
+            }
+            _ => {}
+        }
+    }
+}
+
+impl SettingsController {
+    async fn handle_viewer_key(&mut self, key: KeyEvent, ctx: &mut Context<'_>) {
+        let pages::SettingsAction::ViewConfig(viewer) = &mut self.action else {
+            return;
+        };
+        let len = viewer.lines.len();
+        // Past this the last page is already full, so scrolling further shows nothing new
+        let last = len.saturating_sub(list::page_size());
+        if let Some(line) = list::jump(key.code, viewer.scroll, len) {
+            viewer.scroll = line.min(last);
+            return;
+        }
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                ctx.emit(AppEvent::ConfirmQuit);
+            }
+            KeyCode::Up | KeyCode::Char('k') => viewer.scroll = viewer.scroll.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => viewer.scroll = (viewer.scroll + 1).min(last),
+            KeyCode::Char('r') | KeyCode::Char('R') => match viewer.reload() {
+                Ok(()) => ctx.state.status_message = Some("Clash config reloaded".to_string()),
+                Err(e) => ctx
+                    .state
+                    .report_error("Failed to read the Clash config", &e),
+            },
+            KeyCode::Char('e') | KeyCode::Char('E') => {
+                if viewer.draft.is_none() {
+                    let draft = paths::state_dir().and_then(|dir| {
+                        std::fs::create_dir_all(&dir)?;
+                        let draft = dir.join("config.edit.yaml");
+                        std::fs::copy(&viewer.path, &draft)
+                            .with_context(|| format!("Failed to copy {}", viewer.path.display()))?;
+                        Ok(draft)
+                    });
+                    match draft {
+                        Ok(draft) => viewer.draft = Some(draft),
+                        Err(e) => {
+                            ctx.state
+                                .report_error("Failed to prepare the config for editing", &e);
+                            return;
+                        }
+                    }
+                }
+                if let Some(draft) = &viewer.draft {
+                    ctx.emit(AppEvent::EditFile(draft.clone()));
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') => {
+                if let Some(draft) = viewer.draft.take() {
+                    let _ = std::fs::remove_file(draft);
+                }
+                self.action = pages::SettingsAction::None;
+            }
+            _ => {}
+        }
+    }
+
+    /// Put the copy edited in $EDITOR in place of the config, if it changed and is valid
+    /// An invalid copy, or one held back while a GUI runs, is kept, so the next `e` picks up
+    /// where the edit left off
+    async fn finish_config_edit(&mut self, ctx: &mut Context<'_>) {
+        let pages::SettingsAction::ViewConfig(viewer) = &mut self.action else {
+            return;
+        };
+        let Some(draft) = viewer.draft.clone() else {
+            return;
+        };
+        let state = &mut *ctx.state;
+        let edited = match std::fs::read(&draft) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                state.report_error("Failed to read the edited config", &e.into());
+                return;
+            }
+        };
+        if std::fs::read(&viewer.path).is_ok_and(|current| current == edited) {
+            let _ = std::fs::remove_file(&draft);
+            viewer.draft = None;
+            state.status_message = Some("No changes to the Clash config".to_string());
+            return;
+        }
+
+        let problems = validate::validate_config(&edited);
+        if validate::has_errors(&problems) {
+            state.status_message =
+                Some("The edited config has errors and was not saved; press 'e' to fix it".into());
+            ctx.events.push(AppEvent::ShowProblems(
+                "Edited Clash config".to_string(),
+                problems,
+            ));
+            return;
+        }
+        if !state.allow_gui_write("Save edited Clash config") {
+            return;
+        }
+
+        let client = state.clash_state.client.clone();
+        let result = save_config_edit(&client, &viewer.path, &edited).await;
+        let _ = std::fs::remove_file(&draft);
+        viewer.draft = None;
+        let _ = viewer.reload();
+        match result {
+            Ok(()) => {
+                state.status_message = Some(format!(
+                    "Saved {} and reloaded the core",
+                    viewer.path.display()
+                ));
+                state.request(ApiRequest::Refresh);
+            }
+            Err(e) => state.report_error("Failed to apply the edited config", &e),
+        }
+    }
+}
+
+impl PageController for SettingsController {
+    async fn enter(&mut self, ctx: &mut Context<'_>) {
+        self.action = pages::SettingsAction::None;
+        self.service_running = service_status(ctx.config).await;
+    }
+
+    async fn handle_key(&mut self, key: KeyEvent, ctx: &mut Context<'_>) {
+        let (state, config) = (&mut *ctx.state, &mut *ctx.config);
+        match &self.action {
+            pages::SettingsAction::ExportPrompt => {
+                match key.code {
+                    KeyCode::Char('y') | KeyCode::Char('Y') => {
+                        // Export configuration
+                        let export_path = paths::config_dir()
+                            .map(|p| p.join("clashctl-export.yaml"))
+                            .unwrap_or_else(|_| std::path::PathBuf::from("clashctl-export.yaml"));
+
+                        match config.export_to(&export_path) {
+                            Ok(_) => {
+                                self.action = pages::SettingsAction::ExportSuccess(
+                                    export_path.display().to_string(),
+                                );
+                            }
+                            Err(e) => {
+                                self.action =
+                                    pages::SettingsAction::Error(format!("Export failed: {}", e));
+                            }
+                        }
+                    }
+                    KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                        self.action = pages::SettingsAction::None;
+                    }
+                    _ => {}
+                }
+            }
+            pages::SettingsAction::ImportPrompt => {
+                match key.code {
+                    KeyCode::Char('y') | KeyCode::Char('Y') => {
+                        // Import configuration
+                        let import_path = paths::config_dir()
+                            .map(|p| p.join("clashctl-import.yaml"))
+                            .unwrap_or_else(|_| std::path::PathBuf::from("clashctl-import.yaml"));
+
+                        match AppConfig::import_from(&import_path) {
+                            Ok(imported_config) => {
+                                // Save imported config
+                                if let Err(e) = imported_config.save() {
+                                    self.action = pages::SettingsAction::Error(format!(
+                                        "Failed to save imported config: {}",
+                                        e
+                                    ));
+                                } else {
+                                    *config = imported_config;
+                                    self.action = pages::SettingsAction::ImportSuccess;
+                                }
+                            }
+                            Err(e) => {
+                                self.action =
+                                    pages::SettingsAction::Error(format!("Import failed: {}", e));
+                            }
+                        }
+                    }
+                    KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                        self.action = pages::SettingsAction::None;
+                    }
+                    _ => {}
+                }
+            }
+            pages::SettingsAction::EditApiUrl(_) | pages::SettingsAction::EditSecret(_) => {
+                let editing_url = matches!(self.action, pages::SettingsAction::EditApiUrl(_));
+                let Some(input) = self.action.edit_input_mut() else {
+                    return;
+                };
+                match key.code {
+                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        ctx.emit(AppEvent::ConfirmQuit);
+                    }
+                    KeyCode::Char(c) => input.push(c),
+                    KeyCode::Backspace => {
+                        input.pop();
+                    }
+                    KeyCode::Esc => self.action = pages::SettingsAction::None,
+                    KeyCode::Enter | KeyCode::Tab => {
+                        let value = input.trim().to_string();
+                        let (api_url, secret) = if editing_url {
+                            (value, config.secret.clone())
+                        } else {
+                            (
+                                config.api_url.clone(),
+                                Some(value).filter(|s| !s.is_empty()),
+                            )
+                        };
+                        if let Err(e) = Url::parse(&api_url) {
+                            self.action = pages::SettingsAction::Error(format!(
+                                "Invalid API URL '{}': {}",
+                                api_url, e
+                            ));
+                            return;
+                        }
+
+                        let client = ClashClient::new(api_url.clone(), secret.clone());
+                        let save = key.code == KeyCode::Enter;
+                        let tx = self.task_tx.clone();
+                        state.status_message = Some(format!("Testing {}...", api_url));
+                        state.tasks.spawn("api-test", async move {
+                            let result =
+                                client.test_connection_within(Duration::from_secs(5)).await;
+                            let _ = tx.send(SettingsEvent::ApiTested {
+                                client,
+                                api_url,
+                                secret,
+                                save,
+                                result,
+                            });
+                        });
+                    }
+                    _ => {}
+                }
+            }
+            pages::SettingsAction::EditPorts(_) => self.handle_ports_key(key, ctx).await,
+            pages::SettingsAction::Snapshots(_) => self.handle_snapshots_key(key, ctx).await,
+            pages::SettingsAction::ViewConfig(_) => self.handle_viewer_key(key, ctx).await,
+            pages::SettingsAction::Dashboard { selected, .. } => {
+                let current = *selected;
+                match key.code {
+                    KeyCode::Up | KeyCode::Left => self.select_dashboard(current.saturating_sub(1)),
+                    KeyCode::Down | KeyCode::Right => {
+                        self.select_dashboard((current + 1).min(DASHBOARDS.len() - 1))
+                    }
+                    KeyCode::Char('y') | KeyCode::Char('Y')
+                        if state.allow_gui_write(&format!(
+                            "Install {} dashboard",
+                            DASHBOARDS[current].name
+                        )) =>
+                    {
+                        let choice = DASHBOARDS[current];
+                        let client = state.clash_state.client.clone();
+                        self.action = match set_external_ui(config, &client, &choice).await {
+                            Ok(dir) => {
+                                let tx = self.task_tx.clone();
+                                state.tasks.spawn("dashboard", async move {
+                                    let result = client
+                                        .upgrade_ui()
+                                        .await
+                                        .map(|()| format!("{} installed in {}", choice.name, dir));
+                                    let _ = tx.send(SettingsEvent::DashboardInstalled(result));
+                                });
+                                pages::SettingsAction::InstallingDashboard(choice.name)
+                            }
+                            Err(e) => pages::SettingsAction::Error(format!(
+                                "Failed to set up {}: {}",
+                                choice.name, e
+                            )),
+                        };
+                    }
+                    KeyCode::Char('b') | KeyCode::Char('B') => {
+                        let url = dashboard::page_url(&config.api_url);
+                        match dashboard::open_in_browser(&url) {
+                            Ok(()) => state.status_message = Some(format!("Opened {}", url)),
+                            Err(e) => state.report_error("Failed to open the dashboard", &e),
+                        }
+                    }
+                    KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                        self.action = pages::SettingsAction::None;
+                    }
+                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        ctx.emit(AppEvent::ConfirmQuit);
+                    }
+                    _ => {}
+                }
+            }
+            pages::SettingsAction::UpgradePrompt(plan) => match key.code {
+                KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    let plan = plan.clone();
+                    let config = config.clone();
+                    let tx = self.task_tx.clone();
+                    self.action = pages::SettingsAction::Upgrading(plan.latest.clone());
+                    // Gets the shutdown grace period on quit instead of being aborted mid-install
+                    state.tasks.spawn_graceful("core-upgrade", |_| async move {
+                        let result = install_core_upgrade(&config, &plan).await;
+                        let _ = tx.send(SettingsEvent::Installed(result));
+                    });
+                }
+                KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                    self.action = pages::SettingsAction::None;
+                }
+                _ => {}
+            },
+            _ => {
+                // Normal settings page navigation
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => {
+                        ctx.navigate(Page::Home);
+                        self.action = pages::SettingsAction::None;
+                    }
+                    KeyCode::Char('h') => {
+                        ctx.navigate(Page::Home);
+                        self.action = pages::SettingsAction::None;
+                    }
+                    KeyCode::Char('e') | KeyCode::Char('E') => {
+                        self.action = pages::SettingsAction::ExportPrompt;
+                    }
+                    KeyCode::Char('i') | KeyCode::Char('I') => {
+                        self.action = pages::SettingsAction::ImportPrompt;
+                    }
+                    KeyCode::Char('a') | KeyCode::Char('A') => {
+                        self.action = pages::SettingsAction::EditApiUrl(config.api_url.clone());
+                    }
+                    KeyCode::Char('k') | KeyCode::Char('K') => {
+                        self.action = pages::SettingsAction::EditSecret(String::new());
+                    }
+                    KeyCode::Char('w') | KeyCode::Char('W') if !self.action.is_busy() => {
+                        let (dir, url) = resolve_clash_config_path(config)
+                            .and_then(|path| std::fs::read(path).ok())
+                            .and_then(|bytes| read_external_ui(&bytes).ok())
+                            .unwrap_or_default();
+                        let selected = DASHBOARDS
+                            .iter()
+                            .position(|choice| url.as_deref() == Some(choice.url))
+                            .unwrap_or(0);
+                        self.action = pages::SettingsAction::Dashboard { selected, dir, url };
+                    }
+                    KeyCode::Char('b') | KeyCode::Char('B') => {
+                        let config_path = resolve_clash_config_path(config);
+                        self.action =
+                            match SnapshotStore::open_default().and_then(|store| store.list()) {
+                                Ok(snapshots) => pages::SettingsAction::Snapshots(
+                                    pages::settings::SnapshotsView::new(config_path, snapshots),
+                                ),
+                                Err(e) => pages::SettingsAction::Error(format!(
+                                    "Failed to read snapshots: {}",
+                                    e
+                                )),
+                            };
+                    }
+                    KeyCode::Char('v') | KeyCode::Char('V') => {
+                        self.action = match resolve_clash_config_path(config) {
+                            Some(path) => match pages::settings::ConfigViewer::open(path) {
+                                Ok(viewer) => pages::SettingsAction::ViewConfig(viewer),
+                                Err(e) => pages::SettingsAction::Error(format!("{:#}", e)),
+                            },
+                            None => {
+                                pages::SettingsAction::Error("No Clash config found".to_string())
+                            }
+                        };
+                    }
+                    KeyCode::Char('o') | KeyCode::Char('O') => {
+                        self.reading_ports = true;
+                        state.request(ApiRequest::CoreConfig);
+                    }
+                    KeyCode::Char('c') | KeyCode::Char('C')
+                        if !key.modifiers.contains(KeyModifiers::CONTROL) =>
+                    {
+                        self.testing_connection = true;
+                        state.request(ApiRequest::TestConnection);
+                    }
+                    KeyCode::Char('t') | KeyCode::Char('T') => {
+                        state.status_message = Some(match config.toggle_test_before_switch() {
+                            Ok(true) => "Nodes are tested before switching".to_string(),
+                            Ok(false) => "Nodes switch without testing".to_string(),
+                            Err(e) => format!("Failed to save setting: {}", e),
+                        });
+                    }
+                    KeyCode::Char('p') | KeyCode::Char('P') => {
+                        state.status_message = Some(match config.toggle_keychain() {
+                            Ok(true) => "Secret stored in the system keychain".to_string(),
+                            Ok(false) => "Secret stored in config.yaml".to_string(),
+                            Err(e) => format!("Failed to change secret storage: {:#}", e),
+                        });
+                    }
+                    KeyCode::Char(digit @ '1'..='3') => {
+                        let event = match digit {
+                            '1' => NotifyEvent::NodeDown,
+                            '2' => NotifyEvent::SubscriptionExpiring,
+                            _ => NotifyEvent::CoreUnreachable,
+                        };
+                        state.status_message = Some(match config.toggle_notification(event) {
+                            Ok(enabled) => format!(
+                                "{} notifications {}",
+                                event.label(),
+                                if enabled { "on" } else { "off" }
+                            ),
+                            Err(e) => format!("Failed to save setting: {}", e),
+                        });
+                        state.notifier.set_settings(config.notifications.clone());
+                    }
+                    KeyCode::Char('d') => {
+                        state.status_message = Some(match config.cycle_default_download_policy() {
+                            Ok(policy) => format!("Subscriptions download {}", policy.label()),
+                            Err(e) => format!("Failed to save setting: {}", e),
+                        });
+                    }
+                    KeyCode::Char('4') => {
+                        state.status_message = Some(match config.cycle_download_alert() {
+                            Ok(Some(limit)) => {
+                                format!("Alert when downloading over {} MB/s", limit)
+                            }
+                            Ok(None) => "Download rate alert off".to_string(),
+                            Err(e) => format!("Failed to save setting: {}", e),
+                        });
+                    }
+                    KeyCode::Char('5') => {
+                        state.status_message = Some(match config.cycle_connection_alert() {
+                            Ok(Some(limit)) => format!("Alert over {} active connections", limit),
+                            Ok(None) => "Connection count alert off".to_string(),
+                            Err(e) => format!("Failed to save setting: {}", e),
+                        });
+                    }
+                    KeyCode::Char('l') | KeyCode::Char('L') => {
+                        let language = config.get_language().next();
+                        i18n::set_language(language);
+                        state.status_message = Some(match config.set_language(language) {
+                            Ok(()) => format!("Language: {}", language.name()),
+                            Err(e) => format!("Failed to save setting: {}", e),
+                        });
+                    }
+                    KeyCode::Char('u') | KeyCode::Char('U') if !self.action.is_busy() => {
+                        let client = state.clash_state.client.clone();
+                        let tx = self.task_tx.clone();
+                        self.action = pages::SettingsAction::UpgradeChecking;
+                        state.tasks.spawn("core-upgrade-check", async move {
+                            let result = upgrade::check(&client).await;
+                            let _ = tx.send(SettingsEvent::Checked(result));
+                        });
+                    }
+                    KeyCode::Char('s') | KeyCode::Char('x') | KeyCode::Char('r')
+                        if !self.action.is_busy() =>
+                    {
+                        let action = match key.code {
+                            KeyCode::Char('s') => ServiceAction::Start,
+                            KeyCode::Char('x') => ServiceAction::Stop,
+                            _ => ServiceAction::Restart,
+                        };
+                        let manager = match ServiceManager::from_config(&config.service) {
+                            Ok(manager) => manager,
+                            Err(e) => {
+                                self.action = pages::SettingsAction::Error(format!(
+                                    "Failed to {} core service: {}",
+                                    action.as_str(),
+                                    e
+                                ));
+                                return;
+                            }
+                        };
+                        let tx = self.task_tx.clone();
+                        self.action = pages::SettingsAction::ServiceRunning(action);
+                        state.tasks.spawn("service", async move {
+                            let result = manager.run(action).await;
+                            let running = manager.is_running().await;
+                            let _ = tx.send(SettingsEvent::ServiceRan {
+                                action,
+                                result,
+                                running,
+                            });
+                        });
+                    }
+                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        ctx.emit(AppEvent::ConfirmQuit);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    async fn tick(&mut self, ctx: &mut Context<'_>) -> bool {
+        let mut changed = false;
+        while let Ok(event) = self.task_rx.try_recv() {
+            changed = true;
+            let state = &mut *ctx.state;
+            match event {
+                SettingsEvent::Checked(Ok(plan)) if plan.is_newer() => {
+                    self.action = pages::SettingsAction::UpgradePrompt(plan);
+                }
+                SettingsEvent::Checked(Ok(plan)) => {
+                    self.action = pages::SettingsAction::ServiceSuccess(format!(
+                        "Core is up to date ({})",
+                        plan.latest
+                    ));
+                }
+                SettingsEvent::Checked(Err(e)) => {
+                    self.action =
+                        pages::SettingsAction::Error(format!("Upgrade check failed: {}", e));
+                }
+                SettingsEvent::Installed(Ok(msg)) => {
+                    self.service_running = service_status(ctx.config).await;
+                    state.request(ApiRequest::Refresh);
+                    self.action = pages::SettingsAction::ServiceSuccess(msg);
+                }
+                SettingsEvent::Installed(Err(e)) => {
+                    self.action = pages::SettingsAction::Error(format!("Upgrade failed: {}", e));
+                }
+                SettingsEvent::DashboardInstalled(Ok(msg)) => {
+                    self.action = pages::SettingsAction::ServiceSuccess(msg);
+                }
+                SettingsEvent::DashboardInstalled(Err(e)) => {
+                    self.action =
+                        pages::SettingsAction::Error(format!("Dashboard download failed: {}", e));
+                }
+                SettingsEvent::ServiceRan {
+                    action,
+                    result,
+                    running,
+                } => {
+                    self.action = match result {
+                        Ok(()) => pages::SettingsAction::ServiceSuccess(format!(
+                            "Core service {}",
+                            action.past_tense()
+                        )),
+                        Err(e) => pages::SettingsAction::Error(format!(
+                            "Failed to {} core service: {}",
+                            action.as_str(),
+                            e
+                        )),
+                    };
+                    self.service_running = running;
+                    state.request(ApiRequest::Refresh);
+                }
+                // Tab only tests, and editing goes on
+                SettingsEvent::ApiTested {
+                    api_url,
+                    save: false,
+                    result,
+                    ..
+                } => {
+                    state.status_message = Some(match result {
+                        Ok(()) => format!("Connected to {}", api_url),
+                        Err(e) => format!("Connection test failed: {}", e),
+                    });
+                }
+                SettingsEvent::ApiTested {
+                    client,
+                    api_url,
+                    secret,
+                    save: true,
+                    result,
+                } => {
+                    let config = &mut *ctx.config;
+                    config.api_url = api_url.clone();
+                    config.set_secret(secret);
+                    self.action = match config.save() {
+                        Err(e) => {
+                            pages::SettingsAction::Error(format!("Failed to save config: {}", e))
+                        }
+                        Ok(()) => {
+                            state.set_client(client);
+                            *ctx.last_refresh = Instant::now();
+                            match result {
+                                Ok(()) => pages::SettingsAction::ConnectionOk(api_url),
+                                Err(e) => pages::SettingsAction::Error(format!(
+                                    "Saved, but the API is unreachable: {}",
+                                    e
+                                )),
+                            }
+                        }
+                    };
+                }
+                SettingsEvent::PortsApplied(Err(e)) => {
+                    state.report_error("Failed to update inbound ports", &e);
+                }
+                SettingsEvent::PortsApplied(Ok(applied)) => {
+                    let unreachable = applied.checks.iter().filter(|(_, _, ok)| !ok).count();
+                    state.status_message = Some(match &applied.api {
+                        Err(e) => {
+                            format!("Inbound ports updated, but the API is unreachable: {}", e)
+                        }
+                        Ok(()) if unreachable > 0 => format!(
+                            "Inbound ports updated; {} listener(s) not reachable",
+                            unreachable
+                        ),
+                        Ok(()) => "Inbound ports updated and reachable".to_string(),
+                    });
+                    if let pages::SettingsAction::EditPorts(editor) = &mut self.action {
+                        editor.original = applied.ports;
+                        editor.ports = applied.ports;
+                        editor.checks = applied.checks;
+                    }
+                }
+                SettingsEvent::SnapshotRestored { name, result } => match result {
+                    Ok(()) => {
+                        state.status_message =
+                            Some(format!("Restored {} and reloaded the core", name));
+                        state.request(ApiRequest::Refresh);
+                    }
+                    Err(e) => state.report_error(&format!("Failed to restore {}", name), &e),
+                },
+            }
+        }
+        changed
+    }
+
+    async fn on_event(&mut self, event: &AppEvent, ctx: &mut Context<'_>) {
+        if let AppEvent::Api(ApiResponse::ConnectionTested(result)) = event {
+            if std::mem::take(&mut self.testing_connection) {
+                let url = ctx.config.api_url.clone();
+                self.action = match result {
+                    Ok(()) => pages::SettingsAction::ConnectionOk(url),
+                    Err(e) => match detect_controller(ctx.config) {
+                        Ok(Some(offer)) => {
+                            ctx.events.push(AppEvent::OfferController(offer));
+                            pages::SettingsAction::None
+                        }
+                        _ => pages::SettingsAction::Error(format!("Cannot reach {}: {}", url, e)),
+                    },
+                };
+            }
+            return;
+        }
+        if let AppEvent::Api(ApiResponse::CoreConfig(result)) = event {
+            if std::mem::take(&mut self.reading_ports) {
+                self.action = match result {
+                    Ok(core) => pages::SettingsAction::EditPorts(pages::PortsEditor::new(
+                        InboundPorts::from_config(core),
+                    )),
+                    Err(e) => {
+                        pages::SettingsAction::Error(format!("Failed to read inbound ports: {}", e))
+                    }
+                };
+            }
+            return;
+        }
+        let AppEvent::FileEdited(path, error) = event else {
+            return;
+        };
+        let pages::SettingsAction::ViewConfig(viewer) = &self.action else {
+            return;
+        };
+        if viewer.draft.as_ref() != Some(path) {
+            return;
+        }
+        match error {
+            Some(error) => ctx
+                .state
+                .report_error("Failed to open the editor", &anyhow::anyhow!(error.clone())),
+            None => self.finish_config_edit(ctx).await,
+        }
+    }
+
+    fn captures_text(&self) -> bool {
+        match &self.action {
+            pages::SettingsAction::EditApiUrl(_) | pages::SettingsAction::EditSecret(_) => true,
+            pages::SettingsAction::EditPorts(editor) => editor.input.is_some(),
+            pages::SettingsAction::Snapshots(view) => view.name_input.is_some(),
+            _ => false,
+        }
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, state: &AppState, config: &AppConfig) {
+        pages::render_settings(f, area, state, config, &self.action, self.service_running);
+    }
+}
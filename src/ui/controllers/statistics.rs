@@ -0,0 +1,43 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{layout::Rect, Frame};
+
+use super::{AppEvent, Context, PageController};
+use crate::app::{AppState, Page};
+use crate::config::{stats, AppConfig};
+use crate::ui::{load_usage_report, pages};
+
+/// Traffic statistics recorded while clashctl runs
+#[derive(Default)]
+pub struct StatisticsController {
+    report: Option<stats::UsageReport>,
+}
+
+impl PageController for StatisticsController {
+    async fn enter(&mut self, ctx: &mut Context<'_>) {
+        match load_usage_report() {
+            Ok(report) => self.report = Some(report),
+            Err(e) => ctx.state.report_error("Failed to load statistics", &e),
+        }
+    }
+
+    async fn handle_key(&mut self, key: KeyEvent, ctx: &mut Context<'_>) {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc | KeyCode::Char('h') => ctx.navigate(Page::Home),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                ctx.emit(AppEvent::ConfirmQuit);
+            }
+            KeyCode::Char('r') => match load_usage_report() {
+                Ok(report) => {
+                    self.report = Some(report);
+                    ctx.state.status_message = Some("Statistics refreshed".to_string());
+                }
+                Err(e) => ctx.state.report_error("Failed to load statistics", &e),
+            },
+            _ => {}
+        }
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, state: &AppState, _config: &AppConfig) {
+        pages::render_statistics(f, area, state, self.report.as_ref());
+    }
+}
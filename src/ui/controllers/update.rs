@@ -0,0 +1,1064 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{layout::Rect, Frame};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, Semaphore};
+
+use super::{AppEvent, Context, PageController};
+use crate::app::fetch_failure::FetchFailure;
+use crate::app::hooks::HookEvent;
+use crate::app::preflight::{self, PREFLIGHT_SAMPLE};
+use crate::app::{ApiRequest, ApiResponse, AppState, Page};
+use crate::clash::{ClashClient, Provider};
+use crate::config::backup::BackupStore;
+use crate::config::diff::ConfigDiff;
+use crate::config::{dedup, template, AppConfig};
+use crate::ui::pages::update::{ImportSource, SubscriptionItem, SubscriptionSource, UpdatePhase};
+use crate::ui::{
+    collect_proxy_sources, debug_log, import, is_http_url, pages, profile_switch, save_profile,
+    spawn_preflight_task, spawn_profile_preview_task, spawn_provider_discovery,
+    spawn_schedule_check, spawn_update_task, theme, write_exclusion_filters, write_merged_profile,
+    ConversionReport, Discovery, NodeRules, PreviewAction, ProfilePreview, UpdateEvent,
+    LOW_BANDWIDTH_BUSY, SPINNER_FRAMES,
+};
+
+/// How often the subscription auto-update schedule is checked
+const UPDATE_SCHEDULE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// Time each frame of the loading spinner is shown
+const SPINNER_FRAME: Duration = Duration::from_millis(100);
+/// Status while `r` reloads the list
+const REFRESHING: &str = "Refreshing providers...";
+
+/// Action the GUI guard warns about for a key that writes profiles or the config
+fn gui_action(key: char) -> &'static str {
+    match key {
+        'I' => "Import subscription file",
+        'P' => "Import provider URL",
+        'z' => "Roll back last change",
+        _ => "Update all subscriptions",
+    }
+}
+
+/// Subscriptions: updating, switching, importing and deduplicating them
+pub struct UpdateController {
+    providers: Vec<SubscriptionItem>,
+    selected_index: usize,
+    /// Provider whose nodes are being browsed, with its nodes once loaded
+    browse: Option<(String, Option<Provider>)>,
+    browse_index: usize,
+    /// Import prompt being typed: a file of subscription URLs or a provider URL
+    import_input: Option<(ImportSource, String)>,
+    import: Option<import::ImportProgress>,
+    import_tx: mpsc::UnboundedSender<import::ImportEvent>,
+    import_rx: mpsc::UnboundedReceiver<import::ImportEvent>,
+    duplicates: Option<dedup::DedupReport>,
+    duplicates_scroll: usize,
+    update_tx: mpsc::UnboundedSender<UpdateEvent>,
+    update_rx: mpsc::UnboundedReceiver<UpdateEvent>,
+    in_flight: usize,
+    total: usize,
+    success: usize,
+    fail: usize,
+    /// Subscriptions of the running update that lost share links in conversion
+    lossy: usize,
+    /// Step of each subscription in the running or last update, by name
+    phases: HashMap<String, UpdatePhase>,
+    /// Registry names of the running update's tasks, cancelled by Esc
+    tasks: Vec<String>,
+    /// Switch waiting for its pre-flight node test
+    pending_switch: Option<ProfilePreview>,
+    /// Why each subscription's last update failed, by name
+    failures: HashMap<String, FetchFailure>,
+    schedule_last_check: Instant,
+    /// Whether a schedule check is reading the subscriptions in the background
+    schedule_running: bool,
+    auto_attempts: HashMap<String, Instant>,
+    /// Sources of the list still being read, with when the reading started
+    discovery: Option<(mpsc::UnboundedReceiver<Discovery>, Instant)>,
+    /// Subscription selected before the list was read again
+    reselect: Option<String>,
+    /// Loading spinner frame shown last
+    spinner_frame: usize,
+    /// Whether the discovery in flight has found the GUI running
+    gui_seen: bool,
+}
+
+impl UpdateController {
+    pub fn new() -> Self {
+        let (import_tx, import_rx) = mpsc::unbounded_channel();
+        let (update_tx, update_rx) = mpsc::unbounded_channel();
+        Self {
+            providers: Vec::new(),
+            selected_index: 0,
+            browse: None,
+            browse_index: 0,
+            import_input: None,
+            import: None,
+            import_tx,
+            import_rx,
+            duplicates: None,
+            duplicates_scroll: 0,
+            update_tx,
+            update_rx,
+            in_flight: 0,
+            total: 0,
+            success: 0,
+            fail: 0,
+            lossy: 0,
+            phases: HashMap::new(),
+            tasks: Vec::new(),
+            pending_switch: None,
+            failures: HashMap::new(),
+            schedule_last_check: Instant::now(),
+            schedule_running: false,
+            auto_attempts: HashMap::new(),
+            discovery: None,
+            reselect: None,
+            spinner_frame: 0,
+            gui_seen: false,
+        }
+    }
+
+    /// Read the list again in the background; subscriptions show up as each source resolves
+    /// The selected one is selected again once it shows up
+    fn start_discovery(&mut self, state: &mut AppState, config: &mut AppConfig) {
+        self.reselect = self
+            .providers
+            .get(self.selected_index)
+            .map(|item| item.name.clone());
+        self.providers.clear();
+        self.selected_index = 0;
+        self.gui_seen = false;
+        let rx =
+            spawn_provider_discovery(&mut state.tasks, config, state.clash_state.client.clone());
+        self.discovery = Some((rx, Instant::now()));
+    }
+
+    /// Add the sources the background read resolved since the last tick
+    fn poll_discovery(&mut self, state: &mut AppState) -> bool {
+        let Some((rx, started)) = self.discovery.as_mut() else {
+            return false;
+        };
+        let mut changed = false;
+        let frame = if theme::low_bandwidth() {
+            0
+        } else {
+            (started.elapsed().as_millis() / SPINNER_FRAME.as_millis()) as usize
+                % SPINNER_FRAMES.len()
+        };
+        if frame != self.spinner_frame {
+            self.spinner_frame = frame;
+            changed = true;
+        }
+        loop {
+            match rx.try_recv() {
+                Ok(Discovery::Found(mut items)) => {
+                    let selected = self
+                        .providers
+                        .get(self.selected_index)
+                        .map(|item| item.name.clone());
+                    self.providers.append(&mut items);
+                    self.providers.sort_by(|a, b| a.name.cmp(&b.name));
+                    let position =
+                        |name: &str| self.providers.iter().position(|item| item.name == name);
+                    self.selected_index = match self.reselect.as_deref().and_then(position) {
+                        Some(index) => {
+                            self.reselect = None;
+                            index
+                        }
+                        None => selected.as_deref().and_then(position).unwrap_or(0),
+                    };
+                    changed = true;
+                }
+                Ok(Discovery::Problem(message)) => {
+                    state.status_message = Some(message);
+                    changed = true;
+                }
+                Ok(Discovery::GuiRunning(gui)) => {
+                    state.gui.set_running(Some(gui));
+                    self.gui_seen = true;
+                    state.status_message = Some(format!(
+                        "{} is running: it may overwrite profile changes made here",
+                        gui
+                    ));
+                    changed = true;
+                }
+                Err(mpsc::error::TryRecvError::Empty) => break,
+                Err(mpsc::error::TryRecvError::Disconnected) => {
+                    self.discovery = None;
+                    self.reselect = None;
+                    // The GUI has quit since it was last seen
+                    if !self.gui_seen {
+                        state.gui.set_running(None);
+                    }
+                    if state.status_message.as_deref() == Some(REFRESHING) {
+                        state.status_message = Some("Providers refreshed!".to_string());
+                    }
+                    changed = true;
+                    break;
+                }
+            }
+        }
+        changed
+    }
+
+    /// Update every provider
+    pub fn update_all(&mut self, state: &mut AppState, config: &AppConfig) {
+        if self.in_flight > 0 {
+            state.status_message = Some("Update in progress...".to_string());
+        } else if self.discovery.is_some() {
+            state.status_message = Some("Still looking for subscriptions...".to_string());
+        } else if self.providers.is_empty() {
+            state.status_message = Some("No subscriptions to update".to_string());
+        } else {
+            self.start_batch(self.providers.clone(), state, config);
+            state.status_message = Some(format!("Updating... (0/{}) - Esc to cancel", self.total));
+        }
+    }
+
+    /// Download `entries` into `target` in the background, showing their progress
+    fn start_import(
+        &mut self,
+        target: import::ImportTarget,
+        entries: Vec<import::ImportEntry>,
+        state: &mut AppState,
+        config: &AppConfig,
+    ) {
+        state.status_message = Some(format!("Importing {} subscription(s)...", entries.len()));
+        self.import = Some(import::ImportProgress::new(&target, entries.clone()));
+        let client = ClashClient::new(config.api_url.clone(), config.secret.clone());
+        let template = config.profile_template.clone();
+        let rename = config.node_rename.clone();
+        let policy = config.default_download_policy();
+        let tx = self.import_tx.clone();
+        state.tasks.spawn("import", async move {
+            let result = import::run(
+                target,
+                entries,
+                template,
+                rename,
+                policy,
+                client,
+                tx.clone(),
+            )
+            .await;
+            let _ = tx.send(import::ImportEvent::Done(
+                result.map(|_| ()).map_err(|e| e.to_string()),
+            ));
+        });
+    }
+
+    /// Update `items`, `update_concurrency` at a time
+    fn start_batch(
+        &mut self,
+        items: Vec<SubscriptionItem>,
+        state: &mut AppState,
+        config: &AppConfig,
+    ) {
+        self.total = items.len();
+        self.in_flight = self.total;
+        self.success = 0;
+        self.fail = 0;
+        self.lossy = 0;
+        self.phases.clear();
+        let limit = Arc::new(Semaphore::new(config.update_concurrency()));
+        self.tasks = items
+            .into_iter()
+            .map(|item| {
+                self.phases.insert(item.name.clone(), UpdatePhase::Pending);
+                let nodes = NodeRules::new(config, &item.name);
+                let policy = config.download_policy(&item.name);
+                spawn_update_task(
+                    &mut state.tasks,
+                    self.update_tx.clone(),
+                    item,
+                    state.clash_state.client.clone(),
+                    config.profile_template.clone(),
+                    nodes,
+                    policy,
+                    limit.clone(),
+                )
+            })
+            .collect();
+    }
+
+    /// Abort the running update; subscriptions already written stay updated
+    async fn cancel_batch(&mut self, ctx: &mut Context<'_>) {
+        for task in self.tasks.drain(..) {
+            ctx.state.tasks.cancel(&task);
+        }
+        let mut cancelled = 0;
+        for phase in self.phases.values_mut() {
+            if !phase.is_finished() {
+                *phase = UpdatePhase::Cancelled;
+                cancelled += 1;
+            }
+        }
+        self.in_flight = 0;
+        self.total = 0;
+        self.start_discovery(ctx.state, ctx.config);
+        ctx.state.status_message = Some(format!(
+            "Update cancelled: {} succeeded, {} failed, {} cancelled",
+            self.success, self.fail, cancelled
+        ));
+    }
+
+    async fn on_update_event(&mut self, event: UpdateEvent, ctx: &mut Context<'_>) {
+        let (state, config) = (&mut *ctx.state, &mut *ctx.config);
+        match event {
+            UpdateEvent::Phase { name, phase } => {
+                if let Some(slot) = self.phases.get_mut(&name) {
+                    *slot = phase;
+                }
+            }
+            UpdateEvent::ScheduleDue(items) => {
+                self.schedule_running = false;
+                // While a manual update runs, the next check picks these up
+                if items.is_empty() || self.in_flight > 0 {
+                    return;
+                }
+                for item in &items {
+                    self.auto_attempts.insert(item.name.clone(), Instant::now());
+                }
+                self.start_batch(items, state, config);
+                state.status_message =
+                    Some(format!("Auto-updating {} subscription(s)...", self.total));
+            }
+            UpdateEvent::PreflightDone(preflight) => {
+                let Some(mut preview) = self.pending_switch.take() else {
+                    return;
+                };
+                state.status_message = Some(if preflight.is_dead() {
+                    format!("Most nodes of {} look unreachable", preview.name)
+                } else {
+                    format!("Review switch to {}", preview.name)
+                });
+                preview.preflight = Some(preflight);
+                ctx.emit(AppEvent::PreviewProfile(Box::new(preview)));
+            }
+            UpdateEvent::PreviewReady {
+                item,
+                bytes,
+                report,
+            } => {
+                self.in_flight = self.in_flight.saturating_sub(1);
+                self.total = 0;
+                self.tasks.clear();
+                self.phases.clear();
+                let note = record_conversion(state, &item.name, report.as_ref());
+                if let Some(report) = report {
+                    ctx.events
+                        .push(AppEvent::ShowConversionReport(item.name.clone(), report));
+                }
+                let SubscriptionSource::Profile {
+                    id,
+                    profile_path,
+                    store,
+                } = &item.source
+                else {
+                    return;
+                };
+                let old_bytes = std::fs::read(profile_path).unwrap_or_default();
+                let diff = ConfigDiff::between(&old_bytes, &bytes);
+                if diff.is_empty() {
+                    // Nothing to review; just record the update
+                    state.status_message =
+                        match save_profile(store.as_ref(), id, &bytes, profile_path) {
+                            Ok(_) => Some(format!("{} is already up to date{}", item.name, note)),
+                            Err(e) => Some(format!("Failed to update {}: {}", item.name, e)),
+                        };
+                    self.start_discovery(state, config);
+                } else {
+                    state.status_message = Some(format!("Review changes to {}{}", item.name, note));
+                    ctx.emit(AppEvent::PreviewProfile(Box::new(ProfilePreview {
+                        name: item.name.clone(),
+                        diff,
+                        action: PreviewAction::Update { item, bytes },
+                        preflight: None,
+                    })));
+                }
+            }
+            UpdateEvent::ItemFinished {
+                name,
+                updated_at,
+                success,
+                failure,
+                report,
+            } => {
+                let note = record_conversion(state, &name, report.as_ref());
+                if !note.is_empty() {
+                    self.lossy += 1;
+                }
+                if let Some(updated_at) = updated_at {
+                    if let Some(item) = self.providers.iter_mut().find(|item| item.name == name) {
+                        item.updated_at = Some(updated_at);
+                    }
+                }
+
+                self.in_flight = self.in_flight.saturating_sub(1);
+                if let Some(slot) = self.phases.get_mut(&name) {
+                    *slot = if success {
+                        UpdatePhase::Done
+                    } else {
+                        UpdatePhase::Failed
+                    };
+                }
+
+                let summary = failure.as_ref().map(FetchFailure::summary);
+                match failure {
+                    Some(failure) => self.failures.insert(name.clone(), failure),
+                    None => self.failures.remove(&name),
+                };
+
+                if success {
+                    self.success += 1;
+                    state.hooks.fire(
+                        HookEvent::SubscriptionUpdated,
+                        &[("CLASHCTL_SUBSCRIPTION", &name)],
+                    );
+                } else {
+                    self.fail += 1;
+                }
+
+                let completed = self.success + self.fail;
+                if self.in_flight == 0 && self.total > 0 {
+                    if self.total == 1 {
+                        if success {
+                            state.status_message =
+                                Some(format!("Updated {} successfully!{}", name, note));
+                            if let Some(report) = report {
+                                ctx.events
+                                    .push(AppEvent::ShowConversionReport(name.clone(), report));
+                            }
+                        } else {
+                            let detail = summary.unwrap_or_else(|| "Unknown error".to_string());
+                            state.status_message =
+                                Some(format!("Failed to update {}: {}", name, detail));
+                        }
+                    } else if self.fail == 0 {
+                        state.status_message = Some(format!(
+                            "All {} providers updated successfully!",
+                            self.success
+                        ));
+                    } else {
+                        state.status_message = Some(format!(
+                            "Updated: {} succeeded, {} failed",
+                            self.success, self.fail
+                        ));
+                    }
+                    if self.total > 1 && self.lossy > 0 {
+                        if let Some(message) = state.status_message.as_mut() {
+                            message.push_str(&format!(
+                                " ({} with skipped share links, press ! for details)",
+                                self.lossy
+                            ));
+                        }
+                    }
+                } else if self.total > 0 {
+                    state.status_message = Some(format!(
+                        "Updating... ({}/{}) - Esc to cancel",
+                        completed, self.total
+                    ));
+                }
+
+                if self.in_flight == 0 && self.total > 0 {
+                    self.start_discovery(state, config);
+                    self.total = 0;
+                    self.tasks.clear();
+                }
+            }
+        }
+    }
+}
+
+/// Log share links a subscription lost in conversion, returning a status suffix
+fn record_conversion(
+    state: &mut AppState,
+    name: &str,
+    report: Option<&ConversionReport>,
+) -> String {
+    match report.and_then(ConversionReport::summary) {
+        Some(summary) => {
+            state.errors.record(
+                &format!("Share links skipped in {}", name),
+                &anyhow::anyhow!(summary),
+            );
+            " (some share links skipped, press ! for details)".to_string()
+        }
+        None => String::new(),
+    }
+}
+
+impl PageController for UpdateController {
+    async fn enter(&mut self, ctx: &mut Context<'_>) {
+        self.selected_index = 0;
+        self.browse = None;
+        // A running update reloads the list itself once finished
+        if self.tasks.is_empty() {
+            self.phases.clear();
+            self.start_discovery(ctx.state, ctx.config);
+        }
+    }
+
+    async fn handle_key(&mut self, key: KeyEvent, ctx: &mut Context<'_>) {
+        let (state, config) = (&mut *ctx.state, &mut *ctx.config);
+        if let Some((name, provider)) = &self.browse {
+            let name = name.clone();
+            let node_count = provider.as_ref().map_or(0, |p| p.proxies.len());
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc | KeyCode::Left => {
+                    self.browse = None;
+                }
+                KeyCode::Up => {
+                    self.browse_index = self.browse_index.saturating_sub(1);
+                }
+                KeyCode::Down if self.browse_index + 1 < node_count => {
+                    self.browse_index += 1;
+                }
+                KeyCode::Char('t') => {
+                    let node = provider
+                        .as_ref()
+                        .and_then(|p| p.proxies.get(self.browse_index))
+                        .map(|proxy| proxy.name.clone());
+                    if let Some(node) = node {
+                        state.status_message = Some(format!("Testing {}...", node));
+                        state.start_test_delay(node);
+                    }
+                }
+                KeyCode::Char('c') => {
+                    state.status_message = Some(format!("Running health check of {}...", name));
+                    state.request(ApiRequest::HealthCheckProvider(name));
+                }
+                KeyCode::Char('r') => {
+                    state.request(ApiRequest::Provider(name));
+                }
+                _ => {}
+            }
+            return;
+        }
+        // Path prompt for a bulk import, or URL prompt for a new provider
+        if let Some((source, input)) = self.import_input.as_mut() {
+            match key.code {
+                KeyCode::Char(c) => input.push(c),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Esc => self.import_input = None,
+                KeyCode::Enter => {
+                    let started = match source {
+                        ImportSource::File => {
+                            let path = import::expand_path(input.trim());
+                            import::read_list(&path).and_then(|entries| {
+                                if entries.is_empty() {
+                                    anyhow::bail!("No subscription URLs in {}", path.display());
+                                }
+                                Ok((import::ImportTarget::resolve(config)?, entries))
+                            })
+                        }
+                        ImportSource::ProviderUrl => {
+                            let input = input.trim();
+                            let (name, url) = match input.rsplit_once(char::is_whitespace) {
+                                Some((name, url)) => (Some(name), url),
+                                None => (None, input),
+                            };
+                            import::single_entry(url, name).and_then(|entry| {
+                                Ok((import::ImportTarget::providers(config)?, vec![entry]))
+                            })
+                        }
+                    };
+                    match started {
+                        Ok((target, entries)) => {
+                            self.start_import(target, entries, state, config);
+                            self.import_input = None;
+                        }
+                        Err(e) => {
+                            state.report_error("Import failed", &e);
+                        }
+                    }
+                }
+                _ => {}
+            }
+            return;
+        }
+        // The import report stays until closed once the import is done
+        if let Some(progress) = &self.import {
+            if progress.done.is_some() && matches!(key.code, KeyCode::Esc | KeyCode::Char('q')) {
+                self.import = None;
+            }
+            return;
+        }
+        if let Some(report) = &self.duplicates {
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => self.duplicates = None,
+                KeyCode::Up => {
+                    self.duplicates_scroll = self.duplicates_scroll.saturating_sub(1);
+                }
+                KeyCode::Down if self.duplicates_scroll + 1 < report.sets.len() => {
+                    self.duplicates_scroll += 1;
+                }
+                KeyCode::Char('x') if state.allow_gui_write("Exclude duplicates") => {
+                    let client = state.clash_state.client.clone();
+                    state.status_message = Some(
+                        match write_exclusion_filters(config, &client, report).await {
+                            Ok(count) => {
+                                state.request(ApiRequest::Refresh);
+                                let skipped = report.unfilterable_count();
+                                let mut message =
+                                    format!("Added exclude-filter to {} provider(s)", count);
+                                if skipped > 0 {
+                                    message.push_str(&format!(
+                                        "; {} duplicate(s) outside providers need a merged profile",
+                                        skipped
+                                    ));
+                                }
+                                message
+                            }
+                            Err(e) => {
+                                format!("Failed to exclude duplicates: {}", e)
+                            }
+                        },
+                    );
+                }
+                KeyCode::Char('m') if state.allow_gui_write("Write merged profile") => {
+                    state.status_message = Some(match write_merged_profile(config, report) {
+                        Ok(message) => message,
+                        Err(e) => {
+                            format!("Failed to write merged profile: {}", e)
+                        }
+                    });
+                }
+                _ => {}
+            }
+            return;
+        }
+        match key.code {
+            KeyCode::Esc if !self.tasks.is_empty() => self.cancel_batch(ctx).await,
+            KeyCode::Char('q') | KeyCode::Esc => {
+                // Return to Home instead of quitting
+                ctx.navigate(Page::Home);
+            }
+            KeyCode::Char('h') => ctx.navigate(Page::Home),
+            KeyCode::Char(key @ ('I' | 'P' | 'z' | 'u'))
+                if !state.allow_gui_write(gui_action(key)) => {}
+            KeyCode::Char('I') => self.import_input = Some((ImportSource::File, String::new())),
+            KeyCode::Char('P') => {
+                self.import_input = Some((ImportSource::ProviderUrl, String::new()))
+            }
+            KeyCode::Char('D') => match collect_proxy_sources(config) {
+                Ok(sources) => {
+                    let report = dedup::analyze(&sources);
+                    if report.sets.is_empty() {
+                        state.status_message = Some(format!(
+                            "No duplicates among {} proxies in {} sources",
+                            report.total, report.sources
+                        ));
+                    } else {
+                        state.status_message = Some(format!(
+                            "{} duplicate proxies found",
+                            report.duplicate_count()
+                        ));
+                        self.duplicates = Some(report);
+                        self.duplicates_scroll = 0;
+                    }
+                }
+                Err(e) => {
+                    state.report_error("Failed to read subscriptions", &e);
+                }
+            },
+            KeyCode::Char('l') => {
+                ctx.navigate(Page::Rules);
+            }
+            KeyCode::Char('r') => {
+                // Refresh provider list
+                if self.in_flight > 0 {
+                    state.status_message = Some("Update in progress...".to_string());
+                } else {
+                    state.status_message = Some(REFRESHING.to_string());
+                    self.phases.clear();
+                    self.start_discovery(state, config);
+                }
+            }
+            KeyCode::Up => {
+                self.selected_index = self.selected_index.saturating_sub(1);
+            }
+            KeyCode::Down => {
+                let max_idx = self.providers.len().saturating_sub(1);
+                if self.selected_index < max_idx {
+                    self.selected_index += 1;
+                }
+            }
+            KeyCode::Char('v') | KeyCode::Right => {
+                // Browse the nodes of the selected provider
+                match self
+                    .providers
+                    .get(self.selected_index)
+                    .map(|item| &item.source)
+                {
+                    Some(SubscriptionSource::ClashProvider { name }) => {
+                        self.browse = Some((name.clone(), None));
+                        self.browse_index = 0;
+                        state.request(ApiRequest::Provider(name.clone()));
+                    }
+                    Some(SubscriptionSource::Profile { .. }) => {
+                        state.status_message =
+                            Some("Only proxy providers can be browsed".to_string());
+                    }
+                    None => {}
+                }
+            }
+            KeyCode::Enter => {
+                // Update selected provider
+                if self.in_flight > 0 {
+                    state.status_message = Some("Update in progress...".to_string());
+                } else if self.selected_index < self.providers.len() {
+                    let item = self.providers[self.selected_index].clone();
+                    if matches!(item.source, SubscriptionSource::Profile { .. })
+                        && !state.allow_gui_write(&format!("Update {}", item.name))
+                    {
+                        return;
+                    }
+                    self.total = 1;
+                    self.in_flight = 1;
+                    self.success = 0;
+                    self.fail = 0;
+                    self.lossy = 0;
+                    state.status_message = Some(format!("Updating {}...", item.name));
+                    self.phases.clear();
+                    self.phases
+                        .insert(item.name.clone(), UpdatePhase::Downloading);
+                    let nodes = NodeRules::new(config, &item.name);
+                    let policy = config.download_policy(&item.name);
+                    let task = match (&item.source, item.url.clone()) {
+                        // Review changes to an existing profile before saving
+                        (
+                            SubscriptionSource::Profile {
+                                id,
+                                profile_path,
+                                store,
+                            },
+                            Some(url),
+                        ) if profile_path.is_file() && is_http_url(&url) => {
+                            let (id, work_config) = (id.clone(), store.work_config_path());
+                            spawn_profile_preview_task(
+                                &mut state.tasks,
+                                self.update_tx.clone(),
+                                item,
+                                id,
+                                url,
+                                work_config,
+                                config.profile_template.clone(),
+                                nodes,
+                                policy,
+                                state.clash_state.client.clone(),
+                            )
+                        }
+                        _ => spawn_update_task(
+                            &mut state.tasks,
+                            self.update_tx.clone(),
+                            item,
+                            state.clash_state.client.clone(),
+                            config.profile_template.clone(),
+                            nodes,
+                            policy,
+                            Arc::new(Semaphore::new(1)),
+                        ),
+                    };
+                    self.tasks = vec![task];
+                } else {
+                    state.status_message = Some("No subscriptions to update".to_string());
+                }
+            }
+            KeyCode::Char('s' | 'S') if self.pending_switch.is_some() => {
+                state.status_message = Some("Still testing nodes...".to_string());
+            }
+            KeyCode::Char(pressed @ ('s' | 'S')) => {
+                // Switch current subscription (GUI profiles); `S` tests its nodes first
+                if self.selected_index < self.providers.len() {
+                    let item = self.providers[self.selected_index].clone();
+                    debug_log(&format!(
+                        "switch start name='{}' type='{}' url_present={}",
+                        item.name,
+                        item.provider_type,
+                        item.url.is_some()
+                    ));
+                    if !matches!(item.source, SubscriptionSource::Profile { .. }) {
+                        state.status_message =
+                            Some("Only GUI profiles support switching".to_string());
+                        return;
+                    }
+                    if !state.allow_gui_write(&format!("Switch to {}", item.name)) {
+                        return;
+                    }
+                    let prepared =
+                        match profile_switch::prepare(&item, config, &state.clash_state.client)
+                            .await
+                        {
+                            Ok(prepared) => prepared,
+                            Err(e) => {
+                                state.report_error(
+                                    &format!("Failed to switch to {}", item.name),
+                                    &e,
+                                );
+                                return;
+                            }
+                        };
+                    if let Some(report) = &prepared.report {
+                        record_conversion(state, &item.name, Some(report));
+                        ctx.events.push(AppEvent::ShowConversionReport(
+                            item.name.clone(),
+                            report.clone(),
+                        ));
+                    }
+                    if prepared.has_errors() {
+                        state.status_message =
+                            Some(format!("Not applied: {} has config errors", item.name));
+                        ctx.emit(AppEvent::ShowProblems(item.name.clone(), prepared.problems));
+                        return;
+                    }
+
+                    let old_bytes = std::fs::read(&prepared.work_config_path).unwrap_or_default();
+                    let (nodes, total) =
+                        preflight::sample_nodes(&prepared.output_bytes, PREFLIGHT_SAMPLE);
+                    let preview = ProfilePreview {
+                        name: item.name.clone(),
+                        diff: ConfigDiff::between(&old_bytes, &prepared.output_bytes),
+                        action: PreviewAction::Switch {
+                            item: item.clone(),
+                            work_config_path: prepared.work_config_path,
+                            output_bytes: prepared.output_bytes,
+                            applied_proxy_count: prepared.applied_proxy_count,
+                        },
+                        preflight: None,
+                    };
+                    if (pressed == 'S' || config.preflight_before_switch) && !nodes.is_empty() {
+                        state.status_message = Some(format!(
+                            "Testing {} of {} nodes of {}...",
+                            nodes.len(),
+                            total,
+                            item.name
+                        ));
+                        self.pending_switch = Some(preview);
+                        spawn_preflight_task(
+                            &mut state.tasks,
+                            self.update_tx.clone(),
+                            nodes,
+                            total,
+                        );
+                    } else {
+                        ctx.emit(AppEvent::PreviewProfile(Box::new(preview)));
+                    }
+                } else {
+                    state.status_message = Some("No subscriptions to switch".to_string());
+                }
+            }
+            KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                state.cycle_preset(config)
+            }
+            KeyCode::Char('T') => {
+                // Cycle the base raw subscriptions are converted onto
+                match config.cycle_profile_template(&template::names()) {
+                    Ok(Some(name)) => {
+                        state.status_message =
+                            Some(format!("Raw subscriptions use template: {}", name));
+                    }
+                    Ok(None) => {
+                        state.status_message =
+                            Some("Raw subscriptions use the work config".to_string());
+                    }
+                    Err(e) => {
+                        state.report_error("Failed to save template", &e);
+                    }
+                }
+            }
+            KeyCode::Char('z') => {
+                // Roll back the last change made by clashctl
+                match BackupStore::open_default().and_then(|store| store.latest()) {
+                    Ok(Some(operation)) => {
+                        let target = operation
+                            .reload
+                            .clone()
+                            .or_else(|| operation.files.first().map(|f| f.original.clone()))
+                            .unwrap_or_default();
+                        let current = std::fs::read(&target).unwrap_or_default();
+                        let previous = operation
+                            .backup_of(&target)
+                            .and_then(|path| std::fs::read(path).ok())
+                            .unwrap_or_default();
+                        ctx.emit(AppEvent::PreviewProfile(Box::new(ProfilePreview {
+                            name: operation.label.clone(),
+                            diff: ConfigDiff::between(&current, &previous),
+                            action: PreviewAction::Rollback(operation),
+                            preflight: None,
+                        })));
+                    }
+                    Ok(None) => {
+                        state.status_message = Some("No backups to roll back".to_string());
+                    }
+                    Err(e) => {
+                        state.report_error("Failed to read backups", &e);
+                    }
+                }
+            }
+            KeyCode::Char('d') if self.selected_index < self.providers.len() => {
+                // Cycle how the selected subscription is downloaded
+                let name = self.providers[self.selected_index].name.clone();
+                state.status_message = Some(match config.cycle_download_policy(&name) {
+                    Ok(Some(policy)) => format!("{} downloads {}", name, policy.label()),
+                    Ok(None) => format!(
+                        "{} downloads like the default ({})",
+                        name,
+                        config.default_download_policy().label()
+                    ),
+                    Err(e) => format!("Failed to save setting: {}", e),
+                });
+            }
+            KeyCode::Char('i') if self.selected_index < self.providers.len() => {
+                // Cycle auto-update interval for selected subscription
+                let name = self.providers[self.selected_index].name.clone();
+                match config.cycle_update_interval(&name) {
+                    Ok(Some(minutes)) => {
+                        state.status_message = Some(format!(
+                            "Auto-update {} every {}",
+                            name,
+                            pages::update::format_interval(minutes)
+                        ));
+                    }
+                    Ok(None) => {
+                        state.status_message = Some(format!("Auto-update off for {}", name));
+                    }
+                    Err(e) => {
+                        state.report_error("Failed to save schedule", &e);
+                    }
+                }
+                self.auto_attempts.remove(&name);
+            }
+            KeyCode::Char('u') => self.update_all(state, config),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                ctx.emit(AppEvent::Quit)
+            }
+            _ => {}
+        }
+    }
+
+    async fn tick(&mut self, ctx: &mut Context<'_>) -> bool {
+        let mut changed = false;
+        while let Ok(event) = self.import_rx.try_recv() {
+            changed = true;
+            if let import::ImportEvent::Done(result) = &event {
+                ctx.state.status_message = Some(match result {
+                    Ok(()) => "Import finished".to_string(),
+                    Err(e) => format!("Import failed: {}", e),
+                });
+                self.start_discovery(ctx.state, ctx.config);
+            }
+            if let Some(progress) = self.import.as_mut() {
+                progress.apply(event);
+            }
+        }
+
+        while let Ok(event) = self.update_rx.try_recv() {
+            self.on_update_event(event, ctx).await;
+            changed = true;
+        }
+        changed |= self.poll_discovery(ctx.state);
+
+        // Auto-update subscriptions that are due on their schedule
+        if !ctx.config.update_intervals.is_empty()
+            && self.in_flight == 0
+            && !self.schedule_running
+            && self.schedule_last_check.elapsed() >= UPDATE_SCHEDULE_CHECK_INTERVAL
+        {
+            self.schedule_last_check = Instant::now();
+            self.schedule_running = true;
+            spawn_schedule_check(
+                &mut ctx.state.tasks,
+                self.update_tx.clone(),
+                ctx.config,
+                ctx.state.clash_state.client.clone(),
+                self.auto_attempts.clone(),
+            );
+        }
+        changed
+    }
+
+    async fn on_event(&mut self, event: &AppEvent, ctx: &mut Context<'_>) {
+        let state = &mut *ctx.state;
+        match event {
+            AppEvent::Api(ApiResponse::Provider { name, result }) => match result {
+                Ok(provider) => {
+                    if let Some((browsed, nodes)) = self.browse.as_mut() {
+                        if browsed == name {
+                            self.browse_index = self
+                                .browse_index
+                                .min(provider.proxies.len().saturating_sub(1));
+                            *nodes = Some(provider.clone());
+                        }
+                    }
+                }
+                Err(e) => state.report_error(&format!("Failed to load provider {}", name), e),
+            },
+            AppEvent::Api(ApiResponse::ProviderHealthChecked { name, result }) => match result {
+                Ok(()) => {
+                    state.status_message = Some(format!("Health check of {} finished", name));
+                    if self
+                        .browse
+                        .as_ref()
+                        .is_some_and(|(browsed, _)| browsed == name)
+                    {
+                        state.request(ApiRequest::Provider(name.clone()));
+                    }
+                }
+                Err(e) => state.report_error(&format!("Health check of {} failed", name), e),
+            },
+            AppEvent::SubscriptionsChanged | AppEvent::ProfileSwitched(_) => {
+                self.start_discovery(state, ctx.config);
+            }
+            _ => {}
+        }
+    }
+
+    fn captures_text(&self) -> bool {
+        self.import_input.is_some()
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, state: &AppState, config: &AppConfig) {
+        match &self.browse {
+            Some((name, provider)) => pages::update::render_provider_nodes(
+                f,
+                area,
+                state,
+                name,
+                provider.as_ref(),
+                self.browse_index,
+            ),
+            None => pages::render_update(
+                f,
+                area,
+                state,
+                config,
+                &self.providers,
+                &self.phases,
+                &self.failures,
+                self.selected_index,
+                self.discovery.as_ref().map(|_| {
+                    if theme::low_bandwidth() {
+                        LOW_BANDWIDTH_BUSY
+                    } else {
+                        SPINNER_FRAMES[self.spinner_frame]
+                    }
+                }),
+                match (&self.import_input, &self.import, &self.duplicates) {
+                    (Some((source, input)), _, _) => {
+                        pages::update::UpdatePanel::ImportPrompt(*source, input)
+                    }
+                    (None, Some(progress), _) => pages::update::UpdatePanel::Import(progress),
+                    (None, None, Some(report)) => {
+                        pages::update::UpdatePanel::Duplicates(report, self.duplicates_scroll)
+                    }
+                    (None, None, None) => pages::update::UpdatePanel::Subscriptions,
+                },
+            ),
+        }
+    }
+}
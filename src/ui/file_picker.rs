@@ -0,0 +1,223 @@
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState},
+    Frame,
+};
+use std::path::{Path, PathBuf};
+
+use crate::ui::list;
+
+/// Files the picker offers; directories are always listed
+const EXTENSIONS: [&str; 2] = ["yaml", "yml"];
+
+/// One row of the directory listing
+#[derive(Debug, Clone, PartialEq)]
+pub struct PickerEntry {
+    pub name: String,
+    pub path: PathBuf,
+    pub is_dir: bool,
+}
+
+/// Directory navigator for choosing a YAML file
+#[derive(Debug, Clone)]
+pub struct FilePicker {
+    pub dir: PathBuf,
+    pub entries: Vec<PickerEntry>,
+    pub selected: usize,
+    /// Why the current directory could not be listed
+    pub error: Option<String>,
+}
+
+/// What a key did to the picker
+#[derive(Debug, PartialEq)]
+pub enum PickerOutcome {
+    Pending,
+    Picked(PathBuf),
+    Cancelled,
+}
+
+impl FilePicker {
+    /// Open at `start`, or its closest existing ancestor
+    pub fn new(start: &Path) -> Self {
+        let dir = start
+            .ancestors()
+            .find(|dir| dir.is_dir())
+            .unwrap_or(Path::new("."))
+            .to_path_buf();
+        let mut picker = Self {
+            dir: PathBuf::new(),
+            entries: Vec::new(),
+            selected: 0,
+            error: None,
+        };
+        picker.open(dir);
+        picker
+    }
+
+    /// List `dir`: its parent first, then subdirectories, then YAML files, each by name
+    fn open(&mut self, dir: PathBuf) {
+        let mut entries = Vec::new();
+        if let Some(parent) = dir.parent() {
+            entries.push(PickerEntry {
+                name: "..".to_string(),
+                path: parent.to_path_buf(),
+                is_dir: true,
+            });
+        }
+        self.error = None;
+        match std::fs::read_dir(&dir) {
+            Ok(read) => {
+                let mut listed: Vec<PickerEntry> =
+                    read.filter_map(|entry| entry.ok())
+                        .filter_map(|entry| {
+                            let path = entry.path();
+                            let is_dir = path.is_dir();
+                            let wanted = is_dir
+                                || path.extension().and_then(|ext| ext.to_str()).is_some_and(
+                                    |ext| EXTENSIONS.contains(&ext.to_lowercase().as_str()),
+                                );
+                            wanted.then(|| PickerEntry {
+                                name: entry.file_name().to_string_lossy().to_string(),
+                                path,
+                                is_dir,
+                            })
+                        })
+                        .collect();
+                listed.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then(a.name.cmp(&b.name)));
+                entries.extend(listed);
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+        self.dir = dir;
+        self.entries = entries;
+        self.selected = 0;
+    }
+
+    /// Go up one directory, keeping the one we came from selected
+    fn up(&mut self) {
+        let Some(parent) = self.dir.parent().map(Path::to_path_buf) else {
+            return;
+        };
+        let from = std::mem::replace(&mut self.dir, PathBuf::new());
+        self.open(parent);
+        if let Some(index) = self.entries.iter().position(|entry| entry.path == from) {
+            self.selected = index;
+        }
+    }
+
+    pub fn handle_key(&mut self, code: KeyCode) -> PickerOutcome {
+        if let Some(index) = list::jump(code, self.selected, self.entries.len()) {
+            self.selected = index;
+            return PickerOutcome::Pending;
+        }
+        match code {
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') if self.selected + 1 < self.entries.len() => {
+                self.selected += 1;
+            }
+            KeyCode::Left | KeyCode::Backspace | KeyCode::Char('h') => self.up(),
+            KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => {
+                let Some(entry) = self.entries.get(self.selected).cloned() else {
+                    return PickerOutcome::Pending;
+                };
+                if entry.name == ".." {
+                    self.up();
+                } else if entry.is_dir {
+                    self.open(entry.path);
+                } else if code == KeyCode::Enter {
+                    return PickerOutcome::Picked(entry.path);
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') => return PickerOutcome::Cancelled,
+            _ => {}
+        }
+        PickerOutcome::Pending
+    }
+}
+
+/// Centered picker over `area`
+pub fn render_file_picker(f: &mut Frame, area: Rect, title: &str, picker: &FilePicker) {
+    let width = area.width.saturating_sub(8).min(90);
+    let height = area.height.saturating_sub(4);
+    let dialog_area = Rect {
+        x: area.x + (area.width.saturating_sub(width)) / 2,
+        y: area.y + (area.height.saturating_sub(height)) / 2,
+        width,
+        height,
+    };
+    f.render_widget(Clear, dialog_area);
+
+    let mut items: Vec<ListItem> = picker
+        .entries
+        .iter()
+        .map(|entry| {
+            if entry.is_dir {
+                ListItem::new(Line::from(Span::styled(
+                    format!("{}/", entry.name),
+                    Style::default().fg(Color::Cyan),
+                )))
+            } else {
+                ListItem::new(Line::from(entry.name.clone()))
+            }
+        })
+        .collect();
+    if let Some(error) = &picker.error {
+        items.push(ListItem::new(Line::from(Span::styled(
+            error.clone(),
+            Style::default().fg(Color::Red),
+        ))));
+    }
+
+    list::viewport(dialog_area, 1);
+    let mut list_state = ListState::default();
+    list_state.select(Some(picker.selected));
+    let widget = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Yellow))
+                .title(format!("{}: {}", title, picker.dir.display()))
+                .title_bottom(
+                    Line::from(" ↑↓ Move  Enter Open/Pick  ← Parent  Esc Cancel ").centered(),
+                ),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    f.render_stateful_widget(widget, dialog_area, &mut list_state);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_picker_lists_dirs_and_yaml_and_picks() {
+        let root =
+            std::env::temp_dir().join(format!("clashctl-picker-test-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("mihomo")).unwrap();
+        std::fs::write(root.join("mihomo/config.yaml"), "mixed-port: 7890").unwrap();
+        std::fs::write(root.join("notes.txt"), "").unwrap();
+
+        let mut picker = FilePicker::new(&root.join("missing/config.yaml"));
+        assert_eq!(picker.dir, root);
+        let names: Vec<&str> = picker.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["..", "mihomo"]);
+
+        picker.handle_key(KeyCode::Down);
+        assert_eq!(picker.handle_key(KeyCode::Enter), PickerOutcome::Pending);
+        assert_eq!(picker.dir, root.join("mihomo"));
+        picker.handle_key(KeyCode::Down);
+        assert_eq!(
+            picker.handle_key(KeyCode::Enter),
+            PickerOutcome::Picked(root.join("mihomo/config.yaml"))
+        );
+
+        // Going back up keeps the directory we left selected
+        picker.handle_key(KeyCode::Left);
+        assert_eq!(picker.entries[picker.selected].name, "mihomo");
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,143 @@
+use ratatui::{
+    style::{Color, Style},
+    text::Span,
+};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+use crate::ui::theme;
+
+/// UI language
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Language {
+    #[default]
+    En,
+    ZhCn,
+}
+
+impl Language {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Language::En => "en",
+            Language::ZhCn => "zh-CN",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Self {
+        match s.to_lowercase().replace('_', "-").as_str() {
+            "zh" | "zh-cn" => Language::ZhCn,
+            _ => Language::En,
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            Language::En => Language::ZhCn,
+            Language::ZhCn => Language::En,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Language::En => "English",
+            Language::ZhCn => "简体中文",
+        }
+    }
+
+    fn source(&self) -> &'static str {
+        match self {
+            Language::En => include_str!("locales/en.yaml"),
+            Language::ZhCn => include_str!("locales/zh-CN.yaml"),
+        }
+    }
+
+    fn table(&self) -> &'static HashMap<String, String> {
+        static EN: OnceLock<HashMap<String, String>> = OnceLock::new();
+        static ZH_CN: OnceLock<HashMap<String, String>> = OnceLock::new();
+        let cell = match self {
+            Language::En => &EN,
+            Language::ZhCn => &ZH_CN,
+        };
+        cell.get_or_init(|| serde_yaml::from_str(self.source()).unwrap_or_default())
+    }
+}
+
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+/// Switch the language used by `t`
+pub fn set_language(language: Language) {
+    CURRENT.store(language as u8, Ordering::Relaxed);
+}
+
+/// Language currently used by `t`
+pub fn language() -> Language {
+    match CURRENT.load(Ordering::Relaxed) {
+        1 => Language::ZhCn,
+        _ => Language::En,
+    }
+}
+
+/// Translate `key` into the current language
+/// Falls back to English, then to the key itself
+pub fn t(key: &'static str) -> &'static str {
+    language()
+        .table()
+        .get(key)
+        .or_else(|| Language::En.table().get(key))
+        .map(String::as_str)
+        .unwrap_or(key)
+}
+
+/// Abbreviated translation of `key`, from its `.short` entry when the locale has one
+pub fn t_short(key: &'static str) -> &'static str {
+    let short = format!("{}.short", key);
+    language()
+        .table()
+        .get(&short)
+        .or_else(|| Language::En.table().get(&short))
+        .map(String::as_str)
+        .unwrap_or_else(|| t(key))
+}
+
+/// Help bar spans: each key in yellow followed by its translated label
+/// Compact layouts use the abbreviated labels and tighter gaps
+pub fn key_hints(hints: &[(&str, &'static str)]) -> Vec<Span<'static>> {
+    let compact = theme::compact();
+    let mut spans = Vec::new();
+    for (index, (key, label)) in hints.iter().enumerate() {
+        let gap = match (index + 1 < hints.len(), compact) {
+            (false, _) => "",
+            (true, true) => " ",
+            (true, false) => "  ",
+        };
+        let label = if compact { t_short(label) } else { t(label) };
+        spans.push(Span::styled(
+            key.to_string(),
+            Style::default().fg(Color::Yellow),
+        ));
+        spans.push(Span::raw(format!(" {}{}", label, gap)));
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locales_have_same_keys() {
+        let en = Language::En.table();
+        let zh = Language::ZhCn.table();
+        assert!(!en.is_empty());
+        let mut missing: Vec<_> = en.keys().filter(|key| !zh.contains_key(*key)).collect();
+        missing.extend(zh.keys().filter(|key| !en.contains_key(*key)));
+        assert!(
+            missing.is_empty(),
+            "keys missing a translation: {:?}",
+            missing
+        );
+        assert_eq!(Language::from_str("zh_CN"), Language::ZhCn);
+    }
+}
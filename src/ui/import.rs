@@ -0,0 +1,489 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::{mpsc, Semaphore};
+use url::Url;
+
+use super::{
+    backup_before_write, convert_profile, fetch_profile, is_http_url, looks_like_clash_config,
+    lossy_conversion, parse_raw_subscription, percent_decode, resolve_clash_config_path,
+    save_profile, subscription_client, NodeRules,
+};
+use crate::clash::ClashClient;
+use crate::config::atomic::write_atomic;
+use crate::config::clash_config::add_http_providers;
+use crate::config::download::DownloadPolicy;
+use crate::config::node_filter::NodeFilter;
+use crate::config::node_rename::RenameRules;
+use crate::config::profile_store::{find_profile_store, NewProfile, ProfileStore};
+use crate::config::AppConfig;
+
+/// Subscriptions downloaded at once
+const CONCURRENCY: usize = 4;
+/// Refresh interval of imported proxy providers, in seconds
+const PROVIDER_INTERVAL: u32 = 86400;
+
+/// One subscription from an import file
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportEntry {
+    pub name: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportStatus {
+    Pending,
+    Downloading,
+    /// Imported, with a short summary such as the proxy count
+    Imported(String),
+    /// Left out on purpose, e.g. the URL is already a subscription
+    Skipped(String),
+    Failed(String),
+}
+
+impl ImportStatus {
+    pub fn is_finished(&self) -> bool {
+        !matches!(self, ImportStatus::Pending | ImportStatus::Downloading)
+    }
+}
+
+/// Where imported subscriptions are written
+#[derive(Debug, Clone)]
+pub enum ImportTarget {
+    /// New remote profiles in a GUI's profile list
+    Profiles { store: Arc<dyn ProfileStore> },
+    /// New `http` proxy providers in the Clash config
+    Providers { config_path: PathBuf },
+}
+
+impl ImportTarget {
+    /// GUI profiles when a profile store is found, else providers in the Clash config
+    pub fn resolve(config: &mut AppConfig) -> Result<Self> {
+        let hint = config.clash_config_path.as_deref().map(Path::new);
+        if let Some(store) = find_profile_store(hint) {
+            return Ok(ImportTarget::Profiles { store });
+        }
+        Self::providers(config)
+            .map_err(|_| anyhow::anyhow!("No GUI profile list or Clash config found"))
+    }
+
+    /// Providers in the Clash config, even when a GUI profile list exists
+    pub fn providers(config: &mut AppConfig) -> Result<Self> {
+        resolve_clash_config_path(config)
+            .map(|config_path| ImportTarget::Providers { config_path })
+            .ok_or_else(|| anyhow::anyhow!("No Clash config found"))
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            ImportTarget::Profiles { store } => {
+                format!(
+                    "{} profiles ({})",
+                    store.name(),
+                    store.list_path().display()
+                )
+            }
+            ImportTarget::Providers { config_path } => {
+                format!("proxy providers in {}", config_path.display())
+            }
+        }
+    }
+}
+
+/// Entries of a running or finished import with their latest status
+#[derive(Debug, Clone)]
+pub struct ImportProgress {
+    pub target: String,
+    pub entries: Vec<ImportEntry>,
+    pub statuses: Vec<ImportStatus>,
+    /// Set once the import wrote everything, with its final error if any
+    pub done: Option<Result<(), String>>,
+}
+
+impl ImportProgress {
+    pub fn new(target: &ImportTarget, entries: Vec<ImportEntry>) -> Self {
+        Self {
+            target: target.describe(),
+            statuses: vec![ImportStatus::Pending; entries.len()],
+            entries,
+            done: None,
+        }
+    }
+
+    pub fn apply(&mut self, event: ImportEvent) {
+        match event {
+            ImportEvent::Status(index, status) => {
+                if let Some(slot) = self.statuses.get_mut(index) {
+                    *slot = status;
+                }
+            }
+            ImportEvent::Done(result) => self.done = Some(result),
+        }
+    }
+
+    pub fn finished_count(&self) -> usize {
+        self.statuses.iter().filter(|s| s.is_finished()).count()
+    }
+}
+
+/// Progress of a running import, per entry index
+#[derive(Debug, Clone)]
+pub enum ImportEvent {
+    Status(usize, ImportStatus),
+    /// Everything was written (or an error stopped the final write)
+    Done(Result<(), String>),
+}
+
+/// Path typed in the TUI, with a leading `~` meaning the home directory
+pub fn expand_path(input: &str) -> PathBuf {
+    match (input.strip_prefix('~'), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest.trim_start_matches('/')),
+        _ => PathBuf::from(input),
+    }
+}
+
+/// Read an import file, see `parse_list`
+pub fn read_list(path: &Path) -> Result<Vec<ImportEntry>> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(parse_list(&text))
+}
+
+/// One subscription per line: a URL, optionally preceded by a name (`Work https://...`)
+/// Without a name the URL's `#fragment` or host is used; blank lines and `#` comments are skipped
+pub fn parse_list(text: &str) -> Vec<ImportEntry> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (name, url) = match line.rsplit_once(char::is_whitespace) {
+                Some((name, url)) => (Some(name.trim().to_string()), url.to_string()),
+                None => (None, line.to_string()),
+            };
+            let name = name.or_else(|| default_name(&url))?;
+            Some(ImportEntry { name, url })
+        })
+        .collect()
+}
+
+/// A single subscription URL, named after its `#fragment` or host unless `name` is given
+pub fn single_entry(url: &str, name: Option<&str>) -> Result<ImportEntry> {
+    let url = url.trim();
+    if !is_http_url(url) {
+        anyhow::bail!("{} is not an http(s) URL", url);
+    }
+    let name = name
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .or_else(|| default_name(url))
+        .unwrap_or_else(|| url.to_string());
+    Ok(ImportEntry {
+        name,
+        url: url.to_string(),
+    })
+}
+
+fn default_name(url: &str) -> Option<String> {
+    let parsed = Url::parse(url).ok();
+    parsed
+        .as_ref()
+        .and_then(|url| url.fragment())
+        .map(percent_decode)
+        .filter(|name| !name.trim().is_empty())
+        .or_else(|| parsed.as_ref()?.host_str().map(str::to_string))
+        .or_else(|| Some(url.to_string()))
+}
+
+/// `name`, or `name (2)`, `name (3)`... when it is taken
+fn unique_name(name: &str, taken: &mut Vec<String>) -> String {
+    let mut candidate = name.to_string();
+    let mut counter = 2;
+    while taken.contains(&candidate) {
+        candidate = format!("{} ({})", name, counter);
+        counter += 1;
+    }
+    taken.push(candidate.clone());
+    candidate
+}
+
+/// Proxies in a downloaded subscription, full config or raw share links
+fn count_proxies(bytes: &[u8]) -> usize {
+    if looks_like_clash_config(bytes) {
+        serde_yaml::from_slice::<serde_yaml::Value>(bytes)
+            .ok()
+            .and_then(|value| value.get("proxies")?.as_sequence().map(|seq| seq.len()))
+            .unwrap_or(0)
+    } else {
+        parse_raw_subscription(bytes).proxies.len()
+    }
+}
+
+/// Download every entry concurrently, then write all new profiles or providers at once
+/// Progress goes to `events`; the final status of each entry is returned
+pub async fn run(
+    target: ImportTarget,
+    entries: Vec<ImportEntry>,
+    template: Option<String>,
+    rename: RenameRules,
+    policy: DownloadPolicy,
+    client: ClashClient,
+    events: mpsc::UnboundedSender<ImportEvent>,
+) -> Result<Vec<ImportStatus>> {
+    let mut statuses = vec![ImportStatus::Pending; entries.len()];
+    let report = |statuses: &mut Vec<ImportStatus>, index: usize, status: ImportStatus| {
+        let _ = events.send(ImportEvent::Status(index, status.clone()));
+        statuses[index] = status;
+    };
+
+    // Existing subscriptions by URL, so importing the same file twice adds nothing
+    let (mut taken_names, existing): (Vec<String>, HashMap<String, String>) = match &target {
+        ImportTarget::Profiles { store } => {
+            let list = store.load()?;
+            let existing = list
+                .items
+                .iter()
+                .filter_map(|item| Some((item.url.clone()?, item.name.clone())))
+                .collect();
+            (
+                list.items.into_iter().map(|item| item.name).collect(),
+                existing,
+            )
+        }
+        ImportTarget::Providers { config_path } => {
+            let providers = crate::config::ClashConfig::load(config_path)?.get_providers();
+            let existing = providers
+                .iter()
+                .filter_map(|(name, _, url)| Some((url.clone()?, name.clone())))
+                .collect();
+            (
+                providers.into_iter().map(|(name, _, _)| name).collect(),
+                existing,
+            )
+        }
+    };
+
+    let http = subscription_client(policy, &client).await?;
+    let limit = Arc::new(Semaphore::new(CONCURRENCY));
+    let mut downloads = Vec::new();
+    let mut seen_urls: Vec<&str> = Vec::new();
+    for (index, entry) in entries.iter().enumerate() {
+        if !is_http_url(&entry.url) {
+            report(
+                &mut statuses,
+                index,
+                ImportStatus::Failed("not an http(s) URL".to_string()),
+            );
+            continue;
+        }
+        if let Some(name) = existing.get(&entry.url) {
+            report(
+                &mut statuses,
+                index,
+                ImportStatus::Skipped(format!("already imported as {}", name)),
+            );
+            continue;
+        }
+        if seen_urls.contains(&entry.url.as_str()) {
+            report(
+                &mut statuses,
+                index,
+                ImportStatus::Skipped("duplicate URL in the file".to_string()),
+            );
+            continue;
+        }
+        seen_urls.push(&entry.url);
+
+        let limit = limit.clone();
+        let events = events.clone();
+        let url = entry.url.clone();
+        let target = target.clone();
+        let template = template.clone();
+        let http = http.clone();
+        // New subscriptions have no node filter yet, but are renamed like the others
+        let nodes = NodeRules {
+            subscription: entry.name.clone(),
+            filter: NodeFilter::default(),
+            rename: rename.clone(),
+        };
+        downloads.push((
+            index,
+            tokio::spawn(async move {
+                let _permit = limit.acquire_owned().await?;
+                let _ = events.send(ImportEvent::Status(index, ImportStatus::Downloading));
+                match &target {
+                    ImportTarget::Profiles { store } => {
+                        let work_config = store.work_config_path();
+                        let bytes = fetch_profile("import", &url, &http).await?;
+                        let skipped = lossy_conversion(&bytes).and_then(|report| report.summary());
+                        convert_profile(bytes, Some(&work_config), template.as_deref(), &nodes)
+                            .map(|bytes| (bytes, skipped))
+                    }
+                    // The core parses provider files itself
+                    ImportTarget::Providers { .. } => {
+                        let response = http.get(&url).send().await?.error_for_status()?;
+                        Ok((response.bytes().await?.to_vec(), None))
+                    }
+                }
+            }),
+        ));
+    }
+
+    let mut downloaded = Vec::new();
+    let mut skipped_links = HashMap::new();
+    for (index, handle) in downloads {
+        let result = handle
+            .await
+            .map_err(anyhow::Error::from)
+            .and_then(|result| result);
+        match result {
+            Ok((bytes, skipped)) => match count_proxies(&bytes) {
+                0 => report(
+                    &mut statuses,
+                    index,
+                    ImportStatus::Failed("no proxies found".to_string()),
+                ),
+                count => {
+                    if let Some(skipped) = skipped {
+                        skipped_links.insert(index, skipped);
+                    }
+                    downloaded.push((index, bytes, count))
+                }
+            },
+            Err(e) => report(&mut statuses, index, ImportStatus::Failed(e.to_string())),
+        }
+    }
+    if downloaded.is_empty() {
+        return Ok(statuses);
+    }
+
+    let names: Vec<String> = downloaded
+        .iter()
+        .map(|(index, _, _)| unique_name(&entries[*index].name, &mut taken_names))
+        .collect();
+    match &target {
+        ImportTarget::Profiles { store } => {
+            // Entries first, so saving each profile can record its update time
+            let new: Vec<NewProfile> = downloaded
+                .iter()
+                .zip(&names)
+                .map(|((index, _, _), name)| NewProfile {
+                    name: name.clone(),
+                    url: Some(entries[*index].url.clone()),
+                })
+                .collect();
+            backup_before_write("Import subscriptions", &[store.list_path()], None);
+            let added = store.add(&new)?;
+
+            for ((index, bytes, count), profile) in downloaded.iter().zip(&added) {
+                let status = match save_profile(store.as_ref(), &profile.id, bytes, &profile.path) {
+                    Ok(_) => ImportStatus::Imported(match skipped_links.get(index) {
+                        Some(skipped) => {
+                            format!("{} ({} proxies; {})", profile.name, count, skipped)
+                        }
+                        None => format!("{} ({} proxies)", profile.name, count),
+                    }),
+                    Err(e) => ImportStatus::Failed(e.to_string()),
+                };
+                report(&mut statuses, *index, status);
+            }
+        }
+        ImportTarget::Providers { config_path } => {
+            let config_dir = config_path.parent().unwrap_or(Path::new("."));
+            let mut providers = Vec::new();
+            for ((index, bytes, count), name) in downloaded.iter().zip(&names) {
+                // Prefill the provider file so the nodes show up before the first refresh
+                let path = format!("./proxy_providers/{}.yaml", file_stem(name));
+                let written = std::fs::create_dir_all(config_dir.join("proxy_providers"))
+                    .map_err(anyhow::Error::from)
+                    .and_then(|_| write_atomic(&config_dir.join(&path), bytes));
+                match written {
+                    Ok(()) => {
+                        providers.push((*index, *count, name.clone(), path));
+                    }
+                    Err(e) => report(
+                        &mut statuses,
+                        *index,
+                        ImportStatus::Failed(format!("{:#}", e)),
+                    ),
+                }
+            }
+
+            let blocks: Vec<(String, String, String)> = providers
+                .iter()
+                .map(|(index, _, name, path)| {
+                    (name.clone(), entries[*index].url.clone(), path.clone())
+                })
+                .collect();
+            let bytes = std::fs::read(config_path)?;
+            let output = add_http_providers(&bytes, &blocks, PROVIDER_INTERVAL)?;
+            backup_before_write("Import subscriptions", &[config_path], Some(config_path));
+            write_atomic(config_path, output)?;
+            for (index, count, name, _) in &providers {
+                report(
+                    &mut statuses,
+                    *index,
+                    ImportStatus::Imported(format!("{} ({} proxies)", name, count)),
+                );
+            }
+            client
+                .reload_config_path(&config_path.to_string_lossy())
+                .await
+                .context("Providers were added but reloading the core failed")?;
+        }
+    }
+    Ok(statuses)
+}
+
+/// Provider name usable as a file name
+fn file_stem(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || "-_.".contains(c) {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_list() {
+        let text = "# my subscriptions\n\
+                    https://sub.example.com/link?token=1\n\
+                    \n\
+                    Work Airport https://work.example.net/sub\n\
+                    https://b.example.org/s#%E9%A6%99%E6%B8%AF\n";
+        let entries = parse_list(text);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].name, "sub.example.com");
+        assert_eq!(entries[1].name, "Work Airport");
+        assert_eq!(entries[1].url, "https://work.example.net/sub");
+        assert_eq!(entries[2].name, "香港");
+        assert_eq!(
+            single_entry(" https://sub.example.com/link ", None)
+                .unwrap()
+                .name,
+            "sub.example.com"
+        );
+        assert_eq!(
+            single_entry("https://sub.example.com/link", Some("Work"))
+                .unwrap()
+                .name,
+            "Work"
+        );
+        assert!(single_entry("sub.example.com", None).is_err());
+
+        let mut taken = vec!["sub.example.com".to_string()];
+        assert_eq!(
+            unique_name("sub.example.com", &mut taken),
+            "sub.example.com (2)"
+        );
+    }
+}
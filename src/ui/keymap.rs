@@ -0,0 +1,596 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::app::Page;
+use crate::config::Preset;
+use crate::ui::i18n::t;
+
+/// When a binding is offered
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Needs {
+    Nothing,
+    /// The preset allows speed tests
+    SpeedTest,
+    /// Speed tests, on a selector group
+    Selector,
+    /// The group has a health check
+    HealthCheck,
+    /// A node is pinned on the url-test group
+    Pinned,
+}
+
+impl Needs {
+    /// Whether `preset` offers the binding; group conditions are left to the page
+    pub fn met_by(self, preset: &Preset) -> bool {
+        match self {
+            Needs::SpeedTest | Needs::Selector => preset.show_speed_test(),
+            Needs::Nothing | Needs::HealthCheck | Needs::Pinned => true,
+        }
+    }
+}
+
+/// Keys and the `help.*` (or `home.*`) label of what they do
+#[derive(Debug, Clone, Copy)]
+pub struct Binding {
+    pub keys: &'static str,
+    pub label: &'static str,
+    /// Shown in the page's help bar, not only in the `?` overlay
+    pub in_bar: bool,
+    pub needs: Needs,
+}
+
+const fn key(keys: &'static str, label: &'static str) -> Binding {
+    Binding {
+        keys,
+        label,
+        in_bar: true,
+        needs: Needs::Nothing,
+    }
+}
+
+/// A binding listed only in the overlay
+const fn more(keys: &'static str, label: &'static str) -> Binding {
+    Binding {
+        in_bar: false,
+        ..key(keys, label)
+    }
+}
+
+impl Binding {
+    const fn needs(self, needs: Needs) -> Self {
+        Binding { needs, ..self }
+    }
+}
+
+/// Bindings of one page or one of its modes
+pub struct Section {
+    pub title: &'static str,
+    pub bindings: &'static [Binding],
+}
+
+/// Keys handled on every page, outside text input
+pub const GLOBAL: &[Binding] = &[
+    key("!", "help.errors"),
+    key(":", "help.palette"),
+    key("?", "help.help"),
+    more("Ctrl+P", "help.next_preset"),
+    more("Ctrl+C", "help.quit"),
+];
+
+pub const HOME_ACTIONS: &[Binding] = &[
+    key("m", "home.switch_scene"),
+    key("g", "home.routes"),
+    key("f", "home.favorites"),
+    key("G", "home.groups"),
+    key("l", "home.rules"),
+    key("c", "home.connections"),
+    key("p", "home.performance"),
+    key("d", "home.statistics"),
+    key("H", "home.heatmap"),
+    key("o", "home.logs"),
+    key("u", "home.update"),
+    key("s", "home.settings"),
+    key("r", "home.refresh"),
+];
+
+pub const HOME: &[Binding] = &[key("q", "help.quit"), more("Ctrl+T", "help.theme")];
+
+pub const ROUTES: &[Binding] = &[
+    key("↑↓", "help.navigate"),
+    key("Enter/→", "help.view_nodes"),
+    key("t", "help.test_all").needs(Needs::SpeedTest),
+    key("E", "help.export_tests").needs(Needs::SpeedTest),
+    key("P", "help.primary_group"),
+    key("M", "help.remove_proxy_group"),
+    key("e", "help.edit_layout"),
+    key("x/X", "help.export_group_all"),
+    more("r", "help.refresh"),
+    key("h", "help.home"),
+    key("q/Esc", "help.back"),
+];
+
+pub const ROUTE_NODES: &[Binding] = &[
+    key("↑↓", "help.navigate"),
+    key("Enter", "help.switch"),
+    key("*", "help.favorite"),
+    key("Space", "help.mark"),
+    key("/", "help.search"),
+    key("R", "help.by_region"),
+    key("t", "help.test_all").needs(Needs::SpeedTest),
+    key("a", "help.auto_select").needs(Needs::Selector),
+    key("e", "help.health_check_settings").needs(Needs::HealthCheck),
+    key("u", "help.unpin").needs(Needs::Pinned),
+    key("Esc/q/←", "help.back"),
+    key("h", "help.home"),
+];
+
+pub const ROUTE_MARKS: &[Binding] = &[
+    key("Space", "help.mark_unmark"),
+    key("t", "help.test_marked").needs(Needs::SpeedTest),
+    key("*", "help.add_favorites"),
+    key("G", "help.add_group"),
+    key("Esc", "help.clear_marks"),
+];
+
+pub const ROUTE_LAYOUT: &[Binding] = &[
+    key("↑↓", "help.navigate"),
+    key("K/J", "help.move"),
+    key("Space", "help.hide_show"),
+    key("R", "help.reset"),
+    key("e/Esc", "help.done"),
+];
+
+pub const RULES: &[Binding] = &[
+    key("/", "help.search"),
+    key("↑↓", "help.scroll"),
+    key("r", "help.refresh"),
+    key("s", "help.rule_stats"),
+    key("Enter", "help.inspect_rule_set"),
+    key("e", "help.edit_rules"),
+    key("←→", "help.domain_list"),
+    key("v", "help.domain_lists_view"),
+    key("i", "help.import"),
+    key("x", "help.export"),
+    more("h", "help.home"),
+    key("q", "help.back"),
+];
+
+pub const RULE_LISTS: &[Binding] = &[
+    key("↑↓", "help.select"),
+    key("←→", "help.domain_list"),
+    key("w", "help.add_whitelist"),
+    key("b", "help.add_blacklist"),
+    key("d", "help.delete"),
+    key("i", "help.import"),
+    key("x", "help.export"),
+    key("v", "help.all_rules_view"),
+    more("h", "help.home"),
+    key("q", "help.back"),
+];
+
+pub const RULE_REORDER: &[Binding] = &[
+    key("↑↓", "help.select"),
+    key("K/J", "help.move"),
+    key("d", "help.delete"),
+    key("Enter", "help.preview"),
+    key("Esc", "help.cancel"),
+];
+
+pub const RULE_SET: &[Binding] = &[
+    key("/", "help.search"),
+    key("↑↓", "help.scroll"),
+    key("Esc", "help.back"),
+];
+
+pub const UPDATE: &[Binding] = &[
+    key("↑↓", "help.select"),
+    key("Enter", "help.update_selected"),
+    key("s", "help.set_current"),
+    key("S", "help.test_and_set"),
+    key("u", "help.update_all"),
+    key("v", "help.view_nodes"),
+    key("i", "help.auto_update_interval"),
+    more("d", "help.download_policy"),
+    key("T", "help.template"),
+    key("I", "help.import_urls"),
+    more("P", "help.add_provider"),
+    key("D", "help.find_duplicates"),
+    key("z", "help.rollback"),
+    key("r", "help.refresh"),
+    more("l", "home.rules"),
+    more("h", "help.home"),
+    key("q", "help.back"),
+];
+
+pub const UPDATE_NODES: &[Binding] = &[
+    key("↑↓", "help.select"),
+    key("t", "help.test_node"),
+    key("c", "help.health_check"),
+    key("r", "help.reload"),
+    key("Esc", "help.back"),
+];
+
+pub const UPDATE_DUPLICATES: &[Binding] = &[
+    key("↑↓", "help.scroll"),
+    key("x", "help.exclude_duplicates"),
+    key("m", "help.merge_profile"),
+    key("Esc", "help.back"),
+];
+
+pub const CONNECTIONS: &[Binding] = &[
+    key("/", "help.search"),
+    key("↑↓", "help.navigate"),
+    key("d", "help.close_connection"),
+    key("a", "help.close_all"),
+    key("p", "help.process_filter"),
+    key("P", "help.process_rule"),
+    key("b", "help.bypass_direct"),
+    key("n/v", "help.kind_filter"),
+    key("g", "help.group_by_rule"),
+    more("R", "help.show_rule"),
+    key("E", "help.export"),
+    key("r", "help.refresh"),
+    more("Space", "help.pause_refresh"),
+    more("i", "help.refresh_interval"),
+    key("h", "help.home"),
+    key("q", "help.back"),
+];
+
+pub const CONNECTION_RULE: &[Binding] = &[
+    key("↑↓", "help.navigate"),
+    key("Enter", "help.add_rule"),
+    key("Esc", "help.cancel"),
+];
+
+pub const SETTINGS: &[Binding] = &[
+    key("e", "help.export"),
+    key("i", "help.import"),
+    more("a", "help.api_url"),
+    more("k", "help.secret"),
+    more("c", "help.test_connection"),
+    more("t", "help.test_before_switch"),
+    key("s/x/r", "help.core_service"),
+    key("u", "help.upgrade_core"),
+    key("w", "help.dashboard"),
+    key("l", "help.language"),
+    key("p", "help.keychain"),
+    key("o", "help.inbound_ports"),
+    key("b", "help.snapshots"),
+    key("v", "help.view_clash_config"),
+    key("1-3", "help.notifications"),
+    key("4/5", "help.alert_thresholds"),
+    more("d", "help.download_policy"),
+    key("h", "help.home"),
+    key("q", "help.back"),
+];
+
+pub const SETTINGS_PORTS: &[Binding] = &[
+    key("↑↓", "help.navigate"),
+    key("Enter", "help.edit_value"),
+    key("s", "help.apply"),
+    key("r", "help.reset"),
+    key("Esc", "help.back"),
+];
+
+pub const SETTINGS_SNAPSHOTS: &[Binding] = &[
+    key("↑↓", "help.select"),
+    key("n", "help.new_snapshot"),
+    key("Enter", "help.restore"),
+    key("x", "help.delete"),
+    key("Esc", "help.back"),
+];
+
+pub const SETTINGS_CONFIG_VIEWER: &[Binding] = &[
+    key("↑↓", "help.scroll"),
+    key("e", "help.edit_in_editor"),
+    key("r", "help.reload"),
+    key("Esc", "help.back"),
+];
+
+pub const SETTINGS_DASHBOARD: &[Binding] = &[
+    key("↑↓", "help.select"),
+    key("y", "help.install_update"),
+    key("b", "help.open_browser"),
+    key("Esc", "help.cancel"),
+];
+
+pub const LOGS: &[Binding] = &[
+    key("↑↓", "help.select"),
+    key("Enter", "help.show_connection"),
+    key("f", "help.change_filter"),
+    key("/", "help.search"),
+    key("H", "help.log_history"),
+    key("r", "help.reconnect"),
+    key("L", "help.core_log_level"),
+    key("q/ESC", "help.back"),
+];
+
+pub const PERFORMANCE: &[Binding] = &[
+    key("r", "help.refresh"),
+    key("c", "help.connections"),
+    key("g", "help.exit_grouping"),
+    key("q/ESC", "help.back"),
+];
+
+pub const STATISTICS: &[Binding] = &[key("r", "help.refresh"), key("q/ESC", "help.back")];
+
+pub const FAVORITES: &[Binding] = &[
+    key("↑↓", "help.navigate"),
+    key("Enter", "help.apply"),
+    key("g", "help.pick_group"),
+    key("x", "help.remove"),
+    key("t", "help.test_all").needs(Needs::SpeedTest),
+    more("h", "help.home"),
+    key("q/ESC", "help.back"),
+];
+
+pub const FAVORITE_GROUPS: &[Binding] = &[
+    key("↑↓", "help.navigate"),
+    key("Enter", "help.apply"),
+    key("D", "help.set_default"),
+    key("Esc", "help.cancel"),
+];
+
+pub const GROUPS: &[Binding] = &[
+    key("n", "help.new_group"),
+    key("Enter", "help.view_nodes"),
+    key("d", "help.delete"),
+    more("h", "help.home"),
+    key("q/ESC", "help.back"),
+];
+
+pub const GROUP_NEW: &[Binding] = &[key("Enter", "help.create"), key("ESC", "help.cancel")];
+
+pub const GROUP_VIEW: &[Binding] = &[
+    key("a", "help.add_nodes"),
+    key("d", "help.remove"),
+    key("ESC", "help.back"),
+];
+
+pub const GROUP_NODES: &[Binding] = &[key("Space", "help.toggle"), key("Enter/ESC", "help.done")];
+
+pub const HEATMAP: &[Binding] = &[
+    key("↑↓", "help.scroll"),
+    key("v", "help.switch_view"),
+    key("r", "help.refresh"),
+    key("q/ESC", "help.back"),
+];
+
+/// Every mode of `page`, its main one first
+pub fn sections(page: Page) -> &'static [Section] {
+    match page {
+        Page::Home => &[
+            Section {
+                title: "Home",
+                bindings: HOME,
+            },
+            Section {
+                title: "Quick Actions",
+                bindings: HOME_ACTIONS,
+            },
+        ],
+        Page::Routes => &[
+            Section {
+                title: "Routes",
+                bindings: ROUTES,
+            },
+            Section {
+                title: "Nodes",
+                bindings: ROUTE_NODES,
+            },
+            Section {
+                title: "Marked Nodes",
+                bindings: ROUTE_MARKS,
+            },
+            Section {
+                title: "Edit Layout",
+                bindings: ROUTE_LAYOUT,
+            },
+        ],
+        Page::Rules => &[
+            Section {
+                title: "Rules",
+                bindings: RULES,
+            },
+            Section {
+                title: "Whitelist / Blacklist",
+                bindings: RULE_LISTS,
+            },
+            Section {
+                title: "Reorder Rules",
+                bindings: RULE_REORDER,
+            },
+            Section {
+                title: "Rule-Set",
+                bindings: RULE_SET,
+            },
+        ],
+        Page::Update => &[
+            Section {
+                title: "Update",
+                bindings: UPDATE,
+            },
+            Section {
+                title: "Subscription Nodes",
+                bindings: UPDATE_NODES,
+            },
+            Section {
+                title: "Duplicates",
+                bindings: UPDATE_DUPLICATES,
+            },
+        ],
+        Page::Connections => &[
+            Section {
+                title: "Connections",
+                bindings: CONNECTIONS,
+            },
+            Section {
+                title: "Route App",
+                bindings: CONNECTION_RULE,
+            },
+        ],
+        Page::Settings => &[
+            Section {
+                title: "Settings",
+                bindings: SETTINGS,
+            },
+            Section {
+                title: "Inbound Ports",
+                bindings: SETTINGS_PORTS,
+            },
+            Section {
+                title: "Config Snapshots",
+                bindings: SETTINGS_SNAPSHOTS,
+            },
+            Section {
+                title: "Clash Config",
+                bindings: SETTINGS_CONFIG_VIEWER,
+            },
+            Section {
+                title: "Web Dashboard",
+                bindings: SETTINGS_DASHBOARD,
+            },
+        ],
+        Page::Logs => &[Section {
+            title: "Logs",
+            bindings: LOGS,
+        }],
+        Page::Performance => &[Section {
+            title: "Performance",
+            bindings: PERFORMANCE,
+        }],
+        Page::Statistics => &[Section {
+            title: "Statistics",
+            bindings: STATISTICS,
+        }],
+        Page::Favorites => &[
+            Section {
+                title: "Favorites",
+                bindings: FAVORITES,
+            },
+            Section {
+                title: "Pick Group",
+                bindings: FAVORITE_GROUPS,
+            },
+        ],
+        Page::Groups => &[
+            Section {
+                title: "Node Groups",
+                bindings: GROUPS,
+            },
+            Section {
+                title: "New Group",
+                bindings: GROUP_NEW,
+            },
+            Section {
+                title: "Group",
+                bindings: GROUP_VIEW,
+            },
+            Section {
+                title: "Select Nodes",
+                bindings: GROUP_NODES,
+            },
+        ],
+        Page::Heatmap => &[Section {
+            title: "Latency Heatmap",
+            bindings: HEATMAP,
+        }],
+    }
+}
+
+/// `key_hints` pairs of the help-bar bindings whose needs `allows` accepts
+pub fn bar(
+    bindings: &[Binding],
+    allows: impl Fn(Needs) -> bool,
+) -> Vec<(&'static str, &'static str)> {
+    bindings
+        .iter()
+        .filter(|binding| {
+            binding.in_bar && (binding.needs == Needs::Nothing || allows(binding.needs))
+        })
+        .map(|binding| (binding.keys, binding.label))
+        .collect()
+}
+
+/// The overlay's lines: every section of `page`, then the global keys
+pub fn help_lines(page: Page, preset: &Preset) -> Vec<Line<'static>> {
+    let global = Section {
+        title: "Everywhere",
+        bindings: GLOBAL,
+    };
+    let mut lines = Vec::new();
+    for section in sections(page).iter().chain([&global]) {
+        if !lines.is_empty() {
+            lines.push(Line::from(""));
+        }
+        lines.push(Line::from(Span::styled(
+            section.title,
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )));
+        for binding in section.bindings {
+            if !binding.needs.met_by(preset) {
+                continue;
+            }
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {:>10}  ", binding.keys),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::raw(t(binding.label)),
+            ]));
+        }
+    }
+    lines
+}
+
+/// Key bindings of the current page over the whole screen
+pub fn render_key_help(f: &mut Frame, area: Rect, page: Page, preset: &Preset, scroll: usize) {
+    let width = area.width.saturating_sub(8).min(70);
+    let height = area.height.saturating_sub(4);
+    let dialog_area = Rect {
+        x: area.x + (area.width.saturating_sub(width)) / 2,
+        y: area.y + (area.height.saturating_sub(height)) / 2,
+        width,
+        height,
+    };
+    f.render_widget(Clear, dialog_area);
+
+    let lines: Vec<Line> = help_lines(page, preset).into_iter().skip(scroll).collect();
+    let dialog = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(t("help.key_bindings"))
+            .title_bottom(Line::from(" ↑↓ Scroll  Esc Close ").centered()),
+    );
+    f.render_widget(dialog, dialog_area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bar_filters_by_needs() {
+        let hints = bar(ROUTE_NODES, |needs| needs == Needs::SpeedTest);
+        assert!(hints.contains(&("t", "help.test_all")));
+        assert!(!hints.iter().any(|(keys, _)| *keys == "a" || *keys == "e"));
+        assert!(!bar(RULES, |_| true).contains(&("h", "help.home")));
+
+        // Every label the keymap shows has a translation
+        for page in Page::ALL {
+            for section in sections(page) {
+                for binding in section.bindings.iter().chain(GLOBAL) {
+                    assert_ne!(t(binding.label), binding.label, "{}", binding.label);
+                }
+            }
+        }
+    }
+}
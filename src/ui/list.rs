@@ -0,0 +1,71 @@
+use crossterm::event::KeyCode;
+use ratatui::layout::Rect;
+use std::ops::Range;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Height of the list drawn last, used as the PageUp/PageDown step
+static PAGE_SIZE: AtomicUsize = AtomicUsize::new(10);
+
+/// Rows that fit inside a bordered list drawn in `area`
+/// Remembered as the page size for PageUp/PageDown
+pub fn viewport(area: Rect, rows_per_item: u16) -> usize {
+    let rows = (area.height.saturating_sub(2) / rows_per_item.max(1)).max(1) as usize;
+    PAGE_SIZE.store(rows, Ordering::Relaxed);
+    rows
+}
+
+/// Items moved by PageUp/PageDown
+pub fn page_size() -> usize {
+    PAGE_SIZE.load(Ordering::Relaxed)
+}
+
+/// Scroll offset that keeps `selected` on screen, moving as little as possible from `offset`
+pub fn follow(selected: usize, offset: usize, rows: usize) -> usize {
+    if selected < offset {
+        selected
+    } else if selected >= offset + rows {
+        selected + 1 - rows
+    } else {
+        offset
+    }
+}
+
+/// Items of a `len`-item list to build for a window starting at `offset`
+/// The offset is clamped so the last page stays full
+pub fn window(len: usize, offset: usize, rows: usize) -> Range<usize> {
+    let start = offset.min(len.saturating_sub(rows));
+    start..(start + rows).min(len)
+}
+
+/// Index after a PageUp/PageDown/Home/End key, None for any other key
+pub fn jump(code: KeyCode, index: usize, len: usize) -> Option<usize> {
+    let last = len.saturating_sub(1);
+    match code {
+        KeyCode::PageUp => Some(index.saturating_sub(page_size())),
+        KeyCode::PageDown => Some((index + page_size()).min(last)),
+        KeyCode::Home => Some(0),
+        KeyCode::End => Some(last),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_follows_selection() {
+        assert_eq!(window(10_000, 0, 20), 0..20);
+        // The last page stays full
+        assert_eq!(window(10_000, 9_995, 20), 9_980..10_000);
+        assert_eq!(window(5, 3, 20), 0..5);
+
+        assert_eq!(follow(25, 0, 20), 6);
+        assert_eq!(follow(10, 6, 20), 6);
+        assert_eq!(follow(3, 6, 20), 3);
+
+        assert_eq!(jump(KeyCode::End, 0, 10_000), Some(9_999));
+        assert_eq!(jump(KeyCode::Home, 500, 10_000), Some(0));
+        assert_eq!(jump(KeyCode::Char('x'), 500, 10_000), None);
+    }
+}
@@ -0,0 +1,146 @@
+use ratatui::style::Color;
+use regex::Regex;
+
+use crate::clash::LogEntry;
+use crate::config::LogRulesConfig;
+
+/// Levels a highlight rule may re-classify a line to
+const LEVELS: [&str; 4] = ["debug", "info", "warning", "error"];
+
+struct Highlight {
+    pattern: Regex,
+    color: Option<Color>,
+    level: Option<&'static str>,
+}
+
+/// Compiled `log_rules` from the config
+#[derive(Default)]
+pub struct LogRules {
+    highlights: Vec<Highlight>,
+    mute: Vec<Regex>,
+}
+
+impl LogRules {
+    /// Compile the configured rules, returning a description of each one skipped as invalid
+    pub fn compile(config: &LogRulesConfig) -> (Self, Vec<String>) {
+        let mut rules = Self::default();
+        let mut problems = Vec::new();
+        for rule in &config.highlight {
+            let pattern = match Regex::new(&rule.pattern) {
+                Ok(pattern) => pattern,
+                Err(e) => {
+                    problems.push(format!("highlight '{}': {}", rule.pattern, e));
+                    continue;
+                }
+            };
+            let color = match rule.color.as_deref().map(str::parse::<Color>) {
+                Some(Err(_)) => {
+                    problems.push(format!(
+                        "highlight '{}': unknown color '{}'",
+                        rule.pattern,
+                        rule.color.as_deref().unwrap_or_default()
+                    ));
+                    continue;
+                }
+                color => color.and_then(Result::ok),
+            };
+            let level = match rule.level.as_deref() {
+                Some(level) => match LEVELS
+                    .iter()
+                    .find(|known| known.eq_ignore_ascii_case(level))
+                {
+                    Some(known) => Some(*known),
+                    None => {
+                        problems.push(format!(
+                            "highlight '{}': unknown level '{}'",
+                            rule.pattern, level
+                        ));
+                        continue;
+                    }
+                },
+                None => None,
+            };
+            rules.highlights.push(Highlight {
+                pattern,
+                color,
+                level,
+            });
+        }
+        for pattern in &config.mute {
+            match Regex::new(pattern) {
+                Ok(pattern) => rules.mute.push(pattern),
+                Err(e) => problems.push(format!("mute '{}': {}", pattern, e)),
+            }
+        }
+        (rules, problems)
+    }
+
+    /// Re-classify a new line; false when it is muted and should be dropped
+    pub fn apply(&self, entry: &mut LogEntry) -> bool {
+        if self.mute.iter().any(|mute| mute.is_match(&entry.message)) {
+            return false;
+        }
+        if let Some(level) = self.highlight(entry).and_then(|highlight| highlight.level) {
+            entry.level = level.to_string();
+        }
+        true
+    }
+
+    /// Color of the first highlight rule matching the line
+    pub fn color(&self, entry: &LogEntry) -> Option<Color> {
+        self.highlight(entry).and_then(|highlight| highlight.color)
+    }
+
+    fn highlight(&self, entry: &LogEntry) -> Option<&Highlight> {
+        self.highlights
+            .iter()
+            .find(|highlight| highlight.pattern.is_match(&entry.message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::LogHighlight;
+
+    #[test]
+    fn test_rules_mute_reclassify_and_color() {
+        let config = LogRulesConfig {
+            highlight: vec![
+                LogHighlight {
+                    pattern: r"office\.example\.com".to_string(),
+                    color: Some("magenta".to_string()),
+                    level: None,
+                },
+                LogHighlight {
+                    pattern: "dns resolve failed".to_string(),
+                    color: None,
+                    level: Some("Debug".to_string()),
+                },
+                LogHighlight {
+                    pattern: "(".to_string(),
+                    ..Default::default()
+                },
+            ],
+            mute: vec!["^\\[UDP\\]".to_string()],
+        };
+        let (rules, problems) = LogRules::compile(&config);
+        assert_eq!(problems.len(), 1);
+
+        let entry = |message: &str| {
+            LogEntry::new(
+                "10:00:00".to_string(),
+                "warning".to_string(),
+                message.to_string(),
+            )
+        };
+        let mut noisy = entry("dns resolve failed: lookup example.org");
+        assert!(rules.apply(&mut noisy));
+        assert_eq!(noisy.level, "debug");
+        assert!(!rules.apply(&mut entry("[UDP] 1.2.3.4:53 --> 8.8.8.8:53")));
+
+        let office = entry("[TCP] dial to office.example.com:443");
+        assert_eq!(rules.color(&office), Some(Color::Magenta));
+        assert_eq!(rules.color(&noisy), None);
+    }
+}
@@ -6,35 +6,308 @@ use ratatui::{
     Frame,
 };
 
+use std::collections::{HashMap, HashSet};
+
+use crate::app::connection_breakdown::ConnectionBreakdown;
 use crate::app::AppState;
-use crate::clash::{Connection, ConnectionsResponse};
+use crate::clash::{
+    Connection, ConnectionMetadata, ConnectionsResponse, HumanRoute, IpFamily, ProxyType,
+};
+use crate::config::{AlertConfig, AppConfig};
+use crate::ui::i18n::{key_hints, t};
+use crate::ui::keymap;
+use crate::ui::list;
+use crate::ui::search::SearchFilter;
+use crate::ui::theme;
+
+/// Width of the per-connection ↑/↓ rate columns
+const RATE_WIDTH: usize = 11;
+
+/// Per-connection (upload, download) rates in bytes per second, keyed by connection id
+/// Connections missing from the previous snapshot have no rate yet
+pub fn connection_rates(
+    previous: &ConnectionsResponse,
+    current: &ConnectionsResponse,
+    rate: impl Fn(u64, u64) -> Option<u64>,
+) -> HashMap<String, (u64, u64)> {
+    let before: HashMap<&str, &Connection> = previous
+        .connections
+        .iter()
+        .map(|conn| (conn.id.as_str(), conn))
+        .collect();
+
+    current
+        .connections
+        .iter()
+        .filter_map(|conn| {
+            let old = before.get(conn.id.as_str())?;
+            Some((
+                conn.id.clone(),
+                (
+                    rate(conn.upload, old.upload)?,
+                    rate(conn.download, old.download)?,
+                ),
+            ))
+        })
+        .collect()
+}
+
+/// Connections that appeared or went away in the latest refresh
+#[derive(Debug, Default)]
+pub struct ConnectionChurn {
+    /// Ids of connections the previous refresh didn't have
+    pub opened: HashSet<String>,
+    /// Connections of the previous refresh that are gone, shown greyed until the next one
+    pub closed: Vec<Connection>,
+}
+
+impl ConnectionChurn {
+    pub fn between(previous: &ConnectionsResponse, current: &ConnectionsResponse) -> Self {
+        let before: HashSet<&str> = previous
+            .connections
+            .iter()
+            .map(|conn| conn.id.as_str())
+            .collect();
+        let now: HashSet<&str> = current
+            .connections
+            .iter()
+            .map(|conn| conn.id.as_str())
+            .collect();
+        Self {
+            opened: now.difference(&before).map(|id| id.to_string()).collect(),
+            closed: previous
+                .connections
+                .iter()
+                .filter(|conn| !now.contains(conn.id.as_str()))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+/// How the list refreshes itself
+#[derive(Debug, Clone, Copy)]
+pub struct ConnectionRefresh {
+    pub paused: bool,
+    pub interval_secs: u64,
+}
 
+/// Protocol and address family the list is narrowed to
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct KindFilter {
+    /// Some(true) for UDP only, Some(false) for TCP only
+    pub udp: Option<bool>,
+    pub family: Option<IpFamily>,
+}
+
+impl KindFilter {
+    /// All → TCP → UDP → all
+    pub fn next_network(&mut self) {
+        self.udp = match self.udp {
+            None => Some(false),
+            Some(false) => Some(true),
+            Some(true) => None,
+        };
+    }
+
+    /// All → IPv4 → IPv6 → all
+    pub fn next_family(&mut self) {
+        self.family = match self.family {
+            None => Some(IpFamily::V4),
+            Some(IpFamily::V4) => Some(IpFamily::V6),
+            Some(IpFamily::V6) => None,
+        };
+    }
+
+    pub fn matches(&self, metadata: &ConnectionMetadata) -> bool {
+        self.udp.is_none_or(|udp| metadata.is_udp() == udp)
+            && self
+                .family
+                .is_none_or(|family| metadata.ip_family() == Some(family))
+    }
+
+    /// e.g. "UDP, v6", None when nothing is filtered
+    pub fn describe(&self) -> Option<String> {
+        let parts: Vec<&str> = [
+            self.udp.map(|udp| if udp { "UDP" } else { "TCP" }),
+            self.family.map(IpFamily::label),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        (!parts.is_empty()).then(|| parts.join(", "))
+    }
+}
+
+/// Whether `connection` matches the search and kind filter and, when set, was opened by `process`
+pub fn connection_visible(
+    connection: &Connection,
+    filter: &SearchFilter,
+    process: Option<&str>,
+    kind: KindFilter,
+) -> bool {
+    let name = connection.metadata.process_name();
+    if process.is_some_and(|process| name != Some(process)) || !kind.matches(&connection.metadata) {
+        return false;
+    }
+    let destination = destination(connection);
+    let mut fields = vec![
+        destination.as_str(),
+        connection.metadata.destination_ip.as_str(),
+        connection.metadata.source_ip.as_str(),
+    ];
+    fields.extend(name);
+    fields.extend(connection.chains.iter().map(String::as_str));
+    filter.matches(fields)
+}
+
+/// Connections that matched the same rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleGroup {
+    pub rule_type: String,
+    pub payload: String,
+    /// Where the rule sent them: the last entry of the first connection's chain
+    pub target: String,
+    /// Indexes into the connections, in the core's order
+    pub connections: Vec<usize>,
+    pub upload: u64,
+    pub download: u64,
+}
+
+impl RuleGroup {
+    /// `Type,payload` as the core reports it, or just the type for MATCH
+    pub fn label(&self) -> String {
+        if self.payload.is_empty() {
+            self.rule_type.clone()
+        } else {
+            format!("{},{}", self.rule_type, self.payload)
+        }
+    }
+
+    /// Search that finds the rule on the Rules page
+    pub fn rules_query(&self) -> String {
+        rules_query(&self.rule_type, &self.payload)
+    }
+}
+
+/// Search that finds the rule a connection matched on the Rules page
+pub fn rules_query(rule_type: &str, payload: &str) -> String {
+    format!("{} {}", rule_type, payload).trim().to_string()
+}
+
+/// The `visible` connections grouped by the rule they matched, most connections first
+pub fn group_by_rule(connections: &[Connection], visible: &[usize]) -> Vec<RuleGroup> {
+    let mut groups: Vec<RuleGroup> = Vec::new();
+    for &index in visible {
+        let connection = &connections[index];
+        let payload = connection.rule_payload.as_deref().unwrap_or("");
+        let position = groups
+            .iter()
+            .position(|group| group.rule_type == connection.rule && group.payload == payload)
+            .unwrap_or_else(|| {
+                groups.push(RuleGroup {
+                    rule_type: connection.rule.clone(),
+                    payload: payload.to_string(),
+                    target: connection
+                        .chains
+                        .last()
+                        .cloned()
+                        .unwrap_or_else(|| "DIRECT".to_string()),
+                    connections: Vec::new(),
+                    upload: 0,
+                    download: 0,
+                });
+                groups.len() - 1
+            });
+        let group = &mut groups[position];
+        group.connections.push(index);
+        group.upload += connection.upload;
+        group.download += connection.download;
+    }
+    // Stable, so rules with as many connections keep the order they were first seen in
+    groups.sort_by_key(|group| std::cmp::Reverse(group.connections.len()));
+    groups
+}
+
+/// A line of the list grouped by rule
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RuleRow {
+    /// Index into the groups
+    Group(usize),
+    /// Index into the connections
+    Connection(usize),
+}
+
+/// Rows of the grouped list: every group, followed by its connections when expanded
+pub fn rule_rows(groups: &[RuleGroup], expanded: &HashSet<String>) -> Vec<RuleRow> {
+    let mut rows = Vec::new();
+    for (index, group) in groups.iter().enumerate() {
+        rows.push(RuleRow::Group(index));
+        if expanded.contains(&group.label()) {
+            rows.extend(group.connections.iter().copied().map(RuleRow::Connection));
+        }
+    }
+    rows
+}
+
+/// The list grouped by matched rule: expanded groups (by label) and the selected row
+#[derive(Debug, Clone, Default)]
+pub struct RuleGrouping {
+    pub expanded: HashSet<String>,
+    pub selected: usize,
+    pub scroll_offset: usize,
+}
+
+/// Where a PROCESS-NAME rule can send an app: DIRECT, REJECT, then the selector groups
+pub fn rule_targets(state: &AppState, config: &AppConfig) -> Vec<String> {
+    let mut targets = vec!["DIRECT".to_string(), "REJECT".to_string()];
+    targets.extend(
+        config
+            .arrange_routes(
+                HumanRoute::from_proxies(&state.clash_state.proxies, state.mode),
+                true,
+            )
+            .into_iter()
+            .filter(|route| route.proxy_type == ProxyType::Selector && route.name != "GLOBAL")
+            .map(|route| route.name),
+    );
+    targets
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn render(
     f: &mut Frame,
     area: Rect,
-    _state: &AppState,
+    state: &AppState,
+    config: &AppConfig,
     connections: Option<&ConnectionsResponse>,
+    rates: &HashMap<String, (u64, u64)>,
+    download_rate: u64,
     selected_index: usize,
     scroll_offset: usize,
     search_query: &str,
     search_mode: bool,
+    process_filter: Option<&str>,
+    kind: KindFilter,
+    rule_picker: Option<(&str, usize)>,
+    churn: &ConnectionChurn,
+    refresh: ConnectionRefresh,
+    by_rule: Option<&RuleGrouping>,
 ) {
-    let constraints = if search_mode {
-        vec![
-            Constraint::Length(3), // Title
-            Constraint::Length(3), // Stats
-            Constraint::Length(3), // Search input
-            Constraint::Min(0),    // Connection list
-            Constraint::Length(5), // Help
-        ]
-    } else {
-        vec![
-            Constraint::Length(3), // Title
-            Constraint::Length(3), // Stats
-            Constraint::Min(0),    // Connection list
-            Constraint::Length(5), // Help
-        ]
-    };
+    let mut constraints = vec![
+        Constraint::Length(3),                                    // Title
+        Constraint::Length(if theme::compact() { 3 } else { 4 }), // Stats
+    ];
+    if state.status_message.is_some() {
+        constraints.push(Constraint::Length(3)); // Status message
+    }
+    if search_mode {
+        constraints.push(Constraint::Length(3)); // Search input
+    }
+    constraints.extend([
+        Constraint::Min(0),                       // Connection list
+        Constraint::Length(theme::help_height()), // Help
+    ]);
 
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -45,29 +318,63 @@ pub fn render(
     render_title(f, chunks[chunk_idx]);
     chunk_idx += 1;
 
-    render_stats(f, chunks[chunk_idx], connections);
+    render_stats(
+        f,
+        chunks[chunk_idx],
+        connections,
+        &config.alerts,
+        download_rate,
+    );
     chunk_idx += 1;
 
+    if let Some(msg) = &state.status_message {
+        render_status(f, chunks[chunk_idx], msg);
+        chunk_idx += 1;
+    }
+
     if search_mode {
         render_search_input(f, chunks[chunk_idx], search_query);
         chunk_idx += 1;
     }
 
+    let list_area = match rule_picker {
+        Some((process, target_index)) => {
+            let body = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+                .split(chunks[chunk_idx]);
+            render_rule_picker(
+                f,
+                body[1],
+                &rule_targets(state, config),
+                process,
+                target_index,
+            );
+            body[0]
+        }
+        None => chunks[chunk_idx],
+    };
     render_connections(
         f,
-        chunks[chunk_idx],
+        list_area,
         connections,
+        rates,
         selected_index,
         scroll_offset,
         search_query,
+        process_filter,
+        kind,
+        churn,
+        refresh,
+        by_rule,
     );
     chunk_idx += 1;
 
-    render_help(f, chunks[chunk_idx], search_mode);
+    render_help(f, chunks[chunk_idx], search_mode, rule_picker.is_some());
 }
 
 fn render_title(f: &mut Frame, area: Rect) {
-    let title = Paragraph::new("Active Connections")
+    let title = Paragraph::new(t("title.connections"))
         .style(
             Style::default()
                 .fg(Color::Cyan)
@@ -78,7 +385,14 @@ fn render_title(f: &mut Frame, area: Rect) {
     f.render_widget(title, area);
 }
 
-fn render_stats(f: &mut Frame, area: Rect, connections: Option<&ConnectionsResponse>) {
+/// Totals line; counts and rates over an alert threshold are shown in red
+fn render_stats(
+    f: &mut Frame,
+    area: Rect,
+    connections: Option<&ConnectionsResponse>,
+    alerts: &AlertConfig,
+    download_rate: u64,
+) {
     let (count, upload, download) = if let Some(conn) = connections {
         (
             conn.connections.len(),
@@ -89,27 +403,100 @@ fn render_stats(f: &mut Frame, area: Rect, connections: Option<&ConnectionsRespo
         (0, "0 B".to_string(), "0 B".to_string())
     };
 
-    let stats = Line::from(vec![
+    let count_color = if alerts.connections_exceeded(count) {
+        Color::Red
+    } else {
+        Color::Green
+    };
+    let mut spans = vec![
         Span::raw("Total: "),
         Span::styled(
             format!("{}", count),
             Style::default()
-                .fg(Color::Green)
+                .fg(count_color)
                 .add_modifier(Modifier::BOLD),
         ),
         Span::raw("  |  Upload: "),
         Span::styled(upload, Style::default().fg(Color::Yellow)),
         Span::raw("  |  Download: "),
         Span::styled(download, Style::default().fg(Color::Cyan)),
-    ]);
+    ];
+    if alerts.download_exceeded(download_rate) {
+        spans.push(Span::styled(
+            format!("  ⚠ ↓ {}", format_rate(download_rate)),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        ));
+    }
+    if alerts.connections_exceeded(count) {
+        spans.push(Span::styled(
+            "  ⚠ too many connections",
+            Style::default().fg(Color::Red),
+        ));
+    }
+    let mut lines = vec![Line::from(spans)];
+    if !theme::compact() {
+        let breakdown = connections
+            .map(|conn| ConnectionBreakdown::of(&conn.connections))
+            .unwrap_or_default();
+        lines.push(breakdown_line(&breakdown));
+    }
 
-    let widget = Paragraph::new(stats)
-        .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL).title("Statistics"));
+    let widget = Paragraph::new(lines).alignment(Alignment::Center).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(t("block.connection_stats")),
+    );
 
     f.render_widget(widget, area);
 }
 
+/// TCP/UDP, exit and DNS mode counts, and the busiest chain
+fn breakdown_line(breakdown: &ConnectionBreakdown) -> Line<'static> {
+    let dim = Style::default().fg(Color::DarkGray);
+    let count = |n: usize, color: Color| Span::styled(n.to_string(), Style::default().fg(color));
+    let mut spans = vec![
+        Span::raw("TCP "),
+        count(breakdown.tcp, Color::White),
+        Span::styled(" / ", dim),
+        Span::raw("UDP "),
+        count(breakdown.udp, Color::Magenta),
+        Span::raw("  |  Direct "),
+        count(breakdown.direct, Color::Green),
+        Span::styled(" / ", dim),
+        Span::raw("Proxy "),
+        count(breakdown.proxied, Color::Cyan),
+    ];
+    if breakdown.rejected > 0 {
+        spans.push(Span::styled(" / ", dim));
+        spans.push(Span::raw("Reject "));
+        spans.push(count(breakdown.rejected, Color::Red));
+    }
+    spans.extend([
+        Span::raw("  |  fake-ip "),
+        count(breakdown.fake_ip, Color::Yellow),
+        Span::styled(" / ", dim),
+        Span::raw("real-ip "),
+        count(breakdown.real_ip, Color::Yellow),
+    ]);
+    if let Some((chain, n)) = &breakdown.top_chain {
+        spans.push(Span::raw("  |  Top: "));
+        spans.push(Span::styled(
+            chain.clone(),
+            Style::default().fg(Color::Cyan),
+        ));
+        spans.push(Span::styled(format!(" ({})", n), dim));
+    }
+    Line::from(spans)
+}
+
+fn render_status(f: &mut Frame, area: Rect, msg: &str) {
+    let status = Paragraph::new(msg)
+        .style(Style::default().fg(Color::Yellow))
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(status, area);
+}
+
 fn render_search_input(f: &mut Frame, area: Rect, search_query: &str) {
     let search_text = if search_query.is_empty() {
         Line::from(vec![
@@ -129,20 +516,28 @@ fn render_search_input(f: &mut Frame, area: Rect, search_query: &str) {
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Search (Host/IP/Chain)"),
+                .title(t("block.search_connections")),
         );
 
     f.render_widget(search_widget, area);
 }
 
+#[allow(clippy::too_many_arguments)]
 fn render_connections(
     f: &mut Frame,
     area: Rect,
     connections: Option<&ConnectionsResponse>,
+    rates: &HashMap<String, (u64, u64)>,
     selected_index: usize,
     scroll_offset: usize,
     search_query: &str,
+    process_filter: Option<&str>,
+    kind: KindFilter,
+    churn: &ConnectionChurn,
+    refresh: ConnectionRefresh,
+    by_rule: Option<&RuleGrouping>,
 ) {
+    let filter = SearchFilter::new(search_query);
     let items: Vec<ListItem> = if let Some(conn) = connections {
         if conn.connections.is_empty() {
             vec![ListItem::new(Line::from(vec![Span::styled(
@@ -150,68 +545,87 @@ fn render_connections(
                 Style::default().fg(Color::Gray),
             )]))]
         } else {
-            // Filter connections based on search query
-            let filtered: Vec<(usize, &Connection)> = if search_query.is_empty() {
-                conn.connections.iter().enumerate().collect()
-            } else {
-                conn.connections
-                    .iter()
-                    .enumerate()
-                    .filter(|(_, connection)| {
-                        let query_lower = search_query.to_lowercase();
+            // Fuzzy filter, keeping the core's order so the selection index stays valid
+            let filtered: Vec<(usize, &Connection)> = conn
+                .connections
+                .iter()
+                .enumerate()
+                .filter(|(_, connection)| {
+                    connection_visible(connection, &filter, process_filter, kind)
+                })
+                .collect();
 
-                        // Search in destination host
-                        if let Some(host) = &connection.metadata.host {
-                            if host.to_lowercase().contains(&query_lower) {
-                                return true;
-                            }
-                        }
-
-                        // Search in destination IP
-                        if connection
-                            .metadata
-                            .destination_ip
-                            .to_lowercase()
-                            .contains(&query_lower)
-                        {
-                            return true;
+            if filtered.is_empty() {
+                vec![ListItem::new(Line::from(vec![Span::styled(
+                    match (process_filter, kind.describe()) {
+                        (_, Some(kind)) if search_query.is_empty() => {
+                            format!("No {} connections", kind)
                         }
-
-                        // Search in source IP
-                        if connection
-                            .metadata
-                            .source_ip
-                            .to_lowercase()
-                            .contains(&query_lower)
-                        {
-                            return true;
+                        (Some(process), _) if search_query.is_empty() => {
+                            format!("No connections from {}", process)
                         }
-
-                        // Search in chains
-                        for chain in &connection.chains {
-                            if chain.to_lowercase().contains(&query_lower) {
-                                return true;
+                        _ => format!("No connections matching '{}'", search_query),
+                    },
+                    Style::default().fg(Color::Yellow),
+                )]))]
+            } else if let Some(grouping) = by_rule {
+                let visible: Vec<usize> = filtered.iter().map(|(idx, _)| *idx).collect();
+                let groups = group_by_rule(&conn.connections, &visible);
+                let rows = rule_rows(&groups, &grouping.expanded);
+                // Sized for connection rows, so the selection stays on screen
+                let height = list::viewport(area, if theme::compact() { 1 } else { 2 });
+                let range = list::window(rows.len(), grouping.scroll_offset, height);
+                let start = range.start;
+                rows[range]
+                    .iter()
+                    .enumerate()
+                    .map(|(offset, row)| {
+                        let is_selected = start + offset == grouping.selected;
+                        match *row {
+                            RuleRow::Group(index) => {
+                                let group = &groups[index];
+                                let expanded = grouping.expanded.contains(&group.label());
+                                render_rule_group(
+                                    group,
+                                    &conn.connections,
+                                    rates,
+                                    expanded,
+                                    is_selected,
+                                )
+                            }
+                            RuleRow::Connection(index) => {
+                                let connection = &conn.connections[index];
+                                render_connection_item(
+                                    connection,
+                                    rates.get(&connection.id).copied(),
+                                    is_selected,
+                                    &filter,
+                                    RowMark::Live,
+                                )
                             }
                         }
-
-                        false
                     })
-                    .collect()
-            };
-
-            if filtered.is_empty() {
-                vec![ListItem::new(Line::from(vec![Span::styled(
-                    format!("No connections matching '{}'", search_query),
-                    Style::default().fg(Color::Yellow),
-                )]))]
+                    .collect::<Vec<_>>()
             } else {
-                filtered
+                // Compact rows take one line, full rows two
+                let rows = list::viewport(area, if theme::compact() { 1 } else { 2 });
+                filtered[list::window(filtered.len(), scroll_offset, rows)]
                     .iter()
-                    .skip(scroll_offset)
                     .map(|(idx, connection)| {
-                        render_connection_item(connection, *idx == selected_index)
+                        let mark = if churn.opened.contains(&connection.id) {
+                            RowMark::Opened
+                        } else {
+                            RowMark::Live
+                        };
+                        render_connection_item(
+                            connection,
+                            rates.get(&connection.id).copied(),
+                            *idx == selected_index,
+                            &filter,
+                            mark,
+                        )
                     })
-                    .collect()
+                    .collect::<Vec<_>>()
             }
         }
     } else {
@@ -221,45 +635,160 @@ fn render_connections(
         )]))]
     };
 
-    let title = if search_query.is_empty() {
-        format!("Connections (offset: {})", scroll_offset)
+    // Connections gone since the last refresh stay below the live ones for a cycle
+    let mut items = items;
+    let closed: Vec<&Connection> = churn
+        .closed
+        .iter()
+        .filter(|_| by_rule.is_none())
+        .filter(|connection| connection_visible(connection, &filter, process_filter, kind))
+        .collect();
+    items.extend(closed.iter().map(|connection| {
+        render_connection_item(connection, None, false, &filter, RowMark::Closed)
+    }));
+
+    let mut filters = Vec::new();
+    if refresh.paused {
+        filters.push("PAUSED".to_string());
     } else {
-        format!(
-            "Connections (filtered: '{}', offset: {})",
-            search_query, scroll_offset
-        )
-    };
+        filters.push(format!("every {}s", refresh.interval_secs));
+    }
+    if !churn.opened.is_empty() || !closed.is_empty() {
+        filters.push(format!(
+            "+{} new, -{} closed",
+            churn.opened.len(),
+            closed.len()
+        ));
+    }
+    if by_rule.is_some() {
+        filters.push("by rule".to_string());
+    }
+    if !search_query.is_empty() {
+        filters.push(format!("filtered: '{}'", search_query));
+    }
+    if let Some(process) = process_filter {
+        filters.push(format!("app: {}", process));
+    }
+    if let Some(kind) = kind.describe() {
+        filters.push(kind);
+    }
+    filters.push(format!(
+        "offset: {}",
+        by_rule.map_or(scroll_offset, |grouping| grouping.scroll_offset)
+    ));
+    let title = format!("Connections ({})", filters.join(", "));
 
     let list = List::new(items).block(Block::default().borders(Borders::ALL).title(title));
 
     f.render_widget(list, area);
 }
 
-fn render_connection_item(connection: &Connection, is_selected: bool) -> ListItem<'_> {
+/// Header of a rule's connections: the rule, its target, how many and their traffic
+fn render_rule_group(
+    group: &RuleGroup,
+    connections: &[Connection],
+    rates: &HashMap<String, (u64, u64)>,
+    expanded: bool,
+    is_selected: bool,
+) -> ListItem<'static> {
     let style = if is_selected {
         Style::default()
             .fg(Color::Yellow)
             .add_modifier(Modifier::BOLD)
     } else {
+        Style::default().fg(Color::Cyan)
+    };
+    let (up, down) = group
+        .connections
+        .iter()
+        .filter_map(|index| rates.get(&connections[*index].id))
+        .fold((0, 0), |(up, down), rate| (up + rate.0, down + rate.1));
+    ListItem::new(Line::from(vec![
+        Span::styled(if is_selected { "► " } else { "  " }, style),
+        Span::styled(if expanded { "▾ " } else { "▸ " }, style),
+        Span::styled("↑", Style::default().fg(Color::Green)),
+        Span::raw(format!("{:>width$} ", format_rate(up), width = RATE_WIDTH)),
+        Span::styled("↓", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(
+            "{:>width$}  ",
+            format_rate(down),
+            width = RATE_WIDTH
+        )),
+        Span::styled(group.label(), style),
+        Span::raw(" → "),
+        Span::styled(group.target.clone(), Style::default().fg(Color::Magenta)),
+        Span::styled(
+            format!(
+                "  {} conn  ↑ {}  ↓ {}",
+                group.connections.len(),
+                format_bytes(group.upload),
+                format_bytes(group.download)
+            ),
+            Style::default().fg(Color::Gray),
+        ),
+    ]))
+}
+
+/// `host:port`, or `ip:port` when the core saw no host name
+fn destination(connection: &Connection) -> String {
+    let host = connection
+        .metadata
+        .host
+        .as_deref()
+        .filter(|host| !host.is_empty())
+        .unwrap_or(&connection.metadata.destination_ip);
+    format!("{}:{}", host, connection.metadata.destination_port)
+}
+
+/// Whether a row is new since the last refresh, or already closed
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RowMark {
+    Live,
+    Opened,
+    Closed,
+}
+
+fn render_connection_item(
+    connection: &Connection,
+    rate: Option<(u64, u64)>,
+    is_selected: bool,
+    filter: &SearchFilter,
+    mark: RowMark,
+) -> ListItem<'static> {
+    let style = if is_selected {
         Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        match mark {
+            RowMark::Opened => Style::default()
+                .fg(Color::Green)
+                .add_modifier(Modifier::BOLD),
+            _ => Style::default(),
+        }
     };
 
-    let prefix = if is_selected { "► " } else { "  " };
+    let prefix = match mark {
+        _ if is_selected => "► ",
+        RowMark::Opened => "+ ",
+        RowMark::Closed => "✕ ",
+        RowMark::Live => "  ",
+    };
 
     // Format connection details
-    let network = connection.metadata.network.to_uppercase();
+    let network = match connection.metadata.ip_family() {
+        Some(family) => format!(
+            "{} {}",
+            connection.metadata.network.to_uppercase(),
+            family.label()
+        ),
+        None => connection.metadata.network.to_uppercase(),
+    };
     let source = format!(
         "{}:{}",
         connection.metadata.source_ip, connection.metadata.source_port
     );
-    let dest = if let Some(host) = &connection.metadata.host {
-        format!("{}:{}", host, connection.metadata.destination_port)
-    } else {
-        format!(
-            "{}:{}",
-            connection.metadata.destination_ip, connection.metadata.destination_port
-        )
-    };
+    let dest = destination(connection);
 
     let chain = if !connection.chains.is_empty() {
         connection.chains.join(" → ")
@@ -269,19 +798,56 @@ fn render_connection_item(connection: &Connection, is_selected: bool) -> ListIte
 
     let upload_str = format_bytes(connection.upload);
     let download_str = format_bytes(connection.download);
+    let (upload_rate, download_rate) = match rate {
+        Some((up, down)) => (format_rate(up), format_rate(down)),
+        None => ("-".to_string(), "-".to_string()),
+    };
+
+    if theme::compact() {
+        // One line per connection: rates, destination and the node it went out through
+        let node = connection
+            .chains
+            .first()
+            .cloned()
+            .unwrap_or_else(|| "DIRECT".to_string());
+        let mut spans = vec![
+            Span::styled(prefix, style),
+            Span::styled("↑", Style::default().fg(Color::Green)),
+            Span::raw(format!("{:>9} ", upload_rate)),
+            Span::styled("↓", Style::default().fg(Color::Cyan)),
+            Span::raw(format!("{:>9}  ", download_rate)),
+        ];
+        // Only the less common kinds are tagged, to keep the line short
+        if connection.metadata.is_udp() {
+            spans.push(Span::styled("UDP ", Style::default().fg(Color::Cyan)));
+        }
+        if connection.metadata.ip_family() == Some(IpFamily::V6) {
+            spans.push(Span::styled("v6 ", Style::default().fg(Color::Cyan)));
+        }
+        spans.extend(filter.highlight(&dest, Style::default().fg(Color::Yellow)));
+        spans.push(Span::raw(" → "));
+        spans.extend(filter.highlight(&node, Style::default().fg(Color::Magenta)));
+        return row(vec![Line::from(spans)], mark);
+    }
 
-    let line1 = Line::from(vec![
+    let mut line1 = vec![
         Span::styled(prefix, style),
+        Span::styled("↑", Style::default().fg(Color::Green)),
+        Span::raw(format!("{:>width$} ", upload_rate, width = RATE_WIDTH)),
+        Span::styled("↓", Style::default().fg(Color::Cyan)),
+        Span::raw(format!("{:>width$}  ", download_rate, width = RATE_WIDTH)),
         Span::styled(format!("[{}] ", network), Style::default().fg(Color::Cyan)),
-        Span::styled(source, Style::default().fg(Color::Green)),
-        Span::raw(" → "),
-        Span::styled(dest, Style::default().fg(Color::Yellow)),
-    ]);
+    ];
+    line1.extend(filter.highlight(&source, Style::default().fg(Color::Green)));
+    line1.push(Span::raw(" → "));
+    line1.extend(filter.highlight(&dest, Style::default().fg(Color::Yellow)));
 
-    let line2 = Line::from(vec![
+    let mut line2 = vec![
         Span::raw("    "),
         Span::styled("Chain: ", Style::default().fg(Color::Gray)),
-        Span::styled(chain, Style::default().fg(Color::Magenta)),
+    ];
+    line2.extend(filter.highlight(&chain, Style::default().fg(Color::Magenta)));
+    line2.extend([
         Span::raw("  |  "),
         Span::styled("↑ ", Style::default().fg(Color::Green)),
         Span::raw(upload_str),
@@ -289,35 +855,70 @@ fn render_connection_item(connection: &Connection, is_selected: bool) -> ListIte
         Span::styled("↓ ", Style::default().fg(Color::Cyan)),
         Span::raw(download_str),
     ]);
+    if let Some(process) = connection.metadata.process_name() {
+        line2.extend([
+            Span::raw("  |  "),
+            Span::styled("App: ", Style::default().fg(Color::Gray)),
+        ]);
+        line2.extend(filter.highlight(process, Style::default().fg(Color::Blue)));
+    }
 
-    ListItem::new(vec![line1, line2])
+    row(vec![Line::from(line1), Line::from(line2)], mark)
 }
 
-fn render_help(f: &mut Frame, area: Rect, search_mode: bool) {
+/// Closed connections are drawn in grey, whatever their spans' colors
+fn row(mut lines: Vec<Line<'static>>, mark: RowMark) -> ListItem<'static> {
+    if mark == RowMark::Closed {
+        for span in lines.iter_mut().flat_map(|line| line.spans.iter_mut()) {
+            span.style = Style::default().fg(Color::DarkGray);
+        }
+    }
+    ListItem::new(lines)
+}
+
+fn render_rule_picker(
+    f: &mut Frame,
+    area: Rect,
+    targets: &[String],
+    process: &str,
+    target_index: usize,
+) {
+    let items: Vec<ListItem> = targets
+        .iter()
+        .enumerate()
+        .map(|(i, target)| {
+            let (prefix, style) = if i == target_index {
+                (
+                    "▶ ",
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                )
+            } else {
+                ("  ", Style::default().fg(Color::White))
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(prefix, style),
+                Span::styled(target.clone(), style),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Route {} to...", process)),
+    );
+    f.render_widget(list, area);
+}
+
+fn render_help(f: &mut Frame, area: Rect, search_mode: bool, rule_picker: bool) {
     let help_spans = if search_mode {
-        vec![
-            Span::styled("Esc", Style::default().fg(Color::Yellow)),
-            Span::raw(" Exit Search  "),
-            Span::styled("Enter", Style::default().fg(Color::Yellow)),
-            Span::raw(" Apply Filter"),
-        ]
+        key_hints(&[("Esc", "help.exit_search"), ("Enter", "help.apply_filter")])
+    } else if rule_picker {
+        key_hints(&keymap::bar(keymap::CONNECTION_RULE, |_| true))
     } else {
-        vec![
-            Span::styled("/", Style::default().fg(Color::Yellow)),
-            Span::raw(" Search  "),
-            Span::styled("↑↓", Style::default().fg(Color::Yellow)),
-            Span::raw(" Navigate  "),
-            Span::styled("d", Style::default().fg(Color::Yellow)),
-            Span::raw(" Close Connection  "),
-            Span::styled("a", Style::default().fg(Color::Yellow)),
-            Span::raw(" Close All  "),
-            Span::styled("r", Style::default().fg(Color::Yellow)),
-            Span::raw(" Refresh  "),
-            Span::styled("h", Style::default().fg(Color::Yellow)),
-            Span::raw(" Home  "),
-            Span::styled("q", Style::default().fg(Color::Yellow)),
-            Span::raw(" Back"),
-        ]
+        key_hints(&keymap::bar(keymap::CONNECTIONS, |_| true))
     };
 
     let help = Paragraph::new(Line::from(help_spans))
@@ -327,6 +928,10 @@ fn render_help(f: &mut Frame, area: Rect, search_mode: bool) {
     f.render_widget(help, area);
 }
 
+fn format_rate(bytes_per_sec: u64) -> String {
+    format!("{}/s", format_bytes(bytes_per_sec))
+}
+
 fn format_bytes(bytes: u64) -> String {
     const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
     let mut size = bytes as f64;
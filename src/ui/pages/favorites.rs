@@ -0,0 +1,283 @@
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+    Frame,
+};
+
+use crate::app::AppState;
+use crate::clash::{HumanRoute, ProxyType};
+use crate::config::AppConfig;
+use crate::ui::i18n::{key_hints, t};
+use crate::ui::keymap;
+use crate::ui::theme;
+
+/// Selector groups that contain the given node, in Routes page order
+pub fn groups_for_node(state: &AppState, config: &AppConfig, node: &str) -> Vec<HumanRoute> {
+    config
+        .arrange_routes(
+            HumanRoute::from_proxies(&state.clash_state.proxies, state.mode),
+            true,
+        )
+        .into_iter()
+        .filter(|route| route.proxy_type == ProxyType::Selector)
+        .filter(|route| route.all_nodes.iter().any(|n| n == node))
+        .collect()
+}
+
+pub fn render(
+    f: &mut Frame,
+    area: Rect,
+    state: &AppState,
+    config: &AppConfig,
+    selected_index: usize,
+    group_picker: Option<usize>,
+) {
+    let constraints = if state.status_message.is_some() {
+        vec![
+            Constraint::Length(3), // Title
+            Constraint::Length(3), // Status message
+            Constraint::Min(0),    // Favorites (+ group picker)
+            Constraint::Length(3), // Help
+        ]
+    } else {
+        vec![
+            Constraint::Length(3), // Title
+            Constraint::Min(0),    // Favorites (+ group picker)
+            Constraint::Length(3), // Help
+        ]
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(area);
+
+    let mut chunk_idx = 0;
+    render_title(f, chunks[chunk_idx], config);
+    chunk_idx += 1;
+
+    if let Some(msg) = &state.status_message {
+        render_status(f, chunks[chunk_idx], msg);
+        chunk_idx += 1;
+    }
+
+    match group_picker {
+        Some(group_index) => {
+            let body = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .split(chunks[chunk_idx]);
+            render_favorites(f, body[0], state, config, selected_index);
+            if let Some(node) = config.favorite_nodes.get(selected_index) {
+                render_groups(f, body[1], state, config, node, group_index);
+            }
+        }
+        None => render_favorites(f, chunks[chunk_idx], state, config, selected_index),
+    }
+    chunk_idx += 1;
+
+    render_help(f, chunks[chunk_idx], group_picker.is_some(), state);
+}
+
+fn render_title(f: &mut Frame, area: Rect, config: &AppConfig) {
+    let default = config
+        .favorite_selector
+        .as_deref()
+        .map(|group| format!(" - {}: {}", t("title.favorites_default"), group))
+        .unwrap_or_default();
+    let title = Paragraph::new(format!("{}{}", t("title.favorites"), default))
+        .style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(title, area);
+}
+
+fn render_status(f: &mut Frame, area: Rect, msg: &str) {
+    let status = Paragraph::new(msg)
+        .style(Style::default().fg(Color::Yellow))
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(status, area);
+}
+
+fn render_favorites(
+    f: &mut Frame,
+    area: Rect,
+    state: &AppState,
+    config: &AppConfig,
+    selected_index: usize,
+) {
+    let favorites = &config.favorite_nodes;
+    if favorites.is_empty() {
+        let empty = Paragraph::new(vec![
+            Line::from(""),
+            Line::from("No favorite nodes yet"),
+            Line::from(Span::styled(
+                "Press * on a node in Routes to add it",
+                Style::default().fg(Color::DarkGray),
+            )),
+        ])
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t("block.favorites")),
+        );
+        f.render_widget(empty, area);
+        return;
+    }
+
+    let visible_items = area.height.saturating_sub(2).max(1) as usize;
+    let selected_index = selected_index.min(favorites.len() - 1);
+    let start_index = (selected_index + 1).saturating_sub(visible_items);
+
+    let items: Vec<ListItem> = favorites
+        .iter()
+        .enumerate()
+        .skip(start_index)
+        .take(visible_items)
+        .map(|(i, node)| {
+            let groups = groups_for_node(state, config, node);
+            let active_in: Vec<&str> = groups
+                .iter()
+                .filter(|route| route.current_node.as_deref() == Some(node.as_str()))
+                .map(|route| route.name.as_str())
+                .collect();
+
+            let style = if i == selected_index {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else if !active_in.is_empty() {
+                Style::default().fg(Color::Green)
+            } else if groups.is_empty() {
+                Style::default().fg(Color::DarkGray)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            let prefix = match (i == selected_index, !active_in.is_empty()) {
+                (true, true) => "▶ ✓ ",
+                (true, false) => "▶   ",
+                (false, true) => "  ✓ ",
+                (false, false) => "    ",
+            };
+
+            let mut spans = vec![
+                Span::styled(prefix, style),
+                Span::styled("★ ", Style::default().fg(Color::Yellow)),
+                Span::styled(node.clone(), style),
+            ];
+
+            if state.is_testing(node) {
+                spans.push(Span::styled(
+                    " [Testing...]",
+                    Style::default().fg(Color::Yellow),
+                ));
+            } else if let Some(delay_result) = state.get_delay(node) {
+                let delay = delay_result.delay;
+                let stale = state.is_delay_stale(delay_result);
+                let color = if stale {
+                    Color::DarkGray
+                } else if delay < 200 {
+                    Color::Green
+                } else if delay < 500 {
+                    Color::Yellow
+                } else {
+                    Color::Red
+                };
+                let text = if stale {
+                    format!(" [{}, stale]", theme::delay_text(delay))
+                } else {
+                    format!(" [{}]", theme::delay_text(delay))
+                };
+                spans.push(Span::styled(text, Style::default().fg(color)));
+            }
+
+            let groups_text = if groups.is_empty() {
+                "  (not in any group)".to_string()
+            } else {
+                let names: Vec<&str> = groups.iter().map(|route| route.name.as_str()).collect();
+                format!("  in: {}", names.join(", "))
+            };
+            spans.push(Span::styled(
+                groups_text,
+                Style::default().fg(Color::DarkGray),
+            ));
+
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let list = List::new(items).block(Block::default().borders(Borders::ALL).title(format!(
+        "{} ({}/{})",
+        t("block.favorites"),
+        selected_index + 1,
+        favorites.len()
+    )));
+    f.render_widget(list, area);
+}
+
+fn render_groups(
+    f: &mut Frame,
+    area: Rect,
+    state: &AppState,
+    config: &AppConfig,
+    node: &str,
+    group_index: usize,
+) {
+    let groups = groups_for_node(state, config, node);
+    let items: Vec<ListItem> = groups
+        .iter()
+        .enumerate()
+        .map(|(i, route)| {
+            let style = if i == group_index {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            let prefix = if i == group_index { "▶ " } else { "  " };
+
+            let mut spans = vec![
+                Span::styled(prefix, style),
+                Span::styled(route.display_name(), style),
+                Span::styled(
+                    format!("  → {}", route.current_display()),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ];
+            if config.favorite_selector.as_deref() == Some(route.name.as_str()) {
+                spans.push(Span::styled(" [default]", Style::default().fg(Color::Cyan)));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let list = List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Apply {} to...", node)),
+    );
+    f.render_widget(list, area);
+}
+
+fn render_help(f: &mut Frame, area: Rect, group_picker: bool, state: &AppState) {
+    let bindings = if group_picker {
+        keymap::FAVORITE_GROUPS
+    } else {
+        keymap::FAVORITES
+    };
+    let hints = keymap::bar(bindings, |needs| needs.met_by(&state.preset));
+
+    let help = Paragraph::new(Line::from(key_hints(&hints)))
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(help, area);
+}
@@ -7,14 +7,29 @@ use ratatui::{
 };
 
 use crate::app::AppState;
+use crate::clash::HumanRoute;
 use crate::config::AppConfig;
+use crate::ui::i18n::{key_hints, t};
+use crate::ui::keymap;
+use crate::ui::list;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum GroupsAction {
     None,
-    CreateGroup(String),  // Group name input
-    SelectingNodes(String),  // Group name, selecting nodes to add
-    ViewingGroup(String),  // Viewing nodes in a group
+    CreateGroup,            // Typing the new group's name
+    SelectingNodes(String), // Group name, selecting nodes to add
+    ViewingGroup(String),   // Viewing nodes in a group
+}
+
+/// Every node offered by the core's groups, sorted and without duplicates
+pub fn available_nodes(state: &AppState) -> Vec<String> {
+    let mut nodes: Vec<String> = HumanRoute::from_proxies(&state.clash_state.proxies, state.mode)
+        .into_iter()
+        .flat_map(|route| route.all_nodes)
+        .collect();
+    nodes.sort();
+    nodes.dedup();
+    nodes
 }
 
 pub fn render(
@@ -26,36 +41,71 @@ pub fn render(
     input: &str,
     selected_index: usize,
 ) {
-    match action {
-        GroupsAction::None => render_group_list(f, area, state, config, selected_index),
-        GroupsAction::CreateGroup(_) => render_create_group(f, area, state, input),
-        GroupsAction::SelectingNodes(group_name) => {
-            render_node_selection(f, area, state, config, group_name, selected_index)
-        }
-        GroupsAction::ViewingGroup(group_name) => {
-            render_group_view(f, area, state, config, group_name, selected_index)
-        }
-    }
-}
+    let constraints = if state.status_message.is_some() {
+        vec![
+            Constraint::Length(3), // Title
+            Constraint::Length(3), // Status message
+            Constraint::Min(0),    // Groups / nodes
+            Constraint::Length(3), // Help
+        ]
+    } else {
+        vec![
+            Constraint::Length(3), // Title
+            Constraint::Min(0),    // Groups / nodes
+            Constraint::Length(3), // Help
+        ]
+    };
 
-fn render_group_list(
-    f: &mut Frame,
-    area: Rect,
-    _state: &AppState,
-    config: &AppConfig,
-    selected_index: usize,
-) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3), // Title
-            Constraint::Min(0),    // Group list
-            Constraint::Length(3), // Help
-        ])
+        .constraints(constraints)
         .split(area);
 
-    // Title
-    let title = Paragraph::new("Node Groups")
+    let mut chunk_idx = 0;
+    let title = match action {
+        GroupsAction::None => t("title.groups").to_string(),
+        GroupsAction::CreateGroup => format!("{} - {}", t("title.groups"), t("help.new_group")),
+        GroupsAction::SelectingNodes(group) => {
+            format!("{} - {}: {}", t("title.groups"), t("help.add_nodes"), group)
+        }
+        GroupsAction::ViewingGroup(group) => format!("{}: {}", t("title.groups"), group),
+    };
+    render_title(f, chunks[chunk_idx], &title);
+    chunk_idx += 1;
+
+    if let Some(msg) = &state.status_message {
+        render_status(f, chunks[chunk_idx], msg);
+        chunk_idx += 1;
+    }
+
+    let bindings = match action {
+        GroupsAction::None => {
+            render_group_list(f, chunks[chunk_idx], config, selected_index);
+            keymap::GROUPS
+        }
+        GroupsAction::CreateGroup => {
+            render_create_group(f, chunks[chunk_idx], input);
+            keymap::GROUP_NEW
+        }
+        GroupsAction::SelectingNodes(group) => {
+            render_node_selection(f, chunks[chunk_idx], state, config, group, selected_index);
+            keymap::GROUP_NODES
+        }
+        GroupsAction::ViewingGroup(group) => {
+            render_group_view(f, chunks[chunk_idx], config, group, selected_index);
+            keymap::GROUP_VIEW
+        }
+    };
+    chunk_idx += 1;
+
+    let help = Paragraph::new(Line::from(key_hints(&keymap::bar(bindings, |_| true))))
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(help, chunks[chunk_idx]);
+}
+
+fn render_title(f: &mut Frame, area: Rect, title: &str) {
+    let title = Paragraph::new(title.to_string())
         .style(
             Style::default()
                 .fg(Color::Cyan)
@@ -63,119 +113,117 @@ fn render_group_list(
         )
         .alignment(Alignment::Center)
         .block(Block::default().borders(Borders::ALL));
-    f.render_widget(title, chunks[0]);
+    f.render_widget(title, area);
+}
 
-    // Group list
+fn render_status(f: &mut Frame, area: Rect, msg: &str) {
+    let status = Paragraph::new(msg)
+        .style(Style::default().fg(Color::Yellow))
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(status, area);
+}
+
+/// Selection marker and style for a list row
+fn row_style(selected: bool) -> (&'static str, Style) {
+    if selected {
+        (
+            "▶ ",
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
+    } else {
+        ("  ", Style::default())
+    }
+}
+
+fn render_group_list(f: &mut Frame, area: Rect, config: &AppConfig, selected_index: usize) {
     let group_names = config.get_group_names();
-    let items: Vec<ListItem> = group_names
+    if group_names.is_empty() {
+        let empty = Paragraph::new(vec![
+            Line::from(""),
+            Line::from("No node groups yet"),
+            Line::from(Span::styled(
+                "Press n to create one, or G on marked nodes in Routes",
+                Style::default().fg(Color::DarkGray),
+            )),
+        ])
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t("block.groups")),
+        );
+        f.render_widget(empty, area);
+        return;
+    }
+
+    let rows = list::viewport(area, 1);
+    let selected_index = selected_index.min(group_names.len() - 1);
+    let window = list::window(
+        group_names.len(),
+        list::follow(selected_index, 0, rows),
+        rows,
+    );
+
+    let items: Vec<ListItem> = group_names[window.clone()]
         .iter()
-        .enumerate()
-        .map(|(idx, name)| {
+        .zip(window)
+        .map(|(name, i)| {
+            let (prefix, style) = row_style(i == selected_index);
             let nodes = config.get_group_nodes(name).map(|n| n.len()).unwrap_or(0);
-            let line = if idx == selected_index {
-                Line::from(vec![
-                    Span::styled("▶ ", Style::default().fg(Color::Yellow)),
-                    Span::styled(
-                        name,
-                        Style::default()
-                            .fg(Color::Cyan)
-                            .add_modifier(Modifier::BOLD),
-                    ),
-                    Span::styled(
-                        format!(" ({} nodes)", nodes),
-                        Style::default().fg(Color::Gray),
-                    ),
-                ])
-            } else {
-                Line::from(vec![
-                    Span::raw("  "),
-                    Span::raw(name),
-                    Span::styled(
-                        format!(" ({} nodes)", nodes),
-                        Style::default().fg(Color::Gray),
-                    ),
-                ])
-            };
-            ListItem::new(line)
+            let mut spans = vec![
+                Span::styled(prefix, Style::default().fg(Color::Yellow)),
+                Span::styled(name.clone(), style),
+                Span::styled(
+                    format!(" ({} nodes)", nodes),
+                    Style::default().fg(Color::Gray),
+                ),
+            ];
+            if config.is_group_materialized(name) {
+                spans.push(Span::styled(
+                    " [in Clash config]",
+                    Style::default().fg(Color::Green),
+                ));
+            }
+            ListItem::new(Line::from(spans))
         })
         .collect();
 
-    let list_title = if group_names.is_empty() {
-        "Groups (No groups created)".to_string()
-    } else {
-        format!("Groups ({} total)", group_names.len())
-    };
-
-    let list = List::new(items).block(Block::default().borders(Borders::ALL).title(list_title));
-    f.render_widget(list, chunks[1]);
-
-    // Help
-    let help = Paragraph::new(Line::from(vec![
-        Span::styled("n", Style::default().fg(Color::Yellow)),
-        Span::raw(" New Group  "),
-        Span::styled("Enter", Style::default().fg(Color::Yellow)),
-        Span::raw(" View/Edit  "),
-        Span::styled("d", Style::default().fg(Color::Yellow)),
-        Span::raw(" Delete  "),
-        Span::styled("q/ESC", Style::default().fg(Color::Yellow)),
-        Span::raw(" Back"),
-    ]))
-    .alignment(Alignment::Center)
-    .block(Block::default().borders(Borders::ALL));
-    f.render_widget(help, chunks[2]);
+    let list = List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Groups ({} total)", group_names.len())),
+    );
+    f.render_widget(list, area);
 }
 
-fn render_create_group(f: &mut Frame, area: Rect, _state: &AppState, input: &str) {
+fn render_create_group(f: &mut Frame, area: Rect, input: &str) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(3), // Title
             Constraint::Length(3), // Input
             Constraint::Min(0),    // Info
-            Constraint::Length(3), // Help
         ])
         .split(area);
 
-    // Title
-    let title = Paragraph::new("Create New Group")
-        .style(
-            Style::default()
-                .fg(Color::Green)
-                .add_modifier(Modifier::BOLD),
-        )
-        .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL));
-    f.render_widget(title, chunks[0]);
-
-    // Input
-    let input_widget = Paragraph::new(input)
+    let input_widget = Paragraph::new(format!("{}█", input))
         .style(Style::default().fg(Color::Yellow))
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Group Name"),
+                .title(t("block.group_name")),
         );
-    f.render_widget(input_widget, chunks[1]);
+    f.render_widget(input_widget, chunks[0]);
 
-    // Info
     let info = Paragraph::new(vec![
         Line::from(""),
         Line::from("Enter a name for your new group."),
         Line::from("After creating, you can add nodes to it."),
     ])
     .alignment(Alignment::Center);
-    f.render_widget(info, chunks[2]);
-
-    // Help
-    let help = Paragraph::new(Line::from(vec![
-        Span::styled("Enter", Style::default().fg(Color::Yellow)),
-        Span::raw(" Create  "),
-        Span::styled("ESC", Style::default().fg(Color::Yellow)),
-        Span::raw(" Cancel"),
-    ]))
-    .alignment(Alignment::Center)
-    .block(Block::default().borders(Borders::ALL));
-    f.render_widget(help, chunks[3]);
+    f.render_widget(info, chunks[1]);
 }
 
 fn render_node_selection(
@@ -186,164 +234,99 @@ fn render_node_selection(
     group_name: &str,
     selected_index: usize,
 ) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3), // Title
-            Constraint::Min(0),    // Node list
-            Constraint::Length(3), // Help
-        ])
-        .split(area);
-
-    // Title
-    let title = Paragraph::new(format!("Add Nodes to Group: {}", group_name))
-        .style(
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        )
-        .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL));
-    f.render_widget(title, chunks[0]);
-
-    // Get all available nodes from routes
-    let routes = crate::clash::HumanRoute::from_proxies(&state.clash_state.proxies, state.mode);
-    let mut all_nodes = Vec::new();
-    for route in routes {
-        all_nodes.extend(route.all_nodes);
+    let all_nodes = available_nodes(state);
+    if all_nodes.is_empty() {
+        let empty = Paragraph::new("No nodes available")
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(empty, area);
+        return;
     }
-    all_nodes.sort();
-    all_nodes.dedup();
 
-    // Get nodes already in the group
-    let group_nodes = config.get_group_nodes(group_name).cloned().unwrap_or_default();
+    let group_nodes = config
+        .get_group_nodes(group_name)
+        .cloned()
+        .unwrap_or_default();
+    let rows = list::viewport(area, 1);
+    let selected_index = selected_index.min(all_nodes.len() - 1);
+    let window = list::window(all_nodes.len(), list::follow(selected_index, 0, rows), rows);
 
-    let items: Vec<ListItem> = all_nodes
+    let items: Vec<ListItem> = all_nodes[window.clone()]
         .iter()
-        .enumerate()
-        .map(|(idx, node)| {
-            let in_group = group_nodes.contains(node);
-            let line = if idx == selected_index {
-                Line::from(vec![
-                    Span::styled("▶ ", Style::default().fg(Color::Yellow)),
-                    if in_group {
-                        Span::styled("[✓] ", Style::default().fg(Color::Green))
-                    } else {
-                        Span::raw("[ ] ")
-                    },
-                    Span::styled(
-                        node,
-                        Style::default()
-                            .fg(Color::Cyan)
-                            .add_modifier(Modifier::BOLD),
-                    ),
-                ])
+        .zip(window)
+        .map(|(node, i)| {
+            let (prefix, style) = row_style(i == selected_index);
+            let check = if group_nodes.contains(node) {
+                Span::styled("[✓] ", Style::default().fg(Color::Green))
             } else {
-                Line::from(vec![
-                    Span::raw("  "),
-                    if in_group {
-                        Span::styled("[✓] ", Style::default().fg(Color::Green))
-                    } else {
-                        Span::raw("[ ] ")
-                    },
-                    Span::raw(node),
-                ])
+                Span::raw("[ ] ")
             };
-            ListItem::new(line)
+            ListItem::new(Line::from(vec![
+                Span::styled(prefix, Style::default().fg(Color::Yellow)),
+                check,
+                Span::styled(node.clone(), style),
+            ]))
         })
         .collect();
 
-    let list = List::new(items).block(
-        Block::default()
-            .borders(Borders::ALL)
-            .title(format!("Available Nodes ({})", all_nodes.len())),
-    );
-    f.render_widget(list, chunks[1]);
-
-    // Help
-    let help = Paragraph::new(Line::from(vec![
-        Span::styled("Space", Style::default().fg(Color::Yellow)),
-        Span::raw(" Toggle  "),
-        Span::styled("Enter", Style::default().fg(Color::Yellow)),
-        Span::raw(" Done  "),
-        Span::styled("ESC", Style::default().fg(Color::Yellow)),
-        Span::raw(" Cancel"),
-    ]))
-    .alignment(Alignment::Center)
-    .block(Block::default().borders(Borders::ALL));
-    f.render_widget(help, chunks[2]);
+    let list = List::new(items).block(Block::default().borders(Borders::ALL).title(format!(
+        "Available Nodes ({} in group / {})",
+        group_nodes.len(),
+        all_nodes.len()
+    )));
+    f.render_widget(list, area);
 }
 
 fn render_group_view(
     f: &mut Frame,
     area: Rect,
-    _state: &AppState,
     config: &AppConfig,
     group_name: &str,
     selected_index: usize,
 ) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3), // Title
-            Constraint::Min(0),    // Nodes in group
-            Constraint::Length(3), // Help
+    let nodes = config
+        .get_group_nodes(group_name)
+        .cloned()
+        .unwrap_or_default();
+    if nodes.is_empty() {
+        let empty = Paragraph::new(vec![
+            Line::from(""),
+            Line::from("This group has no nodes"),
+            Line::from(Span::styled(
+                "Press a to add some",
+                Style::default().fg(Color::DarkGray),
+            )),
         ])
-        .split(area);
-
-    // Title
-    let title = Paragraph::new(format!("Group: {}", group_name))
-        .style(
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        )
         .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL));
-    f.render_widget(title, chunks[0]);
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t("block.nodes_empty")),
+        );
+        f.render_widget(empty, area);
+        return;
+    }
+
+    let rows = list::viewport(area, 1);
+    let selected_index = selected_index.min(nodes.len() - 1);
+    let window = list::window(nodes.len(), list::follow(selected_index, 0, rows), rows);
 
-    // Nodes in group
-    let nodes = config.get_group_nodes(group_name).cloned().unwrap_or_default();
-    let items: Vec<ListItem> = nodes
+    let items: Vec<ListItem> = nodes[window.clone()]
         .iter()
-        .enumerate()
-        .map(|(idx, node)| {
-            let line = if idx == selected_index {
-                Line::from(vec![
-                    Span::styled("▶ ", Style::default().fg(Color::Yellow)),
-                    Span::styled(
-                        node,
-                        Style::default()
-                            .fg(Color::Cyan)
-                            .add_modifier(Modifier::BOLD),
-                    ),
-                ])
-            } else {
-                Line::from(vec![Span::raw("  "), Span::raw(node)])
-            };
-            ListItem::new(line)
+        .zip(window)
+        .map(|(node, i)| {
+            let (prefix, style) = row_style(i == selected_index);
+            ListItem::new(Line::from(vec![
+                Span::styled(prefix, Style::default().fg(Color::Yellow)),
+                Span::styled(node.clone(), style),
+            ]))
         })
         .collect();
 
-    let list_title = if nodes.is_empty() {
-        "Nodes (Empty)".to_string()
-    } else {
-        format!("Nodes ({} total)", nodes.len())
-    };
-
-    let list = List::new(items).block(Block::default().borders(Borders::ALL).title(list_title));
-    f.render_widget(list, chunks[1]);
-
-    // Help
-    let help = Paragraph::new(Line::from(vec![
-        Span::styled("a", Style::default().fg(Color::Yellow)),
-        Span::raw(" Add Nodes  "),
-        Span::styled("d", Style::default().fg(Color::Yellow)),
-        Span::raw(" Remove Node  "),
-        Span::styled("ESC", Style::default().fg(Color::Yellow)),
-        Span::raw(" Back"),
-    ]))
-    .alignment(Alignment::Center)
-    .block(Block::default().borders(Borders::ALL));
-    f.render_widget(help, chunks[2]);
+    let list = List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Nodes ({} total)", nodes.len())),
+    );
+    f.render_widget(list, area);
 }
@@ -0,0 +1,187 @@
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::app::AppState;
+use crate::config::delays::{Heatmap, HeatmapCell, HeatmapView, HEATMAP_COLUMNS};
+use crate::ui::i18n::{key_hints, t};
+use crate::ui::keymap;
+use crate::ui::list;
+
+/// Characters per hour column: a two-character cell and a gap
+const COLUMN_WIDTH: usize = 3;
+/// Narrowest node name column
+const MIN_NAME_WIDTH: u16 = 8;
+
+pub fn render(
+    f: &mut Frame,
+    area: Rect,
+    state: &AppState,
+    heatmap: &Heatmap,
+    view: HeatmapView,
+    scroll: usize,
+) {
+    let constraints = if state.status_message.is_some() {
+        vec![
+            Constraint::Length(3), // Title
+            Constraint::Length(3), // Status message
+            Constraint::Min(0),    // Heatmap
+            Constraint::Length(3), // Help
+        ]
+    } else {
+        vec![
+            Constraint::Length(3), // Title
+            Constraint::Min(0),    // Heatmap
+            Constraint::Length(3), // Help
+        ]
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(area);
+
+    let mut chunk_idx = 0;
+    let title = Paragraph::new(t("title.heatmap"))
+        .style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(title, chunks[chunk_idx]);
+    chunk_idx += 1;
+
+    if let Some(msg) = &state.status_message {
+        let status = Paragraph::new(msg.as_str())
+            .style(Style::default().fg(Color::Yellow))
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(status, chunks[chunk_idx]);
+        chunk_idx += 1;
+    }
+
+    render_heatmap(f, chunks[chunk_idx], heatmap, view, scroll);
+    chunk_idx += 1;
+
+    let help = Paragraph::new(Line::from(key_hints(&keymap::bar(keymap::HEATMAP, |_| {
+        true
+    }))))
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::ALL));
+    f.render_widget(help, chunks[chunk_idx]);
+}
+
+fn render_heatmap(f: &mut Frame, area: Rect, heatmap: &Heatmap, view: HeatmapView, scroll: usize) {
+    let block = Block::default().borders(Borders::ALL).title(format!(
+        "Delay by hour ({}) - {} nodes",
+        view.label(),
+        heatmap.rows.len()
+    ));
+    if heatmap.rows.is_empty() {
+        let empty = Paragraph::new(vec![
+            Line::from(""),
+            Line::from("No delay tests recorded yet"),
+            Line::from(Span::styled(
+                "The core's delay history is saved every minute while clashctl runs",
+                Style::default().fg(Color::DarkGray),
+            )),
+        ])
+        .alignment(Alignment::Center)
+        .block(block);
+        f.render_widget(empty, area);
+        return;
+    }
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let sections = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),    // Grid
+            Constraint::Length(1), // Legend
+        ])
+        .split(inner);
+    let (grid, legend) = (sections[0], sections[1]);
+    // Names get whatever the hour columns (and a gap before them) leave
+    let grid_width = (HEATMAP_COLUMNS * COLUMN_WIDTH) as u16 + 1;
+    let name_width = grid.width.saturating_sub(grid_width).max(MIN_NAME_WIDTH);
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(name_width), Constraint::Min(0)])
+        .split(grid);
+    let (names, cells) = (columns[0], columns[1]);
+
+    // The header takes a row; the names are cut by their own column's width
+    let rows = (grid.height as usize).saturating_sub(1).max(1);
+    let window = list::window(heatmap.rows.len(), scroll, rows);
+
+    let mut name_lines = vec![Line::from("")];
+    name_lines.extend(
+        heatmap.rows[window.clone()]
+            .iter()
+            .map(|(node, _)| Line::from(node.clone())),
+    );
+    f.render_widget(Paragraph::new(name_lines), names);
+
+    let mut header = vec![Span::raw(" ")];
+    header.extend(heatmap.hours.iter().map(|hour| {
+        Span::styled(
+            format!("{:<width$}", format!("{:02}", hour), width = COLUMN_WIDTH),
+            Style::default().fg(Color::Gray),
+        )
+    }));
+    let mut cell_lines = vec![Line::from(header)];
+    cell_lines.extend(heatmap.rows[window].iter().map(|(_, row)| {
+        let mut spans = vec![Span::raw(" ")];
+        spans.extend(row.iter().map(|cell| {
+            let (glyph, color) = cell_style(cell);
+            Span::styled(
+                format!("{:<width$}", glyph, width = COLUMN_WIDTH),
+                Style::default().fg(color),
+            )
+        }));
+        Line::from(spans)
+    }));
+    f.render_widget(Paragraph::new(cell_lines), cells);
+
+    let legend_spans: Vec<Span> = [
+        ("░░", Color::Green, "<200ms"),
+        ("▒▒", Color::Yellow, "<500ms"),
+        ("▓▓", Color::Red, "slower"),
+        ("××", Color::Magenta, "mostly timeouts"),
+        ("··", Color::DarkGray, "no tests"),
+    ]
+    .into_iter()
+    .flat_map(|(glyph, color, label)| {
+        [
+            Span::styled(glyph, Style::default().fg(color)),
+            Span::styled(format!(" {}   ", label), Style::default().fg(Color::Gray)),
+        ]
+    })
+    .collect();
+    f.render_widget(
+        Paragraph::new(Line::from(legend_spans)).alignment(Alignment::Center),
+        legend,
+    );
+}
+
+/// Glyph and color of a cell; the glyph alone tells the levels apart without colors
+fn cell_style(cell: &HeatmapCell) -> (&'static str, Color) {
+    if cell.tests == 0 {
+        return ("··", Color::DarkGray);
+    }
+    if cell.timeouts * 2 > cell.tests {
+        return ("××", Color::Magenta);
+    }
+    match cell.mean_delay() {
+        Some(delay) if delay < 200 => ("░░", Color::Green),
+        Some(delay) if delay < 500 => ("▒▒", Color::Yellow),
+        _ => ("▓▓", Color::Red),
+    }
+}
@@ -1,28 +1,143 @@
+use chrono::{DateTime, Local};
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph},
+    widgets::{Block, Borders, Paragraph, Sparkline, Wrap},
     Frame,
 };
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
 
+use super::performance::{format_bytes, format_rate};
+use crate::app::probe::ProbeStatus;
 use crate::app::AppState;
+use crate::clash::{ConnectionsResponse, ProvidersResponse};
+use crate::ui::i18n::{key_hints, t};
+use crate::ui::keymap;
+use crate::ui::rate_per_sec;
 
-pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
-    let constraints = if state.status_message.is_some() {
-        vec![
-            Constraint::Length(5), // Status box
-            Constraint::Length(3), // Status message
-            Constraint::Min(0),    // Quick actions
-            Constraint::Length(3), // Help
-        ]
+/// Rate samples kept for the sparklines
+const RATE_HISTORY: usize = 120;
+
+/// Destinations listed under Top Hosts
+const TOP_HOSTS: usize = 5;
+
+/// Node switches listed under Recent Switches
+const RECENT_SWITCHES: usize = 5;
+
+/// Live data shown on Home, fed by connection and provider responses
+#[derive(Debug, Default)]
+pub struct Dashboard {
+    upload_total: u64,
+    download_total: u64,
+    /// Rates in B/s, oldest first
+    upload_rates: VecDeque<u64>,
+    download_rates: VecDeque<u64>,
+    connection_count: usize,
+    /// (host, bytes) of the active connections, busiest first
+    top_hosts: Vec<(String, u64)>,
+    /// (time, group, node), newest first
+    switches: VecDeque<(DateTime<Local>, String, String)>,
+    /// (provider, seconds left) of subscriptions with an expiry date, soonest first
+    subscriptions: Option<Vec<(String, i64)>>,
+}
+
+impl Dashboard {
+    /// Take a connections sample, `elapsed` after the previous one
+    pub fn sample(&mut self, data: &ConnectionsResponse, elapsed: Option<Duration>) {
+        if let Some(elapsed) = elapsed {
+            let up = rate_per_sec(data.upload_total, self.upload_total, elapsed);
+            let down = rate_per_sec(data.download_total, self.download_total, elapsed);
+            if let (Some(up), Some(down)) = (up, down) {
+                push_capped(&mut self.upload_rates, up);
+                push_capped(&mut self.download_rates, down);
+            }
+        }
+        self.upload_total = data.upload_total;
+        self.download_total = data.download_total;
+        self.connection_count = data.connections.len();
+        self.top_hosts = top_hosts(data, TOP_HOSTS);
+    }
+
+    pub fn record_switch(&mut self, group: &str, node: &str) {
+        self.switches
+            .push_front((Local::now(), group.to_string(), node.to_string()));
+        self.switches.truncate(RECENT_SWITCHES);
+    }
+
+    pub fn set_subscriptions(&mut self, providers: &ProvidersResponse, now: i64) {
+        let mut subscriptions: Vec<(String, i64)> = providers
+            .providers
+            .iter()
+            .filter_map(|(name, provider)| {
+                let info = provider.subscription_info.as_ref()?;
+                (info.expire != 0).then(|| (name.clone(), info.expire as i64 - now))
+            })
+            .collect();
+        subscriptions.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+        self.subscriptions = Some(subscriptions);
+    }
+}
+
+fn push_capped(history: &mut VecDeque<u64>, value: u64) {
+    if history.len() == RATE_HISTORY {
+        history.pop_front();
+    }
+    history.push_back(value);
+}
+
+/// Bytes moved by the active connections per destination, busiest first
+pub fn top_hosts(data: &ConnectionsResponse, limit: usize) -> Vec<(String, u64)> {
+    let mut hosts: HashMap<&str, u64> = HashMap::new();
+    for conn in &data.connections {
+        let host = match conn.metadata.host.as_deref() {
+            Some(host) if !host.is_empty() => host,
+            _ => conn.metadata.destination_ip.as_str(),
+        };
+        *hosts.entry(host).or_insert(0) += conn.upload + conn.download;
+    }
+    let mut hosts: Vec<(String, u64)> = hosts
+        .into_iter()
+        .map(|(host, bytes)| (host.to_string(), bytes))
+        .collect();
+    hosts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    hosts.truncate(limit);
+    hosts
+}
+
+pub fn render(
+    f: &mut Frame,
+    area: Rect,
+    state: &AppState,
+    dashboard: &Dashboard,
+    expiry_days: u64,
+) {
+    // Leave room for the connection error and its hints
+    let status_height = if state.clash_state.error.is_some() {
+        12
     } else {
-        vec![
-            Constraint::Length(5), // Status box
-            Constraint::Min(0),    // Quick actions
-            Constraint::Length(3), // Help
-        ]
+        7
+    };
+    let mut constraints = vec![Constraint::Length(status_height)]; // Status box
+    if state.status_message.is_some() {
+        constraints.push(Constraint::Length(3)); // Status message
+    }
+    // Short terminals keep the traffic row and drop switches/subscriptions
+    let fixed = status_height + if state.status_message.is_some() { 3 } else { 0 } + 3;
+    let dashboard_rows = match area.height.saturating_sub(fixed) {
+        height if height >= 22 => 2,
+        height if height >= 12 => 1,
+        _ => 0,
     };
+    if dashboard_rows > 0 {
+        constraints.push(Constraint::Length(8)); // Traffic and top hosts
+    }
+    if dashboard_rows == 2 {
+        constraints.push(Constraint::Length(7)); // Recent switches and subscriptions
+    }
+    constraints.push(Constraint::Min(0)); // Quick actions
+    constraints.push(Constraint::Length(3)); // Help
 
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -38,6 +153,20 @@ pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
         chunk_idx += 1;
     }
 
+    if dashboard_rows > 0 {
+        let row = split_columns(chunks[chunk_idx]);
+        render_traffic(f, row[0], dashboard);
+        render_top_hosts(f, row[1], dashboard);
+        chunk_idx += 1;
+    }
+
+    if dashboard_rows == 2 {
+        let row = split_columns(chunks[chunk_idx]);
+        render_switches(f, row[0], dashboard);
+        render_subscriptions(f, row[1], dashboard, expiry_days);
+        chunk_idx += 1;
+    }
+
     render_quick_actions(f, chunks[chunk_idx]);
     chunk_idx += 1;
 
@@ -48,14 +177,19 @@ fn render_status(f: &mut Frame, area: Rect, state: &AppState) {
     let clash = &state.clash_state;
 
     let mode_str = format!("{:?} Mode", clash.mode);
-    let route_str = if let Some(proxy) = &clash.current_proxy {
-        format!("Route: {}", proxy)
-    } else {
-        "Route: None".to_string()
+    let route_str = match (&clash.current_selector, &clash.current_proxy) {
+        (Some(group), Some(proxy)) => format!("Route: {} ({})", proxy, group),
+        (None, Some(proxy)) => format!("Route: {}", proxy),
+        _ => "Route: None".to_string(),
     };
 
     let health = clash.get_health_status();
-    let health_line = Line::from(vec![
+    let (core, core_color) = match clash.core_running {
+        Some(true) => ("running", Color::Green),
+        Some(false) => ("stopped", Color::Red),
+        None => ("unknown", Color::Gray),
+    };
+    let mut health_spans = vec![
         Span::raw("Health: "),
         Span::styled(
             health.as_str(),
@@ -63,7 +197,38 @@ fn render_status(f: &mut Frame, area: Rect, state: &AppState) {
                 .fg(health.color())
                 .add_modifier(Modifier::BOLD),
         ),
-    ]);
+        Span::raw("  Core: "),
+        Span::styled(core, Style::default().fg(core_color)),
+    ];
+    if let Some(level) = &clash.log_level {
+        health_spans.push(Span::styled(
+            format!(" (log {})", level),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+    // End-to-end check through the proxy port, once the first probe is back
+    match &state.proxy_probe {
+        Some(ProbeStatus::Reachable(ms)) => {
+            health_spans.push(Span::raw("  Proxy: "));
+            health_spans.push(Span::styled(
+                format!("OK {}ms", ms),
+                Style::default().fg(Color::Green),
+            ));
+        }
+        Some(ProbeStatus::Unreachable(reason)) => {
+            health_spans.push(Span::raw("  Proxy: "));
+            health_spans.push(Span::styled(
+                "Broken",
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            ));
+            health_spans.push(Span::styled(
+                format!(" ({})", reason),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        None => {}
+    }
+    let health_line = Line::from(health_spans);
 
     let mut lines = vec![
         Line::from(Span::styled(
@@ -85,13 +250,17 @@ fn render_status(f: &mut Frame, area: Rect, state: &AppState) {
             ]));
         } else if let Some(delay_result) = state.get_delay(&current_node) {
             let delay = delay_result.delay;
-            let (delay_text, delay_color) = if delay < 200 {
+            let (mut delay_text, mut delay_color) = if delay < 200 {
                 (format!("{}ms ⚡Fast", delay), Color::Green)
             } else if delay < 500 {
                 (format!("{}ms Good", delay), Color::Yellow)
             } else {
                 (format!("{}ms Slow", delay), Color::Red)
             };
+            if state.is_delay_stale(delay_result) {
+                delay_text.push_str(" (stale)");
+                delay_color = Color::DarkGray;
+            }
             lines.push(Line::from(vec![
                 Span::raw("Speed: "),
                 Span::styled(
@@ -138,8 +307,13 @@ fn render_status(f: &mut Frame, area: Rect, state: &AppState) {
                 "  Cannot connect to Clash",
                 Style::default().fg(Color::Red),
             )));
+            let hint = match clash.core_running {
+                Some(false) => "  Clash core is not running - start it from Settings [s]",
+                Some(true) => "  Core is running - check external-controller address",
+                None => "  Make sure Clash is running on port 9090",
+            };
             lines.push(Line::from(Span::styled(
-                "  Make sure Clash is running on port 9090",
+                hint,
                 Style::default().fg(Color::Yellow),
             )));
         } else {
@@ -158,7 +332,11 @@ fn render_status(f: &mut Frame, area: Rect, state: &AppState) {
     }
 
     let status = Paragraph::new(lines)
-        .block(Block::default().borders(Borders::ALL).title("Status"))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t("block.status")),
+        )
         .alignment(Alignment::Left);
 
     f.render_widget(status, area);
@@ -172,67 +350,186 @@ fn render_status_message(f: &mut Frame, area: Rect, message: &str) {
     f.render_widget(msg, area);
 }
 
-fn render_quick_actions(f: &mut Frame, area: Rect) {
-    let actions = Paragraph::new(vec![
-        Line::from(""),
-        Line::from("Quick Actions:"),
-        Line::from(""),
-        Line::from(vec![
-            Span::styled("  [m]", Style::default().fg(Color::Yellow)),
-            Span::raw(" Switch Scene (Rule/Global/Direct)"),
-        ]),
-        Line::from(vec![
-            Span::styled("  [g]", Style::default().fg(Color::Yellow)),
-            Span::raw(" Go to Routes (Node Management)"),
-        ]),
-        Line::from(vec![
-            Span::styled("  [l]", Style::default().fg(Color::Yellow)),
-            Span::raw(" Go to Rules"),
-        ]),
-        Line::from(vec![
-            Span::styled("  [c]", Style::default().fg(Color::Yellow)),
-            Span::raw(" Go to Connections"),
-        ]),
-        Line::from(vec![
-            Span::styled("  [p]", Style::default().fg(Color::Yellow)),
-            Span::raw(" Go to Performance"),
-        ]),
-        Line::from(vec![
-            Span::styled("  [o]", Style::default().fg(Color::Yellow)),
-            Span::raw(" Go to Logs"),
-        ]),
-        Line::from(vec![
-            Span::styled("  [u]", Style::default().fg(Color::Yellow)),
-            Span::raw(" Go to Update"),
-        ]),
-        Line::from(vec![
-            Span::styled("  [s]", Style::default().fg(Color::Yellow)),
-            Span::raw(" Go to Settings"),
-        ]),
-        Line::from(vec![
-            Span::styled("  [r]", Style::default().fg(Color::Yellow)),
-            Span::raw(" Refresh Status"),
-        ]),
-    ])
-    .block(
+fn split_columns(area: Rect) -> std::rc::Rc<[Rect]> {
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area)
+}
+
+fn render_traffic(f: &mut Frame, area: Rect, dashboard: &Dashboard) {
+    let block = Block::default().borders(Borders::ALL).title(format!(
+        "Traffic ({} connections)",
+        dashboard.connection_count
+    ));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Length(2),
+            Constraint::Length(1),
+            Constraint::Length(2),
+        ])
+        .split(inner);
+    let width = inner.width as usize;
+    for (index, (label, rates, color)) in [
+        ("↓", &dashboard.download_rates, Color::Green),
+        ("↑", &dashboard.upload_rates, Color::Cyan),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let current = rates.back().copied();
+        let text = match current {
+            Some(rate) => format!("{} {}", label, format_rate(rate)),
+            None => format!("{} -", label),
+        };
+        f.render_widget(
+            Paragraph::new(Span::styled(text, Style::default().fg(color))),
+            rows[index * 2],
+        );
+        // Newest samples on the right
+        let recent: Vec<u64> = rates
+            .iter()
+            .skip(rates.len().saturating_sub(width))
+            .copied()
+            .collect();
+        f.render_widget(
+            Sparkline::default()
+                .data(&recent)
+                .style(Style::default().fg(color)),
+            rows[index * 2 + 1],
+        );
+    }
+}
+
+fn render_top_hosts(f: &mut Frame, area: Rect, dashboard: &Dashboard) {
+    let lines: Vec<Line> = if dashboard.top_hosts.is_empty() {
+        vec![Line::from(Span::styled(
+            "No active connections",
+            Style::default().fg(Color::DarkGray),
+        ))]
+    } else {
+        dashboard
+            .top_hosts
+            .iter()
+            .map(|(host, bytes)| {
+                Line::from(vec![
+                    Span::styled(
+                        format!("{:>10} ", format_bytes(*bytes)),
+                        Style::default().fg(Color::Yellow),
+                    ),
+                    Span::raw(host.clone()),
+                ])
+            })
+            .collect()
+    };
+    let hosts = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(t("block.top_hosts")),
+    );
+    f.render_widget(hosts, area);
+}
+
+fn render_switches(f: &mut Frame, area: Rect, dashboard: &Dashboard) {
+    let lines: Vec<Line> = if dashboard.switches.is_empty() {
+        vec![Line::from(Span::styled(
+            "No switches this session",
+            Style::default().fg(Color::DarkGray),
+        ))]
+    } else {
+        dashboard
+            .switches
+            .iter()
+            .map(|(at, group, node)| {
+                Line::from(vec![
+                    Span::styled(
+                        format!("{} ", at.format("%H:%M:%S")),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::raw(format!("{} → ", group)),
+                    Span::styled(node.clone(), Style::default().fg(Color::Cyan)),
+                ])
+            })
+            .collect()
+    };
+    let switches = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(t("block.recent_switches")),
+    );
+    f.render_widget(switches, area);
+}
+
+fn render_subscriptions(f: &mut Frame, area: Rect, dashboard: &Dashboard, expiry_days: u64) {
+    let muted = Style::default().fg(Color::DarkGray);
+    let lines: Vec<Line> = match &dashboard.subscriptions {
+        None => vec![Line::from(Span::styled("Checking...", muted))],
+        Some(subscriptions) if subscriptions.is_empty() => {
+            vec![Line::from(Span::styled(
+                "No subscription expiry info",
+                muted,
+            ))]
+        }
+        Some(subscriptions) => subscriptions
+            .iter()
+            .map(|(name, left)| {
+                let days = (left + 86_399) / 86_400;
+                let (text, color) = if *left <= 0 {
+                    ("expired".to_string(), Color::Red)
+                } else if days <= expiry_days as i64 {
+                    (format!("{}d left", days), Color::Yellow)
+                } else {
+                    (format!("{}d left", days), Color::Green)
+                };
+                Line::from(vec![
+                    Span::styled(format!("{:>9} ", text), Style::default().fg(color)),
+                    Span::raw(name.clone()),
+                ])
+            })
+            .collect(),
+    };
+    let subscriptions = Paragraph::new(lines).block(
         Block::default()
             .borders(Borders::ALL)
-            .title("Quick Actions"),
-    )
-    .alignment(Alignment::Left);
+            .title(t("block.subscriptions")),
+    );
+    f.render_widget(subscriptions, area);
+}
+
+fn render_quick_actions(f: &mut Frame, area: Rect) {
+    let spans: Vec<Span> = keymap::bar(keymap::HOME_ACTIONS, |_| true)
+        .into_iter()
+        .flat_map(|(key, label)| {
+            // Non-breaking spaces keep each action on one line when wrapped
+            [
+                Span::styled(format!("[{}]", key), Style::default().fg(Color::Yellow)),
+                Span::raw(format!("\u{a0}{}   ", t(label).replace(' ', "\u{a0}"))),
+            ]
+        })
+        .collect();
+    let actions = Paragraph::new(Line::from(spans))
+        .wrap(Wrap { trim: true })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t("home.quick_actions")),
+        )
+        .alignment(Alignment::Left);
 
     f.render_widget(actions, area);
 }
 
 fn render_help(f: &mut Frame, area: Rect) {
-    let help = Paragraph::new(Line::from(vec![
-        Span::styled("q", Style::default().fg(Color::Yellow)),
-        Span::raw(" Quit  "),
-        Span::styled("?", Style::default().fg(Color::Yellow)),
-        Span::raw(" Help"),
-    ]))
-    .alignment(Alignment::Center)
-    .block(Block::default().borders(Borders::ALL));
+    let mut hints = keymap::bar(keymap::HOME, |_| true);
+    hints.extend(keymap::bar(keymap::GLOBAL, |_| true));
+    let help = Paragraph::new(Line::from(key_hints(&hints)))
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
 
     f.render_widget(help, area);
 }
@@ -6,8 +6,20 @@ use ratatui::{
     Frame,
 };
 
+use std::time::Instant;
+
 use crate::app::AppState;
-use crate::clash::LogEntry;
+use crate::clash::{LogConnection, LogEntry};
+use crate::ui::i18n::{key_hints, t};
+use crate::ui::keymap;
+use crate::ui::list;
+use crate::ui::log_rules::LogRules;
+use crate::ui::search::SearchFilter;
+
+/// Column widths for connection log lines
+const SOURCE_WIDTH: usize = 21;
+const DESTINATION_WIDTH: usize = 32;
+const RULE_WIDTH: usize = 28;
 
 /// Log level filter
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -47,16 +59,35 @@ impl LogLevel {
     }
 }
 
+/// Log levels the core accepts, from quietest to most verbose
+pub const CORE_LOG_LEVELS: [&str; 5] = ["silent", "error", "warning", "info", "debug"];
+
+/// Core log level after `current` in `CORE_LOG_LEVELS`, wrapping around
+pub fn next_core_log_level(current: Option<&str>) -> &'static str {
+    let index = current
+        .and_then(|level| {
+            CORE_LOG_LEVELS
+                .iter()
+                .position(|l| l.eq_ignore_ascii_case(level))
+        })
+        .map_or(0, |index| (index + 1) % CORE_LOG_LEVELS.len());
+    CORE_LOG_LEVELS[index]
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn render(
     f: &mut Frame,
     area: Rect,
-    _state: &AppState,
+    state: &AppState,
     logs: &[LogEntry],
+    history: bool,
     level_filter: LogLevel,
     search_query: &str,
-    scroll_offset: usize,
+    selected_index: usize,
     stream_connected: bool,
     stream_status: Option<&str>,
+    retry: Option<(u32, Instant)>,
+    rules: &LogRules,
 ) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -70,66 +101,98 @@ pub fn render(
     render_filter_bar(
         f,
         chunks[0],
+        state.clash_state.log_level.as_deref(),
         level_filter,
         search_query,
+        history,
         stream_connected,
         stream_status,
+        retry,
     );
     render_logs_list(
         f,
         chunks[1],
         logs,
+        history,
         level_filter,
         search_query,
-        scroll_offset,
+        selected_index,
+        rules,
     );
     render_help(f, chunks[2]);
 }
 
+#[allow(clippy::too_many_arguments)]
 fn render_filter_bar(
     f: &mut Frame,
     area: Rect,
+    core_level: Option<&str>,
     level_filter: LogLevel,
     search_query: &str,
+    history: bool,
     stream_connected: bool,
     stream_status: Option<&str>,
+    retry: Option<(u32, Instant)>,
 ) {
     let is_connecting = matches!(stream_status, Some("connecting") | Some("reconnecting"));
-    let status_label = if stream_connected {
+    // Retry countdown while the reconnect manager waits out its backoff
+    let retry_label = retry.filter(|_| !stream_connected).map(|(attempt, at)| {
+        let left = at.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            format!("Reconnecting (attempt {})", attempt)
+        } else {
+            format!("Retrying in {}s (attempt {})", left.as_secs() + 1, attempt)
+        }
+    });
+    let status_label = if history {
+        "History (H for live)"
+    } else if stream_connected {
         "Live"
+    } else if let Some(label) = retry_label.as_deref() {
+        label
     } else if is_connecting {
         "Connecting"
     } else {
         "Disconnected"
     };
-    let status_color = if stream_connected {
+    let status_color = if history {
+        Color::Blue
+    } else if stream_connected {
         Color::Green
-    } else if is_connecting {
+    } else if is_connecting || retry_label.is_some() {
         Color::Yellow
     } else {
         Color::Red
     };
     let status_detail = match stream_status {
-        Some(detail) if !detail.is_empty() && !stream_connected && !is_connecting => Some(detail),
+        Some(detail) if !detail.is_empty() && !history && !stream_connected && !is_connecting => {
+            Some(detail)
+        }
         _ => None,
     };
 
-    let mut spans = Vec::new();
-    spans.push(Span::raw("Filter: "));
-    spans.push(Span::styled(
-        level_filter.as_str(),
-        Style::default()
-            .fg(level_filter.color())
-            .add_modifier(Modifier::BOLD),
-    ));
-    spans.push(Span::raw(" | "));
-    spans.push(Span::styled("● ", Style::default().fg(status_color)));
-    spans.push(Span::styled(
-        status_label,
-        Style::default()
-            .fg(status_color)
-            .add_modifier(Modifier::BOLD),
-    ));
+    let mut spans = vec![
+        Span::raw("Filter: "),
+        Span::styled(
+            level_filter.as_str(),
+            Style::default()
+                .fg(level_filter.color())
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(" | Core: "),
+        Span::styled(
+            core_level.unwrap_or("?").to_string(),
+            Style::default().fg(Color::Magenta),
+        ),
+        Span::raw(" | "),
+        Span::styled("● ", Style::default().fg(status_color)),
+        Span::styled(
+            status_label,
+            Style::default()
+                .fg(status_color)
+                .add_modifier(Modifier::BOLD),
+        ),
+    ];
     if let Some(detail) = status_detail {
         spans.push(Span::raw(" ("));
         spans.push(Span::styled(detail, Style::default().fg(Color::DarkGray)));
@@ -149,61 +212,73 @@ fn render_filter_bar(
 
     let filter = Paragraph::new(Line::from(spans))
         .alignment(Alignment::Left)
-        .block(Block::default().borders(Borders::ALL).title("Logs Filter"));
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t("title.logs")),
+        );
 
     f.render_widget(filter, area);
 }
 
+/// Whether a log entry passes the level filter and search query
+pub fn log_matches(log: &LogEntry, level_filter: LogLevel, search_query: &str) -> bool {
+    log_passes(log, level_filter, &SearchFilter::new(search_query))
+}
+
+fn log_passes(log: &LogEntry, level_filter: LogLevel, filter: &SearchFilter) -> bool {
+    // Filter by level
+    let level_match = match level_filter {
+        LogLevel::All => true,
+        LogLevel::Info => log.level.to_uppercase().contains("INFO"),
+        LogLevel::Warning => {
+            log.level.to_uppercase().contains("WARNING")
+                || log.level.to_uppercase().contains("WARN")
+        }
+        LogLevel::Error => log.level.to_uppercase().contains("ERROR"),
+    };
+
+    level_match && filter.matches([log.message.as_str(), log.level.as_str()])
+}
+
+/// Logs matching the level filter and search query, in display order
+pub fn filter_logs<'a>(
+    logs: &'a [LogEntry],
+    level_filter: LogLevel,
+    search_query: &str,
+) -> Vec<&'a LogEntry> {
+    let filter = SearchFilter::new(search_query);
+    logs.iter()
+        .filter(|log| log_passes(log, level_filter, &filter))
+        .collect()
+}
+
+#[allow(clippy::too_many_arguments)]
 fn render_logs_list(
     f: &mut Frame,
     area: Rect,
     logs: &[LogEntry],
+    history: bool,
     level_filter: LogLevel,
     search_query: &str,
-    scroll_offset: usize,
+    selected_index: usize,
+    rules: &LogRules,
 ) {
-    // Filter logs by level and search query
-    let filtered_logs: Vec<&LogEntry> = logs
-        .iter()
-        .filter(|log| {
-            // Filter by level
-            let level_match = match level_filter {
-                LogLevel::All => true,
-                LogLevel::Info => log.level.to_uppercase().contains("INFO"),
-                LogLevel::Warning => {
-                    log.level.to_uppercase().contains("WARNING")
-                        || log.level.to_uppercase().contains("WARN")
-                }
-                LogLevel::Error => log.level.to_uppercase().contains("ERROR"),
-            };
-
-            // Filter by search query
-            let search_match = if search_query.is_empty() {
-                true
-            } else {
-                log.message
-                    .to_lowercase()
-                    .contains(&search_query.to_lowercase())
-                    || log
-                        .level
-                        .to_lowercase()
-                        .contains(&search_query.to_lowercase())
-            };
+    let filtered_logs = filter_logs(logs, level_filter, search_query);
+    let filter = SearchFilter::new(search_query);
 
-            level_match && search_match
-        })
-        .collect();
-
-    let visible_count = (area.height as usize).saturating_sub(2); // Account for borders
-    let display_logs = filtered_logs
-        .iter()
-        .skip(scroll_offset)
-        .take(visible_count)
-        .collect::<Vec<_>>();
+    let visible_count = list::viewport(area, 1);
+    let selected_index = selected_index.min(filtered_logs.len().saturating_sub(1));
+    let window = list::window(
+        filtered_logs.len(),
+        list::follow(selected_index, 0, visible_count),
+        visible_count,
+    );
 
-    let items: Vec<ListItem> = display_logs
+    let items: Vec<ListItem> = filtered_logs[window.clone()]
         .iter()
-        .map(|log| {
+        .zip(window)
+        .map(|(log, i)| {
             let level_color = if log.level.to_uppercase().contains("ERROR") {
                 Color::Red
             } else if log.level.to_uppercase().contains("WARN") {
@@ -214,32 +289,44 @@ fn render_logs_list(
                 Color::Gray
             };
 
-            let line = Line::from(vec![
+            let mut spans = vec![
+                Span::raw(if i == selected_index { "▶ " } else { "  " }),
                 Span::styled(
                     format!("[{}] ", log.timestamp),
                     Style::default().fg(Color::DarkGray),
                 ),
                 Span::styled(
-                    format!("[{}] ", log.level),
+                    format!("[{:<7}] ", log.level),
                     Style::default()
                         .fg(level_color)
                         .add_modifier(Modifier::BOLD),
                 ),
-                Span::raw(&log.message),
-            ]);
+            ];
+            let mut message = match &log.connection {
+                Some(conn) => connection_spans(conn, &filter),
+                None => filter.highlight(&log.message, Style::default()),
+            };
+            // Highlight rules color the whole message, keeping search matches underlined
+            if let Some(color) = rules.color(log) {
+                for span in &mut message {
+                    span.style = span.style.fg(color);
+                }
+            }
+            spans.extend(message);
 
-            ListItem::new(line)
+            ListItem::new(Line::from(spans))
         })
         .collect();
 
+    let source = if history { "History" } else { "Logs" };
     let title = if filtered_logs.is_empty() {
-        "Logs (No logs available)".to_string()
+        format!("{} (No logs available)", source)
     } else {
         format!(
-            "Logs ({} entries, showing {}-{})",
-            filtered_logs.len(),
-            scroll_offset + 1,
-            (scroll_offset + visible_count).min(filtered_logs.len())
+            "{} ({}/{} entries)",
+            source,
+            selected_index + 1,
+            filtered_logs.len()
         )
     };
 
@@ -250,21 +337,47 @@ fn render_logs_list(
     f.render_widget(list, area);
 }
 
+/// Column-aligned network / source / destination / rule / proxy, with search matches highlighted
+fn connection_spans(conn: &LogConnection, filter: &SearchFilter) -> Vec<Span<'static>> {
+    let mut spans = vec![Span::styled(
+        format!("{:<4}", conn.network),
+        Style::default().fg(Color::Cyan),
+    )];
+    spans.extend(filter.highlight(
+        &fit(&conn.source, SOURCE_WIDTH),
+        Style::default().fg(Color::Green),
+    ));
+    spans.push(Span::raw(" → "));
+    spans.extend(filter.highlight(
+        &fit(&conn.destination, DESTINATION_WIDTH),
+        Style::default().fg(Color::Yellow),
+    ));
+    spans.extend(filter.highlight(
+        &fit(conn.rule.as_deref().unwrap_or("-"), RULE_WIDTH),
+        Style::default().fg(Color::Gray),
+    ));
+    spans.extend(filter.highlight(
+        conn.proxy.as_deref().unwrap_or("-"),
+        Style::default().fg(Color::Magenta),
+    ));
+    spans
+}
+
+/// Pad or truncate to exactly `width` characters plus a separating space
+pub(super) fn fit(text: &str, width: usize) -> String {
+    let count = text.chars().count();
+    if count > width {
+        let truncated: String = text.chars().take(width.saturating_sub(1)).collect();
+        format!("{}… ", truncated)
+    } else {
+        format!("{}{} ", text, " ".repeat(width - count))
+    }
+}
+
 fn render_help(f: &mut Frame, area: Rect) {
-    let help = Paragraph::new(Line::from(vec![
-        Span::styled("↑↓", Style::default().fg(Color::Yellow)),
-        Span::raw(" Scroll  "),
-        Span::styled("f", Style::default().fg(Color::Yellow)),
-        Span::raw(" Change Filter/Stream  "),
-        Span::styled("/", Style::default().fg(Color::Yellow)),
-        Span::raw(" Search  "),
-        Span::styled("r", Style::default().fg(Color::Yellow)),
-        Span::raw(" Reconnect  "),
-        Span::styled("q/ESC", Style::default().fg(Color::Yellow)),
-        Span::raw(" Back"),
-    ]))
-    .alignment(Alignment::Center)
-    .block(Block::default().borders(Borders::ALL));
+    let help = Paragraph::new(Line::from(key_hints(&keymap::bar(keymap::LOGS, |_| true))))
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
 
     f.render_widget(help, area);
 }
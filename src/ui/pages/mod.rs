@@ -1,17 +1,34 @@
 pub mod connections;
+pub mod favorites;
+pub mod groups;
+pub mod heatmap;
 pub mod home;
 pub mod logs;
 pub mod performance;
 pub mod routes;
 pub mod rules;
 pub mod settings;
+pub mod statistics;
 pub mod update;
 
-pub use connections::render as render_connections;
+pub use connections::{
+    connection_rates, connection_visible, group_by_rule, render as render_connections, rule_rows,
+    rule_targets as connection_rule_targets, rules_query, ConnectionChurn, ConnectionRefresh,
+    KindFilter, RuleGrouping, RuleRow,
+};
+pub use favorites::{groups_for_node as favorite_groups, render as render_favorites};
+pub use groups::{available_nodes as group_available_nodes, render as render_groups, GroupsAction};
+pub use heatmap::render as render_heatmap;
 pub use home::render as render_home;
-pub use logs::{render as render_logs, LogLevel};
-pub use performance::render as render_performance;
-pub use routes::{render as render_routes, render_with_nodes as render_routes_with_nodes};
-pub use rules::{render as render_rules, RuleEditMode, RuleListFocus};
-pub use settings::{render as render_settings, SettingsAction};
+pub use logs::{filter_logs, log_matches, render as render_logs, LogLevel};
+pub use performance::{exit_traffic, render as render_performance};
+pub use routes::{
+    render as render_routes, render_with_nodes as render_routes_with_nodes, HealthCheckEdit,
+    NodeSelection, RegionSelection,
+};
+pub use rules::{
+    render as render_rules, rule_set_target, RuleEditMode, RuleListFocus, RuleReorder, RuleSetView,
+};
+pub use settings::{render as render_settings, PortsEditor, SettingsAction};
+pub use statistics::render as render_statistics;
 pub use update::render as render_update;
@@ -6,10 +6,68 @@ use ratatui::{
     Frame,
 };
 
+use std::collections::HashMap;
+
+use super::logs::fit;
 use crate::app::AppState;
+use crate::clash::ConnectionsResponse;
+use crate::config::AlertConfig;
+use crate::ui::i18n::{key_hints, t};
+use crate::ui::keymap;
+
+/// Width of the proxy/group column of the traffic-by-exit table
+const EXIT_NAME_WIDTH: usize = 24;
+
+/// Traffic of the live connections leaving through one proxy or group
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExitTraffic {
+    pub name: String,
+    pub connections: usize,
+    pub upload: u64,
+    pub download: u64,
+    pub upload_rate: u64,
+    pub download_rate: u64,
+}
+
+/// Live connections summed per exit, busiest first
+/// The core lists a chain from the exit node out to the rule's group, so `by_group`
+/// groups by its last element and otherwise by its first
+pub fn exit_traffic(
+    connections: &ConnectionsResponse,
+    rates: &HashMap<String, (u64, u64)>,
+    by_group: bool,
+) -> Vec<ExitTraffic> {
+    let mut exits: HashMap<&str, ExitTraffic> = HashMap::new();
+    for conn in &connections.connections {
+        let exit = if by_group {
+            conn.chains.last()
+        } else {
+            conn.chains.first()
+        };
+        let name = exit.map_or("DIRECT", String::as_str);
+        let entry = exits.entry(name).or_insert_with(|| ExitTraffic {
+            name: name.to_string(),
+            ..Default::default()
+        });
+        entry.connections += 1;
+        entry.upload += conn.upload;
+        entry.download += conn.download;
+        if let Some((up, down)) = rates.get(&conn.id) {
+            entry.upload_rate += up;
+            entry.download_rate += down;
+        }
+    }
+    let mut exits: Vec<ExitTraffic> = exits.into_values().collect();
+    exits.sort_by(|a, b| {
+        (b.upload + b.download)
+            .cmp(&(a.upload + a.download))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    exits
+}
 
 /// Format bytes to human readable format
-fn format_bytes(bytes: u64) -> String {
+pub(super) fn format_bytes(bytes: u64) -> String {
     const KB: u64 = 1024;
     const MB: u64 = KB * 1024;
     const GB: u64 = MB * 1024;
@@ -26,7 +84,7 @@ fn format_bytes(bytes: u64) -> String {
 }
 
 /// Format rate to human readable format (bytes per second)
-fn format_rate(bytes_per_sec: u64) -> String {
+pub(super) fn format_rate(bytes_per_sec: u64) -> String {
     const KB: u64 = 1024;
     const MB: u64 = KB * 1024;
 
@@ -39,6 +97,7 @@ fn format_rate(bytes_per_sec: u64) -> String {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn render(
     f: &mut Frame,
     area: Rect,
@@ -48,20 +107,27 @@ pub fn render(
     upload_rate: u64,
     download_rate: u64,
     connection_count: usize,
+    alerts: &AlertConfig,
+    exits: &[ExitTraffic],
+    by_group: bool,
 ) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(3),  // Title
-            Constraint::Length(7),  // Traffic stats
-            Constraint::Length(10), // Rate graph
-            Constraint::Min(0),     // Connection info
-            Constraint::Length(3),  // Help
+            Constraint::Length(3), // Title
+            Constraint::Length(7), // Traffic stats + connection info
+            Constraint::Length(8), // Rate graph
+            Constraint::Min(0),    // Traffic by exit
+            Constraint::Length(3), // Help
         ])
         .split(area);
+    let top = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(chunks[1]);
 
     // Title
-    let title = Paragraph::new("Performance Monitor")
+    let title = Paragraph::new(t("title.performance"))
         .style(
             Style::default()
                 .fg(Color::Cyan)
@@ -72,26 +138,29 @@ pub fn render(
     f.render_widget(title, chunks[0]);
 
     // Traffic stats
-    render_traffic_stats(f, chunks[1], upload_total, download_total, connection_count);
+    render_traffic_stats(
+        f,
+        top[0],
+        upload_total,
+        download_total,
+        connection_count,
+        alerts,
+    );
 
     // Rate graph
-    render_rate_graph(f, chunks[2], upload_rate, download_rate);
+    render_rate_graph(f, chunks[2], upload_rate, download_rate, alerts);
 
     // Connection info
-    render_connection_info(f, chunks[3], connection_count);
+    render_connection_info(f, top[1], connection_count);
+
+    render_exit_traffic(f, chunks[3], exits, by_group);
 
     // Help
-    let help = Paragraph::new(Line::from(vec![
-        Span::styled("r", Style::default().fg(Color::Yellow)),
-        Span::raw(" Refresh  "),
-        Span::styled("c", Style::default().fg(Color::Yellow)),
-        Span::raw(" Connections  "),
-        Span::styled("q/ESC", Style::default().fg(Color::Yellow)),
-        Span::raw(" Back  "),
-        Span::raw("Auto-refresh: Every 5s"),
-    ]))
-    .alignment(Alignment::Center)
-    .block(Block::default().borders(Borders::ALL));
+    let mut help_spans = key_hints(&keymap::bar(keymap::PERFORMANCE, |_| true));
+    help_spans.push(Span::raw(format!("  {}", t("help.auto_refresh"))));
+    let help = Paragraph::new(Line::from(help_spans))
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
     f.render_widget(help, chunks[4]);
 }
 
@@ -101,7 +170,28 @@ fn render_traffic_stats(
     upload_total: u64,
     download_total: u64,
     connection_count: usize,
+    alerts: &AlertConfig,
 ) {
+    let mut connection_spans = vec![
+        Span::styled("Connections:    ", Style::default().fg(Color::Gray)),
+        Span::styled(
+            format!("{}", connection_count),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ),
+    ];
+    if let Some(limit) = alerts
+        .connections
+        .filter(|_| alerts.connections_exceeded(connection_count))
+    {
+        connection_spans[1].style = connection_spans[1].style.fg(Color::Red);
+        connection_spans.push(Span::styled(
+            format!("  ⚠ over {}", limit),
+            Style::default().fg(Color::Red),
+        ));
+    }
+
     let stats = Paragraph::new(vec![
         Line::from(""),
         Line::from(vec![
@@ -122,25 +212,23 @@ fn render_traffic_stats(
                     .add_modifier(Modifier::BOLD),
             ),
         ]),
-        Line::from(vec![
-            Span::styled("Connections:    ", Style::default().fg(Color::Gray)),
-            Span::styled(
-                format!("{}", connection_count),
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            ),
-        ]),
+        Line::from(connection_spans),
     ])
     .block(
         Block::default()
             .borders(Borders::ALL)
-            .title("Traffic Statistics"),
+            .title(t("block.traffic")),
     );
     f.render_widget(stats, area);
 }
 
-fn render_rate_graph(f: &mut Frame, area: Rect, upload_rate: u64, download_rate: u64) {
+fn render_rate_graph(
+    f: &mut Frame,
+    area: Rect,
+    upload_rate: u64,
+    download_rate: u64,
+    alerts: &AlertConfig,
+) {
     // Simple text-based visualization
     let max_rate = upload_rate.max(download_rate);
     let max_display = if max_rate == 0 { 100 } else { max_rate };
@@ -151,6 +239,33 @@ fn render_rate_graph(f: &mut Frame, area: Rect, upload_rate: u64, download_rate:
     let upload_bar = "█".repeat(upload_bars);
     let download_bar = "█".repeat(download_bars);
 
+    // A download rate over the alert threshold turns red
+    let download_exceeded = alerts.download_exceeded(download_rate);
+    let download_color = if download_exceeded {
+        Color::Red
+    } else {
+        Color::Green
+    };
+    let mut download_spans = vec![
+        Span::styled("Download: ", Style::default().fg(Color::Gray)),
+        Span::styled(
+            format!("{:<40}", download_bar),
+            Style::default().fg(download_color),
+        ),
+        Span::styled(
+            format!(" {}", format_rate(download_rate)),
+            Style::default()
+                .fg(download_color)
+                .add_modifier(Modifier::BOLD),
+        ),
+    ];
+    if let Some(limit) = alerts.download_rate_mb.filter(|_| download_exceeded) {
+        download_spans.push(Span::styled(
+            format!("  ⚠ over {} MB/s", limit),
+            Style::default().fg(Color::Red),
+        ));
+    }
+
     let graph = Paragraph::new(vec![
         Line::from(""),
         Line::from(vec![
@@ -167,19 +282,7 @@ fn render_rate_graph(f: &mut Frame, area: Rect, upload_rate: u64, download_rate:
             ),
         ]),
         Line::from(""),
-        Line::from(vec![
-            Span::styled("Download: ", Style::default().fg(Color::Gray)),
-            Span::styled(
-                format!("{:<40}", download_bar),
-                Style::default().fg(Color::Green),
-            ),
-            Span::styled(
-                format!(" {}", format_rate(download_rate)),
-                Style::default()
-                    .fg(Color::Green)
-                    .add_modifier(Modifier::BOLD),
-            ),
-        ]),
+        Line::from(download_spans),
         Line::from(""),
         Line::from(vec![
             Span::styled("Scale: ", Style::default().fg(Color::DarkGray)),
@@ -189,7 +292,11 @@ fn render_rate_graph(f: &mut Frame, area: Rect, upload_rate: u64, download_rate:
             ),
         ]),
     ])
-    .block(Block::default().borders(Borders::ALL).title("Current Rate"));
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(t("block.current_rate")),
+    );
     f.render_widget(graph, area);
 }
 
@@ -238,8 +345,88 @@ fn render_connection_info(f: &mut Frame, area: Rect, connection_count: usize) {
     .block(
         Block::default()
             .borders(Borders::ALL)
-            .title("Connection Info"),
+            .title(t("block.connection_info")),
     )
     .alignment(Alignment::Left);
     f.render_widget(info, area);
 }
+
+/// Table of `exit_traffic`, as many rows as fit, then the totals
+fn render_exit_traffic(f: &mut Frame, area: Rect, exits: &[ExitTraffic], by_group: bool) {
+    let row = |name: &str, count: usize, up: u64, down: u64, up_rate: u64, down_rate: u64| {
+        format!(
+            "{}{:>5} {:>11} {:>11} {:>12} {:>12}",
+            fit(name, EXIT_NAME_WIDTH),
+            count,
+            format_bytes(up),
+            format_bytes(down),
+            format_rate(up_rate),
+            format_rate(down_rate)
+        )
+    };
+    let mut lines = vec![Line::from(Span::styled(
+        format!(
+            "{}{:>5} {:>11} {:>11} {:>12} {:>12}",
+            fit(
+                if by_group { "Group" } else { "Exit Node" },
+                EXIT_NAME_WIDTH
+            ),
+            "Conns",
+            "Upload",
+            "Download",
+            "↑ Rate",
+            "↓ Rate"
+        ),
+        Style::default().fg(Color::Gray),
+    ))];
+
+    if exits.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "No active connections",
+            Style::default().fg(Color::DarkGray),
+        )));
+    } else {
+        // Borders and the header take three rows; the totals one more when shown
+        let space = (area.height as usize).saturating_sub(3);
+        let with_total = exits.len() > 1 && space > 1;
+        let rows = if with_total && exits.len() >= space {
+            space - 1
+        } else {
+            space
+        };
+        for exit in exits.iter().take(rows) {
+            lines.push(Line::from(Span::raw(row(
+                &exit.name,
+                exit.connections,
+                exit.upload,
+                exit.download,
+                exit.upload_rate,
+                exit.download_rate,
+            ))));
+        }
+        let total = |field: fn(&ExitTraffic) -> u64| exits.iter().map(field).sum::<u64>();
+        if with_total {
+            lines.push(Line::from(Span::styled(
+                row(
+                    &format!("Total ({})", exits.len()),
+                    exits.iter().map(|exit| exit.connections).sum(),
+                    total(|exit| exit.upload),
+                    total(|exit| exit.download),
+                    total(|exit| exit.upload_rate),
+                    total(|exit| exit.download_rate),
+                ),
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )));
+        }
+    }
+
+    let title = if by_group {
+        "Traffic by Group"
+    } else {
+        "Traffic by Exit Node"
+    };
+    let table = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(table, area);
+}
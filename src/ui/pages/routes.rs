@@ -1,14 +1,34 @@
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
+    symbols::Marker,
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    widgets::{Axis, Block, Borders, Chart, Dataset, GraphType, List, ListItem, Paragraph},
     Frame,
 };
 
+use chrono::{DateTime, Local};
+use std::collections::{HashMap, HashSet};
+
+use crate::app::node_info::{self, NodeOrigins};
+use crate::app::region::{self, RegionGroup, RegionRow};
 use crate::app::{AppState, Mode};
-use crate::clash::HumanRoute;
+use crate::clash::{HumanRoute, ProxyType};
+use crate::config::delays::DelaySample;
 use crate::config::{AppConfig, Preset};
+use crate::ui::i18n::{key_hints, t};
+use crate::ui::keymap::{self, Needs};
+use crate::ui::list;
+use crate::ui::search::SearchFilter;
+
+/// Width of the node info panel, shown when the node list area is at least
+/// `NODE_INFO_MIN_WIDTH` wide
+const NODE_INFO_WIDTH: u16 = 40;
+const NODE_INFO_MIN_WIDTH: u16 = 100;
+/// Hours of delay history charted under the node info
+const DELAY_CHART_HOURS: i64 = 24;
+/// Rows the delay chart needs to be worth drawing
+const DELAY_CHART_MIN_HEIGHT: u16 = 7;
 
 pub fn render(
     f: &mut Frame,
@@ -16,8 +36,46 @@ pub fn render(
     state: &AppState,
     config: &AppConfig,
     selected_index: usize,
+    edit_mode: bool,
 ) {
-    render_normal_view(f, area, state, config, selected_index);
+    render_normal_view(f, area, state, config, selected_index, edit_mode);
+}
+
+/// Node selection state for the expanded view
+pub struct NodeSelection<'a> {
+    pub index: usize,
+    /// Nodes marked for batch actions
+    pub marked: &'a [String],
+    /// Group name being typed for a batch add, if the prompt is open
+    pub group_input: Option<&'a str>,
+    /// Health check being edited, if the editor is open
+    pub health_edit: Option<&'a HealthCheckEdit>,
+    /// Find-as-you-type query, if the search prompt is open
+    pub search: Option<&'a str>,
+    /// Set when the nodes are grouped by region
+    pub regions: Option<RegionSelection<'a>>,
+    /// Subscriptions the nodes are defined in, once read
+    pub origins: Option<&'a NodeOrigins>,
+    /// When each node was switched to this session
+    pub selected_at: &'a HashMap<String, DateTime<Local>>,
+    /// Delay history file, read when a group is opened
+    pub delay_history: &'a [DelaySample],
+}
+
+/// Collapsed sections and the cursor of the region-grouped node list
+pub struct RegionSelection<'a> {
+    pub collapsed: &'a HashSet<String>,
+    /// Header under the cursor; when None the cursor is on the selected node
+    pub header: Option<&'a str>,
+}
+
+/// Editor for the `url`/`interval` of a url-test/fallback group
+pub struct HealthCheckEdit {
+    pub group: String,
+    pub url: String,
+    pub interval: String,
+    /// Whether the interval field has focus (else the URL)
+    pub on_interval: bool,
 }
 
 pub fn render_with_nodes(
@@ -26,17 +84,18 @@ pub fn render_with_nodes(
     state: &AppState,
     config: &AppConfig,
     route_index: usize,
-    node_index: usize,
+    selection: &NodeSelection,
 ) {
-    render_expanded_view(f, area, state, config, route_index, node_index);
+    render_expanded_view(f, area, state, config, route_index, selection);
 }
 
 fn render_normal_view(
     f: &mut Frame,
     area: Rect,
     state: &AppState,
-    _config: &AppConfig,
+    config: &AppConfig,
     selected_index: usize,
+    edit_mode: bool,
 ) {
     let constraints = if state.status_message.is_some() {
         vec![
@@ -59,7 +118,14 @@ fn render_normal_view(
         .split(area);
 
     let mut chunk_idx = 0;
-    render_title(f, chunks[chunk_idx], state.mode, &state.preset, false);
+    let title_suffix = if edit_mode { Some("Edit Layout") } else { None };
+    render_title(
+        f,
+        chunks[chunk_idx],
+        state.mode,
+        &state.preset,
+        title_suffix,
+    );
     chunk_idx += 1;
 
     if let Some(msg) = &state.status_message {
@@ -67,10 +133,21 @@ fn render_normal_view(
         chunk_idx += 1;
     }
 
-    render_routes(f, chunks[chunk_idx], state, selected_index);
+    render_routes(
+        f,
+        chunks[chunk_idx],
+        state,
+        config,
+        selected_index,
+        edit_mode,
+    );
     chunk_idx += 1;
 
-    render_help(f, chunks[chunk_idx], state.mode, &state.preset, false);
+    if edit_mode {
+        render_edit_help(f, chunks[chunk_idx]);
+    } else {
+        render_help(f, chunks[chunk_idx], state.mode, &state.preset, false, None);
+    }
 }
 
 fn render_expanded_view(
@@ -79,20 +156,26 @@ fn render_expanded_view(
     state: &AppState,
     config: &AppConfig,
     route_index: usize,
-    node_index: usize,
+    selection: &NodeSelection,
 ) {
+    // The health check editor needs a line per field plus its hints
+    let help_height = if selection.health_edit.is_some() {
+        5
+    } else {
+        4
+    };
     let constraints = if state.status_message.is_some() {
         vec![
-            Constraint::Length(3), // Title
-            Constraint::Length(3), // Status message
-            Constraint::Min(0),    // Node list
-            Constraint::Length(4), // Help
+            Constraint::Length(3),           // Title
+            Constraint::Length(3),           // Status message
+            Constraint::Min(0),              // Node list
+            Constraint::Length(help_height), // Help
         ]
     } else {
         vec![
-            Constraint::Length(3), // Title
-            Constraint::Min(0),    // Node list
-            Constraint::Length(4), // Help
+            Constraint::Length(3),           // Title
+            Constraint::Min(0),              // Node list
+            Constraint::Length(help_height), // Help
         ]
     };
 
@@ -102,7 +185,13 @@ fn render_expanded_view(
         .split(area);
 
     let mut chunk_idx = 0;
-    render_title(f, chunks[chunk_idx], state.mode, &state.preset, true);
+    render_title(
+        f,
+        chunks[chunk_idx],
+        state.mode,
+        &state.preset,
+        Some("Node Selection"),
+    );
     chunk_idx += 1;
 
     if let Some(msg) = &state.status_message {
@@ -110,17 +199,55 @@ fn render_expanded_view(
         chunk_idx += 1;
     }
 
-    render_nodes(f, chunks[chunk_idx], state, config, route_index, node_index);
+    // The info panel takes the right side on wide terminals
+    let list_area = if chunks[chunk_idx].width >= NODE_INFO_MIN_WIDTH {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Length(NODE_INFO_WIDTH)])
+            .split(chunks[chunk_idx]);
+        render_node_info(f, columns[1], state, config, route_index, selection);
+        columns[0]
+    } else {
+        chunks[chunk_idx]
+    };
+    render_nodes(f, list_area, state, config, route_index, selection);
     chunk_idx += 1;
 
-    render_help(f, chunks[chunk_idx], state.mode, &state.preset, true);
+    if let Some(edit) = selection.health_edit {
+        render_health_check_editor(f, chunks[chunk_idx], edit);
+        return;
+    }
+    if let Some(query) = selection.search {
+        render_search_prompt(f, chunks[chunk_idx], query);
+        return;
+    }
+    match selection.group_input {
+        Some(input) => render_group_prompt(f, chunks[chunk_idx], input, selection.marked.len()),
+        None if !selection.marked.is_empty() => {
+            render_batch_help(f, chunks[chunk_idx], &state.preset)
+        }
+        None => {
+            let routes = config.arrange_routes(
+                HumanRoute::from_proxies(&state.clash_state.proxies, state.mode),
+                false,
+            );
+            render_help(
+                f,
+                chunks[chunk_idx],
+                state.mode,
+                &state.preset,
+                true,
+                routes.get(route_index),
+            )
+        }
+    }
 }
 
-fn render_title(f: &mut Frame, area: Rect, _mode: Mode, preset: &Preset, expanded: bool) {
-    let title_text = if expanded {
-        format!("Route Management [{}] - Node Selection", preset.name())
+fn render_title(f: &mut Frame, area: Rect, _mode: Mode, preset: &Preset, suffix: Option<&str>) {
+    let title_text = if let Some(suffix) = suffix {
+        format!("{} [{}] - {}", t("title.routes"), preset.name(), suffix)
     } else {
-        format!("Route Management [{}]", preset.name())
+        format!("{} [{}]", t("title.routes"), preset.name())
     };
 
     let title = Paragraph::new(title_text)
@@ -142,13 +269,27 @@ fn render_status(f: &mut Frame, area: Rect, message: &str) {
     f.render_widget(status, area);
 }
 
-fn render_routes(f: &mut Frame, area: Rect, state: &AppState, selected_index: usize) {
-    let routes = HumanRoute::from_proxies(&state.clash_state.proxies, state.mode);
+fn render_routes(
+    f: &mut Frame,
+    area: Rect,
+    state: &AppState,
+    config: &AppConfig,
+    selected_index: usize,
+    edit_mode: bool,
+) {
+    let routes = config.arrange_routes(
+        HumanRoute::from_proxies(&state.clash_state.proxies, state.mode),
+        edit_mode,
+    );
 
     if routes.is_empty() {
         let empty = Paragraph::new("No routes available")
             .alignment(Alignment::Center)
-            .block(Block::default().borders(Borders::ALL).title("Routes"));
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(t("block.routes")),
+            );
         f.render_widget(empty, area);
         return;
     }
@@ -162,7 +303,36 @@ fn render_routes(f: &mut Frame, area: Rect, state: &AppState, selected_index: us
             let current_display = route.current_display();
             let node_count = format!(" ({} nodes)", route.node_count);
 
-            let content = if is_selected {
+            if edit_mode {
+                let is_hidden = config.is_group_hidden(&route.name);
+                let name_style = if is_hidden {
+                    Style::default().fg(Color::DarkGray)
+                } else if is_selected {
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                let mut spans = vec![
+                    Span::styled(
+                        if is_selected { "> " } else { "  " },
+                        Style::default().fg(Color::Yellow),
+                    ),
+                    Span::styled(
+                        format!("{:>2}. ", i + 1),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::styled(display_name, name_style),
+                    Span::styled(node_count, Style::default().fg(Color::DarkGray)),
+                ];
+                if is_hidden {
+                    spans.push(Span::styled(" [hidden]", Style::default().fg(Color::Red)));
+                }
+                return ListItem::new(Line::from(spans));
+            }
+
+            let mut content = if is_selected {
                 Line::from(vec![
                     Span::styled("> ", Style::default().fg(Color::Yellow)),
                     Span::styled(
@@ -188,16 +358,37 @@ fn render_routes(f: &mut Frame, area: Rect, state: &AppState, selected_index: us
                     Span::styled(node_count, Style::default().fg(Color::DarkGray)),
                 ])
             };
+            if state.clash_state.current_selector.as_deref() == Some(route.name.as_str()) {
+                content.spans.push(Span::styled(
+                    " [primary]",
+                    Style::default().fg(Color::Magenta),
+                ));
+            }
+            content.spans.extend(group_delay_spans(state, route));
 
             ListItem::new(content)
         })
         .collect();
 
-    let list = List::new(items).block(Block::default().borders(Borders::ALL).title(format!(
-        "Routes ({}/{}) - Press Enter to view nodes",
-        selected_index + 1,
-        routes.len()
-    )));
+    let title = if edit_mode {
+        format!(
+            "Routes ({}/{}) - {} hidden",
+            selected_index + 1,
+            routes.len(),
+            routes
+                .iter()
+                .filter(|route| config.is_group_hidden(&route.name))
+                .count()
+        )
+    } else {
+        format!(
+            "Routes ({}/{}) - Press Enter to view nodes",
+            selected_index + 1,
+            routes.len()
+        )
+    };
+
+    let list = List::new(items).block(Block::default().borders(Borders::ALL).title(title));
 
     f.render_widget(list, area);
 }
@@ -208,9 +399,12 @@ fn render_nodes(
     state: &AppState,
     config: &AppConfig,
     route_index: usize,
-    node_index: usize,
+    selection: &NodeSelection,
 ) {
-    let routes = HumanRoute::from_proxies(&state.clash_state.proxies, state.mode);
+    let routes = config.arrange_routes(
+        HumanRoute::from_proxies(&state.clash_state.proxies, state.mode),
+        false,
+    );
 
     if route_index >= routes.len() {
         let empty = Paragraph::new("No routes available")
@@ -231,170 +425,561 @@ fn render_nodes(
         return;
     }
 
-    let visible_items = area.height.saturating_sub(2).max(1) as usize;
-    let selected_index = node_index.min(nodes.len().saturating_sub(1));
-    let mut start_index = 0usize;
-    if nodes.len() > visible_items {
-        if selected_index >= visible_items {
-            start_index = selected_index + 1 - visible_items;
+    let search = SearchFilter::new(selection.search.unwrap_or_default());
+    let visible_items = list::viewport(area, 1);
+    let selected_index = selection.index.min(nodes.len().saturating_sub(1));
+
+    let node_item = |i: usize, is_selected: bool, indent: &'static str| {
+        let node = &nodes[i];
+        let is_current = route.current_node.as_ref() == Some(node);
+        let is_testing = state.is_testing(node);
+        let cached_delay = state.get_delay(node);
+        let is_favorite = config.is_favorite(node);
+        let is_marked = selection.marked.contains(node);
+
+        let (prefix, style) = if is_selected && is_current {
+            (
+                "▶ ✓ ",
+                Style::default()
+                    .fg(Color::Green)
+                    .add_modifier(Modifier::BOLD),
+            )
+        } else if is_selected {
+            (
+                "▶   ",
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+        } else if is_current {
+            ("  ✓ ", Style::default().fg(Color::Green))
+        } else {
+            ("    ", Style::default().fg(Color::White))
+        };
+
+        let mut spans = vec![Span::raw(indent), Span::styled(prefix, style)];
+
+        if is_marked {
+            spans.push(Span::styled("◆ ", Style::default().fg(Color::Magenta)));
         }
-        let max_start = nodes.len().saturating_sub(visible_items);
-        if start_index > max_start {
-            start_index = max_start;
-        }
-    }
-    let end_index = (start_index + visible_items).min(nodes.len());
 
-    let items: Vec<ListItem> = nodes
-        .iter()
-        .enumerate()
-        .skip(start_index)
-        .take(end_index.saturating_sub(start_index))
-        .map(|(i, node)| {
-            let is_current = route.current_node.as_ref() == Some(node);
-            let is_selected = i == selected_index;
-            let is_testing = state.is_testing(node);
-            let cached_delay = state.get_delay(node);
-            let is_favorite = config.is_favorite(node);
+        // Add favorite indicator
+        if is_favorite {
+            spans.push(Span::styled("★ ", Style::default().fg(Color::Yellow)));
+        }
 
-            let (prefix, style) = if is_selected && is_current {
-                (
-                    "▶ ✓ ",
-                    Style::default()
-                        .fg(Color::Green)
-                        .add_modifier(Modifier::BOLD),
-                )
-            } else if is_selected {
-                (
-                    "▶   ",
-                    Style::default()
-                        .fg(Color::Yellow)
-                        .add_modifier(Modifier::BOLD),
-                )
-            } else if is_current {
-                ("  ✓ ", Style::default().fg(Color::Green))
-            } else {
-                ("    ", Style::default().fg(Color::White))
-            };
+        spans.extend(search.highlight(node, style));
 
-            let mut spans = vec![Span::styled(prefix, style)];
+        if route.fixed_node.as_ref() == Some(node) {
+            spans.push(Span::styled(" [pinned]", Style::default().fg(Color::Cyan)));
+        }
 
-            // Add favorite indicator
-            if is_favorite {
-                spans.push(Span::styled("★ ", Style::default().fg(Color::Yellow)));
-            }
+        let origins = selection
+            .origins
+            .map(|origins| origins.of(node))
+            .unwrap_or_default();
+        if node_info::udp_support(state.clash_state.proxies.get(node), origins) == Some(true) {
+            spans.push(Span::styled(" UDP", Style::default().fg(Color::Blue)));
+        }
 
-            spans.push(Span::styled(node.clone(), style));
+        // Show delay info if available
+        if is_testing {
+            spans.push(Span::styled(
+                " [Testing...]",
+                Style::default().fg(Color::Yellow),
+            ));
+        } else if let Some(delay_result) = cached_delay {
+            let delay = delay_result.delay;
+            let stale = state.is_delay_stale(delay_result);
+            let delay_style = if stale {
+                Style::default().fg(Color::DarkGray)
+            } else if delay < 200 {
+                Style::default().fg(Color::Green)
+            } else if delay < 500 {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default().fg(Color::Red)
+            };
 
-            // Show delay info if available
-            if is_testing {
-                spans.push(Span::styled(
-                    " [Testing...]",
-                    Style::default().fg(Color::Yellow),
-                ));
-            } else if let Some(delay_result) = cached_delay {
-                let delay = delay_result.delay;
-                let delay_style = if delay < 200 {
-                    Style::default().fg(Color::Green)
-                } else if delay < 500 {
-                    Style::default().fg(Color::Yellow)
-                } else {
-                    Style::default().fg(Color::Red)
-                };
+            let label = if delay < 200 {
+                "⚡Fast"
+            } else if delay < 500 {
+                "Good"
+            } else {
+                "Slow"
+            };
+            let delay_text = if stale {
+                format!(" [{}ms {}, stale]", delay, label)
+            } else {
+                format!(" [{}ms {}]", delay, label)
+            };
 
-                let delay_text = if delay < 200 {
-                    format!(" [{}ms ⚡Fast]", delay)
-                } else if delay < 500 {
-                    format!(" [{}ms Good]", delay)
-                } else {
-                    format!(" [{}ms Slow]", delay)
-                };
+            spans.push(Span::styled(delay_text, delay_style));
+        }
 
-                spans.push(Span::styled(delay_text, delay_style));
-            }
+        ListItem::new(Line::from(spans))
+    };
 
-            let content = Line::from(spans);
-            ListItem::new(content)
-        })
-        .collect();
+    let (items, position): (Vec<ListItem>, String) = match &selection.regions {
+        Some(regions) => {
+            let groups = region::group_by_region(nodes);
+            let rows = region::region_rows(&groups, regions.collapsed);
+            let cursor = rows
+                .iter()
+                .position(|row| match row {
+                    RegionRow::Header(group) => regions.header == Some(groups[*group].region),
+                    RegionRow::Node(node) => regions.header.is_none() && *node == selected_index,
+                })
+                .unwrap_or(0);
+            let window = list::window(
+                rows.len(),
+                list::follow(cursor, 0, visible_items),
+                visible_items,
+            );
+            let items = rows[window.clone()]
+                .iter()
+                .zip(window)
+                .map(|(row, i)| match row {
+                    RegionRow::Header(group) => region_header(
+                        state,
+                        nodes,
+                        &groups[*group],
+                        regions.collapsed.contains(groups[*group].region),
+                        i == cursor,
+                    ),
+                    RegionRow::Node(node) => node_item(*node, i == cursor, "  "),
+                })
+                .collect();
+            (items, format!("{} regions", groups.len()))
+        }
+        None => {
+            let window = list::window(
+                nodes.len(),
+                list::follow(selected_index, 0, visible_items),
+                visible_items,
+            );
+            let items = window
+                .map(|i| node_item(i, i == selected_index, ""))
+                .collect();
+            (items, format!("{}/{}", selected_index + 1, nodes.len()))
+        }
+    };
 
-    let title_text = if state.preset.show_speed_test() {
+    let mut title_text = if state.preset.show_speed_test() {
         format!(
-            "{} - Nodes ({}/{}) - Press 't' to test",
+            "{} - Nodes ({}) - Press 't' to test",
             route.display_name(),
-            selected_index + 1,
-            nodes.len()
+            position
         )
     } else {
-        format!(
-            "{} - Nodes ({}/{})",
-            route.display_name(),
-            selected_index + 1,
-            nodes.len()
-        )
+        format!("{} - Nodes ({})", route.display_name(), position)
     };
+    if route.can_pin() {
+        title_text.push_str(match route.fixed_node {
+            Some(_) => " - pinned, 'u' to unpin",
+            None => " - auto, Enter pins",
+        });
+    }
+    if !selection.marked.is_empty() {
+        title_text.push_str(&format!(" - {} marked", selection.marked.len()));
+    }
 
     let list = List::new(items).block(Block::default().borders(Borders::ALL).title(title_text));
 
     f.render_widget(list, area);
 }
 
-fn render_help(f: &mut Frame, area: Rect, _mode: Mode, preset: &Preset, expanded: bool) {
-    let mut help_spans = vec![];
-
-    if expanded {
-        // Node selection mode help
-        help_spans.extend(vec![
-            Span::styled("↑↓", Style::default().fg(Color::Yellow)),
-            Span::raw(" Navigate  "),
-            Span::styled("Enter", Style::default().fg(Color::Yellow)),
-            Span::raw(" Switch  "),
-            Span::styled("*", Style::default().fg(Color::Yellow)),
-            Span::raw(" Favorite  "),
-        ]);
-
-        // Show speed test only if preset allows
-        if preset.show_speed_test() {
-            help_spans.extend(vec![
-                Span::styled("t", Style::default().fg(Color::Yellow)),
-                Span::raw(" Test All  "),
-            ]);
+/// Type, server, UDP, source and history of the node under the cursor
+fn render_node_info(
+    f: &mut Frame,
+    area: Rect,
+    state: &AppState,
+    config: &AppConfig,
+    route_index: usize,
+    selection: &NodeSelection,
+) {
+    let routes = config.arrange_routes(
+        HumanRoute::from_proxies(&state.clash_state.proxies, state.mode),
+        false,
+    );
+    // A region header under the cursor has no node to describe
+    let on_header = selection
+        .regions
+        .as_ref()
+        .is_some_and(|regions| regions.header.is_some());
+    let node = routes
+        .get(route_index)
+        .and_then(|route| route.all_nodes.get(selection.index))
+        .filter(|_| !on_header);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(t("block.node_info"));
+    let Some(node) = node else {
+        f.render_widget(block, area);
+        return;
+    };
+
+    let proxy = state.clash_state.proxies.get(node);
+    let origins = selection
+        .origins
+        .map(|origins| origins.of(node))
+        .unwrap_or_default();
+    let muted = Style::default().fg(Color::DarkGray);
+    let field = |label: &str, value: Span<'static>| {
+        Line::from(vec![Span::styled(format!("{:<9}", label), muted), value])
+    };
+    let unknown = || Span::styled("unknown", muted);
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            node.clone(),
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        field(
+            "Type",
+            proxy.map_or_else(unknown, |proxy| {
+                Span::raw(format!("{:?}", proxy.proxy_type))
+            }),
+        ),
+        field(
+            "Server",
+            origins
+                .iter()
+                .find_map(|origin| origin.address())
+                .map_or_else(unknown, Span::raw),
+        ),
+    ];
+    lines.push(field(
+        "UDP",
+        match node_info::udp_support(proxy, origins) {
+            Some(true) => Span::styled("yes", Style::default().fg(Color::Green)),
+            Some(false) => Span::styled("no", Style::default().fg(Color::Red)),
+            None => unknown(),
+        },
+    ));
+    let source = match (selection.origins, origins) {
+        (None, _) => Span::styled("reading...", muted),
+        (Some(_), []) => unknown(),
+        (Some(_), [only]) => Span::raw(only.source.clone()),
+        (Some(_), [first, rest @ ..]) => {
+            Span::raw(format!("{} (+{} more)", first.source, rest.len()))
         }
+    };
+    lines.push(field("Source", source));
+
+    let now = Local::now();
+    lines.push(field(
+        "Selected",
+        match selection.selected_at.get(node) {
+            Some(at) => Span::raw(format!(
+                "{} ({})",
+                at.format("%H:%M:%S"),
+                node_info::ago((now - *at).num_seconds())
+            )),
+            None => Span::styled("not this session", muted),
+        },
+    ));
+    lines.push(field(
+        "Tested",
+        match node_info::last_tested(proxy, state.delay_cache.get(node)) {
+            Some((at, delay)) => {
+                let ago = node_info::ago(now.timestamp() - at);
+                match delay {
+                    Some(delay) => Span::raw(format!("{}ms, {}", delay, ago)),
+                    None => {
+                        Span::styled(format!("timeout, {}", ago), Style::default().fg(Color::Red))
+                    }
+                }
+            }
+            None => Span::styled("never", muted),
+        },
+    ));
+
+    let info_height = lines.len() as u16 + 2;
+    if area.height < info_height + DELAY_CHART_MIN_HEIGHT {
+        f.render_widget(Paragraph::new(lines).block(block), area);
+        return;
+    }
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(info_height), Constraint::Min(0)])
+        .split(area);
+    f.render_widget(Paragraph::new(lines).block(block), rows[0]);
+    let since = now.timestamp() - DELAY_CHART_HOURS * 3600;
+    let series = node_info::delay_series(selection.delay_history, proxy, node, since);
+    render_delay_chart(f, rows[1], &series);
+}
+
+/// Line chart of a node's delay tests; timeouts are red dots along the top
+fn render_delay_chart(f: &mut Frame, area: Rect, series: &[(i64, Option<u32>)]) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("Delay, last {}h", DELAY_CHART_HOURS));
+    let (Some(first), Some(last)) = (series.first(), series.last()) else {
+        let empty = Paragraph::new("No delay tests yet")
+            .style(Style::default().fg(Color::DarkGray))
+            .alignment(Alignment::Center)
+            .block(block);
+        f.render_widget(empty, area);
+        return;
+    };
+
+    let ceiling = series
+        .iter()
+        .filter_map(|(_, delay)| *delay)
+        .max()
+        .unwrap_or(0)
+        .max(100) as f64;
+    let delays: Vec<(f64, f64)> = series
+        .iter()
+        .filter_map(|(time, delay)| Some((*time as f64, (*delay)? as f64)))
+        .collect();
+    let timeouts: Vec<(f64, f64)> = series
+        .iter()
+        .filter(|(_, delay)| delay.is_none())
+        .map(|(time, _)| (*time as f64, ceiling))
+        .collect();
+    let datasets = vec![
+        Dataset::default()
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(Color::Cyan))
+            .data(&delays),
+        Dataset::default()
+            .marker(Marker::Dot)
+            .graph_type(GraphType::Scatter)
+            .style(Style::default().fg(Color::Red))
+            .data(&timeouts),
+    ];
+
+    let muted = Style::default().fg(Color::DarkGray);
+    let clock = |time: i64| {
+        DateTime::from_timestamp(time, 0)
+            .map(|at| at.with_timezone(&Local).format("%H:%M").to_string())
+            .unwrap_or_default()
+    };
+    let chart = Chart::new(datasets)
+        .block(block)
+        .x_axis(
+            Axis::default()
+                .style(muted)
+                .bounds([first.0 as f64, (last.0 as f64).max(first.0 as f64 + 1.0)])
+                .labels(vec![Span::raw(clock(first.0)), Span::raw(clock(last.0))]),
+        )
+        .y_axis(
+            Axis::default()
+                .style(muted)
+                .bounds([0.0, ceiling])
+                .labels(vec![Span::raw("0"), Span::raw(format!("{}ms", ceiling))]),
+        );
+    f.render_widget(chart, area);
+}
 
-        help_spans.extend(vec![
-            Span::styled("Esc/q/←", Style::default().fg(Color::Yellow)),
-            Span::raw(" Back  "),
-            Span::styled("h", Style::default().fg(Color::Yellow)),
-            Span::raw(" Home"),
-        ]);
+/// Section header of a region: node count and the best cached delay
+fn region_header<'a>(
+    state: &AppState,
+    nodes: &[String],
+    group: &RegionGroup,
+    collapsed: bool,
+    is_selected: bool,
+) -> ListItem<'a> {
+    let best = group
+        .nodes
+        .iter()
+        .filter_map(|node| state.get_delay(&nodes[*node]))
+        .map(|cached| cached.delay)
+        .min();
+    let style = if is_selected {
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD)
     } else {
-        // Route list mode help
-        help_spans.extend(vec![
-            Span::styled("↑↓", Style::default().fg(Color::Yellow)),
-            Span::raw(" Navigate  "),
-            Span::styled("Enter/→", Style::default().fg(Color::Yellow)),
-            Span::raw(" View Nodes  "),
-        ]);
-
-        // Show speed test only if preset allows
-        if preset.show_speed_test() {
-            help_spans.extend(vec![
-                Span::styled("t", Style::default().fg(Color::Yellow)),
-                Span::raw(" Test All  "),
-            ]);
-        }
+        Style::default().fg(Color::Cyan)
+    };
+    let mut spans = vec![
+        Span::styled(if is_selected { "▶ " } else { "  " }, style),
+        Span::styled(if collapsed { "▸ " } else { "▾ " }, style),
+        Span::styled(
+            format!(
+                "{} {:<6} {:>4} nodes",
+                region::flag(group.region),
+                group.region,
+                group.nodes.len()
+            ),
+            style,
+        ),
+    ];
+    if let Some(best) = best {
+        spans.push(Span::styled(
+            format!("   best {}ms", best),
+            Style::default().fg(delay_color(best)),
+        ));
+    }
+    ListItem::new(Line::from(spans))
+}
 
-        help_spans.extend(vec![
-            Span::styled("h", Style::default().fg(Color::Yellow)),
-            Span::raw(" Home  "),
-            Span::styled("q/Esc", Style::default().fg(Color::Yellow)),
-            Span::raw(" Back"),
-        ]);
+/// "now 120ms · best 80ms" of a group on the Routes list; results past the TTL are greyed
+fn group_delay_spans<'a>(state: &AppState, route: &HumanRoute) -> Vec<Span<'a>> {
+    let delays = state.group_delays(route);
+    let style = |tested_at: i64, delay: Option<u32>| match delay {
+        _ if state.is_tested_stale(tested_at) => Style::default().fg(Color::DarkGray),
+        Some(delay) => Style::default().fg(delay_color(delay)),
+        None => Style::default().fg(Color::Red),
+    };
+    let mut spans = Vec::new();
+    if let Some((tested_at, delay)) = delays.current {
+        let text = delay.map_or("timeout".to_string(), |delay| format!("{}ms", delay));
+        spans.push(Span::styled("  now ", Style::default().fg(Color::DarkGray)));
+        spans.push(Span::styled(text, style(tested_at, delay)));
     }
+    if let Some((tested_at, best)) = delays.best {
+        let separator = if spans.is_empty() {
+            "  best "
+        } else {
+            " · best "
+        };
+        spans.push(Span::styled(
+            separator,
+            Style::default().fg(Color::DarkGray),
+        ));
+        spans.push(Span::styled(
+            format!("{}ms", best),
+            style(tested_at, Some(best)),
+        ));
+    }
+    spans
+}
 
-    let help = Paragraph::new(Line::from(help_spans))
+fn delay_color(delay: u32) -> Color {
+    if delay < 200 {
+        Color::Green
+    } else if delay < 500 {
+        Color::Yellow
+    } else {
+        Color::Red
+    }
+}
+
+fn render_help(
+    f: &mut Frame,
+    area: Rect,
+    _mode: Mode,
+    preset: &Preset,
+    expanded: bool,
+    route: Option<&HumanRoute>,
+) {
+    let hints = if expanded {
+        keymap::bar(keymap::ROUTE_NODES, |needs| match needs {
+            Needs::Selector => {
+                needs.met_by(preset)
+                    && route.is_some_and(|route| route.proxy_type == ProxyType::Selector)
+            }
+            Needs::HealthCheck => route.is_some_and(|route| route.has_health_check()),
+            Needs::Pinned => route.is_some_and(|route| route.fixed_node.is_some()),
+            needs => needs.met_by(preset),
+        })
+    } else {
+        keymap::bar(keymap::ROUTES, |needs| needs.met_by(preset))
+    };
+
+    let help = Paragraph::new(Line::from(key_hints(&hints)))
         .alignment(Alignment::Center)
         .block(Block::default().borders(Borders::ALL));
 
     f.render_widget(help, area);
 }
+
+fn render_batch_help(f: &mut Frame, area: Rect, preset: &Preset) {
+    let hints = keymap::bar(keymap::ROUTE_MARKS, |needs| needs.met_by(preset));
+
+    let help = Paragraph::new(Line::from(key_hints(&hints)))
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t("block.batch")),
+        );
+
+    f.render_widget(help, area);
+}
+
+fn render_group_prompt(f: &mut Frame, area: Rect, input: &str, count: usize) {
+    let prompt = Paragraph::new(vec![
+        Line::from(vec![
+            Span::styled(
+                format!("Add {} node(s) to group: ", count),
+                Style::default().fg(Color::Cyan),
+            ),
+            Span::raw(input.to_string()),
+            Span::styled("_", Style::default().fg(Color::Yellow)),
+        ]),
+        Line::from(key_hints(&[
+            ("Enter", "help.add_group_confirm"),
+            ("Tab", "help.add_group_materialize"),
+            ("Esc", "help.cancel"),
+        ])),
+    ])
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::ALL));
+
+    f.render_widget(prompt, area);
+}
+
+fn render_search_prompt(f: &mut Frame, area: Rect, query: &str) {
+    let prompt = Paragraph::new(vec![
+        Line::from(vec![
+            Span::styled("Find node: ", Style::default().fg(Color::Cyan)),
+            Span::raw(query.to_string()),
+            Span::styled("_", Style::default().fg(Color::Yellow)),
+        ]),
+        Line::from(key_hints(&[
+            ("↑↓", "help.navigate"),
+            ("Enter/Esc", "help.done"),
+        ])),
+    ])
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::ALL));
+
+    f.render_widget(prompt, area);
+}
+
+fn render_health_check_editor(f: &mut Frame, area: Rect, edit: &HealthCheckEdit) {
+    let field = |label: &str, value: &str, focused: bool| {
+        let mut spans = vec![
+            Span::styled(format!("{:>12}: ", label), Style::default().fg(Color::Cyan)),
+            Span::raw(value.to_string()),
+        ];
+        if focused {
+            spans.push(Span::styled("_", Style::default().fg(Color::Yellow)));
+        }
+        Line::from(spans)
+    };
+    let editor = Paragraph::new(vec![
+        field("URL", &edit.url, !edit.on_interval),
+        field("Interval (s)", &edit.interval, edit.on_interval),
+        Line::from(key_hints(&[
+            ("Tab", "help.next_field"),
+            ("Enter", "help.save_reload"),
+            ("Esc", "help.cancel"),
+        ])),
+    ])
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Health Check - {}", edit.group)),
+    );
+
+    f.render_widget(editor, area);
+}
+
+fn render_edit_help(f: &mut Frame, area: Rect) {
+    let help = Paragraph::new(Line::from(key_hints(&keymap::bar(
+        keymap::ROUTE_LAYOUT,
+        |_| true,
+    ))))
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::ALL));
+
+    f.render_widget(help, area);
+}
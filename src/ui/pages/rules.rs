@@ -1,3 +1,4 @@
+use chrono::TimeZone;
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
@@ -6,15 +7,63 @@ use ratatui::{
     Frame,
 };
 
+use super::performance::format_bytes;
 use crate::app::AppState;
-use crate::clash::Rule;
+use crate::clash::{Rule, RuleProvider};
+use crate::config::domains::DomainListFormat;
+use crate::config::rule_order::{RuleChange, RuleOrder};
+use crate::config::rule_set::RuleSet;
+use crate::config::stats::{RuleHits, RuleStats};
 use crate::config::AppConfig;
+use crate::ui::i18n::{key_hints, t};
+use crate::ui::keymap;
+use crate::ui::list;
+use crate::ui::search::SearchFilter;
+use crate::ui::theme;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum RuleEditMode {
     None,
     AddWhitelist,
     AddBlacklist,
+    /// Path of a domain list to import into the focused list
+    Import,
+    /// Path the focused list is exported to
+    Export(DomainListFormat),
+}
+
+/// Reordering the `rules` of the Clash config
+pub struct RuleReorder {
+    pub path: std::path::PathBuf,
+    /// Config file contents when editing began
+    pub original: Vec<u8>,
+    pub order: RuleOrder,
+    pub selected: usize,
+    /// Showing the changes to confirm instead of the rules
+    pub preview: bool,
+}
+
+/// Entries of the rule provider behind a RULE-SET rule
+pub struct RuleSetView {
+    pub set: RuleSet,
+    /// Size and update time the core reports, once fetched
+    pub provider: Option<RuleProvider>,
+    pub query: String,
+    pub search_mode: bool,
+    pub scroll_offset: usize,
+}
+
+impl RuleSetView {
+    /// Entries containing the query
+    pub fn filtered(&self) -> Vec<&str> {
+        let query = self.query.trim().to_lowercase();
+        self.set
+            .entries
+            .iter()
+            .map(String::as_str)
+            .filter(|entry| query.is_empty() || entry.to_lowercase().contains(&query))
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -23,6 +72,7 @@ pub enum RuleListFocus {
     Blacklist,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn render(
     f: &mut Frame,
     area: Rect,
@@ -31,11 +81,15 @@ pub fn render(
     search_query: &str,
     search_mode: bool,
     edit_mode: RuleEditMode,
-    _edit_input: &str,
-    _config: &AppConfig,
-    _selected_index: usize,
+    edit_input: &str,
+    config: &AppConfig,
+    selected_index: usize,
     rules: &[Rule],
-    _list_focus: RuleListFocus,
+    list_focus: RuleListFocus,
+    domain_lists: bool,
+    rule_stats: Option<&RuleStats>,
+    reorder: Option<&RuleReorder>,
+    rule_set: Option<&RuleSetView>,
 ) {
     let mut constraints = vec![Constraint::Length(3)]; // Title
 
@@ -43,12 +97,16 @@ pub fn render(
         constraints.push(Constraint::Length(3)); // Status message
     }
 
-    if search_mode {
+    if search_mode || rule_set.is_some_and(|view| view.search_mode) {
         constraints.push(Constraint::Length(3)); // Search input
     }
 
+    if edit_mode != RuleEditMode::None {
+        constraints.push(Constraint::Length(3)); // Domain or path input
+    }
+
     constraints.push(Constraint::Min(0)); // Content
-    constraints.push(Constraint::Length(5)); // Help
+    constraints.push(Constraint::Length(theme::help_height())); // Help
 
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -64,28 +122,60 @@ pub fn render(
         chunk_idx += 1;
     }
 
-    if search_mode {
+    if let Some(view) = rule_set.filter(|view| view.search_mode) {
+        render_rule_set_search(f, chunks[chunk_idx], view);
+        chunk_idx += 1;
+    } else if search_mode {
         render_search_input(f, chunks[chunk_idx], search_query);
         chunk_idx += 1;
     }
 
-    // Always show all rules (expert mode)
-    render_all_rules(
+    if edit_mode != RuleEditMode::None {
+        render_edit_input(f, chunks[chunk_idx], edit_mode, edit_input, list_focus);
+        chunk_idx += 1;
+    }
+
+    if let Some(view) = rule_set {
+        render_rule_set(f, chunks[chunk_idx], view);
+    } else {
+        match (reorder, rule_stats) {
+            (Some(reorder), _) => render_reorder(f, chunks[chunk_idx], reorder),
+            (None, Some(stats)) => render_rule_stats(
+                f,
+                chunks[chunk_idx],
+                scroll_offset,
+                search_query,
+                rules,
+                stats,
+            ),
+            (None, None) if domain_lists => {
+                render_domain_lists(f, chunks[chunk_idx], config, selected_index, list_focus)
+            }
+            (None, None) => render_all_rules(
+                f,
+                chunks[chunk_idx],
+                state,
+                scroll_offset,
+                search_query,
+                rules,
+            ),
+        }
+    }
+    chunk_idx += 1;
+
+    render_help(
         f,
         chunks[chunk_idx],
-        state,
-        scroll_offset,
-        search_query,
-        rules,
+        search_mode,
+        edit_mode,
+        reorder,
+        rule_set,
+        domain_lists && rule_stats.is_none(),
     );
-    chunk_idx += 1;
-
-    render_help(f, chunks[chunk_idx], search_mode, edit_mode);
 }
 
 fn render_title(f: &mut Frame, area: Rect) {
-    let title_text = "Rules Management (规则管理)";
-    let title = Paragraph::new(title_text)
+    let title = Paragraph::new(t("title.rules"))
         .style(
             Style::default()
                 .fg(Color::Cyan)
@@ -113,6 +203,9 @@ fn render_all_rules(
     rules: &[Rule],
 ) {
     let available_width = area.width.saturating_sub(4) as usize; // Subtract borders and padding
+
+    // Narrow terminals drop the rule type column so payload and proxy stay readable
+    let show_rule_type = !theme::compact();
     if rules.is_empty() {
         let content = vec![
             Line::from(""),
@@ -134,28 +227,18 @@ fn render_all_rules(
             Line::from("  • Try refreshing with 'r' key"),
         ];
 
-        let paragraph = Paragraph::new(content)
-            .alignment(Alignment::Left)
-            .block(Block::default().borders(Borders::ALL).title("Rules"));
+        let paragraph = Paragraph::new(content).alignment(Alignment::Left).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t("block.rules")),
+        );
 
         f.render_widget(paragraph, area);
         return;
     }
 
-    // Filter rules based on search query
-    let filtered_rules: Vec<&Rule> = if search_query.is_empty() {
-        rules.iter().collect()
-    } else {
-        let query_lower = search_query.to_lowercase();
-        rules
-            .iter()
-            .filter(|rule| {
-                rule.rule_type.to_lowercase().contains(&query_lower)
-                    || rule.payload.to_lowercase().contains(&query_lower)
-                    || rule.proxy.to_lowercase().contains(&query_lower)
-            })
-            .collect()
-    };
+    let filter = SearchFilter::new(search_query);
+    let filtered_rules = filter_rules(rules, search_query);
 
     if filtered_rules.is_empty() {
         let message = format!("No rules matching '{}'", search_query);
@@ -166,84 +249,89 @@ fn render_all_rules(
                 Style::default().fg(Color::Yellow),
             )]),
         ];
-        let paragraph = Paragraph::new(content)
-            .alignment(Alignment::Center)
-            .block(Block::default().borders(Borders::ALL).title("Rules"));
+        let paragraph = Paragraph::new(content).alignment(Alignment::Center).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t("block.rules")),
+        );
         f.render_widget(paragraph, area);
         return;
     }
 
-    // Render rule list
+    // Only the rules on screen are formatted
+    let rows = list::viewport(area, 1);
     let items: Vec<ListItem> = filtered_rules
-        .iter()
-        .skip(scroll_offset)
-        .take(area.height as usize - 2)
-        .map(|rule| {
-            let rule_type_color = match rule.rule_type.as_str() {
-                "DOMAIN" => Color::Cyan,
-                "DOMAIN-SUFFIX" => Color::Blue,
-                "DOMAIN-KEYWORD" => Color::Magenta,
-                "IP-CIDR" => Color::Green,
-                "GEOIP" => Color::Yellow,
-                "MATCH" => Color::Red,
-                _ => Color::White,
-            };
-
-            // Smart column width allocation based on available space
-            // Priority: ensure proxy is always visible
-            let rule_type_width = 13; // Fixed width for rule type
-            let arrow_width = 3; // " → "
-            let spacing_width = 2; // Two single spaces
-            let min_proxy_width = 15; // Minimum width to show proxy
-
-            // Calculate available width for payload
-            let reserved_width = rule_type_width + arrow_width + spacing_width + min_proxy_width;
-            let payload_max_width = if available_width > reserved_width {
-                (available_width - reserved_width).min(40)
-            } else {
-                20 // Fallback minimum
-            };
-
-            // Format rule type (fixed width with padding)
-            let rule_type_str = if rule.rule_type.len() > rule_type_width {
-                format!("{:.10}...", &rule.rule_type[..10])
-            } else {
-                format!("{:width$}", rule.rule_type, width = rule_type_width)
-            };
-
-            // Format payload (truncate if needed, no padding)
-            let payload_str = if rule.payload.len() > payload_max_width {
-                format!(
-                    "{}...",
-                    &rule.payload[..payload_max_width.saturating_sub(3)]
-                )
-            } else {
-                rule.payload.clone()
-            };
-
-            // Format proxy (truncate if needed, no padding)
-            let proxy_max_width = 25;
-            let proxy_str = if rule.proxy.len() > proxy_max_width {
-                format!("{}...", &rule.proxy[..proxy_max_width.saturating_sub(3)])
-            } else {
-                rule.proxy.clone()
-            };
-
-            let line = Line::from(vec![
-                Span::styled(rule_type_str, Style::default().fg(rule_type_color)),
-                Span::raw(" "),
-                Span::styled(payload_str, Style::default().fg(Color::White)),
-                Span::raw(" → "),
-                Span::styled(
-                    proxy_str,
-                    Style::default()
-                        .fg(Color::Green)
-                        .add_modifier(Modifier::BOLD),
-                ),
-            ]);
-            ListItem::new(line)
-        })
-        .collect();
+        [list::window(filtered_rules.len(), scroll_offset, rows)]
+    .iter()
+    .map(|rule| {
+        let rule_type_color = match rule.rule_type.as_str() {
+            "DOMAIN" => Color::Cyan,
+            "DOMAIN-SUFFIX" => Color::Blue,
+            "DOMAIN-KEYWORD" => Color::Magenta,
+            "IP-CIDR" => Color::Green,
+            "GEOIP" => Color::Yellow,
+            "MATCH" => Color::Red,
+            _ => Color::White,
+        };
+
+        // Smart column width allocation based on available space
+        // Priority: ensure proxy is always visible
+        let rule_type_width = if show_rule_type { 13 } else { 0 }; // Fixed width for rule type
+        let arrow_width = 3; // " → "
+        let spacing_width = 2; // Two single spaces
+        let min_proxy_width = 15; // Minimum width to show proxy
+
+        // Calculate available width for payload
+        let reserved_width = rule_type_width + arrow_width + spacing_width + min_proxy_width;
+        let payload_max_width = if available_width > reserved_width {
+            (available_width - reserved_width).min(40)
+        } else {
+            20 // Fallback minimum
+        };
+
+        // Format rule type (fixed width with padding)
+        let rule_type_str = if rule.rule_type.len() > rule_type_width {
+            format!("{:.10}...", &rule.rule_type[..10])
+        } else {
+            format!("{:width$}", rule.rule_type, width = rule_type_width)
+        };
+
+        // Format payload (truncate if needed, no padding)
+        let payload_str = if rule.payload.len() > payload_max_width {
+            format!(
+                "{}...",
+                &rule.payload[..payload_max_width.saturating_sub(3)]
+            )
+        } else {
+            rule.payload.clone()
+        };
+
+        // Format proxy (truncate if needed, no padding)
+        let proxy_max_width = 25;
+        let proxy_str = if rule.proxy.len() > proxy_max_width {
+            format!("{}...", &rule.proxy[..proxy_max_width.saturating_sub(3)])
+        } else {
+            rule.proxy.clone()
+        };
+
+        let mut spans = Vec::new();
+        if show_rule_type {
+            spans.extend(filter.highlight(&rule_type_str, Style::default().fg(rule_type_color)));
+            spans.push(Span::raw(" "));
+        }
+        spans.extend(filter.highlight(&payload_str, Style::default().fg(Color::White)));
+        spans.push(Span::raw(" → "));
+        spans.extend(
+            filter.highlight(
+                &proxy_str,
+                Style::default()
+                    .fg(Color::Green)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        );
+        ListItem::new(Line::from(spans))
+    })
+    .collect();
 
     let title = if search_query.is_empty() {
         format!(
@@ -258,38 +346,223 @@ fn render_all_rules(
             scroll_offset
         )
     };
+    let title = match rule_set_target(rules, search_query, scroll_offset) {
+        Some(rule) => format!("{} - Enter inspects {}", title, rule.payload),
+        None => title,
+    };
 
     let list = List::new(items).block(Block::default().borders(Borders::ALL).title(title));
 
     f.render_widget(list, area);
 }
 
-fn render_help(f: &mut Frame, area: Rect, search_mode: bool, edit_mode: RuleEditMode) {
-    let help_spans = if edit_mode != RuleEditMode::None {
-        vec![
-            Span::styled("Esc", Style::default().fg(Color::Yellow)),
-            Span::raw(" Cancel  "),
-            Span::styled("Enter", Style::default().fg(Color::Yellow)),
-            Span::raw(" Done"),
-        ]
+/// Whitelist and blacklist side by side, the focused one with the selected domain
+fn render_domain_lists(
+    f: &mut Frame,
+    area: Rect,
+    config: &AppConfig,
+    selected_index: usize,
+    list_focus: RuleListFocus,
+) {
+    let panes = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+    let lists = [
+        (
+            RuleListFocus::Whitelist,
+            "Whitelist (always proxy)",
+            &config.whitelist,
+            'w',
+        ),
+        (
+            RuleListFocus::Blacklist,
+            "Blacklist (always direct)",
+            &config.blacklist,
+            'b',
+        ),
+    ];
+    for (pane, (focus, name, domains, add_key)) in panes.iter().zip(lists) {
+        let focused = focus == list_focus;
+        let border = if focused {
+            Style::default().fg(Color::Cyan)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(border)
+            .title(format!("{} - {}", name, domains.len()));
+        if domains.is_empty() {
+            let empty = Paragraph::new(format!("Empty - press {} to add a domain", add_key))
+                .style(Style::default().fg(Color::DarkGray))
+                .alignment(Alignment::Center)
+                .block(block);
+            f.render_widget(empty, *pane);
+            continue;
+        }
+
+        let rows = list::viewport(*pane, 1);
+        let selected = selected_index.min(domains.len() - 1);
+        let offset = if focused {
+            list::follow(selected, 0, rows)
+        } else {
+            0
+        };
+        let items: Vec<ListItem> = list::window(domains.len(), offset, rows)
+            .map(|i| {
+                let (prefix, style) = if focused && i == selected {
+                    (
+                        "▶ ",
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                } else {
+                    ("  ", Style::default().fg(Color::White))
+                };
+                ListItem::new(Line::from(Span::styled(
+                    format!("{}{}", prefix, domains[i]),
+                    style,
+                )))
+            })
+            .collect();
+        f.render_widget(List::new(items).block(block), *pane);
+    }
+}
+
+/// Rules ordered by connections matched this session, hottest first
+/// Rules that were never hit are dimmed at the bottom
+fn render_rule_stats(
+    f: &mut Frame,
+    area: Rect,
+    scroll_offset: usize,
+    search_query: &str,
+    rules: &[Rule],
+    stats: &RuleStats,
+) {
+    let show_traffic = !theme::compact();
+    let filter = SearchFilter::new(search_query);
+    let mut rows: Vec<(&Rule, RuleHits)> = rules
+        .iter()
+        .filter(|rule| filter.matches(rule_fields(rule)))
+        .map(|rule| (rule, stats.get(&rule.rule_type, &rule.payload)))
+        .collect();
+    // Stable sort keeps config order among rules with equal counts
+    rows.sort_by_key(|(_, hits)| std::cmp::Reverse((hits.hits, hits.bytes())));
+    let dead = rows.iter().filter(|(_, hits)| hits.hits == 0).count();
+
+    let visible = list::viewport(area, 1);
+    let items: Vec<ListItem> = rows[list::window(rows.len(), scroll_offset, visible)]
+        .iter()
+        .map(|(rule, hits)| {
+            let style = if hits.hits == 0 {
+                Style::default().fg(Color::DarkGray)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            let payload = if rule.payload.is_empty() {
+                String::new()
+            } else {
+                format!("{} ", rule.payload)
+            };
+            let traffic = if show_traffic {
+                format!("{:>10}  ", format_bytes(hits.bytes()))
+            } else {
+                " ".to_string()
+            };
+            let mut spans = vec![
+                Span::styled(
+                    format!("{:>6} ", hits.hits),
+                    style.add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(traffic, style),
+            ];
+            spans.extend(filter.highlight(&format!("{} ", rule.rule_type), style.fg(Color::Cyan)));
+            spans.extend(filter.highlight(&payload, style));
+            spans.push(Span::raw("→ "));
+            spans.extend(filter.highlight(&rule.proxy, style.fg(Color::Green)));
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let since = stats
+        .since
+        .and_then(|ts| chrono::Local.timestamp_opt(ts, 0).single())
+        .map(|time| format!("since {}", time.format("%H:%M")))
+        .unwrap_or_else(|| "waiting for first sample".to_string());
+    let title = format!(
+        "Rule Statistics - hits / traffic {} - {} of {} rules never hit (offset: {})",
+        since,
+        dead,
+        rows.len(),
+        scroll_offset
+    );
+
+    let list = List::new(items).block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(list, area);
+}
+
+/// Rules matching the fuzzy filter, best matches first
+/// The sort is stable, so equal scores keep config order
+pub fn filter_rules<'a>(rules: &'a [Rule], search_query: &str) -> Vec<&'a Rule> {
+    let filter = SearchFilter::new(search_query);
+    let mut scored: Vec<(i32, &Rule)> = rules
+        .iter()
+        .filter_map(|rule| Some((filter.score(rule_fields(rule))?, rule)))
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, rule)| rule).collect()
+}
+
+/// The RULE-SET rule Enter inspects: the first one from the top of the list down
+pub fn rule_set_target<'a>(
+    rules: &'a [Rule],
+    search_query: &str,
+    scroll_offset: usize,
+) -> Option<&'a Rule> {
+    filter_rules(rules, search_query)
+        .into_iter()
+        .skip(scroll_offset)
+        .find(|rule| rule.rule_type == "RuleSet" || rule.rule_type == "RULE-SET")
+}
+
+/// Rule text searched by the filter
+fn rule_fields(rule: &Rule) -> [&str; 3] {
+    [&rule.rule_type, &rule.payload, &rule.proxy]
+}
+
+fn render_help(
+    f: &mut Frame,
+    area: Rect,
+    search_mode: bool,
+    edit_mode: RuleEditMode,
+    reorder: Option<&RuleReorder>,
+    rule_set: Option<&RuleSetView>,
+    domain_lists: bool,
+) {
+    let help_spans = if rule_set.is_some_and(|view| view.search_mode) {
+        key_hints(&[("Esc", "help.exit_search"), ("Enter", "help.apply_filter")])
+    } else if rule_set.is_some() {
+        key_hints(&keymap::bar(keymap::RULE_SET, |_| true))
+    } else if reorder.is_some_and(|reorder| reorder.preview) {
+        key_hints(&[("y", "help.save_reload"), ("n/Esc", "help.back")])
+    } else if reorder.is_some() {
+        key_hints(&keymap::bar(keymap::RULE_REORDER, |_| true))
+    } else if let RuleEditMode::Export(_) = edit_mode {
+        key_hints(&[
+            ("Tab", "help.format"),
+            ("Esc", "help.cancel"),
+            ("Enter", "help.export"),
+        ])
+    } else if edit_mode != RuleEditMode::None {
+        key_hints(&[("Esc", "help.cancel"), ("Enter", "help.done")])
     } else if search_mode {
-        vec![
-            Span::styled("Esc", Style::default().fg(Color::Yellow)),
-            Span::raw(" Exit Search  "),
-            Span::styled("Enter", Style::default().fg(Color::Yellow)),
-            Span::raw(" Apply Filter"),
-        ]
+        key_hints(&[("Esc", "help.exit_search"), ("Enter", "help.apply_filter")])
+    } else if domain_lists {
+        key_hints(&keymap::bar(keymap::RULE_LISTS, |_| true))
     } else {
-        vec![
-            Span::styled("/", Style::default().fg(Color::Yellow)),
-            Span::raw(" Search  "),
-            Span::styled("↑↓", Style::default().fg(Color::Yellow)),
-            Span::raw(" Scroll  "),
-            Span::styled("r", Style::default().fg(Color::Yellow)),
-            Span::raw(" Refresh  "),
-            Span::styled("q", Style::default().fg(Color::Yellow)),
-            Span::raw(" Back"),
-        ]
+        key_hints(&keymap::bar(keymap::RULES, |_| true))
     };
 
     let help = Paragraph::new(Line::from(help_spans))
@@ -299,6 +572,223 @@ fn render_help(f: &mut Frame, area: Rect, search_mode: bool, edit_mode: RuleEdit
     f.render_widget(help, area);
 }
 
+/// Entries of a rule-set, filtered by the query, with whether the query itself is in the set
+fn render_rule_set(f: &mut Frame, area: Rect, view: &RuleSetView) {
+    let entries = view.filtered();
+    let query = view.query.trim();
+    let mut lines = vec![Line::from(vec![
+        Span::styled("Source: ", Style::default().fg(Color::Cyan)),
+        Span::raw(view.set.source.clone()),
+    ])];
+    if let Some(provider) = &view.provider {
+        let updated = provider
+            .updated_at
+            .as_deref()
+            .and_then(|time| chrono::DateTime::parse_from_rfc3339(time).ok())
+            .map(|time| {
+                time.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+            })
+            .unwrap_or_else(|| "-".to_string());
+        let mut spans = vec![
+            Span::styled("Core: ", Style::default().fg(Color::Cyan)),
+            Span::raw(format!(
+                "{} {} rule(s), updated {}",
+                provider.vehicle_type, provider.rule_count, updated
+            )),
+        ];
+        if provider.rule_count != view.set.entries.len() {
+            spans.push(Span::styled(
+                "  (file differs from what the core loaded)",
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+        lines.push(Line::from(spans));
+    }
+    if !query.is_empty() {
+        lines.push(match view.set.find_match(query) {
+            Some(entry) => Line::from(Span::styled(
+                format!("✓ {} is in {} (matched by {})", query, view.set.name, entry),
+                Style::default()
+                    .fg(Color::Green)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            None => Line::from(Span::styled(
+                format!("✗ {} is not in {}", query, view.set.name),
+                Style::default().fg(Color::Red),
+            )),
+        });
+    }
+    lines.push(Line::from(""));
+
+    let rows = (area.height.saturating_sub(2) as usize).saturating_sub(lines.len());
+    let offset = view
+        .scroll_offset
+        .min(entries.len().saturating_sub(rows.max(1)));
+    lines.extend(entries.iter().skip(offset).take(rows).map(|entry| {
+        Line::from(vec![
+            Span::raw("  "),
+            Span::styled(entry.to_string(), Style::default().fg(Color::White)),
+        ])
+    }));
+    if entries.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  No entries",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    let title = if query.is_empty() {
+        format!(
+            "Rule-Set {} - {} - {} entries",
+            view.set.name,
+            view.set.behavior,
+            view.set.entries.len()
+        )
+    } else {
+        format!(
+            "Rule-Set {} - {} - {} of {} entries containing '{}'",
+            view.set.name,
+            view.set.behavior,
+            entries.len(),
+            view.set.entries.len(),
+            query
+        )
+    };
+    let paragraph =
+        Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(paragraph, area);
+}
+
+fn render_rule_set_search(f: &mut Frame, area: Rect, view: &RuleSetView) {
+    let search = Paragraph::new(Line::from(vec![
+        Span::styled("Domain or IP: ", Style::default().fg(Color::Cyan)),
+        Span::raw(view.query.clone()),
+        Span::styled("_", Style::default().fg(Color::Yellow)),
+    ]))
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Search {}", view.set.name)),
+    );
+    f.render_widget(search, area);
+}
+
+/// The config's rules in their edited order, or the changes awaiting confirmation
+fn render_reorder(f: &mut Frame, area: Rect, reorder: &RuleReorder) {
+    let file = reorder
+        .path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    if reorder.preview {
+        let changes = reorder.order.changes();
+        let mut lines: Vec<Line> = changes
+            .iter()
+            .map(|change| match change {
+                RuleChange::Deleted { rule, from } => Line::from(vec![
+                    Span::styled(
+                        format!("  - #{:<5}", from + 1),
+                        Style::default().fg(Color::Red),
+                    ),
+                    Span::styled(rule.clone(), Style::default().fg(Color::Red)),
+                ]),
+                RuleChange::Moved { rule, from, to } => Line::from(vec![
+                    Span::styled(
+                        format!("  ↕ #{} → #{}  ", from + 1, to + 1),
+                        Style::default().fg(Color::Yellow),
+                    ),
+                    Span::raw(rule.clone()),
+                ]),
+            })
+            .collect();
+        if lines.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "  No changes",
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        let title = format!(
+            "Dry Run - {} change(s) to {} ({} rules after)",
+            changes.len(),
+            file,
+            reorder.order.len()
+        );
+        let paragraph =
+            Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title));
+        f.render_widget(paragraph, area);
+        return;
+    }
+
+    let rows = list::viewport(area, 1);
+    let len = reorder.order.len();
+    let selected = reorder.selected.min(len.saturating_sub(1));
+    let window = list::window(len, list::follow(selected, 0, rows), rows);
+    let moved = reorder.order.moved();
+    let items: Vec<ListItem> = window
+        .map(|i| {
+            let rule = reorder.order.get(i).unwrap_or_default();
+            let style = if i == selected {
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD)
+            } else if moved.contains(&i) {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default()
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    if i == selected { "> " } else { "  " },
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::styled(
+                    format!("{:>4}. ", i + 1),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(rule.to_string(), style),
+            ]))
+        })
+        .collect();
+    let title = format!(
+        "Edit Rules - {} - {} rules, {} change(s)",
+        file,
+        len,
+        reorder.order.changes().len()
+    );
+    let list = List::new(items).block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(list, area);
+}
+
+fn render_edit_input(
+    f: &mut Frame,
+    area: Rect,
+    edit_mode: RuleEditMode,
+    input: &str,
+    list_focus: RuleListFocus,
+) {
+    let list = match list_focus {
+        RuleListFocus::Whitelist => "whitelist",
+        RuleListFocus::Blacklist => "blacklist",
+    };
+    let label = match edit_mode {
+        RuleEditMode::AddWhitelist => "Add to whitelist: ".to_string(),
+        RuleEditMode::AddBlacklist => "Add to blacklist: ".to_string(),
+        RuleEditMode::Import => format!("Import into {} from (plain/hosts/adblock): ", list),
+        RuleEditMode::Export(format) => format!("Export {} as {} to: ", list, format.name()),
+        RuleEditMode::None => String::new(),
+    };
+    let prompt = Paragraph::new(Line::from(vec![
+        Span::styled(label, Style::default().fg(Color::Cyan)),
+        Span::raw(input.to_string()),
+        Span::styled("_", Style::default().fg(Color::Yellow)),
+    ]))
+    .block(Block::default().borders(Borders::ALL));
+
+    f.render_widget(prompt, area);
+}
+
 fn render_search_input(f: &mut Frame, area: Rect, search_query: &str) {
     let search_text = if search_query.is_empty() {
         Line::from(vec![
@@ -318,7 +808,7 @@ fn render_search_input(f: &mut Frame, area: Rect, search_query: &str) {
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Search (Type/Payload/Proxy)"),
+                .title(t("block.search_rules")),
         );
 
     f.render_widget(search_widget, area);
@@ -1,3 +1,6 @@
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
@@ -6,8 +9,18 @@ use ratatui::{
     Frame,
 };
 
+use crate::app::inbound::{InboundField, InboundPorts};
+use crate::app::notify::NotifyEvent;
 use crate::app::AppState;
+use crate::config::snapshot::Snapshot;
 use crate::config::AppConfig;
+use crate::core::dashboard::{self, DASHBOARDS};
+use crate::core::service::{ServiceAction, ServiceManager};
+use crate::core::upgrade::UpgradePlan;
+use crate::ui::i18n::{key_hints, t};
+use crate::ui::keymap;
+use crate::ui::list;
+use crate::ui::theme;
 
 pub enum SettingsAction {
     None,
@@ -15,32 +28,190 @@ pub enum SettingsAction {
     ImportPrompt,
     ExportSuccess(String),
     ImportSuccess,
+    ServiceSuccess(String),
+    /// Waiting for the service manager to carry out the action
+    ServiceRunning(ServiceAction),
+    UpgradeChecking,
+    UpgradePrompt(UpgradePlan),
+    /// Installing the given core version
+    Upgrading(String),
+    /// Picking a web dashboard; `dir`/`url` are the Clash config's `external-ui`/`external-ui-url`
+    Dashboard {
+        selected: usize,
+        dir: Option<String>,
+        url: Option<String>,
+    },
+    /// The core is downloading the named dashboard
+    InstallingDashboard(&'static str),
+    /// Editing the API URL (current input)
+    EditApiUrl(String),
+    /// Editing the API secret (current input, shown masked)
+    EditSecret(String),
+    /// Connection test succeeded for the given URL
+    ConnectionOk(String),
+    /// Inbound ports sub-page
+    EditPorts(PortsEditor),
+    /// Config snapshots sub-page
+    Snapshots(SnapshotsView),
+    /// The resolved Clash config, read-only
+    ViewConfig(ConfigViewer),
     Error(String),
 }
 
+/// Inbound ports and allow-lan of the running core, being edited
+pub struct PortsEditor {
+    /// Values the core reported
+    pub original: InboundPorts,
+    pub ports: InboundPorts,
+    /// Index into `InboundField::ALL`
+    pub selected: usize,
+    /// Digits typed for the selected port while editing it
+    pub input: Option<String>,
+    /// Privileged ports were listed and applying asked once already
+    pub confirming: bool,
+    /// (field, port, accepting connections) from the re-test after applying
+    pub checks: Vec<(InboundField, u16, bool)>,
+}
+
+impl PortsEditor {
+    pub fn new(ports: InboundPorts) -> Self {
+        Self {
+            original: ports,
+            ports,
+            selected: 0,
+            input: None,
+            confirming: false,
+            checks: Vec::new(),
+        }
+    }
+
+    pub fn field(&self) -> InboundField {
+        InboundField::ALL[self.selected]
+    }
+}
+
+/// Snapshots of the Clash config, to take, restore or delete
+pub struct SnapshotsView {
+    /// Config new snapshots are taken of
+    pub config_path: Option<PathBuf>,
+    /// Newest first
+    pub snapshots: Vec<Snapshot>,
+    pub selected: usize,
+    /// Name typed for a new snapshot
+    pub name_input: Option<String>,
+    /// Enter was pressed on the selected snapshot; `y` restores it
+    pub confirm_restore: bool,
+}
+
+impl SnapshotsView {
+    pub fn new(config_path: Option<PathBuf>, snapshots: Vec<Snapshot>) -> Self {
+        Self {
+            config_path,
+            snapshots,
+            selected: 0,
+            name_input: None,
+            confirm_restore: false,
+        }
+    }
+
+    pub fn selected(&self) -> Option<&Snapshot> {
+        self.snapshots.get(self.selected)
+    }
+}
+
+/// The Clash config clashctl resolved, shown read-only; `e` edits a copy in $EDITOR
+pub struct ConfigViewer {
+    pub path: PathBuf,
+    pub lines: Vec<String>,
+    /// First line shown
+    pub scroll: usize,
+    /// Copy being edited, kept after it failed validation so the next `e` resumes it
+    pub draft: Option<PathBuf>,
+}
+
+impl ConfigViewer {
+    pub fn open(path: PathBuf) -> Result<Self> {
+        let mut viewer = Self {
+            path,
+            lines: Vec::new(),
+            scroll: 0,
+            draft: None,
+        };
+        viewer.reload()?;
+        Ok(viewer)
+    }
+
+    /// Read the file again, keeping the scroll position where it still fits
+    pub fn reload(&mut self) -> Result<()> {
+        let bytes = std::fs::read(&self.path)
+            .with_context(|| format!("Failed to read {}", self.path.display()))?;
+        self.lines = String::from_utf8_lossy(&bytes)
+            .lines()
+            .map(|line| line.replace('\t', "  "))
+            .collect();
+        self.scroll = self.scroll.min(self.lines.len().saturating_sub(1));
+        Ok(())
+    }
+}
+
+impl SettingsAction {
+    /// Whether a core upgrade is in progress
+    pub fn is_busy(&self) -> bool {
+        matches!(
+            self,
+            SettingsAction::ServiceRunning(_)
+                | SettingsAction::UpgradeChecking
+                | SettingsAction::Upgrading(_)
+                | SettingsAction::InstallingDashboard(_)
+        )
+    }
+
+    /// Input of the text field being edited, if any
+    pub fn edit_input_mut(&mut self) -> Option<&mut String> {
+        match self {
+            SettingsAction::EditApiUrl(input) | SettingsAction::EditSecret(input) => Some(input),
+            _ => None,
+        }
+    }
+}
+
 pub fn render(
     f: &mut Frame,
     area: Rect,
-    _state: &AppState,
+    state: &AppState,
     config: &AppConfig,
     action: &SettingsAction,
+    service_running: Option<bool>,
 ) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(3), // Title
-            Constraint::Min(0),    // Settings options
-            Constraint::Length(5), // Help
+            Constraint::Length(3),                    // Title
+            Constraint::Min(0),                       // Settings options
+            Constraint::Length(theme::help_height()), // Help
         ])
         .split(area);
 
     render_title(f, chunks[0]);
-    render_settings(f, chunks[1], config, action);
+    match action {
+        SettingsAction::EditPorts(editor) => render_ports(f, chunks[1], editor),
+        SettingsAction::Snapshots(view) => render_snapshots(f, chunks[1], view),
+        SettingsAction::ViewConfig(viewer) => render_config_viewer(f, chunks[1], viewer),
+        SettingsAction::Dashboard { selected, dir, url } => render_dashboard(
+            f,
+            chunks[1],
+            config,
+            *selected,
+            dir.as_deref(),
+            url.as_deref(),
+        ),
+        _ => render_settings(f, chunks[1], state, config, action, service_running),
+    }
     render_help(f, chunks[2], action);
 }
 
 fn render_title(f: &mut Frame, area: Rect) {
-    let title = Paragraph::new("Settings & Configuration")
+    let title = Paragraph::new(t("title.settings"))
         .style(
             Style::default()
                 .fg(Color::Cyan)
@@ -51,7 +222,14 @@ fn render_title(f: &mut Frame, area: Rect) {
     f.render_widget(title, area);
 }
 
-fn render_settings(f: &mut Frame, area: Rect, config: &AppConfig, action: &SettingsAction) {
+fn render_settings(
+    f: &mut Frame,
+    area: Rect,
+    state: &AppState,
+    config: &AppConfig,
+    action: &SettingsAction,
+    service_running: Option<bool>,
+) {
     let mut lines = vec![
         Line::from(""),
         Line::from(vec![Span::styled(
@@ -74,24 +252,65 @@ fn render_settings(f: &mut Frame, area: Rect, config: &AppConfig, action: &Setti
             "Current Configuration:",
             Style::default().fg(Color::Cyan),
         )]),
+        match action {
+            SettingsAction::EditApiUrl(input) => Line::from(vec![
+                Span::raw("  API URL: "),
+                Span::raw(input.clone()),
+                Span::styled("_", Style::default().fg(Color::Yellow)),
+            ]),
+            _ => Line::from(vec![
+                Span::raw("  API URL: "),
+                Span::styled(&config.api_url, Style::default().fg(Color::Yellow)),
+            ]),
+        },
+        match action {
+            SettingsAction::EditSecret(input) => Line::from(vec![
+                Span::raw("  Secret: "),
+                Span::raw("*".repeat(input.chars().count())),
+                Span::styled("_", Style::default().fg(Color::Yellow)),
+                Span::styled(
+                    "  (leave empty to clear)",
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]),
+            _ => Line::from(vec![
+                Span::raw("  Secret: "),
+                Span::styled(
+                    if config.secret.is_some() {
+                        "✓ Configured"
+                    } else {
+                        "Not set"
+                    },
+                    if config.secret.is_some() {
+                        Color::Green
+                    } else {
+                        Color::Gray
+                    },
+                ),
+            ]),
+        },
         Line::from(vec![
-            Span::raw("  API URL: "),
-            Span::styled(&config.api_url, Style::default().fg(Color::Yellow)),
+            Span::styled("  [p]", Style::default().fg(Color::Green)),
+            Span::raw(" Secret Storage: "),
+            if config.keychain {
+                Span::styled("System keychain", Style::default().fg(Color::Green))
+            } else {
+                Span::styled("config.yaml", Style::default().fg(Color::Gray))
+            },
         ]),
         Line::from(vec![
-            Span::raw("  Secret: "),
-            Span::styled(
-                if config.secret.is_some() {
-                    "✓ Configured"
-                } else {
-                    "Not set"
-                },
-                if config.secret.is_some() {
-                    Color::Green
-                } else {
-                    Color::Gray
-                },
-            ),
+            Span::styled("  [a]", Style::default().fg(Color::Green)),
+            Span::raw(" Edit API URL  "),
+            Span::styled("[k]", Style::default().fg(Color::Green)),
+            Span::raw(" Edit Secret  "),
+            Span::styled("[c]", Style::default().fg(Color::Green)),
+            Span::raw(" Test Connection  "),
+            Span::styled("[o]", Style::default().fg(Color::Green)),
+            Span::raw(" Inbound Ports  "),
+            Span::styled("[b]", Style::default().fg(Color::Green)),
+            Span::raw(" Config Snapshots  "),
+            Span::styled("[v]", Style::default().fg(Color::Green)),
+            Span::raw(" View Clash Config"),
         ]),
         Line::from(vec![
             Span::raw("  Preset: "),
@@ -118,9 +337,58 @@ fn render_settings(f: &mut Frame, area: Rect, config: &AppConfig, action: &Setti
                 Style::default().fg(Color::Yellow),
             ),
         ]),
+        Line::from(vec![
+            Span::styled("  [t]", Style::default().fg(Color::Green)),
+            Span::raw(" Test Before Switch: "),
+            if config.test_before_switch {
+                Span::styled("On", Style::default().fg(Color::Green))
+            } else {
+                Span::styled("Off", Style::default().fg(Color::Gray))
+            },
+        ]),
+        Line::from(vec![
+            Span::styled("  [d]", Style::default().fg(Color::Green)),
+            Span::raw(" Subscription Downloads: "),
+            Span::styled(
+                config.default_download_policy().label(),
+                Style::default().fg(Color::Cyan),
+            ),
+        ]),
+        Line::from(vec![
+            Span::raw("  Primary Group: "),
+            Span::styled(
+                state
+                    .clash_state
+                    .current_selector
+                    .clone()
+                    .unwrap_or_else(|| "None".to_string()),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::styled(
+                if config.primary_group.is_some() {
+                    "  (set with P on Routes)"
+                } else {
+                    "  (auto-detected, set with P on Routes)"
+                },
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  [l]", Style::default().fg(Color::Green)),
+            Span::raw(" Language: "),
+            Span::styled(
+                config.get_language().name().to_string(),
+                Style::default().fg(Color::Cyan),
+            ),
+        ]),
+        notification_line(config),
+        alert_line(config),
         Line::from(""),
     ];
 
+    lines.extend(service_lines(config, service_running));
+    lines.push(Line::from(""));
+
     // Show action-specific messages
     match action {
         SettingsAction::ExportPrompt => {
@@ -176,6 +444,74 @@ fn render_settings(f: &mut Frame, area: Rect, config: &AppConfig, action: &Setti
                 "  Please restart the application to apply changes",
             )]));
         }
+        SettingsAction::ServiceSuccess(msg) => {
+            lines.push(Line::from(vec![
+                Span::styled("✓ ", Style::default().fg(Color::Green)),
+                Span::styled(
+                    msg,
+                    Style::default()
+                        .fg(Color::Green)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ]));
+        }
+        SettingsAction::ServiceRunning(action) => {
+            lines.push(Line::from(vec![Span::styled(
+                format!("Waiting for the core service to {}...", action.as_str()),
+                Style::default().fg(Color::Yellow),
+            )]));
+        }
+        SettingsAction::UpgradeChecking => {
+            lines.push(Line::from(vec![Span::styled(
+                "Checking latest mihomo release...",
+                Style::default().fg(Color::Yellow),
+            )]));
+        }
+        SettingsAction::UpgradePrompt(plan) => {
+            lines.push(Line::from(vec![
+                Span::styled("Core Upgrade: ", Style::default().fg(Color::Yellow)),
+                Span::raw(plan.current.as_deref().unwrap_or("unknown").to_string()),
+                Span::raw(" → "),
+                Span::styled(plan.latest.clone(), Style::default().fg(Color::Green)),
+            ]));
+            lines.push(Line::from(vec![Span::raw(format!(
+                "  Asset: {}",
+                plan.asset_name
+            ))]));
+            lines.push(Line::from(vec![Span::styled(
+                "Press 'y' to download, install and restart the core",
+                Style::default().fg(Color::Green),
+            )]));
+        }
+        SettingsAction::Upgrading(version) => {
+            lines.push(Line::from(vec![Span::styled(
+                format!("Installing {} and restarting the core...", version),
+                Style::default().fg(Color::Yellow),
+            )]));
+        }
+        SettingsAction::InstallingDashboard(name) => {
+            lines.push(Line::from(vec![Span::styled(
+                format!("The core is downloading {}...", name),
+                Style::default().fg(Color::Yellow),
+            )]));
+        }
+        SettingsAction::ConnectionOk(url) => {
+            lines.push(Line::from(vec![
+                Span::styled("✓ ", Style::default().fg(Color::Green)),
+                Span::styled(
+                    format!("Connected to {}", url),
+                    Style::default()
+                        .fg(Color::Green)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ]));
+        }
+        SettingsAction::EditApiUrl(_)
+        | SettingsAction::EditSecret(_)
+        | SettingsAction::EditPorts(_)
+        | SettingsAction::Snapshots(_)
+        | SettingsAction::ViewConfig(_)
+        | SettingsAction::Dashboard { .. } => {}
         SettingsAction::Error(err) => {
             lines.push(Line::from(vec![
                 Span::styled("✗ ", Style::default().fg(Color::Red)),
@@ -193,30 +529,447 @@ fn render_settings(f: &mut Frame, area: Rect, config: &AppConfig, action: &Setti
     }
 
     let settings = Paragraph::new(lines)
-        .block(Block::default().borders(Borders::ALL).title("Settings"))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t("block.settings")),
+        )
         .alignment(Alignment::Left);
 
     f.render_widget(settings, area);
 }
 
+fn render_ports(f: &mut Frame, area: Rect, editor: &PortsEditor) {
+    let mut lines = vec![Line::from("")];
+    for (i, field) in InboundField::ALL.iter().enumerate() {
+        let selected = i == editor.selected;
+        let marker = if selected { "▶ " } else { "  " };
+        let mut spans = vec![Span::styled(
+            format!("{}{:<12}", marker, field.key()),
+            if selected {
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            },
+        )];
+        match (editor.ports.port(*field), &editor.input) {
+            (Some(_), Some(input)) if selected => {
+                spans.push(Span::raw(input.clone()));
+                spans.push(Span::styled("_", Style::default().fg(Color::Yellow)));
+            }
+            (Some(port), _) => {
+                let changed = editor.original.port(*field) != Some(port);
+                spans.push(if port == 0 {
+                    Span::styled("off", Style::default().fg(Color::Gray))
+                } else {
+                    Span::styled(
+                        port.to_string(),
+                        Style::default().fg(if changed { Color::Yellow } else { Color::Green }),
+                    )
+                });
+                if changed {
+                    spans.push(Span::styled(
+                        format!("  (was {})", editor.original.port(*field).unwrap_or(0)),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+            }
+            (None, _) => {
+                spans.push(if editor.ports.allow_lan {
+                    Span::styled("On", Style::default().fg(Color::Green))
+                } else {
+                    Span::styled("Off", Style::default().fg(Color::Gray))
+                });
+                if editor.ports.allow_lan != editor.original.allow_lan {
+                    spans.push(Span::styled(
+                        "  (changed)",
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+            }
+        }
+        lines.push(Line::from(spans));
+    }
+    lines.push(Line::from(""));
+
+    for problem in editor.ports.problems(&editor.original) {
+        let (mark, color) = if problem.is_blocking() {
+            ("✗ ", Color::Red)
+        } else {
+            ("! ", Color::Yellow)
+        };
+        lines.push(Line::from(vec![
+            Span::styled(mark, Style::default().fg(color)),
+            Span::styled(problem.to_string(), Style::default().fg(color)),
+        ]));
+    }
+    if editor.confirming {
+        lines.push(Line::from(vec![Span::styled(
+            "Press 's' again to apply anyway",
+            Style::default().fg(Color::Yellow),
+        )]));
+    }
+
+    if !editor.checks.is_empty() {
+        lines.push(Line::from(vec![Span::styled(
+            "Re-test after applying:",
+            Style::default().fg(Color::Cyan),
+        )]));
+        for (field, port, accepting) in &editor.checks {
+            lines.push(Line::from(if *accepting {
+                vec![
+                    Span::styled("  ✓ ", Style::default().fg(Color::Green)),
+                    Span::raw(format!("{} {} accepts connections", field.key(), port)),
+                ]
+            } else {
+                vec![
+                    Span::styled("  ✗ ", Style::default().fg(Color::Red)),
+                    Span::raw(format!("{} {} is not reachable", field.key(), port)),
+                ]
+            }));
+        }
+    }
+
+    let ports = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t("block.inbound_ports")),
+        )
+        .alignment(Alignment::Left);
+    f.render_widget(ports, area);
+}
+
+fn render_snapshots(f: &mut Frame, area: Rect, view: &SnapshotsView) {
+    let mut lines = vec![
+        Line::from(vec![
+            Span::raw("  Clash config: "),
+            Span::styled(
+                view.config_path
+                    .as_ref()
+                    .map(|path| path.display().to_string())
+                    .unwrap_or_else(|| "not found".to_string()),
+                Style::default().fg(Color::Cyan),
+            ),
+        ]),
+        Line::from(""),
+    ];
+    if view.snapshots.is_empty() {
+        lines.push(Line::from(vec![Span::styled(
+            "  No snapshots yet: press 'n' to save the current config as a known good one",
+            Style::default().fg(Color::Gray),
+        )]));
+    }
+    for (i, snapshot) in view.snapshots.iter().enumerate() {
+        let selected = i == view.selected;
+        let style = if selected {
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!(
+                    "  {} {:<32}",
+                    if selected { "▶" } else { " " },
+                    snapshot.name
+                ),
+                style,
+            ),
+            Span::styled(
+                format!(" {}", snapshot.created_label()),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]));
+    }
+    lines.push(Line::from(""));
+    if let Some(input) = &view.name_input {
+        lines.push(Line::from(vec![
+            Span::styled("Snapshot name: ", Style::default().fg(Color::Yellow)),
+            Span::raw(input.clone()),
+            Span::styled("_", Style::default().fg(Color::Yellow)),
+            Span::styled(
+                "  (empty for the date)",
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]));
+    } else if let Some(snapshot) = view.selected().filter(|_| view.confirm_restore) {
+        lines.push(Line::from(vec![Span::styled(
+            format!(
+                "Restore '{}' over {} and reload the core? Press 'y' to confirm",
+                snapshot.name,
+                snapshot.source.display()
+            ),
+            Style::default().fg(Color::Red),
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  The current config is backed up first, so the Update page's rollback undoes this",
+            Style::default().fg(Color::DarkGray),
+        )]));
+    }
+
+    let snapshots = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t("block.snapshots")),
+        )
+        .alignment(Alignment::Left);
+    f.render_widget(snapshots, area);
+}
+
+fn render_config_viewer(f: &mut Frame, area: Rect, viewer: &ConfigViewer) {
+    let rows = list::viewport(area, 1);
+    let gutter = viewer.lines.len().max(1).to_string().len();
+    let lines: Vec<Line> = list::window(viewer.lines.len(), viewer.scroll, rows)
+        .map(|index| {
+            let text = &viewer.lines[index];
+            let mut spans = vec![Span::styled(
+                format!("{:>width$} ", index + 1, width = gutter),
+                Style::default().fg(Color::DarkGray),
+            )];
+            let code = text.trim_start();
+            if code.starts_with('#') {
+                spans.push(Span::styled(
+                    text.clone(),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            } else {
+                // Mapping keys in cyan, up to the first `:` outside of a list item's value
+                let indent = text.len() - code.len();
+                let item = code.strip_prefix("- ").map_or(0, |_| 2);
+                let key_end = code[item..]
+                    .find(": ")
+                    .map(|at| at + item + 1)
+                    .or_else(|| code.ends_with(':').then_some(code.len()))
+                    .filter(|&end| !code[item..end].contains(['{', '[', '"', '\'']));
+                match key_end {
+                    Some(end) => {
+                        spans.push(Span::raw(text[..indent + item].to_string()));
+                        spans.push(Span::styled(
+                            text[indent + item..indent + end].to_string(),
+                            Style::default().fg(Color::Cyan),
+                        ));
+                        spans.push(Span::raw(text[indent + end..].to_string()));
+                    }
+                    None => spans.push(Span::raw(text.clone())),
+                }
+            }
+            Line::from(spans)
+        })
+        .collect();
+
+    let mut title = format!(" {} ", viewer.path.display());
+    if viewer.draft.is_some() {
+        title.push_str("(unsaved edit: e resumes it) ");
+    }
+    let widget = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .title_bottom(format!(
+                " {}/{} ",
+                (viewer.scroll + 1).min(viewer.lines.len()),
+                viewer.lines.len()
+            )),
+    );
+    f.render_widget(widget, area);
+}
+
+fn render_dashboard(
+    f: &mut Frame,
+    area: Rect,
+    config: &AppConfig,
+    selected: usize,
+    dir: Option<&str>,
+    url: Option<&str>,
+) {
+    let mut lines = vec![Line::from("")];
+    let current = match url {
+        Some(url) => dashboard::find(url).map_or(url, |known| known.name),
+        None => "none",
+    };
+    lines.push(Line::from(vec![
+        Span::raw("  external-ui: "),
+        Span::styled(
+            dir.map(str::to_string)
+                .unwrap_or_else(|| format!("{} (default)", dashboard::DEFAULT_DIR)),
+            Style::default().fg(Color::Cyan),
+        ),
+        Span::raw("  Current: "),
+        Span::styled(current.to_string(), Style::default().fg(Color::Cyan)),
+    ]));
+    for (i, choice) in DASHBOARDS.iter().enumerate() {
+        let style = if i == selected {
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        lines.push(Line::from(vec![Span::styled(
+            format!(
+                "  {} {}",
+                if i == selected { "▶" } else { " " },
+                choice.name
+            ),
+            style,
+        )]));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![Span::styled(
+        format!(
+            "Press 'y' to have the core download it, 'b' to open {}",
+            dashboard::page_url(&config.api_url)
+        ),
+        Style::default().fg(Color::Green),
+    )]));
+
+    let dashboards = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t("block.dashboard")),
+        )
+        .alignment(Alignment::Left);
+    f.render_widget(dashboards, area);
+}
+
+fn service_lines(config: &AppConfig, service_running: Option<bool>) -> Vec<Line<'static>> {
+    let mut lines = vec![Line::from(vec![Span::styled(
+        "Core Service",
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+    )])];
+
+    match ServiceManager::from_config(&config.service) {
+        Ok(manager) => {
+            let (status, color) = match service_running {
+                Some(true) => ("● running", Color::Green),
+                Some(false) => ("○ stopped", Color::Red),
+                None => ("unknown", Color::Gray),
+            };
+            lines.push(Line::from(vec![
+                Span::raw("  Manager: "),
+                Span::styled(manager.describe(), Style::default().fg(Color::Cyan)),
+            ]));
+            lines.push(Line::from(vec![
+                Span::raw("  Status: "),
+                Span::styled(status, Style::default().fg(color)),
+            ]));
+            lines.push(Line::from(vec![
+                Span::styled("  [s]", Style::default().fg(Color::Green)),
+                Span::raw(" Start  "),
+                Span::styled("[x]", Style::default().fg(Color::Green)),
+                Span::raw(" Stop  "),
+                Span::styled("[r]", Style::default().fg(Color::Green)),
+                Span::raw(" Restart  "),
+                Span::styled("[u]", Style::default().fg(Color::Green)),
+                Span::raw(" Upgrade  "),
+                Span::styled("[w]", Style::default().fg(Color::Green)),
+                Span::raw(" Web Dashboard"),
+            ]));
+        }
+        Err(e) => lines.push(Line::from(vec![
+            Span::raw("  "),
+            Span::styled(e.to_string(), Style::default().fg(Color::Red)),
+        ])),
+    }
+
+    lines
+}
+
+/// Traffic alert thresholds: [4] download rate, [5] active connections
+fn alert_line(config: &AppConfig) -> Line<'static> {
+    let threshold = |value: Option<String>| match value {
+        Some(value) => Span::styled(value, Style::default().fg(Color::Yellow)),
+        None => Span::styled("Off", Style::default().fg(Color::Gray)),
+    };
+    Line::from(vec![
+        Span::raw("  Alerts:"),
+        Span::styled(" [4]", Style::default().fg(Color::Green)),
+        Span::raw(" Download > "),
+        threshold(
+            config
+                .alerts
+                .download_rate_mb
+                .map(|limit| format!("{} MB/s", limit)),
+        ),
+        Span::styled("  [5]", Style::default().fg(Color::Green)),
+        Span::raw(" Connections > "),
+        threshold(config.alerts.connections.map(|limit| limit.to_string())),
+    ])
+}
+
+/// Per-event notification toggles: [1] node down, [2] subscription expiring, [3] core unreachable
+fn notification_line(config: &AppConfig) -> Line<'static> {
+    let mut spans = vec![Span::raw("  Notifications:")];
+    for (key, event) in [
+        ("1", NotifyEvent::NodeDown),
+        ("2", NotifyEvent::SubscriptionExpiring),
+        ("3", NotifyEvent::CoreUnreachable),
+    ] {
+        let enabled = config.notifications.is_enabled(event);
+        spans.push(Span::styled(
+            format!(" [{}]", key),
+            Style::default().fg(Color::Green),
+        ));
+        spans.push(Span::raw(format!(" {} ", event.label())));
+        spans.push(if enabled {
+            Span::styled("On", Style::default().fg(Color::Green))
+        } else {
+            Span::styled("Off", Style::default().fg(Color::Gray))
+        });
+    }
+    if config.notifications.subscription_expiring {
+        spans.push(Span::styled(
+            format!(
+                "  (expiry within {} days)",
+                config.notifications.expiry_days
+            ),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+    Line::from(spans)
+}
+
 fn render_help(f: &mut Frame, area: Rect, action: &SettingsAction) {
     let help_spans = match action {
-        SettingsAction::ExportPrompt | SettingsAction::ImportPrompt => vec![
-            Span::styled("y", Style::default().fg(Color::Yellow)),
-            Span::raw(" Confirm  "),
-            Span::styled("n/Esc", Style::default().fg(Color::Yellow)),
-            Span::raw(" Cancel"),
-        ],
-        _ => vec![
-            Span::styled("e", Style::default().fg(Color::Yellow)),
-            Span::raw(" Export  "),
-            Span::styled("i", Style::default().fg(Color::Yellow)),
-            Span::raw(" Import  "),
-            Span::styled("h", Style::default().fg(Color::Yellow)),
-            Span::raw(" Home  "),
-            Span::styled("q", Style::default().fg(Color::Yellow)),
-            Span::raw(" Back"),
-        ],
+        SettingsAction::ExportPrompt
+        | SettingsAction::ImportPrompt
+        | SettingsAction::UpgradePrompt(_) => {
+            key_hints(&[("y", "help.confirm"), ("n/Esc", "help.cancel")])
+        }
+        SettingsAction::EditApiUrl(_) | SettingsAction::EditSecret(_) => key_hints(&[
+            ("Enter", "help.save_reconnect"),
+            ("Tab", "help.test_without_saving"),
+            ("Esc", "help.cancel"),
+        ]),
+        SettingsAction::Dashboard { .. } => {
+            key_hints(&keymap::bar(keymap::SETTINGS_DASHBOARD, |_| true))
+        }
+        SettingsAction::EditPorts(editor) if editor.input.is_some() => {
+            key_hints(&[("Enter", "help.confirm"), ("Esc", "help.cancel")])
+        }
+        SettingsAction::EditPorts(_) => key_hints(&keymap::bar(keymap::SETTINGS_PORTS, |_| true)),
+        SettingsAction::Snapshots(view) if view.name_input.is_some() => {
+            key_hints(&[("Enter", "help.confirm"), ("Esc", "help.cancel")])
+        }
+        SettingsAction::Snapshots(view) if view.confirm_restore => {
+            key_hints(&[("y", "help.confirm"), ("n/Esc", "help.cancel")])
+        }
+        SettingsAction::Snapshots(_) => {
+            key_hints(&keymap::bar(keymap::SETTINGS_SNAPSHOTS, |_| true))
+        }
+        SettingsAction::ViewConfig(_) => {
+            key_hints(&keymap::bar(keymap::SETTINGS_CONFIG_VIEWER, |_| true))
+        }
+        _ => key_hints(&keymap::bar(keymap::SETTINGS, |_| true)),
     };
 
     let help = Paragraph::new(Line::from(help_spans))
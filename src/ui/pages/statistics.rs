@@ -0,0 +1,200 @@
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use super::performance::format_bytes;
+use crate::app::AppState;
+use crate::config::stats::{Usage, UsageReport};
+use crate::ui::i18n::{key_hints, t};
+use crate::ui::keymap;
+
+/// Width of the longest daily usage bar
+const BAR_WIDTH: usize = 30;
+
+pub fn render(f: &mut Frame, area: Rect, state: &AppState, report: Option<&UsageReport>) {
+    let constraints = if state.status_message.is_some() {
+        vec![
+            Constraint::Length(3), // Title
+            Constraint::Length(3), // Status message
+            Constraint::Length(5), // Summary
+            Constraint::Min(0),    // Daily usage + top hosts
+            Constraint::Length(3), // Help
+        ]
+    } else {
+        vec![
+            Constraint::Length(3), // Title
+            Constraint::Length(5), // Summary
+            Constraint::Min(0),    // Daily usage + top hosts
+            Constraint::Length(3), // Help
+        ]
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(area);
+
+    let mut chunk_idx = 0;
+    render_title(f, chunks[chunk_idx]);
+    chunk_idx += 1;
+
+    if let Some(msg) = &state.status_message {
+        render_status(f, chunks[chunk_idx], msg);
+        chunk_idx += 1;
+    }
+
+    let report = report.cloned().unwrap_or_default();
+    render_summary(f, chunks[chunk_idx], &report);
+    chunk_idx += 1;
+
+    let body = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(chunks[chunk_idx]);
+    render_daily(f, body[0], &report);
+    render_top_hosts(f, body[1], &report);
+    chunk_idx += 1;
+
+    render_help(f, chunks[chunk_idx]);
+}
+
+fn render_title(f: &mut Frame, area: Rect) {
+    let title = Paragraph::new(t("title.statistics"))
+        .style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(title, area);
+}
+
+fn render_status(f: &mut Frame, area: Rect, msg: &str) {
+    let status = Paragraph::new(msg)
+        .style(Style::default().fg(Color::Yellow))
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(status, area);
+}
+
+fn render_help(f: &mut Frame, area: Rect) {
+    let mut help_spans = key_hints(&keymap::bar(keymap::STATISTICS, |_| true));
+    help_spans.push(Span::raw(format!("  {}", t("help.recorded"))));
+    let help = Paragraph::new(Line::from(help_spans))
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(help, area);
+}
+
+fn usage_line(label: &str, usage: Usage) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(format!("{:<13}", label), Style::default().fg(Color::Gray)),
+        Span::styled(
+            format!("↑ {:<12}", format_bytes(usage.upload)),
+            Style::default().fg(Color::Yellow),
+        ),
+        Span::styled(
+            format!("↓ {:<12}", format_bytes(usage.download)),
+            Style::default().fg(Color::Green),
+        ),
+        Span::styled(
+            format!("Σ {}", format_bytes(usage.total())),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ),
+    ])
+}
+
+fn render_summary(f: &mut Frame, area: Rect, report: &UsageReport) {
+    let summary = Paragraph::new(vec![
+        usage_line("Today:", report.today),
+        usage_line("Last 7 days:", report.week),
+        usage_line("This month:", report.month),
+    ])
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(t("block.usage")),
+    );
+    f.render_widget(summary, area);
+}
+
+fn render_daily(f: &mut Frame, area: Rect, report: &UsageReport) {
+    let max_total = report
+        .daily
+        .iter()
+        .map(|(_, usage)| usage.total())
+        .max()
+        .unwrap_or(0)
+        .max(1);
+
+    let lines: Vec<Line> = report
+        .daily
+        .iter()
+        .rev()
+        .map(|(date, usage)| {
+            let upload_bars = (usage.upload as f64 / max_total as f64 * BAR_WIDTH as f64) as usize;
+            let download_bars =
+                (usage.download as f64 / max_total as f64 * BAR_WIDTH as f64) as usize;
+            Line::from(vec![
+                Span::styled(
+                    format!("{} ", date.format("%m-%d %a")),
+                    Style::default().fg(Color::Gray),
+                ),
+                Span::styled("█".repeat(upload_bars), Style::default().fg(Color::Yellow)),
+                Span::styled("█".repeat(download_bars), Style::default().fg(Color::Green)),
+                Span::styled(
+                    format!(" {}", format_bytes(usage.total())),
+                    Style::default().fg(Color::White),
+                ),
+            ])
+        })
+        .collect();
+
+    let daily = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(format!(
+        "Daily Usage (last {} days, ↑ yellow ↓ green)",
+        report.daily.len()
+    )));
+    f.render_widget(daily, area);
+}
+
+fn render_top_hosts(f: &mut Frame, area: Rect, report: &UsageReport) {
+    let lines: Vec<Line> = if report.top_hosts.is_empty() {
+        vec![Line::from(Span::styled(
+            "No host data recorded this month",
+            Style::default().fg(Color::DarkGray),
+        ))]
+    } else {
+        report
+            .top_hosts
+            .iter()
+            .enumerate()
+            .map(|(idx, (host, bytes))| {
+                Line::from(vec![
+                    Span::styled(
+                        format!("{:>2}. ", idx + 1),
+                        Style::default().fg(Color::Gray),
+                    ),
+                    Span::styled(
+                        format!("{:<10} ", format_bytes(*bytes)),
+                        Style::default().fg(Color::Cyan),
+                    ),
+                    Span::raw(host.clone()),
+                ])
+            })
+            .collect()
+    };
+
+    let hosts = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(t("block.top_hosts_month")),
+    );
+    f.render_widget(hosts, area);
+}
@@ -1,22 +1,35 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
     Frame,
 };
 
+use crate::app::fetch_failure::FetchFailure;
 use crate::app::AppState;
+use crate::clash::Provider;
+use crate::config::dedup::DedupReport;
+use crate::config::profile_store::ProfileStore;
+use crate::config::AppConfig;
+use crate::ui::i18n::{key_hints, t};
+use crate::ui::import::{ImportProgress, ImportStatus};
+use crate::ui::keymap;
+use crate::ui::theme;
 
 #[derive(Debug, Clone)]
 pub enum SubscriptionSource {
     ClashProvider {
         name: String,
     },
-    MihomoPartyProfile {
+    /// A profile in a GUI's profile list
+    Profile {
         id: String,
         profile_path: std::path::PathBuf,
-        list_path: std::path::PathBuf,
+        store: Arc<dyn ProfileStore>,
     },
 }
 
@@ -27,29 +40,79 @@ pub struct SubscriptionItem {
     pub url: Option<String>,
     pub proxy_count: usize,
     pub updated_at: Option<String>,
+    /// Last update as a unix timestamp, used by the auto-update schedule
+    pub updated_ts: Option<i64>,
     pub is_current: bool,
     pub source: SubscriptionSource,
 }
 
+/// Where a subscription is in a running (or the last) update
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UpdatePhase {
+    /// Waiting for a download slot
+    Pending,
+    Downloading,
+    /// Converting a raw subscription and writing the profile
+    Converting,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+impl UpdatePhase {
+    pub fn is_finished(self) -> bool {
+        matches!(
+            self,
+            UpdatePhase::Done | UpdatePhase::Failed | UpdatePhase::Cancelled
+        )
+    }
+}
+
+/// What an import prompt asks for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImportSource {
+    /// Path of a file of subscription URLs
+    File,
+    /// One subscription URL to add as a proxy provider
+    ProviderUrl,
+}
+
+/// What the Update page shows in place of (or below) the subscription list
+#[derive(Clone, Copy)]
+pub enum UpdatePanel<'a> {
+    Subscriptions,
+    /// Import prompt being typed in
+    ImportPrompt(ImportSource, &'a str),
+    Import(&'a ImportProgress),
+    /// Duplicate proxies, scrolled down by the given number of sets
+    Duplicates(&'a DedupReport, usize),
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn render(
     f: &mut Frame,
     area: Rect,
     state: &AppState,
+    config: &AppConfig,
     providers: &[SubscriptionItem],
+    phases: &HashMap<String, UpdatePhase>,
+    failures: &HashMap<String, FetchFailure>,
     selected_index: usize,
+    loading: Option<&str>,
+    panel: UpdatePanel,
 ) {
     let constraints = if state.status_message.is_some() {
         vec![
             Constraint::Length(3), // Title
             Constraint::Length(3), // Status message
             Constraint::Min(0),    // Content
-            Constraint::Length(5), // Help
+            Constraint::Length(4), // Help (two lines)
         ]
     } else {
         vec![
             Constraint::Length(3), // Title
             Constraint::Min(0),    // Content
-            Constraint::Length(5), // Help
+            Constraint::Length(4), // Help (two lines)
         ]
     };
 
@@ -67,15 +130,333 @@ pub fn render(
         chunk_idx += 1;
     }
 
-    render_providers(f, chunks[chunk_idx], providers, selected_index);
+    let (content, help) = (chunks[chunk_idx], chunks[chunk_idx + 1]);
+    match panel {
+        UpdatePanel::Subscriptions => {
+            render_providers(
+                f,
+                content,
+                config,
+                providers,
+                phases,
+                failures,
+                selected_index,
+                loading,
+            );
+            render_help(f, help);
+        }
+        UpdatePanel::ImportPrompt(source, input) => {
+            render_providers(
+                f,
+                content,
+                config,
+                providers,
+                phases,
+                failures,
+                selected_index,
+                loading,
+            );
+            render_import_prompt(f, help, source, input);
+        }
+        UpdatePanel::Import(progress) => {
+            render_import_progress(f, content, progress);
+            render_import_help(f, help, progress);
+        }
+        UpdatePanel::Duplicates(report, scroll) => {
+            render_duplicates(f, content, report, scroll);
+            render_duplicates_help(f, help);
+        }
+    }
+}
+
+/// Duplicate sets: the kept proxy, then each duplicate with its source
+fn render_duplicates(f: &mut Frame, area: Rect, report: &DedupReport, scroll: usize) {
+    let items: Vec<ListItem> = report
+        .sets
+        .iter()
+        .skip(scroll)
+        .map(|set| {
+            let mut lines = vec![Line::from(vec![
+                Span::styled(
+                    format!("{}  ", set.endpoint),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::styled(
+                    set.kept.name.clone(),
+                    Style::default()
+                        .fg(Color::Green)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    format!(" [{}]", set.kept.source),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ])];
+            lines.extend(set.dropped.iter().map(|dropped| {
+                Line::from(vec![
+                    Span::styled("  ✗ ", Style::default().fg(Color::Red)),
+                    Span::raw(dropped.name.clone()),
+                    Span::styled(
+                        format!(" [{}]", dropped.source),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ])
+            }));
+            ListItem::new(lines)
+        })
+        .collect();
+
+    let list = List::new(items).block(Block::default().borders(Borders::ALL).title(format!(
+        "Duplicates - {} of {} proxies in {} sources ({} endpoints)",
+        report.duplicate_count(),
+        report.total,
+        report.sources,
+        report.sets.len()
+    )));
+    f.render_widget(list, area);
+}
+
+fn render_duplicates_help(f: &mut Frame, area: Rect) {
+    let help = Paragraph::new(Line::from(key_hints(&keymap::bar(
+        keymap::UPDATE_DUPLICATES,
+        |_| true,
+    ))))
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::ALL));
+
+    f.render_widget(help, area);
+}
+
+/// Per-URL report of a bulk import
+fn render_import_progress(f: &mut Frame, area: Rect, progress: &ImportProgress) {
+    let items: Vec<ListItem> = progress
+        .entries
+        .iter()
+        .zip(&progress.statuses)
+        .map(|(entry, status)| {
+            let (mark, color, detail) = match status {
+                ImportStatus::Pending => ("·", Color::DarkGray, "waiting".to_string()),
+                ImportStatus::Downloading => ("…", Color::Yellow, "downloading".to_string()),
+                ImportStatus::Imported(summary) => ("✓", Color::Green, summary.clone()),
+                ImportStatus::Skipped(reason) => ("-", Color::DarkGray, reason.clone()),
+                ImportStatus::Failed(error) => ("✗", Color::Red, error.clone()),
+            };
+            ListItem::new(vec![
+                Line::from(vec![
+                    Span::styled(format!("{} ", mark), Style::default().fg(color)),
+                    Span::styled(
+                        entry.name.clone(),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(format!("  {}", detail), Style::default().fg(color)),
+                ]),
+                Line::from(Span::styled(
+                    format!("  {}", entry.url),
+                    Style::default().fg(Color::DarkGray),
+                )),
+            ])
+        })
+        .collect();
+
+    let state = match &progress.done {
+        None => format!(
+            "{}/{} finished",
+            progress.finished_count(),
+            progress.entries.len()
+        ),
+        Some(Ok(())) => "done".to_string(),
+        Some(Err(e)) => format!("failed: {}", e),
+    };
+    let list = List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Import into {} - {}", progress.target, state)),
+    );
+    f.render_widget(list, area);
+}
+
+fn render_import_prompt(f: &mut Frame, area: Rect, source: ImportSource, input: &str) {
+    let (label, action) = match source {
+        ImportSource::File => ("File of subscription URLs: ", "help.import_urls"),
+        ImportSource::ProviderUrl => (
+            "Subscription URL for a new proxy provider ([name] url): ",
+            "help.add_provider",
+        ),
+    };
+    let prompt = Paragraph::new(vec![
+        Line::from(vec![
+            Span::styled(label, Style::default().fg(Color::Cyan)),
+            Span::raw(input.to_string()),
+            Span::styled("_", Style::default().fg(Color::Yellow)),
+        ]),
+        Line::from(key_hints(&[("Enter", action), ("Esc", "help.cancel")])),
+    ])
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::ALL));
+
+    f.render_widget(prompt, area);
+}
+
+fn render_import_help(f: &mut Frame, area: Rect, progress: &ImportProgress) {
+    let hint = if progress.done.is_some() {
+        key_hints(&[("Esc", "help.back")])
+    } else {
+        vec![Span::styled(
+            "Importing...",
+            Style::default().fg(Color::Yellow),
+        )]
+    };
+    let help = Paragraph::new(Line::from(hint))
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+
+    f.render_widget(help, area);
+}
+
+/// Nodes of one proxy provider with their latest delay
+pub fn render_provider_nodes(
+    f: &mut Frame,
+    area: Rect,
+    state: &AppState,
+    name: &str,
+    provider: Option<&Provider>,
+    selected_index: usize,
+) {
+    let mut constraints = vec![Constraint::Length(3)]; // Title
+    if state.status_message.is_some() {
+        constraints.push(Constraint::Length(3)); // Status message
+    }
+    constraints.push(Constraint::Min(0)); // Content
+    constraints.push(Constraint::Length(3)); // Help
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(area);
+
+    let mut chunk_idx = 0;
+    render_title(f, chunks[chunk_idx]);
+    chunk_idx += 1;
+
+    if let Some(msg) = &state.status_message {
+        render_status(f, chunks[chunk_idx], msg);
+        chunk_idx += 1;
+    }
+
+    let Some(provider) = provider else {
+        let loading = Paragraph::new(format!("Loading nodes of {}...", name))
+            .style(Style::default().fg(Color::DarkGray))
+            .alignment(Alignment::Center)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(name.to_string()),
+            );
+        f.render_widget(loading, chunks[chunk_idx]);
+        render_provider_nodes_help(f, chunks[chunk_idx + 1]);
+        return;
+    };
+
+    let visible = chunks[chunk_idx].height.saturating_sub(2) as usize;
+    let skip = (selected_index + 1).saturating_sub(visible);
+    let items: Vec<ListItem> = provider
+        .proxies
+        .iter()
+        .enumerate()
+        .skip(skip)
+        .take(visible)
+        .map(|(idx, proxy)| {
+            let is_selected = idx == selected_index;
+            let name_style = if is_selected {
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            let mut spans = vec![
+                Span::styled(
+                    if is_selected { "▶ " } else { "  " },
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::styled(proxy.name.clone(), name_style),
+                Span::styled(
+                    format!("  [{:?}]", proxy.proxy_type),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ];
+
+            // A manual test is newer than the provider's health check history
+            let delay = state.get_delay(&proxy.name).map(|d| d.delay).or_else(|| {
+                proxy
+                    .history
+                    .as_ref()
+                    .and_then(|history| history.last())
+                    .map(|entry| entry.delay)
+            });
+            if state.is_testing(&proxy.name) {
+                spans.push(Span::styled(
+                    " [Testing...]",
+                    Style::default().fg(Color::Yellow),
+                ));
+            } else {
+                match delay {
+                    Some(0) => {
+                        spans.push(Span::styled(" [Timeout]", Style::default().fg(Color::Red)))
+                    }
+                    Some(delay) => {
+                        let color = if delay < 200 {
+                            Color::Green
+                        } else if delay < 500 {
+                            Color::Yellow
+                        } else {
+                            Color::Red
+                        };
+                        spans.push(Span::styled(
+                            format!(" [{}]", theme::delay_text(delay)),
+                            Style::default().fg(color),
+                        ));
+                    }
+                    None => spans.push(Span::styled(
+                        " [untested]",
+                        Style::default().fg(Color::DarkGray),
+                    )),
+                }
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let updated = provider
+        .updated_at
+        .as_deref()
+        .map(|time| format!(" - updated {}", time))
+        .unwrap_or_default();
+    let list = List::new(items).block(Block::default().borders(Borders::ALL).title(format!(
+        "{} - {} nodes{}",
+        name,
+        provider.proxies.len(),
+        updated
+    )));
+    f.render_widget(list, chunks[chunk_idx]);
     chunk_idx += 1;
 
-    render_help(f, chunks[chunk_idx]);
+    render_provider_nodes_help(f, chunks[chunk_idx]);
+}
+
+fn render_provider_nodes_help(f: &mut Frame, area: Rect) {
+    let help = Paragraph::new(Line::from(key_hints(&keymap::bar(
+        keymap::UPDATE_NODES,
+        |_| true,
+    ))))
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::ALL));
+
+    f.render_widget(help, area);
 }
 
 fn render_title(f: &mut Frame, area: Rect) {
-    let title_text = "Subscription Management (订阅管理)";
-    let title = Paragraph::new(title_text)
+    let title = Paragraph::new(t("title.update"))
         .style(
             Style::default()
                 .fg(Color::Cyan)
@@ -94,12 +475,41 @@ fn render_status(f: &mut Frame, area: Rect, message: &str) {
     f.render_widget(status, area);
 }
 
+/// Format an interval in minutes as e.g. "30m", "6h" or "1d"
+pub fn format_interval(minutes: u64) -> String {
+    if minutes.is_multiple_of(1440) {
+        format!("{}d", minutes / 1440)
+    } else if minutes.is_multiple_of(60) {
+        format!("{}h", minutes / 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+/// Subscriptions found so far; `loading` is the spinner frame while sources are still read
+#[allow(clippy::too_many_arguments)]
 fn render_providers(
     f: &mut Frame,
     area: Rect,
+    config: &AppConfig,
     providers: &[SubscriptionItem],
+    phases: &HashMap<String, UpdatePhase>,
+    failures: &HashMap<String, FetchFailure>,
     selected_index: usize,
+    loading: Option<&str>,
 ) {
+    if let (true, Some(spinner)) = (providers.is_empty(), loading) {
+        let paragraph = Paragraph::new(format!("{} Looking for subscriptions...", spinner))
+            .style(Style::default().fg(Color::DarkGray))
+            .alignment(Alignment::Center)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(t("block.subscriptions")),
+            );
+        f.render_widget(paragraph, area);
+        return;
+    }
     if providers.is_empty() {
         let content = vec![
             Line::from(""),
@@ -143,13 +553,28 @@ fn render_providers(
         let paragraph = Paragraph::new(content).alignment(Alignment::Left).block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Subscriptions (订阅)"),
+                .title(t("block.subscriptions")),
         );
 
         f.render_widget(paragraph, area);
         return;
     }
 
+    let selected_failure = providers
+        .get(selected_index)
+        .and_then(|item| failures.get(&item.name));
+    let area = match selected_failure {
+        Some(failure) => {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(6)])
+                .split(area);
+            render_failure(f, chunks[1], failure);
+            chunks[0]
+        }
+        None => area,
+    };
+
     // Render provider list with selection
     let items: Vec<ListItem> = providers
         .iter()
@@ -173,7 +598,7 @@ fn render_providers(
 
             let is_selected = idx == selected_index;
 
-            let line1 = Line::from(vec![
+            let mut line1 = Line::from(vec![
                 Span::styled(
                     if is_selected { "▶ " } else { "  " },
                     Style::default().fg(if is_selected {
@@ -216,6 +641,31 @@ fn render_providers(
                     Style::default().fg(Color::Magenta),
                 ),
             ]);
+            let failure = failures.get(&item.name);
+            match phases.get(&item.name).copied() {
+                Some(phase) if phase != UpdatePhase::Failed || failure.is_none() => {
+                    let (mark, color, label) = match phase {
+                        UpdatePhase::Pending => ("·", Color::DarkGray, "pending"),
+                        UpdatePhase::Downloading => ("…", Color::Yellow, "downloading"),
+                        UpdatePhase::Converting => ("…", Color::Yellow, "converting"),
+                        UpdatePhase::Done => ("✓", Color::Green, "done"),
+                        UpdatePhase::Failed => ("✗", Color::Red, "failed"),
+                        UpdatePhase::Cancelled => ("-", Color::DarkGray, "cancelled"),
+                    };
+                    line1.spans.push(Span::styled(
+                        format!("  {} {}", mark, label),
+                        Style::default().fg(color),
+                    ));
+                }
+                _ => {
+                    if let Some(failure) = failure {
+                        line1.spans.push(Span::styled(
+                            format!("  ✗ {}", failure.kind.label()),
+                            Style::default().fg(Color::Red),
+                        ));
+                    }
+                }
+            }
 
             let line2 = Line::from(vec![
                 Span::raw(if is_selected { "   " } else { "     " }),
@@ -230,40 +680,83 @@ fn render_providers(
                 ),
             ]);
 
-            let line3 = Line::from(vec![
+            let mut line3_spans = vec![
                 Span::raw(if is_selected { "   " } else { "     " }),
                 Span::styled(updated_str, Style::default().fg(Color::DarkGray)),
-            ]);
+            ];
+            if let Some(minutes) = config.update_interval(&item.name) {
+                line3_spans.push(Span::styled(
+                    format!("  ⟳ Auto-update every {}", format_interval(minutes)),
+                    Style::default().fg(Color::Green),
+                ));
+            }
+            if let Some(policy) = config.download_policies.get(&item.name) {
+                line3_spans.push(Span::styled(
+                    format!("  ⇣ {}", policy.label()),
+                    Style::default().fg(Color::Cyan),
+                ));
+            }
+            let filter = config.node_filter(&item.name);
+            if !filter.is_empty() {
+                line3_spans.push(Span::styled(
+                    format!("  Filter: {}", filter.describe()),
+                    Style::default().fg(Color::Magenta),
+                ));
+            }
+            let line3 = Line::from(line3_spans);
 
             ListItem::new(vec![line1, line2, line3])
         })
         .collect();
 
-    let list = List::new(items).block(Block::default().borders(Borders::ALL).title(format!(
-        "Your Subscriptions (订阅) - {} total",
-        providers.len()
-    )));
+    let base = config.profile_template.as_deref().unwrap_or("work config");
+    let mut title = format!(
+        "{} - {} total - raw base: {}",
+        t("block.subscriptions"),
+        providers.len(),
+        base
+    );
+    if let Some(spinner) = loading {
+        title.push_str(&format!(" - {} loading", spinner));
+    }
+    let list = List::new(items).block(Block::default().borders(Borders::ALL).title(title));
 
     f.render_widget(list, area);
 }
 
-fn render_help(f: &mut Frame, area: Rect) {
-    let help_spans = vec![
-        Span::styled("↑↓", Style::default().fg(Color::Yellow)),
-        Span::raw(" Select  "),
-        Span::styled("Enter", Style::default().fg(Color::Yellow)),
-        Span::raw(" Update Selected  "),
-        Span::styled("s", Style::default().fg(Color::Yellow)),
-        Span::raw(" Set Current  "),
-        Span::styled("u", Style::default().fg(Color::Yellow)),
-        Span::raw(" Update All  "),
-        Span::styled("r", Style::default().fg(Color::Yellow)),
-        Span::raw(" Refresh  "),
-        Span::styled("q", Style::default().fg(Color::Yellow)),
-        Span::raw(" Back"),
+/// Why the selected subscription's last update failed and what to try
+fn render_failure(f: &mut Frame, area: Rect, failure: &FetchFailure) {
+    let lines = vec![
+        Line::from(Span::styled(
+            failure.kind.label(),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(vec![
+            Span::styled("→ ", Style::default().fg(Color::Yellow)),
+            Span::raw(failure.kind.hint()),
+        ]),
+        Line::from(Span::styled(
+            failure.detail.clone(),
+            Style::default().fg(Color::DarkGray),
+        )),
     ];
+    let paragraph = Paragraph::new(lines).wrap(Wrap { trim: true }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Red))
+            .title(t("block.last_update_failed")),
+    );
+    f.render_widget(paragraph, area);
+}
+
+fn render_help(f: &mut Frame, area: Rect) {
+    // Two lines: the selected subscription's keys, then the page's
+    let hints = keymap::bar(keymap::UPDATE, |_| true);
+    let (line1, line2) = hints.split_at(6);
+    let help_line1 = Line::from(key_hints(line1));
+    let help_line2 = Line::from(key_hints(line2));
 
-    let help = Paragraph::new(Line::from(help_spans))
+    let help = Paragraph::new(vec![help_line1, help_line2])
         .alignment(Alignment::Center)
         .block(Block::default().borders(Borders::ALL));
 
@@ -0,0 +1,269 @@
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+use crate::app::Page;
+use crate::clash::ClashMode;
+use crate::ui::list;
+use crate::ui::search::SearchFilter;
+
+/// Something the command palette can run from any page
+#[derive(Debug, Clone, PartialEq)]
+pub enum PaletteAction {
+    GoTo(Page),
+    SwitchMode(ClashMode),
+    /// Delay-test every node of the primary group
+    TestPrimaryGroup,
+    /// Switch the primary group to its fastest node
+    PickFastest,
+    UpdateAllSubscriptions,
+    CloseAllConnections,
+    ToggleTun,
+    Refresh,
+    CyclePreset,
+    CycleTheme,
+    ShowErrors,
+    ShowMessages,
+    ShowHelp,
+    Quit,
+}
+
+/// A palette entry: what it does and the key that does it on its page
+#[derive(Debug, Clone)]
+pub struct PaletteItem {
+    pub label: String,
+    pub keys: &'static str,
+    pub action: PaletteAction,
+}
+
+/// Every action the palette offers
+pub fn items() -> Vec<PaletteItem> {
+    let item = |label: &str, keys, action| PaletteItem {
+        label: label.to_string(),
+        keys,
+        action,
+    };
+    let mut items = vec![
+        item(
+            "Switch to Rule mode",
+            "Home: m",
+            PaletteAction::SwitchMode(ClashMode::Rule),
+        ),
+        item(
+            "Switch to Global mode",
+            "Home: m",
+            PaletteAction::SwitchMode(ClashMode::Global),
+        ),
+        item(
+            "Switch to Direct mode",
+            "Home: m",
+            PaletteAction::SwitchMode(ClashMode::Direct),
+        ),
+        item(
+            "Test all nodes of the primary group",
+            "Routes: t",
+            PaletteAction::TestPrimaryGroup,
+        ),
+        item(
+            "Switch the primary group to its fastest node",
+            "Routes: a",
+            PaletteAction::PickFastest,
+        ),
+        item(
+            "Update all subscriptions",
+            "Update: u",
+            PaletteAction::UpdateAllSubscriptions,
+        ),
+        item(
+            "Close all connections",
+            "Connections: a",
+            PaletteAction::CloseAllConnections,
+        ),
+        item("Toggle TUN mode", "", PaletteAction::ToggleTun),
+        item("Refresh", "Home: r", PaletteAction::Refresh),
+        item("Cycle preset", "Ctrl+P", PaletteAction::CyclePreset),
+        item("Cycle theme", "Home: Ctrl+T", PaletteAction::CycleTheme),
+        item("Show errors", "!", PaletteAction::ShowErrors),
+        item("Show message history", "! Tab", PaletteAction::ShowMessages),
+        item("Show key bindings", "?", PaletteAction::ShowHelp),
+        item("Quit", "Home: q", PaletteAction::Quit),
+    ];
+    let pages = [
+        (Page::Home, "Home", "h"),
+        (Page::Routes, "Routes", "Home: g"),
+        (Page::Rules, "Rules", "Home: l"),
+        (Page::Update, "Update (subscriptions)", "Home: u"),
+        (Page::Connections, "Connections", "Home: c"),
+        (Page::Settings, "Settings", "Home: s"),
+        (Page::Logs, "Logs", "Home: o"),
+        (Page::Performance, "Performance", "Home: p"),
+        (Page::Statistics, "Statistics (data usage)", "Home: d"),
+        (Page::Favorites, "Favorites", "Home: f"),
+        (Page::Groups, "Node Groups", "Home: G"),
+        (Page::Heatmap, "Latency Heatmap", "Home: H"),
+    ];
+    items.extend(pages.into_iter().map(|(page, name, keys)| {
+        item(&format!("Go to {}", name), keys, PaletteAction::GoTo(page))
+    }));
+    items
+}
+
+/// Indices of the items matching `query`, best match first (list order on ties)
+pub fn filter(items: &[PaletteItem], query: &str) -> Vec<usize> {
+    let filter = SearchFilter::new(query);
+    let mut scored: Vec<(i32, usize)> = items
+        .iter()
+        .enumerate()
+        .filter_map(|(index, item)| Some((filter.score([item.label.as_str()])?, index)))
+        .collect();
+    scored.sort_by_key(|(score, index)| (std::cmp::Reverse(*score), *index));
+    scored.into_iter().map(|(_, index)| index).collect()
+}
+
+/// What a key did to the palette
+#[derive(Debug, PartialEq)]
+pub enum PaletteOutcome {
+    Pending,
+    Run(PaletteAction),
+    Cancelled,
+}
+
+/// Fuzzy-searchable list of actions
+pub struct Palette {
+    items: Vec<PaletteItem>,
+    query: String,
+    selected: usize,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self {
+            items: items(),
+            query: String::new(),
+            selected: 0,
+        }
+    }
+}
+
+impl Palette {
+    pub fn handle_key(&mut self, code: KeyCode) -> PaletteOutcome {
+        let matches = filter(&self.items, &self.query);
+        if let Some(index) = list::jump(code, self.selected, matches.len()) {
+            self.selected = index;
+            return PaletteOutcome::Pending;
+        }
+        match code {
+            KeyCode::Esc => return PaletteOutcome::Cancelled,
+            KeyCode::Enter => {
+                if let Some(index) = matches.get(self.selected) {
+                    return PaletteOutcome::Run(self.items[*index].action.clone());
+                }
+            }
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down if self.selected + 1 < matches.len() => self.selected += 1,
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.selected = 0;
+            }
+            KeyCode::Char(c) => {
+                self.query.push(c);
+                self.selected = 0;
+            }
+            _ => {}
+        }
+        PaletteOutcome::Pending
+    }
+}
+
+/// Query line over the matching actions, centered in `area`
+pub fn render_palette(f: &mut Frame, area: Rect, palette: &Palette) {
+    let width = area.width.saturating_sub(8).min(80);
+    let height = area.height.saturating_sub(4).min(20);
+    let dialog_area = Rect {
+        x: area.x + (area.width.saturating_sub(width)) / 2,
+        y: area.y + (area.height.saturating_sub(height)) / 3,
+        width,
+        height,
+    };
+    f.render_widget(Clear, dialog_area);
+
+    let input_area = Rect {
+        height: 3.min(dialog_area.height),
+        ..dialog_area
+    };
+    let list_area = Rect {
+        y: dialog_area.y + input_area.height,
+        height: dialog_area.height - input_area.height,
+        ..dialog_area
+    };
+
+    let input = Paragraph::new(Line::from(vec![
+        Span::styled(": ", Style::default().fg(Color::Yellow)),
+        Span::raw(palette.query.clone()),
+        Span::styled("_", Style::default().fg(Color::DarkGray)),
+    ]))
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow))
+            .title("Command Palette"),
+    );
+    f.render_widget(input, input_area);
+
+    let search = SearchFilter::new(&palette.query);
+    let matches = filter(&palette.items, &palette.query);
+    let rows = list::viewport(list_area, 1);
+    let window = list::window(matches.len(), list::follow(palette.selected, 0, rows), rows);
+    let key_width = 16;
+    let label_width = (list_area.width as usize).saturating_sub(key_width + 4);
+    let items: Vec<ListItem> = matches[window.clone()]
+        .iter()
+        .map(|index| {
+            let item = &palette.items[*index];
+            let mut spans = search.highlight(&item.label, Style::default());
+            let padding = label_width.saturating_sub(item.label.chars().count());
+            spans.push(Span::raw(" ".repeat(padding)));
+            spans.push(Span::styled(
+                format!("{:>width$}", item.keys, width = key_width),
+                Style::default().fg(Color::DarkGray),
+            ));
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let mut list_state = ListState::default();
+    if !matches.is_empty() {
+        list_state.select(Some(palette.selected - window.start));
+    }
+    let widget = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Yellow))
+                .title(format!("{} actions", matches.len()))
+                .title_bottom(Line::from(" ↑↓ Move  Enter Run  Esc Close ").centered()),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    f.render_stateful_widget(widget, list_area, &mut list_state);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_ranks_best_match_first() {
+        let items = items();
+        let label = |query: &str| items[filter(&items, query)[0]].label.as_str();
+        assert_eq!(label("upd sub"), "Update all subscriptions");
+        assert_eq!(label("close conn"), "Close all connections");
+        assert_eq!(label("global"), "Switch to Global mode");
+        assert_eq!(filter(&items, "").len(), items.len());
+        assert!(filter(&items, "zzzz").is_empty());
+    }
+}
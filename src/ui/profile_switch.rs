@@ -0,0 +1,256 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use std::path::{Path, PathBuf};
+
+use super::{
+    backup_before_write, convert_raw_subscription_to_config, debug_log, is_http_url,
+    load_profile_subscriptions, looks_like_clash_config, lossy_conversion, subscription_client,
+    update_profile, ConversionReport, NodeRules,
+};
+use crate::clash::ClashClient;
+use crate::config::atomic::write_atomic;
+use crate::config::validate::{self, ConfigProblem};
+use crate::config::AppConfig;
+use crate::ui::pages::update::{SubscriptionItem, SubscriptionSource};
+
+/// A GUI profile converted and validated, ready to become the work config
+#[derive(Debug, Clone)]
+pub struct PreparedSwitch {
+    pub work_config_path: PathBuf,
+    pub output_bytes: Vec<u8>,
+    /// Proxies of a raw subscription converted onto the template; None for full configs
+    pub applied_proxy_count: Option<usize>,
+    pub problems: Vec<ConfigProblem>,
+    /// Share links the conversion had to skip
+    pub(super) report: Option<ConversionReport>,
+}
+
+impl PreparedSwitch {
+    /// Whether the output has config errors and must not be applied
+    pub fn has_errors(&self) -> bool {
+        validate::has_errors(&self.problems)
+    }
+
+    /// Share links the conversion skipped, summarized
+    pub fn skipped_summary(&self) -> Option<String> {
+        self.report.as_ref().and_then(ConversionReport::summary)
+    }
+}
+
+/// GUI profiles that can be switched to, sorted by name
+pub fn list(config: &AppConfig) -> Result<Vec<SubscriptionItem>> {
+    let mut items = load_profile_subscriptions(config)?;
+    items.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(items)
+}
+
+/// The profile named `name`, or the only one whose name contains it (ignoring case)
+pub fn find(items: &[SubscriptionItem], name: &str) -> Result<SubscriptionItem> {
+    if let Some(item) = items.iter().find(|item| item.name == name) {
+        return Ok(item.clone());
+    }
+    let needle = name.to_lowercase();
+    let matches: Vec<&SubscriptionItem> = items
+        .iter()
+        .filter(|item| item.name.to_lowercase().contains(&needle))
+        .collect();
+    match matches.as_slice() {
+        [item] => Ok((*item).clone()),
+        [] => anyhow::bail!("No profile named {}", name),
+        _ => anyhow::bail!(
+            "{} matches several profiles: {}",
+            name,
+            matches
+                .iter()
+                .map(|item| item.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// Download the profile if it has no file yet, then filter, rename and convert its
+/// nodes into a full config
+pub async fn prepare(
+    item: &SubscriptionItem,
+    config: &AppConfig,
+    core: &ClashClient,
+) -> Result<PreparedSwitch> {
+    let SubscriptionSource::Profile {
+        id,
+        profile_path,
+        store,
+    } = &item.source
+    else {
+        anyhow::bail!("Only GUI profiles support switching");
+    };
+    debug_log(&format!(
+        "switch profile id={} path={} list={}",
+        id,
+        profile_path.display(),
+        store.list_path().display()
+    ));
+    let work_config_path = store.work_config_path();
+    if !profile_path.is_file() {
+        let Some(url) = item.url.as_deref() else {
+            anyhow::bail!("Profile file not found, please update first");
+        };
+        if is_http_url(url) {
+            let http = subscription_client(config.download_policy(&item.name), core).await?;
+            update_profile(
+                store.as_ref(),
+                id,
+                url,
+                profile_path,
+                config.profile_template.as_deref(),
+                &NodeRules::new(config, &item.name),
+                &http,
+            )
+            .await
+            .context("Failed to download subscription")?;
+        } else {
+            let bytes = std::fs::read(url).context("Failed to read subscription file")?;
+            if let Some(parent) = profile_path.parent() {
+                let _ = std::fs::create_dir_all(parent);
+            }
+            write_atomic(profile_path, &bytes).context("Failed to write profile")?;
+            let _ = store.set_updated(id, Utc::now().timestamp_millis());
+        }
+    }
+
+    let bytes = std::fs::read(profile_path).context("Failed to read profile")?;
+    let nodes = NodeRules::new(config, &item.name)
+        .compile()
+        .context("Failed to apply node rules")?;
+    let mut applied_proxy_count = None;
+    let mut report = None;
+    let output_bytes = if looks_like_clash_config(&bytes) {
+        debug_log(&format!(
+            "switch profile looks_like_config bytes={}",
+            bytes.len()
+        ));
+        nodes
+            .apply_to_config(&bytes)
+            .context("Failed to apply node rules")?
+    } else {
+        debug_log(&format!("switch profile raw bytes={}", bytes.len()));
+        let (output, count) = convert_raw_subscription_to_config(
+            &bytes,
+            config.profile_template.as_deref(),
+            Some(&work_config_path),
+            &nodes,
+        )
+        .map_err(anyhow::Error::msg)?;
+        debug_log(&format!(
+            "switch raw converted count={} output_bytes={}",
+            count,
+            output.len()
+        ));
+        applied_proxy_count = Some(count);
+        report = lossy_conversion(&bytes);
+        output
+    };
+
+    let problems = validate::validate_config(&output_bytes);
+    if validate::has_errors(&problems) {
+        debug_log(&format!(
+            "switch validation failed problems={}",
+            problems.len()
+        ));
+    }
+    Ok(PreparedSwitch {
+        work_config_path,
+        output_bytes,
+        applied_proxy_count,
+        problems,
+        report,
+    })
+}
+
+/// Write `output_bytes` as the work config (backing up what it replaces), have the
+/// core reload it and mark the profile current
+pub async fn apply(
+    client: &ClashClient,
+    item: &SubscriptionItem,
+    work_config_path: &Path,
+    output_bytes: &[u8],
+    applied_proxy_count: Option<usize>,
+) -> Result<()> {
+    let SubscriptionSource::Profile {
+        id,
+        profile_path,
+        store,
+    } = &item.source
+    else {
+        anyhow::bail!("Only GUI profiles support switching");
+    };
+    backup_before_write(
+        &format!("Switch to {}", item.name),
+        &[work_config_path, profile_path, store.list_path()],
+        Some(work_config_path),
+    );
+    if applied_proxy_count.is_some() {
+        let _ = write_atomic(profile_path, output_bytes);
+    }
+
+    if let Some(parent) = work_config_path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if let Err(e) = write_atomic(work_config_path, output_bytes) {
+        debug_log(&format!("switch write work config failed: {:#}", e));
+        return Err(e.context("Failed to apply subscription"));
+    }
+
+    let path_str = work_config_path.to_string_lossy().to_string();
+    let temp_path = work_config_path.with_file_name("config.switch.yaml");
+    let temp_path_str = temp_path.to_string_lossy().to_string();
+
+    let mut reload_result: Option<Result<()>> = None;
+    if std::fs::write(&temp_path, output_bytes).is_ok() {
+        if client.reload_config_path(&temp_path_str).await.is_ok() {
+            debug_log("switch temp path reload ok");
+            reload_result = Some(client.reload_config_path(&path_str).await);
+        }
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    let reload_result = match reload_result {
+        Some(result) => result,
+        None => client.reload_config_path(&path_str).await,
+    };
+    if let Err(e) = reload_result {
+        debug_log(&format!("switch reload failed: {}", e));
+        return Err(e.context("Failed to reload Clash config"));
+    }
+
+    debug_log("switch reload ok");
+    let _ = store.set_current(id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_profile_by_name_or_unique_part() {
+        let items: Vec<SubscriptionItem> = ["Airport", "Airport Backup", "Work"]
+            .into_iter()
+            .map(|name| SubscriptionItem {
+                name: name.to_string(),
+                provider_type: "profile/remote".to_string(),
+                url: None,
+                proxy_count: 0,
+                updated_at: None,
+                updated_ts: None,
+                is_current: false,
+                source: SubscriptionSource::ClashProvider {
+                    name: name.to_string(),
+                },
+            })
+            .collect();
+        assert_eq!(find(&items, "Airport").unwrap().name, "Airport");
+        assert_eq!(find(&items, "backup").unwrap().name, "Airport Backup");
+        assert!(find(&items, "air").is_err());
+        assert!(find(&items, "Home").is_err());
+    }
+}
@@ -0,0 +1,236 @@
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Span;
+
+/// Score for each matched character
+const SCORE_MATCH: i32 = 16;
+/// Bonus for a match at the start of a word (after a separator, a camelCase hump or a digit run)
+const BONUS_BOUNDARY: i32 = 8;
+/// Bonus for a match right after the previous one
+const BONUS_CONSECUTIVE: i32 = 8;
+/// Penalty for skipping characters between two matches, plus one per extra character skipped
+const PENALTY_GAP_START: i32 = 3;
+const PENALTY_GAP_EXTENSION: i32 = 1;
+
+/// Best match of one query term in a text: higher scores are better
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzyMatch {
+    pub score: i32,
+    /// Char indices of the matched characters
+    pub positions: Vec<usize>,
+}
+
+/// Search box query matched skim-style, case-insensitively
+/// Terms match in any order; each term's characters match in order but need not be adjacent
+/// Terms are split on whitespace and on symbols never found in hosts or node names, like `»`
+#[derive(Debug, Clone, Default)]
+pub struct SearchFilter {
+    terms: Vec<Vec<char>>,
+}
+
+impl SearchFilter {
+    pub fn new(query: &str) -> Self {
+        let terms = query
+            .split(is_term_separator)
+            .filter(|term| !term.is_empty())
+            .map(|term| term.chars().map(fold).collect())
+            .collect();
+        Self { terms }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Total score with every term matched against its best field, None when a term matches none
+    /// An empty query scores 0 for everything
+    pub fn score<'a>(&self, fields: impl IntoIterator<Item = &'a str>) -> Option<i32> {
+        if self.is_empty() {
+            return Some(0);
+        }
+        let fields: Vec<Vec<char>> = fields.into_iter().map(|f| f.chars().collect()).collect();
+        self.terms.iter().try_fold(0, |total, term| {
+            let best = fields
+                .iter()
+                .filter_map(|field| fuzzy_match(term, field))
+                .map(|m| m.score)
+                .max()?;
+            Some(total + best)
+        })
+    }
+
+    /// Whether every term matches one of `fields`
+    pub fn matches<'a>(&self, fields: impl IntoIterator<Item = &'a str>) -> bool {
+        self.score(fields).is_some()
+    }
+
+    /// Index of the best-scoring text, the earliest one on ties
+    pub fn best_match<'a>(&self, texts: impl IntoIterator<Item = &'a str>) -> Option<usize> {
+        texts
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, text)| Some((self.score([text])?, index)))
+            .max_by_key(|(score, index)| (*score, std::cmp::Reverse(*index)))
+            .map(|(_, index)| index)
+    }
+
+    /// `text` as spans in `style`, with the characters any term matched highlighted
+    pub fn highlight(&self, text: &str, style: Style) -> Vec<Span<'static>> {
+        let chars: Vec<char> = text.chars().collect();
+        let mut marked = vec![false; chars.len()];
+        for term in &self.terms {
+            if let Some(m) = fuzzy_match(term, &chars) {
+                for position in m.positions {
+                    marked[position] = true;
+                }
+            }
+        }
+
+        let highlighted = style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+        let mut spans = Vec::new();
+        let mut start = 0;
+        for end in 1..=chars.len() {
+            if end == chars.len() || marked[end] != marked[start] {
+                let piece: String = chars[start..end].iter().collect();
+                let piece_style = if marked[start] { highlighted } else { style };
+                spans.push(Span::styled(piece, piece_style));
+                start = end;
+            }
+        }
+        spans
+    }
+}
+
+fn is_term_separator(c: char) -> bool {
+    c.is_whitespace() || !(c.is_alphanumeric() || ".:-_/,@*#[]()".contains(c))
+}
+
+fn fold(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// Bonus for matching `text[j]`, which starts a word when it follows a separator or changes case/kind
+fn boundary_bonus(text: &[char], j: usize) -> i32 {
+    let current = text[j];
+    let starts_word = match j.checked_sub(1).map(|prev| text[prev]) {
+        None => true,
+        Some(prev) => {
+            !prev.is_alphanumeric()
+                || (prev.is_lowercase() && current.is_uppercase())
+                || (prev.is_alphabetic() && current.is_numeric())
+        }
+    };
+    if starts_word {
+        BONUS_BOUNDARY
+    } else {
+        0
+    }
+}
+
+/// Best-scoring placement of `term` (already lowercased) in `text`, via dynamic programming
+pub fn fuzzy_match(term: &[char], text: &[char]) -> Option<FuzzyMatch> {
+    if term.is_empty() {
+        return Some(FuzzyMatch {
+            score: 0,
+            positions: Vec::new(),
+        });
+    }
+    let lower: Vec<char> = text.iter().map(|&c| fold(c)).collect();
+
+    // Cheap subsequence check first: most texts don't match at all
+    let mut pending = term.iter().peekable();
+    for c in &lower {
+        if pending.peek() == Some(&c) {
+            pending.next();
+        }
+    }
+    if pending.peek().is_some() {
+        return None;
+    }
+
+    let n = lower.len();
+    let bonus: Vec<i32> = (0..n).map(|j| boundary_bonus(text, j)).collect();
+    // scores[i][j]: best score with term[..=i] matched and term[i] at text[j]
+    let mut scores: Vec<Vec<Option<i32>>> = vec![vec![None; n]; term.len()];
+    for (i, &wanted) in term.iter().enumerate() {
+        // Best previous match at least two characters back, less the gap penalty up to j
+        let mut gapped: Option<i32> = None;
+        for j in 0..n {
+            let previous = if i == 0 {
+                Some(0)
+            } else if j == 0 {
+                None
+            } else {
+                if j >= 2 {
+                    gapped = gapped
+                        .map(|score| score - PENALTY_GAP_EXTENSION)
+                        .max(scores[i - 1][j - 2].map(|score| score - PENALTY_GAP_START));
+                }
+                let consecutive = scores[i - 1][j - 1].map(|score| score + BONUS_CONSECUTIVE);
+                consecutive.max(gapped)
+            };
+            if lower[j] == wanted {
+                scores[i][j] = previous.map(|score| score + SCORE_MATCH + bonus[j]);
+            }
+        }
+    }
+
+    let last = term.len() - 1;
+    let (mut j, score) = scores[last]
+        .iter()
+        .enumerate()
+        .filter_map(|(j, score)| score.map(|score| (j, score)))
+        .fold(None, |best: Option<(usize, i32)>, (j, score)| match best {
+            Some((_, best_score)) if best_score >= score => best,
+            _ => Some((j, score)),
+        })?;
+
+    // Walk back through the placements that produced the best score
+    let mut positions = vec![j];
+    for i in (1..=last).rev() {
+        let target = scores[i][j]? - SCORE_MATCH - bonus[j];
+        j = (0..j).rev().find(|&k| {
+            let gap = (j - 1 - k) as i32;
+            let via = if gap == 0 {
+                scores[i - 1][k].map(|score| score + BONUS_CONSECUTIVE)
+            } else {
+                scores[i - 1][k]
+                    .map(|score| score - PENALTY_GAP_START - PENALTY_GAP_EXTENSION * (gap - 1))
+            };
+            via == Some(target)
+        })?;
+        positions.push(j);
+    }
+    positions.reverse();
+    Some(FuzzyMatch { score, positions })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn positions(query: &str, text: &str) -> Option<Vec<usize>> {
+        let term: Vec<char> = query.chars().collect();
+        let text: Vec<char> = text.chars().collect();
+        fuzzy_match(&term, &text).map(|m| m.positions)
+    }
+
+    #[test]
+    fn test_fuzzy_match() {
+        assert_eq!(positions("hkg", "HK-GT-01"), Some(vec![0, 1, 3]));
+        // Word starts win over scattered letters
+        assert_eq!(positions("gt", "big-GT"), Some(vec![4, 5]));
+        assert_eq!(positions("xyz", "HK-GT-01"), None);
+
+        let filter = SearchFilter::new("hkgt»443");
+        assert!(filter.matches(["🇭🇰 HK GT 01", "example.com:443"]));
+        assert!(!filter.matches(["🇭🇰 HK GT 01", "example.com:80"]));
+        // An exact substring outscores a scattered match
+        let query = SearchFilter::new("google");
+        assert!(query.score(["google.com"]) > query.score(["go-ogle-edge"]));
+        assert_eq!(SearchFilter::new("  ").score(["anything"]), Some(0));
+
+        let spans = SearchFilter::new("gt").highlight("HK-GT-01", Style::default());
+        let texts: Vec<&str> = spans.iter().map(|span| span.content.as_ref()).collect();
+        assert_eq!(texts, ["HK-", "GT", "-01"]);
+    }
+}
@@ -1,11 +1,120 @@
 #![allow(dead_code)]
 
+use ratatui::buffer::Buffer;
 use ratatui::style::Color;
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static NO_COLOR: AtomicBool = AtomicBool::new(false);
+static COMPACT: AtomicBool = AtomicBool::new(false);
+static LOW_BANDWIDTH: AtomicBool = AtomicBool::new(false);
+
+/// How much longer polling intervals get in low-bandwidth mode
+const LOW_BANDWIDTH_SLOWDOWN: u32 = 3;
+
+/// Frames narrower than this use the compact layouts
+pub const COMPACT_WIDTH: u16 = 100;
+
+/// Pick the layout for a frame of the given width
+pub fn set_compact(width: u16) {
+    COMPACT.store(width < COMPACT_WIDTH, Ordering::Relaxed);
+}
+
+/// Whether pages should use their compact (narrow terminal) layouts
+pub fn compact() -> bool {
+    COMPACT.load(Ordering::Relaxed)
+}
+
+/// Height of a page's help bar: one line of hints inside the borders when compact
+pub fn help_height() -> u16 {
+    if compact() {
+        3
+    } else {
+        5
+    }
+}
+
+/// Turn the accessibility (no-color) mode on or off
+pub fn set_no_color(enabled: bool) {
+    NO_COLOR.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether the UI is drawn without colors
+pub fn no_color() -> bool {
+    NO_COLOR.load(Ordering::Relaxed)
+}
+
+/// Turn the low-bandwidth (slow SSH link) mode on or off
+pub fn set_low_bandwidth(enabled: bool) {
+    LOW_BANDWIDTH.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether the UI refreshes and redraws less to spare a slow link
+pub fn low_bandwidth() -> bool {
+    LOW_BANDWIDTH.load(Ordering::Relaxed)
+}
+
+/// A polling interval, stretched in low-bandwidth mode
+pub fn paced(interval: std::time::Duration) -> std::time::Duration {
+    if low_bandwidth() {
+        interval * LOW_BANDWIDTH_SLOWDOWN
+    } else {
+        interval
+    }
+}
+
+/// Blank the emoji (flags, pictographs) of a drawn frame; each costs several bytes
+/// and redraws badly on many remote terminals
+pub fn strip_emoji(buf: &mut Buffer) {
+    for cell in buf.content.iter_mut() {
+        if cell.symbol().chars().any(is_emoji) {
+            cell.set_symbol(" ");
+        }
+    }
+}
+
+/// Flags and pictographs; the ✓ ✗ ⚠ markers the pages use are kept
+fn is_emoji(c: char) -> bool {
+    matches!(c as u32, 0x1F000..=0x1FAFF | 0xFE0F)
+}
+
+/// Strip the colors from a drawn frame and replace box-drawing borders with ASCII
+/// Bold and other modifiers are kept, so selections stay visible
+pub fn plain_buffer(buf: &mut Buffer) {
+    for cell in buf.content.iter_mut() {
+        cell.fg = Color::Reset;
+        cell.bg = Color::Reset;
+        let ascii = match cell.symbol() {
+            "─" | "━" | "═" => "-",
+            "│" | "┃" | "║" => "|",
+            "┌" | "┐" | "└" | "┘" | "╭" | "╮" | "╰" | "╯" | "┏" | "┓" | "┗" | "┛" | "╔" | "╗"
+            | "╚" | "╝" | "├" | "┤" | "┬" | "┴" | "┼" => "+",
+            _ => continue,
+        };
+        cell.set_symbol(ascii);
+    }
+}
+
+/// Delay as text, with its quality spelled out when colors are off
+pub fn delay_text(delay: u32) -> String {
+    if !no_color() {
+        return format!("{}ms", delay);
+    }
+    let quality = if delay < 200 {
+        "Fast"
+    } else if delay < 500 {
+        "Good"
+    } else {
+        "Slow"
+    };
+    format!("{}ms {}", delay, quality)
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
+#[derive(Default)]
 pub enum Theme {
+    #[default]
     Dark,
     Light,
     Dracula,
@@ -22,6 +131,7 @@ impl Theme {
         }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Self {
         match s.to_lowercase().as_str() {
             "light" => Theme::Light,
@@ -149,9 +259,3 @@ impl Theme {
         }
     }
 }
-
-impl Default for Theme {
-    fn default() -> Self {
-        Theme::Dark
-    }
-}